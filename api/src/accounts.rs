@@ -7,6 +7,7 @@ use crate::{
     context::Context,
     failpoint::fail_point_poem,
     page::determine_limit,
+    resource_filter::ResourceFilter,
     response::{
         account_not_found, resource_not_found, struct_field_not_found, BadRequestError,
         BasicErrorWith404, BasicResponse, BasicResponseStatus, BasicResultWith404, InternalError,
@@ -15,8 +16,8 @@ use crate::{
 };
 use anyhow::Context as AnyhowContext;
 use aptos_api_types::{
-    AccountData, Address, AptosErrorCode, AsConverter, LedgerInfo, MoveModuleBytecode,
-    MoveModuleId, MoveResource, MoveStructTag, StateKeyWrapper, U64,
+    AccountData, Address, AptosErrorCode, AsConverter, IdentifierWrapper, LedgerInfo,
+    MoveModuleBytecode, MoveModuleId, MoveResource, MoveStructTag, StateKeyWrapper, U64,
 };
 use aptos_types::{
     access_path::AccessPath,
@@ -109,6 +110,15 @@ impl AccountsApi {
         ///
         /// If not provided, defaults to default page size.
         limit: Query<Option<u16>>,
+        /// Only return resources defined at this module address
+        module_address: Query<Option<Address>>,
+        /// Only return resources defined in a module with this name
+        module_name: Query<Option<IdentifierWrapper>>,
+        /// Only return resources whose type starts with this prefix
+        ///
+        /// The prefix must start with an address, e.g. `0x1::coin` or
+        /// `0x1::coin::CoinStore<0x1::aptos_coin::AptosCoin>`.
+        resource_type_prefix: Query<Option<String>>,
     ) -> BasicResultWith404<Vec<MoveResource>> {
         fail_point_poem("endpoint_get_account_resources")?;
         self.context
@@ -120,7 +130,20 @@ impl AccountsApi {
             start.0.map(StateKey::from),
             limit.0,
         )?;
-        account.resources(&accept_type)
+        let filter = ResourceFilter::new(
+            module_address.0.map(Into::into),
+            module_name.0.map(Into::into),
+            resource_type_prefix.0.as_deref(),
+        )
+        .context("Invalid resource filter")
+        .map_err(|err| {
+            BasicErrorWith404::bad_request_with_code(
+                err,
+                AptosErrorCode::InvalidInput,
+                &account.latest_ledger_info,
+            )
+        })?;
+        account.resources(&accept_type, &filter)
     }
 
     /// Get account modules
@@ -307,7 +330,11 @@ impl Account {
     ///
     /// Note: For the BCS response, if results are being returned in pages, i.e. with the
     /// `start` and `limit` query parameters, the results will only be sorted within each page.
-    pub fn resources(self, accept_type: &AcceptType) -> BasicResultWith404<Vec<MoveResource>> {
+    pub fn resources(
+        self,
+        accept_type: &AcceptType,
+        filter: &ResourceFilter,
+    ) -> BasicResultWith404<Vec<MoveResource>> {
         // check account exists
         self.verify_account_or_object_resource()?;
         let max_account_resources_page_size = self.context.max_account_resources_page_size();
@@ -324,6 +351,7 @@ impl Account {
                     max_account_resources_page_size,
                     &self.latest_ledger_info,
                 )? as u64,
+                filter,
             )
             .context("Failed to get resources from storage")
             .map_err(|err| {
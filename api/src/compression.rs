@@ -0,0 +1,213 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::metrics::{COMPRESSION_BYTES, COMPRESSION_RESPONSES};
+use flate2::{write::GzEncoder, Compression as GzipLevel};
+use poem::{
+    error::InternalServerError,
+    http::{header, HeaderValue, StatusCode},
+    Body, Endpoint, IntoResponse, Middleware, Request, Response, Result,
+};
+use std::io::Write;
+
+const IDENTITY: &str = "identity";
+
+/// Content encodings the API can compress responses with
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Encoding {
+    Zstd,
+    Gzip,
+}
+
+impl Encoding {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Encoding::Zstd => "zstd",
+            Encoding::Gzip => "gzip",
+        }
+    }
+
+    fn compress(&self, data: &[u8]) -> std::io::Result<Vec<u8>> {
+        match self {
+            Encoding::Zstd => zstd::bulk::compress(data, zstd::DEFAULT_COMPRESSION_LEVEL),
+            Encoding::Gzip => {
+                let mut encoder = GzEncoder::new(Vec::new(), GzipLevel::default());
+                encoder.write_all(data)?;
+                encoder.finish()
+            },
+        }
+    }
+
+    /// Picks the encoding with the highest quality value in an Accept-Encoding header,
+    /// preferring zstd on ties. Returns None if the client accepts neither.
+    pub fn negotiate(accept_encoding: &str) -> Option<Encoding> {
+        let mut zstd_quality = None;
+        let mut gzip_quality = None;
+        let mut wildcard_quality = None;
+        for item in accept_encoding.split(',') {
+            let mut parts = item.split(';');
+            let name = parts.next().unwrap_or_default().trim().to_ascii_lowercase();
+            let quality = parts
+                .filter_map(|param| param.trim().strip_prefix("q="))
+                .map(|q| q.trim().parse::<f32>().unwrap_or(0.0))
+                .next()
+                .unwrap_or(1.0);
+            match name.as_str() {
+                "zstd" => zstd_quality = Some(quality),
+                "gzip" | "x-gzip" => gzip_quality = Some(quality),
+                "*" => wildcard_quality = Some(quality),
+                _ => {},
+            }
+        }
+
+        let zstd_quality = zstd_quality.or(wildcard_quality).unwrap_or(0.0);
+        let gzip_quality = gzip_quality.or(wildcard_quality).unwrap_or(0.0);
+        if zstd_quality <= 0.0 && gzip_quality <= 0.0 {
+            None
+        } else if zstd_quality >= gzip_quality {
+            Some(Encoding::Zstd)
+        } else {
+            Some(Encoding::Gzip)
+        }
+    }
+}
+
+/// This middleware compresses response bodies with the encoding negotiated from the
+/// Accept-Encoding header of the request. Bodies smaller than `min_size` bytes, and
+/// responses that already have a Content-Encoding, are left as is.
+pub struct Compression {
+    min_size: usize,
+}
+
+impl Compression {
+    pub fn new(min_size: usize) -> Self {
+        Self { min_size }
+    }
+}
+
+impl<E: Endpoint> Middleware<E> for Compression {
+    type Output = CompressionEndpoint<E>;
+
+    fn transform(&self, ep: E) -> Self::Output {
+        CompressionEndpoint {
+            inner: ep,
+            min_size: self.min_size,
+        }
+    }
+}
+
+/// Endpoint for Compression middleware.
+pub struct CompressionEndpoint<E> {
+    inner: E,
+    min_size: usize,
+}
+
+#[async_trait::async_trait]
+impl<E: Endpoint> Endpoint for CompressionEndpoint<E> {
+    type Output = Response;
+
+    async fn call(&self, req: Request) -> Result<Self::Output> {
+        let encoding = req
+            .headers()
+            .get(header::ACCEPT_ENCODING)
+            .and_then(|value| value.to_str().ok())
+            .and_then(Encoding::negotiate);
+        let response = self.inner.call(req).await?.into_response();
+
+        // WebSocket upgrades keep the connection, so there is no body to compress
+        let encoding = match encoding {
+            Some(encoding)
+                if response.status() != StatusCode::SWITCHING_PROTOCOLS
+                    && !response.headers().contains_key(header::CONTENT_ENCODING) =>
+            {
+                encoding
+            },
+            _ => {
+                COMPRESSION_RESPONSES.with_label_values(&[IDENTITY]).inc();
+                return Ok(response);
+            },
+        };
+
+        let (mut parts, body) = response.into_parts();
+        let bytes = body.into_bytes().await?;
+        if bytes.len() < self.min_size {
+            COMPRESSION_RESPONSES.with_label_values(&[IDENTITY]).inc();
+            return Ok(Response::from_parts(parts, Body::from_bytes(bytes)));
+        }
+
+        let uncompressed_size = bytes.len();
+        let compressed = tokio::task::spawn_blocking(move || encoding.compress(&bytes))
+            .await
+            .map_err(InternalServerError)?
+            .map_err(InternalServerError)?;
+
+        COMPRESSION_RESPONSES
+            .with_label_values(&[encoding.as_str()])
+            .inc();
+        COMPRESSION_BYTES
+            .with_label_values(&[encoding.as_str(), "uncompressed"])
+            .inc_by(uncompressed_size as u64);
+        COMPRESSION_BYTES
+            .with_label_values(&[encoding.as_str(), "compressed"])
+            .inc_by(compressed.len() as u64);
+
+        parts.headers.remove(header::CONTENT_LENGTH);
+        parts.headers.insert(
+            header::CONTENT_ENCODING,
+            HeaderValue::from_static(encoding.as_str()),
+        );
+        parts
+            .headers
+            .append(header::VARY, HeaderValue::from_static("accept-encoding"));
+        Ok(Response::from_parts(parts, Body::from_vec(compressed)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_negotiate() {
+        assert_eq!(Encoding::negotiate(""), None);
+        assert_eq!(Encoding::negotiate("identity"), None);
+        assert_eq!(Encoding::negotiate("gzip"), Some(Encoding::Gzip));
+        assert_eq!(
+            Encoding::negotiate("gzip, deflate, br"),
+            Some(Encoding::Gzip)
+        );
+        assert_eq!(Encoding::negotiate("gzip, zstd"), Some(Encoding::Zstd));
+        assert_eq!(
+            Encoding::negotiate("zstd;q=0.5, gzip"),
+            Some(Encoding::Gzip)
+        );
+        assert_eq!(Encoding::negotiate("zstd;q=0, gzip;q=0"), None);
+        assert_eq!(Encoding::negotiate("*"), Some(Encoding::Zstd));
+        assert_eq!(
+            Encoding::negotiate("*;q=0.1, zstd;q=0"),
+            Some(Encoding::Gzip)
+        );
+    }
+
+    #[test]
+    fn test_compress_roundtrip() {
+        let data = "aptos".repeat(1000).into_bytes();
+
+        let compressed = Encoding::Zstd.compress(&data).unwrap();
+        assert!(compressed.len() < data.len());
+        assert_eq!(
+            zstd::bulk::decompress(&compressed, data.len()).unwrap(),
+            data
+        );
+
+        let compressed = Encoding::Gzip.compress(&data).unwrap();
+        assert!(compressed.len() < data.len());
+        let mut decompressed = Vec::new();
+        std::io::Read::read_to_end(
+            &mut flate2::read::GzDecoder::new(compressed.as_slice()),
+            &mut decompressed,
+        )
+        .unwrap();
+        assert_eq!(decompressed, data);
+    }
+}
@@ -4,11 +4,14 @@
 
 use crate::{
     accept_type::AcceptType,
+    resource_filter::ResourceFilter,
     response::{
         bcs_api_disabled, block_not_found_by_height, block_not_found_by_version,
         block_pruned_by_height, json_api_disabled, version_not_found, version_pruned,
-        ForbiddenError, InternalError, NotFoundError, ServiceUnavailableError, StdApiError,
+        ForbiddenError, GoneError, InternalError, NotFoundError, ServiceUnavailableError,
+        StdApiError,
     },
+    transaction_filter::AccountTransactionFilter,
 };
 use anyhow::{bail, ensure, format_err, Context as AnyhowContext, Result};
 use aptos_api_types::{
@@ -173,6 +176,10 @@ impl Context {
         self.node_config.api.max_submit_transaction_batch_size
     }
 
+    pub fn max_view_batch_size(&self) -> usize {
+        self.node_config.api.max_view_batch_size
+    }
+
     pub async fn submit_transaction(&self, txn: SignedTransaction) -> Result<SubmissionStatus> {
         let (req_sender, callback) = oneshot::channel();
         self.mp_sender
@@ -294,12 +301,15 @@ impl Context {
         Ok(kvs)
     }
 
+    /// Resources not matching `filter` are skipped while scanning the account's state, so they
+    /// don't count towards `limit`
     pub fn get_resources_by_pagination(
         &self,
         address: AccountAddress,
         prev_state_key: Option<&StateKey>,
         version: u64,
         limit: u64,
+        filter: &ResourceFilter,
     ) -> Result<(Vec<(StructTag, Vec<u8>)>, Option<StateKey>)> {
         let account_iter = self.db.get_prefixed_state_value_iterator(
             &StateKeyPrefix::from(address),
@@ -316,12 +326,16 @@ impl Context {
                 Ok((k, v)) => match k.inner() {
                     StateKeyInner::AccessPath(AccessPath { address: _, path }) => {
                         match Path::try_from(path.as_slice()) {
-                            Ok(Path::Resource(struct_tag)) => {
-                                Some(Ok((struct_tag, v.into_bytes())))
-                            }
+                            Ok(Path::Resource(struct_tag)) => filter
+                                .matches(&struct_tag)
+                                .then(|| Ok((struct_tag, v.into_bytes()))),
                             // TODO: Consider expanding to Path::Resource
                             Ok(Path::ResourceGroup(struct_tag)) => {
-                                Some(Ok((struct_tag, v.into_bytes())))
+                                match filter.matches_resource_group(v.bytes()) {
+                                    Ok(true) => Some(Ok((struct_tag, v.into_bytes()))),
+                                    Ok(false) => None,
+                                    Err(e) => Some(Err(e)),
+                                }
                             }
                             Ok(Path::Code(_)) => None,
                             Err(e) => Some(Err(anyhow::Error::from(e))),
@@ -363,6 +377,7 @@ impl Context {
             .collect::<Result<Vec<Vec<(StructTag, Vec<u8>)>>>>()?
             .into_iter()
             .flatten()
+            .filter(|(struct_tag, _)| filter.matches(struct_tag))
             .collect();
 
         let next_key = if let Some((struct_tag, _v)) = resource_iter.next().transpose()? {
@@ -675,33 +690,8 @@ impl Context {
         } else {
             // Get the current account state, and get the sequence number to get the limit most
             // recent transactions
-            let account_state = self
-                .get_account_state(address, ledger_info.version(), ledger_info)?
-                .ok_or_else(|| {
-                    E::not_found_with_code(
-                        "Account not found",
-                        AptosErrorCode::AccountNotFound,
-                        ledger_info,
-                    )
-                })?;
-            let resource = account_state
-                .get_account_resource()
-                .map_err(|err| {
-                    E::internal_with_code(
-                        format!("Failed to get account resource {}", err),
-                        AptosErrorCode::InternalError,
-                        ledger_info,
-                    )
-                })?
-                .ok_or_else(|| {
-                    E::not_found_with_code(
-                        "Account not found",
-                        AptosErrorCode::AccountNotFound,
-                        ledger_info,
-                    )
-                })?;
-
-            resource.sequence_number().saturating_sub(limit as u64)
+            self.get_account_sequence_number(address, ledger_info)?
+                .saturating_sub(limit as u64)
         };
 
         let txns = self
@@ -725,6 +715,203 @@ impl Context {
             .map_err(|err| E::internal_with_code(err, AptosErrorCode::InternalError, ledger_info))
     }
 
+    fn get_account_sequence_number<E: NotFoundError + InternalError>(
+        &self,
+        address: AccountAddress,
+        ledger_info: &LedgerInfo,
+    ) -> Result<u64, E> {
+        let account_state = self
+            .get_account_state(address, ledger_info.version(), ledger_info)?
+            .ok_or_else(|| {
+                E::not_found_with_code(
+                    "Account not found",
+                    AptosErrorCode::AccountNotFound,
+                    ledger_info,
+                )
+            })?;
+        let resource = account_state
+            .get_account_resource()
+            .map_err(|err| {
+                E::internal_with_code(
+                    format!("Failed to get account resource {}", err),
+                    AptosErrorCode::InternalError,
+                    ledger_info,
+                )
+            })?
+            .ok_or_else(|| {
+                E::not_found_with_code(
+                    "Account not found",
+                    AptosErrorCode::AccountNotFound,
+                    ledger_info,
+                )
+            })?;
+        Ok(resource.sequence_number())
+    }
+
+    /// Like `get_account_transactions`, but skips the transactions not matching `filter`.
+    ///
+    /// At most `max_account_transactions_filter_scan` transactions of the account are read,
+    /// from `start_seq_number` when given, or else the latest ones. If the page is not full and
+    /// some transactions were not read, the sequence number to continue from is returned along
+    /// with the matching transactions: the one after the scanned ones when reading from
+    /// `start_seq_number`, or the start of the same number of transactions before the scanned
+    /// ones when reading the latest ones.
+    pub fn get_filtered_account_transactions<E: NotFoundError + GoneError + InternalError>(
+        &self,
+        address: AccountAddress,
+        start_seq_number: Option<u64>,
+        limit: u16,
+        ledger_version: u64,
+        filter: &AccountTransactionFilter,
+        ledger_info: &LedgerInfo,
+    ) -> Result<(Vec<TransactionOnChainData>, Option<u64>), E> {
+        let max_scan = self.node_config.api.max_account_transactions_filter_scan;
+        let (versions, next_seq_number) = match start_seq_number {
+            Some(start_seq_number) => {
+                let (versions, next_seq_number, pruned) = self.scan_account_transactions(
+                    address,
+                    start_seq_number,
+                    max_scan,
+                    Some(limit as usize),
+                    ledger_version,
+                    filter,
+                    ledger_info,
+                )?;
+                if pruned {
+                    return Err(E::gone_with_code(
+                        format!(
+                            "Transactions of account {} from sequence number {} have been pruned",
+                            address, start_seq_number
+                        ),
+                        AptosErrorCode::VersionPruned,
+                        ledger_info,
+                    ));
+                }
+                (versions, next_seq_number)
+            },
+            None => {
+                let end_seq_number = self.get_account_sequence_number(address, ledger_info)?;
+                let window_start = end_seq_number.saturating_sub(max_scan);
+                let (mut versions, _, pruned) = self.scan_account_transactions(
+                    address,
+                    window_start,
+                    end_seq_number - window_start,
+                    None,
+                    ledger_version,
+                    filter,
+                    ledger_info,
+                )?;
+                versions.drain(..versions.len().saturating_sub(limit as usize));
+                let next_seq_number =
+                    if versions.len() < limit as usize && window_start > 0 && !pruned {
+                        Some(window_start.saturating_sub(max_scan))
+                    } else {
+                        None
+                    };
+                (versions, next_seq_number)
+            },
+        };
+
+        let txns = versions
+            .into_iter()
+            .map(|version| {
+                self.db
+                    .get_transaction_by_version(version, ledger_version, true)
+                    .and_then(|t| self.convert_into_transaction_on_chain_data(t))
+            })
+            .collect::<Result<Vec<_>>>()
+            .context("Failed to parse account transactions")
+            .map_err(|err| {
+                E::internal_with_code(err, AptosErrorCode::InternalError, ledger_info)
+            })?;
+        Ok((txns, next_seq_number))
+    }
+
+    /// Reads up to `num_to_scan` transactions of the account from `start_seq_number` on, and
+    /// returns the versions of the ones matching `filter`, stopping early once `limit` of them
+    /// are found. Also returns the sequence number to continue from when stopping early, and
+    /// whether some of the transactions were pruned.
+    #[allow(clippy::too_many_arguments)]
+    fn scan_account_transactions<E: InternalError>(
+        &self,
+        address: AccountAddress,
+        start_seq_number: u64,
+        num_to_scan: u64,
+        limit: Option<usize>,
+        ledger_version: u64,
+        filter: &AccountTransactionFilter,
+        ledger_info: &LedgerInfo,
+    ) -> Result<(Vec<u64>, Option<u64>, bool), E> {
+        let end_seq_number = start_seq_number.saturating_add(num_to_scan);
+        let mut versions = vec![];
+        let mut next_seq_number = None;
+        let mut pruned = false;
+        let iter = self
+            .db
+            .get_account_transaction_version_iterator(address, start_seq_number, ledger_version)
+            .context("Failed to retrieve account transactions")
+            .map_err(|err| {
+                E::internal_with_code(err, AptosErrorCode::InternalError, ledger_info)
+            })?;
+        for (scanned, item) in iter.enumerate() {
+            let (seq_number, version) = item
+                .context("Failed to retrieve account transactions")
+                .map_err(|err| {
+                    E::internal_with_code(err, AptosErrorCode::InternalError, ledger_info)
+                })?;
+            // The pruner deletes the account index after the transactions become unreadable,
+            // so either can be missing
+            if scanned == 0 && seq_number > start_seq_number {
+                pruned = true;
+            }
+            if seq_number >= end_seq_number || limit.map_or(false, |limit| versions.len() >= limit)
+            {
+                if limit.is_some() {
+                    next_seq_number = Some(seq_number);
+                }
+                break;
+            }
+            if version < ledger_info.oldest_ledger_version.0 {
+                pruned = true;
+                continue;
+            }
+            if self
+                .account_transaction_matches(version, filter)
+                .map_err(|err| {
+                    E::internal_with_code(err, AptosErrorCode::InternalError, ledger_info)
+                })?
+            {
+                versions.push(version);
+            }
+        }
+        Ok((versions, next_seq_number, pruned))
+    }
+
+    /// Reads only what `filter` needs from the transaction at `version`, without proofs or events
+    fn account_transaction_matches(
+        &self,
+        version: u64,
+        filter: &AccountTransactionFilter,
+    ) -> Result<bool> {
+        let txn = if filter.needs_transaction() {
+            self.db
+                .get_transaction_iterator(version, 1)?
+                .next()
+                .transpose()?
+        } else {
+            None
+        };
+        let info = if filter.needs_info() {
+            self.db
+                .get_transaction_info_iterator(version, 1)?
+                .next()
+                .transpose()?
+        } else {
+            None
+        };
+        Ok(filter.matches(txn.as_ref(), info.as_ref()))
+    }
+
     pub fn get_transaction_by_hash(
         &self,
         hash: HashValue,
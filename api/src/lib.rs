@@ -10,6 +10,7 @@ mod basic;
 mod bcs_payload;
 mod blocks;
 mod check_size;
+mod compression;
 pub mod context;
 mod error_converter;
 mod events;
@@ -18,12 +19,16 @@ mod index;
 mod log;
 pub mod metrics;
 mod page;
+mod resource_filter;
 mod response;
 mod runtime;
 mod set_failpoints;
 mod state;
+mod state_overrides;
+mod stream;
 #[cfg(test)]
 pub mod tests;
+mod transaction_filter;
 mod transactions;
 mod view_function;
 
@@ -62,3 +62,21 @@ pub static REQUEST_SOURCE_CLIENT: Lazy<IntCounterVec> = Lazy::new(|| {
     )
     .unwrap()
 });
+
+pub static COMPRESSION_RESPONSES: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_api_compression_responses",
+        "API responses grouped by content encoding (identity when not compressed)",
+        &["encoding"]
+    )
+    .unwrap()
+});
+
+pub static COMPRESSION_BYTES: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_api_compression_bytes",
+        "Bytes of compressed API responses grouped by content encoding, before and after compression",
+        &["encoding", "stage"]
+    )
+    .unwrap()
+});
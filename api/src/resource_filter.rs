@@ -0,0 +1,127 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::{format_err, Result};
+use aptos_api_types::ResourceGroup;
+use move_core_types::{
+    account_address::AccountAddress, identifier::Identifier, language_storage::StructTag,
+};
+
+/// Restricts the resources of an account to the ones of a given module or whose type starts
+/// with a given prefix. An empty filter matches every resource.
+#[derive(Clone, Debug, Default)]
+pub struct ResourceFilter {
+    module_address: Option<AccountAddress>,
+    module_name: Option<Identifier>,
+    /// Prefix of the resource type, with its address in the short form used by the API
+    type_prefix: Option<String>,
+}
+
+impl ResourceFilter {
+    pub fn new(
+        module_address: Option<AccountAddress>,
+        module_name: Option<Identifier>,
+        type_prefix: Option<&str>,
+    ) -> Result<Self> {
+        Ok(Self {
+            module_address,
+            module_name,
+            type_prefix: type_prefix.map(normalize_type_prefix).transpose()?,
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.module_address.is_none() && self.module_name.is_none() && self.type_prefix.is_none()
+    }
+
+    pub fn matches(&self, struct_tag: &StructTag) -> bool {
+        if let Some(module_address) = &self.module_address {
+            if &struct_tag.address != module_address {
+                return false;
+            }
+        }
+        if let Some(module_name) = &self.module_name {
+            if &struct_tag.module != module_name {
+                return false;
+            }
+        }
+        match &self.type_prefix {
+            Some(type_prefix) => struct_tag.to_string().starts_with(type_prefix),
+            None => true,
+        }
+    }
+
+    /// A resource group matches if any of the resources it holds does
+    pub fn matches_resource_group(&self, bytes: &[u8]) -> Result<bool> {
+        if self.is_empty() {
+            return Ok(true);
+        }
+        let group: ResourceGroup = bcs::from_bytes(bytes)?;
+        Ok(group.keys().any(|struct_tag| self.matches(struct_tag)))
+    }
+}
+
+/// Rewrites the address at the start of a type prefix to the short form resource types are
+/// displayed with, so `0x0001::coin` and `0x1::coin` select the same resources.
+fn normalize_type_prefix(type_prefix: &str) -> Result<String> {
+    let (address, rest) = match type_prefix.split_once("::") {
+        Some((address, rest)) => (address, Some(rest)),
+        None => (type_prefix, None),
+    };
+    let address = AccountAddress::from_hex_literal(address.trim()).map_err(|_| {
+        format_err!(
+            "Resource type prefix must start with an address, got: {}",
+            type_prefix
+        )
+    })?;
+    Ok(format!(
+        "0x{}::{}",
+        address.short_str_lossless(),
+        rest.unwrap_or_default()
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    fn struct_tag(s: &str) -> StructTag {
+        StructTag::from_str(s).unwrap()
+    }
+
+    #[test]
+    fn test_resource_filter() {
+        let coin_store = struct_tag("0x1::coin::CoinStore<0x1::aptos_coin::AptosCoin>");
+        let account = struct_tag("0x1::account::Account");
+        let custom = struct_tag("0xcafe::coin::CoinStore");
+
+        let filter = ResourceFilter::default();
+        assert!(filter.is_empty());
+        assert!(filter.matches(&coin_store) && filter.matches(&account));
+
+        let filter = ResourceFilter::new(None, None, Some("0x0001::coin")).unwrap();
+        assert!(filter.matches(&coin_store));
+        assert!(!filter.matches(&account));
+        assert!(!filter.matches(&custom));
+
+        let filter = ResourceFilter::new(None, None, Some("0x1")).unwrap();
+        assert!(filter.matches(&coin_store) && filter.matches(&account));
+
+        let filter =
+            ResourceFilter::new(None, Some(Identifier::new("coin").unwrap()), None).unwrap();
+        assert!(filter.matches(&coin_store) && filter.matches(&custom));
+        assert!(!filter.matches(&account));
+
+        let filter = ResourceFilter::new(
+            Some(AccountAddress::from_hex_literal("0xcafe").unwrap()),
+            Some(Identifier::new("coin").unwrap()),
+            None,
+        )
+        .unwrap();
+        assert!(filter.matches(&custom));
+        assert!(!filter.matches(&coin_store));
+
+        assert!(ResourceFilter::new(None, None, Some("coin::CoinStore")).is_err());
+    }
+}
@@ -471,6 +471,18 @@ macro_rules! generate_success_response {
                 }
                 self
             }
+
+            /// Sets the cursor to the account sequence number to continue from
+            pub fn with_sequence_number_cursor(mut self, next_sequence_number: Option<u64>) -> Self {
+                match self {
+                    $(
+                    [<$enum_name>]::$name(_, _, _, _, _, _, _, _, ref mut cursor) => {
+                        *cursor = next_sequence_number.map(|n| n.to_string());
+                    }
+                    )*
+                }
+                self
+            }
         }
         }
     };
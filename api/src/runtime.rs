@@ -4,9 +4,9 @@
 
 use crate::{
     accounts::AccountsApi, basic::BasicApi, blocks::BlocksApi, check_size::PostSizeLimit,
-    context::Context, error_converter::convert_error, events::EventsApi, index::IndexApi,
-    log::middleware_log, set_failpoints, state::StateApi, transactions::TransactionsApi,
-    view_function::ViewFunctionApi,
+    compression::Compression, context::Context, error_converter::convert_error, events::EventsApi,
+    index::IndexApi, log::middleware_log, set_failpoints, state::StateApi, stream,
+    transactions::TransactionsApi, view_function::ViewFunctionApi,
 };
 use anyhow::Context as AnyhowContext;
 use aptos_api_types::X_APTOS_CLIENT;
@@ -118,11 +118,14 @@ pub fn attach_poem_to_runtime(
     let context = Arc::new(context);
 
     let size_limit = context.content_length_limit();
+    let compression_enabled = config.api.compression_enabled;
+    let compression_min_size = config.api.compression_min_size;
 
     let api_service = get_api_service(context.clone());
 
     let spec_json = api_service.spec_endpoint();
     let spec_yaml = api_service.spec_endpoint_yaml();
+    let stream_api = Arc::new(stream::StreamApi::new(context.clone()));
 
     let mut address = config.api.address;
 
@@ -189,10 +192,13 @@ pub fn attach_poem_to_runtime(
                     .at(
                         "/set_failpoint",
                         poem::get(set_failpoints::set_failpoint_poem).data(context.clone()),
-                    ),
+                    )
+                    // WebSockets can't be described in the OpenAPI spec.
+                    .at("/stream", poem::get(stream::stream_poem).data(stream_api)),
             )
             .with(cors)
             .with(PostSizeLimit::new(size_limit))
+            .with_if(compression_enabled, Compression::new(compression_min_size))
             // NOTE: Make sure to keep this after all the `with` middleware.
             .catch_all_error(convert_error)
             .around(middleware_log);
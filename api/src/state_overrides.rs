@@ -0,0 +1,108 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::{format_err, Context as AnyhowContext, Result};
+use aptos_api_types::{AsConverter, StateOverrides};
+use aptos_state_view::{StateView, StateViewId, TStateView};
+use aptos_storage_interface::DbReader;
+use aptos_types::{
+    access_path::AccessPath,
+    account_address::AccountAddress,
+    account_config::CoinStoreResource,
+    state_store::{
+        state_key::StateKey, state_storage_usage::StateStorageUsage, state_value::StateValue,
+    },
+};
+use aptos_vm::data_cache::AsMoveResolver;
+use move_core_types::{
+    language_storage::{StructTag, TypeTag},
+    move_resource::MoveStructType,
+};
+use std::{collections::HashMap, sync::Arc};
+
+/// State view with some of the values of the `base` one replaced, to simulate transactions
+/// against a state that doesn't exist on chain
+pub struct OverriddenStateView<'a, S> {
+    base: &'a S,
+    overrides: HashMap<StateKey, StateValue>,
+}
+
+impl<'a, S: StateView> OverriddenStateView<'a, S> {
+    /// Resolves the overridden resources and balances against the `base` state, balance
+    /// overrides applying on top of the resource ones
+    pub fn new(
+        base: &'a S,
+        db: Arc<dyn DbReader>,
+        state_overrides: &StateOverrides,
+    ) -> Result<Self> {
+        let mut overrides = HashMap::new();
+        let resolver = base.as_move_resolver();
+        let converter = resolver.as_converter(db);
+        for resource in &state_overrides.resources {
+            let struct_tag = StructTag::try_from(resource.resource_type.clone())?;
+            let type_tag = TypeTag::Struct(Box::new(struct_tag.clone()));
+            let value = converter
+                .try_into_vm_value(&type_tag, resource.data.clone())
+                .with_context(|| {
+                    format!(
+                        "Invalid data of resource {} of account {}",
+                        resource.resource_type, resource.address
+                    )
+                })?;
+            let bytes = value.simple_serialize().ok_or_else(|| {
+                format_err!("Failed to serialize resource {}", resource.resource_type)
+            })?;
+            overrides.insert(
+                resource_key(resource.address.into(), struct_tag)?,
+                StateValue::new_legacy(bytes),
+            );
+        }
+        for balance in &state_overrides.balances {
+            let key = resource_key(balance.address.into(), CoinStoreResource::struct_tag())?;
+            let bytes = match overrides.get(&key) {
+                Some(value) => Some(value.bytes().to_vec()),
+                None => base.get_state_value_bytes(&key)?,
+            }
+            .ok_or_else(|| format_err!("Account {} has no APT coin store", balance.address))?;
+            let coin_store: CoinStoreResource = bcs::from_bytes(&bytes)
+                .with_context(|| format!("Invalid APT coin store of {}", balance.address))?;
+            let coin_store = CoinStoreResource::new(
+                balance.amount.0,
+                coin_store.frozen(),
+                coin_store.deposit_events().clone(),
+                coin_store.withdraw_events().clone(),
+            );
+            overrides.insert(key, StateValue::new_legacy(bcs::to_bytes(&coin_store)?));
+        }
+        Ok(Self { base, overrides })
+    }
+}
+
+fn resource_key(address: AccountAddress, struct_tag: StructTag) -> Result<StateKey> {
+    Ok(StateKey::access_path(AccessPath::resource_access_path(
+        address, struct_tag,
+    )?))
+}
+
+impl<'a, S: StateView> TStateView for OverriddenStateView<'a, S> {
+    type Key = StateKey;
+
+    fn id(&self) -> StateViewId {
+        self.base.id()
+    }
+
+    fn get_state_value(&self, state_key: &StateKey) -> Result<Option<StateValue>> {
+        match self.overrides.get(state_key) {
+            Some(value) => Ok(Some(value.clone())),
+            None => self.base.get_state_value(state_key),
+        }
+    }
+
+    fn is_genesis(&self) -> bool {
+        self.base.is_genesis()
+    }
+
+    fn get_usage(&self) -> Result<StateStorageUsage> {
+        self.base.get_usage()
+    }
+}
@@ -0,0 +1,439 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Streaming of committed transactions or events over a WebSocket, so clients following the
+//! chain don't need to poll the paginated endpoints.
+//!
+//! The client connects to `/v1/stream` with the query parameters of [`StreamParams`]. The
+//! server then sends JSON text messages ([`StreamMessage`]): the matching transactions or
+//! events in order of version, and after each processed page of versions a `cursor` message
+//! with the version to resume from, to be passed as `start_version` when reconnecting. Cursor
+//! messages are sent even when nothing in the page matched, so clients with sparse filters
+//! can still checkpoint their progress.
+
+use crate::context::Context;
+use anyhow::{bail, Context as AnyhowContext, Result};
+use aptos_api_types::{
+    Address, AsConverter, EntryFunctionId, Transaction, TransactionOnChainData, VersionedEvent, U64,
+};
+use aptos_logger::debug;
+use aptos_types::{
+    account_address::AccountAddress,
+    contract_event::{ContractEvent, EventWithVersion},
+    transaction::TransactionPayload,
+};
+use aptos_vm::data_cache::AsMoveResolver;
+use futures::{SinkExt, StreamExt};
+use move_core_types::{
+    identifier::Identifier,
+    language_storage::{ModuleId, TypeTag},
+    parser::parse_type_tag,
+};
+use poem::{
+    handler,
+    http::StatusCode,
+    web::{
+        websocket::{Message, WebSocket, WebSocketStream},
+        Data, Query,
+    },
+    IntoResponse,
+};
+use serde::{Deserialize, Serialize};
+use std::{str::FromStr, sync::Arc, time::Duration};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+/// How often a stream caught up with the chain checks for new versions
+const POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// State shared by the streams of the node
+pub struct StreamApi {
+    pub context: Arc<Context>,
+    /// Permits for the open streams, up to `max_stream_connections`
+    connections: Arc<Semaphore>,
+}
+
+impl StreamApi {
+    pub fn new(context: Arc<Context>) -> Self {
+        let max_connections = context.node_config.api.max_stream_connections;
+        Self {
+            context,
+            connections: Arc::new(Semaphore::new(max_connections)),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum StreamKind {
+    #[default]
+    Transactions,
+    Events,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct StreamParams {
+    /// Version to start streaming from, the `cursor` of the last message received when
+    /// resuming. Defaults to the version after the latest one.
+    start_version: Option<u64>,
+    /// Whether to stream transactions or events, defaults to transactions
+    #[serde(default)]
+    kind: StreamKind,
+    /// Only transactions sent by the account, or events emitted to its handles
+    account: Option<String>,
+    /// Only transactions emitting an event of the type, or events of the type,
+    /// e.g. `0x1::coin::DepositEvent`
+    event_type: Option<String>,
+    /// Only transactions calling the entry function, or events they emitted,
+    /// e.g. `0x1::coin::transfer`
+    entry_function: Option<String>,
+}
+
+/// Messages sent to the client, as JSON text
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum StreamMessage {
+    Transaction {
+        transaction: Transaction,
+    },
+    Event {
+        event: VersionedEvent,
+    },
+    /// Every version before `cursor` was sent, resume from it
+    Cursor {
+        cursor: U64,
+    },
+    /// The stream failed and is closed
+    Error {
+        message: String,
+    },
+}
+
+/// Filters of a stream, all the ones set need to match
+#[derive(Debug, Default)]
+struct StreamFilter {
+    account: Option<AccountAddress>,
+    event_type: Option<TypeTag>,
+    entry_function: Option<(ModuleId, Identifier)>,
+}
+
+impl StreamFilter {
+    fn from_params(params: &StreamParams) -> Result<Self> {
+        let account = params
+            .account
+            .as_deref()
+            .map(|account| Address::from_str(account).map(AccountAddress::from))
+            .transpose()
+            .context("Invalid account")?;
+        let event_type = params
+            .event_type
+            .as_deref()
+            .map(parse_type_tag)
+            .transpose()
+            .context("Invalid event_type")?;
+        let entry_function = params
+            .entry_function
+            .as_deref()
+            .map(|function| {
+                EntryFunctionId::from_str(function)
+                    .map(|id| (ModuleId::from(id.module), Identifier::from(id.name)))
+            })
+            .transpose()
+            .context("Invalid entry_function")?;
+        Ok(Self {
+            account,
+            event_type,
+            entry_function,
+        })
+    }
+
+    fn matches_entry_function(&self, txn: &TransactionOnChainData) -> bool {
+        let (module, function) = match &self.entry_function {
+            Some(entry_function) => entry_function,
+            None => return true,
+        };
+        match txn
+            .transaction
+            .try_as_signed_user_txn()
+            .map(|txn| txn.payload())
+        {
+            Some(TransactionPayload::EntryFunction(entry_function)) => {
+                entry_function.module() == module
+                    && entry_function.function() == function.as_ident_str()
+            },
+            _ => false,
+        }
+    }
+
+    fn matches_transaction(&self, txn: &TransactionOnChainData) -> bool {
+        let sent_by_account = match self.account {
+            Some(account) => txn
+                .transaction
+                .try_as_signed_user_txn()
+                .map_or(false, |txn| txn.sender() == account),
+            None => true,
+        };
+        let emits_event_type = match &self.event_type {
+            Some(event_type) => txn
+                .events
+                .iter()
+                .any(|event| event.type_tag() == event_type),
+            None => true,
+        };
+        sent_by_account && emits_event_type && self.matches_entry_function(txn)
+    }
+
+    fn matches_event(&self, event: &ContractEvent) -> bool {
+        self.account
+            .map_or(true, |account| event.key().get_creator_address() == account)
+            && self
+                .event_type
+                .as_ref()
+                .map_or(true, |event_type| event.type_tag() == event_type)
+    }
+}
+
+/// Streams committed transactions or events over a WebSocket, see the module documentation
+#[handler]
+pub async fn stream_poem(
+    ws: WebSocket,
+    Query(params): Query<StreamParams>,
+    api: Data<&Arc<StreamApi>>,
+) -> poem::Result<impl IntoResponse> {
+    if !api.context.node_config.api.stream_enabled {
+        return Err(poem::Error::from_string(
+            "Streaming is disabled on this node",
+            StatusCode::FORBIDDEN,
+        ));
+    }
+    let filter = StreamFilter::from_params(&params)
+        .map_err(|err| poem::Error::from_string(format!("{:#}", err), StatusCode::BAD_REQUEST))?;
+    let permit = api.connections.clone().try_acquire_owned().map_err(|_| {
+        poem::Error::from_string(
+            "Too many open streams, try again later",
+            StatusCode::SERVICE_UNAVAILABLE,
+        )
+    })?;
+    let context = api.context.clone();
+    Ok(ws.on_upgrade(move |socket| run_stream(context, params, filter, socket, permit)))
+}
+
+async fn run_stream(
+    context: Arc<Context>,
+    params: StreamParams,
+    filter: StreamFilter,
+    socket: WebSocketStream,
+    _permit: OwnedSemaphorePermit,
+) {
+    let (mut sink, mut incoming) = socket.split();
+    let filter = Arc::new(filter);
+    let mut cursor = match params.start_version {
+        Some(start_version) => start_version,
+        None => {
+            let context = context.clone();
+            match read_db(move || context.get_latest_ledger_info_wrapped()).await {
+                Ok(ledger_info) => ledger_info.version() + 1,
+                Err(err) => return close_with_error(&mut sink, err).await,
+            }
+        },
+    };
+    loop {
+        let page = {
+            let (context, filter, kind) = (context.clone(), filter.clone(), params.kind);
+            read_db(move || next_page(&context, kind, &filter, cursor)).await
+        };
+        cursor = match page {
+            Ok(Some((next_cursor, messages))) => {
+                for message in messages.iter().chain(&[StreamMessage::Cursor {
+                    cursor: next_cursor.into(),
+                }]) {
+                    if send(&mut sink, message).await.is_err() {
+                        return;
+                    }
+                }
+                next_cursor
+            },
+            Ok(None) => {
+                // Caught up with the chain, wait for new versions unless the client leaves
+                tokio::select! {
+                    message = incoming.next() => match message {
+                        Some(Ok(Message::Close(_))) | Some(Err(_)) | None => return,
+                        Some(Ok(_)) => {},
+                    },
+                    _ = tokio::time::sleep(POLL_INTERVAL) => {},
+                }
+                cursor
+            },
+            Err(err) => return close_with_error(&mut sink, err).await,
+        };
+    }
+}
+
+/// Runs the DB reads off the async runtime, as streams can read many pages in a row
+async fn read_db<T: Send + 'static>(f: impl FnOnce() -> Result<T> + Send + 'static) -> Result<T> {
+    tokio::task::spawn_blocking(f)
+        .await
+        .context("Failed to read from the DB")?
+}
+
+async fn close_with_error<S: futures::Sink<Message> + Unpin>(sink: &mut S, err: anyhow::Error) {
+    debug!("Closing stream after error: {:#}", err);
+    let message = StreamMessage::Error {
+        message: format!("{:#}", err),
+    };
+    let _ = send(sink, &message).await;
+    let _ = sink.close().await;
+}
+
+async fn send<S: futures::Sink<Message> + Unpin>(
+    sink: &mut S,
+    message: &StreamMessage,
+) -> Result<(), S::Error> {
+    let text = serde_json::to_string(message).expect("Stream messages serialize to JSON");
+    sink.send(Message::Text(text)).await
+}
+
+/// Matching messages of the page of versions starting at `cursor`, with the cursor after
+/// the page, or None if there is no version to read yet
+fn next_page(
+    context: &Context,
+    kind: StreamKind,
+    filter: &StreamFilter,
+    start_version: u64,
+) -> Result<Option<(u64, Vec<StreamMessage>)>> {
+    let ledger_info = context.get_latest_ledger_info_wrapped()?;
+    let ledger_version = ledger_info.version();
+    if start_version < ledger_info.oldest_ledger_version.0 {
+        bail!(
+            "Version {} is pruned, the oldest version available is {}",
+            start_version,
+            ledger_info.oldest_ledger_version.0
+        );
+    }
+    if start_version > ledger_version {
+        return Ok(None);
+    }
+    let limit = std::cmp::min(
+        context.max_transactions_page_size() as u64,
+        ledger_version - start_version + 1,
+    ) as u16;
+    let txns = context
+        .get_transactions(start_version, limit, ledger_version)
+        .context("Failed to read transactions")?;
+    let next_cursor = start_version + txns.len() as u64;
+
+    let state_view = context.state_view_at_version(ledger_version)?;
+    let resolver = state_view.as_move_resolver();
+    let converter = resolver.as_converter(context.db.clone());
+    let txns = txns
+        .into_iter()
+        .filter(|txn| match kind {
+            StreamKind::Transactions => filter.matches_transaction(txn),
+            StreamKind::Events => filter.matches_entry_function(txn),
+        })
+        .collect::<Vec<_>>();
+    let messages = match kind {
+        StreamKind::Transactions => txns
+            .into_iter()
+            .map(|txn| {
+                let timestamp = context.db.get_block_timestamp(txn.version)?;
+                let transaction = converter.try_into_onchain_transaction(timestamp, txn)?;
+                Ok(StreamMessage::Transaction { transaction })
+            })
+            .collect::<Result<Vec<_>>>()
+            .context("Failed to convert transactions from storage")?,
+        StreamKind::Events => {
+            let events = txns
+                .into_iter()
+                .flat_map(|txn| {
+                    let version = txn.version;
+                    txn.events
+                        .into_iter()
+                        .filter(|event| filter.matches_event(event))
+                        .map(move |event| EventWithVersion::new(version, event))
+                })
+                .collect::<Vec<_>>();
+            converter
+                .try_into_versioned_events(&events)
+                .context("Failed to convert events from storage")?
+                .into_iter()
+                .map(|event| StreamMessage::Event { event })
+                .collect()
+        },
+    };
+    Ok(Some((next_cursor, messages)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn params(
+        account: Option<&str>,
+        event_type: Option<&str>,
+        entry_function: Option<&str>,
+    ) -> StreamParams {
+        StreamParams {
+            start_version: None,
+            kind: StreamKind::Transactions,
+            account: account.map(String::from),
+            event_type: event_type.map(String::from),
+            entry_function: entry_function.map(String::from),
+        }
+    }
+
+    #[test]
+    fn test_filter_from_params() {
+        let filter = StreamFilter::from_params(&params(
+            Some("0x1"),
+            Some("0x1::coin::DepositEvent"),
+            Some("0x1::coin::transfer"),
+        ))
+        .unwrap();
+        assert_eq!(filter.account, Some(AccountAddress::ONE));
+        assert_eq!(
+            filter.event_type,
+            Some(parse_type_tag("0x1::coin::DepositEvent").unwrap())
+        );
+        let (module, function) = filter.entry_function.unwrap();
+        assert_eq!(module.address(), &AccountAddress::ONE);
+        assert_eq!(module.name().as_str(), "coin");
+        assert_eq!(function.as_str(), "transfer");
+
+        let filter = StreamFilter::from_params(&params(None, None, None)).unwrap();
+        assert!(filter.account.is_none());
+        assert!(filter.event_type.is_none());
+        assert!(filter.entry_function.is_none());
+    }
+
+    #[test]
+    fn test_invalid_filter() {
+        assert!(StreamFilter::from_params(&params(Some("0xzz"), None, None)).is_err());
+        assert!(StreamFilter::from_params(&params(None, Some("not a type!"), None)).is_err());
+        assert!(StreamFilter::from_params(&params(None, None, Some("transfer"))).is_err());
+    }
+
+    #[test]
+    fn test_event_filter() {
+        let deposit = parse_type_tag("0x1::coin::DepositEvent").unwrap();
+        let withdraw = parse_type_tag("0x1::coin::WithdrawEvent").unwrap();
+        let account = AccountAddress::from_hex_literal("0xa").unwrap();
+        let event = |creator, type_tag: &TypeTag| {
+            ContractEvent::new(
+                aptos_types::event::EventKey::new(0, creator),
+                0,
+                type_tag.clone(),
+                vec![],
+            )
+        };
+
+        let filter = StreamFilter {
+            account: Some(account),
+            event_type: Some(deposit.clone()),
+            ..Default::default()
+        };
+        assert!(filter.matches_event(&event(account, &deposit)));
+        assert!(!filter.matches_event(&event(account, &withdraw)));
+        assert!(!filter.matches_event(&event(AccountAddress::ONE, &deposit)));
+        assert!(StreamFilter::default().matches_event(&event(AccountAddress::ONE, &withdraw)));
+    }
+}
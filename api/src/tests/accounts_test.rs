@@ -277,6 +277,75 @@ async fn test_get_account_resources_with_pagination() {
     assert_eq!(resources, all_resources[10..].to_vec());
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_account_resources_with_filter() {
+    let context = new_test_context(current_function_name!());
+    let address = "0x1";
+
+    let req = warp::test::request()
+        .method("GET")
+        .path(&format!("/v1{}", account_resources(address)));
+    let resp = context.reply(req).await;
+    assert_eq!(resp.status(), 200);
+    let all_resources: Vec<MoveResource> = serde_json::from_slice(resp.body()).unwrap();
+
+    // The address of the prefix doesn't need to be in its short form
+    let expected: Vec<MoveResource> = all_resources
+        .iter()
+        .filter(|resource| resource.typ.to_string().starts_with("0x1::account::"))
+        .cloned()
+        .collect();
+    assert!(!expected.is_empty());
+    let req = warp::test::request().method("GET").path(&format!(
+        "/v1{}?resource_type_prefix=0x01::account::",
+        account_resources(address)
+    ));
+    let resp = context.reply(req).await;
+    assert_eq!(resp.status(), 200);
+    let resources: Vec<MoveResource> = serde_json::from_slice(resp.body()).unwrap();
+    assert_eq!(resources, expected);
+
+    // Page through the resources of a module, only matching ones count towards the limit
+    let expected: Vec<MoveResource> = all_resources
+        .iter()
+        .filter(|resource| resource.typ.module.0.as_str() == "coin")
+        .cloned()
+        .collect();
+    assert!(!expected.is_empty());
+    let mut resources: Vec<MoveResource> = vec![];
+    let mut start = None;
+    loop {
+        let mut path = format!(
+            "/v1{}?module_address=0x1&module_name=coin&limit=1",
+            account_resources(address)
+        );
+        if let Some(start) = &start {
+            path.push_str(&format!("&start={}", start));
+        }
+        let resp = context
+            .reply(warp::test::request().method("GET").path(&path))
+            .await;
+        assert_eq!(resp.status(), 200);
+        let page: Vec<MoveResource> = serde_json::from_slice(resp.body()).unwrap();
+        assert_eq!(page.len(), 1);
+        resources.extend(page);
+        match resp.headers().get("X-Aptos-Cursor") {
+            Some(cursor) => {
+                start = Some(StateKeyWrapper::from_str(cursor.to_str().unwrap()).unwrap())
+            },
+            None => break,
+        }
+    }
+    assert_eq!(resources, expected);
+
+    let req = warp::test::request().method("GET").path(&format!(
+        "/v1{}?resource_type_prefix=account::Account",
+        account_resources(address)
+    ));
+    let resp = context.reply(req).await;
+    assert_eq!(resp.status(), 400);
+}
+
 // Same as the above test but for modules.
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn test_get_account_modules_with_pagination() {
@@ -592,6 +592,165 @@ async fn test_get_account_transactions() {
     assert_json(txns, expected_txns);
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_account_transactions_filter_by_function_and_status() {
+    let mut context = new_test_context(current_function_name!());
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account).await;
+    context.commit_block(&vec![txn]).await;
+    // Aborts, the root account doesn't hold that much
+    let mut root = context.root_account().await;
+    let txn = context.account_transfer(&mut root, &account, u64::MAX);
+    context.commit_block(&vec![txn]).await;
+    let txn = context.mint_user_account(&account).await;
+    context.commit_block(&vec![txn]).await;
+
+    let root_address = context.root_account().await.address();
+    let sequence_numbers = |txns: serde_json::Value| -> Vec<String> {
+        txns.as_array()
+            .unwrap()
+            .iter()
+            .map(|txn| txn["sequence_number"].as_str().unwrap().to_string())
+            .collect()
+    };
+
+    let txns = context
+        .get(&format!(
+            "/accounts/{}/transactions?function=0x1::aptos_account::transfer",
+            root_address
+        ))
+        .await;
+    assert_eq!(sequence_numbers(txns), vec!["1", "2"]);
+
+    let txns = context
+        .get(&format!(
+            "/accounts/{}/transactions?function=0x1::aptos_account::transfer&success=true",
+            root_address
+        ))
+        .await;
+    assert_eq!(sequence_numbers(txns), vec!["2"]);
+
+    let txns = context
+        .get(&format!(
+            "/accounts/{}/transactions?success=false",
+            root_address
+        ))
+        .await;
+    assert_eq!(sequence_numbers(txns), vec!["1"]);
+
+    // A full page returns the sequence number to continue from
+    let resp = context
+        .reply(warp::test::request().method("GET").path(&format!(
+            "/v1/accounts/{}/transactions?success=true&start=0&limit=1",
+            root_address
+        )))
+        .await;
+    assert_eq!(resp.status(), 200);
+    assert_eq!(resp.headers().get("X-Aptos-Cursor").unwrap(), "1");
+    let txns: serde_json::Value = serde_json::from_slice(resp.body()).unwrap();
+    assert_eq!(sequence_numbers(txns), vec!["0"]);
+
+    let resp = context
+        .reply(warp::test::request().method("GET").path(&format!(
+            "/v1/accounts/{}/transactions?success=true&start=1&limit=1",
+            root_address
+        )))
+        .await;
+    assert_eq!(resp.status(), 200);
+    assert!(!resp.headers().contains_key("X-Aptos-Cursor"));
+    let txns: serde_json::Value = serde_json::from_slice(resp.body()).unwrap();
+    assert_eq!(sequence_numbers(txns), vec!["2"]);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_account_transactions_filter_beyond_scan_limit() {
+    let mut node_config = NodeConfig::default();
+    node_config.api.max_account_transactions_filter_scan = 2;
+    let mut context = new_test_context_with_config(current_function_name!(), node_config);
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account).await;
+    context.commit_block(&vec![txn]).await;
+    let mut root = context.root_account().await;
+    let txn = context.account_transfer(&mut root, &account, u64::MAX);
+    context.commit_block(&vec![txn]).await;
+    for _ in 0..3 {
+        let txn = context.account_transfer(&mut root, &account, 1);
+        context.commit_block(&vec![txn]).await;
+    }
+
+    // The failed transaction is older than the latest scanned ones, the cursor leads to it
+    let resp = context
+        .reply(warp::test::request().method("GET").path(&format!(
+            "/v1/accounts/{}/transactions?success=false",
+            root.address()
+        )))
+        .await;
+    assert_eq!(resp.status(), 200);
+    assert_eq!(resp.headers().get("X-Aptos-Cursor").unwrap(), "1");
+    let txns: serde_json::Value = serde_json::from_slice(resp.body()).unwrap();
+    assert_json(txns, json!([]));
+
+    let txns = context
+        .get(&format!(
+            "/accounts/{}/transactions?success=false&start=1",
+            root.address()
+        ))
+        .await;
+    assert_eq!(txns.as_array().unwrap().len(), 1);
+    assert_eq!(txns[0]["sequence_number"], "1");
+
+    let resp = context
+        .reply(warp::test::request().method("GET").path(&format!(
+            "/v1/accounts/{}/transactions?success=false&start=2",
+            root.address()
+        )))
+        .await;
+    assert_eq!(resp.status(), 200);
+    assert_eq!(resp.headers().get("X-Aptos-Cursor").unwrap(), "4");
+    let txns: serde_json::Value = serde_json::from_slice(resp.body()).unwrap();
+    assert_json(txns, json!([]));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_account_transactions_filter_without_match_beyond_scan_limit() {
+    let mut node_config = NodeConfig::default();
+    node_config.api.max_account_transactions_filter_scan = 2;
+    let mut context = new_test_context_with_config(current_function_name!(), node_config);
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account).await;
+    context.commit_block(&vec![txn]).await;
+    let mut root = context.root_account().await;
+    for _ in 0..3 {
+        let txn = context.account_transfer(&mut root, &account, 1);
+        context.commit_block(&vec![txn]).await;
+    }
+
+    // Only the latest transactions are read, and then the earlier ones one request at a time
+    let mut cursors = vec![];
+    let mut path = format!("/v1/accounts/{}/transactions?success=false", root.address());
+    loop {
+        let resp = context
+            .reply(warp::test::request().method("GET").path(&path))
+            .await;
+        assert_eq!(resp.status(), 200);
+        let txns: serde_json::Value = serde_json::from_slice(resp.body()).unwrap();
+        assert_json(txns, json!([]));
+        match resp.headers().get("X-Aptos-Cursor") {
+            Some(cursor) => {
+                let cursor = cursor.to_str().unwrap().to_string();
+                path = format!(
+                    "/v1/accounts/{}/transactions?success=false&start={}",
+                    root.address(),
+                    cursor
+                );
+                cursors.push(cursor);
+            },
+            None => break,
+        }
+    }
+    assert_eq!(cursors, vec!["0", "2"]);
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn test_get_account_transactions_filter_transactions_by_start_sequence_number() {
     let mut context = new_test_context(current_function_name!());
@@ -1306,3 +1465,70 @@ fn gen_string(len: u64) -> String {
 fn build_path(path: &str) -> String {
     format!("/v1/transactions{}", path)
 }
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_simulation_with_balance_override() {
+    let mut context = new_test_context(current_function_name!());
+    let account = context.gen_account();
+    let txn = context.mint_user_account(&account).await;
+    context.commit_block(&vec![txn]).await;
+
+    let amount = 1_000_000_000_000_000u64;
+    let payload = json!({
+        "type": "entry_function_payload",
+        "function": "0x1::aptos_account::transfer",
+        "type_arguments": [],
+        "arguments": [AccountAddress::ONE.to_hex_literal(), amount.to_string()],
+    });
+    let resp = context
+        .simulate_transaction(&account, payload.clone(), 200)
+        .await;
+    assert!(!resp[0]["success"].as_bool().unwrap(), "{}", pretty(&resp));
+
+    let resp = context
+        .simulate_transaction_with_overrides(
+            &account,
+            payload.clone(),
+            json!({
+                "balances": [{
+                    "address": account.address().to_hex_literal(),
+                    "amount": (2 * amount).to_string(),
+                }],
+            }),
+            200,
+        )
+        .await;
+    assert!(resp[0]["success"].as_bool().unwrap(), "{}", pretty(&resp));
+
+    // Nothing is written to the ledger
+    let resp = context.simulate_transaction(&account, payload, 200).await;
+    assert!(!resp[0]["success"].as_bool().unwrap(), "{}", pretty(&resp));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_simulation_with_invalid_resource_override() {
+    let mut context = new_test_context(current_function_name!());
+    let account = context.gen_account();
+    let txn = context.mint_user_account(&account).await;
+    context.commit_block(&vec![txn]).await;
+
+    context
+        .simulate_transaction_with_overrides(
+            &account,
+            json!({
+                "type": "entry_function_payload",
+                "function": "0x1::aptos_account::transfer",
+                "type_arguments": [],
+                "arguments": [AccountAddress::ONE.to_hex_literal(), "1"],
+            }),
+            json!({
+                "resources": [{
+                    "address": account.address().to_hex_literal(),
+                    "type": "0x1::account::Account",
+                    "data": { "sequence_number": "not a number" },
+                }],
+            }),
+            400,
+        )
+        .await;
+}
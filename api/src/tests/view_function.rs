@@ -111,3 +111,55 @@ async fn test_view_tuple() {
         .await;
     context.check_golden_output_no_prune(resp);
 }
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_view_batch() {
+    let mut context = new_test_context(current_function_name!());
+    let creator = &mut context.gen_account();
+    let owner = &mut context.gen_account();
+    let txn1 = context.mint_user_account(creator).await;
+    let txn2 = context.account_transfer(creator, owner, 100_000);
+
+    context.commit_block(&vec![txn1, txn2]).await;
+
+    let resp = context
+        .post(
+            "/view/batch",
+            json!([
+                {
+                    "function":"0x1::coin::balance",
+                    "arguments": vec![owner.address().to_string()],
+                    "type_arguments": vec!["0x1::aptos_coin::AptosCoin"],
+                },
+                {
+                    "function":"0x1::aptos_account::assert_account_exists",
+                    "arguments": vec![owner.address().to_string()],
+                    "type_arguments": [],
+                },
+            ]),
+        )
+        .await;
+
+    let results = resp.as_array().unwrap();
+    assert_eq!(results.len(), 2);
+    assert_eq!(results[0]["result"], json!(["100000"]));
+    assert!(results[0].get("error").is_none());
+    assert!(results[1].get("result").is_none());
+    assert_eq!(results[1]["error"]["error_code"], json!("invalid_input"));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_view_batch_too_large() {
+    let context = new_test_context(current_function_name!());
+    let request = json!({
+        "function":"0x1::coin::balance",
+        "arguments": ["0x1"],
+        "type_arguments": vec!["0x1::aptos_coin::AptosCoin"],
+    });
+    let max_view_batch_size = context.context.max_view_batch_size();
+
+    context
+        .expect_status_code(400)
+        .post("/view/batch", json!(vec![request; max_view_batch_size + 1]))
+        .await;
+}
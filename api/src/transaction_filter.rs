@@ -0,0 +1,61 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_api_types::EntryFunctionId;
+use aptos_types::transaction::{Transaction, TransactionInfo, TransactionPayload};
+use move_core_types::{identifier::Identifier, language_storage::ModuleId};
+
+/// Restricts the transactions of an account to the ones calling a given entry function and/or
+/// with a given execution status. An empty filter matches every transaction.
+#[derive(Clone, Debug, Default)]
+pub struct AccountTransactionFilter {
+    entry_function: Option<(ModuleId, Identifier)>,
+    success: Option<bool>,
+}
+
+impl AccountTransactionFilter {
+    pub fn new(entry_function: Option<EntryFunctionId>, success: Option<bool>) -> Self {
+        Self {
+            entry_function: entry_function
+                .map(|function| (function.module.into(), function.name.into())),
+            success,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entry_function.is_none() && self.success.is_none()
+    }
+
+    pub fn needs_transaction(&self) -> bool {
+        self.entry_function.is_some()
+    }
+
+    pub fn needs_info(&self) -> bool {
+        self.success.is_some()
+    }
+
+    /// `txn` and `info` only have to be given when `needs_transaction` and `needs_info`
+    /// respectively return true
+    pub fn matches(&self, txn: Option<&Transaction>, info: Option<&TransactionInfo>) -> bool {
+        if let Some((module, function)) = &self.entry_function {
+            let calls_function = match txn {
+                Some(Transaction::UserTransaction(signed_txn)) => match signed_txn.payload() {
+                    TransactionPayload::EntryFunction(entry_function) => {
+                        entry_function.module() == module
+                            && entry_function.function() == function.as_ident_str()
+                    },
+                    _ => false,
+                },
+                _ => false,
+            };
+            if !calls_function {
+                return false;
+            }
+        }
+        match (self.success, info) {
+            (Some(success), Some(info)) => info.status().is_success() == success,
+            (Some(_), None) => false,
+            (None, _) => true,
+        }
+    }
+}
@@ -16,14 +16,17 @@ use crate::{
         BasicErrorWith404, BasicResponse, BasicResponseStatus, BasicResult, BasicResultWith404,
         InsufficientStorageError, InternalError,
     },
+    state_overrides::OverriddenStateView,
+    transaction_filter::AccountTransactionFilter,
     ApiTags,
 };
 use anyhow::{anyhow, Context as AnyhowContext};
 use aptos_api_types::{
     verify_function_identifier, verify_module_identifier, Address, AptosError, AptosErrorCode,
-    AsConverter, EncodeSubmissionRequest, GasEstimation, GasEstimationBcs, HashValue,
-    HexEncodedBytes, LedgerInfo, MoveType, PendingTransaction, SubmitTransactionRequest,
-    Transaction, TransactionData, TransactionOnChainData, TransactionsBatchSingleSubmissionFailure,
+    AsConverter, EncodeSubmissionRequest, EntryFunctionId, GasEstimation, GasEstimationBcs,
+    HashValue, HexEncodedBytes, LedgerInfo, MoveType, PendingTransaction,
+    SimulateTransactionRequest, StateOverrides, SubmitTransactionRequest, Transaction,
+    TransactionData, TransactionOnChainData, TransactionsBatchSingleSubmissionFailure,
     TransactionsBatchSubmissionResult, UserTransaction, VerifyInput, VerifyInputWithRecursion,
     MAX_RECURSIVE_TYPES_ALLOWED, U64,
 };
@@ -255,6 +258,15 @@ impl TransactionsApi {
         ///
         /// If not provided, defaults to default page size
         limit: Query<Option<u16>>,
+        /// Only return transactions calling this entry function, e.g. `0x1::coin::transfer`
+        ///
+        /// When filtering, a bounded number of the account's transactions are read per
+        /// request, so a page may hold less than `limit` transactions. The X-Aptos-Cursor
+        /// header then gives the `start` to continue from, or to read the older transactions
+        /// from when `start` is not provided.
+        function: Query<Option<EntryFunctionId>>,
+        /// Only return successful transactions if true, or failed ones if false
+        success: Query<Option<bool>>,
     ) -> BasicResultWith404<Vec<Transaction>> {
         fail_point_poem("endpoint_get_accounts_transactions")?;
         self.context
@@ -264,7 +276,8 @@ impl TransactionsApi {
             limit.0,
             self.context.max_transactions_page_size(),
         );
-        self.list_by_account(&accept_type, page, address.0)
+        let filter = AccountTransactionFilter::new(function.0, success.0);
+        self.list_by_account(&accept_type, page, address.0, &filter)
     }
 
     /// Submit transaction
@@ -429,100 +442,81 @@ impl TransactionsApi {
         self.context
             .check_api_output_enabled("Simulate transaction", &accept_type)?;
         let ledger_info = self.context.get_latest_ledger_info()?;
-        let mut signed_transaction = self.get_signed_transaction(&ledger_info, data)?;
+        let signed_transaction = self.get_signed_transaction(&ledger_info, data)?;
 
-        let estimated_gas_unit_price = match (
+        self.simulate_with_estimates(
+            &accept_type,
+            ledger_info,
+            signed_transaction,
+            estimate_max_gas_amount.0.unwrap_or_default(),
             estimate_gas_unit_price.0.unwrap_or_default(),
             estimate_prioritized_gas_unit_price.0.unwrap_or_default(),
-        ) {
-            (_, true) => {
-                let gas_estimation = self.context.estimate_gas_price(&ledger_info)?;
-                // The prioritized gas estimate should always be set, but if it's not use the gas estimate
-                Some(
-                    gas_estimation
-                        .prioritized_gas_estimate
-                        .unwrap_or(gas_estimation.gas_estimate),
-                )
-            },
-            (true, false) => Some(self.context.estimate_gas_price(&ledger_info)?.gas_estimate),
-            (false, false) => None,
-        };
-
-        // If estimate max gas amount is provided, we will just make it the maximum value
-        let estimated_max_gas_amount = if estimate_max_gas_amount.0.unwrap_or_default() {
-            // Retrieve max possible gas units
-            let (_, gas_params) = self.context.get_gas_schedule(&ledger_info)?;
-            let min_number_of_gas_units = u64::from(gas_params.vm.txn.min_transaction_gas_units)
-                / u64::from(gas_params.vm.txn.gas_unit_scaling_factor);
-            let max_number_of_gas_units = u64::from(gas_params.vm.txn.maximum_number_of_gas_units);
-
-            // Retrieve account balance to determine max gas available
-            let account_state = self
-                .context
-                .get_account_state(
-                    signed_transaction.sender(),
-                    ledger_info.version(),
-                    &ledger_info,
-                )?
-                .ok_or_else(|| {
-                    SubmitTransactionError::bad_request_with_code(
-                        "Account not found",
-                        AptosErrorCode::InvalidInput,
-                        &ledger_info,
-                    )
-                })?;
-            let coin_store: CoinStoreResource = account_state
-                .get_coin_store_resource()
-                .and_then(|inner| {
-                    inner.ok_or_else(|| {
-                        anyhow!(
-                            "No coin store found for account {}",
-                            signed_transaction.sender()
-                        )
-                    })
-                })
-                .map_err(|err| {
-                    SubmitTransactionError::internal_with_code(
-                        format!("Failed to get coin store resource {}", err),
-                        AptosErrorCode::InternalError,
-                        &ledger_info,
-                    )
-                })?;
-
-            let gas_unit_price =
-                estimated_gas_unit_price.unwrap_or_else(|| signed_transaction.gas_unit_price());
-
-            // With 0 gas price, we set it to max gas units, since we can't divide by 0
-            let max_account_gas_units = if gas_unit_price == 0 {
-                coin_store.coin()
-            } else {
-                coin_store.coin() / gas_unit_price
-            };
-
-            // To give better error messaging, we should not go below the minimum number of gas units
-            let max_account_gas_units =
-                std::cmp::max(min_number_of_gas_units, max_account_gas_units);
-
-            // Minimum of the max account and the max total needs to be used for estimation
-            Some(std::cmp::min(
-                max_account_gas_units,
-                max_number_of_gas_units,
-            ))
-        } else {
-            None
-        };
+            &StateOverrides::default(),
+        )
+        .await
+    }
 
-        // If there is an estimation of either, replace the values
-        if estimated_max_gas_amount.is_some() || estimated_gas_unit_price.is_some() {
-            signed_transaction = override_gas_parameters(
-                &signed_transaction,
-                estimated_max_gas_amount,
-                estimated_gas_unit_price,
-            );
+    /// Simulate transaction with state overrides
+    ///
+    /// Same as the simulate transaction endpoint, with changes applied to the ledger state
+    /// the transaction is simulated against, such as the APT balance of the sender or the
+    /// value of resources, to know whether the transaction would succeed in that state.
+    /// The changes only apply to the simulation, nothing is written to the ledger.
+    ///
+    /// The transaction is given in JSON, as in the submit transaction endpoint.
+    #[oai(
+        path = "/transactions/simulate_with_overrides",
+        method = "post",
+        operation_id = "simulate_transaction_with_overrides",
+        tag = "ApiTags::Transactions"
+    )]
+    async fn simulate_transaction_with_overrides(
+        &self,
+        accept_type: AcceptType,
+        /// If set to true, the max gas value in the transaction will be ignored
+        /// and the maximum possible gas will be used
+        estimate_max_gas_amount: Query<Option<bool>>,
+        /// If set to true, the gas unit price in the transaction will be ignored
+        /// and the estimated value will be used
+        estimate_gas_unit_price: Query<Option<bool>>,
+        /// If set to true, the transaction will use a higher price than the original
+        /// estimate.
+        estimate_prioritized_gas_unit_price: Query<Option<bool>>,
+        data: Json<SimulateTransactionRequest>,
+    ) -> SimulateTransactionResult<Vec<UserTransaction>> {
+        data.0
+            .verify()
+            .context("Simulated transaction invalid")
+            .map_err(|err| {
+                SubmitTransactionError::bad_request_with_code_no_info(
+                    err,
+                    AptosErrorCode::InvalidInput,
+                )
+            })?;
+        fail_point_poem("endpoint_simulate_transaction_with_overrides")?;
+        if !self.context.node_config.api.transaction_simulation_enabled {
+            return Err(api_disabled("Simulate transaction"));
         }
-
-        self.simulate(&accept_type, ledger_info, signed_transaction)
-            .await
+        self.context
+            .check_api_output_enabled("Simulate transaction", &accept_type)?;
+        let ledger_info = self.context.get_latest_ledger_info()?;
+        let SimulateTransactionRequest {
+            transaction,
+            state_overrides,
+        } = data.0;
+        let signed_transaction = self
+            .get_signed_transaction(&ledger_info, SubmitTransactionPost::Json(Json(transaction)))?;
+
+        self.simulate_with_estimates(
+            &accept_type,
+            ledger_info,
+            signed_transaction,
+            estimate_max_gas_amount.0.unwrap_or_default(),
+            estimate_gas_unit_price.0.unwrap_or_default(),
+            estimate_prioritized_gas_unit_price.0.unwrap_or_default(),
+            &state_overrides,
+        )
+        .await
     }
 
     /// Encode submission
@@ -811,6 +805,7 @@ impl TransactionsApi {
         accept_type: &AcceptType,
         page: Page,
         address: Address,
+        filter: &AccountTransactionFilter,
     ) -> BasicResultWith404<Vec<Transaction>> {
         // Verify the account exists
         let account = Account::new(self.context.clone(), address, None, None, None)?;
@@ -818,13 +813,25 @@ impl TransactionsApi {
 
         let latest_ledger_info = account.latest_ledger_info;
         // TODO: Return more specific errors from within this function.
-        let data = self.context.get_account_transactions(
-            address.into(),
-            page.start_option(),
-            page.limit(&latest_ledger_info)?,
-            latest_ledger_info.version(),
-            &latest_ledger_info,
-        )?;
+        let (data, next_seq_number) = if filter.is_empty() {
+            let data = self.context.get_account_transactions(
+                address.into(),
+                page.start_option(),
+                page.limit(&latest_ledger_info)?,
+                latest_ledger_info.version(),
+                &latest_ledger_info,
+            )?;
+            (data, None)
+        } else {
+            self.context.get_filtered_account_transactions(
+                address.into(),
+                page.start_option(),
+                page.limit(&latest_ledger_info)?,
+                latest_ledger_info.version(),
+                filter,
+                &latest_ledger_info,
+            )?
+        };
         match accept_type {
             AcceptType::Json => BasicResponse::try_from_json((
                 self.context
@@ -836,6 +843,7 @@ impl TransactionsApi {
                 BasicResponse::try_from_bcs((data, &latest_ledger_info, BasicResponseStatus::Ok))
             },
         }
+        .map(|response| response.with_sequence_number_cursor(next_seq_number))
     }
 
     /// Parses a single signed transaction
@@ -1163,6 +1171,122 @@ impl TransactionsApi {
         ))
     }
 
+    /// Simulate a transaction, after replacing its gas parameters with the estimated ones
+    /// if requested
+    #[allow(clippy::too_many_arguments)]
+    async fn simulate_with_estimates(
+        &self,
+        accept_type: &AcceptType,
+        ledger_info: LedgerInfo,
+        mut signed_transaction: SignedTransaction,
+        estimate_max_gas_amount: bool,
+        estimate_gas_unit_price: bool,
+        estimate_prioritized_gas_unit_price: bool,
+        state_overrides: &StateOverrides,
+    ) -> SimulateTransactionResult<Vec<UserTransaction>> {
+        let estimated_gas_unit_price =
+            match (estimate_gas_unit_price, estimate_prioritized_gas_unit_price) {
+                (_, true) => {
+                    let gas_estimation = self.context.estimate_gas_price(&ledger_info)?;
+                    // The prioritized gas estimate should always be set, but if it's not use the gas estimate
+                    Some(
+                        gas_estimation
+                            .prioritized_gas_estimate
+                            .unwrap_or(gas_estimation.gas_estimate),
+                    )
+                },
+                (true, false) => Some(self.context.estimate_gas_price(&ledger_info)?.gas_estimate),
+                (false, false) => None,
+            };
+
+        // If estimate max gas amount is provided, we will just make it the maximum value
+        let estimated_max_gas_amount = if estimate_max_gas_amount {
+            // Retrieve max possible gas units
+            let (_, gas_params) = self.context.get_gas_schedule(&ledger_info)?;
+            let min_number_of_gas_units = u64::from(gas_params.vm.txn.min_transaction_gas_units)
+                / u64::from(gas_params.vm.txn.gas_unit_scaling_factor);
+            let max_number_of_gas_units = u64::from(gas_params.vm.txn.maximum_number_of_gas_units);
+
+            // Retrieve account balance to determine max gas available, unless overridden
+            let balance = match state_overrides.balance_of(signed_transaction.sender()) {
+                Some(balance) => balance,
+                None => {
+                    let account_state = self
+                        .context
+                        .get_account_state(
+                            signed_transaction.sender(),
+                            ledger_info.version(),
+                            &ledger_info,
+                        )?
+                        .ok_or_else(|| {
+                            SubmitTransactionError::bad_request_with_code(
+                                "Account not found",
+                                AptosErrorCode::InvalidInput,
+                                &ledger_info,
+                            )
+                        })?;
+                    let coin_store: CoinStoreResource = account_state
+                        .get_coin_store_resource()
+                        .and_then(|inner| {
+                            inner.ok_or_else(|| {
+                                anyhow!(
+                                    "No coin store found for account {}",
+                                    signed_transaction.sender()
+                                )
+                            })
+                        })
+                        .map_err(|err| {
+                            SubmitTransactionError::internal_with_code(
+                                format!("Failed to get coin store resource {}", err),
+                                AptosErrorCode::InternalError,
+                                &ledger_info,
+                            )
+                        })?;
+                    coin_store.coin()
+                },
+            };
+
+            let gas_unit_price =
+                estimated_gas_unit_price.unwrap_or_else(|| signed_transaction.gas_unit_price());
+
+            // With 0 gas price, we set it to max gas units, since we can't divide by 0
+            let max_account_gas_units = if gas_unit_price == 0 {
+                balance
+            } else {
+                balance / gas_unit_price
+            };
+
+            // To give better error messaging, we should not go below the minimum number of gas units
+            let max_account_gas_units =
+                std::cmp::max(min_number_of_gas_units, max_account_gas_units);
+
+            // Minimum of the max account and the max total needs to be used for estimation
+            Some(std::cmp::min(
+                max_account_gas_units,
+                max_number_of_gas_units,
+            ))
+        } else {
+            None
+        };
+
+        // If there is an estimation of either, replace the values
+        if estimated_max_gas_amount.is_some() || estimated_gas_unit_price.is_some() {
+            signed_transaction = override_gas_parameters(
+                &signed_transaction,
+                estimated_max_gas_amount,
+                estimated_gas_unit_price,
+            );
+        }
+
+        self.simulate(
+            accept_type,
+            ledger_info,
+            signed_transaction,
+            state_overrides,
+        )
+        .await
+    }
+
     // TODO: This function leverages a lot of types from aptos_types, use the
     // local API types and just return those directly, instead of converting
     // from these types in render_transactions.
@@ -1175,6 +1299,7 @@ impl TransactionsApi {
         accept_type: &AcceptType,
         ledger_info: LedgerInfo,
         txn: SignedTransaction,
+        state_overrides: &StateOverrides,
     ) -> SimulateTransactionResult<Vec<UserTransaction>> {
         // Transactions shouldn't have a valid signature or this could be used to attack
         if txn.signature_is_valid() {
@@ -1187,6 +1312,16 @@ impl TransactionsApi {
 
         // Simulate transaction
         let state_view = self.context.latest_state_view_poem(&ledger_info)?;
+        let state_view =
+            OverriddenStateView::new(&state_view, self.context.db.clone(), state_overrides)
+                .context("Invalid state overrides")
+                .map_err(|err| {
+                    SubmitTransactionError::bad_request_with_code(
+                        err,
+                        AptosErrorCode::InvalidInput,
+                        &ledger_info,
+                    )
+                })?;
         let move_resolver = state_view.as_move_resolver();
         let (_, output) = AptosVM::simulate_signed_transaction(&txn, &move_resolver);
         let version = ledger_info.version();
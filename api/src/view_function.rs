@@ -9,8 +9,11 @@ use crate::{
     },
     ApiTags, Context,
 };
-use aptos_api_types::{AptosErrorCode, AsConverter, MoveValue, ViewRequest, U64};
-use aptos_vm::{data_cache::AsMoveResolver, AptosVM};
+use aptos_api_types::{
+    AptosError, AptosErrorCode, AsConverter, MoveConverter, MoveValue, ViewRequest, ViewResult, U64,
+};
+use aptos_types::transaction::EntryFunction;
+use aptos_vm::{data_cache::AsMoveResolver, move_vm_ext::MoveResolverExt, AptosVM};
 use move_core_types::language_storage::TypeTag;
 use poem_openapi::{param::Query, payload::Json, OpenApi};
 use std::sync::Arc;
@@ -94,41 +97,157 @@ impl ViewFunctionApi {
                 BasicResponse::try_from_bcs((return_vals, &ledger_info, BasicResponseStatus::Ok))
             },
             AcceptType::Json => {
-                let return_types = resolver
-                    .as_converter(self.context.db.clone())
-                    .function_return_types(&entry_func)
-                    .and_then(|tys| {
-                        tys.into_iter()
-                            .map(TypeTag::try_from)
-                            .collect::<anyhow::Result<Vec<_>>>()
-                    })
-                    .map_err(|err| {
-                        BasicErrorWith404::bad_request_with_code(
-                            err,
-                            AptosErrorCode::InternalError,
-                            &ledger_info,
-                        )
-                    })?;
-
-                let move_vals = return_vals
-                    .into_iter()
-                    .zip(return_types.into_iter())
-                    .map(|(v, ty)| {
-                        resolver
-                            .as_converter(self.context.db.clone())
-                            .try_into_move_value(&ty, &v)
-                    })
-                    .collect::<anyhow::Result<Vec<_>>>()
-                    .map_err(|err| {
-                        BasicErrorWith404::bad_request_with_code(
-                            err,
-                            AptosErrorCode::InternalError,
-                            &ledger_info,
-                        )
-                    })?;
+                let move_vals = return_move_values(
+                    &resolver.as_converter(self.context.db.clone()),
+                    &entry_func,
+                    return_vals,
+                )
+                .map_err(|err| {
+                    BasicErrorWith404::bad_request_with_code(
+                        err,
+                        AptosErrorCode::InternalError,
+                        &ledger_info,
+                    )
+                })?;
 
                 BasicResponse::try_from_json((move_vals, &ledger_info, BasicResponseStatus::Ok))
             },
         }
     }
+
+    /// Execute a batch of view functions
+    ///
+    /// Execute each of the Move functions with the given parameters against the state at the
+    /// same ledger version, and return the result of each of them, in the order of the
+    /// requests. A failing function doesn't fail the others, its result holds the error
+    /// instead of the returned values.
+    ///
+    /// The number of requests in a batch is limited by the node's configuration.
+    #[oai(
+        path = "/view/batch",
+        method = "post",
+        operation_id = "view_batch",
+        tag = "ApiTags::View"
+    )]
+    async fn view_function_batch(
+        &self,
+        accept_type: AcceptType,
+        /// View function requests with type and position arguments
+        requests: Json<Vec<ViewRequest>>,
+        /// Ledger version to get state of account
+        ///
+        /// If not provided, it will be the latest version
+        ledger_version: Query<Option<U64>>,
+    ) -> BasicResultWith404<Vec<ViewResult>> {
+        fail_point_poem("endpoint_view_function_batch")?;
+        self.context
+            .check_api_output_enabled("View function batch", &accept_type)?;
+
+        let (ledger_info, requested_version) = self
+            .context
+            .get_latest_ledger_info_and_verify_lookup_version(
+                ledger_version.map(|inner| inner.0),
+            )?;
+        if self.context.max_view_batch_size() < requests.0.len() {
+            return Err(BasicErrorWith404::bad_request_with_code(
+                format!(
+                    "Sent too many view requests: {}, while limit is {}",
+                    requests.0.len(),
+                    self.context.max_view_batch_size(),
+                ),
+                AptosErrorCode::InvalidInput,
+                &ledger_info,
+            ));
+        }
+
+        let state_view = self.context.latest_state_view_poem(&ledger_info)?;
+        let resolver = state_view.as_move_resolver();
+        let converter = resolver.as_converter(self.context.db.clone());
+        let requested_state_view = self
+            .context
+            .state_view_at_version(requested_version)
+            .map_err(|err| {
+                BasicErrorWith404::bad_request_with_code(
+                    err,
+                    AptosErrorCode::InternalError,
+                    &ledger_info,
+                )
+            })?;
+
+        let results = requests
+            .0
+            .into_iter()
+            .map(|request| -> Result<_, AptosError> {
+                let entry_func = converter.convert_view_function(request).map_err(|err| {
+                    AptosError::new_with_error_code(err, AptosErrorCode::InvalidInput)
+                })?;
+                let return_vals = AptosVM::execute_view_function(
+                    &requested_state_view,
+                    entry_func.module().clone(),
+                    entry_func.function().to_owned(),
+                    entry_func.ty_args().to_owned(),
+                    entry_func.args().to_owned(),
+                    self.context.node_config.api.max_gas_view_function,
+                )
+                .map_err(|err| {
+                    AptosError::new_with_error_code(err, AptosErrorCode::InvalidInput)
+                })?;
+                Ok((entry_func, return_vals))
+            });
+        match accept_type {
+            AcceptType::Bcs => {
+                let results = results
+                    .map(|result| result.map(|(_, return_vals)| return_vals))
+                    .collect::<Vec<Result<_, AptosError>>>();
+                BasicResponse::try_from_bcs((results, &ledger_info, BasicResponseStatus::Ok))
+            },
+            AcceptType::Json => {
+                let results = results
+                    .map(|result| {
+                        let move_vals = result.and_then(|(entry_func, return_vals)| {
+                            return_move_values(&converter, &entry_func, return_vals).map_err(
+                                |err| {
+                                    AptosError::new_with_error_code(
+                                        err,
+                                        AptosErrorCode::InternalError,
+                                    )
+                                },
+                            )
+                        });
+                        match move_vals {
+                            Ok(move_vals) => ViewResult {
+                                result: Some(move_vals),
+                                error: None,
+                            },
+                            Err(err) => ViewResult {
+                                result: None,
+                                error: Some(err),
+                            },
+                        }
+                    })
+                    .collect();
+                BasicResponse::try_from_json((results, &ledger_info, BasicResponseStatus::Ok))
+            },
+        }
+    }
+}
+
+/// Converts the BCS encoded values returned by the view function to their JSON representation
+fn return_move_values<R: MoveResolverExt + ?Sized>(
+    converter: &MoveConverter<R>,
+    entry_func: &EntryFunction,
+    return_vals: Vec<Vec<u8>>,
+) -> anyhow::Result<Vec<MoveValue>> {
+    let return_types = converter
+        .function_return_types(entry_func)
+        .and_then(|tys| {
+            tys.into_iter()
+                .map(TypeTag::try_from)
+                .collect::<anyhow::Result<Vec<_>>>()
+        })?;
+    return_vals
+        .into_iter()
+        .zip(return_types.into_iter())
+        .map(|(v, ty)| converter.try_into_move_value(&ty, &v))
+        .collect()
 }
@@ -833,6 +833,32 @@ impl TestContext {
         payload: Value,
         status_code: u16,
     ) -> Value {
+        let request = self.simulation_request(sender, payload).await;
+        self.expect_status_code(status_code)
+            .post("/transactions/simulate", request)
+            .await
+    }
+
+    pub async fn simulate_transaction_with_overrides(
+        &mut self,
+        sender: &LocalAccount,
+        payload: Value,
+        state_overrides: Value,
+        status_code: u16,
+    ) -> Value {
+        let transaction = self.simulation_request(sender, payload).await;
+        self.expect_status_code(status_code)
+            .post(
+                "/transactions/simulate_with_overrides",
+                json!({
+                    "transaction": transaction,
+                    "state_overrides": state_overrides,
+                }),
+            )
+            .await
+    }
+
+    async fn simulation_request(&mut self, sender: &LocalAccount, payload: Value) -> Value {
         let mut request = json!({
             "sender": sender.address(),
             "sequence_number": sender.sequence_number().to_string(),
@@ -864,9 +890,7 @@ impl TestContext {
             "signature": HexEncodedBytes::from(sig.to_bytes().to_vec()),
         });
 
-        self.expect_status_code(status_code)
-            .post("/transactions/simulate", request)
-            .await
+        request
     }
 
     pub fn prepend_path(&self, path: &str) -> String {
@@ -18,6 +18,7 @@ mod ledger_info;
 pub mod mime_types;
 mod move_types;
 mod state;
+mod state_overrides;
 mod table;
 pub mod transaction;
 mod view;
@@ -42,6 +43,7 @@ pub use move_types::{
 };
 use serde::{Deserialize, Deserializer};
 pub use state::RawStateValueRequest;
+pub use state_overrides::{BalanceOverride, ResourceOverride, StateOverrides};
 use std::str::FromStr;
 pub use table::{RawTableItemRequest, TableItemRequest};
 pub use transaction::{
@@ -50,13 +52,14 @@ pub use transaction::{
     FeePayerSignature, GasEstimation, GasEstimationBcs, GenesisPayload, GenesisTransaction,
     ModuleBundlePayload, MultiAgentSignature, MultiEd25519Signature, MultisigPayload,
     MultisigTransactionPayload, PendingTransaction, ScriptPayload, ScriptWriteSet,
-    SubmitTransactionRequest, Transaction, TransactionData, TransactionId, TransactionInfo,
-    TransactionOnChainData, TransactionPayload, TransactionSignature, TransactionSigningMessage,
-    TransactionsBatchSingleSubmissionFailure, TransactionsBatchSubmissionResult,
-    UserCreateSigningMessageRequest, UserTransaction, UserTransactionRequest, VersionedEvent,
-    WriteModule, WriteResource, WriteSet, WriteSetChange, WriteSetPayload, WriteTableItem,
+    SimulateTransactionRequest, SubmitTransactionRequest, Transaction, TransactionData,
+    TransactionId, TransactionInfo, TransactionOnChainData, TransactionPayload,
+    TransactionSignature, TransactionSigningMessage, TransactionsBatchSingleSubmissionFailure,
+    TransactionsBatchSubmissionResult, UserCreateSigningMessageRequest, UserTransaction,
+    UserTransactionRequest, VersionedEvent, WriteModule, WriteResource, WriteSet, WriteSetChange,
+    WriteSetPayload, WriteTableItem,
 };
-pub use view::ViewRequest;
+pub use view::{ViewRequest, ViewResult};
 pub use wrappers::{EventGuid, IdentifierWrapper, StateKeyWrapper};
 
 pub fn deserialize_from_string<'de, D, T>(deserializer: D) -> Result<T, D::Error>
@@ -0,0 +1,69 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{Address, MoveStructTag, VerifyInput, VerifyInputWithRecursion, U64};
+use aptos_types::account_address::AccountAddress;
+use poem_openapi::Object;
+use serde::{Deserialize, Serialize};
+
+/// Changes to the ledger state a transaction is simulated against
+///
+/// They only apply to the simulation, nothing is written to the ledger.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, Object)]
+pub struct StateOverrides {
+    /// APT balances of accounts, replacing the coin of their existing
+    /// `0x1::coin::CoinStore<0x1::aptos_coin::AptosCoin>`
+    #[serde(default)]
+    #[oai(default)]
+    pub balances: Vec<BalanceOverride>,
+    /// Resources of accounts, replacing the existing ones or adding them
+    ///
+    /// Resources stored in resource groups can't be overridden.
+    #[serde(default)]
+    #[oai(default)]
+    pub resources: Vec<ResourceOverride>,
+}
+
+impl StateOverrides {
+    pub fn is_empty(&self) -> bool {
+        self.balances.is_empty() && self.resources.is_empty()
+    }
+
+    /// Overridden APT balance of the account, the last one if overridden more than once
+    pub fn balance_of(&self, address: AccountAddress) -> Option<u64> {
+        self.balances
+            .iter()
+            .rev()
+            .find(|balance| AccountAddress::from(balance.address) == address)
+            .map(|balance| balance.amount.0)
+    }
+}
+
+impl VerifyInput for StateOverrides {
+    fn verify(&self) -> anyhow::Result<()> {
+        for resource in &self.resources {
+            resource.resource_type.verify(0)?;
+        }
+        Ok(())
+    }
+}
+
+/// APT balance of an account for a simulation
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Object)]
+pub struct BalanceOverride {
+    pub address: Address,
+    /// Balance in octas
+    pub amount: U64,
+}
+
+/// Resource of an account for a simulation
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Object)]
+pub struct ResourceOverride {
+    pub address: Address,
+    /// Type of the resource, e.g. `0x1::account::Account`
+    #[serde(rename = "type")]
+    #[oai(rename = "type")]
+    pub resource_type: MoveStructTag,
+    /// JSON representation of the resource, as returned by the resource APIs
+    pub data: serde_json::Value,
+}
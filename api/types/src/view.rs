@@ -1,7 +1,7 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{EntryFunctionId, MoveType};
+use crate::{AptosError, EntryFunctionId, MoveType, MoveValue};
 use poem_openapi::Object;
 use serde::{Deserialize, Serialize};
 
@@ -14,3 +14,13 @@ pub struct ViewRequest {
     /// Arguments of the function
     pub arguments: Vec<serde_json::Value>,
 }
+
+/// Outcome of one of the requests of a batch of view requests, with either the values
+/// returned by the function or the error it failed with
+#[derive(Clone, Debug, Serialize, Object)]
+pub struct ViewResult {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<Vec<MoveValue>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<AptosError>,
+}
@@ -3,8 +3,9 @@
 
 use anyhow::{format_err, Result};
 use aptos_gas_meter::{StandardGasAlgebra, StandardGasMeter};
-use aptos_gas_profiling::{GasProfiler, TransactionGasLog};
+use aptos_gas_profiling::{ExecutionTrace, GasProfiler, TransactionGasLog};
 use aptos_gas_schedule::{MiscGasParameters, NativeGasParameters, LATEST_GAS_FEATURE_VERSION};
+use aptos_logger::warn;
 use aptos_memory_usage_tracker::MemoryTrackedGasMeter;
 use aptos_resource_viewer::{AnnotatedAccountStateBlob, AptosValueAnnotator};
 use aptos_rest_client::Client;
@@ -103,6 +104,42 @@ impl AptosDebugger {
         Ok((status, output, gas_profiler.finish()))
     }
 
+    /// Re-executes the user transactions in the range one at a time with the gas profiler, and
+    /// returns their execution traces along with the statuses. Transactions of other kinds, and
+    /// those with payloads not supported by the profiler, are skipped with a warning.
+    pub async fn trace_past_transactions(
+        &self,
+        begin: Version,
+        limit: u64,
+    ) -> Result<Vec<(Version, VMStatus, ExecutionTrace)>> {
+        let (txns, _txn_infos) = self
+            .debugger
+            .get_committed_transactions(begin, limit)
+            .await?;
+
+        let mut ret = vec![];
+        for (version, txn) in (begin..).zip(txns) {
+            let txn = match txn {
+                Transaction::UserTransaction(txn) => txn,
+                _ => continue,
+            };
+            if !matches!(
+                txn.payload(),
+                TransactionPayload::Script(_) | TransactionPayload::EntryFunction(_)
+            ) {
+                warn!(
+                    "Skipped tracing transaction {}, payload not supported.",
+                    version
+                );
+                continue;
+            }
+            let (status, _output, gas_log) =
+                self.execute_transaction_at_version_with_gas_profiler(version, txn)?;
+            ret.push((version, status, gas_log.to_execution_trace()));
+        }
+        Ok(ret)
+    }
+
     pub async fn execute_past_transactions(
         &self,
         mut begin: Version,
@@ -29,6 +29,11 @@ pub struct Argument {
 
     #[clap(long, default_value_t = 1)]
     concurrency_level: usize,
+
+    /// If set, also re-execute the user transactions one at a time and save a JSON execution
+    /// trace for each of them into this directory, as `<version>.trace.json`.
+    #[clap(long)]
+    trace_output_dir: Option<PathBuf>,
 }
 
 #[tokio::main]
@@ -51,6 +56,29 @@ async fn main() -> Result<()> {
             .await?
     );
 
+    if let Some(dir) = args.trace_output_dir {
+        std::fs::create_dir_all(&dir)?;
+        for (version, status, trace) in debugger
+            .trace_past_transactions(args.begin_version, args.limit)
+            .await?
+        {
+            let path = dir.join(format!("{}.trace.json", version));
+            std::fs::write(
+                &path,
+                serde_json::to_vec_pretty(&serde_json::json!({
+                    "version": version,
+                    "status": format!("{:?}", status),
+                    "trace": trace,
+                }))?,
+            )?;
+            println!(
+                "Execution trace of transaction {} saved to {}",
+                version,
+                path.display()
+            );
+        }
+    }
+
     Ok(())
 }
 
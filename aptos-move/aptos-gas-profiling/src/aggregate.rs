@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    log::{ExecutionAndIOCosts, ExecutionGasEvent},
+    log::{CallFrame, ExecutionAndIOCosts, ExecutionGasEvent},
     render::{Render, TableKey},
 };
 use aptos_gas_algebra::{GasQuantity, GasScalingFactor, InternalGas};
@@ -25,6 +25,8 @@ pub struct AggregatedExecutionGasEvents {
 
     // TODO: Make this more strongly typed?
     pub ops: Vec<(String, usize, InternalGas)>,
+    /// Calls to each function, along with the gas they used including the functions they called.
+    pub calls: Vec<(String, usize, InternalGas)>,
     pub storage_reads: Vec<(String, usize, InternalGas)>,
     pub storage_writes: Vec<(String, usize, InternalGas)>,
 }
@@ -65,12 +67,50 @@ where
     v
 }
 
+/// Adds the cost of the frame, including the frames it calls, to `calls` under
+/// the name of its function, then returns that cost.
+///
+/// `active` holds the functions of the enclosing frames. A recursive call is still counted,
+/// but its cost is already part of the outermost frame of the same function, so it is not
+/// added again, which keeps the cost of every function within the total.
+fn aggregate_calls(
+    frame: &CallFrame,
+    calls: &mut BTreeMap<String, (usize, InternalGas)>,
+    active: &mut Vec<String>,
+) -> InternalGas {
+    use ExecutionGasEvent::*;
+
+    let name = format!("{}", frame.name);
+    let recursive = active.contains(&name);
+    active.push(name);
+
+    let mut frame_cost = InternalGas::new(0);
+    for event in &frame.events {
+        match event {
+            Loc(..) => (),
+            Bytecode { cost, .. } | CallNative { cost, .. } | LoadResource { cost, .. } => {
+                frame_cost += *cost
+            },
+            Call(inner_frame) => frame_cost += aggregate_calls(inner_frame, calls, active),
+        }
+    }
+
+    let name = active.pop().expect("frame was pushed above");
+    let r = calls.entry(name).or_insert((0, InternalGas::new(0)));
+    r.0 += 1;
+    if !recursive {
+        r.1 += frame_cost;
+    }
+    frame_cost
+}
+
 impl ExecutionAndIOCosts {
     /// Counts the number of hits and aggregates the gas costs for each type of event.
     pub fn aggregate_gas_events(&self) -> AggregatedExecutionGasEvents {
         use ExecutionGasEvent::*;
 
         let mut ops = BTreeMap::new();
+        let mut calls = BTreeMap::new();
         let mut storage_reads = BTreeMap::new();
         let mut storage_writes = BTreeMap::new();
 
@@ -103,6 +143,8 @@ impl ExecutionAndIOCosts {
             }
         }
 
+        aggregate_calls(&self.call_graph, &mut calls, &mut vec![]);
+
         for write in &self.write_set_transient {
             use StateKeyInner::*;
 
@@ -122,8 +164,71 @@ impl ExecutionAndIOCosts {
             total: self.total,
 
             ops: into_sorted_vec(ops),
+            calls: into_sorted_vec(calls),
             storage_reads: into_sorted_vec(storage_reads),
             storage_writes: into_sorted_vec(storage_writes),
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use move_binary_format::file_format_common::Opcodes;
+    use move_core_types::{
+        account_address::AccountAddress, identifier::Identifier, language_storage::ModuleId,
+    };
+
+    fn frame(name: &str, events: Vec<ExecutionGasEvent>) -> CallFrame {
+        let mut frame = CallFrame::new_function(
+            ModuleId::new(AccountAddress::ONE, Identifier::new("m").unwrap()),
+            Identifier::new(name).unwrap(),
+            vec![],
+        );
+        frame.events = events;
+        frame
+    }
+
+    fn bytecode(cost: u64) -> ExecutionGasEvent {
+        ExecutionGasEvent::Bytecode {
+            op: Opcodes::ADD,
+            cost: InternalGas::new(cost),
+        }
+    }
+
+    #[test]
+    fn test_aggregate_recursive_calls() {
+        // f calls itself twice over, then calls g.
+        let call_graph = frame("f", vec![
+            bytecode(10),
+            ExecutionGasEvent::Call(frame("f", vec![
+                bytecode(5),
+                ExecutionGasEvent::Call(frame("f", vec![bytecode(3)])),
+            ])),
+            ExecutionGasEvent::Call(frame("g", vec![bytecode(2)])),
+        ]);
+        let costs = ExecutionAndIOCosts {
+            gas_scaling_factor: GasScalingFactor::new(1),
+            total: InternalGas::new(20),
+            intrinsic_cost: InternalGas::new(0),
+            call_graph,
+            write_set_transient: vec![],
+        };
+
+        let aggregated = costs.aggregate_gas_events();
+        // Each recursive call is counted, but the cost of f includes them only once.
+        assert_eq!(aggregated.calls, vec![
+            ("0x1::m::f".to_string(), 3, InternalGas::new(20)),
+            ("0x1::m::g".to_string(), 1, InternalGas::new(2)),
+        ]);
+        assert!(aggregated
+            .calls
+            .iter()
+            .all(|(_, _, cost)| *cost <= aggregated.total));
+        assert_eq!(aggregated.ops, vec![(
+            "add".to_string(),
+            4,
+            InternalGas::new(20)
+        )]);
+    }
+}
@@ -8,7 +8,10 @@ mod log;
 mod misc;
 mod profiler;
 mod render;
+mod report;
 mod textualize;
+mod trace;
 
 pub use log::{FrameName, TransactionGasLog};
 pub use profiler::GasProfiler;
+pub use trace::{EventTrace, ExecutionTrace, FrameTrace, NativeCallTrace, ReadTrace, WriteTrace};
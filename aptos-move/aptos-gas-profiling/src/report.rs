@@ -0,0 +1,171 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::log::TransactionGasLog;
+use aptos_gas_algebra::InternalGas;
+use std::fmt::Write;
+
+/// Escapes the characters that have a meaning in HTML, which show up in type
+/// arguments, e.g. `0x1::coin::transfer<0x1::aptos_coin::AptosCoin>`.
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+impl TransactionGasLog {
+    /// Generates a self-contained HTML report of the gas used by the transaction, with the gas
+    /// used per instruction, per function call and per storage access, as well as the execution
+    /// & IO and storage fee flamegraphs.
+    ///
+    /// The flamegraphs are the ones returned by `to_flamegraph`, passed in so that callers
+    /// saving them to files as well don't have to render them twice.
+    pub fn to_html_report(
+        &self,
+        title: String,
+        exec_io_graph: Option<&[u8]>,
+        storage_graph: Option<&[u8]>,
+    ) -> anyhow::Result<String> {
+        let aggregated = self.exec_io.aggregate_gas_events();
+        let total = u64::from(aggregated.total) as f64;
+        let scaling_factor = u64::from(aggregated.gas_scaling_factor) as f64;
+
+        let mut html = String::new();
+        writeln!(html, "<!DOCTYPE html>")?;
+        writeln!(html, "<html>\n<head>\n<meta charset=\"utf-8\">")?;
+        writeln!(html, "<title>{}</title>", escape(&title))?;
+        writeln!(
+            html,
+            "<style>body {{ font-family: sans-serif; }} \
+            table {{ border-collapse: collapse; margin-bottom: 2em; }} \
+            th, td {{ border: 1px solid #ccc; padding: 4px 8px; text-align: right; }} \
+            th:first-child, td:first-child {{ text-align: left; font-family: monospace; }}</style>"
+        )?;
+        writeln!(html, "</head>\n<body>")?;
+        writeln!(html, "<h1>{}</h1>", escape(&title))?;
+        writeln!(
+            html,
+            "<p>Execution &amp; IO: {} gas units. Storage fee: {} Octa.</p>",
+            crate::misc::strip_trailing_zeros_and_decimal_point(&format!(
+                "{:.8}",
+                total / scaling_factor
+            )),
+            u64::from(self.storage.total)
+        )?;
+
+        let mut write_table =
+            |heading: &str, items: &[(String, usize, InternalGas)]| -> anyhow::Result<()> {
+                writeln!(html, "<h2>{}</h2>", heading)?;
+                if items.is_empty() {
+                    writeln!(html, "<p>None</p>")?;
+                    return Ok(());
+                }
+                writeln!(
+                    html,
+                    "<table>\n<tr><th>Name</th><th>Count</th><th>Gas units</th><th>Share</th></tr>"
+                )?;
+                for (name, count, cost) in items {
+                    let cost = u64::from(*cost) as f64;
+                    writeln!(
+                        html,
+                        "<tr><td>{}</td><td>{}</td><td>{}</td><td>{:.2}%</td></tr>",
+                        escape(name),
+                        count,
+                        crate::misc::strip_trailing_zeros_and_decimal_point(&format!(
+                            "{:.8}",
+                            cost / scaling_factor
+                        )),
+                        cost / total * 100.0
+                    )?;
+                }
+                writeln!(html, "</table>")?;
+                Ok(())
+            };
+        write_table("Instructions &amp; native calls", &aggregated.ops)?;
+        write_table("Function calls (including callees)", &aggregated.calls)?;
+        write_table("Storage reads", &aggregated.storage_reads)?;
+        write_table("Storage writes", &aggregated.storage_writes)?;
+
+        // Inferno generates standalone SVGs, which browsers render fine inline.
+        let graphs = [
+            ("Execution &amp; IO flamegraph", exec_io_graph),
+            ("Storage fee flamegraph", storage_graph),
+        ];
+        for (heading, graph) in graphs {
+            writeln!(html, "<h2>{}</h2>", heading)?;
+            match graph {
+                Some(graph_bytes) => {
+                    let svg = String::from_utf8_lossy(graph_bytes);
+                    // Drop the XML prolog, it isn't valid in the middle of an HTML document.
+                    let svg = match svg.find("<svg") {
+                        Some(start) => &svg[start..],
+                        None => &svg,
+                    };
+                    writeln!(html, "<div>{}</div>", svg)?;
+                },
+                None => writeln!(html, "<p>None</p>")?,
+            }
+        }
+
+        writeln!(html, "</body>\n</html>")?;
+        Ok(html)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::log::{CallFrame, ExecutionAndIOCosts, ExecutionGasEvent, StorageFees};
+    use aptos_gas_algebra::{Fee, GasScalingFactor};
+    use move_binary_format::file_format_common::Opcodes;
+    use move_core_types::{
+        account_address::AccountAddress, identifier::Identifier, language_storage::ModuleId,
+    };
+
+    fn gas_log() -> TransactionGasLog {
+        let mut call_graph = CallFrame::new_function(
+            ModuleId::new(AccountAddress::ONE, Identifier::new("m").unwrap()),
+            Identifier::new("f").unwrap(),
+            vec![],
+        );
+        call_graph.events = vec![ExecutionGasEvent::Bytecode {
+            op: Opcodes::ADD,
+            cost: InternalGas::new(200),
+        }];
+        TransactionGasLog {
+            exec_io: ExecutionAndIOCosts {
+                gas_scaling_factor: GasScalingFactor::new(100),
+                total: InternalGas::new(200),
+                intrinsic_cost: InternalGas::new(0),
+                call_graph,
+                write_set_transient: vec![],
+            },
+            storage: StorageFees {
+                total: Fee::new(0),
+                write_set_storage: vec![],
+                events: vec![],
+                event_discount: Fee::new(0),
+                txn_storage: Fee::new(0),
+            },
+        }
+    }
+
+    #[test]
+    fn test_html_report() {
+        let exec_io_graph: &[u8] = b"<?xml version=\"1.0\"?>\n<svg>graph</svg>";
+        let html = gas_log()
+            .to_html_report("Test <1>".to_string(), Some(exec_io_graph), None)
+            .unwrap();
+
+        assert!(html.contains("<title>Test &lt;1&gt;</title>"));
+        assert!(html.contains("<p>Execution &amp; IO: 2 gas units. Storage fee: 0 Octa.</p>"));
+        assert!(html.contains("<tr><td>add</td><td>1</td><td>2</td><td>100.00%</td></tr>"));
+        assert!(html.contains("<tr><td>0x1::m::f</td><td>1</td><td>2</td><td>100.00%</td></tr>"));
+        assert!(html.contains("<h2>Storage reads</h2>\n<p>None</p>"));
+        // The graphs passed in are embedded as they are, without the XML prolog.
+        assert!(html.contains("<div><svg>graph</svg></div>"));
+        assert!(!html.contains("<?xml"));
+        assert!(html.contains("<h2>Storage fee flamegraph</h2>\n<p>None</p>"));
+    }
+}
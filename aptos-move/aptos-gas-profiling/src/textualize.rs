@@ -171,6 +171,16 @@ impl AggregatedExecutionGasEvents {
             table.push(fmt_item(name, *count, *cost));
         }
 
+        table.push([
+            "    function calls (including callees)".to_string(),
+            "".to_string(),
+            "".to_string(),
+            "".to_string(),
+        ]);
+        for (name, count, cost) in &self.calls {
+            table.push(fmt_item(name, *count, *cost));
+        }
+
         table.push([
             "    storage reads".to_string(),
             "".to_string(),
@@ -0,0 +1,343 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::log::{CallFrame, ExecutionGasEvent, FrameName, TransactionGasLog, WriteOpType};
+use aptos_types::{
+    access_path::Path,
+    state_store::state_key::{StateKey, StateKeyInner},
+};
+use move_core_types::{
+    identifier::IdentStr,
+    language_storage::{ModuleId, TypeTag},
+};
+use serde::Serialize;
+use std::{collections::HashMap, ops::Deref};
+
+/// A structured trace of a transaction's execution, reconstructed from its gas log, meant to help
+/// explain why a transaction behaved the way it did: the functions it called, the resources they
+/// read, and what it ended up writing.
+///
+/// Unlike the flamegraphs and the HTML report, names are never truncated, so the trace can be
+/// consumed by other tools. Execution & IO gas is in internal gas units, storage fees in Octas.
+#[derive(Debug, Serialize)]
+pub struct ExecutionTrace {
+    /// Divide the internal gas amounts by this to get the amounts in external gas units.
+    pub gas_scaling_factor: u64,
+    pub intrinsic_gas: u64,
+    /// Total execution & IO gas, including the intrinsic gas.
+    pub execution_and_io_gas: u64,
+    pub storage_fee: u64,
+    pub txn_storage_fee: u64,
+    pub event_storage_discount: u64,
+    pub call_graph: FrameTrace,
+    pub writes: Vec<WriteTrace>,
+    pub events: Vec<EventTrace>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FrameTrace {
+    pub function: String,
+    /// Gas used by the frame, including the functions it called.
+    pub gas: u64,
+    /// Gas used by the frame itself, i.e. its instructions, native calls and resource loads.
+    pub self_gas: u64,
+    pub reads: Vec<ReadTrace>,
+    pub native_calls: Vec<NativeCallTrace>,
+    pub calls: Vec<FrameTrace>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ReadTrace {
+    pub address: String,
+    pub resource_type: String,
+    pub gas: u64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct NativeCallTrace {
+    pub function: String,
+    pub gas: u64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct WriteTrace {
+    pub key: String,
+    pub op: &'static str,
+    pub io_gas: u64,
+    pub storage_fee: u64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct EventTrace {
+    pub event_type: String,
+    pub storage_fee: u64,
+}
+
+fn function_name(module_id: &ModuleId, name: &IdentStr, ty_args: &[TypeTag]) -> String {
+    let mut s = format!(
+        "{}::{}::{}",
+        module_id.address().to_hex_literal(),
+        module_id.name(),
+        name
+    );
+    if !ty_args.is_empty() {
+        s.push_str(&format!(
+            "<{}>",
+            ty_args
+                .iter()
+                .map(|ty| ty.to_string())
+                .collect::<Vec<_>>()
+                .join(",")
+        ));
+    }
+    s
+}
+
+fn state_key_name(key: &StateKey) -> String {
+    match key.deref() {
+        StateKeyInner::AccessPath(ap) => match ap.get_path() {
+            Path::Code(module_id) => format!(
+                "{}::code::{}",
+                ap.address.to_hex_literal(),
+                module_id.name()
+            ),
+            Path::Resource(struct_tag) | Path::ResourceGroup(struct_tag) => {
+                format!("{}::{}", ap.address.to_hex_literal(), struct_tag)
+            },
+        },
+        StateKeyInner::TableItem { handle, key } => format!(
+            "table_item<{},0x{}>",
+            handle.0.to_hex_literal(),
+            key.iter().map(|b| format!("{:02x}", b)).collect::<String>()
+        ),
+        StateKeyInner::Raw(bytes) => format!(
+            "raw<0x{}>",
+            bytes
+                .iter()
+                .map(|b| format!("{:02x}", b))
+                .collect::<String>()
+        ),
+    }
+}
+
+fn op_name(op_type: &WriteOpType) -> &'static str {
+    match op_type {
+        WriteOpType::Creation => "create",
+        WriteOpType::Modification => "modify",
+        WriteOpType::Deletion => "delete",
+    }
+}
+
+impl CallFrame {
+    fn to_trace(&self) -> FrameTrace {
+        use ExecutionGasEvent::*;
+
+        let mut trace = FrameTrace {
+            function: match &self.name {
+                FrameName::Script => "<script>".to_string(),
+                FrameName::Function {
+                    module_id,
+                    name,
+                    ty_args,
+                } => function_name(module_id, name, ty_args),
+            },
+            gas: 0,
+            self_gas: 0,
+            reads: vec![],
+            native_calls: vec![],
+            calls: vec![],
+        };
+
+        for event in &self.events {
+            match event {
+                Loc(..) => (),
+                Bytecode { cost, .. } => trace.self_gas += u64::from(*cost),
+                Call(frame) => {
+                    let child = frame.to_trace();
+                    trace.gas += child.gas;
+                    trace.calls.push(child);
+                },
+                CallNative {
+                    module_id,
+                    fn_name,
+                    ty_args,
+                    cost,
+                } => {
+                    trace.self_gas += u64::from(*cost);
+                    trace.native_calls.push(NativeCallTrace {
+                        function: function_name(module_id, fn_name, ty_args),
+                        gas: (*cost).into(),
+                    });
+                },
+                LoadResource { addr, ty, cost } => {
+                    trace.self_gas += u64::from(*cost);
+                    trace.reads.push(ReadTrace {
+                        address: addr.to_hex_literal(),
+                        resource_type: ty.to_string(),
+                        gas: (*cost).into(),
+                    });
+                },
+            }
+        }
+        trace.gas += trace.self_gas;
+
+        trace
+    }
+}
+
+impl TransactionGasLog {
+    /// Builds a structured execution trace out of the gas log, which can be serialized into JSON
+    /// or any other format supported by serde.
+    pub fn to_execution_trace(&self) -> ExecutionTrace {
+        let mut storage_fees: HashMap<&StateKey, u64> = HashMap::new();
+        for write in &self.storage.write_set_storage {
+            *storage_fees.entry(&write.key).or_default() += u64::from(write.cost);
+        }
+
+        let writes = self
+            .exec_io
+            .write_set_transient
+            .iter()
+            .map(|write| WriteTrace {
+                key: state_key_name(&write.key),
+                op: op_name(&write.op_type),
+                io_gas: write.cost.into(),
+                storage_fee: storage_fees.get(&write.key).copied().unwrap_or(0),
+            })
+            .collect();
+
+        let events = self
+            .storage
+            .events
+            .iter()
+            .map(|event| EventTrace {
+                event_type: event.ty.to_string(),
+                storage_fee: event.cost.into(),
+            })
+            .collect();
+
+        ExecutionTrace {
+            gas_scaling_factor: self.exec_io.gas_scaling_factor.into(),
+            intrinsic_gas: self.exec_io.intrinsic_cost.into(),
+            execution_and_io_gas: self.exec_io.total.into(),
+            storage_fee: self.storage.total.into(),
+            txn_storage_fee: self.storage.txn_storage.into(),
+            event_storage_discount: self.storage.event_discount.into(),
+            call_graph: self.exec_io.call_graph.to_trace(),
+            writes,
+            events,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::log::{
+        EventStorage, ExecutionAndIOCosts, StorageFees, WriteStorage, WriteTransient,
+    };
+    use aptos_gas_algebra::{Fee, GasScalingFactor, InternalGas};
+    use move_binary_format::file_format_common::Opcodes;
+    use move_core_types::{account_address::AccountAddress, identifier::Identifier};
+    use std::str::FromStr;
+
+    fn gas_log() -> TransactionGasLog {
+        let module_id =
+            |name: &str| ModuleId::new(AccountAddress::ONE, Identifier::new(name).unwrap());
+
+        let mut callee =
+            CallFrame::new_function(module_id("n"), Identifier::new("g").unwrap(), vec![
+                TypeTag::U64,
+            ]);
+        callee.events = vec![ExecutionGasEvent::Bytecode {
+            op: Opcodes::ADD,
+            cost: InternalGas::new(5),
+        }];
+        let mut call_graph = CallFrame::new_script();
+        call_graph.events = vec![
+            ExecutionGasEvent::Loc(0),
+            ExecutionGasEvent::Bytecode {
+                op: Opcodes::CALL,
+                cost: InternalGas::new(10),
+            },
+            ExecutionGasEvent::LoadResource {
+                addr: AccountAddress::TWO,
+                ty: TypeTag::from_str("0x1::m::R").unwrap(),
+                cost: InternalGas::new(20),
+            },
+            ExecutionGasEvent::CallNative {
+                module_id: module_id("m"),
+                fn_name: Identifier::new("h").unwrap(),
+                ty_args: vec![],
+                cost: InternalGas::new(3),
+            },
+            ExecutionGasEvent::Call(callee),
+        ];
+
+        let key = StateKey::raw(vec![0xAB, 0x01]);
+        TransactionGasLog {
+            exec_io: ExecutionAndIOCosts {
+                gas_scaling_factor: GasScalingFactor::new(100),
+                total: InternalGas::new(88),
+                intrinsic_cost: InternalGas::new(40),
+                call_graph,
+                write_set_transient: vec![WriteTransient {
+                    key: key.clone(),
+                    op_type: WriteOpType::Creation,
+                    cost: InternalGas::new(10),
+                }],
+            },
+            storage: StorageFees {
+                total: Fee::new(700),
+                write_set_storage: vec![WriteStorage {
+                    key,
+                    op_type: WriteOpType::Creation,
+                    cost: Fee::new(500),
+                }],
+                events: vec![EventStorage {
+                    ty: TypeTag::from_str("0x1::m::E").unwrap(),
+                    cost: Fee::new(50),
+                }],
+                event_discount: Fee::new(0),
+                txn_storage: Fee::new(150),
+            },
+        }
+    }
+
+    #[test]
+    fn test_execution_trace() {
+        let trace = gas_log().to_execution_trace();
+
+        assert_eq!(trace.gas_scaling_factor, 100);
+        assert_eq!(trace.intrinsic_gas, 40);
+        assert_eq!(trace.execution_and_io_gas, 88);
+        assert_eq!(trace.storage_fee, 700);
+        assert_eq!(trace.txn_storage_fee, 150);
+
+        let root = &trace.call_graph;
+        assert_eq!(root.function, "<script>");
+        // The callee's gas counts towards the frame's gas, but not its self gas.
+        assert_eq!(root.self_gas, 33);
+        assert_eq!(root.gas, 38);
+        assert_eq!(root.reads.len(), 1);
+        assert_eq!(root.reads[0].address, "0x2");
+        assert_eq!(root.reads[0].resource_type, "0x1::m::R");
+        assert_eq!(root.reads[0].gas, 20);
+        assert_eq!(root.native_calls.len(), 1);
+        assert_eq!(root.native_calls[0].function, "0x1::m::h");
+        assert_eq!(root.calls.len(), 1);
+        assert_eq!(root.calls[0].function, "0x1::n::g<u64>");
+        assert_eq!(root.calls[0].gas, 5);
+
+        // Writes are matched with their storage fees.
+        assert_eq!(trace.writes.len(), 1);
+        assert_eq!(trace.writes[0].key, "raw<0xab01>");
+        assert_eq!(trace.writes[0].op, "create");
+        assert_eq!(trace.writes[0].io_gas, 10);
+        assert_eq!(trace.writes[0].storage_fee, 500);
+
+        assert_eq!(trace.events.len(), 1);
+        assert_eq!(trace.events[0].event_type, "0x1::m::E");
+        assert_eq!(trace.events[0].storage_fee, 50);
+    }
+}
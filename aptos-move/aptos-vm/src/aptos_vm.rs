@@ -83,6 +83,7 @@ use std::{
 };
 
 static EXECUTION_CONCURRENCY_LEVEL: OnceCell<usize> = OnceCell::new();
+static ADAPTIVE_CONCURRENCY: OnceCell<bool> = OnceCell::new();
 static NUM_EXECUTION_SHARD: OnceCell<usize> = OnceCell::new();
 static NUM_PROOF_READING_THREADS: OnceCell<usize> = OnceCell::new();
 static PARANOID_TYPE_CHECKS: OnceCell<bool> = OnceCell::new();
@@ -149,6 +150,22 @@ impl AptosVM {
         }
     }
 
+    /// Sets whether the concurrency level adapts to the conflict rate of recent blocks, when
+    /// invoked the first time.
+    pub fn set_adaptive_concurrency_once(enable: bool) {
+        // Only the first call succeeds, due to OnceCell semantics.
+        ADAPTIVE_CONCURRENCY.set(enable).ok();
+    }
+
+    /// Get the adaptive concurrency flag if already set, otherwise return default false, i.e.
+    /// the concurrency level is used as is.
+    pub fn get_adaptive_concurrency() -> bool {
+        match ADAPTIVE_CONCURRENCY.get() {
+            Some(enable) => *enable,
+            None => false,
+        }
+    }
+
     pub fn set_num_shards_once(mut num_shards: usize) {
         num_shards = max(num_shards, 1);
         // Only the first call succeeds, due to OnceCell semantics.
@@ -15,6 +15,7 @@ use crate::{
 };
 use aptos_aggregator::delta_change_set::DeltaOp;
 use aptos_block_executor::{
+    concurrency_controller::ConcurrencyController,
     errors::Error,
     executor::BlockExecutor,
     task::{
@@ -36,10 +37,14 @@ use aptos_types::{
 use aptos_vm_logging::{flush_speculative_logs, init_speculative_logs};
 use aptos_vm_types::output::VMOutput;
 use move_core_types::vm_status::VMStatus;
-use once_cell::sync::OnceCell;
+use once_cell::sync::{Lazy, OnceCell};
 use rayon::{prelude::*, ThreadPool};
 use std::sync::Arc;
 
+/// Shared by all the blocks executed, so that it sees the conflict rate of the recent ones.
+static CONCURRENCY_CONTROLLER: Lazy<Arc<ConcurrencyController>> =
+    Lazy::new(|| Arc::new(ConcurrencyController::new()));
+
 impl BlockExecutorTransaction for PreprocessedTransaction {
     type Event = ContractEvent;
     type Key = StateKey;
@@ -206,6 +211,7 @@ impl BlockAptosVM {
             executor_thread_pool,
             maybe_block_gas_limit,
             transaction_commit_listener,
+            AptosVM::get_adaptive_concurrency().then(|| CONCURRENCY_CONTROLLER.clone()),
         );
 
         let ret = executor.execute_block(state_view, signature_verified_block, state_view);
@@ -0,0 +1,148 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::counters::{ADAPTIVE_CONCURRENCY_LEVEL, OBSERVED_CONFLICT_RATE};
+use aptos_infallible::Mutex;
+
+/// Picks the number of Block-STM workers for each block based on the conflict rate, i.e. the
+/// number of speculative aborts per transaction, observed in recent blocks. Highly conflicting
+/// workloads don't benefit from more workers, which then only waste cores re-executing
+/// transactions, or even from parallel execution at all.
+///
+/// Meant to be shared across the blocks executed, see `BlockExecutor::new()`.
+pub struct ConcurrencyController {
+    state: Mutex<ControllerState>,
+}
+
+struct ControllerState {
+    /// Exponential moving average of the conflict rate of the blocks executed in parallel.
+    conflict_rate: f64,
+    /// Number of blocks executed sequentially since the last parallel execution.
+    num_sequential_blocks: usize,
+}
+
+impl ConcurrencyController {
+    /// The full concurrency level is used below this conflict rate.
+    const LOW_CONFLICT_RATE: f64 = 0.1;
+    /// The conflict rates of smaller blocks are too noisy to be taken into account.
+    const MIN_BLOCK_SIZE_TO_OBSERVE: usize = 10;
+    /// Sequential execution tells nothing about the conflicts, hence parallel execution is
+    /// retried with the minimum number of workers every this many blocks.
+    const PARALLEL_RETRY_INTERVAL: usize = 10;
+    /// Execution falls back to sequential at this conflict rate or higher, the concurrency level
+    /// scales down linearly in between.
+    const SEQUENTIAL_CONFLICT_RATE: f64 = 1.0;
+    /// Weight of the latest block in the moving average.
+    const SMOOTHING_FACTOR: f64 = 0.3;
+
+    pub fn new() -> Self {
+        Self {
+            state: Mutex::new(ControllerState {
+                conflict_rate: 0.0,
+                num_sequential_blocks: 0,
+            }),
+        }
+    }
+
+    /// Returns the concurrency level to execute the next block with, which is 1 for sequential
+    /// execution and never exceeds `max_concurrency_level`.
+    pub fn concurrency_level(&self, max_concurrency_level: usize) -> usize {
+        let concurrency_level = self.concurrency_level_impl(max_concurrency_level);
+        ADAPTIVE_CONCURRENCY_LEVEL.set(concurrency_level as i64);
+        concurrency_level
+    }
+
+    fn concurrency_level_impl(&self, max_concurrency_level: usize) -> usize {
+        if max_concurrency_level <= 1 {
+            return max_concurrency_level;
+        }
+
+        let state = self.state.lock();
+        if state.conflict_rate >= Self::SEQUENTIAL_CONFLICT_RATE {
+            return if state.num_sequential_blocks < Self::PARALLEL_RETRY_INTERVAL {
+                1
+            } else {
+                2
+            };
+        }
+        if state.conflict_rate <= Self::LOW_CONFLICT_RATE {
+            return max_concurrency_level;
+        }
+
+        let ratio = (Self::SEQUENTIAL_CONFLICT_RATE - state.conflict_rate)
+            / (Self::SEQUENTIAL_CONFLICT_RATE - Self::LOW_CONFLICT_RATE);
+        2 + ((max_concurrency_level - 2) as f64 * ratio).round() as usize
+    }
+
+    pub fn observe_parallel_execution(&self, num_txns: usize, num_aborts: usize) {
+        if num_txns < Self::MIN_BLOCK_SIZE_TO_OBSERVE {
+            return;
+        }
+
+        let conflict_rate = num_aborts as f64 / num_txns as f64;
+        let mut state = self.state.lock();
+        state.conflict_rate = if state.num_sequential_blocks > 0 {
+            // What's observed before falling back to sequential execution is stale.
+            conflict_rate
+        } else {
+            Self::SMOOTHING_FACTOR * conflict_rate
+                + (1.0 - Self::SMOOTHING_FACTOR) * state.conflict_rate
+        };
+        state.num_sequential_blocks = 0;
+        OBSERVED_CONFLICT_RATE.set(state.conflict_rate);
+    }
+
+    pub fn observe_sequential_execution(&self) {
+        self.state.lock().num_sequential_blocks += 1;
+    }
+}
+
+impl Default for ConcurrencyController {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::concurrency_controller::ConcurrencyController;
+
+    #[test]
+    fn test_scale_with_conflict_rate() {
+        let controller = ConcurrencyController::new();
+        assert_eq!(controller.concurrency_level(16), 16);
+        assert_eq!(controller.concurrency_level(1), 1);
+
+        // Too small to be observed.
+        controller.observe_parallel_execution(5, 50);
+        assert_eq!(controller.concurrency_level(16), 16);
+
+        for _ in 0..5 {
+            controller.observe_parallel_execution(1000, 500);
+        }
+        let concurrency_level = controller.concurrency_level(16);
+        assert!(concurrency_level > 2 && concurrency_level < 16);
+
+        for _ in 0..20 {
+            controller.observe_parallel_execution(1000, 0);
+        }
+        assert_eq!(controller.concurrency_level(16), 16);
+    }
+
+    #[test]
+    fn test_sequential_fallback_and_retry() {
+        let controller = ConcurrencyController::new();
+        for _ in 0..10 {
+            controller.observe_parallel_execution(1000, 3000);
+        }
+        for _ in 0..ConcurrencyController::PARALLEL_RETRY_INTERVAL {
+            assert_eq!(controller.concurrency_level(16), 1);
+            controller.observe_sequential_execution();
+        }
+        assert_eq!(controller.concurrency_level(16), 2);
+
+        // The retry finds the conflicts gone.
+        controller.observe_parallel_execution(1000, 0);
+        assert_eq!(controller.concurrency_level(16), 16);
+    }
+}
@@ -2,8 +2,9 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use aptos_metrics_core::{
-    exponential_buckets, register_histogram, register_histogram_vec, register_int_counter,
-    register_int_counter_vec, Histogram, HistogramVec, IntCounter, IntCounterVec,
+    exponential_buckets, register_gauge, register_histogram, register_histogram_vec,
+    register_int_counter, register_int_counter_vec, register_int_gauge, Gauge, Histogram,
+    HistogramVec, IntCounter, IntCounterVec, IntGauge,
 };
 use aptos_types::fee_statement::FeeStatement;
 use once_cell::sync::Lazy;
@@ -72,6 +73,24 @@ pub static SPECULATIVE_ABORT_COUNT: Lazy<IntCounter> = Lazy::new(|| {
     .unwrap()
 });
 
+/// Moving average of the speculative aborts per transaction, as seen by the concurrency controller.
+pub static OBSERVED_CONFLICT_RATE: Lazy<Gauge> = Lazy::new(|| {
+    register_gauge!(
+        "aptos_execution_observed_conflict_rate",
+        "Moving average of speculative aborts per transaction in recent parallel executions"
+    )
+    .unwrap()
+});
+
+/// Concurrency level picked by the concurrency controller for the latest block.
+pub static ADAPTIVE_CONCURRENCY_LEVEL: Lazy<IntGauge> = Lazy::new(|| {
+    register_int_gauge!(
+        "aptos_execution_adaptive_concurrency_level",
+        "Concurrency level picked by the concurrency controller for the latest block"
+    )
+    .unwrap()
+});
+
 /// Count of times the BlockSTM is early halted due to exceeding the per-block gas limit.
 pub static EXCEED_PER_BLOCK_GAS_LIMIT_COUNT: Lazy<IntCounterVec> = Lazy::new(|| {
     register_int_counter_vec!(
@@ -3,6 +3,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    concurrency_controller::ConcurrencyController,
     counters,
     counters::{
         PARALLEL_EXECUTION_SECONDS, RAYON_EXECUTION_SECONDS, TASK_EXECUTE_SECONDS,
@@ -75,6 +76,7 @@ pub struct BlockExecutor<T, E, S, L, X> {
     executor_thread_pool: Arc<ThreadPool>,
     maybe_block_gas_limit: Option<u64>,
     transaction_commit_hook: Option<L>,
+    concurrency_controller: Option<Arc<ConcurrencyController>>,
     phantom: PhantomData<(T, E, S, L, X)>,
 }
 
@@ -88,22 +90,32 @@ where
 {
     /// The caller needs to ensure that concurrency_level > 1 (0 is illegal and 1 should
     /// be handled by sequential execution) and that concurrency_level <= num_cpus.
+    ///
+    /// If a concurrency controller is provided, concurrency_level is the maximum, and the actual
+    /// concurrency level is picked by the controller based on the blocks it observed so far.
     pub fn new(
         concurrency_level: usize,
         executor_thread_pool: Arc<ThreadPool>,
         maybe_block_gas_limit: Option<u64>,
         transaction_commit_hook: Option<L>,
+        concurrency_controller: Option<Arc<ConcurrencyController>>,
     ) -> Self {
         assert!(
             concurrency_level > 0 && concurrency_level <= num_cpus::get(),
             "Parallel execution concurrency level {} should be between 1 and number of CPUs",
             concurrency_level
         );
+        let concurrency_level = concurrency_controller
+            .as_ref()
+            .map_or(concurrency_level, |controller| {
+                controller.concurrency_level(concurrency_level)
+            });
         Self {
             concurrency_level,
             executor_thread_pool,
             maybe_block_gas_limit,
             transaction_commit_hook,
+            concurrency_controller,
             phantom: PhantomData,
         }
     }
@@ -551,6 +563,11 @@ where
         });
         drop(timer);
 
+        if let Some(controller) = &self.concurrency_controller {
+            controller
+                .observe_parallel_execution(num_txns as usize, scheduler.num_aborts() as usize);
+        }
+
         let num_txns = num_txns as usize;
         // TODO: for large block sizes and many cores, extract outputs in parallel.
         let mut final_results = Vec::with_capacity(num_txns);
@@ -707,6 +724,9 @@ where
                 base_view,
             )
         } else {
+            if let Some(controller) = &self.concurrency_controller {
+                controller.observe_sequential_execution();
+            }
             self.execute_transactions_sequential(
                 executor_arguments,
                 &signature_verified_block,
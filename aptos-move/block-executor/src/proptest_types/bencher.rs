@@ -134,7 +134,7 @@ where
             EmptyDataView<KeyType<K>, ValueType<V>>,
             NoOpTransactionCommitHook<MockOutput<KeyType<K>, ValueType<V>, E>, usize>,
             ExecutableTestType,
-        >::new(num_cpus::get(), executor_thread_pool, None, None)
+        >::new(num_cpus::get(), executor_thread_pool, None, None, None)
         .execute_transactions_parallel((), &self.transactions, &data_view);
 
         self.baseline_output.assert_output(&output);
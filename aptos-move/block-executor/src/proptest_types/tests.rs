@@ -77,6 +77,7 @@ fn run_transactions<K, V, E>(
             executor_thread_pool.clone(),
             maybe_block_gas_limit,
             None,
+            None,
         )
         .execute_transactions_parallel((), &transactions, &data_view);
 
@@ -215,6 +216,7 @@ fn deltas_writes_mixed_with_block_gas_limit(num_txns: usize, maybe_block_gas_lim
             executor_thread_pool.clone(),
             maybe_block_gas_limit,
             None,
+            None,
         )
         .execute_transactions_parallel((), &transactions, &data_view);
 
@@ -269,6 +271,7 @@ fn deltas_resolver_with_block_gas_limit(num_txns: usize, maybe_block_gas_limit:
             executor_thread_pool.clone(),
             maybe_block_gas_limit,
             None,
+            None,
         )
         .execute_transactions_parallel((), &transactions, &data_view);
 
@@ -428,6 +431,7 @@ fn publishing_fixed_params_with_block_gas_limit(
         executor_thread_pool,
         maybe_block_gas_limit,
         None,
+        None,
     )
     .execute_transactions_parallel((), &transactions, &data_view);
     assert_ok!(output);
@@ -474,6 +478,7 @@ fn publishing_fixed_params_with_block_gas_limit(
             executor_thread_pool.clone(),
             Some(max(w_index, r_index) as u64 * MAX_GAS_PER_TXN + 1),
             None,
+            None,
         ) // Ensure enough gas limit to commit the module txns (4 is maximum gas per txn)
         .execute_transactions_parallel((), &transactions, &data_view);
 
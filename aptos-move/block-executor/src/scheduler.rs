@@ -238,6 +238,9 @@ pub struct Scheduler {
 
     /// Shared marker that is set when a thread detects that all txns can be committed.
     done_marker: CachePadded<AtomicBool>,
+
+    /// Number of successful aborts, i.e. the conflicts detected by validation.
+    num_aborts: CachePadded<AtomicU32>,
 }
 
 /// Public Interfaces for the Scheduler
@@ -263,6 +266,7 @@ impl Scheduler {
             execution_idx: AtomicU32::new(0),
             validation_idx: AtomicU64::new(0),
             done_marker: CachePadded::new(AtomicBool::new(false)),
+            num_aborts: CachePadded::new(AtomicU32::new(0)),
         }
     }
 
@@ -270,6 +274,10 @@ impl Scheduler {
         self.num_txns
     }
 
+    pub fn num_aborts(&self) -> u32 {
+        self.num_aborts.load(Ordering::Relaxed)
+    }
+
     /// If successful, returns Some(TxnIndex), the index of committed transaction.
     /// The current implementation has one dedicated thread to try_commit.
     /// Should not be called after the last transaction is committed.
@@ -335,6 +343,7 @@ impl Scheduler {
 
         if *status == ExecutionStatus::Executed(incarnation) {
             *status = ExecutionStatus::Aborting(incarnation);
+            self.num_aborts.fetch_add(1, Ordering::Relaxed);
             true
         } else {
             false
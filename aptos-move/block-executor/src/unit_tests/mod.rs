@@ -51,7 +51,7 @@ where
         DeltaDataView<K, V>,
         NoOpTransactionCommitHook<MockOutput<K, V, E>, usize>,
         ExecutableTestType,
-    >::new(num_cpus::get(), executor_thread_pool, None, None)
+    >::new(num_cpus::get(), executor_thread_pool, None, None, None)
     .execute_transactions_parallel((), &transactions, &data_view);
 
     let baseline = BaselineOutput::generate(&transactions, None);
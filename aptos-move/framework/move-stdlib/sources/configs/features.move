@@ -220,6 +220,17 @@ module std::features {
         is_enabled(BULLETPROOFS_NATIVES)
     }
 
+    /// Whether validators may advertise QUIC network addresses. Nodes that don't know about QUIC fail to
+    /// decode them, so peers only dial them once this is enabled.
+    /// Lifetime: transient
+    const QUIC_TRANSPORT: u64 = 25;
+
+    public fun get_quic_transport_feature(): u64 { QUIC_TRANSPORT }
+
+    public fun quic_transport_enabled(): bool acquires Features {
+        is_enabled(QUIC_TRANSPORT)
+    }
+
     // ============================================================================================
     // Feature Flag Implementation
 
@@ -18,7 +18,7 @@ mod tests;
 use anyhow::anyhow;
 use aptos_api::bootstrap as bootstrap_api;
 use aptos_build_info::build_information;
-use aptos_config::config::{merge_node_config, NodeConfig, PersistableConfig};
+use aptos_config::config::{merge_node_config, IndexerGrpcConfig, NodeConfig, PersistableConfig};
 use aptos_framework::ReleaseBundle;
 use aptos_logger::{prelude::*, telemetry_log_writer::TelemetryLog, Level, LoggerFilterUpdater};
 use aptos_state_sync_driver::driver_factory::StateSyncRuntimes;
@@ -30,6 +30,7 @@ use rand::{rngs::StdRng, SeedableRng};
 use std::{
     fs,
     io::{Read, Write},
+    net::SocketAddr,
     path::PathBuf,
     sync::{
         atomic::{AtomicBool, Ordering},
@@ -126,6 +127,7 @@ impl AptosNodeArgs {
                 self.test_dir,
                 self.random_ports,
                 self.lazy,
+                None,
                 &genesis_framework,
                 rng,
             )
@@ -233,6 +235,7 @@ pub fn setup_test_environment_and_start_node<R>(
     test_dir: Option<PathBuf>,
     random_ports: bool,
     enable_lazy_mode: bool,
+    indexer_grpc_address: Option<SocketAddr>,
     framework: &ReleaseBundle,
     rng: R,
 ) -> anyhow::Result<()>
@@ -252,7 +255,7 @@ where
     let aptos_root_key_path = test_dir.join("mint.key");
 
     // If there's already a config, use it. Otherwise create a test one.
-    let config = if validator_config_path.exists() {
+    let mut config = if validator_config_path.exists() {
         NodeConfig::load_from_path(&validator_config_path)
             .map_err(|error| anyhow!("Unable to load config: {:?}", error))?
     } else {
@@ -292,6 +295,12 @@ where
         validators[0].config.clone()
     };
 
+    // Serve the indexer gRPC stream from the node, if requested
+    if let Some(indexer_grpc_address) = indexer_grpc_address {
+        config.indexer_grpc =
+            IndexerGrpcConfig::enabled_with_address(indexer_grpc_address.to_string());
+    }
+
     // Prepare log file since we cannot automatically route logs to stderr
     let log_file = test_dir.join("validator.log");
 
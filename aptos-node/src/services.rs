@@ -136,7 +136,11 @@ pub fn start_node_inspection_service(
     node_config: &NodeConfig,
     peers_and_metadata: Arc<PeersAndMetadata>,
 ) {
-    aptos_inspection_service::start_inspection_service(node_config.clone(), peers_and_metadata)
+    aptos_inspection_service::start_inspection_service(
+        node_config.clone(),
+        peers_and_metadata,
+        Arc::new(|| serde_json::to_string(&aptos_consensus::anchor_vote_history())),
+    )
 }
 
 /// Starts the peer monitoring service and returns the runtime
@@ -48,10 +48,23 @@ pub struct ApiConfig {
     /// Enables transaction simulation
     #[serde(default = "default_enabled")]
     pub transaction_simulation_enabled: bool,
+    /// Enables the WebSocket stream of committed transactions and events
+    #[serde(default = "default_disabled")]
+    pub stream_enabled: bool,
+    /// Maximum number of WebSocket streams open at the same time
+    pub max_stream_connections: usize,
+    /// Enables gzip and zstd compression of responses, as negotiated with Accept-Encoding
+    #[serde(default = "default_enabled")]
+    pub compression_enabled: bool,
+    /// Responses smaller than this many bytes are sent uncompressed
+    pub compression_min_size: usize,
     /// Maximum number of transactions that can be sent with the Batch submit API
     pub max_submit_transaction_batch_size: usize,
     /// Maximum page size for transaction paginated APIs
     pub max_transactions_page_size: u16,
+    /// Maximum number of account transactions read to answer one filtered account transactions
+    /// request, whether it starts from a given sequence number or lists the latest transactions
+    pub max_account_transactions_filter_scan: u64,
     /// Maximum page size for event paginated APIs
     pub max_events_page_size: u16,
     /// Maximum page size for resource paginated APIs
@@ -62,6 +75,8 @@ pub struct ApiConfig {
     ///
     /// This limits the execution length of a view function to the given gas used.
     pub max_gas_view_function: u64,
+    /// Maximum number of view requests that can be sent with the batch view API
+    pub max_view_batch_size: usize,
     /// Optional: Maximum number of worker threads for the API.
     ///
     /// If not set, `runtime_worker_multiplier` will multiply times the number of CPU cores on the machine
@@ -79,8 +94,12 @@ const DEFAULT_PORT: u16 = 8080;
 const DEFAULT_REQUEST_CONTENT_LENGTH_LIMIT: u64 = 8 * 1024 * 1024; // 8 MB
 pub const DEFAULT_MAX_SUBMIT_TRANSACTION_BATCH_SIZE: usize = 10;
 pub const DEFAULT_MAX_PAGE_SIZE: u16 = 100;
+const DEFAULT_MAX_ACCOUNT_TRANSACTIONS_FILTER_SCAN: u64 = 1000;
+const DEFAULT_MAX_STREAM_CONNECTIONS: usize = 500;
+const DEFAULT_COMPRESSION_MIN_SIZE: usize = 1024; // 1 KB
 const DEFAULT_MAX_ACCOUNT_RESOURCES_PAGE_SIZE: u16 = 9999;
 const DEFAULT_MAX_ACCOUNT_MODULES_PAGE_SIZE: u16 = 9999;
+const DEFAULT_MAX_VIEW_BATCH_SIZE: usize = 50;
 const DEFAULT_MAX_VIEW_GAS: u64 = 2_000_000; // We keep this value the same as the max number of gas allowed for one single transaction defined in aptos-gas.
 
 fn default_enabled() -> bool {
@@ -107,12 +126,18 @@ impl Default for ApiConfig {
             encode_submission_enabled: default_enabled(),
             transaction_submission_enabled: default_enabled(),
             transaction_simulation_enabled: default_enabled(),
+            stream_enabled: default_disabled(),
+            max_stream_connections: DEFAULT_MAX_STREAM_CONNECTIONS,
+            compression_enabled: default_enabled(),
+            compression_min_size: DEFAULT_COMPRESSION_MIN_SIZE,
             max_submit_transaction_batch_size: DEFAULT_MAX_SUBMIT_TRANSACTION_BATCH_SIZE,
             max_transactions_page_size: DEFAULT_MAX_PAGE_SIZE,
+            max_account_transactions_filter_scan: DEFAULT_MAX_ACCOUNT_TRANSACTIONS_FILTER_SCAN,
             max_events_page_size: DEFAULT_MAX_PAGE_SIZE,
             max_account_resources_page_size: DEFAULT_MAX_ACCOUNT_RESOURCES_PAGE_SIZE,
             max_account_modules_page_size: DEFAULT_MAX_ACCOUNT_MODULES_PAGE_SIZE,
             max_gas_view_function: DEFAULT_MAX_VIEW_GAS,
+            max_view_batch_size: DEFAULT_MAX_VIEW_BATCH_SIZE,
             max_runtime_workers: None,
             runtime_worker_multiplier: 2,
             gas_estimation: GasEstimationConfig::default(),
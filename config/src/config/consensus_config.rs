@@ -3,8 +3,8 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::config::{
-    config_sanitizer::ConfigSanitizer, node_config_loader::NodeType, Error, NodeConfig,
-    QuorumStoreConfig, SafetyRulesConfig,
+    config_sanitizer::ConfigSanitizer, node_config_loader::NodeType, DagConsensusConfig, Error,
+    NodeConfig, QuorumStoreConfig, SafetyRulesConfig,
 };
 use aptos_types::chain_id::ChainId;
 use cfg_if::cfg_if;
@@ -59,6 +59,7 @@ pub struct ConsensusConfig {
     pub wait_for_full_blocks_above_recent_fill_threshold: f32,
     pub intra_consensus_channel_buffer_size: usize,
     pub quorum_store: QuorumStoreConfig,
+    pub dag_consensus: DagConsensusConfig,
     pub vote_back_pressure_limit: u64,
     pub pipeline_backpressure: Vec<PipelineBackpressureValues>,
     // Used to decide if backoff is needed.
@@ -126,6 +127,7 @@ impl Default for ConsensusConfig {
             wait_for_full_blocks_above_recent_fill_threshold: 1.1,
             intra_consensus_channel_buffer_size: 10,
             quorum_store: QuorumStoreConfig::default(),
+            dag_consensus: DagConsensusConfig::default(),
 
             // Voting backpressure is only used as a backup, to make sure pending rounds don't
             // increase uncontrollably, and we know when to go to state sync.
@@ -357,9 +359,10 @@ impl ConfigSanitizer for ConsensusConfig {
     ) -> Result<(), Error> {
         let sanitizer_name = Self::get_sanitizer_name();
 
-        // Verify that the safety rules and quorum store configs are valid
+        // Verify that the safety rules, quorum store and DAG configs are valid
         SafetyRulesConfig::sanitize(node_config, node_type, chain_id)?;
         QuorumStoreConfig::sanitize(node_config, node_type, chain_id)?;
+        DagConsensusConfig::sanitize(node_config, node_type, chain_id)?;
 
         // Verify that the consensus-only feature is not enabled in mainnet
         if chain_id.is_mainnet() && is_consensus_only_perf_test_enabled() {
@@ -0,0 +1,128 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::config::{
+    config_sanitizer::ConfigSanitizer, node_config_loader::NodeType, Error, NodeConfig,
+};
+use aptos_types::chain_id::ChainId;
+use cfg_if::cfg_if;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DagConsensusConfig {
+    // Max number of ordered batches buffered before the DAG pauses ordering
+    pub ordered_nodes_channel_size: usize,
+    // Length of the inbound queue of DAG rpc requests
+    pub rpc_channel_size: usize,
+    // Report a stall once no anchor got ordered for this long while the DAG kept advancing
+    // rounds, None disables the health monitor. Switching back to Jolteon is done through the
+    // on-chain consensus config at the next epoch.
+    pub stall_threshold_ms: Option<u64>,
+    // Interval between two health checks of the DAG ordering
+    pub health_check_interval_ms: u64,
+    // Max number of transactions in the payload of a node
+    pub max_node_txns: u64,
+    // Max size in bytes of the payload of a node
+    pub max_node_bytes: u64,
+    // Max number of rounds a node can be ahead of the local highest round
+    pub max_node_round_lookahead: u64,
+    // Max size in bytes of the certified nodes in a response to a round range fetch request
+    pub max_fetch_response_bytes: u64,
+    // How often the DAG has an anchor. Every round is only accepted by test builds, the order
+    // rule needs anchors at least two rounds apart.
+    pub anchor_frequency: DagAnchorFrequency,
+}
+
+impl Default for DagConsensusConfig {
+    fn default() -> DagConsensusConfig {
+        DagConsensusConfig {
+            ordered_nodes_channel_size: 10,
+            rpc_channel_size: 100,
+            stall_threshold_ms: Some(30_000),
+            health_check_interval_ms: 1_000,
+            max_node_txns: 1_000,
+            max_node_bytes: 2 * 1024 * 1024,
+            max_node_round_lookahead: 10,
+            max_fetch_response_bytes: 8 * 1024 * 1024,
+            anchor_frequency: DagAnchorFrequency::EveryOtherRound,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DagAnchorFrequency {
+    EveryRound,
+    EveryOtherRound,
+}
+
+impl ConfigSanitizer for DagConsensusConfig {
+    fn sanitize(
+        node_config: &mut NodeConfig,
+        _node_type: NodeType,
+        _chain_id: ChainId,
+    ) -> Result<(), Error> {
+        let sanitizer_name = Self::get_sanitizer_name();
+
+        // Verify that anchors every round are only used by test builds
+        if node_config.consensus.dag_consensus.anchor_frequency == DagAnchorFrequency::EveryRound
+            && !are_every_round_anchors_allowed()
+        {
+            return Err(Error::ConfigSanitizerFailed(
+                sanitizer_name,
+                "DAG anchors every round are only supported in test builds!".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns true iff the build allows DAG anchors every round
+fn are_every_round_anchors_allowed() -> bool {
+    cfg_if! {
+        if #[cfg(any(feature = "testing", feature = "fuzzing"))] {
+            true
+        } else {
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::config::ConsensusConfig;
+
+    #[test]
+    fn test_sanitize_anchor_frequency() {
+        // Create a node config with anchors every other round
+        let mut node_config = NodeConfig::default();
+
+        // Sanitize the config and verify that it succeeds
+        DagConsensusConfig::sanitize(&mut node_config, NodeType::Validator, ChainId::mainnet())
+            .unwrap();
+
+        // Create a node config with anchors every round
+        let mut node_config = NodeConfig {
+            consensus: ConsensusConfig {
+                dag_consensus: DagConsensusConfig {
+                    anchor_frequency: DagAnchorFrequency::EveryRound,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        // Sanitize the config and verify that it only succeeds in test builds
+        let result =
+            DagConsensusConfig::sanitize(&mut node_config, NodeType::Validator, ChainId::testnet());
+        if are_every_round_anchors_allowed() {
+            result.unwrap();
+        } else {
+            assert!(matches!(result, Err(Error::ConfigSanitizerFailed(_, _))));
+        }
+    }
+}
@@ -26,6 +26,9 @@ pub struct ExecutionConfig {
     pub genesis_file_location: PathBuf,
     /// Number of threads to run execution
     pub concurrency_level: u16,
+    /// Adapts the number of threads to run execution (up to concurrency_level) to the conflict
+    /// rate of recent blocks, falling back to sequential execution for highly conflicting ones
+    pub adaptive_concurrency: bool,
     /// Number of threads to read proofs
     pub num_proof_reading_threads: u16,
     /// Enables paranoid mode for types, which adds extra runtime VM checks
@@ -59,6 +62,7 @@ impl Default for ExecutionConfig {
             genesis_file_location: PathBuf::new(),
             // Parallel execution by default.
             concurrency_level: 8,
+            adaptive_concurrency: false,
             num_proof_reading_threads: 32,
             paranoid_type_verification: true,
             paranoid_hot_potato_verification: true,
@@ -35,6 +35,20 @@ pub struct IndexerGrpcConfig {
     pub output_batch_size: Option<u16>,
 }
 
+impl IndexerGrpcConfig {
+    /// Returns an enabled config listening on the given address, with the
+    /// default task count and batch sizes.
+    pub fn enabled_with_address(address: String) -> Self {
+        Self {
+            enabled: true,
+            address: Some(address),
+            processor_task_count: Some(DEFAULT_PROCESSOR_TASK_COUNT),
+            processor_batch_size: Some(DEFAULT_PROCESSOR_BATCH_SIZE),
+            output_batch_size: Some(DEFAULT_OUTPUT_BATCH_SIZE),
+        }
+    }
+}
+
 impl ConfigSanitizer for IndexerGrpcConfig {
     fn sanitize(
         node_config: &mut NodeConfig,
@@ -20,8 +20,17 @@ pub struct MempoolConfig {
     pub capacity_bytes: usize,
     /// Maximum number of transactions allowed in the Mempool per user
     pub capacity_per_user: usize,
+    /// Maximum number of "non-ready" transactions, i.e. with a sequence number gap, allowed in the
+    /// Mempool per user
+    pub parking_lot_capacity_per_user: usize,
     /// Number of failover peers to broadcast to when the primary network is alive
     pub default_failovers: usize,
+    /// Percentage of failed broadcasts (timeouts, backoffs and send errors) above which an
+    /// upstream peer is deprioritized in favor of healthier peers on the same network.
+    pub broadcast_peer_max_failure_percentage: u64,
+    /// Margin, in percent, by which a peer has to be more responsive (or healthier) than the peers
+    /// currently broadcast to before replacing them, so that the prioritized peers don't flap.
+    pub broadcast_peer_priority_hysteresis_percentage: u64,
     /// The maximum number of broadcasts sent to a single peer that are pending a response ACK at any point.
     pub max_broadcasts_per_peer: usize,
     /// Maximum number of inbound network messages to the Mempool application
@@ -51,6 +60,13 @@ pub struct MempoolConfig {
     ///
     /// Overriding this won't make much of a difference if the upstream nodes don't match.
     pub broadcast_buckets: Vec<u64>,
+    /// Gas unit price lanes of the priority queue, as the minimum gas unit price of each lane.
+    ///
+    /// When Mempool is full, the transactions of the lowest lane are evicted first to make room
+    /// for the transactions of a higher lane.
+    pub priority_lanes: Vec<u64>,
+    /// Maximum number of ready transactions in each of the `priority_lanes`.
+    pub priority_lane_capacities: Vec<usize>,
     pub eager_expire_threshold_ms: Option<u64>,
     pub eager_expire_time_ms: u64,
 }
@@ -70,11 +86,16 @@ impl Default for MempoolConfig {
             capacity: 2_000_000,
             capacity_bytes: 2 * 1024 * 1024 * 1024,
             capacity_per_user: 100,
+            parking_lot_capacity_per_user: 20,
             default_failovers: 1,
+            broadcast_peer_max_failure_percentage: 50,
+            broadcast_peer_priority_hysteresis_percentage: 20,
             shared_mempool_peer_update_interval_ms: 1_000,
             system_transaction_timeout_secs: 600,
             system_transaction_gc_interval_ms: 60_000,
             broadcast_buckets: DEFAULT_BUCKETS.to_vec(),
+            priority_lanes: vec![0, 150, 1000],
+            priority_lane_capacities: vec![1_000_000, 2_000_000, 2_000_000],
             eager_expire_threshold_ms: Some(10_000),
             eager_expire_time_ms: 3_000,
         }
@@ -80,7 +80,10 @@ pub struct NetworkConfig {
     /// Identity of this network
     pub identity: Identity,
     // TODO: Add support for multiple listen/advertised addresses in config.
-    /// The address that this node is listening on for new connections.
+    /// The address that this node is listening on for new connections, over TCP for
+    /// `/ip4/<addr>/tcp/<port>` or QUIC for `/ip4/<addr>/quic/<port>`. Either way, peers are
+    /// dialed over TCP or QUIC depending on their addresses. QUIC addresses of the on-chain
+    /// validator set are only used once the `QUIC_TRANSPORT` feature is enabled.
     pub listen_address: NetworkAddress,
     /// Select this to enforce that both peers should authenticate each other, otherwise
     /// authentication only occurs for outgoing connections.
@@ -105,6 +105,9 @@ pub struct StateSyncDriverConfig {
     pub max_connection_deadline_secs: u64,
     /// The maximum number of notifications to process per driver loop
     pub max_consecutive_stream_notifications: u64,
+    /// The maximum number of data chunks to verify concurrently, ahead of their
+    /// (sequential) execution or application
+    pub max_parallel_chunk_verifications: u64,
     /// The maximum number of stream timeouts allowed before termination
     pub max_num_stream_timeouts: u64,
     /// The maximum number of data chunks pending execution or commit
@@ -130,6 +133,7 @@ impl Default for StateSyncDriverConfig {
             progress_check_interval_ms: 50,
             max_connection_deadline_secs: 10,
             max_consecutive_stream_notifications: 10,
+            max_parallel_chunk_verifications: 4,
             max_num_stream_timeouts: 12,
             max_pending_data_chunks: 100,
             max_stream_wait_time_ms: 5000,
@@ -128,6 +128,11 @@ pub const NO_OP_STORAGE_PRUNER_CONFIG: PrunerConfig = PrunerConfig {
         batch_size: 0,
         user_pruning_window_offset: 0,
     },
+    state_kv_pruner_config: StateKvPrunerConfig {
+        enable: false,
+        prune_window: None,
+        batch_size: 0,
+    },
     state_merkle_pruner_config: StateMerklePrunerConfig {
         enable: false,
         prune_window: 0,
@@ -146,9 +151,8 @@ pub struct LedgerPrunerConfig {
     /// Boolean to enable/disable the ledger pruner. The ledger pruner is responsible for pruning
     /// everything else except for states (e.g. transactions, events etc.)
     pub enable: bool,
-    /// This is the default pruning window for any other store except for state store. State store
-    /// being big in size, we might want to configure a smaller window for state store vs other
-    /// store.
+    /// This is the pruning window for the ledger history, i.e. transactions and their outputs.
+    /// State values and state trees are pruned according to their own configs.
     pub prune_window: u64,
     /// Batch size of the versions to be sent to the ledger pruner - this is to avoid slowdown due to
     /// issuing too many DB calls and batch prune instead. For ledger pruner, this means the number
@@ -158,6 +162,26 @@ pub struct LedgerPrunerConfig {
     pub user_pruning_window_offset: u64,
 }
 
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct StateKvPrunerConfig {
+    /// Boolean to enable/disable the state kv pruner. The state kv pruner is responsible for
+    /// pruning stale state values.
+    pub enable: bool,
+    /// Window size in versions. If not set, the ledger pruner's window is used, so that the state
+    /// at any version with transactions available is also readable.
+    pub prune_window: Option<u64>,
+    /// Batch size of the versions to be sent to the state kv pruner.
+    pub batch_size: usize,
+}
+
+impl StateKvPrunerConfig {
+    /// Returns the window size in versions, falling back to the given ledger pruner's window.
+    pub fn effective_prune_window(&self, ledger_prune_window: u64) -> u64 {
+        self.prune_window.unwrap_or(ledger_prune_window)
+    }
+}
+
 #[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
 #[serde(default, deny_unknown_fields)]
 pub struct StateMerklePrunerConfig {
@@ -198,6 +222,7 @@ impl From<EpochSnapshotPrunerConfig> for StateMerklePrunerConfig {
 #[serde(default, deny_unknown_fields)]
 pub struct PrunerConfig {
     pub ledger_pruner_config: LedgerPrunerConfig,
+    pub state_kv_pruner_config: StateKvPrunerConfig,
     pub state_merkle_pruner_config: StateMerklePrunerConfig,
     pub epoch_snapshot_pruner_config: EpochSnapshotPrunerConfig,
 }
@@ -215,6 +240,16 @@ impl Default for LedgerPrunerConfig {
     }
 }
 
+impl Default for StateKvPrunerConfig {
+    fn default() -> Self {
+        StateKvPrunerConfig {
+            enable: true,
+            prune_window: None,
+            batch_size: 500,
+        }
+    }
+}
+
 impl Default for StateMerklePrunerConfig {
     fn default() -> Self {
         StateMerklePrunerConfig {
@@ -303,6 +338,10 @@ impl ConfigSanitizer for StorageConfig {
             .storage_pruner_config
             .ledger_pruner_config
             .prune_window;
+        let state_kv_prune_window = config
+            .storage_pruner_config
+            .state_kv_pruner_config
+            .effective_prune_window(ledger_prune_window);
         let state_merkle_prune_window = config
             .storage_pruner_config
             .state_merkle_pruner_config
@@ -319,6 +358,9 @@ impl ConfigSanitizer for StorageConfig {
         if ledger_prune_window < 50_000_000 {
             warn!("Ledger prune_window is too small, harming network data availability.");
         }
+        if state_kv_prune_window < state_merkle_prune_window {
+            warn!("State KV prune_window is smaller than the State Merkle prune_window, reading recent states might fail.");
+        }
         if state_merkle_prune_window < 100_000 {
             warn!("State Merkle prune_window is too small, node might stop functioning.");
         }
@@ -344,7 +386,7 @@ impl ConfigSanitizer for StorageConfig {
 
 #[cfg(test)]
 mod test {
-    use crate::config::PrunerConfig;
+    use crate::config::{PrunerConfig, StateKvPrunerConfig};
 
     #[test]
     pub fn test_default_prune_window() {
@@ -352,7 +394,37 @@ mod test {
 
         let config = PrunerConfig::default();
         assert!(config.ledger_pruner_config.prune_window >= 50_000_000);
+        assert!(
+            config
+                .state_kv_pruner_config
+                .effective_prune_window(config.ledger_pruner_config.prune_window)
+                >= config.state_merkle_pruner_config.prune_window
+        );
         assert!(config.state_merkle_pruner_config.prune_window >= 100_000);
         assert!(config.epoch_snapshot_pruner_config.prune_window > 50_000_000);
     }
+
+    #[test]
+    pub fn test_state_kv_prune_window_fallback() {
+        // Configs without a state kv pruner window keep the ledger pruner's window
+        let config: PrunerConfig = serde_yaml::from_str(
+            r#"
+            ledger_pruner_config:
+                prune_window: 1000000000
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            config
+                .state_kv_pruner_config
+                .effective_prune_window(config.ledger_pruner_config.prune_window),
+            1_000_000_000
+        );
+
+        let config = StateKvPrunerConfig {
+            prune_window: Some(100_000_000),
+            ..StateKvPrunerConfig::default()
+        };
+        assert_eq!(config.effective_prune_window(1_000_000_000), 100_000_000);
+    }
 }
@@ -203,6 +203,33 @@ impl Block {
         }
     }
 
+    pub fn new_for_dag(
+        epoch: u64,
+        round: Round,
+        timestamp_usecs: u64,
+        payload: Payload,
+        author: Author,
+        failed_authors: Vec<(Round, Author)>,
+        parent_block_info: BlockInfo,
+        node_digests: Vec<HashValue>,
+    ) -> Self {
+        let block_data = BlockData::new_for_dag(
+            epoch,
+            round,
+            timestamp_usecs,
+            payload,
+            author,
+            failed_authors,
+            parent_block_info,
+            node_digests,
+        );
+        Self {
+            id: block_data.hash(),
+            block_data,
+            signature: None,
+        }
+    }
+
     pub fn new_proposal(
         payload: Payload,
         round: Round,
@@ -249,6 +276,7 @@ impl Block {
     pub fn validate_signature(&self, validator: &ValidatorVerifier) -> anyhow::Result<()> {
         match self.block_data.block_type() {
             BlockType::Genesis => bail!("We should not accept genesis from others"),
+            BlockType::DAGBlock { .. } => bail!("We should not accept DAG block from others"),
             BlockType::NilBlock { .. } => self.quorum_cert().verify(validator),
             BlockType::Proposal { author, .. } => {
                 let signature = self
@@ -42,6 +42,14 @@ pub enum BlockType {
     /// from the previous epoch.  The genesis block is used as the first root block of the
     /// BlockTree for all epochs.
     Genesis,
+    /// A virtual block that's constructed by nodes from DAG, this is purely a local thing so
+    /// we hide it from other validators
+    DAGBlock {
+        author: Author,
+        failed_authors: Vec<(Round, Author)>,
+        payload: Payload,
+        node_digests: Vec<HashValue>,
+    },
 }
 
 #[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq, CryptoHasher, BCSCryptoHash)]
@@ -79,10 +87,9 @@ pub struct BlockData {
 
 impl BlockData {
     pub fn author(&self) -> Option<Author> {
-        if let BlockType::Proposal { author, .. } = self.block_type {
-            Some(author)
-        } else {
-            None
+        match self.block_type {
+            BlockType::Proposal { author, .. } | BlockType::DAGBlock { author, .. } => Some(author),
+            _ => None,
         }
     }
 
@@ -99,10 +106,11 @@ impl BlockData {
     }
 
     pub fn payload(&self) -> Option<&Payload> {
-        if let BlockType::Proposal { payload, .. } = &self.block_type {
-            Some(payload)
-        } else {
-            None
+        match &self.block_type {
+            BlockType::Proposal { payload, .. } | BlockType::DAGBlock { payload, .. } => {
+                Some(payload)
+            },
+            _ => None,
         }
     }
 
@@ -126,6 +134,10 @@ impl BlockData {
         matches!(self.block_type, BlockType::NilBlock { .. })
     }
 
+    pub fn is_dag_block(&self) -> bool {
+        matches!(self.block_type, BlockType::DAGBlock { .. })
+    }
+
     /// the list of consecutive proposers from the immediately preceeding
     /// rounds that didn't produce a successful block
     pub fn failed_authors(&self) -> Option<&Vec<(Round, Author)>> {
@@ -134,6 +146,9 @@ impl BlockData {
                 ref failed_authors, ..
             } => Some(failed_authors),
             BlockType::NilBlock { ref failed_authors } => Some(failed_authors),
+            BlockType::DAGBlock {
+                ref failed_authors, ..
+            } => Some(failed_authors),
             BlockType::Genesis => None,
         }
     }
@@ -232,6 +247,39 @@ impl BlockData {
         }
     }
 
+    /// DAG blocks don't carry a real QC, the parent is ordered by the DAG so we fill in a
+    /// placeholder that certifies the parent block.
+    pub fn new_for_dag(
+        epoch: u64,
+        round: Round,
+        timestamp_usecs: u64,
+        payload: Payload,
+        author: Author,
+        failed_authors: Vec<(Round, Author)>,
+        parent_block_info: BlockInfo,
+        node_digests: Vec<HashValue>,
+    ) -> Self {
+        let quorum_cert = QuorumCert::new(
+            VoteData::new(parent_block_info.clone(), parent_block_info.clone()),
+            LedgerInfoWithSignatures::new(
+                LedgerInfo::new(parent_block_info, HashValue::zero()),
+                AggregateSignature::empty(),
+            ),
+        );
+        Self {
+            epoch,
+            round,
+            timestamp_usecs,
+            quorum_cert,
+            block_type: BlockType::DAGBlock {
+                author,
+                failed_authors,
+                payload,
+                node_digests,
+            },
+        }
+    }
+
     /// It's a reconfiguration suffix block if the parent block's executed state indicates next epoch.
     pub fn is_reconfiguration_suffix(&self) -> bool {
         self.quorum_cert.certified_block().has_reconfiguration()
@@ -72,4 +72,14 @@ impl TSafetyRules for LocalClient {
             .write()
             .sign_commit_vote(ledger_info, new_ledger_info)
     }
+
+    fn sign_dag_commit_vote(
+        &mut self,
+        ordered_ledger_info: LedgerInfo,
+        new_ledger_info: LedgerInfo,
+    ) -> Result<bls12381::Signature, Error> {
+        self.internal
+            .write()
+            .sign_dag_commit_vote(ordered_ledger_info, new_ledger_info)
+    }
 }
@@ -54,6 +54,7 @@ pub enum LogEntry {
     State,
     Waypoint,
     SignCommitVote,
+    SignDagCommitVote,
 }
 
 impl LogEntry {
@@ -72,6 +73,7 @@ impl LogEntry {
             LogEntry::State => "state",
             LogEntry::Waypoint => "waypoint",
             LogEntry::SignCommitVote => "sign_commit_vote",
+            LogEntry::SignDagCommitVote => "sign_dag_commit_vote",
         }
     }
 }
@@ -360,6 +360,40 @@ impl SafetyRules {
 
         Ok(signature)
     }
+
+    fn guarded_sign_dag_commit_vote(
+        &mut self,
+        ordered_ledger_info: LedgerInfo,
+        new_ledger_info: LedgerInfo,
+    ) -> Result<bls12381::Signature, Error> {
+        self.signer()?;
+
+        let epoch = self.epoch_state()?.epoch;
+        if ordered_ledger_info.epoch() != epoch {
+            return Err(Error::IncorrectEpoch(ordered_ledger_info.epoch(), epoch));
+        }
+
+        if !ordered_ledger_info.commit_info().is_ordered_only() {
+            return Err(Error::InvalidOrderedLedgerInfo(
+                ordered_ledger_info.to_string(),
+            ));
+        }
+
+        if !ordered_ledger_info
+            .commit_info()
+            .match_ordered_only(new_ledger_info.commit_info())
+            || ordered_ledger_info.consensus_data_hash() != new_ledger_info.consensus_data_hash()
+        {
+            return Err(Error::InconsistentExecutionResult(
+                ordered_ledger_info.commit_info().to_string(),
+                new_ledger_info.commit_info().to_string(),
+            ));
+        }
+
+        let signature = self.sign(&new_ledger_info)?;
+
+        Ok(signature)
+    }
 }
 
 impl TSafetyRules for SafetyRules {
@@ -414,6 +448,15 @@ impl TSafetyRules for SafetyRules {
         let cb = || self.guarded_sign_commit_vote(ledger_info, new_ledger_info);
         run_and_log(cb, |log| log, LogEntry::SignCommitVote)
     }
+
+    fn sign_dag_commit_vote(
+        &mut self,
+        ordered_ledger_info: LedgerInfo,
+        new_ledger_info: LedgerInfo,
+    ) -> Result<bls12381::Signature, Error> {
+        let cb = || self.guarded_sign_dag_commit_vote(ordered_ledger_info, new_ledger_info);
+        run_and_log(cb, |log| log, LogEntry::SignDagCommitVote)
+    }
 }
 
 fn run_and_log<F, L, R>(callback: F, log_cb: L, log_entry: LogEntry) -> Result<R, Error>
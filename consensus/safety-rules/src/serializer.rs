@@ -29,6 +29,7 @@ pub enum SafetyRulesInput {
     ),
     ConstructAndSignVoteTwoChain(Box<VoteProposal>, Box<Option<TwoChainTimeoutCertificate>>),
     SignCommitVote(Box<LedgerInfoWithSignatures>, Box<LedgerInfo>),
+    SignDagCommitVote(Box<LedgerInfo>, Box<LedgerInfo>),
 }
 
 pub struct SerializerService {
@@ -69,6 +70,13 @@ impl SerializerService {
                     .internal
                     .sign_commit_vote(*ledger_info, *new_ledger_info),
             ),
+            SafetyRulesInput::SignDagCommitVote(ordered_ledger_info, new_ledger_info) => {
+                serde_json::to_vec(
+                    &self
+                        .internal
+                        .sign_dag_commit_vote(*ordered_ledger_info, *new_ledger_info),
+                )
+            },
         };
 
         Ok(output?)
@@ -153,6 +161,19 @@ impl TSafetyRules for SerializerClient {
         ))?;
         serde_json::from_slice(&response)?
     }
+
+    fn sign_dag_commit_vote(
+        &mut self,
+        ordered_ledger_info: LedgerInfo,
+        new_ledger_info: LedgerInfo,
+    ) -> Result<bls12381::Signature, Error> {
+        let _timer = counters::start_timer("external", LogEntry::SignDagCommitVote.as_str());
+        let response = self.request(SafetyRulesInput::SignDagCommitVote(
+            Box::new(ordered_ledger_info),
+            Box::new(new_ledger_info),
+        ))?;
+        serde_json::from_slice(&response)?
+    }
 }
 
 pub trait TSerializerClient: Send + Sync {
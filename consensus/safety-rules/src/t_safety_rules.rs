@@ -52,4 +52,12 @@ pub trait TSafetyRules {
         ledger_info: LedgerInfoWithSignatures,
         new_ledger_info: LedgerInfo,
     ) -> Result<bls12381::Signature, Error>;
+
+    /// Same as sign_commit_vote for a block ordered by the DAG. The DAG orders blocks without
+    /// a quorum certificate, so the ordered ledger info carries no signatures to verify.
+    fn sign_dag_commit_vote(
+        &mut self,
+        ordered_ledger_info: LedgerInfo,
+        new_ledger_info: LedgerInfo,
+    ) -> Result<bls12381::Signature, Error>;
 }
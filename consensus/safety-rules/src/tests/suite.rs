@@ -56,6 +56,7 @@ pub fn run_test_suite(safety_rules: &Callback) {
     test_2chain_rules(safety_rules);
     test_2chain_timeout(safety_rules);
     test_sign_commit_vote(safety_rules);
+    test_sign_dag_commit_vote(safety_rules);
     test_bad_execution_output(safety_rules);
 }
 
@@ -669,3 +670,65 @@ fn test_sign_commit_vote(constructor: &Callback) {
         Error::InconsistentExecutionResult(_, _)
     ));
 }
+
+fn test_sign_dag_commit_vote(constructor: &Callback) {
+    let (mut safety_rules, signer) = constructor();
+    let (proof, genesis_qc) = test_utils::make_genesis(&signer);
+
+    let round = genesis_qc.certified_block().round();
+    safety_rules.initialize(&proof).unwrap();
+
+    let a1 = test_utils::make_proposal_with_qc(round + 1, genesis_qc, &signer);
+    let ordered_block_info = a1
+        .block()
+        .gen_block_info(*ACCUMULATOR_PLACEHOLDER_HASH, 0, None);
+    let ordered_ledger_info = LedgerInfo::new(ordered_block_info.clone(), HashValue::zero());
+
+    // the ordered ledger info doesn't need any signature
+    assert!(safety_rules
+        .sign_dag_commit_vote(ordered_ledger_info.clone(), ordered_ledger_info.clone())
+        .is_ok());
+
+    // ledger info of another epoch
+    let other_epoch_block_info = BlockInfo::new(
+        ordered_block_info.epoch() + 1,
+        ordered_block_info.round(),
+        ordered_block_info.id(),
+        *ACCUMULATOR_PLACEHOLDER_HASH,
+        0,
+        ordered_block_info.timestamp_usecs(),
+        None,
+    );
+    let other_epoch_ledger_info = LedgerInfo::new(other_epoch_block_info, HashValue::zero());
+    assert!(matches!(
+        safety_rules
+            .sign_dag_commit_vote(other_epoch_ledger_info.clone(), other_epoch_ledger_info)
+            .unwrap_err(),
+        Error::IncorrectEpoch(_, _)
+    ));
+
+    // non-dummy blockinfo test
+    let executed_ledger_info = LedgerInfo::new(
+        a1.block()
+            .gen_block_info(*ACCUMULATOR_PLACEHOLDER_HASH, 100, None),
+        HashValue::zero(),
+    );
+    assert!(matches!(
+        safety_rules
+            .sign_dag_commit_vote(executed_ledger_info, ordered_ledger_info.clone())
+            .unwrap_err(),
+        Error::InvalidOrderedLedgerInfo(_)
+    ));
+
+    // inconsistent ledger_info test
+    let bad_ledger_info = LedgerInfo::new(
+        BlockInfo::random(ordered_ledger_info.round()),
+        ordered_ledger_info.consensus_data_hash(),
+    );
+    assert!(matches!(
+        safety_rules
+            .sign_dag_commit_vote(ordered_ledger_info, bad_ledger_info)
+            .unwrap_err(),
+        Error::InconsistentExecutionResult(_, _)
+    ));
+}
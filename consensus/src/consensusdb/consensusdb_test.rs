@@ -93,7 +93,15 @@ fn test_dag() {
     let tmp_dir = TempPath::new();
     let db = ConsensusDB::new(&tmp_dir);
 
-    let node = Node::new(1, 1, Author::random(), 123, Payload::empty(false), vec![]);
+    let node = Node::new(
+        1,
+        1,
+        Author::random(),
+        123,
+        Payload::empty(false),
+        vec![],
+        vec![],
+    );
     test_dag_type::<NodeSchema, <NodeSchema as Schema>::Key>(node.digest(), node.clone(), &db);
 
     let certified_node = CertifiedNode::new(node.clone(), AggregateSignature::empty());
@@ -113,3 +121,159 @@ fn test_dag() {
         &db,
     );
 }
+
+#[test]
+fn test_dag_schema_migration() {
+    let tmp_dir = TempPath::new();
+    let db = ConsensusDB::new(&tmp_dir);
+    assert_eq!(db.get_dag_schema_version().unwrap(), DAG_SCHEMA_VERSION);
+
+    // unversioned DAG data is dropped on open
+    let node = Node::new(
+        1,
+        1,
+        Author::random(),
+        123,
+        Payload::empty(false),
+        vec![],
+        vec![],
+    );
+    db.save_data::<NodeSchema>(&node.digest(), &node).unwrap();
+    db.save_data::<OrderedAnchorIdSchema>(&node.id(), &())
+        .unwrap();
+    db.delete_data::<DagMetadataSchema>(vec![DagMetadataKey::SchemaVersion])
+        .unwrap();
+    drop(db);
+
+    let db = ConsensusDB::new(&tmp_dir);
+    assert_eq!(db.get_dag_schema_version().unwrap(), DAG_SCHEMA_VERSION);
+    assert!(db.get_all_data::<NodeSchema>().unwrap().is_empty());
+    assert!(db
+        .get_all_data::<OrderedAnchorIdSchema>()
+        .unwrap()
+        .is_empty());
+
+    // versioned DAG data is kept
+    db.save_data::<NodeSchema>(&node.digest(), &node).unwrap();
+    drop(db);
+    let db = ConsensusDB::new(&tmp_dir);
+    assert_eq!(db.get_all_data::<NodeSchema>().unwrap().len(), 1);
+}
+
+#[test]
+fn test_dag_schema_migration_drops_nodes_without_weak_links() {
+    let tmp_dir = TempPath::new();
+    let db = ConsensusDB::new(&tmp_dir);
+
+    let node = Node::new(
+        1,
+        1,
+        Author::random(),
+        123,
+        Payload::empty(false),
+        vec![],
+        vec![],
+    );
+    db.save_data::<NodeSchema>(&node.digest(), &node).unwrap();
+    db.save_data::<OrderedAnchorIdSchema>(&node.id(), &())
+        .unwrap();
+    db.save_data::<DagMetadataSchema>(&DagMetadataKey::SchemaVersion, &1)
+        .unwrap();
+    drop(db);
+
+    let db = ConsensusDB::new(&tmp_dir);
+    assert_eq!(db.get_dag_schema_version().unwrap(), DAG_SCHEMA_VERSION);
+    assert!(db.get_all_data::<NodeSchema>().unwrap().is_empty());
+    assert_eq!(db.get_all_data::<OrderedAnchorIdSchema>().unwrap().len(), 1);
+}
+
+#[test]
+fn test_prune_dag_data() {
+    let tmp_dir = TempPath::new();
+    let db = ConsensusDB::new(&tmp_dir);
+
+    let author = Author::random();
+    for (epoch, round) in [(1, 5), (2, 1), (2, 3)] {
+        let node = Node::new(
+            epoch,
+            round,
+            author,
+            123,
+            Payload::empty(false),
+            vec![],
+            vec![],
+        );
+        let vote = Vote::new(node.metadata().clone(), Signature::dummy_signature());
+        db.save_data::<DagVoteSchema>(&node.id(), &vote).unwrap();
+        db.save_data::<OrderedAnchorIdSchema>(&node.id(), &())
+            .unwrap();
+        db.save_data::<NodeSchema>(&node.digest(), &node).unwrap();
+        let certified_node = CertifiedNode::new(node, AggregateSignature::empty());
+        db.save_certified_node(&certified_node).unwrap();
+    }
+
+    db.prune_dag_data(2, 2).unwrap();
+    let nodes = db.get_all_data::<NodeSchema>().unwrap();
+    assert_eq!(nodes.len(), 1);
+    assert_eq!((nodes[0].1.epoch(), nodes[0].1.round()), (2, 3));
+    assert_eq!(db.get_all_data::<CertifiedNodeSchema>().unwrap().len(), 1);
+    assert_eq!(
+        db.get_all_data::<CertifiedNodeRoundIndexSchema>()
+            .unwrap()
+            .len(),
+        1
+    );
+    assert_eq!(db.get_all_data::<DagVoteSchema>().unwrap().len(), 1);
+    assert_eq!(db.get_all_data::<OrderedAnchorIdSchema>().unwrap().len(), 1);
+}
+
+#[test]
+fn test_certified_nodes_in_range() {
+    let tmp_dir = TempPath::new();
+    let db = ConsensusDB::new(&tmp_dir);
+
+    let author = Author::random();
+    let mut digests = HashMap::new();
+    for (epoch, round) in [(1, 9), (2, 1), (2, 2), (2, 3), (2, 4), (3, 2)] {
+        let node = Node::new(
+            epoch,
+            round,
+            author,
+            123,
+            Payload::empty(false),
+            vec![],
+            vec![],
+        );
+        let certified_node = CertifiedNode::new(node, AggregateSignature::empty());
+        digests.insert((epoch, round), certified_node.digest());
+        db.save_certified_node(&certified_node).unwrap();
+    }
+
+    let rounds = |nodes: Vec<CertifiedNode>| -> Vec<_> {
+        nodes
+            .iter()
+            .map(|node| (node.epoch(), node.round()))
+            .collect()
+    };
+    assert_eq!(
+        rounds(db.get_certified_nodes_in_range(2, 2, 3).unwrap()),
+        vec![(2, 2), (2, 3)]
+    );
+    assert_eq!(
+        rounds(db.get_certified_nodes_in_range(2, 3, 10).unwrap()),
+        vec![(2, 3), (2, 4)]
+    );
+
+    // deleted nodes are dropped from the index as well
+    db.delete_certified_nodes(vec![digests[&(2, 3)]]).unwrap();
+    assert_eq!(
+        rounds(db.get_certified_nodes_in_range(2, 0, 10).unwrap()),
+        vec![(2, 1), (2, 2), (2, 4)]
+    );
+    assert_eq!(
+        db.get_all_data::<CertifiedNodeRoundIndexSchema>()
+            .unwrap()
+            .len(),
+        5
+    );
+}
@@ -6,9 +6,9 @@
 mod consensusdb_test;
 mod schema;
 
-use crate::error::DbError;
+use crate::{dag::CertifiedNode, error::DbError};
 use anyhow::Result;
-use aptos_consensus_types::{block::Block, quorum_cert::QuorumCert};
+use aptos_consensus_types::{block::Block, common::Round, quorum_cert::QuorumCert};
 use aptos_crypto::HashValue;
 use aptos_logger::prelude::*;
 use aptos_schemadb::{
@@ -16,19 +16,31 @@ use aptos_schemadb::{
 };
 pub use schema::{
     block::BlockSchema,
-    dag::{CertifiedNodeSchema, DagVoteSchema, NodeSchema, OrderedAnchorIdSchema},
+    dag::{
+        CertifiedNodeRoundIndexSchema, CertifiedNodeSchema, DagMetadataKey, DagMetadataSchema,
+        DagVoteSchema, NodeSchema, OrderedAnchorIdSchema,
+    },
     quorum_certificate::QCSchema,
 };
 use schema::{
     single_entry::{SingleEntryKey, SingleEntrySchema},
-    BLOCK_CF_NAME, CERTIFIED_NODE_CF_NAME, DAG_VOTE_CF_NAME, NODE_CF_NAME,
-    ORDERED_ANCHOR_ID_CF_NAME, QC_CF_NAME, SINGLE_ENTRY_CF_NAME,
+    BLOCK_CF_NAME, CERTIFIED_NODE_CF_NAME, CERTIFIED_NODE_ROUND_INDEX_CF_NAME,
+    DAG_METADATA_CF_NAME, DAG_VOTE_CF_NAME, NODE_CF_NAME, ORDERED_ANCHOR_ID_CF_NAME, QC_CF_NAME,
+    SINGLE_ENTRY_CF_NAME,
 };
 use std::{iter::Iterator, path::Path, time::Instant};
 
 /// The name of the consensus db file
 pub const CONSENSUS_DB_NAME: &str = "consensus_db";
 
+/// Version of the layout of the DAG column families, bump it with a migration step in
+/// `ConsensusDB::migrate_dag_schema` whenever the stored DAG data changes incompatibly.
+/// 0: DAG data written before the schema got versioned
+/// 1: versioned DAG data
+/// 2: nodes carry weak links, which are part of their digest
+/// 3: certified nodes indexed by epoch and round
+pub const DAG_SCHEMA_VERSION: u64 = 3;
+
 /// Creates new physical DB checkpoint in directory specified by `checkpoint_path`.
 pub fn create_checkpoint<P: AsRef<Path> + Clone>(db_path: P, checkpoint_path: P) -> Result<()> {
     let start = Instant::now();
@@ -60,6 +72,8 @@ impl ConsensusDB {
             CERTIFIED_NODE_CF_NAME,
             DAG_VOTE_CF_NAME,
             ORDERED_ANCHOR_ID_CF_NAME,
+            DAG_METADATA_CF_NAME,
+            CERTIFIED_NODE_ROUND_INDEX_CF_NAME,
         ];
 
         let path = db_root_path.as_ref().join(CONSENSUS_DB_NAME);
@@ -76,7 +90,162 @@ impl ConsensusDB {
             instant.elapsed().as_millis()
         );
 
-        Self { db }
+        let consensus_db = Self { db };
+        consensus_db
+            .migrate_dag_schema()
+            .expect("ConsensusDB DAG schema migration failed; unable to continue");
+        consensus_db
+    }
+
+    pub fn get_dag_schema_version(&self) -> Result<u64, DbError> {
+        Ok(self
+            .db
+            .get::<DagMetadataSchema>(&DagMetadataKey::SchemaVersion)?
+            .unwrap_or(0))
+    }
+
+    /// Bring the DAG column families up to `DAG_SCHEMA_VERSION`, one version at a time.
+    fn migrate_dag_schema(&self) -> Result<(), DbError> {
+        let mut version = self.get_dag_schema_version()?;
+        if version > DAG_SCHEMA_VERSION {
+            return Err(anyhow::anyhow!(
+                "DAG schema version {} is newer than the supported version {}",
+                version,
+                DAG_SCHEMA_VERSION
+            )
+            .into());
+        }
+        while version < DAG_SCHEMA_VERSION {
+            let batch = SchemaBatch::new();
+            match version {
+                0 => {
+                    // The unversioned data was only ever written by the experimental DAG and
+                    // may not decode anymore, start from a clean slate.
+                    self.delete_all::<NodeSchema>(&batch)?;
+                    self.delete_all::<CertifiedNodeSchema>(&batch)?;
+                    self.delete_all::<DagVoteSchema>(&batch)?;
+                    self.delete_all::<OrderedAnchorIdSchema>(&batch)?;
+                },
+                1 => {
+                    // Nodes without weak links don't decode anymore and their digests, which
+                    // the certificates and votes sign, changed. The ordered anchor ids don't
+                    // depend on the node encoding and are kept.
+                    self.delete_all::<NodeSchema>(&batch)?;
+                    self.delete_all::<CertifiedNodeSchema>(&batch)?;
+                    self.delete_all::<DagVoteSchema>(&batch)?;
+                },
+                2 => {
+                    for (digest, node) in self.get_all_data::<CertifiedNodeSchema>()? {
+                        batch.put::<CertifiedNodeRoundIndexSchema>(
+                            &(node.epoch(), node.round(), digest),
+                            &(),
+                        )?;
+                    }
+                },
+                _ => unreachable!("no migration from DAG schema version {}", version),
+            }
+            version += 1;
+            batch.put::<DagMetadataSchema>(&DagMetadataKey::SchemaVersion, &version)?;
+            self.commit(batch)?;
+            info!("Migrated ConsensusDB DAG schema to version {}", version);
+        }
+        Ok(())
+    }
+
+    /// Drop the DAG data of the epochs before the given one, and of the given epoch below
+    /// `min_round`.
+    pub fn prune_dag_data(&self, epoch: u64, min_round: Round) -> Result<(), DbError> {
+        let is_expired = |node_epoch: u64, round: Round| {
+            node_epoch < epoch || (node_epoch == epoch && round < min_round)
+        };
+        let batch = SchemaBatch::new();
+        for (digest, node) in self.get_all_data::<NodeSchema>()? {
+            if is_expired(node.epoch(), node.round()) {
+                batch.delete::<NodeSchema>(&digest)?;
+            }
+        }
+        // the index is ordered by epoch and round, only the expired prefix needs to be read
+        let mut iter = self
+            .db
+            .iter::<CertifiedNodeRoundIndexSchema>(ReadOptions::default())?;
+        iter.seek_to_first();
+        for item in iter {
+            let (key, _) = item?;
+            let (node_epoch, round, digest) = key;
+            if !is_expired(node_epoch, round) {
+                break;
+            }
+            batch.delete::<CertifiedNodeSchema>(&digest)?;
+            batch.delete::<CertifiedNodeRoundIndexSchema>(&key)?;
+        }
+        for (node_id, _) in self.get_all_data::<DagVoteSchema>()? {
+            if is_expired(node_id.epoch(), node_id.round()) {
+                batch.delete::<DagVoteSchema>(&node_id)?;
+            }
+        }
+        for (node_id, _) in self.get_all_data::<OrderedAnchorIdSchema>()? {
+            if is_expired(node_id.epoch(), node_id.round()) {
+                batch.delete::<OrderedAnchorIdSchema>(&node_id)?;
+            }
+        }
+        self.commit(batch)
+    }
+
+    pub fn save_certified_node(&self, node: &CertifiedNode) -> Result<(), DbError> {
+        let batch = SchemaBatch::new();
+        batch.put::<CertifiedNodeSchema>(&node.digest(), node)?;
+        batch.put::<CertifiedNodeRoundIndexSchema>(
+            &(node.epoch(), node.round(), node.digest()),
+            &(),
+        )?;
+        self.commit(batch)
+    }
+
+    pub fn delete_certified_nodes(&self, digests: Vec<HashValue>) -> Result<(), DbError> {
+        let batch = SchemaBatch::new();
+        for digest in digests {
+            if let Some(node) = self.db.get::<CertifiedNodeSchema>(&digest)? {
+                batch.delete::<CertifiedNodeRoundIndexSchema>(&(
+                    node.epoch(),
+                    node.round(),
+                    digest,
+                ))?;
+            }
+            batch.delete::<CertifiedNodeSchema>(&digest)?;
+        }
+        self.commit(batch)
+    }
+
+    /// The certified nodes of the epoch from `start_round` to `end_round` included, in round
+    /// order.
+    pub fn get_certified_nodes_in_range(
+        &self,
+        epoch: u64,
+        start_round: Round,
+        end_round: Round,
+    ) -> Result<Vec<CertifiedNode>, DbError> {
+        let mut iter = self
+            .db
+            .iter::<CertifiedNodeRoundIndexSchema>(ReadOptions::default())?;
+        iter.seek(&(epoch, start_round))?;
+        let mut nodes = vec![];
+        for item in iter {
+            let ((node_epoch, round, digest), _) = item?;
+            if node_epoch != epoch || round > end_round {
+                break;
+            }
+            if let Some(node) = self.db.get::<CertifiedNodeSchema>(&digest)? {
+                nodes.push(node);
+            }
+        }
+        Ok(nodes)
+    }
+
+    fn delete_all<S: Schema>(&self, batch: &SchemaBatch) -> Result<(), DbError> {
+        for (key, _) in self.get_all_data::<S>()? {
+            batch.delete::<S>(&key)?;
+        }
+        Ok(())
     }
 
     pub fn get_data(
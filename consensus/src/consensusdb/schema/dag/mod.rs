@@ -8,18 +8,35 @@
 //! |<---key---->|<---value--->|
 //! |   digest   |   node/certified node    |
 //! ```
+//!
+//! The certified nodes are indexed by epoch and round, for the round range fetches.
+//! ```text
+//! |<--------------key-------------->|<-value->|
+//! | epoch (BE) | round (BE) | digest |   ()    |
+//! ```
+//!
+//! Metadata about the stored DAG data, like the schema version, is kept in its own column
+//! family under a single-byte key.
+//! ```text
+//! |<-------key------->|<--value-->|
+//! | dag metadata key  |  u64 (BE) |
+//! ```
 
 use crate::{
     consensusdb::schema::ensure_slice_len_eq,
     dag::{CertifiedNode, Node, NodeId, Vote},
     define_schema,
 };
-use anyhow::Result;
+use anyhow::{format_err, Result};
+use aptos_consensus_types::common::Round;
 use aptos_crypto::HashValue;
 use aptos_schemadb::{
-    schema::{KeyCodec, ValueCodec},
+    schema::{KeyCodec, SeekKeyCodec, ValueCodec},
     ColumnFamilyName,
 };
+use byteorder::{BigEndian, ReadBytesExt};
+use num_derive::{FromPrimitive, ToPrimitive};
+use num_traits::{FromPrimitive, ToPrimitive};
 use std::mem::size_of;
 
 pub const NODE_CF_NAME: ColumnFamilyName = "node";
@@ -99,6 +116,54 @@ impl ValueCodec<CertifiedNodeSchema> for CertifiedNode {
     }
 }
 
+pub const CERTIFIED_NODE_ROUND_INDEX_CF_NAME: ColumnFamilyName = "certified_node_round_index";
+
+define_schema!(
+    CertifiedNodeRoundIndexSchema,
+    (u64, Round, HashValue),
+    (),
+    CERTIFIED_NODE_ROUND_INDEX_CF_NAME
+);
+
+impl KeyCodec<CertifiedNodeRoundIndexSchema> for (u64, Round, HashValue) {
+    fn encode_key(&self) -> Result<Vec<u8>> {
+        let (epoch, round, digest) = self;
+        let mut encoded =
+            SeekKeyCodec::<CertifiedNodeRoundIndexSchema>::encode_seek_key(&(*epoch, *round))?;
+        encoded.extend_from_slice(digest.as_ref());
+        Ok(encoded)
+    }
+
+    fn decode_key(mut data: &[u8]) -> Result<Self> {
+        ensure_slice_len_eq(data, 2 * size_of::<u64>() + HashValue::LENGTH)?;
+        let epoch = data.read_u64::<BigEndian>()?;
+        let round = data.read_u64::<BigEndian>()?;
+        let digest = HashValue::from_slice(data)?;
+        Ok((epoch, round, digest))
+    }
+}
+
+/// Seeks to the first certified node of the (epoch, round).
+impl SeekKeyCodec<CertifiedNodeRoundIndexSchema> for (u64, Round) {
+    fn encode_seek_key(&self) -> Result<Vec<u8>> {
+        let (epoch, round) = self;
+        let mut encoded = epoch.to_be_bytes().to_vec();
+        encoded.extend_from_slice(&round.to_be_bytes());
+        Ok(encoded)
+    }
+}
+
+impl ValueCodec<CertifiedNodeRoundIndexSchema> for () {
+    fn encode_value(&self) -> Result<Vec<u8>> {
+        Ok(vec![])
+    }
+
+    fn decode_value(data: &[u8]) -> Result<Self> {
+        ensure_slice_len_eq(data, size_of::<Self>())?;
+        Ok(())
+    }
+}
+
 pub const ORDERED_ANCHOR_ID_CF_NAME: ColumnFamilyName = "ordered_anchor_id";
 
 define_schema!(OrderedAnchorIdSchema, NodeId, (), ORDERED_ANCHOR_ID_CF_NAME);
@@ -123,3 +188,42 @@ impl ValueCodec<OrderedAnchorIdSchema> for () {
         Ok(())
     }
 }
+
+pub const DAG_METADATA_CF_NAME: ColumnFamilyName = "dag_metadata";
+
+define_schema!(DagMetadataSchema, DagMetadataKey, u64, DAG_METADATA_CF_NAME);
+
+#[derive(Debug, Eq, PartialEq, FromPrimitive, ToPrimitive)]
+#[repr(u8)]
+pub enum DagMetadataKey {
+    // Version of the layout of the DAG column families
+    SchemaVersion = 0,
+}
+
+impl KeyCodec<DagMetadataSchema> for DagMetadataKey {
+    fn encode_key(&self) -> Result<Vec<u8>> {
+        Ok(vec![self
+            .to_u8()
+            .ok_or_else(|| format_err!("ToPrimitive failed."))?])
+    }
+
+    fn decode_key(mut data: &[u8]) -> Result<Self> {
+        ensure_slice_len_eq(data, size_of::<u8>())?;
+        let key = data.read_u8()?;
+        DagMetadataKey::from_u8(key).ok_or_else(|| format_err!("FromPrimitive failed."))
+    }
+}
+
+impl ValueCodec<DagMetadataSchema> for u64 {
+    fn encode_value(&self) -> Result<Vec<u8>> {
+        Ok(self.to_be_bytes().to_vec())
+    }
+
+    fn decode_value(mut data: &[u8]) -> Result<Self> {
+        ensure_slice_len_eq(data, size_of::<Self>())?;
+        Ok(data.read_u64::<BigEndian>()?)
+    }
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,18 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use aptos_schemadb::{schema::fuzzing::assert_encode_decode, test_no_panic_decoding};
+
+#[test]
+fn test_certified_node_round_index_schema() {
+    assert_encode_decode::<CertifiedNodeRoundIndexSchema>(&(1, 2, HashValue::random()), &());
+}
+
+#[test]
+fn test_dag_metadata_schema() {
+    assert_encode_decode::<DagMetadataSchema>(&DagMetadataKey::SchemaVersion, &1);
+}
+
+test_no_panic_decoding!(CertifiedNodeRoundIndexSchema);
+test_no_panic_decoding!(DagMetadataSchema);
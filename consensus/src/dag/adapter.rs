@@ -0,0 +1,240 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    dag::{
+        counters::OUTSTANDING_ORDERED_BLOCKS, health::OrderingProgress, storage::DAGStorage,
+        CertifiedNode,
+    },
+    experimental::buffer_manager::OrderedBlocks,
+};
+use anyhow::{anyhow, ensure};
+use aptos_consensus_types::{
+    block::Block,
+    block_data::BlockType,
+    common::{Payload, Round},
+    executed_block::ExecutedBlock,
+};
+use aptos_crypto::{hash::ACCUMULATOR_PLACEHOLDER_HASH, HashValue};
+use aptos_executor_types::StateComputeResult;
+use aptos_infallible::Mutex;
+use aptos_logger::{debug, error, info};
+use aptos_types::{
+    aggregate_signature::AggregateSignature,
+    block_info::BlockInfo,
+    epoch_state::EpochState,
+    ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
+};
+use futures::{channel::mpsc::UnboundedSender, StreamExt};
+use futures_channel::{mpsc::Receiver, oneshot};
+use std::{collections::VecDeque, sync::Arc};
+
+/// The block id of a DAG block covers its parent block info, which is only known as ordered
+/// while the DAG keeps ordering ahead of execution. A validator that restarts from its committed
+/// ledger info must derive the same ids as the ones that never stopped, so the parent is always
+/// taken in its ordered-only form, otherwise the commit votes of the validators wouldn't match.
+fn ordered_only_block_info(block_info: &BlockInfo) -> BlockInfo {
+    BlockInfo::new(
+        block_info.epoch(),
+        block_info.round(),
+        block_info.id(),
+        *ACCUMULATOR_PLACEHOLDER_HASH,
+        0,
+        block_info.timestamp_usecs(),
+        None,
+    )
+}
+
+/// An ordered block sent to execution that's not committed yet.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OutstandingBlock {
+    pub round: Round,
+    pub block_id: HashValue,
+    pub parent_id: HashValue,
+}
+
+/// Turns the nodes ordered by the DAG into blocks and feeds them to the execution pipeline,
+/// one block per ordered anchor. Ordering doesn't wait for execution, each block is chained to
+/// the previously ordered one and stays outstanding until its commit comes back. Once the commit proof of a block ending the epoch comes back,
+/// no more blocks are sent for the epoch.
+pub struct NotifierAdapter {
+    epoch_state: Arc<EpochState>,
+    executor_channel: UnboundedSender<OrderedBlocks>,
+    commit_tx: tokio::sync::mpsc::UnboundedSender<Round>,
+    storage: Arc<dyn DAGStorage>,
+    ordering_progress: Arc<OrderingProgress>,
+    parent_block_info: BlockInfo,
+    outstanding_blocks: Arc<Mutex<VecDeque<OutstandingBlock>>>,
+    epoch_ending_ledger_info: Arc<Mutex<Option<LedgerInfoWithSignatures>>>,
+}
+
+impl NotifierAdapter {
+    pub fn new(
+        epoch_state: Arc<EpochState>,
+        latest_ledger_info: LedgerInfo,
+        executor_channel: UnboundedSender<OrderedBlocks>,
+        commit_tx: tokio::sync::mpsc::UnboundedSender<Round>,
+        storage: Arc<dyn DAGStorage>,
+        ordering_progress: Arc<OrderingProgress>,
+    ) -> Self {
+        Self {
+            epoch_state,
+            executor_channel,
+            commit_tx,
+            storage,
+            ordering_progress,
+            parent_block_info: ordered_only_block_info(latest_ledger_info.commit_info()),
+            outstanding_blocks: Arc::new(Mutex::new(VecDeque::new())),
+            epoch_ending_ledger_info: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// The ordered blocks still executing, from the oldest to the latest one.
+    pub fn outstanding_blocks(&self) -> Vec<OutstandingBlock> {
+        self.outstanding_blocks.lock().iter().cloned().collect()
+    }
+
+    /// The aggregated commit proof of the block ending the epoch, if it's committed already.
+    pub fn epoch_ending_ledger_info(&self) -> Option<LedgerInfoWithSignatures> {
+        self.epoch_ending_ledger_info.lock().clone()
+    }
+
+    pub async fn start(
+        mut self,
+        mut ordered_nodes_rx: Receiver<Vec<Arc<CertifiedNode>>>,
+        mut shutdown_rx: oneshot::Receiver<oneshot::Sender<()>>,
+    ) {
+        info!(epoch = self.epoch_state.epoch, "NotifierAdapter starts.");
+        loop {
+            tokio::select! {
+                biased;
+                maybe_ack_tx = &mut shutdown_rx => {
+                    if let Ok(ack_tx) = maybe_ack_tx {
+                        let _ = ack_tx.send(());
+                    }
+                    break;
+                },
+                Some(ordered_nodes) = ordered_nodes_rx.next() => {
+                    if let Err(e) = self.send_ordered_nodes(ordered_nodes) {
+                        error!("Failed to send ordered nodes to execution {:?}", e);
+                        break;
+                    }
+                },
+                else => break,
+            }
+        }
+        info!(epoch = self.epoch_state.epoch, "NotifierAdapter stops.");
+    }
+
+    /// The ordered nodes end with their anchor, the block takes its round, author and timestamp
+    /// from the anchor and the payloads of all the nodes in order.
+    pub fn send_ordered_nodes(
+        &mut self,
+        ordered_nodes: Vec<Arc<CertifiedNode>>,
+    ) -> anyhow::Result<()> {
+        if let Some(ledger_info) = self.epoch_ending_ledger_info.lock().as_ref() {
+            debug!(
+                "Epoch {} ended at {}, dropping ordered nodes",
+                self.epoch_state.epoch,
+                ledger_info.commit_info()
+            );
+            return Ok(());
+        }
+        let anchor = ordered_nodes
+            .last()
+            .ok_or_else(|| anyhow!("ordered nodes must not be empty"))?
+            .clone();
+        let payload = ordered_nodes
+            .iter()
+            .map(|node| node.payload().clone())
+            .reduce(Payload::extend)
+            .expect("ordered nodes are not empty");
+        let node_digests = ordered_nodes.iter().map(|node| node.digest()).collect();
+        // TODO: pass the failed anchors from the order rule for leader reputation
+        let block = ExecutedBlock::new(
+            Block::new_for_dag(
+                self.epoch_state.epoch,
+                anchor.round(),
+                anchor.timestamp(),
+                payload,
+                *anchor.author(),
+                vec![],
+                self.parent_block_info.clone(),
+                node_digests,
+            ),
+            StateComputeResult::new_dummy(),
+        );
+        let block_info = block.block_info();
+        {
+            let mut outstanding_blocks = self.outstanding_blocks.lock();
+            if let Some(last) = outstanding_blocks.back() {
+                ensure!(
+                    last.block_id == block.parent_id(),
+                    "Ordered block {} doesn't extend the last outstanding block {}",
+                    block.id(),
+                    last.block_id
+                );
+            }
+            outstanding_blocks.push_back(OutstandingBlock {
+                round: block.round(),
+                block_id: block.id(),
+                parent_id: block.parent_id(),
+            });
+            OUTSTANDING_ORDERED_BLOCKS.set(outstanding_blocks.len() as i64);
+        }
+        self.parent_block_info = block_info.clone();
+        self.ordering_progress.update(anchor.round());
+
+        let storage = self.storage.clone();
+        let commit_tx = self.commit_tx.clone();
+        let epoch_ending_ledger_info = self.epoch_ending_ledger_info.clone();
+        let outstanding_blocks = self.outstanding_blocks.clone();
+        self.executor_channel
+            .unbounded_send(OrderedBlocks {
+                ordered_blocks: vec![block],
+                ordered_proof: LedgerInfoWithSignatures::new(
+                    LedgerInfo::new(block_info, anchor.digest()),
+                    AggregateSignature::empty(),
+                ),
+                callback: Box::new(
+                    move |committed_blocks: &[Arc<ExecutedBlock>],
+                          commit_decision: LedgerInfoWithSignatures| {
+                        for block in committed_blocks {
+                            if let BlockType::DAGBlock { node_digests, .. } =
+                                block.block().block_data().block_type()
+                            {
+                                if let Err(e) = storage.delete_certified_nodes(node_digests.clone())
+                                {
+                                    error!("Failed to delete committed nodes {:?}", e);
+                                }
+                                // our own nodes among them are kept until they're committed
+                                if let Err(e) = storage.delete_nodes(node_digests.clone()) {
+                                    error!("Failed to delete committed own nodes {:?}", e);
+                                }
+                            }
+                            if let Err(e) = commit_tx.send(block.round()) {
+                                error!(
+                                    "Failed to notify commit of round {} {:?}",
+                                    block.round(),
+                                    e
+                                );
+                            }
+                        }
+                        if let Some(last_committed) = committed_blocks.last() {
+                            let mut outstanding_blocks = outstanding_blocks.lock();
+                            outstanding_blocks.retain(|block| block.round > last_committed.round());
+                            OUTSTANDING_ORDERED_BLOCKS.set(outstanding_blocks.len() as i64);
+                        }
+                        if commit_decision.ledger_info().ends_epoch() {
+                            info!(
+                                "Epoch ending ledger info committed {}",
+                                commit_decision.commit_info()
+                            );
+                            *epoch_ending_ledger_info.lock() = Some(commit_decision);
+                        }
+                    },
+                ),
+            })
+            .map_err(|e| anyhow!("unable to send ordered blocks {}", e))
+    }
+}
@@ -1,28 +1,110 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
+use aptos_config::config::DagAnchorFrequency;
 use aptos_consensus_types::common::{Author, Round};
+use aptos_infallible::Mutex;
+use std::collections::BTreeMap;
 
-pub trait AnchorElection {
+/// How often the DAG has an anchor, every other round gives anchors a full round of votes
+/// while every round lowers the ordering latency.
+/// Note: EveryRound is only for tests, the config sanitizer rejects it otherwise, as the order
+/// rule only guarantees that an anchor ordered directly is reachable from all later anchors
+/// when they're at least two rounds apart.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AnchorFrequency {
+    EveryRound,
+    EveryOtherRound,
+}
+
+impl AnchorFrequency {
+    /// Number of rounds between two consecutive anchors
+    pub fn stride(&self) -> Round {
+        match self {
+            AnchorFrequency::EveryRound => 1,
+            AnchorFrequency::EveryOtherRound => 2,
+        }
+    }
+}
+
+impl From<DagAnchorFrequency> for AnchorFrequency {
+    fn from(frequency: DagAnchorFrequency) -> Self {
+        match frequency {
+            DagAnchorFrequency::EveryRound => AnchorFrequency::EveryRound,
+            DagAnchorFrequency::EveryOtherRound => AnchorFrequency::EveryOtherRound,
+        }
+    }
+}
+
+pub trait AnchorElection: Send + Sync {
     fn get_anchor(&self, round: Round) -> Author;
 
+    fn anchor_frequency(&self) -> AnchorFrequency;
+
     fn commit(&mut self, round: Round);
 }
 
 pub struct RoundRobinAnchorElection {
     validators: Vec<Author>,
+    frequency: AnchorFrequency,
 }
 
 impl RoundRobinAnchorElection {
-    pub fn new(validators: Vec<Author>) -> Self {
-        Self { validators }
+    pub fn new(validators: Vec<Author>, frequency: AnchorFrequency) -> Self {
+        Self {
+            validators,
+            frequency,
+        }
     }
 }
 
 impl AnchorElection for RoundRobinAnchorElection {
     fn get_anchor(&self, round: Round) -> Author {
-        self.validators[(round / 2) as usize % self.validators.len()]
+        self.validators[(round / self.frequency.stride()) as usize % self.validators.len()]
+    }
+
+    fn anchor_frequency(&self) -> AnchorFrequency {
+        self.frequency
     }
 
     fn commit(&mut self, _round: Round) {}
 }
+
+// Wrapper around AnchorElection.
+//
+// The order rule asks for the anchor of the same round many times while walking the DAG,
+// and reputation based elections can be expensive, so we make sure the anchor is computed
+// only once for a given round. The wrapper lives for a single epoch, and the cache is
+// reset on every commit since the commit history may change the anchors of later rounds.
+pub struct CachedAnchorElection {
+    anchor_election: Box<dyn AnchorElection>,
+    recent_anchors: Mutex<BTreeMap<Round, Author>>,
+}
+
+impl CachedAnchorElection {
+    pub fn new(anchor_election: Box<dyn AnchorElection>) -> Self {
+        Self {
+            anchor_election,
+            recent_anchors: Mutex::new(BTreeMap::new()),
+        }
+    }
+}
+
+impl AnchorElection for CachedAnchorElection {
+    fn get_anchor(&self, round: Round) -> Author {
+        *self
+            .recent_anchors
+            .lock()
+            .entry(round)
+            .or_insert_with(|| self.anchor_election.get_anchor(round))
+    }
+
+    fn anchor_frequency(&self) -> AnchorFrequency {
+        self.anchor_election.anchor_frequency()
+    }
+
+    fn commit(&mut self, round: Round) {
+        self.anchor_election.commit(round);
+        self.recent_anchors.lock().clear();
+    }
+}
@@ -0,0 +1,74 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use super::counters;
+use aptos_consensus_types::common::{Author, Round};
+use aptos_infallible::Mutex;
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// Number of anchor rounds kept in the history
+const ANCHOR_VOTE_HISTORY_SIZE: usize = 1000;
+
+/// The last observation of the votes for an anchor, taken every time the order rule checks
+/// whether the anchor can be ordered.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct AnchorVoteRecord {
+    pub epoch: u64,
+    pub round: Round,
+    pub anchor: Author,
+    /// Whether the anchor node was in the local DAG at all
+    pub present: bool,
+    pub voting_power_for: u128,
+    pub voting_power_against: u128,
+    /// Voting power an anchor needs to be ordered directly, i.e. f+1
+    pub threshold_voting_power: u128,
+    pub met_threshold: bool,
+}
+
+static ANCHOR_VOTE_HISTORY: Lazy<AnchorVoteHistory> =
+    Lazy::new(|| AnchorVoteHistory::new(ANCHOR_VOTE_HISTORY_SIZE));
+
+/// Anchor vote records of the latest anchor rounds, the oldest round is evicted once full.
+pub struct AnchorVoteHistory {
+    capacity: usize,
+    records: Mutex<BTreeMap<(u64, Round), AnchorVoteRecord>>,
+}
+
+impl AnchorVoteHistory {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            records: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    pub fn record(&self, record: AnchorVoteRecord) {
+        let mut records = self.records.lock();
+        records.insert((record.epoch, record.round), record);
+        while records.len() > self.capacity {
+            records.pop_first();
+        }
+    }
+
+    pub fn records(&self) -> Vec<AnchorVoteRecord> {
+        self.records.lock().values().cloned().collect()
+    }
+}
+
+/// Export the observation as metrics and keep it in the global history.
+pub fn observe_anchor_votes(record: AnchorVoteRecord) {
+    counters::ANCHOR_VOTING_POWER
+        .with_label_values(&["for"])
+        .set(record.voting_power_for as i64);
+    counters::ANCHOR_VOTING_POWER
+        .with_label_values(&["against"])
+        .set(record.voting_power_against as i64);
+    ANCHOR_VOTE_HISTORY.record(record);
+}
+
+/// The anchor vote records of the latest anchor rounds, in ascending (epoch, round) order.
+pub fn anchor_vote_history() -> Vec<AnchorVoteRecord> {
+    ANCHOR_VOTE_HISTORY.records()
+}
@@ -0,0 +1,155 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use super::{
+    adapter::NotifierAdapter,
+    anchor_election::{CachedAnchorElection, RoundRobinAnchorElection},
+    dag_driver::DagDriver,
+    dag_handler::NetworkHandler,
+    dag_store::Dag,
+    health::{DagHealthMonitor, OrderingProgress},
+    order_rule::OrderRule,
+    storage::DAGStorage,
+    types::DAGMessage,
+    validation::NodeValidator,
+};
+use crate::{
+    experimental::buffer_manager::OrderedBlocks, network::IncomingDAGRequest,
+    state_replication::PayloadClient, util::time_service::TimeService,
+};
+use aptos_channels::aptos_channel;
+use aptos_config::config::DagConsensusConfig;
+use aptos_consensus_types::common::Author;
+use aptos_infallible::RwLock;
+use aptos_logger::error;
+use aptos_reliable_broadcast::{RBNetworkSender, ReliableBroadcast};
+use aptos_types::{
+    epoch_state::EpochState, ledger_info::LedgerInfo, validator_signer::ValidatorSigner,
+};
+use futures::{
+    channel::mpsc::UnboundedSender,
+    future::{AbortHandle, Abortable},
+};
+use futures_channel::{mpsc, oneshot};
+use std::{sync::Arc, time::Duration};
+use tokio_retry::strategy::ExponentialBackoff;
+
+/// Wire up the DAG components for the epoch and spawn them, the returned sender stops all of
+/// them and acks once the order rule, the notifier and the health monitor have exited.
+pub fn bootstrap_dag(
+    self_peer: Author,
+    signer: ValidatorSigner,
+    epoch_state: Arc<EpochState>,
+    latest_ledger_info: LedgerInfo,
+    storage: Arc<dyn DAGStorage>,
+    rb_network_sender: Arc<dyn RBNetworkSender<DAGMessage>>,
+    time_service: Arc<dyn TimeService>,
+    payload_client: Arc<dyn PayloadClient>,
+    quorum_store_enabled: bool,
+    config: &DagConsensusConfig,
+    dag_rpc_rx: aptos_channel::Receiver<Author, IncomingDAGRequest>,
+    executor_channel: UnboundedSender<OrderedBlocks>,
+) -> oneshot::Sender<oneshot::Sender<()>> {
+    let validators = epoch_state.verifier.get_ordered_account_addresses();
+    let (ordered_nodes_tx, ordered_nodes_rx) = mpsc::channel(config.ordered_nodes_channel_size);
+    let (certified_node_tx, certified_node_rx) =
+        tokio::sync::mpsc::channel(config.rpc_channel_size);
+    // Commits are bounded by the outstanding ordered blocks already, and the commit callback
+    // can't wait, so none of them gets dropped
+    let (commit_tx, commit_rx) = tokio::sync::mpsc::unbounded_channel();
+
+    if let Err(e) = storage.prune(epoch_state.epoch, 0) {
+        error!("Failed to prune the DAG data of previous epochs {:?}", e);
+    }
+    let dag = Arc::new(RwLock::new(Dag::new(epoch_state.clone(), storage.clone())));
+
+    let anchor_election = Box::new(CachedAnchorElection::new(Box::new(
+        RoundRobinAnchorElection::new(validators.clone(), config.anchor_frequency.into()),
+    )));
+    let order_rule = OrderRule::new(
+        epoch_state.clone(),
+        latest_ledger_info.clone(),
+        dag.clone(),
+        anchor_election,
+        ordered_nodes_tx,
+    );
+    let ordering_progress = Arc::new(OrderingProgress::default());
+    let notifier = NotifierAdapter::new(
+        epoch_state.clone(),
+        latest_ledger_info,
+        executor_channel,
+        commit_tx,
+        storage.clone(),
+        ordering_progress.clone(),
+    );
+    let health_monitor = config.stall_threshold_ms.map(|threshold_ms| {
+        DagHealthMonitor::new(
+            epoch_state.clone(),
+            dag.clone(),
+            ordering_progress,
+            time_service.clone(),
+            Duration::from_millis(threshold_ms),
+        )
+    });
+
+    let reliable_broadcast = Arc::new(ReliableBroadcast::new(
+        validators,
+        rb_network_sender,
+        ExponentialBackoff::from_millis(10).max_delay(Duration::from_secs(1)),
+        aptos_time_service::TimeService::real(),
+    ));
+    let dag_driver = DagDriver::new(
+        self_peer,
+        epoch_state.clone(),
+        dag.clone(),
+        payload_client,
+        reliable_broadcast,
+        time_service,
+        storage.clone(),
+        certified_node_tx,
+        quorum_store_enabled,
+    );
+    let node_validator = NodeValidator::from_config(config, epoch_state.verifier.len());
+    let handler = NetworkHandler::new(
+        dag,
+        dag_rpc_rx,
+        signer,
+        epoch_state,
+        storage,
+        dag_driver,
+        node_validator,
+        config.max_fetch_response_bytes,
+        quorum_store_enabled,
+    );
+
+    let (order_rule_shutdown_tx, order_rule_shutdown_rx) = oneshot::channel();
+    let (notifier_shutdown_tx, notifier_shutdown_rx) = oneshot::channel();
+    let (handler_abort_handle, handler_abort_registration) = AbortHandle::new_pair();
+    tokio::spawn(order_rule.start(certified_node_rx, commit_rx, order_rule_shutdown_rx));
+    tokio::spawn(notifier.start(ordered_nodes_rx, notifier_shutdown_rx));
+    tokio::spawn(Abortable::new(handler.start(), handler_abort_registration));
+    let mut shutdown_txs = vec![order_rule_shutdown_tx, notifier_shutdown_tx];
+    if let Some(health_monitor) = health_monitor {
+        let (monitor_shutdown_tx, monitor_shutdown_rx) = oneshot::channel();
+        tokio::spawn(health_monitor.start(
+            Duration::from_millis(config.health_check_interval_ms),
+            monitor_shutdown_rx,
+        ));
+        shutdown_txs.push(monitor_shutdown_tx);
+    }
+
+    let (shutdown_tx, shutdown_rx) = oneshot::channel::<oneshot::Sender<()>>();
+    tokio::spawn(async move {
+        if let Ok(ack_tx) = shutdown_rx.await {
+            handler_abort_handle.abort();
+            for shutdown_tx in shutdown_txs {
+                let (tx, rx) = oneshot::channel();
+                if shutdown_tx.send(tx).is_ok() {
+                    let _ = rx.await;
+                }
+            }
+            let _ = ack_tx.send(());
+        }
+    });
+    shutdown_tx
+}
@@ -0,0 +1,39 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    experimental::signing_phase::CommitSignerProvider, metrics_safety_rules::MetricsSafetyRules,
+};
+use aptos_crypto::bls12381;
+use aptos_infallible::Mutex;
+use aptos_safety_rules::{Error, TSafetyRules};
+use aptos_types::ledger_info::{LedgerInfo, LedgerInfoWithSignatures};
+
+/// The blocks ordered by the DAG don't carry a quorum certificate, the ordering is already
+/// agreed on by the DAG itself. Commit votes still go through safety rules, which check that the
+/// commit ledger info matches the ordered one, but don't expect the ordered one to be signed.
+/// That's what makes the aggregated commit proof of the epoch-ending block usable by state sync
+/// and the next epoch.
+pub struct DagCommitSigner {
+    safety_rules: Mutex<MetricsSafetyRules>,
+}
+
+impl DagCommitSigner {
+    pub fn new(safety_rules: MetricsSafetyRules) -> Self {
+        Self {
+            safety_rules: Mutex::new(safety_rules),
+        }
+    }
+}
+
+impl CommitSignerProvider for DagCommitSigner {
+    fn sign_commit_vote(
+        &self,
+        ledger_info: LedgerInfoWithSignatures,
+        new_ledger_info: LedgerInfo,
+    ) -> Result<bls12381::Signature, Error> {
+        self.safety_rules
+            .lock()
+            .sign_dag_commit_vote(ledger_info.ledger_info().clone(), new_ledger_info)
+    }
+}
@@ -0,0 +1,93 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_metrics_core::{
+    register_int_counter, register_int_counter_vec, register_int_gauge, register_int_gauge_vec,
+    IntCounter, IntCounterVec, IntGauge, IntGaugeVec,
+};
+use once_cell::sync::Lazy;
+
+/// Count of the times the order rule paused because the ordered nodes channel was full
+pub static ORDERING_BACKPRESSURE_COUNT: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "aptos_consensus_dag_ordering_backpressure_count",
+        "Count of the times the order rule paused because the ordered nodes channel was full"
+    )
+    .unwrap()
+});
+
+/// Number of ordered batches waiting for room in the ordered nodes channel
+pub static PENDING_ORDERED_BATCHES: Lazy<IntGauge> = Lazy::new(|| {
+    register_int_gauge!(
+        "aptos_consensus_dag_pending_ordered_batches",
+        "Number of ordered batches waiting for room in the ordered nodes channel"
+    )
+    .unwrap()
+});
+
+/// Number of ordered blocks sent to execution and not committed yet
+pub static OUTSTANDING_ORDERED_BLOCKS: Lazy<IntGauge> = Lazy::new(|| {
+    register_int_gauge!(
+        "aptos_consensus_dag_outstanding_ordered_blocks",
+        "Number of ordered blocks sent to execution and not committed yet"
+    )
+    .unwrap()
+});
+
+/// Time since the last anchor was ordered, as observed by the health monitor
+pub static ORDERING_STALL_DURATION: Lazy<IntGauge> = Lazy::new(|| {
+    register_int_gauge!(
+        "aptos_consensus_dag_ordering_stall_duration_ms",
+        "Time since the last anchor was ordered, as observed by the health monitor"
+    )
+    .unwrap()
+});
+
+/// Count of the times the health monitor found the DAG ordering stalled
+pub static DAG_STALL_COUNT: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "aptos_consensus_dag_stall_count",
+        "Count of the times the health monitor found the DAG ordering stalled"
+    )
+    .unwrap()
+});
+
+/// Voting power for and against the last anchor checked by the order rule
+pub static ANCHOR_VOTING_POWER: Lazy<IntGaugeVec> = Lazy::new(|| {
+    register_int_gauge_vec!(
+        "aptos_consensus_dag_anchor_voting_power",
+        "Voting power for and against the last anchor checked by the order rule",
+        &["vote"]
+    )
+    .unwrap()
+});
+
+/// Count of the anchors that got ordered and the ones that were skipped
+pub static ANCHOR_OUTCOME_COUNT: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_consensus_dag_anchor_outcome_count",
+        "Count of the anchors that got ordered and the ones that were skipped",
+        &["outcome"]
+    )
+    .unwrap()
+});
+
+/// Count of the nodes rejected by the node validator, by reason
+pub static NODE_VALIDATION_REJECTION_COUNT: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_consensus_dag_node_validation_rejection_count",
+        "Count of the nodes rejected by the node validator, by reason",
+        &["reason"]
+    )
+    .unwrap()
+});
+
+/// Count of the nodes from the network that failed verification, by error class
+pub static NODE_VERIFICATION_FAILURE_COUNT: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_consensus_dag_node_verification_failure_count",
+        "Count of the nodes from the network that failed verification, by error class",
+        &["class"]
+    )
+    .unwrap()
+});
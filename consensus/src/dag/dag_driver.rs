@@ -1,7 +1,12 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use super::{storage::DAGStorage, types::DAGMessage};
+use super::{
+    dag_network::RpcHandler,
+    reliable_broadcast::CertifiedNodeHandleError,
+    storage::DAGStorage,
+    types::{CertifiedAck, DAGMessage},
+};
 use crate::{
     dag::{
         dag_store::Dag,
@@ -10,17 +15,66 @@ use crate::{
     state_replication::PayloadClient,
     util::time_service::TimeService,
 };
-use aptos_consensus_types::common::{Author, Payload};
+use anyhow::bail;
+use aptos_consensus_types::common::{Author, Payload, ProofWithData};
+use aptos_crypto::HashValue;
 use aptos_infallible::RwLock;
+use aptos_logger::{error, info};
 use aptos_reliable_broadcast::ReliableBroadcast;
-use aptos_types::{block_info::Round, epoch_state::EpochState};
+use aptos_types::{account_address::AccountAddress, block_info::Round, epoch_state::EpochState};
+use fail::fail_point;
 use futures::{
     future::{AbortHandle, Abortable},
     FutureExt,
 };
-use std::sync::Arc;
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    sync::Arc,
+};
+use tokio::sync::mpsc::Sender;
 use tokio_retry::strategy::ExponentialBackoff;
 
+/// Transactions and batches already proposed, so a recovered payload is proposed at most once.
+#[derive(Default)]
+struct ProposedPayloads {
+    txns: HashSet<(AccountAddress, u64)>,
+    batches: HashSet<HashValue>,
+}
+
+impl ProposedPayloads {
+    fn insert(&mut self, payload: &Payload) {
+        match payload {
+            Payload::DirectMempool(txns) => self
+                .txns
+                .extend(txns.iter().map(|txn| (txn.sender(), txn.sequence_number()))),
+            Payload::InQuorumStore(proof_with_data) => self.batches.extend(
+                proof_with_data
+                    .proofs
+                    .iter()
+                    .map(|proof| *proof.info().digest()),
+            ),
+        }
+    }
+
+    /// Drop what's proposed already from the payload and record the rest as proposed.
+    fn dedup(&mut self, payload: Payload) -> Payload {
+        match payload {
+            Payload::DirectMempool(txns) => Payload::DirectMempool(
+                txns.into_iter()
+                    .filter(|txn| self.txns.insert((txn.sender(), txn.sequence_number())))
+                    .collect(),
+            ),
+            Payload::InQuorumStore(proof_with_data) => Payload::InQuorumStore(ProofWithData::new(
+                proof_with_data
+                    .proofs
+                    .into_iter()
+                    .filter(|proof| self.batches.insert(*proof.info().digest()))
+                    .collect(),
+            )),
+        }
+    }
+}
+
 pub(crate) struct DagDriver {
     author: Author,
     epoch_state: Arc<EpochState>,
@@ -29,8 +83,16 @@ pub(crate) struct DagDriver {
     reliable_broadcast: Arc<ReliableBroadcast<DAGMessage, ExponentialBackoff>>,
     current_round: Round,
     time_service: Arc<dyn TimeService>,
-    rb_abort_handle: Option<AbortHandle>,
+    rb_abort_handle: Option<(AbortHandle, HashValue)>,
     storage: Arc<dyn DAGStorage>,
+    certified_node_tx: Sender<Arc<CertifiedNode>>,
+    quorum_store_enabled: bool,
+    /// Payloads of our nodes lost in a restart, re-proposed one per new node. The lost node is
+    /// deleted once its payload is persisted in the new one.
+    recovered_payloads: VecDeque<(HashValue, Payload)>,
+    /// Certified nodes received before their parents, by digest. They're in flight until the
+    /// parents are fetched, a recovered payload must not propose their transactions again.
+    nodes_missing_parents: HashMap<HashValue, (Round, Payload)>,
 }
 
 impl DagDriver {
@@ -40,35 +102,118 @@ impl DagDriver {
         dag: Arc<RwLock<Dag>>,
         payload_client: Arc<dyn PayloadClient>,
         reliable_broadcast: Arc<ReliableBroadcast<DAGMessage, ExponentialBackoff>>,
-        current_round: Round,
         time_service: Arc<dyn TimeService>,
         storage: Arc<dyn DAGStorage>,
+        certified_node_tx: Sender<Arc<CertifiedNode>>,
+        quorum_store_enabled: bool,
     ) -> Self {
-        // TODO: rebroadcast nodes after recovery
-        Self {
+        let (is_empty, highest_round) = {
+            let dag_reader = dag.read();
+            (dag_reader.is_empty(), dag_reader.highest_round())
+        };
+        let mut driver = Self {
             author,
             epoch_state,
             dag,
             payload_client,
             reliable_broadcast,
-            current_round,
+            current_round: highest_round,
             time_service,
             rb_abort_handle: None,
             storage,
+            certified_node_tx,
+            quorum_store_enabled,
+            recovered_payloads: VecDeque::new(),
+            nodes_missing_parents: HashMap::new(),
+        };
+        let (maybe_pending_node, recovered_payloads) = driver.read_pending_nodes();
+        if !recovered_payloads.is_empty() {
+            info!(
+                num_payloads = recovered_payloads.len(),
+                "Re-propose the payloads of our nodes lost in the restart"
+            );
+        }
+        driver.recovered_payloads = recovered_payloads;
+        if let Some(pending_node) = maybe_pending_node {
+            // Resume the broadcast of our node that didn't get certified before the restart,
+            // creating a new one for the same round would equivocate.
+            info!(
+                round = pending_node.round(),
+                "Resume the broadcast of the pending node"
+            );
+            driver.current_round = pending_node.round();
+            driver.broadcast_node(pending_node);
+        } else if is_empty {
+            // A fresh epoch starts from round 0 which doesn't need any strong links
+            driver.enter_new_round(0, vec![]);
         }
+        driver
+    }
+
+    /// Our nodes are persisted until they're committed. The latest one of the epoch that isn't
+    /// certified yet is resumed and the certified ones get ordered from the DAG, the others never
+    /// made it into the DAG so their payloads are recovered, minus anything the DAG already has.
+    fn read_pending_nodes(&self) -> (Option<Node>, VecDeque<(HashValue, Payload)>) {
+        let mut pending_nodes = self.storage.get_pending_nodes().unwrap_or_default();
+        pending_nodes.sort_by_key(|(_, node)| node.round());
+        let dag_reader = self.dag.read();
+        let epoch = self.epoch_state.epoch;
+        let maybe_pending = match pending_nodes.last() {
+            Some((_, node))
+                if node.epoch() == epoch
+                    && *node.author() == self.author
+                    && !dag_reader.exists(node.metadata()) =>
+            {
+                pending_nodes.pop().map(|(_, node)| node)
+            },
+            _ => None,
+        };
+
+        let mut proposed = ProposedPayloads::default();
+        for node in dag_reader.all_nodes() {
+            proposed.insert(node.payload());
+        }
+        if let Some(pending_node) = &maybe_pending {
+            proposed.insert(pending_node.payload());
+        }
+        let mut recovered_payloads = VecDeque::new();
+        let mut obsolete = vec![];
+        for (digest, node) in pending_nodes {
+            if node.epoch() == epoch && dag_reader.exists(node.metadata()) {
+                continue;
+            }
+            if node.epoch() == epoch
+                && *node.author() == self.author
+                && node.payload().is_direct() != self.quorum_store_enabled
+            {
+                let payload = proposed.dedup(node.payload().clone());
+                if !payload.is_empty() {
+                    recovered_payloads.push_back((digest, payload));
+                    continue;
+                }
+            }
+            obsolete.push(digest);
+        }
+        if let Err(e) = self.storage.delete_nodes(obsolete) {
+            error!("Failed to delete obsolete nodes {:?}", e);
+        }
+        (maybe_pending, recovered_payloads)
     }
 
     pub fn add_node(&mut self, node: CertifiedNode) -> anyhow::Result<()> {
         let mut dag_writer = self.dag.write();
         let round = node.metadata().round();
-        if dag_writer.all_exists(node.parents_metadata()) {
-            dag_writer.add_node(node)?;
+        if dag_writer.all_exists(node.links_metadata()) {
+            let node = dag_writer.add_node(node)?;
+            if let Err(e) = self.certified_node_tx.try_send(node) {
+                error!("Failed to notify the order rule {:?}", e);
+            }
             if self.current_round == round {
                 let maybe_strong_links = dag_writer
                     .get_strong_links_for_round(self.current_round, &self.epoch_state.verifier);
                 drop(dag_writer);
                 if let Some(strong_links) = maybe_strong_links {
-                    self.enter_new_round(strong_links);
+                    self.enter_new_round(self.current_round + 1, strong_links);
                 }
             }
         }
@@ -76,12 +221,52 @@ impl DagDriver {
         Ok(())
     }
 
-    pub fn enter_new_round(&mut self, strong_links: Vec<NodeCertificate>) {
+    /// The next recovered payload minus what got proposed since the restart, either by nodes
+    /// added to the DAG or by nodes still in flight. Fully proposed ones are deleted.
+    fn next_recovered_payload(&mut self) -> Option<(HashValue, Payload)> {
+        if self.recovered_payloads.is_empty() {
+            return None;
+        }
+        let mut proposed = ProposedPayloads::default();
+        {
+            let dag_reader = self.dag.read();
+            for node in dag_reader.all_nodes() {
+                proposed.insert(node.payload());
+            }
+            let lowest_round = dag_reader.lowest_round();
+            self.nodes_missing_parents
+                .retain(|_, (round, _)| *round >= lowest_round);
+        }
+        for (_, payload) in self.nodes_missing_parents.values() {
+            proposed.insert(payload);
+        }
+        let mut obsolete = vec![];
+        let mut next = None;
+        while let Some((digest, payload)) = self.recovered_payloads.pop_front() {
+            let payload = proposed.dedup(payload);
+            if payload.is_empty() {
+                obsolete.push(digest);
+            } else {
+                next = Some((digest, payload));
+                break;
+            }
+        }
+        if let Err(e) = self.storage.delete_nodes(obsolete) {
+            error!("Failed to delete obsolete nodes {:?}", e);
+        }
+        next
+    }
+
+    pub fn enter_new_round(&mut self, new_round: Round, strong_links: Vec<NodeCertificate>) {
         // TODO: support pulling payload
-        let payload = Payload::empty(false);
+        let (maybe_recovered_digest, payload) = match self.next_recovered_payload() {
+            Some((digest, payload)) => (Some(digest), payload),
+            None => (None, Payload::empty(self.quorum_store_enabled)),
+        };
         // TODO: need to wait to pass median of parents timestamp
         let timestamp = self.time_service.get_current_timestamp();
-        self.current_round += 1;
+        let weak_links = self.dag.read().get_weak_links(&strong_links);
+        self.current_round = new_round;
         let new_node = Node::new(
             self.epoch_state.epoch,
             self.current_round,
@@ -89,15 +274,38 @@ impl DagDriver {
             timestamp.as_micros() as u64,
             payload,
             strong_links,
+            weak_links,
         );
         self.storage
             .save_node(&new_node)
             .expect("node must be saved");
+        if let Some(digest) = maybe_recovered_digest {
+            if let Err(e) = self.storage.delete_nodes(vec![digest]) {
+                error!("Failed to delete the recovered node {:?}", e);
+            }
+        }
+        fail_point!("consensus::dag::equivocate", |_| {
+            // broadcast a conflicting node for the round instead of the persisted one
+            let equivocating_node = Node::new(
+                new_node.epoch(),
+                new_node.round(),
+                *new_node.author(),
+                new_node.timestamp() + 1,
+                new_node.payload().clone(),
+                new_node.parents().to_vec(),
+                new_node.weak_links().to_vec(),
+            );
+            self.broadcast_node(equivocating_node);
+        });
         self.broadcast_node(new_node);
     }
 
+    /// Keep sending the node until it's certified and the certificate until everyone acked it,
+    /// the persisted node is deleted once committed.
     pub fn broadcast_node(&mut self, node: Node) {
+        fail_point!("consensus::dag::broadcast_node", |_| ());
         let rb = self.reliable_broadcast.clone();
+        let digest = node.digest();
         let (abort_handle, abort_registration) = AbortHandle::new_pair();
         let signature_builder =
             SignatureBuilder::new(node.metadata().clone(), self.epoch_state.clone());
@@ -110,8 +318,45 @@ impl DagDriver {
                 rb.broadcast(certified_node, cert_ack_set)
             });
         tokio::spawn(Abortable::new(task, abort_registration));
-        if let Some(prev_handle) = self.rb_abort_handle.replace(abort_handle) {
+        if let Some((prev_handle, _)) = self.rb_abort_handle.replace((abort_handle, digest)) {
+            // the previous node is superseded, if it never gets certified its payload is
+            // recovered after a restart
             prev_handle.abort();
         }
     }
 }
+
+impl RpcHandler for DagDriver {
+    type Request = CertifiedNode;
+    type Response = CertifiedAck;
+
+    fn process(&mut self, node: Self::Request) -> anyhow::Result<Self::Response> {
+        let epoch = node.metadata().epoch();
+        {
+            let dag_reader = self.dag.read();
+            if dag_reader.exists(node.metadata()) {
+                return Ok(CertifiedAck::new(epoch));
+            }
+
+            if !dag_reader.all_exists(node.links_metadata()) {
+                // TODO(ibalajiarun): implement fetching logic.
+                self.nodes_missing_parents
+                    .insert(node.digest(), (node.round(), node.payload().clone()));
+                bail!(CertifiedNodeHandleError::MissingParents);
+            }
+        }
+
+        self.nodes_missing_parents.remove(&node.digest());
+        self.add_node(node)?;
+
+        Ok(CertifiedAck::new(epoch))
+    }
+}
+
+impl Drop for DagDriver {
+    fn drop(&mut self) {
+        if let Some((handle, _)) = self.rb_abort_handle.take() {
+            handle.abort();
+        }
+    }
+}
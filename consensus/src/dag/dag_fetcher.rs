@@ -1,14 +1,17 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use super::{dag_network::RpcWithFallback, types::NodeMetadata, RpcHandler};
+use super::{dag_network::RpcWithFallback, storage::DAGStorage, types::NodeMetadata, RpcHandler};
 use crate::dag::{
     dag_network::DAGNetworkSender,
     dag_store::Dag,
-    types::{CertifiedNode, FetchResponse, Node, RemoteFetchRequest},
+    types::{
+        CertifiedNode, FetchResponse, Node, RemoteFetchRequest, RoundRangeFetchRequest,
+        RoundRangeFetchResponse,
+    },
 };
 use anyhow::ensure;
-use aptos_consensus_types::common::Author;
+use aptos_consensus_types::common::{Author, Round};
 use aptos_infallible::RwLock;
 use aptos_logger::error;
 use aptos_time_service::TimeService;
@@ -91,7 +94,7 @@ impl DagFetcher {
                 let dag_reader = self.dag.read();
 
                 let missing_parents: Vec<NodeMetadata> = dag_reader
-                    .filter_missing(local_request.node().parents_metadata())
+                    .filter_missing(local_request.node().links_metadata())
                     .cloned()
                     .collect();
 
@@ -138,7 +141,7 @@ impl DagFetcher {
                     if self
                         .dag
                         .read()
-                        .all_exists(local_request.node().parents_metadata())
+                        .all_exists(local_request.node().links_metadata())
                     {
                         local_request.notify();
                         break;
@@ -212,3 +215,139 @@ impl RpcHandler for FetchRequestHandler {
         Ok(FetchResponse::new(message.epoch(), certified_nodes))
     }
 }
+
+/// Fetch the certified nodes of a range of rounds from the responder, following the pages of
+/// the responses. Every response is verified against its request before it's used.
+pub async fn fetch_round_range(
+    network: &dyn DAGNetworkSender,
+    epoch_state: &EpochState,
+    responder: Author,
+    request: RoundRangeFetchRequest,
+    rpc_timeout: Duration,
+) -> anyhow::Result<Vec<CertifiedNode>> {
+    let mut certified_nodes = vec![];
+    let mut maybe_request = Some(request);
+    while let Some(request) = maybe_request {
+        let response = network
+            .send_rpc(responder, request.clone().into(), rpc_timeout)
+            .await
+            .and_then(RoundRangeFetchResponse::try_from)
+            .and_then(|response| response.verify(&request, &epoch_state.verifier))?;
+        let (nodes, next_page) = response.into_parts();
+        certified_nodes.extend(nodes);
+        maybe_request = next_page;
+    }
+    Ok(certified_nodes)
+}
+
+#[derive(Debug, ThisError)]
+pub enum RoundRangeFetchHandleError {
+    #[error("request for epoch {0}, current epoch is {1}")]
+    WrongEpoch(u64, u64),
+}
+
+/// Serves the certified nodes of a range of rounds to peers catching up, from the DAG or, for
+/// the rounds the DAG doesn't hold anymore, from the storage. A response is cut once the nodes
+/// reach the size limit, it always has at least one node so the requester makes progress.
+pub struct RoundRangeFetchHandler {
+    epoch: u64,
+    dag: Arc<RwLock<Dag>>,
+    storage: Arc<dyn DAGStorage>,
+    author_to_index: HashMap<Author, usize>,
+    max_response_bytes: u64,
+}
+
+impl RoundRangeFetchHandler {
+    pub fn new(
+        dag: Arc<RwLock<Dag>>,
+        storage: Arc<dyn DAGStorage>,
+        epoch_state: Arc<EpochState>,
+        max_response_bytes: u64,
+    ) -> Self {
+        Self {
+            epoch: epoch_state.epoch,
+            dag,
+            storage,
+            author_to_index: epoch_state.verifier.address_to_validator_index().clone(),
+            max_response_bytes,
+        }
+    }
+
+    /// The stored nodes of the requested range below the given round, in (round, author index)
+    /// order.
+    fn stored_nodes(
+        &self,
+        request: &RoundRangeFetchRequest,
+        below_round: Round,
+    ) -> anyhow::Result<Vec<(Round, usize, Arc<CertifiedNode>)>> {
+        let end_round = request.end_round().min(below_round - 1);
+        let mut nodes: Vec<_> = self
+            .storage
+            .get_certified_nodes_in_range(self.epoch, request.start_round(), end_round)?
+            .into_iter()
+            .filter_map(|node| {
+                let index = *self.author_to_index.get(node.author())?;
+                request
+                    .contains(node.round(), index)
+                    .then(|| (node.round(), index, Arc::new(node)))
+            })
+            .collect();
+        nodes.sort_by_key(|(round, index, _)| (*round, *index));
+        Ok(nodes)
+    }
+}
+
+impl RpcHandler for RoundRangeFetchHandler {
+    type Request = RoundRangeFetchRequest;
+    type Response = RoundRangeFetchResponse;
+
+    fn process(&mut self, request: Self::Request) -> anyhow::Result<Self::Response> {
+        ensure!(
+            request.epoch() == self.epoch,
+            RoundRangeFetchHandleError::WrongEpoch(request.epoch(), self.epoch)
+        );
+
+        let dag_reader = self.dag.read();
+        let (lowest_round, highest_round) = if dag_reader.is_empty() {
+            (Round::MAX, 0)
+        } else {
+            (dag_reader.lowest_round(), dag_reader.highest_round())
+        };
+        let mut nodes = if request.start_round() < lowest_round {
+            self.stored_nodes(&request, lowest_round)?
+        } else {
+            vec![]
+        };
+        for round in
+            request.start_round().max(lowest_round)..=request.end_round().min(highest_round)
+        {
+            nodes.extend(
+                dag_reader
+                    .get_round_nodes(round)
+                    .filter(|(index, _)| request.contains(round, *index))
+                    .map(|(index, node)| (round, index, node.clone())),
+            );
+        }
+        drop(dag_reader);
+
+        let mut certified_nodes = vec![];
+        let mut response_bytes = 0;
+        let mut next_page = None;
+        for (round, index, node) in nodes {
+            let node_bytes = bcs::serialized_size(node.as_ref())? as u64;
+            if !certified_nodes.is_empty() && response_bytes + node_bytes > self.max_response_bytes
+            {
+                next_page = Some(request.next_page(round, index));
+                break;
+            }
+            response_bytes += node_bytes;
+            certified_nodes.push(node.as_ref().clone());
+        }
+
+        Ok(RoundRangeFetchResponse::new(
+            self.epoch,
+            certified_nodes,
+            next_page,
+        ))
+    }
+}
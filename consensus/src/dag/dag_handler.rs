@@ -1,13 +1,19 @@
 // Copyright © Aptos Foundation
 
 use super::{
-    dag_fetcher::FetchRequestHandler, reliable_broadcast::CertifiedNodeHandler,
-    storage::DAGStorage, types::TDAGMessage,
+    dag_driver::DagDriver,
+    dag_fetcher::{FetchRequestHandler, RoundRangeFetchHandler},
+    storage::DAGStorage,
+    types::TDAGMessage,
+    validation::NodeValidator,
+    verification::{verify_certified_node_for_epoch, verify_node_for_epoch, NodeVerificationError},
 };
 use crate::{
     dag::{
-        dag_network::RpcHandler, dag_store::Dag, reliable_broadcast::NodeBroadcastHandler,
-        types::DAGMessage,
+        dag_network::RpcHandler,
+        dag_store::Dag,
+        reliable_broadcast::NodeBroadcastHandler,
+        types::{DAGMessage, Node},
     },
     network::{IncomingDAGRequest, TConsensusMsg},
 };
@@ -15,28 +21,36 @@ use anyhow::bail;
 use aptos_channels::aptos_channel;
 use aptos_consensus_types::common::Author;
 use aptos_infallible::RwLock;
-use aptos_logger::{error, warn};
+use aptos_logger::{debug, error, warn};
 use aptos_network::protocols::network::RpcError;
 use aptos_types::{epoch_state::EpochState, validator_signer::ValidatorSigner};
 use bytes::Bytes;
 use futures::StreamExt;
 use std::sync::Arc;
 
-struct NetworkHandler {
+pub(crate) struct NetworkHandler {
     dag_rpc_rx: aptos_channel::Receiver<Author, IncomingDAGRequest>,
     node_receiver: NodeBroadcastHandler,
-    certified_node_receiver: CertifiedNodeHandler,
+    dag_driver: DagDriver,
     fetch_receiver: FetchRequestHandler,
+    round_range_fetch_receiver: RoundRangeFetchHandler,
     epoch_state: Arc<EpochState>,
+    dag: Arc<RwLock<Dag>>,
+    node_validator: NodeValidator,
+    quorum_store_enabled: bool,
 }
 
 impl NetworkHandler {
-    fn new(
+    pub fn new(
         dag: Arc<RwLock<Dag>>,
         dag_rpc_rx: aptos_channel::Receiver<Author, IncomingDAGRequest>,
         signer: ValidatorSigner,
         epoch_state: Arc<EpochState>,
         storage: Arc<dyn DAGStorage>,
+        dag_driver: DagDriver,
+        node_validator: NodeValidator,
+        max_fetch_response_bytes: u64,
+        quorum_store_enabled: bool,
     ) -> Self {
         Self {
             dag_rpc_rx,
@@ -44,15 +58,24 @@ impl NetworkHandler {
                 dag.clone(),
                 signer,
                 epoch_state.clone(),
-                storage,
+                storage.clone(),
             ),
-            certified_node_receiver: CertifiedNodeHandler::new(dag.clone()),
+            dag_driver,
             epoch_state: epoch_state.clone(),
-            fetch_receiver: FetchRequestHandler::new(dag, epoch_state),
+            fetch_receiver: FetchRequestHandler::new(dag.clone(), epoch_state.clone()),
+            round_range_fetch_receiver: RoundRangeFetchHandler::new(
+                dag.clone(),
+                storage,
+                epoch_state,
+                max_fetch_response_bytes,
+            ),
+            dag,
+            node_validator,
+            quorum_store_enabled,
         }
     }
 
-    async fn start(mut self) {
+    pub async fn start(mut self) {
         // TODO(ibalajiarun): clean up Reliable Broadcast storage periodically.
         while let Some(msg) = self.dag_rpc_rx.next().await {
             if let Err(e) = self.process_rpc(msg).await {
@@ -64,26 +87,59 @@ impl NetworkHandler {
     async fn process_rpc(&mut self, rpc_request: IncomingDAGRequest) -> anyhow::Result<()> {
         let dag_message: DAGMessage = rpc_request.req.try_into()?;
 
-        let author = dag_message
-            .author()
-            .map_err(|_| anyhow::anyhow!("unexpected rpc message {:?}", dag_message))?;
-        if author != rpc_request.sender {
-            bail!("message author and network author mismatch");
+        // fetch requests can come from any peer, the other messages only from their author
+        if let Ok(author) = dag_message.author() {
+            if author != rpc_request.sender {
+                bail!("message author and network author mismatch");
+            }
         }
 
         let response: anyhow::Result<DAGMessage> = match dag_message {
-            DAGMessage::NodeMsg(node) => node
-                .verify(&self.epoch_state.verifier)
+            DAGMessage::NodeMsg(node) => self
+                .validate_node(&node)
+                .and_then(|_| {
+                    self.observe_verification(
+                        &node,
+                        verify_node_for_epoch(
+                            &node,
+                            self.epoch_state.epoch,
+                            &self.epoch_state.verifier,
+                            self.quorum_store_enabled,
+                        ),
+                    )
+                })
                 .and_then(|_| self.node_receiver.process(node))
                 .map(|r| r.into()),
-            DAGMessage::CertifiedNodeMsg(node) => node
-                .verify(&self.epoch_state.verifier)
-                .and_then(|_| self.certified_node_receiver.process(node))
-                .map(|r| r.into()),
+            DAGMessage::CertifiedNodeMsg(node) => {
+                let node_id = node.id();
+                self.validate_node(&node)
+                    .and_then(|_| {
+                        self.observe_verification(
+                            &node,
+                            verify_certified_node_for_epoch(
+                                &node,
+                                self.epoch_state.epoch,
+                                &self.epoch_state.verifier,
+                                self.quorum_store_enabled,
+                            ),
+                        )
+                    })
+                    .and_then(|_| self.dag_driver.process(node))
+                    .map(|r| {
+                        if let Err(e) = self.node_receiver.compact_certified(&node_id) {
+                            warn!(error = ?e, "unable to compact votes for certified node");
+                        }
+                        r.into()
+                    })
+            },
             DAGMessage::FetchRequest(request) => request
                 .verify(&self.epoch_state.verifier)
                 .and_then(|_| self.fetch_receiver.process(request))
                 .map(|r| r.into()),
+            DAGMessage::RoundRangeFetchRequest(request) => request
+                .verify(&self.epoch_state.verifier)
+                .and_then(|_| self.round_range_fetch_receiver.process(request))
+                .map(|r| r.into()),
             _ => {
                 error!("unknown rpc message {:?}", dag_message);
                 Err(anyhow::anyhow!("unknown rpc message"))
@@ -104,4 +160,44 @@ impl NetworkHandler {
             .send(response)
             .map_err(|_| anyhow::anyhow!("unable to respond to rpc"))
     }
+
+    fn validate_node(&self, node: &Node) -> anyhow::Result<()> {
+        let (lowest_round, highest_round) = {
+            let dag_reader = self.dag.read();
+            (dag_reader.lowest_round(), dag_reader.highest_round())
+        };
+        self.node_validator
+            .validate(node, lowest_round, highest_round)
+            .map_err(|e| {
+                warn!(error = ?e, author = %node.author(), round = node.round(), "rejected node");
+                e.into()
+            })
+    }
+
+    fn observe_verification(
+        &self,
+        node: &Node,
+        result: Result<(), NodeVerificationError>,
+    ) -> anyhow::Result<()> {
+        result.map_err(|e| {
+            if e.is_malicious() {
+                warn!(
+                    error = ?e,
+                    class = e.class(),
+                    author = %node.author(),
+                    round = node.round(),
+                    "invalid node"
+                );
+            } else {
+                debug!(
+                    error = ?e,
+                    class = e.class(),
+                    author = %node.author(),
+                    round = node.round(),
+                    "node from another epoch"
+                );
+            }
+            e.into()
+        })
+    }
 }
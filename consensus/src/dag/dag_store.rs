@@ -96,7 +96,11 @@ impl Dag {
             .unwrap_or(&0)
     }
 
-    pub fn add_node(&mut self, node: CertifiedNode) -> anyhow::Result<()> {
+    pub fn is_empty(&self) -> bool {
+        self.nodes_by_round.is_empty()
+    }
+
+    pub fn add_node(&mut self, node: CertifiedNode) -> anyhow::Result<Arc<CertifiedNode>> {
         let node = Arc::new(node);
         let author = node.metadata().author();
         let index = *self
@@ -106,8 +110,8 @@ impl Dag {
         let round = node.metadata().round();
         ensure!(round >= self.lowest_round(), "round too low");
         ensure!(round <= self.highest_round() + 1, "round too high");
-        for parent in node.parents() {
-            ensure!(self.exists(parent.metadata()), "parent not exist");
+        for parent in node.links_metadata() {
+            ensure!(self.exists(parent), "parent not exist");
         }
         let round_ref = self
             .nodes_by_round
@@ -118,7 +122,7 @@ impl Dag {
         // mutate after all checks pass
         self.storage.save_certified_node(&node)?;
         round_ref[index] = Some(NodeStatus::Unordered(node.clone()));
-        Ok(())
+        Ok(node)
     }
 
     pub fn exists(&self, metadata: &NodeMetadata) -> bool {
@@ -146,12 +150,36 @@ impl Dag {
         round_ref[*index].as_ref()
     }
 
+    /// All the nodes of the DAG in ascending round order
+    pub fn all_nodes(&self) -> impl Iterator<Item = &Arc<CertifiedNode>> {
+        self.nodes_by_round
+            .values()
+            .flat_map(|round_ref| round_ref.iter().flatten())
+            .map(|node_status| node_status.as_node())
+    }
+
     fn get_round_iter(&self, round: Round) -> Option<impl Iterator<Item = &NodeStatus>> {
         self.nodes_by_round
             .get(&round)
             .map(|round_ref| round_ref.iter().flatten())
     }
 
+    /// The nodes of the round along with the index of their author, in index order
+    pub fn get_round_nodes(
+        &self,
+        round: Round,
+    ) -> impl Iterator<Item = (usize, &Arc<CertifiedNode>)> {
+        self.nodes_by_round
+            .get(&round)
+            .into_iter()
+            .flat_map(|round_ref| round_ref.iter().enumerate())
+            .filter_map(|(index, node_status)| {
+                node_status
+                    .as_ref()
+                    .map(|node_status| (index, node_status.as_node()))
+            })
+    }
+
     pub fn get_node(&self, metadata: &NodeMetadata) -> Option<Arc<CertifiedNode>> {
         self.get_node_ref_by_metadata(metadata)
             .map(|node_status| node_status.as_node().clone())
@@ -188,12 +216,43 @@ impl Dag {
             .unwrap_or(false)
     }
 
+    /// Split the voting power of the next round between the nodes linking to the given node and
+    /// the ones that don't, returns (for, against).
+    pub fn voting_power_for_node(
+        &self,
+        metadata: &NodeMetadata,
+        validator_verifier: &ValidatorVerifier,
+    ) -> (u128, u128) {
+        let mut voting_power_for = 0;
+        let mut voting_power_against = 0;
+        for node_status in self
+            .get_round_iter(metadata.round() + 1)
+            .into_iter()
+            .flatten()
+        {
+            let node = node_status.as_node();
+            let voting_power = validator_verifier
+                .get_voting_power(node.author())
+                .unwrap_or(0) as u128;
+            if node
+                .parents()
+                .iter()
+                .any(|cert| cert.metadata() == metadata)
+            {
+                voting_power_for += voting_power;
+            } else {
+                voting_power_against += voting_power;
+            }
+        }
+        (voting_power_for, voting_power_against)
+    }
+
     fn reachable_filter(start: Vec<HashValue>) -> impl FnMut(&Arc<CertifiedNode>) -> bool {
         let mut reachable: HashSet<HashValue> = HashSet::from_iter(start.into_iter());
         move |node| {
             if reachable.contains(&node.digest()) {
-                for parent in node.parents() {
-                    reachable.insert(*parent.metadata().digest());
+                for parent in node.links_metadata() {
+                    reachable.insert(*parent.digest());
                 }
                 true
             } else {
@@ -265,6 +324,46 @@ impl Dag {
         }
     }
 
+    /// Unordered nodes below the round of the strong links that aren't in their causal history,
+    /// usually from validators lagging behind. Linking them weakly gets them ordered by the next
+    /// anchor that has the new node in its causal history. At most the latest such node of each
+    /// validator is linked, the older ones are left to the following rounds.
+    pub fn get_weak_links(&self, strong_links: &[NodeCertificate]) -> Vec<NodeCertificate> {
+        let strong_round = match strong_links.first() {
+            Some(link) => link.metadata().round(),
+            None => return vec![],
+        };
+        let mut reachable: HashSet<HashValue> = strong_links
+            .iter()
+            .map(|link| *link.metadata().digest())
+            .collect();
+        let mut weak_links = vec![];
+        let mut linked_authors = HashSet::new();
+        for node_status in self
+            .nodes_by_round
+            .range(..=strong_round)
+            .rev()
+            .flat_map(|(_, round_ref)| round_ref.iter())
+            .flatten()
+        {
+            let node = node_status.as_node();
+            if !reachable.contains(&node.digest()) {
+                if node.round() == strong_round
+                    || !matches!(node_status, NodeStatus::Unordered(_))
+                    || !linked_authors.insert(*node.author())
+                {
+                    continue;
+                }
+                weak_links.push(node.certificate());
+            }
+            // the causal history of a weak link is covered as well
+            for parent in node.links_metadata() {
+                reachable.insert(*parent.digest());
+            }
+        }
+        weak_links
+    }
+
     pub fn lowest_incomplete_round(&self) -> Option<Round> {
         for (round, round_nodes) in &self.nodes_by_round {
             if round_nodes.iter().any(|node| node.is_none()) {
@@ -0,0 +1,146 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use super::{counters, dag_store::Dag};
+use crate::util::time_service::TimeService;
+use aptos_consensus_types::common::Round;
+use aptos_infallible::RwLock;
+use aptos_logger::{info, warn};
+use aptos_types::epoch_state::EpochState;
+use futures_channel::oneshot;
+use std::{
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+/// The number of rounds the DAG must have advanced past the last ordered anchor for the
+/// validator set to be considered live, one full anchor window plus the round in progress.
+const LIVENESS_ROUND_GAP: Round = 3;
+
+/// Round of the last anchor handed to execution, written by the notifier and read by the
+/// health monitor.
+#[derive(Default)]
+pub struct OrderingProgress {
+    last_ordered_round: AtomicU64,
+}
+
+impl OrderingProgress {
+    pub fn update(&self, round: Round) {
+        self.last_ordered_round.fetch_max(round, Ordering::Relaxed);
+    }
+
+    pub fn last_ordered_round(&self) -> Round {
+        self.last_ordered_round.load(Ordering::Relaxed)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum DagHealth {
+    Healthy,
+    /// No round progress in the DAG either, the validator set itself is not live and switching
+    /// protocols wouldn't help.
+    Inactive,
+    Stalled,
+}
+
+/// Watches the ordering progress and reports a stall when no anchor gets ordered for longer
+/// than the threshold while the DAG keeps moving to new rounds.
+/// Reporting is all it does, once per stall, with a warning and `DAG_STALL_COUNT` for alerting.
+/// The monitor never switches protocols by itself: a validator acting on its local view would
+/// leave the others behind. Falling back to Jolteon is done by changing the on-chain consensus
+/// config, which every validator applies at the same epoch boundary.
+pub struct DagHealthMonitor {
+    epoch_state: Arc<EpochState>,
+    dag: Arc<RwLock<Dag>>,
+    progress: Arc<OrderingProgress>,
+    time_service: Arc<dyn TimeService>,
+    stall_threshold: Duration,
+    last_ordered_round: Round,
+    last_progress_time: Duration,
+    stall_reported: bool,
+}
+
+impl DagHealthMonitor {
+    pub fn new(
+        epoch_state: Arc<EpochState>,
+        dag: Arc<RwLock<Dag>>,
+        progress: Arc<OrderingProgress>,
+        time_service: Arc<dyn TimeService>,
+        stall_threshold: Duration,
+    ) -> Self {
+        let last_ordered_round = progress.last_ordered_round();
+        let last_progress_time = time_service.get_current_timestamp();
+        Self {
+            epoch_state,
+            dag,
+            progress,
+            time_service,
+            stall_threshold,
+            last_ordered_round,
+            last_progress_time,
+            stall_reported: false,
+        }
+    }
+
+    pub async fn start(
+        mut self,
+        check_interval: Duration,
+        mut shutdown_rx: oneshot::Receiver<oneshot::Sender<()>>,
+    ) {
+        info!(epoch = self.epoch_state.epoch, "DagHealthMonitor starts.");
+        let mut interval = tokio::time::interval(check_interval);
+        loop {
+            tokio::select! {
+                biased;
+                maybe_ack_tx = &mut shutdown_rx => {
+                    if let Ok(ack_tx) = maybe_ack_tx {
+                        let _ = ack_tx.send(());
+                    }
+                    break;
+                },
+                _ = interval.tick() => {
+                    self.report_stall();
+                },
+            }
+        }
+        info!(epoch = self.epoch_state.epoch, "DagHealthMonitor stops.");
+    }
+
+    /// Checks the health and reports a stall the first time it's seen, returns whether it did.
+    pub fn report_stall(&mut self) -> bool {
+        let stalled = self.check() == DagHealth::Stalled;
+        let report = stalled && !self.stall_reported;
+        if report {
+            warn!(
+                epoch = self.epoch_state.epoch,
+                last_ordered_round = self.last_ordered_round,
+                highest_round = self.dag.read().highest_round(),
+                "DAG ordering stalled, the on-chain consensus config can switch back to Jolteon at the next epoch"
+            );
+            counters::DAG_STALL_COUNT.inc();
+        }
+        self.stall_reported = stalled;
+        report
+    }
+
+    pub fn check(&mut self) -> DagHealth {
+        let now = self.time_service.get_current_timestamp();
+        let last_ordered_round = self.progress.last_ordered_round();
+        if last_ordered_round > self.last_ordered_round {
+            self.last_ordered_round = last_ordered_round;
+            self.last_progress_time = now;
+        }
+        let stalled_for = now.saturating_sub(self.last_progress_time);
+        counters::ORDERING_STALL_DURATION.set(stalled_for.as_millis() as i64);
+        if stalled_for <= self.stall_threshold {
+            return DagHealth::Healthy;
+        }
+        if self.dag.read().highest_round() < self.last_ordered_round + LIVENESS_ROUND_GAP {
+            return DagHealth::Inactive;
+        }
+        DagHealth::Stalled
+    }
+}
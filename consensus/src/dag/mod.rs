@@ -2,18 +2,31 @@
 // SPDX-License-Identifier: Apache-2.0
 #![allow(dead_code)]
 
+mod adapter;
 mod anchor_election;
+mod anchor_votes;
+mod bootstrap;
+mod commit_signer;
+mod counters;
 mod dag_driver;
 mod dag_fetcher;
 mod dag_handler;
 mod dag_network;
 mod dag_store;
+mod health;
 mod order_rule;
 mod reliable_broadcast;
+mod snapshot;
 mod storage;
 #[cfg(test)]
 mod tests;
 mod types;
+mod validation;
+mod verification;
 
+pub use anchor_votes::{anchor_vote_history, AnchorVoteRecord};
+pub use bootstrap::bootstrap_dag;
+pub use commit_signer::DagCommitSigner;
 pub use dag_network::RpcHandler;
-pub use types::{CertifiedNode, DAGNetworkMessage, Node, NodeId, Vote};
+pub use snapshot::{export_dag_snapshot, DagSnapshot};
+pub use types::{CertifiedNode, DAGMessage, DAGNetworkMessage, Node, NodeId, Vote};
@@ -3,23 +3,38 @@
 
 use super::dag_store::NodeStatus;
 use crate::dag::{
-    anchor_election::AnchorElection, dag_store::Dag, types::NodeMetadata, CertifiedNode,
+    anchor_election::AnchorElection,
+    anchor_votes::{observe_anchor_votes, AnchorVoteRecord},
+    counters::{ANCHOR_OUTCOME_COUNT, ORDERING_BACKPRESSURE_COUNT, PENDING_ORDERED_BATCHES},
+    dag_store::Dag,
+    types::NodeMetadata,
+    CertifiedNode,
 };
 use aptos_consensus_types::common::Round;
-use aptos_crypto::HashValue;
 use aptos_infallible::RwLock;
-use aptos_logger::error;
+use aptos_logger::{error, info, warn};
 use aptos_types::{epoch_state::EpochState, ledger_info::LedgerInfo};
-use futures_channel::mpsc::UnboundedSender;
-use std::sync::Arc;
+use fail::fail_point;
+use futures::future::poll_fn;
+use futures_channel::{mpsc::Sender, oneshot};
+use std::{collections::VecDeque, sync::Arc};
+use tokio::sync::mpsc::{Receiver, UnboundedReceiver};
+
+/// Lets tests act as if the votes for the anchors were held back from this node, e.g. by a
+/// partition, so anchors only get ordered through the causal history of later ones.
+fn anchor_votes_withheld() -> bool {
+    fail_point!("consensus::dag::withhold_anchor_votes", |_| true);
+    false
+}
 
 pub struct OrderRule {
     epoch_state: Arc<EpochState>,
-    ordered_block_id: HashValue,
     lowest_unordered_anchor_round: Round,
     dag: Arc<RwLock<Dag>>,
     anchor_election: Box<dyn AnchorElection>,
-    ordered_nodes_sender: UnboundedSender<Vec<Arc<CertifiedNode>>>,
+    ordered_nodes_sender: Sender<Vec<Arc<CertifiedNode>>>,
+    /// Ordered batches that didn't fit in the channel, ordering is paused until they're flushed
+    pending_ordered_nodes: VecDeque<Vec<Arc<CertifiedNode>>>,
 }
 
 impl OrderRule {
@@ -28,37 +43,89 @@ impl OrderRule {
         latest_ledger_info: LedgerInfo,
         dag: Arc<RwLock<Dag>>,
         anchor_election: Box<dyn AnchorElection>,
-        ordered_nodes_sender: UnboundedSender<Vec<Arc<CertifiedNode>>>,
+        ordered_nodes_sender: Sender<Vec<Arc<CertifiedNode>>>,
     ) -> Self {
         // TODO: we need to initialize the anchor election based on the dag
         Self {
             epoch_state,
-            ordered_block_id: latest_ledger_info.commit_info().id(),
             lowest_unordered_anchor_round: latest_ledger_info.commit_info().round() + 1,
             dag,
             anchor_election,
             ordered_nodes_sender,
+            pending_ordered_nodes: VecDeque::new(),
         }
     }
 
-    /// Check if two rounds have the same parity
-    fn check_parity(r1: Round, r2: Round) -> bool {
-        (r1 ^ r2) & 1 == 0
+    /// Run the order rule as its own task, driven by newly certified nodes and commit
+    /// notifications from the execution pipeline. Ordering stays paused while the ordered
+    /// nodes channel is full, and resumes as soon as there's room again.
+    pub async fn start(
+        mut self,
+        mut certified_node_rx: Receiver<Arc<CertifiedNode>>,
+        mut commit_rx: UnboundedReceiver<Round>,
+        mut shutdown_rx: oneshot::Receiver<oneshot::Sender<()>>,
+    ) {
+        info!(epoch = self.epoch_state.epoch, "OrderRule starts.");
+        loop {
+            tokio::select! {
+                biased;
+                maybe_ack_tx = &mut shutdown_rx => {
+                    if let Ok(ack_tx) = maybe_ack_tx {
+                        let _ = ack_tx.send(());
+                    }
+                    break;
+                },
+                ready = poll_fn(|cx| self.ordered_nodes_sender.poll_ready(cx)),
+                    if self.is_paused() => {
+                    if let Err(e) = ready {
+                        error!("Ordered nodes channel is closed {:?}", e);
+                        break;
+                    }
+                    self.resume();
+                },
+                Some(round) = commit_rx.recv() => {
+                    self.anchor_election.commit(round);
+                },
+                Some(node) = certified_node_rx.recv() => {
+                    self.process_new_node(&node);
+                },
+                else => break,
+            }
+        }
+        info!(epoch = self.epoch_state.epoch, "OrderRule stops.");
+    }
+
+    /// Check if two rounds are a multiple of the anchor stride apart
+    fn check_stride(&self, r1: Round, r2: Round) -> bool {
+        r1.abs_diff(r2) % self.anchor_stride() == 0
+    }
+
+    fn anchor_stride(&self) -> Round {
+        self.anchor_election.anchor_frequency().stride()
     }
 
     pub fn process_new_node(&mut self, node: &CertifiedNode) {
+        if self.is_paused() {
+            self.resume();
+            return;
+        }
         let round = node.round();
         // If the node comes from the proposal round in the current instance, it can't trigger any ordering
         if round <= self.lowest_unordered_anchor_round
-            || Self::check_parity(round, self.lowest_unordered_anchor_round)
+            || !self.check_stride(round - 1, self.lowest_unordered_anchor_round)
         {
             return;
         }
         // This node's votes can trigger an anchor from previous round to be ordered.
-        let mut start_round = round - 1;
-        while start_round <= round {
+        self.check_ordering_between(round - 1, round);
+    }
+
+    /// Order any anchor from the start round until the target round that has enough votes,
+    /// stops early if the downstream can't keep up.
+    fn check_ordering_between(&mut self, mut start_round: Round, target_round: Round) {
+        while start_round <= target_round && !self.is_paused() {
             if let Some(direct_anchor) =
-                self.find_first_anchor_with_enough_votes(start_round, round)
+                self.find_first_anchor_with_enough_votes(start_round, target_round)
             {
                 let ordered_anchor = self.find_first_anchor_to_order(direct_anchor);
                 self.finalize_order(ordered_anchor);
@@ -70,6 +137,34 @@ impl OrderRule {
         }
     }
 
+    /// Ordering may have been skipped for nodes arriving while paused, so re-check
+    /// everything from the lowest unordered anchor once the downstream catches up.
+    fn resume(&mut self) {
+        if self.flush_pending() {
+            let highest_round = self.dag.read().highest_round();
+            self.check_ordering_between(self.lowest_unordered_anchor_round, highest_round);
+        }
+    }
+
+    fn is_paused(&self) -> bool {
+        !self.pending_ordered_nodes.is_empty()
+    }
+
+    /// Try to push the pending ordered batches downstream, returns true if all of them are sent.
+    fn flush_pending(&mut self) -> bool {
+        while let Some(ordered_nodes) = self.pending_ordered_nodes.pop_front() {
+            if let Err(e) = self.ordered_nodes_sender.try_send(ordered_nodes) {
+                if e.is_full() {
+                    self.pending_ordered_nodes.push_front(e.into_inner());
+                    break;
+                }
+                error!("Failed to send ordered nodes {:?}", e);
+            }
+        }
+        PENDING_ORDERED_BATCHES.set(self.pending_ordered_nodes.len() as i64);
+        self.pending_ordered_nodes.is_empty()
+    }
+
     /// From the start round until the target_round, try to find if there's any anchor has enough votes to trigger ordering
     pub fn find_first_anchor_with_enough_votes(
         &self,
@@ -77,20 +172,36 @@ impl OrderRule {
         target_round: Round,
     ) -> Option<Arc<CertifiedNode>> {
         let dag_reader = self.dag.read();
+        let verifier = &self.epoch_state.verifier;
+        let threshold_voting_power =
+            verifier.total_voting_power() - verifier.quorum_voting_power() + 1;
         while start_round < target_round {
             let anchor_author = self.anchor_election.get_anchor(start_round);
             // I "think" it's impossible to get ordered/committed node here but to double check
-            if let Some(anchor_node) =
-                dag_reader.get_node_by_round_author(start_round, &anchor_author)
-            {
-                // f+1 or 2f+1?
-                if dag_reader
-                    .check_votes_for_node(anchor_node.metadata(), &self.epoch_state.verifier)
-                {
-                    return Some(anchor_node.clone());
-                }
+            let maybe_anchor_node =
+                dag_reader.get_node_by_round_author(start_round, &anchor_author);
+            let (voting_power_for, voting_power_against) = maybe_anchor_node
+                .map(|anchor_node| {
+                    dag_reader.voting_power_for_node(anchor_node.metadata(), verifier)
+                })
+                .unwrap_or_default();
+            // f+1 or 2f+1?
+            let met_threshold =
+                voting_power_for >= threshold_voting_power && !anchor_votes_withheld();
+            observe_anchor_votes(AnchorVoteRecord {
+                epoch: self.epoch_state.epoch,
+                round: start_round,
+                anchor: anchor_author,
+                present: maybe_anchor_node.is_some(),
+                voting_power_for,
+                voting_power_against,
+                threshold_voting_power,
+                met_threshold,
+            });
+            if met_threshold {
+                return maybe_anchor_node.cloned();
             }
-            start_round += 2;
+            start_round += self.anchor_stride();
         }
         None
     }
@@ -103,7 +214,7 @@ impl OrderRule {
         let dag_reader = self.dag.read();
         let anchor_round = current_anchor.round();
         let is_anchor = |metadata: &NodeMetadata| -> bool {
-            Self::check_parity(metadata.round(), anchor_round)
+            self.check_stride(metadata.round(), anchor_round)
                 && *metadata.author() == self.anchor_election.get_anchor(metadata.round())
         };
         while let Some(prev_anchor) = dag_reader
@@ -124,14 +235,15 @@ impl OrderRule {
 
     /// Finalize the ordering with the given anchor node, update anchor election and construct blocks for execution.
     pub fn finalize_order(&mut self, anchor: Arc<CertifiedNode>) {
-        let _failed_anchors: Vec<_> = (self.lowest_unordered_anchor_round..anchor.round())
-            .step_by(2)
+        let failed_anchors: Vec<_> = (self.lowest_unordered_anchor_round..anchor.round())
+            .step_by(self.anchor_stride() as usize)
             .map(|failed_round| self.anchor_election.get_anchor(failed_round))
             .collect();
-        assert!(Self::check_parity(
-            self.lowest_unordered_anchor_round,
-            anchor.round(),
-        ));
+        ANCHOR_OUTCOME_COUNT
+            .with_label_values(&["failed"])
+            .inc_by(failed_anchors.len() as u64);
+        ANCHOR_OUTCOME_COUNT.with_label_values(&["ordered"]).inc();
+        assert!(self.check_stride(self.lowest_unordered_anchor_round, anchor.round()));
         self.lowest_unordered_anchor_round = anchor.round() + 1;
 
         let mut dag_writer = self.dag.write();
@@ -143,8 +255,19 @@ impl OrderRule {
             })
             .collect();
         ordered_nodes.reverse();
-        if let Err(e) = self.ordered_nodes_sender.unbounded_send(ordered_nodes) {
-            error!("Failed to send ordered nodes {:?}", e);
+        drop(dag_writer);
+        if let Err(e) = self.ordered_nodes_sender.try_send(ordered_nodes) {
+            if e.is_full() {
+                warn!(
+                    "Ordered nodes channel is full, pausing ordering at round {}",
+                    anchor.round()
+                );
+                ORDERING_BACKPRESSURE_COUNT.inc();
+                self.pending_ordered_nodes.push_back(e.into_inner());
+                PENDING_ORDERED_BATCHES.set(self.pending_ordered_nodes.len() as i64);
+            } else {
+                error!("Failed to send ordered nodes {:?}", e);
+            }
         }
     }
 }
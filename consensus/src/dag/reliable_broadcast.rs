@@ -16,6 +16,7 @@ use aptos_consensus_types::common::{Author, Round};
 use aptos_infallible::RwLock;
 use aptos_logger::error;
 use aptos_types::{epoch_state::EpochState, validator_signer::ValidatorSigner};
+use fail::fail_point;
 use std::{collections::BTreeMap, mem, sync::Arc};
 use thiserror::Error as ThisError;
 
@@ -56,6 +57,20 @@ impl NodeBroadcastHandler {
         }
     }
 
+    /// Drop our vote for a node once it's certified, the certified node in the DAG is enough
+    /// to keep us from voting for an equivocating node and the vote can be re-created on demand.
+    pub fn compact_certified(&mut self, node_id: &NodeId) -> anyhow::Result<()> {
+        if let Some(votes_by_peer) = self.votes_by_round_peer.get_mut(&node_id.round()) {
+            if votes_by_peer.remove(&node_id.author()).is_some() {
+                if votes_by_peer.is_empty() {
+                    self.votes_by_round_peer.remove(&node_id.round());
+                }
+                self.storage.delete_votes(vec![node_id.clone()])?;
+            }
+        }
+        Ok(())
+    }
+
     pub fn gc_before_round(&mut self, min_round: Round) -> anyhow::Result<()> {
         let to_retain = self.votes_by_round_peer.split_off(&min_round);
         let to_delete = mem::replace(&mut self.votes_by_round_peer, to_retain);
@@ -92,6 +107,7 @@ impl NodeBroadcastHandler {
         let missing_parents: Vec<NodeCertificate> = node
             .parents()
             .iter()
+            .chain(node.weak_links())
             .filter(|parent| !dag_reader.exists(parent.metadata()))
             .cloned()
             .collect();
@@ -141,8 +157,21 @@ impl RpcHandler for NodeBroadcastHandler {
     type Response = Vote;
 
     fn process(&mut self, node: Self::Request) -> anyhow::Result<Self::Response> {
+        fail_point!("consensus::dag::vote", |_| {
+            Err(anyhow::anyhow!("Injected error in dag vote"))
+        });
         self.validate(&node)?;
 
+        // The vote is compacted once the node is certified, re-sign the certified node instead.
+        if let Some(certified_node) = self
+            .dag
+            .read()
+            .get_node_by_round_author(node.metadata().round(), node.metadata().author())
+        {
+            let signature = certified_node.sign_vote(&self.signer)?;
+            return Ok(Vote::new(certified_node.metadata().clone(), signature));
+        }
+
         let votes_by_peer = self
             .votes_by_round_peer
             .entry(node.metadata().round())
@@ -192,7 +221,7 @@ impl RpcHandler for CertifiedNodeHandler {
                 return Ok(CertifiedAck::new(node.metadata().epoch()));
             }
 
-            if !dag_reader.all_exists(node.parents_metadata()) {
+            if !dag_reader.all_exists(node.links_metadata()) {
                 // TODO(ibalajiarun): implement fetching logic.
                 bail!(CertifiedNodeHandleError::MissingParents);
             }
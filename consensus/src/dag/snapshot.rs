@@ -0,0 +1,99 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use super::{dag_store::Dag, storage::DAGStorage, types::CertifiedNode, NodeId};
+use crate::consensusdb::ConsensusDB;
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use std::{fs, path::Path};
+
+/// A dump of the DAG window of an epoch, taken from a running DAG or from the consensus db of a
+/// validator, that can be loaded back into a storage to replay the ordering locally.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DagSnapshot {
+    epoch: u64,
+    /// Certified nodes in ascending round order
+    nodes: Vec<CertifiedNode>,
+    ordered_anchor_ids: Vec<NodeId>,
+}
+
+impl DagSnapshot {
+    pub fn from_dag(epoch: u64, dag: &Dag) -> Self {
+        Self {
+            epoch,
+            nodes: dag.all_nodes().map(|node| node.as_ref().clone()).collect(),
+            ordered_anchor_ids: vec![],
+        }
+    }
+
+    pub fn from_storage(storage: &dyn DAGStorage, epoch: u64) -> anyhow::Result<Self> {
+        let mut nodes: Vec<_> = storage
+            .get_certified_nodes()?
+            .into_iter()
+            .map(|(_, node)| node)
+            .filter(|node| node.epoch() == epoch)
+            .collect();
+        nodes.sort_by_key(|node| node.round());
+        let mut ordered_anchor_ids: Vec<_> = storage
+            .get_ordered_anchor_ids()?
+            .into_iter()
+            .map(|(id, _)| id)
+            .filter(|id| id.epoch() == epoch)
+            .collect();
+        ordered_anchor_ids.sort_by_key(|id| id.round());
+        Ok(Self {
+            epoch,
+            nodes,
+            ordered_anchor_ids,
+        })
+    }
+
+    pub fn epoch(&self) -> u64 {
+        self.epoch
+    }
+
+    pub fn nodes(&self) -> &[CertifiedNode] {
+        &self.nodes
+    }
+
+    pub fn ordered_anchor_ids(&self) -> &[NodeId] {
+        &self.ordered_anchor_ids
+    }
+
+    /// Write the snapshot into the storage, a `Dag` created on top of it afterwards holds the
+    /// nodes of the snapshot.
+    pub fn restore(&self, storage: &dyn DAGStorage) -> anyhow::Result<()> {
+        for node in &self.nodes {
+            storage.save_certified_node(node)?;
+        }
+        for anchor_id in &self.ordered_anchor_ids {
+            storage.save_ordered_anchor_id(anchor_id)?;
+        }
+        Ok(())
+    }
+
+    pub fn save(&self, path: &Path) -> anyhow::Result<()> {
+        let bytes = bcs::to_bytes(self)?;
+        fs::write(path, bytes)
+            .with_context(|| format!("unable to write the DAG snapshot to {}", path.display()))
+    }
+
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let bytes = fs::read(path)
+            .with_context(|| format!("unable to read the DAG snapshot from {}", path.display()))?;
+        Ok(bcs::from_bytes(&bytes)?)
+    }
+}
+
+/// Dump the persisted DAG window of the epoch from the consensus db under `db_root_path` to
+/// `output`. Meant for debugging, the db must not be in use by a running node.
+pub fn export_dag_snapshot(
+    db_root_path: &Path,
+    epoch: u64,
+    output: &Path,
+) -> anyhow::Result<DagSnapshot> {
+    let db = ConsensusDB::new(db_root_path);
+    let snapshot = DagSnapshot::from_storage(&db, epoch)?;
+    snapshot.save(output)?;
+    Ok(snapshot)
+}
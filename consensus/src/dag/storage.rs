@@ -8,12 +8,15 @@ use crate::{
     },
     dag::{CertifiedNode, Node},
 };
+use aptos_consensus_types::common::Round;
 use aptos_crypto::HashValue;
 
 pub trait DAGStorage: Send + Sync {
     fn save_node(&self, node: &Node) -> anyhow::Result<()>;
 
-    fn delete_node(&self, digest: HashValue) -> anyhow::Result<()>;
+    fn get_pending_nodes(&self) -> anyhow::Result<Vec<(HashValue, Node)>>;
+
+    fn delete_nodes(&self, digests: Vec<HashValue>) -> anyhow::Result<()>;
 
     fn save_vote(&self, node_id: &NodeId, vote: &Vote) -> anyhow::Result<()>;
 
@@ -25,6 +28,14 @@ pub trait DAGStorage: Send + Sync {
 
     fn get_certified_nodes(&self) -> anyhow::Result<Vec<(HashValue, CertifiedNode)>>;
 
+    /// The certified nodes of the epoch from `start_round` to `end_round` included.
+    fn get_certified_nodes_in_range(
+        &self,
+        epoch: u64,
+        start_round: Round,
+        end_round: Round,
+    ) -> anyhow::Result<Vec<CertifiedNode>>;
+
     fn delete_certified_nodes(&self, digests: Vec<HashValue>) -> anyhow::Result<()>;
 
     fn save_ordered_anchor_id(&self, node_id: &NodeId) -> anyhow::Result<()>;
@@ -32,6 +43,10 @@ pub trait DAGStorage: Send + Sync {
     fn get_ordered_anchor_ids(&self) -> anyhow::Result<Vec<(NodeId, ())>>;
 
     fn delete_ordered_anchor_ids(&self, node_ids: Vec<NodeId>) -> anyhow::Result<()>;
+
+    /// Drop everything stored for the epochs before the given one, and for the given epoch
+    /// below `min_round`.
+    fn prune(&self, epoch: u64, min_round: Round) -> anyhow::Result<()>;
 }
 
 impl DAGStorage for ConsensusDB {
@@ -39,8 +54,12 @@ impl DAGStorage for ConsensusDB {
         Ok(self.save_data::<NodeSchema>(&node.digest(), node)?)
     }
 
-    fn delete_node(&self, digest: HashValue) -> anyhow::Result<()> {
-        Ok(self.delete_data::<NodeSchema>(vec![digest])?)
+    fn get_pending_nodes(&self) -> anyhow::Result<Vec<(HashValue, Node)>> {
+        Ok(self.get_all_data::<NodeSchema>()?)
+    }
+
+    fn delete_nodes(&self, digests: Vec<HashValue>) -> anyhow::Result<()> {
+        Ok(self.delete_data::<NodeSchema>(digests)?)
     }
 
     fn save_vote(&self, node_id: &NodeId, vote: &Vote) -> anyhow::Result<()> {
@@ -56,15 +75,29 @@ impl DAGStorage for ConsensusDB {
     }
 
     fn save_certified_node(&self, node: &CertifiedNode) -> anyhow::Result<()> {
-        Ok(self.save_data::<CertifiedNodeSchema>(&node.digest(), node)?)
+        Ok(ConsensusDB::save_certified_node(self, node)?)
     }
 
     fn get_certified_nodes(&self) -> anyhow::Result<Vec<(HashValue, CertifiedNode)>> {
         Ok(self.get_all_data::<CertifiedNodeSchema>()?)
     }
 
+    fn get_certified_nodes_in_range(
+        &self,
+        epoch: u64,
+        start_round: Round,
+        end_round: Round,
+    ) -> anyhow::Result<Vec<CertifiedNode>> {
+        Ok(ConsensusDB::get_certified_nodes_in_range(
+            self,
+            epoch,
+            start_round,
+            end_round,
+        )?)
+    }
+
     fn delete_certified_nodes(&self, digests: Vec<HashValue>) -> anyhow::Result<()> {
-        Ok(self.delete_data::<CertifiedNodeSchema>(digests)?)
+        Ok(ConsensusDB::delete_certified_nodes(self, digests)?)
     }
 
     fn save_ordered_anchor_id(&self, node_id: &NodeId) -> anyhow::Result<()> {
@@ -78,4 +111,8 @@ impl DAGStorage for ConsensusDB {
     fn delete_ordered_anchor_ids(&self, node_ids: Vec<NodeId>) -> anyhow::Result<()> {
         Ok(self.delete_data::<OrderedAnchorIdSchema>(node_ids)?)
     }
+
+    fn prune(&self, epoch: u64, min_round: Round) -> anyhow::Result<()> {
+        Ok(self.prune_dag_data(epoch, min_round)?)
+    }
 }
@@ -0,0 +1,284 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    dag::{
+        adapter::NotifierAdapter,
+        health::OrderingProgress,
+        storage::DAGStorage,
+        tests::{dag_test::MockStorage, helpers::new_certified_node},
+        CertifiedNode,
+    },
+    test_utils::placeholder_ledger_info,
+};
+use aptos_crypto::HashValue;
+use aptos_types::{
+    aggregate_signature::AggregateSignature,
+    block_info::BlockInfo,
+    epoch_state::EpochState,
+    ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
+    validator_verifier::random_validator_verifier,
+};
+use futures::{channel::mpsc::unbounded, StreamExt};
+use std::sync::Arc;
+
+#[tokio::test]
+async fn test_ordered_nodes_to_blocks() {
+    let (signers, validator_verifier) = random_validator_verifier(4, None, false);
+    let epoch_state = Arc::new(EpochState {
+        epoch: 1,
+        verifier: validator_verifier,
+    });
+    let storage = Arc::new(MockStorage::new());
+    let (executor_tx, mut executor_rx) = unbounded();
+    let (commit_tx, mut commit_rx) = tokio::sync::mpsc::unbounded_channel();
+    let ledger_info = placeholder_ledger_info();
+    let ordering_progress = Arc::new(OrderingProgress::default());
+    let mut adapter = NotifierAdapter::new(
+        epoch_state,
+        ledger_info.clone(),
+        executor_tx,
+        commit_tx,
+        storage.clone(),
+        ordering_progress.clone(),
+    );
+
+    let round_one: Vec<_> = signers
+        .iter()
+        .map(|signer| Arc::new(new_certified_node(1, signer.author(), vec![])))
+        .collect();
+    let anchor = Arc::new(new_certified_node(
+        2,
+        signers[1].author(),
+        round_one.iter().map(|node| node.certificate()).collect(),
+    ));
+    let mut ordered_nodes = round_one;
+    ordered_nodes.push(anchor.clone());
+    for node in &ordered_nodes {
+        storage.save_certified_node(node).unwrap();
+    }
+
+    adapter.send_ordered_nodes(ordered_nodes).unwrap();
+    let ordered = executor_rx.next().await.unwrap();
+    assert_eq!(ordered.ordered_blocks.len(), 1);
+    let block = ordered.ordered_blocks[0].clone();
+    assert_eq!(block.round(), anchor.round());
+    assert_eq!(block.block().author(), Some(*anchor.author()));
+    assert_eq!(block.parent_id(), ledger_info.commit_info().id());
+    assert_eq!(ordered.ordered_proof.commit_info(), &block.block_info());
+    assert_eq!(ordering_progress.last_ordered_round(), anchor.round());
+
+    // committing the block cleans up the nodes and notifies the order rule
+    (ordered.callback)(&[Arc::new(block.clone())], ordered.ordered_proof.clone());
+    assert!(storage.get_certified_nodes().unwrap().is_empty());
+    assert_eq!(commit_rx.recv().await, Some(anchor.round()));
+
+    // the next block is chained to the previous one
+    let next_anchor: Arc<CertifiedNode> =
+        Arc::new(new_certified_node(4, signers[2].author(), vec![
+            anchor.certificate()
+        ]));
+    adapter.send_ordered_nodes(vec![next_anchor]).unwrap();
+    let ordered = executor_rx.next().await.unwrap();
+    assert_eq!(ordered.ordered_blocks[0].parent_id(), block.id());
+}
+
+#[tokio::test]
+async fn test_stop_after_epoch_ending_commit() {
+    let (signers, validator_verifier) = random_validator_verifier(4, None, false);
+    let epoch_state = Arc::new(EpochState {
+        epoch: 1,
+        verifier: validator_verifier,
+    });
+    let (executor_tx, mut executor_rx) = unbounded();
+    let (commit_tx, _commit_rx) = tokio::sync::mpsc::unbounded_channel();
+    let mut adapter = NotifierAdapter::new(
+        epoch_state,
+        placeholder_ledger_info(),
+        executor_tx,
+        commit_tx,
+        Arc::new(MockStorage::new()),
+        Arc::new(OrderingProgress::default()),
+    );
+
+    let anchor = Arc::new(new_certified_node(2, signers[0].author(), vec![]));
+    adapter.send_ordered_nodes(vec![anchor]).unwrap();
+    let ordered = executor_rx.next().await.unwrap();
+    assert!(adapter.epoch_ending_ledger_info().is_none());
+
+    // the executed block reconfigures
+    let block_info = ordered.ordered_proof.commit_info();
+    let commit_info = BlockInfo::new(
+        block_info.epoch(),
+        block_info.round(),
+        block_info.id(),
+        HashValue::random(),
+        10,
+        block_info.timestamp_usecs(),
+        Some(EpochState::empty()),
+    );
+    let commit_decision = LedgerInfoWithSignatures::new(
+        LedgerInfo::new(
+            commit_info,
+            ordered.ordered_proof.ledger_info().consensus_data_hash(),
+        ),
+        AggregateSignature::empty(),
+    );
+    (ordered.callback)(
+        &[Arc::new(ordered.ordered_blocks[0].clone())],
+        commit_decision.clone(),
+    );
+    assert_eq!(adapter.epoch_ending_ledger_info(), Some(commit_decision));
+
+    // nothing is sent to execution for the rest of the epoch
+    let next_anchor = Arc::new(new_certified_node(4, signers[1].author(), vec![]));
+    adapter.send_ordered_nodes(vec![next_anchor]).unwrap();
+    assert!(executor_rx.try_next().is_err());
+}
+
+#[tokio::test]
+async fn test_same_block_id_after_restart() {
+    let (signers, validator_verifier) = random_validator_verifier(4, None, false);
+    let epoch_state = Arc::new(EpochState {
+        epoch: 1,
+        verifier: validator_verifier,
+    });
+    let new_adapter = |ledger_info: LedgerInfo| {
+        let (executor_tx, executor_rx) = unbounded();
+        let (commit_tx, _commit_rx) = tokio::sync::mpsc::unbounded_channel();
+        let adapter = NotifierAdapter::new(
+            epoch_state.clone(),
+            ledger_info,
+            executor_tx,
+            commit_tx,
+            Arc::new(MockStorage::new()),
+            Arc::new(OrderingProgress::default()),
+        );
+        (adapter, executor_rx)
+    };
+
+    let (mut adapter, mut executor_rx) = new_adapter(placeholder_ledger_info());
+    let anchor = Arc::new(new_certified_node(2, signers[0].author(), vec![]));
+    adapter.send_ordered_nodes(vec![anchor]).unwrap();
+    let ordered = executor_rx.next().await.unwrap();
+
+    // restart from the executed ledger info of the block
+    let block_info = ordered.ordered_proof.commit_info();
+    let committed_info = BlockInfo::new(
+        block_info.epoch(),
+        block_info.round(),
+        block_info.id(),
+        HashValue::random(),
+        10,
+        block_info.timestamp_usecs(),
+        None,
+    );
+    let (mut restarted, mut restarted_rx) =
+        new_adapter(LedgerInfo::new(committed_info, HashValue::zero()));
+
+    let next_anchor = Arc::new(new_certified_node(4, signers[1].author(), vec![]));
+    adapter
+        .send_ordered_nodes(vec![next_anchor.clone()])
+        .unwrap();
+    restarted.send_ordered_nodes(vec![next_anchor]).unwrap();
+    let block = executor_rx.next().await.unwrap().ordered_blocks[0].clone();
+    let restarted_block = restarted_rx.next().await.unwrap().ordered_blocks[0].clone();
+    assert_eq!(block.id(), restarted_block.id());
+}
+
+#[tokio::test]
+async fn test_ordering_ahead_of_execution() {
+    let (signers, validator_verifier) = random_validator_verifier(4, None, false);
+    let epoch_state = Arc::new(EpochState {
+        epoch: 1,
+        verifier: validator_verifier,
+    });
+    let (executor_tx, mut executor_rx) = unbounded();
+    let (commit_tx, mut commit_rx) = tokio::sync::mpsc::unbounded_channel();
+    let ledger_info = placeholder_ledger_info();
+    let mut adapter = NotifierAdapter::new(
+        epoch_state,
+        ledger_info.clone(),
+        executor_tx,
+        commit_tx,
+        Arc::new(MockStorage::new()),
+        Arc::new(OrderingProgress::default()),
+    );
+
+    // keep ordering while none of the blocks is executed
+    let mut batches = vec![];
+    for (index, round) in [2, 4, 6].into_iter().enumerate() {
+        let anchor = Arc::new(new_certified_node(round, signers[index].author(), vec![]));
+        adapter.send_ordered_nodes(vec![anchor]).unwrap();
+        batches.push(executor_rx.next().await.unwrap());
+    }
+    let outstanding = adapter.outstanding_blocks();
+    assert_eq!(
+        outstanding
+            .iter()
+            .map(|block| block.round)
+            .collect::<Vec<_>>(),
+        vec![2, 4, 6]
+    );
+    assert_eq!(outstanding[0].parent_id, ledger_info.commit_info().id());
+    for (parent, block) in outstanding.iter().zip(outstanding.iter().skip(1)) {
+        assert_eq!(block.parent_id, parent.block_id);
+    }
+    for (batch, block) in batches.iter().zip(&outstanding) {
+        assert_eq!(batch.ordered_blocks[0].id(), block.block_id);
+    }
+
+    // the first two blocks commit together, the last one is still executing
+    let committed: Vec<_> = batches[..2]
+        .iter()
+        .map(|batch| Arc::new(batch.ordered_blocks[0].clone()))
+        .collect();
+    let second = batches.remove(1);
+    (second.callback)(&committed, second.ordered_proof);
+    assert_eq!(commit_rx.recv().await, Some(2));
+    assert_eq!(commit_rx.recv().await, Some(4));
+    assert_eq!(adapter.outstanding_blocks(), outstanding[2..].to_vec());
+}
+
+#[tokio::test]
+async fn test_commit_notifications_not_dropped() {
+    let (signers, validator_verifier) = random_validator_verifier(4, None, false);
+    let epoch_state = Arc::new(EpochState {
+        epoch: 1,
+        verifier: validator_verifier,
+    });
+    let (executor_tx, mut executor_rx) = unbounded();
+    let (commit_tx, mut commit_rx) = tokio::sync::mpsc::unbounded_channel();
+    let mut adapter = NotifierAdapter::new(
+        epoch_state,
+        placeholder_ledger_info(),
+        executor_tx,
+        commit_tx,
+        Arc::new(MockStorage::new()),
+        Arc::new(OrderingProgress::default()),
+    );
+
+    // more blocks commit at once than the ordered nodes channel holds, while the order rule
+    // doesn't read the notifications
+    let rounds: Vec<_> = (1..=50).map(|index| index * 2).collect();
+    let mut batches = vec![];
+    for (index, round) in rounds.iter().enumerate() {
+        let author = signers[index % signers.len()].author();
+        let anchor = Arc::new(new_certified_node(*round, author, vec![]));
+        adapter.send_ordered_nodes(vec![anchor]).unwrap();
+        batches.push(executor_rx.next().await.unwrap());
+    }
+    let committed: Vec<_> = batches
+        .iter()
+        .map(|batch| Arc::new(batch.ordered_blocks[0].clone()))
+        .collect();
+    let last = batches.pop().unwrap();
+    (last.callback)(&committed, last.ordered_proof);
+
+    let mut notified = vec![];
+    while let Ok(round) = commit_rx.try_recv() {
+        notified.push(round);
+    }
+    assert_eq!(notified, rounds);
+    assert!(adapter.outstanding_blocks().is_empty());
+}
@@ -0,0 +1,83 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::dag::anchor_election::{
+    AnchorElection, AnchorFrequency, CachedAnchorElection, RoundRobinAnchorElection,
+};
+use aptos_consensus_types::common::{Author, Round};
+use std::sync::{
+    atomic::{AtomicU32, Ordering},
+    Arc,
+};
+
+struct MockAnchorElection {
+    validators: Vec<Author>,
+    asked: Arc<AtomicU32>,
+    committed: Round,
+}
+
+impl AnchorElection for MockAnchorElection {
+    fn get_anchor(&self, round: Round) -> Author {
+        self.asked.fetch_add(1, Ordering::SeqCst);
+        self.validators[(round + self.committed) as usize % self.validators.len()]
+    }
+
+    fn anchor_frequency(&self) -> AnchorFrequency {
+        AnchorFrequency::EveryRound
+    }
+
+    fn commit(&mut self, round: Round) {
+        self.committed = round;
+    }
+}
+
+#[test]
+fn test_cached_anchor_election() {
+    let asked = Arc::new(AtomicU32::new(0));
+    let validators: Vec<Author> = (0..4).map(|_| Author::random()).collect();
+    let mut election = CachedAnchorElection::new(Box::new(MockAnchorElection {
+        validators: validators.clone(),
+        asked: asked.clone(),
+        committed: 0,
+    }));
+
+    assert_eq!(election.get_anchor(1), validators[1]);
+    assert_eq!(election.get_anchor(1), validators[1]);
+    assert_eq!(asked.load(Ordering::SeqCst), 1);
+
+    assert_eq!(election.get_anchor(2), validators[2]);
+    assert_eq!(election.get_anchor(1), validators[1]);
+    assert_eq!(asked.load(Ordering::SeqCst), 2);
+
+    // commit invalidates the cache as later anchors may change
+    election.commit(1);
+    assert_eq!(election.get_anchor(2), validators[3]);
+    assert_eq!(election.get_anchor(2), validators[3]);
+    assert_eq!(asked.load(Ordering::SeqCst), 3);
+}
+
+#[test]
+fn test_round_robin_anchor_frequency() {
+    let validators: Vec<Author> = (0..4).map(|_| Author::random()).collect();
+
+    let election = RoundRobinAnchorElection::new(validators.clone(), AnchorFrequency::EveryRound);
+    let anchors: Vec<_> = (0..5).map(|round| election.get_anchor(round)).collect();
+    assert_eq!(anchors, vec![
+        validators[0],
+        validators[1],
+        validators[2],
+        validators[3],
+        validators[0],
+    ]);
+
+    let election =
+        RoundRobinAnchorElection::new(validators.clone(), AnchorFrequency::EveryOtherRound);
+    let anchors: Vec<_> = (0..5).map(|round| election.get_anchor(round)).collect();
+    assert_eq!(anchors, vec![
+        validators[0],
+        validators[0],
+        validators[1],
+        validators[1],
+        validators[2],
+    ]);
+}
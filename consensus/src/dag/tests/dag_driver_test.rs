@@ -0,0 +1,228 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    dag::{
+        dag_driver::DagDriver,
+        dag_network::RpcHandler,
+        dag_store::Dag,
+        storage::DAGStorage,
+        tests::{dag_test::MockStorage, helpers::new_certified_node},
+        types::{CertifiedNode, DAGMessage, Node},
+    },
+    test_utils::MockPayloadManager,
+    util::mock_time_service::SimulatedTimeService,
+};
+use anyhow::bail;
+use aptos_consensus_types::common::{Author, Payload, Round};
+use aptos_crypto::{ed25519::Ed25519PrivateKey, PrivateKey, Uniform};
+use aptos_infallible::RwLock;
+use aptos_reliable_broadcast::{RBNetworkSender, ReliableBroadcast};
+use aptos_types::{
+    account_address::AccountAddress,
+    aggregate_signature::AggregateSignature,
+    chain_id::ChainId,
+    epoch_state::EpochState,
+    transaction::{RawTransaction, Script, SignedTransaction, TransactionPayload},
+    validator_verifier::random_validator_verifier,
+};
+use async_trait::async_trait;
+use std::{sync::Arc, time::Duration};
+use tokio_retry::strategy::ExponentialBackoff;
+
+struct MockNetworkSender;
+
+#[async_trait]
+impl RBNetworkSender<DAGMessage> for MockNetworkSender {
+    async fn send_rpc(
+        &self,
+        _receiver: Author,
+        _message: DAGMessage,
+        _timeout: Duration,
+    ) -> anyhow::Result<DAGMessage> {
+        bail!("unreachable")
+    }
+}
+
+fn new_txn() -> SignedTransaction {
+    let private_key = Ed25519PrivateKey::generate_for_testing();
+    let raw_txn = RawTransaction::new(
+        AccountAddress::random(),
+        0,
+        TransactionPayload::Script(Script::new(vec![], vec![], vec![])),
+        0,
+        0,
+        0,
+        ChainId::test(),
+    );
+    raw_txn
+        .sign(&private_key, private_key.public_key())
+        .unwrap()
+        .into_inner()
+}
+
+fn new_node_with_payload(round: Round, author: Author, txns: Vec<SignedTransaction>) -> Node {
+    Node::new(
+        1,
+        round,
+        author,
+        0,
+        Payload::DirectMempool(txns),
+        vec![],
+        vec![],
+    )
+}
+
+#[tokio::test]
+async fn test_repropose_payload_after_restart() {
+    let (signers, validator_verifier) = random_validator_verifier(4, None, false);
+    let epoch_state = Arc::new(EpochState {
+        epoch: 1,
+        verifier: validator_verifier,
+    });
+    let author = signers[0].author();
+    let storage = Arc::new(MockStorage::new());
+    let txns: Vec<_> = (0..3).map(|_| new_txn()).collect();
+
+    // our certified node of round 0 isn't committed yet
+    let certified = new_node_with_payload(0, author, vec![txns[0].clone()]);
+    storage.save_node(&certified).unwrap();
+    let dag = Arc::new(RwLock::new(Dag::new(epoch_state.clone(), storage.clone())));
+    dag.write()
+        .add_node(CertifiedNode::new(
+            certified.clone(),
+            AggregateSignature::empty(),
+        ))
+        .unwrap();
+    // the node of round 1 got superseded before it was certified
+    let lost = new_node_with_payload(1, author, vec![txns[0].clone(), txns[1].clone()]);
+    storage.save_node(&lost).unwrap();
+    // the node of round 2 was still being broadcast
+    let pending = new_node_with_payload(2, author, vec![txns[2].clone()]);
+    storage.save_node(&pending).unwrap();
+
+    let (certified_node_tx, _certified_node_rx) = tokio::sync::mpsc::channel(10);
+    let reliable_broadcast = Arc::new(ReliableBroadcast::new(
+        signers.iter().map(|signer| signer.author()).collect(),
+        Arc::new(MockNetworkSender),
+        ExponentialBackoff::from_millis(10).max_delay(Duration::from_secs(1)),
+        aptos_time_service::TimeService::real(),
+    ));
+    let mut driver = DagDriver::new(
+        author,
+        epoch_state,
+        dag,
+        Arc::new(MockPayloadManager::new(None)),
+        reliable_broadcast,
+        Arc::new(SimulatedTimeService::new()),
+        storage.clone(),
+        certified_node_tx,
+        false,
+    );
+
+    let pending_rounds = |storage: &MockStorage| {
+        let mut rounds: Vec<_> = storage
+            .get_pending_nodes()
+            .unwrap()
+            .into_iter()
+            .map(|(_, node)| node.round())
+            .collect();
+        rounds.sort();
+        rounds
+    };
+    // the lost node is kept until its payload is proposed again
+    assert_eq!(pending_rounds(&storage), vec![0, 1, 2]);
+
+    driver.enter_new_round(3, vec![]);
+    assert_eq!(pending_rounds(&storage), vec![0, 2, 3]);
+    let (_, new_node) = storage
+        .get_pending_nodes()
+        .unwrap()
+        .into_iter()
+        .find(|(_, node)| node.round() == 3)
+        .unwrap();
+    // the transaction of the certified node isn't proposed twice
+    assert_eq!(
+        new_node.payload(),
+        &Payload::DirectMempool(vec![txns[1].clone()])
+    );
+
+    // nothing left to recover
+    driver.enter_new_round(4, vec![]);
+    assert!(storage
+        .get_pending_nodes()
+        .unwrap()
+        .into_iter()
+        .any(|(_, node)| node.round() == 4 && node.payload().is_empty()));
+}
+
+#[tokio::test]
+async fn test_recovered_payload_skips_nodes_missing_parents() {
+    let (signers, validator_verifier) = random_validator_verifier(4, None, false);
+    let epoch_state = Arc::new(EpochState {
+        epoch: 1,
+        verifier: validator_verifier,
+    });
+    let author = signers[0].author();
+    let storage = Arc::new(MockStorage::new());
+    let txn = new_txn();
+
+    // the node of round 1 got superseded before it was certified, round 2 is still pending
+    let lost = new_node_with_payload(1, author, vec![txn.clone()]);
+    storage.save_node(&lost).unwrap();
+    let pending = new_node_with_payload(2, author, vec![]);
+    storage.save_node(&pending).unwrap();
+
+    let dag = Arc::new(RwLock::new(Dag::new(epoch_state.clone(), storage.clone())));
+    let (certified_node_tx, _certified_node_rx) = tokio::sync::mpsc::channel(10);
+    let reliable_broadcast = Arc::new(ReliableBroadcast::new(
+        signers.iter().map(|signer| signer.author()).collect(),
+        Arc::new(MockNetworkSender),
+        ExponentialBackoff::from_millis(10).max_delay(Duration::from_secs(1)),
+        aptos_time_service::TimeService::real(),
+    ));
+    let mut driver = DagDriver::new(
+        author,
+        epoch_state,
+        dag,
+        Arc::new(MockPayloadManager::new(None)),
+        reliable_broadcast,
+        Arc::new(SimulatedTimeService::new()),
+        storage.clone(),
+        certified_node_tx,
+        false,
+    );
+
+    // another validator proposed the same transaction, its node waits for its parents
+    let missing_parent = new_certified_node(1, signers[2].author(), vec![]);
+    let node = Node::new(
+        1,
+        2,
+        signers[1].author(),
+        0,
+        Payload::DirectMempool(vec![txn]),
+        vec![missing_parent.certificate()],
+        vec![],
+    );
+    assert!(driver
+        .process(CertifiedNode::new(node, AggregateSignature::empty()))
+        .is_err());
+
+    // the transaction isn't proposed again and the lost node is dropped
+    driver.enter_new_round(3, vec![]);
+    let mut pending_nodes: Vec<_> = storage
+        .get_pending_nodes()
+        .unwrap()
+        .into_iter()
+        .map(|(_, node)| node)
+        .collect();
+    pending_nodes.sort_by_key(|node| node.round());
+    assert_eq!(
+        pending_nodes
+            .iter()
+            .map(|node| node.round())
+            .collect::<Vec<_>>(),
+        vec![2, 3]
+    );
+    assert!(pending_nodes[1].payload().is_empty());
+}
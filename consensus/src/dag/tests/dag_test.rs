@@ -8,11 +8,12 @@ use crate::dag::{
     types::{CertifiedNode, DagSnapshotBitmask, Node},
     NodeId, Vote,
 };
+use aptos_consensus_types::common::{Payload, Round};
 use aptos_crypto::HashValue;
 use aptos_infallible::Mutex;
 use aptos_types::{
-    epoch_state::EpochState, validator_signer::ValidatorSigner,
-    validator_verifier::random_validator_verifier,
+    aggregate_signature::AggregateSignature, epoch_state::EpochState,
+    validator_signer::ValidatorSigner, validator_verifier::random_validator_verifier,
 };
 use std::{collections::HashMap, sync::Arc};
 
@@ -20,6 +21,7 @@ pub struct MockStorage {
     node_data: Mutex<HashMap<HashValue, Node>>,
     vote_data: Mutex<HashMap<NodeId, Vote>>,
     certified_node_data: Mutex<HashMap<HashValue, CertifiedNode>>,
+    ordered_anchor_id_data: Mutex<HashMap<NodeId, ()>>,
 }
 
 impl MockStorage {
@@ -28,6 +30,7 @@ impl MockStorage {
             node_data: Mutex::new(HashMap::new()),
             vote_data: Mutex::new(HashMap::new()),
             certified_node_data: Mutex::new(HashMap::new()),
+            ordered_anchor_id_data: Mutex::new(HashMap::new()),
         }
     }
 }
@@ -38,8 +41,14 @@ impl DAGStorage for MockStorage {
         Ok(())
     }
 
-    fn delete_node(&self, digest: HashValue) -> anyhow::Result<()> {
-        self.node_data.lock().remove(&digest);
+    fn get_pending_nodes(&self) -> anyhow::Result<Vec<(HashValue, Node)>> {
+        Ok(self.node_data.lock().clone().into_iter().collect())
+    }
+
+    fn delete_nodes(&self, digests: Vec<HashValue>) -> anyhow::Result<()> {
+        for digest in digests {
+            self.node_data.lock().remove(&digest);
+        }
         Ok(())
     }
 
@@ -75,6 +84,23 @@ impl DAGStorage for MockStorage {
             .collect())
     }
 
+    fn get_certified_nodes_in_range(
+        &self,
+        epoch: u64,
+        start_round: Round,
+        end_round: Round,
+    ) -> anyhow::Result<Vec<CertifiedNode>> {
+        Ok(self
+            .certified_node_data
+            .lock()
+            .values()
+            .filter(|node| {
+                node.epoch() == epoch && (start_round..=end_round).contains(&node.round())
+            })
+            .cloned()
+            .collect())
+    }
+
     fn delete_certified_nodes(&self, digests: Vec<HashValue>) -> anyhow::Result<()> {
         for digest in digests {
             self.certified_node_data.lock().remove(&digest);
@@ -82,16 +108,46 @@ impl DAGStorage for MockStorage {
         Ok(())
     }
 
-    fn save_ordered_anchor_id(&self, _node_id: &NodeId) -> anyhow::Result<()> {
-        todo!()
+    fn save_ordered_anchor_id(&self, node_id: &NodeId) -> anyhow::Result<()> {
+        self.ordered_anchor_id_data
+            .lock()
+            .insert(node_id.clone(), ());
+        Ok(())
     }
 
     fn get_ordered_anchor_ids(&self) -> anyhow::Result<Vec<(NodeId, ())>> {
-        todo!()
+        Ok(self
+            .ordered_anchor_id_data
+            .lock()
+            .clone()
+            .into_iter()
+            .collect())
+    }
+
+    fn delete_ordered_anchor_ids(&self, node_ids: Vec<NodeId>) -> anyhow::Result<()> {
+        for node_id in node_ids {
+            self.ordered_anchor_id_data.lock().remove(&node_id);
+        }
+        Ok(())
     }
 
-    fn delete_ordered_anchor_ids(&self, _node_ids: Vec<NodeId>) -> anyhow::Result<()> {
-        todo!()
+    fn prune(&self, epoch: u64, min_round: Round) -> anyhow::Result<()> {
+        let is_expired = |node_epoch: u64, round: Round| {
+            node_epoch < epoch || (node_epoch == epoch && round < min_round)
+        };
+        self.node_data
+            .lock()
+            .retain(|_, node| !is_expired(node.epoch(), node.round()));
+        self.vote_data
+            .lock()
+            .retain(|node_id, _| !is_expired(node_id.epoch(), node_id.round()));
+        self.certified_node_data
+            .lock()
+            .retain(|_, node| !is_expired(node.epoch(), node.round()));
+        self.ordered_anchor_id_data
+            .lock()
+            .retain(|node_id, _| !is_expired(node_id.epoch(), node_id.round()));
+        Ok(())
     }
 }
 
@@ -237,3 +293,96 @@ fn test_dag_bitmask() {
     assert_eq!(dag.bitmask(15), DagSnapshotBitmask::new(5, vec![]));
     assert_eq!(dag.bitmask(6), DagSnapshotBitmask::new(5, vec![]));
 }
+
+#[test]
+fn test_voting_power_for_node() {
+    let (signers, epoch_state, mut dag, _) = setup();
+
+    let round_one: Vec<_> = signers
+        .iter()
+        .map(|signer| new_certified_node(1, signer.author(), vec![]))
+        .collect();
+    for node in &round_one {
+        assert!(dag.add_node(node.clone()).is_ok());
+    }
+
+    // Round 2 - nodes 0, 1 link to 0, 1, 2 and node 2 links to 1, 2, 3
+    for signer in &signers[0..2] {
+        let parents = round_one[0..3]
+            .iter()
+            .map(|node| node.certificate())
+            .collect();
+        assert!(dag
+            .add_node(new_certified_node(2, signer.author(), parents))
+            .is_ok());
+    }
+    let parents = round_one[1..4]
+        .iter()
+        .map(|node| node.certificate())
+        .collect();
+    assert!(dag
+        .add_node(new_certified_node(2, signers[2].author(), parents))
+        .is_ok());
+
+    assert_eq!(
+        dag.voting_power_for_node(round_one[0].metadata(), &epoch_state.verifier),
+        (2, 1)
+    );
+    assert_eq!(
+        dag.voting_power_for_node(round_one[1].metadata(), &epoch_state.verifier),
+        (3, 0)
+    );
+    assert_eq!(
+        dag.voting_power_for_node(round_one[3].metadata(), &epoch_state.verifier),
+        (1, 2)
+    );
+}
+
+#[test]
+fn test_weak_links() {
+    let (signers, _, mut dag, _) = setup();
+
+    let round_one: Vec<_> = signers
+        .iter()
+        .map(|signer| new_certified_node(1, signer.author(), vec![]))
+        .collect();
+    for node in &round_one {
+        assert!(dag.add_node(node.clone()).is_ok());
+    }
+
+    // Round 2 - nodes 0, 1, 2 link to 0, 1, 2, node 3 of round 1 is left behind
+    let parents: Vec<_> = round_one[0..3]
+        .iter()
+        .map(|node| node.certificate())
+        .collect();
+    let round_two: Vec<_> = signers[0..3]
+        .iter()
+        .map(|signer| new_certified_node(2, signer.author(), parents.clone()))
+        .collect();
+    for node in &round_two {
+        assert!(dag.add_node(node.clone()).is_ok());
+    }
+
+    let strong_links: Vec<_> = round_two.iter().map(|node| node.certificate()).collect();
+    let weak_links = dag.get_weak_links(&strong_links);
+    assert_eq!(weak_links, vec![round_one[3].certificate()]);
+
+    // the ordering traverses the weak links
+    let node = Node::new(
+        1,
+        3,
+        signers[0].author(),
+        0,
+        Payload::empty(false),
+        strong_links,
+        weak_links,
+    );
+    let node = CertifiedNode::new(node, AggregateSignature::empty());
+    assert!(dag.add_node(node.clone()).is_ok());
+    let reachable: Vec<_> = dag
+        .reachable(&[node.metadata().clone()], None, |_| true)
+        .map(|node_status| node_status.as_node().digest())
+        .collect();
+    assert!(reachable.contains(&round_one[3].digest()));
+    assert_eq!(reachable.len(), 1 + round_two.len() + round_one.len());
+}
@@ -0,0 +1,430 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Failures the fail points in the DAG components inject in forge and twins runs. The scenarios
+//! are reproduced directly against the handlers and the order rule to check that ordering stays
+//! safe, and with the `failpoints` feature the fail points themselves are exercised.
+
+use crate::{
+    dag::{
+        anchor_election::{AnchorElection, AnchorFrequency, RoundRobinAnchorElection},
+        dag_store::Dag,
+        order_rule::OrderRule,
+        reliable_broadcast::NodeBroadcastHandler,
+        tests::{
+            dag_test::MockStorage,
+            helpers::{new_certified_node, new_node},
+        },
+        types::{DAGMessage, SignatureBuilder},
+        CertifiedNode, RpcHandler,
+    },
+    test_utils::placeholder_ledger_info,
+};
+use aptos_consensus_types::common::{Author, Round};
+use aptos_infallible::RwLock;
+use aptos_reliable_broadcast::BroadcastStatus;
+use aptos_types::{
+    epoch_state::EpochState, validator_signer::ValidatorSigner,
+    validator_verifier::random_validator_verifier,
+};
+use futures_channel::mpsc::channel;
+use std::sync::Arc;
+
+fn setup() -> (Vec<ValidatorSigner>, Arc<EpochState>) {
+    let (signers, validator_verifier) = random_validator_verifier(4, None, false);
+    let epoch_state = Arc::new(EpochState {
+        epoch: 1,
+        verifier: validator_verifier,
+    });
+    (signers, epoch_state)
+}
+
+/// Every validator has a node in every round, linking the nodes of the previous round for
+/// which `linked(round, index)` holds.
+fn build_dag(
+    authors: &[Author],
+    num_rounds: Round,
+    linked: impl Fn(Round, usize) -> bool,
+) -> Vec<CertifiedNode> {
+    let mut nodes = vec![];
+    let mut previous_round: Vec<CertifiedNode> = vec![];
+    for round in 1..=num_rounds {
+        let parents: Vec<_> = previous_round
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| linked(round - 1, *index))
+            .map(|(_, node)| node.certificate())
+            .collect();
+        previous_round = authors
+            .iter()
+            .map(|author| new_certified_node(round, *author, parents.clone()))
+            .collect();
+        nodes.extend(previous_round.iter().cloned());
+    }
+    nodes
+}
+
+/// Feed the nodes to a fresh DAG and order rule, returns the (round, author) of the ordered nodes.
+fn order(epoch_state: Arc<EpochState>, nodes: &[CertifiedNode]) -> Vec<(Round, Author)> {
+    let dag = Arc::new(RwLock::new(Dag::new(
+        epoch_state.clone(),
+        Arc::new(MockStorage::new()),
+    )));
+    let anchor_election = Box::new(RoundRobinAnchorElection::new(
+        epoch_state.verifier.get_ordered_account_addresses(),
+        AnchorFrequency::EveryOtherRound,
+    ));
+    let (tx, mut rx) = channel(nodes.len());
+    let mut order_rule = OrderRule::new(
+        epoch_state,
+        placeholder_ledger_info(),
+        dag.clone(),
+        anchor_election,
+        tx,
+    );
+    for node in nodes {
+        let node = dag.write().add_node(node.clone()).unwrap();
+        order_rule.process_new_node(&node);
+    }
+    let mut ordered = vec![];
+    while let Ok(Some(ordered_nodes)) = rx.try_next() {
+        ordered.extend(
+            ordered_nodes
+                .iter()
+                .map(|node| (node.round(), *node.author())),
+        );
+    }
+    ordered
+}
+
+fn assert_prefix_consistent(a: &[(Round, Author)], b: &[(Round, Author)]) {
+    let len = a.len().min(b.len());
+    assert_eq!(a[..len], b[..len]);
+}
+
+#[test]
+fn test_equivocating_node_never_certified() {
+    let (signers, epoch_state) = setup();
+    let node = new_node(0, 10, signers[0].author(), vec![]);
+    let equivocating_node = new_node(0, 20, signers[0].author(), vec![]);
+
+    // half of the validators see each node first
+    let mut builder = SignatureBuilder::new(node.metadata().clone(), epoch_state.clone());
+    let mut equivocating_builder =
+        SignatureBuilder::new(equivocating_node.metadata().clone(), epoch_state.clone());
+    let mut certified = false;
+    for (index, signer) in signers.iter().enumerate() {
+        let storage = Arc::new(MockStorage::new());
+        let dag = Arc::new(RwLock::new(Dag::new(epoch_state.clone(), storage.clone())));
+        let mut handler =
+            NodeBroadcastHandler::new(dag, signer.clone(), epoch_state.clone(), storage);
+        let (first, second) = if index < 2 {
+            (node.clone(), equivocating_node.clone())
+        } else {
+            (equivocating_node.clone(), node.clone())
+        };
+        let first_vote = handler.process(first).unwrap();
+        let second_vote = handler.process(second).unwrap();
+        // a validator only ever votes for the first node it sees
+        assert_eq!(first_vote, second_vote);
+
+        for vote in [first_vote, second_vote] {
+            if let Ok(Some(_)) =
+                BroadcastStatus::<DAGMessage>::add(&mut builder, signer.author(), vote.clone())
+            {
+                certified = true;
+            }
+            if let Ok(Some(_)) =
+                BroadcastStatus::<DAGMessage>::add(&mut equivocating_builder, signer.author(), vote)
+            {
+                certified = true;
+            }
+        }
+    }
+    // neither node gathers a quorum of votes
+    assert!(!certified);
+}
+
+#[test]
+fn test_withheld_votes_keep_ordering_consistent() {
+    let (signers, epoch_state) = setup();
+    let authors: Vec<_> = signers.iter().map(|signer| signer.author()).collect();
+    let anchor_election = RoundRobinAnchorElection::new(
+        epoch_state.verifier.get_ordered_account_addresses(),
+        AnchorFrequency::EveryOtherRound,
+    );
+    let anchor = anchor_election.get_anchor(2);
+    let anchor_index = authors.iter().position(|author| *author == anchor).unwrap();
+
+    // nobody votes for the anchor of round 2
+    let nodes = build_dag(&authors, 10, |round, index| {
+        !(round == 2 && index == anchor_index)
+    });
+    let ordered = order(epoch_state.clone(), &nodes);
+    assert!(!ordered.is_empty());
+    assert!(!ordered.contains(&(2, anchor)));
+
+    // the nodes arrive in a different order on another validator
+    let mut reordered = vec![];
+    for round_nodes in nodes.chunks(authors.len()) {
+        reordered.extend(round_nodes.iter().rev().cloned());
+    }
+    assert_prefix_consistent(&ordered, &order(epoch_state, &reordered));
+}
+
+#[test]
+fn test_partition_keeps_ordering_consistent() {
+    let (signers, epoch_state) = setup();
+    let authors: Vec<_> = signers.iter().map(|signer| signer.author()).collect();
+
+    // the last validator is partitioned away for rounds 1 to 6, nobody links its nodes
+    let partitioned = |round: Round, index: usize| index == 3 && (1..=6).contains(&round);
+    let nodes = build_dag(&authors, 12, |round, index| !partitioned(round, index));
+    let ordered = order(epoch_state.clone(), &nodes);
+
+    // a validator on the other side of the partition never receives those nodes
+    let nodes_without_partitioned: Vec<_> = nodes
+        .iter()
+        .filter(|node| {
+            let index = authors
+                .iter()
+                .position(|author| author == node.author())
+                .unwrap();
+            !partitioned(node.round(), index)
+        })
+        .cloned()
+        .collect();
+    let ordered_without_partitioned = order(epoch_state, &nodes_without_partitioned);
+
+    assert!(!ordered.is_empty());
+    assert_prefix_consistent(&ordered, &ordered_without_partitioned);
+}
+
+#[cfg(feature = "failpoints")]
+mod fail_point_tests {
+    use super::*;
+    use crate::{
+        dag::{dag_driver::DagDriver, storage::DAGStorage, types::Node},
+        test_utils::MockPayloadManager,
+        util::mock_time_service::SimulatedTimeService,
+    };
+    use anyhow::bail;
+    use aptos_crypto::HashValue;
+    use aptos_infallible::Mutex;
+    use aptos_reliable_broadcast::{RBNetworkSender, ReliableBroadcast};
+    use async_trait::async_trait;
+    use fail::FailScenario;
+    use std::time::Duration;
+    use tokio_retry::strategy::ExponentialBackoff;
+
+    /// Records the nodes sent by the reliable broadcast, nobody ever answers.
+    #[derive(Default)]
+    struct RecordingNetworkSender {
+        sent_nodes: Mutex<Vec<Node>>,
+    }
+
+    #[async_trait]
+    impl RBNetworkSender<DAGMessage> for RecordingNetworkSender {
+        async fn send_rpc(
+            &self,
+            _receiver: Author,
+            message: DAGMessage,
+            _timeout: Duration,
+        ) -> anyhow::Result<DAGMessage> {
+            if let DAGMessage::NodeMsg(node) = message {
+                self.sent_nodes.lock().push(node);
+            }
+            bail!("unreachable")
+        }
+    }
+
+    /// A driver starting a fresh epoch, it broadcasts its node of round 0 right away.
+    fn start_driver(
+        signers: &[ValidatorSigner],
+        epoch_state: Arc<EpochState>,
+        storage: Arc<MockStorage>,
+        network_sender: Arc<RecordingNetworkSender>,
+    ) -> DagDriver {
+        let dag = Arc::new(RwLock::new(Dag::new(epoch_state.clone(), storage.clone())));
+        let reliable_broadcast = Arc::new(ReliableBroadcast::new(
+            signers.iter().map(|signer| signer.author()).collect(),
+            network_sender,
+            ExponentialBackoff::from_millis(10).max_delay(Duration::from_secs(1)),
+            aptos_time_service::TimeService::real(),
+        ));
+        let (certified_node_tx, _) = tokio::sync::mpsc::channel(10);
+        DagDriver::new(
+            signers[0].author(),
+            epoch_state,
+            dag,
+            Arc::new(MockPayloadManager::new(None)),
+            reliable_broadcast,
+            Arc::new(SimulatedTimeService::new()),
+            storage,
+            certified_node_tx,
+            false,
+        )
+    }
+
+    fn sent_digests(network_sender: &RecordingNetworkSender) -> Vec<HashValue> {
+        let mut digests: Vec<_> = network_sender
+            .sent_nodes
+            .lock()
+            .iter()
+            .map(|node| node.digest())
+            .collect();
+        digests.dedup();
+        digests
+    }
+
+    #[tokio::test]
+    async fn test_broadcast_node_fail_point() {
+        let scenario = FailScenario::setup();
+        fail::cfg("consensus::dag::broadcast_node", "return").unwrap();
+        let (signers, epoch_state) = setup();
+        let storage = Arc::new(MockStorage::new());
+        let network_sender = Arc::new(RecordingNetworkSender::default());
+
+        let _driver = start_driver(
+            &signers,
+            epoch_state,
+            storage.clone(),
+            network_sender.clone(),
+        );
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        // the node is persisted but never sent
+        assert_eq!(storage.get_pending_nodes().unwrap().len(), 1);
+        assert!(network_sender.sent_nodes.lock().is_empty());
+        scenario.teardown();
+    }
+
+    #[tokio::test]
+    async fn test_equivocate_fail_point() {
+        let scenario = FailScenario::setup();
+        fail::cfg("consensus::dag::equivocate", "return").unwrap();
+        let (signers, epoch_state) = setup();
+        let storage = Arc::new(MockStorage::new());
+        let network_sender = Arc::new(RecordingNetworkSender::default());
+
+        let _driver = start_driver(
+            &signers,
+            epoch_state.clone(),
+            storage.clone(),
+            network_sender.clone(),
+        );
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        fail::remove("consensus::dag::equivocate");
+
+        // the broadcast node conflicts with the persisted one, which a restart would resume
+        let (_, persisted_node) = storage.get_pending_nodes().unwrap().pop().unwrap();
+        let sent_nodes = network_sender.sent_nodes.lock().clone();
+        assert_eq!(sent_digests(&network_sender).len(), 1);
+        let equivocating_node = sent_nodes[0].clone();
+        assert_eq!(equivocating_node.round(), persisted_node.round());
+        assert_ne!(equivocating_node.digest(), persisted_node.digest());
+
+        // every validator votes for the first node it sees, neither node is certified
+        let mut builder =
+            SignatureBuilder::new(persisted_node.metadata().clone(), epoch_state.clone());
+        let mut equivocating_builder =
+            SignatureBuilder::new(equivocating_node.metadata().clone(), epoch_state.clone());
+        for (index, signer) in signers.iter().enumerate() {
+            let storage = Arc::new(MockStorage::new());
+            let dag = Arc::new(RwLock::new(Dag::new(epoch_state.clone(), storage.clone())));
+            let mut handler =
+                NodeBroadcastHandler::new(dag, signer.clone(), epoch_state.clone(), storage);
+            let first = if index < 2 {
+                persisted_node.clone()
+            } else {
+                equivocating_node.clone()
+            };
+            let vote = handler.process(first).unwrap();
+            assert!(!matches!(
+                BroadcastStatus::<DAGMessage>::add(&mut builder, signer.author(), vote.clone()),
+                Ok(Some(_))
+            ));
+            assert!(!matches!(
+                BroadcastStatus::<DAGMessage>::add(
+                    &mut equivocating_builder,
+                    signer.author(),
+                    vote
+                ),
+                Ok(Some(_))
+            ));
+        }
+        scenario.teardown();
+    }
+
+    #[test]
+    fn test_vote_fail_point() {
+        let scenario = FailScenario::setup();
+        let (signers, epoch_state) = setup();
+        let storage = Arc::new(MockStorage::new());
+        let dag = Arc::new(RwLock::new(Dag::new(epoch_state.clone(), storage.clone())));
+        let mut handler = NodeBroadcastHandler::new(dag, signers[1].clone(), epoch_state, storage);
+        let node = new_node(0, 10, signers[0].author(), vec![]);
+
+        fail::cfg("consensus::dag::vote", "return").unwrap();
+        assert!(handler.process(node.clone()).is_err());
+
+        // the node gets a vote once the fail point is removed
+        fail::remove("consensus::dag::vote");
+        assert!(handler.process(node).is_ok());
+        scenario.teardown();
+    }
+
+    #[test]
+    fn test_withhold_anchor_votes_fail_point() {
+        let scenario = FailScenario::setup();
+        let (signers, epoch_state) = setup();
+        let authors: Vec<_> = signers.iter().map(|signer| signer.author()).collect();
+        let nodes = build_dag(&authors, 10, |_, _| true);
+        let expected = order(epoch_state.clone(), &nodes);
+        assert!(!expected.is_empty());
+
+        let dag = Arc::new(RwLock::new(Dag::new(
+            epoch_state.clone(),
+            Arc::new(MockStorage::new()),
+        )));
+        let anchor_election = Box::new(RoundRobinAnchorElection::new(
+            epoch_state.verifier.get_ordered_account_addresses(),
+            AnchorFrequency::EveryOtherRound,
+        ));
+        let (tx, mut rx) = channel(nodes.len());
+        let mut order_rule = OrderRule::new(
+            epoch_state,
+            placeholder_ledger_info(),
+            dag.clone(),
+            anchor_election,
+            tx,
+        );
+        let (withheld, rest) = nodes.split_at(authors.len() * 5);
+
+        // no anchor gets enough votes while they're withheld
+        fail::cfg("consensus::dag::withhold_anchor_votes", "return").unwrap();
+        for node in withheld {
+            let node = dag.write().add_node(node.clone()).unwrap();
+            order_rule.process_new_node(&node);
+        }
+        assert!(rx.try_next().is_err());
+
+        // the skipped anchors get ordered through the causal history of the later ones
+        fail::remove("consensus::dag::withhold_anchor_votes");
+        for node in rest {
+            let node = dag.write().add_node(node.clone()).unwrap();
+            order_rule.process_new_node(&node);
+        }
+        let mut ordered = vec![];
+        while let Ok(Some(ordered_nodes)) = rx.try_next() {
+            ordered.extend(
+                ordered_nodes
+                    .iter()
+                    .map(|node| (node.round(), *node.author())),
+            );
+        }
+        assert!(!ordered.is_empty());
+        assert_prefix_consistent(&ordered, &expected);
+        scenario.teardown();
+    }
+}
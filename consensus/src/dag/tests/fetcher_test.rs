@@ -2,16 +2,27 @@
 
 use super::dag_test::MockStorage;
 use crate::dag::{
-    dag_fetcher::FetchRequestHandler,
+    dag_fetcher::{fetch_round_range, FetchRequestHandler, RoundRangeFetchHandler},
+    dag_network::{DAGNetworkSender, RpcWithFallback},
     dag_store::Dag,
+    storage::DAGStorage,
     tests::helpers::new_certified_node,
-    types::{DagSnapshotBitmask, FetchResponse, RemoteFetchRequest},
+    types::{
+        CertifiedNode, DAGMessage, DagSnapshotBitmask, FetchResponse, Node, RemoteFetchRequest,
+        RoundRangeFetchRequest, SignatureBuilder, Vote,
+    },
     RpcHandler,
 };
-use aptos_infallible::RwLock;
-use aptos_types::{epoch_state::EpochState, validator_verifier::random_validator_verifier};
+use aptos_consensus_types::common::{Author, Payload};
+use aptos_infallible::{Mutex, RwLock};
+use aptos_reliable_broadcast::BroadcastStatus;
+use aptos_types::{
+    epoch_state::EpochState, validator_signer::ValidatorSigner,
+    validator_verifier::random_validator_verifier,
+};
+use async_trait::async_trait;
 use claims::assert_ok_eq;
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 
 #[test]
 fn test_dag_fetcher_receiver() {
@@ -55,4 +66,182 @@ fn test_dag_fetcher_receiver() {
     );
 }
 
+#[test]
+fn test_round_range_fetch_paging() {
+    let (_, validator_verifier) = random_validator_verifier(4, None, false);
+    let authors = validator_verifier.get_ordered_account_addresses();
+    let epoch_state = Arc::new(EpochState {
+        epoch: 1,
+        verifier: validator_verifier,
+    });
+    let nodes: Vec<_> = (1..=3)
+        .flat_map(|round| {
+            authors
+                .iter()
+                .map(move |author| new_certified_node(round, *author, vec![]))
+        })
+        .collect();
+
+    // the DAG only holds rounds 2 and 3, round 1 is left in the storage
+    let storage = Arc::new(MockStorage::new());
+    for node in &nodes[4..] {
+        storage.save_certified_node(node).unwrap();
+    }
+    let dag = Arc::new(RwLock::new(Dag::new(epoch_state.clone(), storage.clone())));
+    for node in &nodes[..4] {
+        storage.save_certified_node(node).unwrap();
+    }
+    assert_eq!(dag.read().lowest_round(), 2);
+
+    // three nodes fit in a response
+    let node_bytes = bcs::serialized_size(&nodes[0]).unwrap() as u64;
+    let mut handler = RoundRangeFetchHandler::new(
+        dag.clone(),
+        storage.clone(),
+        epoch_state.clone(),
+        3 * node_bytes,
+    );
+
+    let mut fetched = vec![];
+    let mut num_pages = 0;
+    let mut maybe_request = Some(RoundRangeFetchRequest::new(1, 1, 3));
+    while let Some(request) = maybe_request {
+        let response = handler.process(request).unwrap();
+        assert!(response.certified_nodes().len() <= 3);
+        fetched.extend(response.certified_nodes().iter().cloned());
+        maybe_request = response.next_page().cloned();
+        num_pages += 1;
+    }
+    assert_eq!(num_pages, 4);
+    assert_eq!(fetched, nodes);
+
+    // a node larger than the limit is still served on its own
+    let mut handler = RoundRangeFetchHandler::new(dag, storage, epoch_state, 0);
+    let request = RoundRangeFetchRequest::new(1, 1, 3);
+    let response = handler.process(request.clone()).unwrap();
+    assert_eq!(response.certified_nodes(), &nodes[..1]);
+    assert_eq!(response.next_page(), Some(&request.next_page(1, 1)));
+}
+
+/// Serves the round range fetch requests of every receiver with the same handler.
+struct RoundRangeNetworkSender {
+    handler: Mutex<RoundRangeFetchHandler>,
+}
+
+#[async_trait]
+impl DAGNetworkSender for RoundRangeNetworkSender {
+    async fn send_rpc(
+        &self,
+        _receiver: Author,
+        message: DAGMessage,
+        _timeout: Duration,
+    ) -> anyhow::Result<DAGMessage> {
+        let request: RoundRangeFetchRequest = message.try_into()?;
+        Ok(self.handler.lock().process(request)?.into())
+    }
+
+    async fn send_rpc_with_fallbacks(
+        &self,
+        _responders: Vec<Author>,
+        _message: DAGMessage,
+        _retry_interval: Duration,
+        _rpc_timeout: Duration,
+    ) -> RpcWithFallback {
+        unimplemented!()
+    }
+}
+
+/// A node of the round signed by a quorum of the validators.
+fn new_signed_certified_node(
+    round: u64,
+    author: Author,
+    signers: &[ValidatorSigner],
+    epoch_state: Arc<EpochState>,
+) -> CertifiedNode {
+    let node = Node::new(1, round, author, 0, Payload::empty(false), vec![], vec![]);
+    let mut builder = SignatureBuilder::new(node.metadata().clone(), epoch_state);
+    for signer in signers {
+        let vote = Vote::new(
+            node.metadata().clone(),
+            signer.sign(node.metadata()).unwrap(),
+        );
+        if let Some(certificate) =
+            BroadcastStatus::<DAGMessage>::add(&mut builder, signer.author(), vote).unwrap()
+        {
+            return CertifiedNode::new(node, certificate.signatures().clone());
+        }
+    }
+    unreachable!("all the validators signed")
+}
+
+#[tokio::test]
+async fn test_fetch_round_range() {
+    let (signers, validator_verifier) = random_validator_verifier(4, None, false);
+    let epoch_state = Arc::new(EpochState {
+        epoch: 1,
+        verifier: validator_verifier,
+    });
+    let authors = epoch_state.verifier.get_ordered_account_addresses();
+    let nodes: Vec<_> = (1..=3)
+        .flat_map(|round| {
+            authors
+                .iter()
+                .map(|author| {
+                    new_signed_certified_node(round, *author, &signers, epoch_state.clone())
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect();
+    let storage = Arc::new(MockStorage::new());
+    let dag = Arc::new(RwLock::new(Dag::new(epoch_state.clone(), storage.clone())));
+    for node in &nodes {
+        dag.write().add_node(node.clone()).unwrap();
+    }
+
+    // the nodes are served three by three and verified page by page
+    let node_bytes = bcs::serialized_size(&nodes[0]).unwrap() as u64;
+    let network = RoundRangeNetworkSender {
+        handler: Mutex::new(RoundRangeFetchHandler::new(
+            dag,
+            storage,
+            epoch_state.clone(),
+            3 * node_bytes,
+        )),
+    };
+    let fetched = fetch_round_range(
+        &network,
+        &epoch_state,
+        authors[1],
+        RoundRangeFetchRequest::new(1, 1, 3),
+        Duration::from_secs(1),
+    )
+    .await
+    .unwrap();
+    assert_eq!(fetched, nodes);
+
+    // nodes without a valid certificate are rejected
+    let storage = Arc::new(MockStorage::new());
+    let dag = Arc::new(RwLock::new(Dag::new(epoch_state.clone(), storage.clone())));
+    dag.write()
+        .add_node(new_certified_node(1, authors[0], vec![]))
+        .unwrap();
+    let network = RoundRangeNetworkSender {
+        handler: Mutex::new(RoundRangeFetchHandler::new(
+            dag,
+            storage,
+            epoch_state.clone(),
+            node_bytes,
+        )),
+    };
+    assert!(fetch_round_range(
+        &network,
+        &epoch_state,
+        authors[1],
+        RoundRangeFetchRequest::new(1, 1, 3),
+        Duration::from_secs(1),
+    )
+    .await
+    .is_err());
+}
+
 // TODO: add more tests after commit rule tests
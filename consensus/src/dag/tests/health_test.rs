@@ -0,0 +1,110 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    dag::{
+        dag_store::Dag,
+        health::{DagHealth, DagHealthMonitor, OrderingProgress},
+        tests::{dag_test::MockStorage, helpers::new_certified_node},
+    },
+    util::{mock_time_service::SimulatedTimeService, time_service::TimeService},
+};
+use aptos_infallible::RwLock;
+use aptos_types::{epoch_state::EpochState, validator_verifier::random_validator_verifier};
+use std::{sync::Arc, time::Duration};
+
+#[tokio::test]
+async fn test_stall_detection() {
+    let (signers, validator_verifier) = random_validator_verifier(4, None, false);
+    let epoch_state = Arc::new(EpochState {
+        epoch: 1,
+        verifier: validator_verifier,
+    });
+    let dag = Arc::new(RwLock::new(Dag::new(
+        epoch_state.clone(),
+        Arc::new(MockStorage::new()),
+    )));
+    let progress = Arc::new(OrderingProgress::default());
+    let time_service = Arc::new(SimulatedTimeService::new());
+    let mut monitor = DagHealthMonitor::new(
+        epoch_state,
+        dag.clone(),
+        progress.clone(),
+        time_service.clone(),
+        Duration::from_secs(10),
+    );
+
+    assert_eq!(monitor.check(), DagHealth::Healthy);
+
+    // no ordering and no new rounds, the validators are not live
+    time_service.sleep(Duration::from_secs(11)).await;
+    assert_eq!(monitor.check(), DagHealth::Inactive);
+
+    // ordering resumes
+    progress.update(2);
+    assert_eq!(monitor.check(), DagHealth::Healthy);
+
+    // the dag keeps advancing rounds without ordering anything
+    let mut parents = vec![];
+    for round in 1..=5 {
+        let nodes: Vec<_> = signers
+            .iter()
+            .map(|signer| new_certified_node(round, signer.author(), parents.clone()))
+            .collect();
+        parents = nodes.iter().map(|node| node.certificate()).collect();
+        for node in nodes {
+            dag.write().add_node(node).unwrap();
+        }
+    }
+    time_service.sleep(Duration::from_secs(5)).await;
+    assert_eq!(monitor.check(), DagHealth::Healthy);
+    time_service.sleep(Duration::from_secs(6)).await;
+    assert_eq!(monitor.check(), DagHealth::Stalled);
+}
+
+#[tokio::test]
+async fn test_stall_reported_once() {
+    let (signers, validator_verifier) = random_validator_verifier(4, None, false);
+    let epoch_state = Arc::new(EpochState {
+        epoch: 1,
+        verifier: validator_verifier,
+    });
+    let dag = Arc::new(RwLock::new(Dag::new(
+        epoch_state.clone(),
+        Arc::new(MockStorage::new()),
+    )));
+    let progress = Arc::new(OrderingProgress::default());
+    let time_service = Arc::new(SimulatedTimeService::new());
+    let mut monitor = DagHealthMonitor::new(
+        epoch_state,
+        dag.clone(),
+        progress.clone(),
+        time_service.clone(),
+        Duration::from_secs(10),
+    );
+
+    let mut parents = vec![];
+    for round in 1..=5 {
+        let nodes: Vec<_> = signers
+            .iter()
+            .map(|signer| new_certified_node(round, signer.author(), parents.clone()))
+            .collect();
+        parents = nodes.iter().map(|node| node.certificate()).collect();
+        for node in nodes {
+            dag.write().add_node(node).unwrap();
+        }
+    }
+    assert!(!monitor.report_stall());
+
+    // the stall is reported when it starts, not on every check
+    time_service.sleep(Duration::from_secs(11)).await;
+    assert!(monitor.report_stall());
+    time_service.sleep(Duration::from_secs(1)).await;
+    assert!(!monitor.report_stall());
+
+    // ordering resumes, then stalls again
+    progress.update(2);
+    assert!(!monitor.report_stall());
+    time_service.sleep(Duration::from_secs(11)).await;
+    assert!(monitor.report_stall());
+}
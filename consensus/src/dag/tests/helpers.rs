@@ -9,7 +9,7 @@ pub(crate) fn new_certified_node(
     author: Author,
     parents: Vec<NodeCertificate>,
 ) -> CertifiedNode {
-    let node = Node::new(1, round, author, 0, Payload::empty(false), parents);
+    let node = Node::new(1, round, author, 0, Payload::empty(false), parents, vec![]);
     CertifiedNode::new(node, AggregateSignature::empty())
 }
 
@@ -19,5 +19,13 @@ pub(crate) fn new_node(
     author: Author,
     parents: Vec<NodeCertificate>,
 ) -> Node {
-    Node::new(0, round, author, timestamp, Payload::empty(false), parents)
+    Node::new(
+        0,
+        round,
+        author,
+        timestamp,
+        Payload::empty(false),
+        parents,
+        vec![],
+    )
 }
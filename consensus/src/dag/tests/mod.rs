@@ -1,10 +1,18 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
+mod adapter_test;
+mod anchor_election_test;
+mod dag_driver_test;
 mod dag_network_test;
 mod dag_test;
+mod fault_tests;
 mod fetcher_test;
+mod health_test;
 mod helpers;
 mod order_rule_tests;
 mod reliable_broadcast_tests;
+mod snapshot_test;
 mod types_test;
+mod validation_test;
+mod verification_test;
@@ -3,7 +3,7 @@
 
 use crate::{
     dag::{
-        anchor_election::RoundRobinAnchorElection,
+        anchor_election::{AnchorFrequency, CachedAnchorElection, RoundRobinAnchorElection},
         dag_store::Dag,
         order_rule::OrderRule,
         tests::{dag_test::MockStorage, helpers::new_certified_node},
@@ -18,7 +18,11 @@ use aptos_types::{
     aggregate_signature::AggregateSignature, epoch_state::EpochState,
     validator_verifier::random_validator_verifier,
 };
-use futures_channel::mpsc::{unbounded, UnboundedReceiver};
+use futures::StreamExt;
+use futures_channel::{
+    mpsc::{channel, Receiver},
+    oneshot,
+};
 use proptest::prelude::*;
 use std::sync::Arc;
 
@@ -120,12 +124,17 @@ fn generate_dag_nodes(
 fn create_order_rule(
     epoch_state: Arc<EpochState>,
     dag: Arc<RwLock<Dag>>,
-) -> (OrderRule, UnboundedReceiver<Vec<Arc<CertifiedNode>>>) {
+    anchor_frequency: AnchorFrequency,
+    channel_size: usize,
+) -> (OrderRule, Receiver<Vec<Arc<CertifiedNode>>>) {
     let ledger_info = placeholder_ledger_info();
-    let anchor_election = Box::new(RoundRobinAnchorElection::new(
-        epoch_state.verifier.get_ordered_account_addresses(),
-    ));
-    let (tx, rx) = unbounded();
+    let anchor_election = Box::new(CachedAnchorElection::new(Box::new(
+        RoundRobinAnchorElection::new(
+            epoch_state.verifier.get_ordered_account_addresses(),
+            anchor_frequency,
+        ),
+    )));
+    let (tx, rx) = channel(channel_size);
     (
         OrderRule::new(epoch_state, ledger_info, dag, anchor_election, tx),
         rx,
@@ -165,7 +174,7 @@ proptest! {
             for seq in sequences {
                 s.spawn(|_| {
                     let dag = Arc::new(RwLock::new(dag.clone()));
-                    let (mut order_rule, mut receiver) = create_order_rule(epoch_state.clone(), dag);
+                    let (mut order_rule, mut receiver) = create_order_rule(epoch_state.clone(), dag, AnchorFrequency::EveryOtherRound, NUM_ROUNDS as usize);
                     for idx in seq {
                         order_rule.process_new_node(&flatten_nodes[idx]);
                     }
@@ -188,9 +197,8 @@ proptest! {
     }
 }
 
-#[test]
-fn test_order_rule_basic() {
-    let dag = vec![
+fn basic_virtual_dag() -> Vec<Vec<Option<Vec<bool>>>> {
+    vec![
         vec![Some(vec![]), Some(vec![]), Some(vec![]), Some(vec![])],
         vec![
             Some(vec![false, true, true, true]),
@@ -222,11 +230,30 @@ fn test_order_rule_basic() {
             Some(vec![true, true, true, false]),
             None,
         ],
-    ];
+    ]
+}
+
+fn basic_expected_order() -> Vec<Vec<(u64, usize)>> {
+    vec![
+        // anchor (1, 0) has 1 votes, anchor (3, 1) has 2 votes and a path to (1, 0)
+        vec![(1, 0)],
+        // anchor (2, 1) has 3 votes
+        vec![(1, 2), (1, 1), (2, 1)],
+        // anchor (3, 1) has 2 votes
+        vec![(1, 3), (2, 2), (2, 0), (3, 1)],
+        // anchor (4, 2) has 3 votes
+        vec![(3, 3), (3, 2), (3, 0), (4, 2)],
+        // anchor (5, 2) has 3 votes
+        vec![(4, 1), (4, 0), (5, 2)],
+    ]
+}
+
+#[test]
+fn test_order_rule_basic() {
     let (_, validator_verifier) = random_validator_verifier(4, None, false);
     let validators = validator_verifier.get_ordered_account_addresses();
     let author_indexes = validator_verifier.address_to_validator_index().clone();
-    let nodes = generate_dag_nodes(&dag, &validators);
+    let nodes = generate_dag_nodes(&basic_virtual_dag(), &validators);
     let epoch_state = Arc::new(EpochState {
         epoch: 1,
         verifier: validator_verifier,
@@ -239,22 +266,16 @@ fn test_order_rule_basic() {
     }
     let display = |node: &NodeMetadata| (node.round(), *author_indexes.get(node.author()).unwrap());
     let dag = Arc::new(RwLock::new(dag.clone()));
-    let (mut order_rule, mut receiver) = create_order_rule(epoch_state, dag);
+    let (mut order_rule, mut receiver) = create_order_rule(
+        epoch_state,
+        dag,
+        AnchorFrequency::EveryOtherRound,
+        NUM_ROUNDS as usize,
+    );
     for node in nodes.iter().flatten().flatten() {
         order_rule.process_new_node(node);
     }
-    let expected_order = vec![
-        // anchor (1, 0) has 1 votes, anchor (3, 1) has 2 votes and a path to (1, 0)
-        vec![(1, 0)],
-        // anchor (2, 1) has 3 votes
-        vec![(1, 2), (1, 1), (2, 1)],
-        // anchor (3, 1) has 2 votes
-        vec![(1, 3), (2, 2), (2, 0), (3, 1)],
-        // anchor (4, 2) has 3 votes
-        vec![(3, 3), (3, 2), (3, 0), (4, 2)],
-        // anchor (5, 2) has 3 votes
-        vec![(4, 1), (4, 0), (5, 2)],
-    ];
+    let expected_order = basic_expected_order();
     let mut batch = 0;
     while let Ok(Some(ordered_nodes)) = receiver.try_next() {
         assert_eq!(
@@ -266,4 +287,133 @@ fn test_order_rule_basic() {
         );
         batch += 1;
     }
+    assert_eq!(batch, expected_order.len());
+}
+
+#[test]
+fn test_order_rule_backpressure() {
+    let (_, validator_verifier) = random_validator_verifier(4, None, false);
+    let validators = validator_verifier.get_ordered_account_addresses();
+    let author_indexes = validator_verifier.address_to_validator_index().clone();
+    let nodes = generate_dag_nodes(&basic_virtual_dag(), &validators);
+    let epoch_state = Arc::new(EpochState {
+        epoch: 1,
+        verifier: validator_verifier,
+    });
+    let mut dag = Dag::new(epoch_state.clone(), Arc::new(MockStorage::new()));
+    for round_nodes in &nodes {
+        for node in round_nodes.iter().flatten() {
+            dag.add_node(node.clone()).unwrap();
+        }
+    }
+    let display = |node: &NodeMetadata| (node.round(), *author_indexes.get(node.author()).unwrap());
+    let dag = Arc::new(RwLock::new(dag.clone()));
+    // the channel only has room for a single batch
+    let (mut order_rule, mut receiver) =
+        create_order_rule(epoch_state, dag, AnchorFrequency::EveryOtherRound, 0);
+    let all_nodes: Vec<_> = nodes.iter().flatten().flatten().collect();
+    for node in &all_nodes {
+        order_rule.process_new_node(node);
+    }
+    let mut ordered = vec![];
+    while let Ok(Some(ordered_nodes)) = receiver.try_next() {
+        ordered.push(
+            ordered_nodes
+                .iter()
+                .map(|node| display(node.metadata()))
+                .collect::<Vec<_>>(),
+        );
+        // any new node resumes the ordering once there's room downstream
+        order_rule.process_new_node(all_nodes[0]);
+    }
+    assert_eq!(ordered, basic_expected_order());
+}
+
+#[tokio::test]
+async fn test_order_rule_task() {
+    let (_, validator_verifier) = random_validator_verifier(4, None, false);
+    let validators = validator_verifier.get_ordered_account_addresses();
+    let author_indexes = validator_verifier.address_to_validator_index().clone();
+    let nodes = generate_dag_nodes(&basic_virtual_dag(), &validators);
+    let epoch_state = Arc::new(EpochState {
+        epoch: 1,
+        verifier: validator_verifier,
+    });
+    let mut dag = Dag::new(epoch_state.clone(), Arc::new(MockStorage::new()));
+    for round_nodes in &nodes {
+        for node in round_nodes.iter().flatten() {
+            dag.add_node(node.clone()).unwrap();
+        }
+    }
+    let display = |node: &NodeMetadata| (node.round(), *author_indexes.get(node.author()).unwrap());
+    let dag = Arc::new(RwLock::new(dag.clone()));
+    let (order_rule, mut receiver) =
+        create_order_rule(epoch_state, dag, AnchorFrequency::EveryOtherRound, 0);
+    let (node_tx, node_rx) = tokio::sync::mpsc::channel(16);
+    let (_commit_tx, commit_rx) = tokio::sync::mpsc::unbounded_channel();
+    let (shutdown_tx, shutdown_rx) = oneshot::channel();
+    let handle = tokio::spawn(order_rule.start(node_rx, commit_rx, shutdown_rx));
+
+    for node in nodes.into_iter().flatten().flatten() {
+        node_tx.send(Arc::new(node)).await.unwrap();
+    }
+    let expected_order = basic_expected_order();
+    for expected in &expected_order {
+        let ordered_nodes = receiver.next().await.unwrap();
+        assert_eq!(
+            &ordered_nodes
+                .iter()
+                .map(|node| display(node.metadata()))
+                .collect::<Vec<_>>(),
+            expected
+        );
+    }
+
+    let (ack_tx, ack_rx) = oneshot::channel();
+    shutdown_tx.send(ack_tx).unwrap();
+    ack_rx.await.unwrap();
+    handle.await.unwrap();
+}
+
+#[test]
+fn test_order_rule_every_round() {
+    let (_, validator_verifier) = random_validator_verifier(4, None, false);
+    let validators = validator_verifier.get_ordered_account_addresses();
+    let author_indexes = validator_verifier.address_to_validator_index().clone();
+    // every node links the nodes of validators 0, 1 and 2 in the previous round
+    let virtual_dag = vec![
+        vec![Some(vec![]); 4],
+        vec![Some(vec![true, true, true, false]); 4],
+        vec![Some(vec![true, true, true, false]); 4],
+        vec![Some(vec![true, true, true, false]); 4],
+    ];
+    let nodes = generate_dag_nodes(&virtual_dag, &validators);
+    let epoch_state = Arc::new(EpochState {
+        epoch: 1,
+        verifier: validator_verifier,
+    });
+    let mut dag = Dag::new(epoch_state.clone(), Arc::new(MockStorage::new()));
+    for round_nodes in &nodes {
+        for node in round_nodes.iter().flatten() {
+            dag.add_node(node.clone()).unwrap();
+        }
+    }
+    let display = |node: &NodeMetadata| (node.round(), *author_indexes.get(node.author()).unwrap());
+    let dag = Arc::new(RwLock::new(dag));
+    let (mut order_rule, mut receiver) = create_order_rule(
+        epoch_state,
+        dag,
+        AnchorFrequency::EveryRound,
+        NUM_ROUNDS as usize,
+    );
+    for node in nodes.iter().flatten().flatten() {
+        order_rule.process_new_node(node);
+    }
+    let mut anchors = vec![];
+    while let Ok(Some(ordered_nodes)) = receiver.try_next() {
+        anchors.push(display(ordered_nodes.last().unwrap().metadata()));
+    }
+    // the anchors of rounds 1 and 2 get ordered back to back, the anchor of round 3 is never
+    // linked and the anchor of round 4 has no votes yet
+    assert_eq!(anchors, vec![(1, 1), (2, 2)]);
 }
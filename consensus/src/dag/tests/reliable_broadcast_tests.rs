@@ -12,12 +12,13 @@ use crate::dag::{
         dag_test::MockStorage,
         helpers::{new_certified_node, new_node},
     },
-    types::{CertifiedAck, NodeCertificate},
+    types::{CertifiedAck, CertifiedNode, NodeCertificate},
     NodeId, RpcHandler, Vote,
 };
 use aptos_infallible::RwLock;
 use aptos_types::{
-    aggregate_signature::PartialSignatures, epoch_state::EpochState,
+    aggregate_signature::{AggregateSignature, PartialSignatures},
+    epoch_state::EpochState,
     validator_verifier::random_validator_verifier,
 };
 use claims::{assert_ok, assert_ok_eq};
@@ -150,6 +151,43 @@ fn test_node_broadcast_receiver_storage() {
     assert_eq!(storage.get_votes().unwrap().len(), 0);
 }
 
+#[test]
+fn test_node_broadcast_receiver_compaction() {
+    let (signers, validator_verifier) = random_validator_verifier(4, None, false);
+    let epoch_state = Arc::new(EpochState {
+        epoch: 1,
+        verifier: validator_verifier,
+    });
+    let storage = Arc::new(MockStorage::new());
+    let dag = Arc::new(RwLock::new(Dag::new(epoch_state.clone(), storage.clone())));
+
+    let node = new_node(0, 10, signers[0].author(), vec![]);
+    let equivocating_node = new_node(0, 20, signers[0].author(), vec![]);
+
+    let mut rb_receiver = NodeBroadcastHandler::new(
+        dag.clone(),
+        signers[3].clone(),
+        epoch_state,
+        storage.clone(),
+    );
+    let vote = rb_receiver.process(node.clone()).expect("must succeed");
+    assert_eq!(storage.get_votes().unwrap().len(), 1);
+
+    dag.write()
+        .add_node(CertifiedNode::new(
+            node.clone(),
+            AggregateSignature::empty(),
+        ))
+        .unwrap();
+    assert_ok!(rb_receiver.compact_certified(&node.id()));
+    assert_eq!(storage.get_votes().unwrap().len(), 0);
+
+    // the vote is re-created from the certified node
+    assert_ok_eq!(rb_receiver.process(node), vote.clone());
+    assert_ok_eq!(rb_receiver.process(equivocating_node), vote);
+    assert_eq!(storage.get_votes().unwrap().len(), 0);
+}
+
 #[test]
 fn test_certified_node_receiver() {
     let (signers, validator_verifier) = random_validator_verifier(4, None, false);
@@ -0,0 +1,63 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::dag::{
+    dag_store::Dag,
+    snapshot::DagSnapshot,
+    storage::DAGStorage,
+    tests::{dag_test::MockStorage, helpers::new_certified_node},
+};
+use aptos_temppath::TempPath;
+use aptos_types::{epoch_state::EpochState, validator_verifier::random_validator_verifier};
+use std::sync::Arc;
+
+#[test]
+fn test_snapshot_round_trip() {
+    let (signers, validator_verifier) = random_validator_verifier(4, None, false);
+    let epoch_state = Arc::new(EpochState {
+        epoch: 1,
+        verifier: validator_verifier,
+    });
+    let storage = Arc::new(MockStorage::new());
+    let mut dag = Dag::new(epoch_state.clone(), storage.clone());
+    let mut parents = vec![];
+    for round in 1..=3 {
+        let nodes: Vec<_> = signers
+            .iter()
+            .map(|signer| new_certified_node(round, signer.author(), parents.clone()))
+            .collect();
+        parents = nodes.iter().map(|node| node.certificate()).collect();
+        for node in nodes {
+            dag.add_node(node).unwrap();
+        }
+    }
+    let anchor_id = dag.all_nodes().next().unwrap().id();
+    storage.save_ordered_anchor_id(&anchor_id).unwrap();
+
+    let snapshot = DagSnapshot::from_dag(1, &dag);
+    assert_eq!(snapshot.nodes().len(), 12);
+    assert!(snapshot
+        .nodes()
+        .windows(2)
+        .all(|pair| pair[0].round() <= pair[1].round()));
+
+    let path = TempPath::new();
+    snapshot.save(path.path()).unwrap();
+    let loaded = DagSnapshot::load(path.path()).unwrap();
+    assert_eq!(loaded, snapshot);
+
+    // the persisted window carries the ordered anchors as well
+    let persisted = DagSnapshot::from_storage(storage.as_ref(), 1).unwrap();
+    assert_eq!(persisted.nodes(), snapshot.nodes());
+    assert_eq!(persisted.ordered_anchor_ids(), &[anchor_id]);
+
+    // loading the snapshot into a fresh storage rebuilds the same DAG
+    let replay_storage = Arc::new(MockStorage::new());
+    persisted.restore(replay_storage.as_ref()).unwrap();
+    let replayed = Dag::new(epoch_state, replay_storage.clone());
+    assert_eq!(DagSnapshot::from_dag(1, &replayed), snapshot);
+    assert_eq!(
+        DagSnapshot::from_storage(replay_storage.as_ref(), 1).unwrap(),
+        persisted
+    );
+}
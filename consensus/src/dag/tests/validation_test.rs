@@ -0,0 +1,43 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::dag::{
+    tests::helpers::{new_certified_node, new_node},
+    validation::{NodeValidationError, NodeValidator},
+};
+use aptos_types::validator_verifier::random_validator_verifier;
+
+#[test]
+fn test_node_validation() {
+    let (signers, _) = random_validator_verifier(4, None, false);
+    let validator = NodeValidator::new(3, 10, 1024, 5);
+
+    let parents: Vec<_> = signers
+        .iter()
+        .take(3)
+        .map(|signer| new_certified_node(1, signer.author(), vec![]).certificate())
+        .collect();
+    let node = new_node(2, 0, signers[0].author(), parents.clone());
+    assert_eq!(validator.validate(&node, 1, 1), Ok(()));
+    assert_eq!(
+        validator.validate(&node, 3, 7),
+        Err(NodeValidationError::RoundTooOld(2, 3))
+    );
+
+    let node = new_node(7, 0, signers[0].author(), parents);
+    assert_eq!(validator.validate(&node, 1, 2), Ok(()));
+    assert_eq!(
+        validator.validate(&node, 1, 1),
+        Err(NodeValidationError::RoundTooFarAhead(7, 1))
+    );
+
+    let parents: Vec<_> = signers
+        .iter()
+        .map(|signer| new_certified_node(1, signer.author(), vec![]).certificate())
+        .collect();
+    let node = new_node(2, 0, signers[0].author(), parents);
+    assert_eq!(
+        validator.validate(&node, 1, 1),
+        Err(NodeValidationError::TooManyParents(4, 3))
+    );
+}
@@ -0,0 +1,117 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::dag::{
+    tests::helpers::{new_certified_node, new_node},
+    types::{CertifiedNode, Node, NodeCertificate, NodeMetadata},
+    verification::{
+        verify_certified_node, verify_certified_node_for_epoch, verify_node, verify_node_for_epoch,
+        NodeVerificationError,
+    },
+};
+use aptos_consensus_types::common::{Author, Payload};
+use aptos_crypto::HashValue;
+use aptos_types::{
+    aggregate_signature::{AggregateSignature, PartialSignatures},
+    validator_verifier::{random_validator_verifier, VerifyError},
+};
+
+#[test]
+fn test_node_verification_errors() {
+    let (signers, validator_verifier) = random_validator_verifier(4, None, false);
+
+    let tampered_node = Node::new_for_test(
+        NodeMetadata::new_for_test(0, 0, signers[0].author(), 0, HashValue::random()),
+        Payload::empty(false),
+        vec![],
+    );
+    assert_eq!(
+        verify_node(&tampered_node, &validator_verifier),
+        Err(NodeVerificationError::InvalidDigest)
+    );
+
+    let stranger = Author::random();
+    assert_eq!(
+        verify_node(&new_node(0, 10, stranger, vec![]), &validator_verifier),
+        Err(NodeVerificationError::UnknownAuthor(stranger))
+    );
+
+    let parent = new_node(0, 10, signers[1].author(), vec![]);
+    let parent_cert = NodeCertificate::new(parent.metadata().clone(), AggregateSignature::empty());
+    assert_eq!(
+        verify_node(
+            &new_node(1, 20, signers[0].author(), vec![parent_cert.clone()]),
+            &validator_verifier
+        ),
+        Err(NodeVerificationError::InsufficientParentQuorum)
+    );
+    assert_eq!(
+        verify_node(
+            &new_node(2, 20, signers[0].author(), vec![parent_cert]),
+            &validator_verifier
+        ),
+        Err(NodeVerificationError::InvalidParentRound)
+    );
+
+    let node = new_node(0, 10, signers[0].author(), vec![]);
+    assert_eq!(
+        verify_node_for_epoch(&node, 1, &validator_verifier, false),
+        Err(NodeVerificationError::WrongEpoch(0, 1))
+    );
+    assert_eq!(
+        verify_node_for_epoch(&node, 0, &validator_verifier, false),
+        Ok(())
+    );
+    // a direct mempool payload in an epoch with quorum store can't be merged with the others
+    assert!(matches!(
+        verify_node_for_epoch(&node, 0, &validator_verifier, true),
+        Err(NodeVerificationError::InvalidPayload(_))
+    ));
+}
+
+#[test]
+fn test_certified_node_verification_errors() {
+    let (signers, validator_verifier) = random_validator_verifier(4, None, false);
+
+    let unsigned_node = new_certified_node(0, signers[0].author(), vec![]);
+    assert_eq!(
+        verify_certified_node(&unsigned_node, &validator_verifier),
+        Err(NodeVerificationError::BadSignature(
+            VerifyError::InvalidBitVec
+        ))
+    );
+
+    let node = Node::new(
+        1,
+        0,
+        signers[0].author(),
+        0,
+        Payload::empty(false),
+        vec![],
+        vec![],
+    );
+    let mut partial_sigs = PartialSignatures::empty();
+    for signer in &signers {
+        partial_sigs.add_signature(signer.author(), node.sign_vote(signer).unwrap());
+    }
+    let certified_node = CertifiedNode::new(
+        node,
+        validator_verifier
+            .aggregate_signatures(&partial_sigs)
+            .unwrap(),
+    );
+    assert_eq!(
+        verify_certified_node_for_epoch(&certified_node, 1, &validator_verifier, false),
+        Ok(())
+    );
+    assert!(matches!(
+        verify_certified_node_for_epoch(&certified_node, 1, &validator_verifier, true),
+        Err(NodeVerificationError::InvalidPayload(_))
+    ));
+    let error = verify_certified_node_for_epoch(&certified_node, 2, &validator_verifier, false)
+        .unwrap_err();
+    assert_eq!(error, NodeVerificationError::WrongEpoch(1, 2));
+    // a node from another epoch can come from an honest peer, a bad signature can't
+    assert!(!error.is_malicious());
+    assert!(NodeVerificationError::BadSignature(VerifyError::InvalidBitVec).is_malicious());
+}
@@ -1,6 +1,7 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
+use super::verification::{verify_certified_node, verify_node};
 use crate::{network::TConsensusMsg, network_interface::ConsensusMsg};
 use anyhow::{bail, ensure};
 use aptos_consensus_types::common::{Author, Payload, Round};
@@ -20,7 +21,7 @@ use aptos_types::{
     validator_verifier::ValidatorVerifier,
 };
 use serde::{Deserialize, Serialize};
-use std::{collections::HashSet, ops::Deref, sync::Arc};
+use std::{collections::HashSet, mem, ops::Deref, sync::Arc};
 
 pub trait TDAGMessage: Into<DAGMessage> + TryFrom<DAGMessage> {
     fn verify(&self, verifier: &ValidatorVerifier) -> anyhow::Result<()>;
@@ -46,6 +47,7 @@ struct NodeWithoutDigest<'a> {
     timestamp: u64,
     payload: &'a Payload,
     parents: &'a Vec<NodeCertificate>,
+    weak_links: &'a Vec<NodeCertificate>,
 }
 
 impl<'a> CryptoHash for NodeWithoutDigest<'a> {
@@ -68,6 +70,7 @@ impl<'a> From<&'a Node> for NodeWithoutDigest<'a> {
             timestamp: node.metadata.timestamp,
             payload: &node.payload,
             parents: &node.parents,
+            weak_links: &node.weak_links,
         }
     }
 }
@@ -115,6 +118,10 @@ impl NodeMetadata {
     pub fn epoch(&self) -> u64 {
         self.epoch
     }
+
+    pub fn timestamp(&self) -> u64 {
+        self.timestamp
+    }
 }
 
 impl Deref for NodeMetadata {
@@ -126,11 +133,13 @@ impl Deref for NodeMetadata {
 }
 
 /// Node representation in the DAG, parents contain 2f+1 strong links (links to previous round)
+/// and weak links point to older nodes that aren't in the causal history of the strong links yet
 #[derive(Clone, Serialize, Deserialize, CryptoHasher, Debug, PartialEq)]
 pub struct Node {
     metadata: NodeMetadata,
     payload: Payload,
     parents: Vec<NodeCertificate>,
+    weak_links: Vec<NodeCertificate>,
 }
 
 impl Node {
@@ -141,9 +150,17 @@ impl Node {
         timestamp: u64,
         payload: Payload,
         parents: Vec<NodeCertificate>,
+        weak_links: Vec<NodeCertificate>,
     ) -> Self {
-        let digest =
-            Self::calculate_digest_internal(epoch, round, author, timestamp, &payload, &parents);
+        let digest = Self::calculate_digest_internal(
+            epoch,
+            round,
+            author,
+            timestamp,
+            &payload,
+            &parents,
+            &weak_links,
+        );
 
         Self {
             metadata: NodeMetadata {
@@ -157,6 +174,7 @@ impl Node {
             },
             payload,
             parents,
+            weak_links,
         }
     }
 
@@ -170,6 +188,7 @@ impl Node {
             metadata,
             payload,
             parents,
+            weak_links: vec![],
         }
     }
 
@@ -181,6 +200,7 @@ impl Node {
         timestamp: u64,
         payload: &Payload,
         parents: &Vec<NodeCertificate>,
+        weak_links: &Vec<NodeCertificate>,
     ) -> HashValue {
         let node_with_out_digest = NodeWithoutDigest {
             epoch,
@@ -189,11 +209,12 @@ impl Node {
             timestamp,
             payload,
             parents,
+            weak_links,
         };
         node_with_out_digest.hash()
     }
 
-    fn calculate_digest(&self) -> HashValue {
+    pub(crate) fn calculate_digest(&self) -> HashValue {
         Self::calculate_digest_internal(
             self.metadata.epoch,
             self.metadata.round,
@@ -201,6 +222,7 @@ impl Node {
             self.metadata.timestamp,
             &self.payload,
             &self.parents,
+            &self.weak_links,
         )
     }
 
@@ -212,6 +234,10 @@ impl Node {
         &self.metadata
     }
 
+    pub fn payload(&self) -> &Payload {
+        &self.payload
+    }
+
     pub fn parents(&self) -> &[NodeCertificate] {
         &self.parents
     }
@@ -220,6 +246,18 @@ impl Node {
         self.parents().iter().map(|cert| &cert.metadata)
     }
 
+    pub fn weak_links(&self) -> &[NodeCertificate] {
+        &self.weak_links
+    }
+
+    /// Both the strong and the weak links, all of them must be in the DAG before the node
+    pub fn links_metadata(&self) -> impl Iterator<Item = &NodeMetadata> {
+        self.parents()
+            .iter()
+            .chain(self.weak_links())
+            .map(|cert| &cert.metadata)
+    }
+
     pub fn author(&self) -> &Author {
         self.metadata.author()
     }
@@ -239,44 +277,15 @@ impl Node {
     pub fn round(&self) -> Round {
         self.metadata.round
     }
+
+    pub fn timestamp(&self) -> u64 {
+        self.metadata.timestamp
+    }
 }
 
 impl TDAGMessage for Node {
     fn verify(&self, verifier: &ValidatorVerifier) -> anyhow::Result<()> {
-        // TODO: move this check to rpc process logic to delay it as much as possible for performance
-        ensure!(self.digest() == self.calculate_digest(), "invalid digest");
-
-        let current_round = self.metadata().round();
-
-        if current_round == 0 {
-            ensure!(self.parents().is_empty(), "invalid parents for round 0");
-            return Ok(());
-        }
-
-        let prev_round = current_round - 1;
-        // check if the parents' round is the node's round - 1
-        ensure!(
-            self.parents()
-                .iter()
-                .all(|parent| parent.metadata().round() == prev_round),
-            "invalid parent round"
-        );
-
-        ensure!(
-            verifier
-                .check_voting_power(
-                    self.parents()
-                        .iter()
-                        .map(|parent| parent.metadata().author()),
-                    true,
-                )
-                .is_ok(),
-            "not enough parents to satisfy voting power"
-        );
-
-        // TODO: validate timestamp
-
-        Ok(())
+        Ok(verify_node(self, verifier)?)
     }
 }
 
@@ -371,11 +380,7 @@ impl Deref for CertifiedNode {
 
 impl TDAGMessage for CertifiedNode {
     fn verify(&self, verifier: &ValidatorVerifier) -> anyhow::Result<()> {
-        ensure!(self.digest() == self.calculate_digest(), "invalid digest");
-
-        verifier
-            .verify_multi_signatures(self.metadata(), self.certificate().signatures())
-            .map_err(|e| anyhow::anyhow!("unable to verify: {}", e))
+        Ok(verify_certified_node(self, verifier)?)
     }
 }
 
@@ -401,6 +406,8 @@ impl Vote {
 pub struct SignatureBuilder {
     metadata: NodeMetadata,
     partial_signatures: PartialSignatures,
+    /// Set once quorum is reached, the individual votes are dropped at that point.
+    certificate: Option<NodeCertificate>,
     epoch_state: Arc<EpochState>,
 }
 
@@ -409,6 +416,7 @@ impl SignatureBuilder {
         Self {
             metadata,
             partial_signatures: PartialSignatures::empty(),
+            certificate: None,
             epoch_state,
         }
     }
@@ -426,20 +434,28 @@ where
 
     fn add(&mut self, peer: Author, ack: Self::Ack) -> anyhow::Result<Option<Self::Aggregated>> {
         ensure!(self.metadata == ack.metadata, "Digest mismatch");
+        if let Some(certificate) = &self.certificate {
+            return Ok(Some(certificate.clone()));
+        }
         self.partial_signatures.add_signature(peer, ack.signature);
-        Ok(self
+        if self
             .epoch_state
             .verifier
             .check_voting_power(self.partial_signatures.signatures().keys(), true)
-            .ok()
-            .map(|_| {
-                let aggregated_signature = self
-                    .epoch_state
-                    .verifier
-                    .aggregate_signatures(&self.partial_signatures)
-                    .expect("Signature aggregation should succeed");
-                NodeCertificate::new(self.metadata.clone(), aggregated_signature)
-            }))
+            .is_err()
+        {
+            return Ok(None);
+        }
+        let partial_signatures =
+            mem::replace(&mut self.partial_signatures, PartialSignatures::empty());
+        let aggregated_signature = self
+            .epoch_state
+            .verifier
+            .aggregate_signatures(&partial_signatures)
+            .expect("Signature aggregation should succeed");
+        let certificate = NodeCertificate::new(self.metadata.clone(), aggregated_signature);
+        self.certificate = Some(certificate.clone());
+        Ok(Some(certificate))
     }
 }
 
@@ -570,6 +586,150 @@ impl FetchResponse {
     }
 }
 
+/// Asks for the certified nodes of the rounds from `start_round` to `end_round`, e.g. for a
+/// validator catching up after it fell behind. Responses are limited in size, the rest of the
+/// range is asked for with the `next_page` request of the response.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct RoundRangeFetchRequest {
+    epoch: u64,
+    start_round: Round,
+    end_round: Round,
+    /// Index of the first validator whose node is returned for the start round
+    start_author_index: usize,
+}
+
+impl RoundRangeFetchRequest {
+    pub fn new(epoch: u64, start_round: Round, end_round: Round) -> Self {
+        Self::new_page(epoch, start_round, end_round, 0)
+    }
+
+    fn new_page(
+        epoch: u64,
+        start_round: Round,
+        end_round: Round,
+        start_author_index: usize,
+    ) -> Self {
+        Self {
+            epoch,
+            start_round,
+            end_round,
+            start_author_index,
+        }
+    }
+
+    /// The request for the rest of the range, starting with the node of the given round and
+    /// validator.
+    pub fn next_page(&self, round: Round, author_index: usize) -> Self {
+        Self::new_page(self.epoch, round, self.end_round, author_index)
+    }
+
+    pub fn epoch(&self) -> u64 {
+        self.epoch
+    }
+
+    pub fn start_round(&self) -> Round {
+        self.start_round
+    }
+
+    pub fn end_round(&self) -> Round {
+        self.end_round
+    }
+
+    pub fn start_author_index(&self) -> usize {
+        self.start_author_index
+    }
+
+    /// Whether the node of the given round and validator is part of the requested range
+    pub fn contains(&self, round: Round, author_index: usize) -> bool {
+        (round > self.start_round
+            || (round == self.start_round && author_index >= self.start_author_index))
+            && round <= self.end_round
+    }
+}
+
+impl TDAGMessage for RoundRangeFetchRequest {
+    fn verify(&self, verifier: &ValidatorVerifier) -> anyhow::Result<()> {
+        ensure!(self.start_round <= self.end_round, "invalid round range");
+        ensure!(
+            self.start_author_index < verifier.len(),
+            "invalid start author index"
+        );
+
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct RoundRangeFetchResponse {
+    epoch: u64,
+    certified_nodes: Vec<CertifiedNode>,
+    next_page: Option<RoundRangeFetchRequest>,
+}
+
+impl RoundRangeFetchResponse {
+    pub fn new(
+        epoch: u64,
+        certified_nodes: Vec<CertifiedNode>,
+        next_page: Option<RoundRangeFetchRequest>,
+    ) -> Self {
+        Self {
+            epoch,
+            certified_nodes,
+            next_page,
+        }
+    }
+
+    pub fn certified_nodes(&self) -> &[CertifiedNode] {
+        &self.certified_nodes
+    }
+
+    /// The request for the nodes that didn't fit in this response, if any.
+    pub fn next_page(&self) -> Option<&RoundRangeFetchRequest> {
+        self.next_page.as_ref()
+    }
+
+    pub fn into_parts(self) -> (Vec<CertifiedNode>, Option<RoundRangeFetchRequest>) {
+        (self.certified_nodes, self.next_page)
+    }
+
+    pub fn verify(
+        self,
+        request: &RoundRangeFetchRequest,
+        validator_verifier: &ValidatorVerifier,
+    ) -> anyhow::Result<Self> {
+        let author_to_index = validator_verifier.address_to_validator_index();
+        ensure!(self.epoch == request.epoch(), "wrong epoch");
+        ensure!(
+            self.certified_nodes.iter().all(|node| {
+                node.epoch() == request.epoch()
+                    && author_to_index
+                        .get(node.author())
+                        .map_or(false, |index| request.contains(node.round(), *index))
+            }),
+            "certified nodes out of the requested range"
+        );
+        if let Some(next_page) = &self.next_page {
+            // a page always makes progress, otherwise the requester would loop forever
+            ensure!(
+                !self.certified_nodes.is_empty()
+                    && next_page.epoch() == request.epoch()
+                    && next_page.end_round() == request.end_round()
+                    && (next_page.start_round(), next_page.start_author_index())
+                        > (request.start_round(), request.start_author_index()),
+                "invalid next page"
+            );
+        }
+        ensure!(
+            self.certified_nodes
+                .iter()
+                .all(|node| node.verify(validator_verifier).is_ok()),
+            "unable to verify certified nodes"
+        );
+
+        Ok(self)
+    }
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct DAGNetworkMessage {
     pub epoch: u64,
@@ -585,6 +745,8 @@ pub enum DAGMessage {
     CertifiedAckMsg(CertifiedAck),
     FetchRequest(RemoteFetchRequest),
     FetchResponse(FetchResponse),
+    RoundRangeFetchRequest(RoundRangeFetchRequest),
+    RoundRangeFetchResponse(RoundRangeFetchResponse),
 
     #[cfg(test)]
     TestMessage(TestMessage),
@@ -601,6 +763,8 @@ impl DAGMessage {
             DAGMessage::CertifiedAckMsg(_) => "CertifiedAckMsg",
             DAGMessage::FetchRequest(_) => "FetchRequest",
             DAGMessage::FetchResponse(_) => "FetchResponse",
+            DAGMessage::RoundRangeFetchRequest(_) => "RoundRangeFetchRequest",
+            DAGMessage::RoundRangeFetchResponse(_) => "RoundRangeFetchResponse",
             #[cfg(test)]
             DAGMessage::TestMessage(_) => "TestMessage",
             #[cfg(test)]
@@ -628,6 +792,8 @@ impl TConsensusMsg for DAGMessage {
             DAGMessage::CertifiedAckMsg(ack) => ack.epoch,
             DAGMessage::FetchRequest(req) => req.epoch,
             DAGMessage::FetchResponse(res) => res.epoch,
+            DAGMessage::RoundRangeFetchRequest(req) => req.epoch,
+            DAGMessage::RoundRangeFetchResponse(res) => res.epoch,
             #[cfg(test)]
             DAGMessage::TestMessage(_) => 1,
             #[cfg(test)]
@@ -0,0 +1,129 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use super::{counters::NODE_VALIDATION_REJECTION_COUNT, types::Node};
+use aptos_config::config::DagConsensusConfig;
+use aptos_consensus_types::common::Round;
+use thiserror::Error as ThisError;
+
+#[derive(ThisError, Clone, Debug, PartialEq, Eq)]
+pub enum NodeValidationError {
+    #[error("too many parents: {0} > {1}")]
+    TooManyParents(usize, usize),
+    #[error("too many weak links: {0} > {1}")]
+    TooManyWeakLinks(usize, usize),
+    #[error("too many transactions in payload: {0} > {1}")]
+    TooManyTxns(u64, u64),
+    #[error("payload too large: {0} > {1} bytes")]
+    PayloadTooLarge(u64, u64),
+    #[error("round {0} too far ahead of local round {1}")]
+    RoundTooFarAhead(Round, Round),
+    #[error("round {0} below lowest round {1}")]
+    RoundTooOld(Round, Round),
+}
+
+impl NodeValidationError {
+    /// Label of the rejection counter
+    pub fn reason(&self) -> &'static str {
+        match self {
+            NodeValidationError::TooManyParents(..) => "too_many_parents",
+            NodeValidationError::TooManyWeakLinks(..) => "too_many_weak_links",
+            NodeValidationError::TooManyTxns(..) => "too_many_txns",
+            NodeValidationError::PayloadTooLarge(..) => "payload_too_large",
+            NodeValidationError::RoundTooFarAhead(..) => "round_too_far_ahead",
+            NodeValidationError::RoundTooOld(..) => "round_too_old",
+        }
+    }
+}
+
+/// Limits a node has to respect before we vote for it or add it to the DAG, checked before
+/// any signature verification so oversized or far-off nodes are cheap to reject.
+#[derive(Clone, Debug)]
+pub struct NodeValidator {
+    max_parents: usize,
+    max_txns: u64,
+    max_bytes: u64,
+    max_round_lookahead: Round,
+}
+
+impl NodeValidator {
+    pub fn new(
+        max_parents: usize,
+        max_txns: u64,
+        max_bytes: u64,
+        max_round_lookahead: Round,
+    ) -> Self {
+        Self {
+            max_parents,
+            max_txns,
+            max_bytes,
+            max_round_lookahead,
+        }
+    }
+
+    /// A node links at most one node of each validator in the previous round.
+    pub fn from_config(config: &DagConsensusConfig, num_validators: usize) -> Self {
+        Self::new(
+            num_validators,
+            config.max_node_txns,
+            config.max_node_bytes,
+            config.max_node_round_lookahead,
+        )
+    }
+
+    pub fn validate(
+        &self,
+        node: &Node,
+        lowest_round: Round,
+        highest_round: Round,
+    ) -> Result<(), NodeValidationError> {
+        self.validate_internal(node, lowest_round, highest_round)
+            .map_err(|e| {
+                NODE_VALIDATION_REJECTION_COUNT
+                    .with_label_values(&[e.reason()])
+                    .inc();
+                e
+            })
+    }
+
+    fn validate_internal(
+        &self,
+        node: &Node,
+        lowest_round: Round,
+        highest_round: Round,
+    ) -> Result<(), NodeValidationError> {
+        let round = node.round();
+        if round < lowest_round {
+            return Err(NodeValidationError::RoundTooOld(round, lowest_round));
+        }
+        if round > highest_round + self.max_round_lookahead {
+            return Err(NodeValidationError::RoundTooFarAhead(round, highest_round));
+        }
+        let num_parents = node.parents().len();
+        if num_parents > self.max_parents {
+            return Err(NodeValidationError::TooManyParents(
+                num_parents,
+                self.max_parents,
+            ));
+        }
+        let num_weak_links = node.weak_links().len();
+        if num_weak_links > self.max_parents {
+            return Err(NodeValidationError::TooManyWeakLinks(
+                num_weak_links,
+                self.max_parents,
+            ));
+        }
+        let num_txns = node.payload().len() as u64;
+        if num_txns > self.max_txns {
+            return Err(NodeValidationError::TooManyTxns(num_txns, self.max_txns));
+        }
+        let num_bytes = node.payload().size() as u64;
+        if num_bytes > self.max_bytes {
+            return Err(NodeValidationError::PayloadTooLarge(
+                num_bytes,
+                self.max_bytes,
+            ));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,182 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use super::{
+    counters::NODE_VERIFICATION_FAILURE_COUNT,
+    types::{CertifiedNode, Node},
+};
+use aptos_consensus_types::common::Author;
+use aptos_types::validator_verifier::{ValidatorVerifier, VerifyError};
+use thiserror::Error as ThisError;
+
+#[derive(ThisError, Debug, PartialEq, Eq)]
+pub enum NodeVerificationError {
+    #[error("invalid digest")]
+    InvalidDigest,
+    #[error("unable to verify: {0}")]
+    BadSignature(VerifyError),
+    #[error("unknown author {0}")]
+    UnknownAuthor(Author),
+    #[error("wrong epoch: {0} != {1}")]
+    WrongEpoch(u64, u64),
+    #[error("invalid parent round")]
+    InvalidParentRound,
+    #[error("invalid weak link round")]
+    InvalidWeakLinkRound,
+    #[error("not enough parents to satisfy voting power")]
+    InsufficientParentQuorum,
+    #[error("invalid payload: {0}")]
+    InvalidPayload(String),
+}
+
+impl NodeVerificationError {
+    /// Label of the failure counter
+    pub fn class(&self) -> &'static str {
+        match self {
+            NodeVerificationError::InvalidDigest => "invalid_digest",
+            NodeVerificationError::BadSignature(_) => "bad_signature",
+            NodeVerificationError::UnknownAuthor(_) => "unknown_author",
+            NodeVerificationError::WrongEpoch(..) => "wrong_epoch",
+            NodeVerificationError::InvalidParentRound => "invalid_parent_round",
+            NodeVerificationError::InvalidWeakLinkRound => "invalid_weak_link_round",
+            NodeVerificationError::InsufficientParentQuorum => "insufficient_parent_quorum",
+            NodeVerificationError::InvalidPayload(_) => "invalid_payload",
+        }
+    }
+
+    /// An honest peer lagging behind or ahead of us can send a node of another epoch, any
+    /// other failure means the sender forged or tampered with the node.
+    pub fn is_malicious(&self) -> bool {
+        !matches!(self, NodeVerificationError::WrongEpoch(..))
+    }
+}
+
+fn verify_author(
+    author: &Author,
+    verifier: &ValidatorVerifier,
+) -> Result<(), NodeVerificationError> {
+    if verifier.get_voting_power(author).is_none() {
+        return Err(NodeVerificationError::UnknownAuthor(*author));
+    }
+    Ok(())
+}
+
+pub fn verify_node(node: &Node, verifier: &ValidatorVerifier) -> Result<(), NodeVerificationError> {
+    // TODO: move this check to rpc process logic to delay it as much as possible for performance
+    if node.digest() != node.calculate_digest() {
+        return Err(NodeVerificationError::InvalidDigest);
+    }
+    verify_author(node.author(), verifier)?;
+
+    let current_round = node.round();
+    if current_round == 0 {
+        if !node.parents().is_empty() {
+            return Err(NodeVerificationError::InvalidParentRound);
+        }
+        if !node.weak_links().is_empty() {
+            return Err(NodeVerificationError::InvalidWeakLinkRound);
+        }
+        return Ok(());
+    }
+
+    let prev_round = current_round - 1;
+    // check if the parents' round is the node's round - 1
+    if !node
+        .parents()
+        .iter()
+        .all(|parent| parent.metadata().round() == prev_round)
+    {
+        return Err(NodeVerificationError::InvalidParentRound);
+    }
+
+    // weak links skip at least one round, the previous round is covered by the strong links
+    if !node
+        .weak_links()
+        .iter()
+        .all(|link| link.metadata().round() < prev_round)
+    {
+        return Err(NodeVerificationError::InvalidWeakLinkRound);
+    }
+
+    if verifier
+        .check_voting_power(
+            node.parents()
+                .iter()
+                .map(|parent| parent.metadata().author()),
+            true,
+        )
+        .is_err()
+    {
+        return Err(NodeVerificationError::InsufficientParentQuorum);
+    }
+
+    // TODO: validate timestamp
+
+    Ok(())
+}
+
+pub fn verify_certified_node(
+    node: &CertifiedNode,
+    verifier: &ValidatorVerifier,
+) -> Result<(), NodeVerificationError> {
+    if node.digest() != node.calculate_digest() {
+        return Err(NodeVerificationError::InvalidDigest);
+    }
+    verify_author(node.author(), verifier)?;
+
+    verifier
+        .verify_multi_signatures(node.metadata(), node.signatures())
+        .map_err(NodeVerificationError::BadSignature)
+}
+
+/// Verify a node received from the network for the given epoch, failures are counted by class.
+pub fn verify_node_for_epoch(
+    node: &Node,
+    epoch: u64,
+    verifier: &ValidatorVerifier,
+    quorum_store_enabled: bool,
+) -> Result<(), NodeVerificationError> {
+    check_epoch(node, epoch)
+        .and_then(|_| verify_node(node, verifier))
+        .and_then(|_| verify_payload(node, verifier, quorum_store_enabled))
+        .map_err(observe_failure)
+}
+
+/// Same as [`verify_node_for_epoch`] for a certified node.
+pub fn verify_certified_node_for_epoch(
+    node: &CertifiedNode,
+    epoch: u64,
+    verifier: &ValidatorVerifier,
+    quorum_store_enabled: bool,
+) -> Result<(), NodeVerificationError> {
+    check_epoch(node, epoch)
+        .and_then(|_| verify_certified_node(node, verifier))
+        .and_then(|_| verify_payload(node, verifier, quorum_store_enabled))
+        .map_err(observe_failure)
+}
+
+/// The payloads of the ordered nodes are merged into a single block, so every node must carry
+/// the payload type of the epoch.
+fn verify_payload(
+    node: &Node,
+    verifier: &ValidatorVerifier,
+    quorum_store_enabled: bool,
+) -> Result<(), NodeVerificationError> {
+    node.payload()
+        .verify(verifier, quorum_store_enabled)
+        .map_err(|e| NodeVerificationError::InvalidPayload(e.to_string()))
+}
+
+fn check_epoch(node: &Node, epoch: u64) -> Result<(), NodeVerificationError> {
+    if node.epoch() != epoch {
+        return Err(NodeVerificationError::WrongEpoch(node.epoch(), epoch));
+    }
+    Ok(())
+}
+
+fn observe_failure(e: NodeVerificationError) -> NodeVerificationError {
+    NODE_VERIFICATION_FAILURE_COUNT
+        .with_label_values(&[e.class()])
+        .inc();
+    e
+}
@@ -8,11 +8,13 @@ use crate::{
         BlockStore,
     },
     counters,
+    dag::{bootstrap_dag, DagCommitSigner},
     error::{error_kind, DbError},
     experimental::{
         buffer_manager::{OrderedBlocks, ResetRequest},
         decoupled_execution_utils::prepare_phases_and_buffer_manager,
         ordering_state_computer::OrderingStateComputer,
+        signing_phase::CommitSignerProvider,
     },
     liveness::{
         cached_proposer_election::CachedProposerElection,
@@ -32,11 +34,12 @@ use crate::{
     metrics_safety_rules::MetricsSafetyRules,
     monitor,
     network::{
-        IncomingBatchRetrievalRequest, IncomingBlockRetrievalRequest, IncomingRpcRequest,
-        NetworkReceivers, NetworkSender,
+        IncomingBatchRetrievalRequest, IncomingBlockRetrievalRequest, IncomingDAGRequest,
+        IncomingRpcRequest, NetworkReceivers, NetworkSender,
     },
     network_interface::{ConsensusMsg, ConsensusNetworkClient},
     payload_client::QuorumStoreClient,
+    payload_manager::PayloadManager,
     persistent_liveness_storage::{LedgerRecoveryData, PersistentLivenessStorage, RecoveryData},
     quorum_store::{
         quorum_store_builder::{DirectMempoolInnerBuilder, InnerBuilder, QuorumStoreBuilder},
@@ -50,7 +53,7 @@ use crate::{
     transaction_shuffler::create_transaction_shuffler,
     util::time_service::TimeService,
 };
-use anyhow::{bail, ensure, Context};
+use anyhow::{anyhow, bail, ensure, Context};
 use aptos_bounded_executor::BoundedExecutor;
 use aptos_channels::{aptos_channel, message_queues::QueueStyle};
 use aptos_config::config::{ConsensusConfig, NodeConfig};
@@ -63,7 +66,8 @@ use aptos_infallible::{duration_since_epoch, Mutex};
 use aptos_logger::prelude::*;
 use aptos_mempool::QuorumStoreRequest;
 use aptos_network::{application::interface::NetworkClient, protocols::network::Event};
-use aptos_safety_rules::SafetyRulesManager;
+use aptos_safety_rules::{PersistentSafetyStorage, SafetyRulesManager};
+use aptos_secure_storage::{KVStorage, Storage};
 use aptos_types::{
     account_address::AccountAddress,
     epoch_change::EpochChangeProof,
@@ -72,6 +76,7 @@ use aptos_types::{
         LeaderReputationType, OnChainConfigPayload, OnChainConfigProvider, OnChainConsensusConfig,
         OnChainExecutionConfig, ProposerElectionType, ValidatorSet,
     },
+    validator_signer::ValidatorSigner,
     validator_verifier::ValidatorVerifier,
 };
 use fail::fail_point;
@@ -137,6 +142,9 @@ pub struct EpochManager<P: OnChainConfigProvider> {
     quorum_store_storage: Arc<dyn QuorumStoreStorage>,
     batch_retrieval_tx:
         Option<aptos_channel::Sender<AccountAddress, IncomingBatchRetrievalRequest>>,
+    // channels to the DAG
+    dag_rpc_tx: Option<aptos_channel::Sender<AccountAddress, IncomingDAGRequest>>,
+    dag_shutdown_tx: Option<oneshot::Sender<oneshot::Sender<()>>>,
     bounded_executor: BoundedExecutor,
     // recovery_mode is set to true when the recovery manager is spawned
     recovery_mode: bool,
@@ -184,6 +192,8 @@ impl<P: OnChainConfigProvider> EpochManager<P> {
             quorum_store_coordinator_tx: None,
             quorum_store_storage,
             batch_retrieval_tx: None,
+            dag_rpc_tx: None,
+            dag_shutdown_tx: None,
             bounded_executor,
             recovery_mode: false,
         }
@@ -480,12 +490,16 @@ impl<P: OnChainConfigProvider> EpochManager<P> {
     }
 
     /// this function spawns the phases and a buffer manager
-    /// it sets `self.commit_msg_tx` to a new aptos_channel::Sender and returns an OrderingStateComputer
+    /// it sets `self.commit_msg_tx` to a new aptos_channel::Sender and returns the channels to
+    /// send ordered blocks and reset requests to the buffer manager
     fn spawn_decoupled_execution(
         &mut self,
-        safety_rules_container: Arc<Mutex<MetricsSafetyRules>>,
+        commit_signer_provider: Arc<dyn CommitSignerProvider>,
         verifier: ValidatorVerifier,
-    ) -> OrderingStateComputer {
+    ) -> (
+        UnboundedSender<OrderedBlocks>,
+        UnboundedSender<ResetRequest>,
+    ) {
         let network_sender = NetworkSender::new(
             self.author,
             self.network_sender.clone(),
@@ -509,7 +523,7 @@ impl<P: OnChainConfigProvider> EpochManager<P> {
             prepare_phases_and_buffer_manager(
                 self.author,
                 self.commit_state_computer.clone(),
-                safety_rules_container,
+                commit_signer_provider,
                 network_sender,
                 commit_msg_rx,
                 self.commit_state_computer.clone(),
@@ -523,7 +537,7 @@ impl<P: OnChainConfigProvider> EpochManager<P> {
         tokio::spawn(persisting_phase.start());
         tokio::spawn(buffer_manager.start());
 
-        OrderingStateComputer::new(block_tx, self.commit_state_computer.clone(), reset_tx)
+        (block_tx, reset_tx)
     }
 
     async fn shutdown_current_processor(&mut self) {
@@ -539,6 +553,18 @@ impl<P: OnChainConfigProvider> EpochManager<P> {
         }
         self.round_manager_tx = None;
 
+        if let Some(close_tx) = self.dag_shutdown_tx.take() {
+            // Release the previous DAG components before stopping the buffer manager
+            let (ack_tx, ack_rx) = oneshot::channel();
+            close_tx
+                .send(ack_tx)
+                .expect("[EpochManager] Fail to drop DAG bootstrapper");
+            ack_rx
+                .await
+                .expect("[EpochManager] Fail to drop DAG bootstrapper");
+        }
+        self.dag_rpc_tx = None;
+
         // Shutdown the previous buffer manager, to release the SafetyRule client
         self.buffer_manager_msg_tx = None;
         if let Some(mut tx) = self.buffer_manager_reset_tx.take() {
@@ -649,60 +675,22 @@ impl<P: OnChainConfigProvider> EpochManager<P> {
 
         let safety_rules_container = Arc::new(Mutex::new(safety_rules));
 
-        // Start QuorumStore
-        let (consensus_to_quorum_store_tx, consensus_to_quorum_store_rx) =
-            mpsc::channel(self.config.intra_consensus_channel_buffer_size);
-
-        let mut quorum_store_builder = if self.quorum_store_enabled {
-            info!("Building QuorumStore");
-            QuorumStoreBuilder::QuorumStore(InnerBuilder::new(
-                self.epoch(),
-                self.author,
-                epoch_state.verifier.len() as u64,
-                self.config.quorum_store.clone(),
-                consensus_to_quorum_store_rx,
-                self.quorum_store_to_mempool_sender.clone(),
-                self.config.mempool_txn_pull_timeout_ms,
-                self.storage.aptos_db().clone(),
-                network_sender.clone(),
-                epoch_state.verifier.clone(),
-                self.config.safety_rules.backend.clone(),
-                self.quorum_store_storage.clone(),
-            ))
-        } else {
-            info!("Building DirectMempool");
-            QuorumStoreBuilder::DirectMempool(DirectMempoolInnerBuilder::new(
-                consensus_to_quorum_store_rx,
-                self.quorum_store_to_mempool_sender.clone(),
-                self.config.mempool_txn_pull_timeout_ms,
-            ))
-        };
-
-        let (payload_manager, quorum_store_msg_tx) = quorum_store_builder.init_payload_manager();
-        let transaction_shuffler =
-            create_transaction_shuffler(onchain_execution_config.transaction_shuffler_type());
-        let block_gas_limit = onchain_execution_config.block_gas_limit();
-        let transaction_deduper =
-            create_transaction_deduper(onchain_execution_config.transaction_deduper_type());
-        self.quorum_store_msg_tx = quorum_store_msg_tx;
-
-        let payload_client = QuorumStoreClient::new(
-            consensus_to_quorum_store_tx,
-            self.config.quorum_store_pull_timeout_ms,
-            self.config.wait_for_full_blocks_above_recent_fill_threshold,
-            self.config.wait_for_full_blocks_above_pending_blocks,
-        );
-        self.commit_state_computer.new_epoch(
+        let (payload_manager, payload_client, quorum_store_builder) =
+            self.init_payload_provider(&epoch_state, network_sender.clone());
+        self.new_state_computer_epoch(
             &epoch_state,
             payload_manager.clone(),
-            transaction_shuffler,
-            block_gas_limit,
-            transaction_deduper,
+            &onchain_execution_config,
         );
         let state_computer = if onchain_consensus_config.decoupled_execution() {
-            Arc::new(self.spawn_decoupled_execution(
+            let (block_tx, reset_tx) = self.spawn_decoupled_execution(
                 safety_rules_container.clone(),
                 epoch_state.verifier.clone(),
+            );
+            Arc::new(OrderingStateComputer::new(
+                block_tx,
+                self.commit_state_computer.clone(),
+                reset_tx,
             ))
         } else {
             self.commit_state_computer.clone()
@@ -792,6 +780,174 @@ impl<P: OnChainConfigProvider> EpochManager<P> {
         self.spawn_block_retrieval_task(epoch, block_store);
     }
 
+    fn init_payload_provider(
+        &mut self,
+        epoch_state: &EpochState,
+        network_sender: NetworkSender,
+    ) -> (Arc<PayloadManager>, QuorumStoreClient, QuorumStoreBuilder) {
+        // Start QuorumStore
+        let (consensus_to_quorum_store_tx, consensus_to_quorum_store_rx) =
+            mpsc::channel(self.config.intra_consensus_channel_buffer_size);
+
+        let mut quorum_store_builder = if self.quorum_store_enabled {
+            info!("Building QuorumStore");
+            QuorumStoreBuilder::QuorumStore(InnerBuilder::new(
+                self.epoch(),
+                self.author,
+                epoch_state.verifier.len() as u64,
+                self.config.quorum_store.clone(),
+                consensus_to_quorum_store_rx,
+                self.quorum_store_to_mempool_sender.clone(),
+                self.config.mempool_txn_pull_timeout_ms,
+                self.storage.aptos_db().clone(),
+                network_sender,
+                epoch_state.verifier.clone(),
+                self.config.safety_rules.backend.clone(),
+                self.quorum_store_storage.clone(),
+            ))
+        } else {
+            info!("Building DirectMempool");
+            QuorumStoreBuilder::DirectMempool(DirectMempoolInnerBuilder::new(
+                consensus_to_quorum_store_rx,
+                self.quorum_store_to_mempool_sender.clone(),
+                self.config.mempool_txn_pull_timeout_ms,
+            ))
+        };
+
+        let (payload_manager, quorum_store_msg_tx) = quorum_store_builder.init_payload_manager();
+        self.quorum_store_msg_tx = quorum_store_msg_tx;
+
+        let payload_client = QuorumStoreClient::new(
+            consensus_to_quorum_store_tx,
+            self.config.quorum_store_pull_timeout_ms,
+            self.config.wait_for_full_blocks_above_recent_fill_threshold,
+            self.config.wait_for_full_blocks_above_pending_blocks,
+        );
+        (payload_manager, payload_client, quorum_store_builder)
+    }
+
+    fn new_state_computer_epoch(
+        &self,
+        epoch_state: &EpochState,
+        payload_manager: Arc<PayloadManager>,
+        onchain_execution_config: &OnChainExecutionConfig,
+    ) {
+        let transaction_shuffler =
+            create_transaction_shuffler(onchain_execution_config.transaction_shuffler_type());
+        let block_gas_limit = onchain_execution_config.block_gas_limit();
+        let transaction_deduper =
+            create_transaction_deduper(onchain_execution_config.transaction_deduper_type());
+        self.commit_state_computer.new_epoch(
+            epoch_state,
+            payload_manager,
+            transaction_shuffler,
+            block_gas_limit,
+            transaction_deduper,
+        );
+    }
+
+    /// The DAG signs its nodes and votes with the consensus key of the epoch, which is looked up
+    /// by its public key in the validator set the same way safety rules reconciles it.
+    fn load_consensus_signer(&self, epoch_state: &EpochState) -> anyhow::Result<ValidatorSigner> {
+        let public_key = epoch_state
+            .verifier
+            .get_public_key(&self.author)
+            .ok_or_else(|| anyhow!("{} is not in the validator set", self.author))?;
+        let storage = Storage::from(&self.config.safety_rules.backend);
+        storage.available()?;
+        let private_key =
+            PersistentSafetyStorage::new(storage, false).consensus_key_for_version(public_key)?;
+        Ok(ValidatorSigner::new(self.author, private_key))
+    }
+
+    async fn start_new_epoch_with_dag(
+        &mut self,
+        epoch_state: EpochState,
+        onchain_execution_config: OnChainExecutionConfig,
+    ) {
+        let epoch = epoch_state.epoch;
+        counters::EPOCH.set(epoch_state.epoch as i64);
+        counters::CURRENT_EPOCH_VALIDATORS.set(epoch_state.verifier.len() as i64);
+        info!(
+            epoch = epoch_state.epoch,
+            validators = epoch_state.verifier.to_string(),
+            "Starting new epoch with DAG",
+        );
+
+        let signer = match self.load_consensus_signer(&epoch_state) {
+            Ok(signer) => signer,
+            Err(error) => {
+                error!(
+                    epoch = epoch,
+                    error = ?error,
+                    "Unable to load the consensus key, not starting the DAG",
+                );
+                return;
+            },
+        };
+
+        let network_sender = NetworkSender::new(
+            self.author,
+            self.network_sender.clone(),
+            self.self_sender.clone(),
+            epoch_state.verifier.clone(),
+        );
+        let (payload_manager, payload_client, quorum_store_builder) =
+            self.init_payload_provider(&epoch_state, network_sender.clone());
+        self.new_state_computer_epoch(&epoch_state, payload_manager, &onchain_execution_config);
+
+        info!(epoch = epoch, "Update SafetyRules");
+        let mut safety_rules =
+            MetricsSafetyRules::new(self.safety_rules_manager.client(), self.storage.clone());
+        if let Err(error) = safety_rules.perform_initialize() {
+            error!(
+                epoch = epoch,
+                error = error,
+                "Unable to initialize safety rules.",
+            );
+        }
+        let commit_signer = Arc::new(DagCommitSigner::new(safety_rules));
+        let (block_tx, _) =
+            self.spawn_decoupled_execution(commit_signer, epoch_state.verifier.clone());
+
+        if let Some((quorum_store_coordinator_tx, batch_retrieval_rx)) =
+            quorum_store_builder.start()
+        {
+            self.quorum_store_coordinator_tx = Some(quorum_store_coordinator_tx);
+            self.batch_retrieval_tx = Some(batch_retrieval_rx);
+        }
+
+        let latest_ledger_info = self
+            .storage
+            .aptos_db()
+            .get_latest_ledger_info()
+            .expect("Unable to read the latest ledger info")
+            .ledger_info()
+            .clone();
+        let (dag_rpc_tx, dag_rpc_rx) = aptos_channel::new(
+            QueueStyle::FIFO,
+            self.config.dag_consensus.rpc_channel_size,
+            None,
+        );
+        self.dag_rpc_tx = Some(dag_rpc_tx);
+
+        info!(epoch = epoch, "Bootstrap DAG");
+        self.dag_shutdown_tx = Some(bootstrap_dag(
+            self.author,
+            signer,
+            Arc::new(epoch_state),
+            latest_ledger_info,
+            self.storage.consensus_db(),
+            Arc::new(network_sender),
+            self.time_service.clone(),
+            Arc::new(payload_client),
+            self.quorum_store_enabled,
+            &self.config.dag_consensus,
+            dag_rpc_rx,
+            block_tx,
+        ));
+    }
+
     async fn start_new_epoch(&mut self, payload: OnChainConfigPayload<P>) {
         let validator_set: ValidatorSet = payload
             .get()
@@ -813,6 +969,20 @@ impl<P: OnChainConfigProvider> EpochManager<P> {
 
         self.epoch_state = Some(Arc::new(epoch_state.clone()));
 
+        // All validators read the same on-chain config at the epoch boundary, so they switch
+        // between the DAG and Jolteon together.
+        if let Ok(consensus_config) = &onchain_consensus_config {
+            if consensus_config.is_dag_enabled() {
+                let execution_config = onchain_execution_config
+                    .unwrap_or_else(|_| OnChainExecutionConfig::default_if_missing());
+                self.quorum_store_enabled = self.enable_quorum_store(consensus_config);
+                self.recovery_mode = false;
+                self.start_new_epoch_with_dag(epoch_state, execution_config)
+                    .await;
+                return;
+            }
+        }
+
         match self.storage.start() {
             LivenessStorageData::FullRecoveryData(initial_data) => {
                 let consensus_config = onchain_consensus_config.unwrap_or_default();
@@ -1067,15 +1237,18 @@ impl<P: OnChainConfigProvider> EpochManager<P> {
                 }
             },
             IncomingRpcRequest::DAGRequest(request) => {
-                let dag_message = request.req;
+                let epoch = request.req.epoch;
 
-                if dag_message.epoch == self.epoch() {
-                    // TODO: send message to DAG handler
-                    Ok(())
+                if epoch == self.epoch() {
+                    if let Some(tx) = &self.dag_rpc_tx {
+                        tx.push(peer_id, request)
+                    } else {
+                        Err(anyhow::anyhow!("DAG not started"))
+                    }
                 } else {
                     monitor!(
                         "process_different_epoch_dag_rpc",
-                        self.process_different_epoch(dag_message.epoch, peer_id)
+                        self.process_different_epoch(epoch, peer_id)
                     )
                 }
             },
@@ -398,7 +398,9 @@ impl BufferItem {
                 }
             },
             Self::Executed(executed) => {
-                if executed.commit_info == *target_commit_info {
+                // the signature covers the whole ledger info, a vote for the same block with a
+                // different consensus data hash would break the aggregated signature
+                if executed.partial_commit_proof.ledger_info() == vote.ledger_info() {
                     executed
                         .partial_commit_proof
                         .add_signature(author, signature);
@@ -406,7 +408,7 @@ impl BufferItem {
                 }
             },
             Self::Signed(signed) => {
-                if signed.partial_commit_proof.commit_info() == target_commit_info {
+                if signed.partial_commit_proof.ledger_info() == vote.ledger_info() {
                     signed.partial_commit_proof.add_signature(author, signature);
                     return Ok(());
                 }
@@ -8,16 +8,14 @@ use crate::{
         execution_phase::{ExecutionPhase, ExecutionRequest, ExecutionResponse},
         persisting_phase::{PersistingPhase, PersistingRequest},
         pipeline_phase::{CountedRequest, PipelinePhase},
-        signing_phase::{SigningPhase, SigningRequest, SigningResponse},
+        signing_phase::{CommitSignerProvider, SigningPhase, SigningRequest, SigningResponse},
     },
-    metrics_safety_rules::MetricsSafetyRules,
     network::NetworkSender,
     round_manager::VerifiedEvent,
     state_replication::StateComputer,
 };
 use aptos_channels::aptos_channel::Receiver;
 use aptos_consensus_types::common::Author;
-use aptos_infallible::Mutex;
 use aptos_types::{account_address::AccountAddress, validator_verifier::ValidatorVerifier};
 use futures::channel::mpsc::UnboundedReceiver;
 use std::sync::{atomic::AtomicU64, Arc};
@@ -26,7 +24,7 @@ use std::sync::{atomic::AtomicU64, Arc};
 pub fn prepare_phases_and_buffer_manager(
     author: Author,
     execution_proxy: Arc<dyn StateComputer>,
-    safety_rules: Arc<Mutex<MetricsSafetyRules>>,
+    commit_signer_provider: Arc<dyn CommitSignerProvider>,
     commit_msg_tx: NetworkSender,
     commit_msg_rx: Receiver<AccountAddress, VerifiedEvent>,
     persisting_proxy: Arc<dyn StateComputer>,
@@ -60,7 +58,7 @@ pub fn prepare_phases_and_buffer_manager(
     let (signing_phase_response_tx, signing_phase_response_rx) =
         create_channel::<SigningResponse>();
 
-    let signing_phase_processor = SigningPhase::new(safety_rules);
+    let signing_phase_processor = SigningPhase::new(commit_signer_provider);
     let signing_phase = PipelinePhase::new(
         signing_phase_request_rx,
         Some(signing_phase_response_tx),
@@ -58,6 +58,10 @@ use aptos_metrics_core::IntGauge;
 pub use consensusdb::create_checkpoint;
 /// Required by the smoke tests
 pub use consensusdb::CONSENSUS_DB_NAME;
+/// Required by the node to serve the anchor votes from the inspection service
+pub use dag::{anchor_vote_history, AnchorVoteRecord};
+/// Debug tooling to replay the DAG ordering locally
+pub use dag::{export_dag_snapshot, DagSnapshot};
 pub use quorum_store::quorum_store_db::QUORUM_STORE_DB_NAME;
 #[cfg(feature = "fuzzing")]
 pub use round_manager::round_manager_fuzzing;
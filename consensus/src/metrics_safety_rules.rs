@@ -131,6 +131,19 @@ impl TSafetyRules for MetricsSafetyRules {
             )
         })
     }
+
+    fn sign_dag_commit_vote(
+        &mut self,
+        ordered_ledger_info: LedgerInfo,
+        new_ledger_info: LedgerInfo,
+    ) -> Result<bls12381::Signature, Error> {
+        self.retry(|inner| {
+            monitor!(
+                "safety_rules",
+                inner.sign_dag_commit_vote(ordered_ledger_info.clone(), new_ledger_info.clone())
+            )
+        })
+    }
 }
 
 #[cfg(test)]
@@ -220,6 +233,14 @@ mod tests {
         ) -> Result<bls12381::Signature, Error> {
             unimplemented!()
         }
+
+        fn sign_dag_commit_vote(
+            &mut self,
+            _: LedgerInfo,
+            _: LedgerInfo,
+        ) -> Result<bls12381::Signature, Error> {
+            unimplemented!()
+        }
     }
 
     #[test]
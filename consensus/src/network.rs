@@ -5,10 +5,10 @@
 use crate::{
     block_storage::tracing::{observe_block, BlockStage},
     counters,
-    dag::DAGNetworkMessage,
+    dag::{DAGMessage, DAGNetworkMessage},
     logging::LogEvent,
     monitor,
-    network_interface::{ConsensusMsg, ConsensusNetworkClient},
+    network_interface::{ConsensusMsg, ConsensusNetworkClient, RPC},
     quorum_store::types::{Batch, BatchMsg, BatchRequest},
 };
 use anyhow::{anyhow, bail, ensure};
@@ -29,6 +29,7 @@ use aptos_network::{
     protocols::{network::Event, rpc::error::RpcError},
     ProtocolId,
 };
+use aptos_reliable_broadcast::RBNetworkSender;
 use aptos_types::{
     account_address::AccountAddress, epoch_change::EpochChangeProof,
     ledger_info::LedgerInfoWithSignatures, validator_verifier::ValidatorVerifier,
@@ -339,6 +340,40 @@ impl NetworkSender {
         self.author
     }
 
+    /// Sends the rpc to the given peer, rpcs to ourself are looped back through the self sender
+    /// so that broadcasting protocols can treat us like any other validator.
+    pub async fn send_rpc(
+        &self,
+        receiver: Author,
+        msg: ConsensusMsg,
+        timeout: Duration,
+    ) -> anyhow::Result<ConsensusMsg> {
+        fail_point!("consensus::send::any", |_| {
+            Err(anyhow::anyhow!("Injected error in send_rpc"))
+        });
+        counters::CONSENSUS_SENT_MSGS
+            .with_label_values(&[msg.name()])
+            .inc();
+        if receiver == self.author {
+            let (tx, rx) = oneshot::channel();
+            let protocol = RPC[0];
+            let self_msg = Event::RpcRequest(self.author, msg, protocol, tx);
+            self.self_sender.clone().send(self_msg).await?;
+            let response = tokio::time::timeout(timeout, rx)
+                .await
+                .map_err(|_| anyhow!("self rpc timed out"))?
+                .map_err(|_| anyhow!("self rpc response dropped"))??;
+            protocol.from_bytes(&response)
+        } else {
+            Ok(monitor!(
+                "send_rpc",
+                self.consensus_network_client
+                    .send_rpc(receiver, msg, timeout)
+                    .await
+            )?)
+        }
+    }
+
     pub async fn broadcast_commit_proof(&mut self, ledger_info: LedgerInfoWithSignatures) {
         fail_point!("consensus::send::broadcast_commit_proof", |_| ());
         let msg = ConsensusMsg::CommitDecisionMsg(Box::new(CommitDecision::new(ledger_info)));
@@ -404,6 +439,20 @@ impl QuorumStoreSender for NetworkSender {
     }
 }
 
+#[async_trait::async_trait]
+impl RBNetworkSender<DAGMessage> for NetworkSender {
+    async fn send_rpc(
+        &self,
+        receiver: Author,
+        message: DAGMessage,
+        timeout: Duration,
+    ) -> anyhow::Result<DAGMessage> {
+        self.send_rpc(receiver, message.into_network_message(), timeout)
+            .await
+            .and_then(DAGMessage::from_network_message)
+    }
+}
+
 pub struct NetworkTask {
     consensus_messages_tx: aptos_channel::Sender<
         (AccountAddress, Discriminant<ConsensusMsg>),
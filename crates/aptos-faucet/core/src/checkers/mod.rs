@@ -8,6 +8,7 @@ mod magic_header;
 mod memory_ratelimit;
 mod redis_ratelimit;
 mod referer_blocklist;
+mod sliding_window_ratelimit;
 mod tap_captcha;
 
 pub use self::tap_captcha::CaptchaManager;
@@ -19,6 +20,9 @@ use self::{
     memory_ratelimit::{MemoryRatelimitChecker, MemoryRatelimitCheckerConfig},
     redis_ratelimit::{RedisRatelimitChecker, RedisRatelimitCheckerConfig},
     referer_blocklist::RefererBlocklistChecker,
+    sliding_window_ratelimit::{
+        SlidingWindowRatelimitChecker, SlidingWindowRatelimitCheckerConfig,
+    },
     tap_captcha::{TapCaptchaChecker, TapCaptchaCheckerConfig},
 };
 use crate::{
@@ -102,6 +106,10 @@ pub enum CheckerConfig {
     /// Rejects requests if their Referer is blocklisted.
     RefererBlocklist(ListManagerConfig),
 
+    /// Ratelimiter with per IP and per account budgets over sliding windows,
+    /// backed by memory or Redis.
+    SlidingWindowRatelimit(SlidingWindowRatelimitCheckerConfig),
+
     /// In-house captcha solution.
     TapCaptcha(TapCaptchaCheckerConfig),
 }
@@ -124,6 +132,9 @@ impl CheckerConfig {
             CheckerConfig::RefererBlocklist(config) => {
                 Checker::from(RefererBlocklistChecker::new(config)?)
             },
+            CheckerConfig::SlidingWindowRatelimit(config) => {
+                Checker::from(SlidingWindowRatelimitChecker::new(config).await?)
+            },
             CheckerConfig::TapCaptcha(config) => {
                 Checker::from(TapCaptchaChecker::new(config, captcha_manager)?)
             },
@@ -141,11 +152,15 @@ pub enum Checker {
     MemoryRatelimitChecker,
     RedisRatelimitChecker,
     RefererBlocklistChecker,
+    SlidingWindowRatelimitChecker,
     TapCaptchaChecker,
 }
 
 #[derive(Clone, Debug)]
 pub struct CheckerData {
+    /// Random identifier of the request, telling it apart from other requests
+    /// received at the same time.
+    pub request_id: u64,
     pub time_request_received_secs: u64,
     pub receiver: AccountAddress,
     pub source_ip: IpAddr,
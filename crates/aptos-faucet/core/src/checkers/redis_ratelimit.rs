@@ -3,74 +3,26 @@
 
 use super::{CheckerData, CheckerTrait, CompleteData};
 use crate::{
+    common::RedisConnectionConfig,
     endpoints::{AptosTapError, AptosTapErrorCode, RejectionReason, RejectionReasonCode},
     helpers::{days_since_tap_epoch, get_current_time_secs, seconds_until_next_day},
 };
 use anyhow::{Context, Result};
 use async_trait::async_trait;
-use deadpool_redis::{
-    redis::{AsyncCommands, ConnectionAddr, ConnectionInfo, RedisConnectionInfo},
-    Config, Connection, Pool, Runtime,
-};
+use deadpool_redis::{redis::AsyncCommands, Connection, Pool};
 use serde::{Deserialize, Serialize};
 use std::net::IpAddr;
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct RedisRatelimitCheckerConfig {
-    /// The database address to connect to, not including port,
-    /// e.g. db.example.com or 234.121.222.42.
-    pub database_address: String,
-
-    /// The port to connect to.
-    #[serde(default = "RedisRatelimitCheckerConfig::default_database_port")]
-    pub database_port: u16,
-
-    /// The number of the database to use. If it doesn't exist, it will be created (todo verify this)
-    #[serde(default = "RedisRatelimitCheckerConfig::default_database_number")]
-    pub database_number: i64,
-
-    /// The name of the user to use, if necessary.
-    pub database_user: Option<String>,
-
-    /// The password of the given user, if necessary.
-    pub database_password: Option<String>,
+    #[serde(flatten)]
+    pub database_args: RedisConnectionConfig,
 
     /// Max number of requests per IP per day. 500s are not counted, because
     /// they are not the user's fault, but everything else is.
     pub max_requests_per_ip_per_day: u32,
 }
 
-impl RedisRatelimitCheckerConfig {
-    fn default_database_port() -> u16 {
-        6379
-    }
-
-    fn default_database_number() -> i64 {
-        0
-    }
-
-    fn build_connection_info(&self) -> ConnectionInfo {
-        ConnectionInfo {
-            addr: ConnectionAddr::Tcp(self.database_address.clone(), self.database_port),
-            redis: RedisConnectionInfo {
-                db: self.database_number,
-                username: self.database_user.clone(),
-                password: self.database_password.clone(),
-            },
-        }
-    }
-
-    pub fn build_db_pool(&self) -> Result<Pool> {
-        let connection_info = self.build_connection_info();
-        let cfg = Config {
-            connection: Some(connection_info.into()),
-            ..Default::default()
-        };
-        cfg.create_pool(Some(Runtime::Tokio1))
-            .context("Failed to build redis connection pool")
-    }
-}
-
 /// The RedisRatelimitChecker backend uses redis to ratelimit requests to the tap. Unlike
 /// the PostgresStorage backend, it does not store full information for each
 /// request. Instead, it uses counters to track limits. This is heavily inspired
@@ -108,7 +60,7 @@ pub struct RedisRatelimitChecker {
 
 impl RedisRatelimitChecker {
     pub async fn new(args: RedisRatelimitCheckerConfig) -> Result<Self> {
-        let db_pool = args.build_db_pool()?;
+        let db_pool = args.database_args.build_db_pool()?;
 
         // Ensure we can connect.
         db_pool
@@ -0,0 +1,175 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use super::{CheckerData, CheckerTrait, CompleteData};
+use crate::{
+    common::{RatelimitStorage, RatelimitStorageConfig, RatelimitStorageTrait},
+    endpoints::{AptosTapError, RejectionReason, RejectionReasonCode},
+};
+use anyhow::{bail, Result};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+/// How many requests we allow over a sliding window of time.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct SlidingWindowConfig {
+    pub max_requests: u32,
+    pub window_secs: u64,
+}
+
+impl SlidingWindowConfig {
+    fn window_ms(&self) -> u64 {
+        self.window_secs * 1000
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct SlidingWindowRatelimitCheckerConfig {
+    /// Where to keep track of the requests. Use a storage shared by all the
+    /// replicas of the tap, e.g. Redis, to enforce the same limits on all of them.
+    pub storage: RatelimitStorageConfig,
+
+    /// Budget of requests for each source IP.
+    pub per_ip: Option<SlidingWindowConfig>,
+
+    /// Budget of requests for each receiving account.
+    pub per_account: Option<SlidingWindowConfig>,
+}
+
+/// Ratelimiter enforcing budgets of requests per IP and / or per receiving
+/// account over sliding windows, unlike the other ratelimiters which reset
+/// their counters every day. 500s are not counted, because they are not the
+/// user's fault, but everything else is.
+pub struct SlidingWindowRatelimitChecker {
+    storage: RatelimitStorage,
+    per_ip: Option<SlidingWindowConfig>,
+    per_account: Option<SlidingWindowConfig>,
+    cost: u8,
+}
+
+impl SlidingWindowRatelimitChecker {
+    pub async fn new(args: SlidingWindowRatelimitCheckerConfig) -> Result<Self> {
+        if args.per_ip.is_none() && args.per_account.is_none() {
+            bail!("At least one of per_ip and per_account must be set");
+        }
+        for window in args.per_ip.iter().chain(args.per_account.iter()) {
+            if window.max_requests == 0 || window.window_secs == 0 {
+                bail!("max_requests and window_secs must be greater than 0");
+            }
+        }
+        let cost = match args.storage {
+            RatelimitStorageConfig::Memory(_) => 20,
+            RatelimitStorageConfig::Redis(_) => 50,
+        };
+        Ok(Self {
+            storage: args.storage.build().await?,
+            per_ip: args.per_ip,
+            per_account: args.per_account,
+            cost,
+        })
+    }
+
+    fn ip_key(data: &CheckerData) -> String {
+        format!("ratelimit:ip:{}", data.source_ip)
+    }
+
+    fn account_key(data: &CheckerData) -> String {
+        format!("ratelimit:account:{}", data.receiver)
+    }
+}
+
+#[async_trait]
+impl CheckerTrait for SlidingWindowRatelimitChecker {
+    async fn check(
+        &self,
+        data: CheckerData,
+        dry_run: bool,
+    ) -> Result<Vec<RejectionReason>, AptosTapError> {
+        let now_ms = data.time_request_received_secs * 1000;
+
+        if let Some(per_ip) = &self.per_ip {
+            if let Some(retry_after_ms) = self
+                .storage
+                .acquire(
+                    &Self::ip_key(&data),
+                    data.request_id,
+                    per_ip.max_requests,
+                    per_ip.window_ms(),
+                    now_ms,
+                    dry_run,
+                )
+                .await?
+            {
+                return Ok(vec![RejectionReason::new(
+                    format!(
+                        "IP {} has reached the maximum allowed number of requests per {} seconds: {}",
+                        data.source_ip, per_ip.window_secs, per_ip.max_requests
+                    ),
+                    RejectionReasonCode::IpUsageLimitExhausted,
+                )
+                .retry_after((retry_after_ms + 999) / 1000)]);
+            }
+        }
+
+        if let Some(per_account) = &self.per_account {
+            if let Some(retry_after_ms) = self
+                .storage
+                .acquire(
+                    &Self::account_key(&data),
+                    data.request_id,
+                    per_account.max_requests,
+                    per_account.window_ms(),
+                    now_ms,
+                    dry_run,
+                )
+                .await?
+            {
+                // The request is rejected, so it shouldn't use up the budget of the IP.
+                if self.per_ip.is_some() && !dry_run {
+                    self.storage
+                        .release(&Self::ip_key(&data), data.request_id)
+                        .await?;
+                }
+                return Ok(vec![RejectionReason::new(
+                    format!(
+                        "Account {} has reached the maximum allowed number of requests per {} seconds: {}",
+                        data.receiver, per_account.window_secs, per_account.max_requests
+                    ),
+                    RejectionReasonCode::AccountUsageLimitExhausted,
+                )
+                .retry_after((retry_after_ms + 999) / 1000)]);
+            }
+        }
+
+        Ok(vec![])
+    }
+
+    /// All we have to do here is release the requests if they were a failure
+    /// due to something wrong on our end.
+    async fn complete(&self, data: CompleteData) -> Result<(), AptosTapError> {
+        if !data.response_is_500 {
+            return Ok(());
+        }
+        if self.per_ip.is_some() {
+            self.storage
+                .release(
+                    &Self::ip_key(&data.checker_data),
+                    data.checker_data.request_id,
+                )
+                .await?;
+        }
+        if self.per_account.is_some() {
+            self.storage
+                .release(
+                    &Self::account_key(&data.checker_data),
+                    data.checker_data.request_id,
+                )
+                .await?;
+        }
+        Ok(())
+    }
+
+    fn cost(&self) -> u8 {
+        self.cost
+    }
+}
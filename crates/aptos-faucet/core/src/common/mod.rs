@@ -3,6 +3,13 @@
 
 mod ip_range_manager;
 mod list_manager;
+mod ratelimit_storage;
+mod redis_connection;
 
 pub use ip_range_manager::{IpRangeManager, IpRangeManagerConfig};
 pub use list_manager::{ListManager, ListManagerConfig};
+pub use ratelimit_storage::{
+    MemoryRatelimitStorage, MemoryRatelimitStorageConfig, RatelimitStorage, RatelimitStorageConfig,
+    RatelimitStorageTrait, RedisRatelimitStorage,
+};
+pub use redis_connection::RedisConnectionConfig;
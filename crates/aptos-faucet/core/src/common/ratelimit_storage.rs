@@ -0,0 +1,357 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use super::RedisConnectionConfig;
+use crate::endpoints::{AptosTapError, AptosTapErrorCode};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use deadpool_redis::{Connection, Pool};
+use enum_dispatch::enum_dispatch;
+use lru::LruCache;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::{collections::VecDeque, num::NonZeroUsize};
+use tokio::sync::Mutex;
+
+/// Implementers of this trait store the requests made under a ratelimit key
+/// (e.g. an IP or an account address), so we can tell how many of them fall
+/// within a sliding window. Storages shared by every replica of the tap, e.g.
+/// Redis, let horizontally scaled taps enforce the same limits.
+#[async_trait]
+#[enum_dispatch]
+pub trait RatelimitStorageTrait: Sync + Send + 'static {
+    /// Records the request `request_id` for `key` at `now_ms`, unless
+    /// `max_requests` requests were already recorded in the `window_ms` before
+    /// it. In that case nothing is recorded and we return how many milliseconds
+    /// until the oldest of them leaves the window. If dry_run is set, the request
+    /// is never recorded.
+    async fn acquire(
+        &self,
+        key: &str,
+        request_id: u64,
+        max_requests: u32,
+        window_ms: u64,
+        now_ms: u64,
+        dry_run: bool,
+    ) -> Result<Option<u64>, AptosTapError>;
+
+    /// Forgets the request `request_id` recorded for `key`. We use this when a
+    /// request failed for reasons that are not the user's fault.
+    async fn release(&self, key: &str, request_id: u64) -> Result<(), AptosTapError>;
+}
+
+/// This enum lets us represent all the different ratelimit storages in a config.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(tag = "type")]
+pub enum RatelimitStorageConfig {
+    /// Keeps the requests in memory, so every replica of the tap has its own limits.
+    Memory(MemoryRatelimitStorageConfig),
+
+    /// Keeps the requests in Redis, so every replica of the tap shares the same limits.
+    Redis(RedisConnectionConfig),
+}
+
+impl RatelimitStorageConfig {
+    pub async fn build(self) -> Result<RatelimitStorage> {
+        Ok(match self {
+            RatelimitStorageConfig::Memory(config) => {
+                RatelimitStorage::from(MemoryRatelimitStorage::new(config))
+            },
+            RatelimitStorageConfig::Redis(config) => {
+                RatelimitStorage::from(RedisRatelimitStorage::new(config).await?)
+            },
+        })
+    }
+}
+
+/// This enum has as its variants all possible implementations of RatelimitStorageTrait.
+#[enum_dispatch(RatelimitStorageTrait)]
+pub enum RatelimitStorage {
+    MemoryRatelimitStorage,
+    RedisRatelimitStorage,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct MemoryRatelimitStorageConfig {
+    #[serde(default = "MemoryRatelimitStorageConfig::default_max_entries_in_map")]
+    pub max_entries_in_map: NonZeroUsize,
+}
+
+impl MemoryRatelimitStorageConfig {
+    fn default_max_entries_in_map() -> NonZeroUsize {
+        NonZeroUsize::new(1000000).unwrap()
+    }
+}
+
+/// In memory storage, keeping the time and ID of each request in the window of
+/// each key. To avoid OOMing the server, we set a limit on how many keys we track.
+pub struct MemoryRatelimitStorage {
+    key_to_requests: Mutex<LruCache<String, VecDeque<(u64, u64)>>>,
+}
+
+impl MemoryRatelimitStorage {
+    pub fn new(args: MemoryRatelimitStorageConfig) -> Self {
+        Self {
+            key_to_requests: Mutex::new(LruCache::new(args.max_entries_in_map)),
+        }
+    }
+}
+
+#[async_trait]
+impl RatelimitStorageTrait for MemoryRatelimitStorage {
+    async fn acquire(
+        &self,
+        key: &str,
+        request_id: u64,
+        max_requests: u32,
+        window_ms: u64,
+        now_ms: u64,
+        dry_run: bool,
+    ) -> Result<Option<u64>, AptosTapError> {
+        let mut key_to_requests = self.key_to_requests.lock().await;
+        let requests = key_to_requests.get_or_insert_mut(key.to_string(), VecDeque::new);
+
+        // Drop the requests that left the window.
+        while let Some((oldest_ms, _)) = requests.front() {
+            if oldest_ms + window_ms > now_ms {
+                break;
+            }
+            requests.pop_front();
+        }
+
+        if requests.len() >= max_requests as usize {
+            let oldest_ms = requests.front().map_or(now_ms, |(time_ms, _)| *time_ms);
+            return Ok(Some((oldest_ms + window_ms).saturating_sub(now_ms)));
+        }
+        if !dry_run {
+            requests.push_back((now_ms, request_id));
+        }
+        Ok(None)
+    }
+
+    async fn release(&self, key: &str, request_id: u64) -> Result<(), AptosTapError> {
+        if let Some(requests) = self.key_to_requests.lock().await.get_mut(key) {
+            requests.retain(|(_, id)| *id != request_id);
+        }
+        Ok(())
+    }
+}
+
+/// Trims the requests that left the window and, if there is room left, records
+/// the new one, all in one atomic step. Returns the number of milliseconds until
+/// the oldest request leaves the window if there is no room, 0 otherwise.
+static ACQUIRE_SCRIPT: Lazy<redis::Script> = Lazy::new(|| {
+    redis::Script::new(
+        r#"
+        local now_ms = tonumber(ARGV[1])
+        local window_ms = tonumber(ARGV[2])
+        local max_requests = tonumber(ARGV[3])
+        redis.call("ZREMRANGEBYSCORE", KEYS[1], "-inf", now_ms - window_ms)
+        if redis.call("ZCARD", KEYS[1]) >= max_requests then
+            local oldest = redis.call("ZRANGE", KEYS[1], 0, 0, "WITHSCORES")
+            return math.max(tonumber(oldest[2]) + window_ms - now_ms, 1)
+        end
+        if ARGV[5] == "1" then
+            redis.call("ZADD", KEYS[1], now_ms, ARGV[4])
+            redis.call("PEXPIRE", KEYS[1], window_ms)
+        end
+        return 0
+        "#,
+    )
+});
+
+/// Redis storage, keeping the IDs of the requests in the window of each key in
+/// a sorted set scored by the time of the request. This implements the sliding window
+/// log described in https://redis.com/glossary/rate-limiting/. Unlike
+/// RedisRatelimitChecker, which uses a counter per day, the window slides with
+/// time, so users can't make twice their budget of requests around midnight.
+pub struct RedisRatelimitStorage {
+    db_pool: Pool,
+}
+
+impl RedisRatelimitStorage {
+    pub async fn new(args: RedisConnectionConfig) -> Result<Self> {
+        let db_pool = args.build_db_pool()?;
+
+        // Ensure we can connect.
+        db_pool
+            .get()
+            .await
+            .context("Failed to connect to redis on startup")?;
+
+        Ok(Self { db_pool })
+    }
+
+    async fn get_redis_connection(&self) -> Result<Connection, AptosTapError> {
+        self.db_pool.get().await.map_err(|e| {
+            AptosTapError::new_with_error_code(
+                format!("Failed to connect to redis storage: {}", e),
+                AptosTapErrorCode::StorageError,
+            )
+        })
+    }
+}
+
+#[async_trait]
+impl RatelimitStorageTrait for RedisRatelimitStorage {
+    async fn acquire(
+        &self,
+        key: &str,
+        request_id: u64,
+        max_requests: u32,
+        window_ms: u64,
+        now_ms: u64,
+        dry_run: bool,
+    ) -> Result<Option<u64>, AptosTapError> {
+        let mut conn = self.get_redis_connection().await?;
+        let retry_after_ms: u64 = ACQUIRE_SCRIPT
+            .key(key)
+            .arg(now_ms)
+            .arg(window_ms)
+            .arg(max_requests)
+            .arg(request_id)
+            .arg(if dry_run { "0" } else { "1" })
+            .invoke_async(&mut *conn)
+            .await
+            .map_err(|e| {
+                AptosTapError::new_with_error_code(
+                    format!("Failed to acquire ratelimit for redis key {}: {}", key, e),
+                    AptosTapErrorCode::StorageError,
+                )
+            })?;
+
+        Ok(if retry_after_ms > 0 {
+            Some(retry_after_ms)
+        } else {
+            None
+        })
+    }
+
+    async fn release(&self, key: &str, request_id: u64) -> Result<(), AptosTapError> {
+        let mut conn = self.get_redis_connection().await?;
+        redis::cmd("ZREM")
+            .arg(key)
+            .arg(request_id)
+            .query_async::<_, ()>(&mut *conn)
+            .await
+            .map_err(|e| {
+                AptosTapError::new_with_error_code(
+                    format!("Failed to release ratelimit for redis key {}: {}", key, e),
+                    AptosTapErrorCode::StorageError,
+                )
+            })?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_memory_ratelimit_storage() {
+        let storage = MemoryRatelimitStorage::new(MemoryRatelimitStorageConfig {
+            max_entries_in_map: NonZeroUsize::new(10).unwrap(),
+        });
+
+        // Two requests per second.
+        assert_eq!(
+            storage.acquire("a", 1, 2, 1000, 0, false).await.unwrap(),
+            None
+        );
+        assert_eq!(
+            storage.acquire("a", 2, 2, 1000, 400, true).await.unwrap(),
+            None
+        );
+        assert_eq!(
+            storage.acquire("a", 3, 2, 1000, 500, false).await.unwrap(),
+            None
+        );
+        assert_eq!(
+            storage.acquire("a", 4, 2, 1000, 600, false).await.unwrap(),
+            Some(400)
+        );
+
+        // Other keys have their own budget.
+        assert_eq!(
+            storage.acquire("b", 5, 2, 1000, 600, false).await.unwrap(),
+            None
+        );
+
+        // The first request leaves the window, making room for another.
+        assert_eq!(
+            storage.acquire("a", 6, 2, 1000, 1000, false).await.unwrap(),
+            None
+        );
+        assert_eq!(
+            storage.acquire("a", 7, 2, 1000, 1100, false).await.unwrap(),
+            Some(400)
+        );
+
+        // Released requests don't count against the budget.
+        storage.release("a", 6).await.unwrap();
+        assert_eq!(
+            storage.acquire("a", 8, 2, 1000, 1100, false).await.unwrap(),
+            None
+        );
+    }
+
+    /// Releasing a request forgets that request, not the most recent one, so
+    /// the window still ends when the other requests leave it.
+    async fn test_release_request(storage: RatelimitStorage, key: &str) {
+        assert_eq!(
+            storage.acquire(key, 1, 2, 1000, 0, false).await.unwrap(),
+            None
+        );
+        assert_eq!(
+            storage.acquire(key, 2, 2, 1000, 500, false).await.unwrap(),
+            None
+        );
+        storage.release(key, 1).await.unwrap();
+        assert_eq!(
+            storage.acquire(key, 3, 2, 1000, 600, false).await.unwrap(),
+            None
+        );
+
+        // Requests 2 and 3 are left, so the window ends when request 2 leaves it.
+        assert_eq!(
+            storage.acquire(key, 4, 2, 1000, 700, false).await.unwrap(),
+            Some(800)
+        );
+        assert_eq!(
+            storage.acquire(key, 5, 2, 1000, 1500, false).await.unwrap(),
+            None
+        );
+
+        // Releasing an unknown request changes nothing.
+        storage.release(key, 42).await.unwrap();
+        assert_eq!(
+            storage.acquire(key, 6, 2, 1000, 1500, false).await.unwrap(),
+            Some(100)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_memory_ratelimit_storage_release() {
+        let storage = MemoryRatelimitStorage::new(MemoryRatelimitStorageConfig {
+            max_entries_in_map: NonZeroUsize::new(10).unwrap(),
+        });
+        test_release_request(storage.into(), "a").await;
+    }
+
+    // This requires a local redis, see DEV.md.
+    #[tokio::test]
+    async fn test_redis_ratelimit_storage_release() {
+        let storage = RedisRatelimitStorage::new(RedisConnectionConfig {
+            database_address: "127.0.0.1".to_string(),
+            database_port: 6379,
+            database_number: 0,
+            database_user: None,
+            database_password: None,
+        })
+        .await
+        .expect("Failed to connect to a local redis, have you started one?");
+        let key = format!("test_ratelimit:{}", rand::random::<u64>());
+        test_release_request(storage.into(), &key).await;
+    }
+}
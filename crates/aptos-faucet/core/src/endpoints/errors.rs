@@ -64,7 +64,11 @@ impl AptosTapError {
     pub fn status_and_retry_after(&self) -> (StatusCode, Option<u64>) {
         let (mut status_code, mut retry_after) = (self.error_code.status(), None);
         for rejection_reason in &self.rejection_reasons {
-            if rejection_reason.code == RejectionReasonCode::IpUsageLimitExhausted {
+            if matches!(
+                rejection_reason.code,
+                RejectionReasonCode::IpUsageLimitExhausted
+                    | RejectionReasonCode::AccountUsageLimitExhausted
+            ) {
                 status_code = StatusCode::TOO_MANY_REQUESTS;
                 retry_after = rejection_reason.retry_after;
                 break;
@@ -256,4 +260,7 @@ pub enum RejectionReasonCode {
 
     /// Referer was in the blocklist.
     RefererBlocklisted = 108,
+
+    /// Account has exhausted its usage limit.
+    AccountUsageLimitExhausted = 109,
 }
@@ -232,6 +232,7 @@ impl FundApiComponents {
         };
 
         let checker_data = CheckerData {
+            request_id: rand::random(),
             receiver,
             source_ip,
             headers: Arc::new(header_map.clone()),
@@ -0,0 +1,539 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use super::{
+    common::{
+        submit_transaction, update_sequence_numbers, ApiConnectionConfig, GasUnitPriceManager,
+        TransactionSubmissionConfig,
+    },
+    transfer::{account_address_from_private_key, AmountToFund, MinimumFunds},
+    FunderHealthMessage, FunderTrait,
+};
+use crate::{
+    endpoints::{AptosTapError, AptosTapErrorCode, RejectionReason, RejectionReasonCode},
+    middleware::FEE_PAYER_FUNDER_ACCOUNT_BALANCE,
+};
+use anyhow::{bail, Result};
+use aptos_config::keys::ConfigKey;
+use aptos_logger::{info, warn};
+use aptos_sdk::{
+    crypto::ed25519::Ed25519PrivateKey,
+    rest_client::Client,
+    transaction_builder::{aptos_stdlib, TransactionFactory},
+    types::{
+        account_address::AccountAddress, chain_id::ChainId, transaction::SignedTransaction,
+        LocalAccount,
+    },
+};
+use async_trait::async_trait;
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+use std::{
+    sync::atomic::{AtomicBool, AtomicUsize, Ordering},
+    time::Duration,
+};
+use tokio::sync::RwLock;
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct FeePayerFunderConfig {
+    /// The key given here is the one of the fee payer account, which pays the
+    /// gas of every transaction but never sends any itself.
+    #[serde(flatten)]
+    pub api_connection_config: ApiConnectionConfig,
+
+    #[serde(flatten)]
+    pub transaction_submission_config: TransactionSubmissionConfig,
+
+    /// Hex strings of the Ed25519PrivateKeys of the accounts the coins are
+    /// transferred from. We rotate between them, so requests don't all queue up
+    /// behind the sequence number of a single account.
+    pub funder_keys: Vec<ConfigKey<Ed25519PrivateKey>>,
+
+    /// The minimum amount of coins each funder account should have. Funder
+    /// accounts with less than this are taken out of the rotation until they
+    /// get topped up.
+    pub minimum_funds: MinimumFunds,
+
+    /// The minimum amount of coins the fee payer account should have to pay
+    /// for gas. If it has less than this, the funder is unhealthy.
+    pub minimum_fee_payer_funds: MinimumFunds,
+
+    /// The amount of coins to fund the receiver account.
+    pub amount_to_fund: AmountToFund,
+}
+
+impl FeePayerFunderConfig {
+    pub async fn build_funder(&self) -> Result<FeePayerFunder> {
+        if self.funder_keys.is_empty() {
+            bail!("At least one funder key must be given");
+        }
+
+        let fee_payer_key = self.api_connection_config.get_key()?;
+        let fee_payer_account = LocalAccount::new(
+            account_address_from_private_key(&fee_payer_key),
+            fee_payer_key,
+            0,
+        );
+        let funder_accounts = self
+            .funder_keys
+            .iter()
+            .map(|key| {
+                let key = key.private_key();
+                LocalAccount::new(account_address_from_private_key(&key), key, 0)
+            })
+            .collect();
+
+        let funder = FeePayerFunder::new(
+            fee_payer_account,
+            funder_accounts,
+            self.api_connection_config.chain_id,
+            self.api_connection_config.node_url.clone(),
+            self.minimum_funds,
+            self.minimum_fee_payer_funds,
+            self.amount_to_fund,
+            self.transaction_submission_config
+                .get_gas_unit_price_ttl_secs(),
+            self.transaction_submission_config.gas_unit_price_override,
+            self.transaction_submission_config.max_gas_amount,
+            self.transaction_submission_config
+                .transaction_expiration_secs,
+            self.transaction_submission_config
+                .wait_for_outstanding_txns_secs,
+            self.transaction_submission_config.wait_for_transactions,
+        );
+
+        Ok(funder)
+    }
+}
+
+/// One of the accounts the coins are transferred from. Each of them tracks
+/// its own sequence number and outstanding requests.
+struct FunderAccount {
+    account: RwLock<LocalAccount>,
+
+    /// When recovering from being overloaded, this ensures we handle requests
+    /// in the order they came in.
+    outstanding_requests: RwLock<Vec<(AccountAddress, u64)>>,
+
+    /// Whether the account had the minimum funds at the last health check.
+    has_minimum_funds: AtomicBool,
+}
+
+/// This funder creates and funds accounts with fee payer (sponsored)
+/// transactions. Coins are transferred from a pool of funder accounts,
+/// which we rotate between, while a single fee payer account pays for the
+/// gas of all of them. Since only the sender's sequence number is used by a
+/// fee payer transaction, this spreads the load over as many sequence
+/// numbers as there are funder accounts, instead of making every request
+/// contend on the sequence number of a single hot account.
+pub struct FeePayerFunder {
+    fee_payer_account: LocalAccount,
+
+    funder_accounts: Vec<FunderAccount>,
+
+    /// Index of the funder account to try first for the next request.
+    next_funder_account: AtomicUsize,
+
+    transaction_factory: TransactionFactory,
+
+    /// URL of an Aptos node API.
+    node_url: Url,
+
+    /// The minimum amount of funds each funder account should have.
+    minimum_funds: MinimumFunds,
+
+    /// The minimum amount of funds the fee payer account should have.
+    minimum_fee_payer_funds: MinimumFunds,
+
+    /// Maximum amount we'll fund an account.
+    amount_to_fund: AmountToFund,
+
+    /// See comment of gas_unit_price.
+    gas_unit_price_manager: GasUnitPriceManager,
+
+    /// If this is Some, we'll use this. If not, we'll get the gas_unit_price
+    /// from the gas_unit_price_manager.
+    gas_unit_price_override: Option<u64>,
+
+    /// Amount of time we'll wait for the seqnum to catch up before resetting it.
+    wait_for_outstanding_txns_secs: u64,
+
+    /// If set, we won't return responses until the transaction is processed.
+    wait_for_transactions: bool,
+}
+
+impl FeePayerFunder {
+    pub fn new(
+        fee_payer_account: LocalAccount,
+        funder_accounts: Vec<LocalAccount>,
+        chain_id: ChainId,
+        node_url: Url,
+        minimum_funds: MinimumFunds,
+        minimum_fee_payer_funds: MinimumFunds,
+        amount_to_fund: AmountToFund,
+        gas_unit_price_ttl_secs: Duration,
+        gas_unit_price_override: Option<u64>,
+        max_gas_amount: u64,
+        transaction_expiration_secs: u64,
+        wait_for_outstanding_txns_secs: u64,
+        wait_for_transactions: bool,
+    ) -> Self {
+        let gas_unit_price_manager =
+            GasUnitPriceManager::new(node_url.clone(), gas_unit_price_ttl_secs);
+
+        Self {
+            fee_payer_account,
+            funder_accounts: funder_accounts
+                .into_iter()
+                .map(|account| FunderAccount {
+                    account: RwLock::new(account),
+                    outstanding_requests: RwLock::new(vec![]),
+                    has_minimum_funds: AtomicBool::new(true),
+                })
+                .collect(),
+            next_funder_account: AtomicUsize::new(0),
+            transaction_factory: TransactionFactory::new(chain_id)
+                .with_max_gas_amount(max_gas_amount)
+                .with_transaction_expiration_time(transaction_expiration_secs),
+            node_url,
+            minimum_funds,
+            minimum_fee_payer_funds,
+            amount_to_fund,
+            gas_unit_price_manager,
+            gas_unit_price_override,
+            wait_for_outstanding_txns_secs,
+            wait_for_transactions,
+        }
+    }
+
+    /// Within a single request we should just call this once and use this client
+    /// the entire time because it uses cookies, ensuring we're talking to the same
+    /// node behind the LB every time.
+    pub fn get_api_client(&self) -> Client {
+        Client::new(self.node_url.clone())
+    }
+
+    async fn get_gas_unit_price(&self) -> Result<u64, AptosTapError> {
+        match self.gas_unit_price_override {
+            Some(gas_unit_price) => Ok(gas_unit_price),
+            None => self
+                .gas_unit_price_manager
+                .get_gas_unit_price()
+                .await
+                .map_err(|e| {
+                    AptosTapError::new_with_error_code(e, AptosTapErrorCode::AptosApiError)
+                }),
+        }
+    }
+
+    /// Picks the funder account for the next request, going round robin over
+    /// the ones that had the minimum funds at the last health check.
+    fn pick_funder_account(&self) -> Result<&FunderAccount, AptosTapError> {
+        let start = self.next_funder_account.fetch_add(1, Ordering::Relaxed);
+        (0..self.funder_accounts.len())
+            .map(|offset| &self.funder_accounts[(start + offset) % self.funder_accounts.len()])
+            .find(|funder_account| funder_account.has_minimum_funds.load(Ordering::Relaxed))
+            .ok_or_else(|| {
+                AptosTapError::new(
+                    "Tap FeePayerFunder has no funder account with sufficient funds".to_string(),
+                    AptosTapErrorCode::FunderAccountProblem,
+                )
+            })
+    }
+
+    /// Returns the APT balance of the given account.
+    async fn get_balance(&self, client: &Client, address: AccountAddress) -> Result<u64, String> {
+        client
+            .get_account_balance_bcs(address, "0x1::aptos_coin::AptosCoin")
+            .await
+            .map(|response| response.into_inner())
+            .map_err(|e| {
+                format!(
+                    "Failed to get account balance of {} to determine whether it has sufficient funds: {:#}",
+                    address, e
+                )
+            })
+    }
+}
+
+#[async_trait]
+impl FunderTrait for FeePayerFunder {
+    /// As with the TransferFunder, the checks we do before submitting the
+    /// transaction only reduce the prevalence of transaction failure, the
+    /// transaction itself ensures that the account doesn't exist already.
+    async fn fund(
+        &self,
+        amount: Option<u64>,
+        receiver_address: AccountAddress,
+        check_only: bool,
+        did_bypass_checkers: bool,
+    ) -> Result<Vec<SignedTransaction>, AptosTapError> {
+        let client = self.get_api_client();
+        let funder_account = self.pick_funder_account()?;
+
+        // Determine amount to fund.
+        let amount = self.get_amount(amount, did_bypass_checkers);
+
+        // Update the sequence numbers of the funder account we picked and the
+        // receiver account.
+        let (_funder_seq_num, receiver_seq_num) = update_sequence_numbers(
+            &client,
+            &funder_account.account,
+            &funder_account.outstanding_requests,
+            receiver_address,
+            amount,
+            self.wait_for_outstanding_txns_secs,
+        )
+        .await?;
+
+        // When updating the sequence numbers, we expect that the receiver sequence
+        // number should be None, because the account should not exist yet.
+        if receiver_seq_num.is_some() {
+            return Err(AptosTapError::new(
+                "Account ineligible".to_string(),
+                AptosTapErrorCode::Rejected,
+            )
+            .rejection_reasons(vec![RejectionReason::new(
+                format!("Account {} already exists", receiver_address),
+                RejectionReasonCode::AccountAlreadyExists,
+            )]));
+        }
+
+        if check_only {
+            return Ok(vec![]);
+        }
+
+        // This Move function creates the receiver account if it doesn't exist
+        // and transfers the requested amount of coins to it, all in one step.
+        let transaction_builder = self
+            .transaction_factory
+            .clone()
+            .with_gas_unit_price(self.get_gas_unit_price().await?)
+            .payload(aptos_stdlib::aptos_account_transfer(
+                receiver_address,
+                amount,
+            ));
+        let signed_transaction = funder_account
+            .account
+            .write()
+            .await
+            .sign_fee_payer_with_transaction_builder(
+                vec![],
+                &self.fee_payer_account,
+                transaction_builder,
+            );
+
+        let txn = submit_transaction(
+            &client,
+            &funder_account.account,
+            signed_transaction,
+            &receiver_address,
+            self.wait_for_transactions,
+        )
+        .await?;
+        info!(
+            hash = txn.clone().committed_hash().to_hex_literal(),
+            address = receiver_address,
+            amount = amount,
+            sender = txn.sender(),
+            event = "transaction_submitted"
+        );
+        Ok(vec![txn])
+    }
+
+    fn get_amount(
+        &self,
+        amount: Option<u64>,
+        // Ignored, as with the TransferFunder.
+        _did_bypass_checkers: bool,
+    ) -> u64 {
+        match amount {
+            Some(amount) => std::cmp::min(amount, self.amount_to_fund.0),
+            None => self.amount_to_fund.0,
+        }
+    }
+
+    /// Assert the fee payer account has the minimum funds to pay for gas, and
+    /// take the funder accounts without the minimum funds out of the rotation.
+    /// We're healthy as long as at least one funder account can be used.
+    async fn is_healthy(&self) -> FunderHealthMessage {
+        let client = self.get_api_client();
+
+        let fee_payer_address = self.fee_payer_account.address();
+        let fee_payer_balance = match self.get_balance(&client, fee_payer_address).await {
+            Ok(balance) => balance,
+            Err(message) => {
+                return FunderHealthMessage {
+                    can_process_requests: false,
+                    message: Some(message),
+                }
+            },
+        };
+        FEE_PAYER_FUNDER_ACCOUNT_BALANCE
+            .with_label_values(&[fee_payer_address.to_hex_literal().as_str()])
+            .set(fee_payer_balance as i64);
+        if fee_payer_balance < self.minimum_fee_payer_funds.0 {
+            return FunderHealthMessage {
+                can_process_requests: false,
+                message: Some(format!(
+                    "Fee payer account {} has insufficient funds. It has {}, but the minimum is {}",
+                    fee_payer_address, fee_payer_balance, self.minimum_fee_payer_funds.0
+                )),
+            };
+        }
+
+        let mut num_usable = 0;
+        for funder_account in &self.funder_accounts {
+            let address = funder_account.account.read().await.address();
+            let has_minimum_funds = match self.get_balance(&client, address).await {
+                Ok(balance) => {
+                    FEE_PAYER_FUNDER_ACCOUNT_BALANCE
+                        .with_label_values(&[address.to_hex_literal().as_str()])
+                        .set(balance as i64);
+                    balance >= self.minimum_funds.0
+                },
+                Err(message) => {
+                    warn!("{}", message);
+                    false
+                },
+            };
+            if funder_account
+                .has_minimum_funds
+                .swap(has_minimum_funds, Ordering::Relaxed)
+                != has_minimum_funds
+            {
+                info!(
+                    address = address,
+                    has_minimum_funds = has_minimum_funds,
+                    event = "funder_account_rotation_changed"
+                );
+            }
+            if has_minimum_funds {
+                num_usable += 1;
+            }
+        }
+
+        if num_usable == 0 {
+            FunderHealthMessage {
+                can_process_requests: false,
+                message: Some(format!(
+                    "None of the {} funder accounts has the minimum funds of {}",
+                    self.funder_accounts.len(),
+                    self.minimum_funds.0
+                )),
+            }
+        } else {
+            FunderHealthMessage {
+                can_process_requests: true,
+                message: None,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    fn new_funder(num_funder_accounts: usize) -> FeePayerFunder {
+        let mut rng = StdRng::from_seed([0; 32]);
+        FeePayerFunder::new(
+            LocalAccount::generate(&mut rng),
+            (0..num_funder_accounts)
+                .map(|_| LocalAccount::generate(&mut rng))
+                .collect(),
+            ChainId::test(),
+            Url::parse("http://127.0.0.1:8080").unwrap(),
+            MinimumFunds(1000),
+            MinimumFunds(1000),
+            AmountToFund(100),
+            Duration::from_secs(30),
+            Some(100),
+            500_000,
+            25,
+            30,
+            false,
+        )
+    }
+
+    async fn pick_funder_address(funder: &FeePayerFunder) -> Result<AccountAddress, AptosTapError> {
+        Ok(funder.pick_funder_account()?.account.read().await.address())
+    }
+
+    #[tokio::test]
+    async fn test_pick_funder_account() {
+        let funder = new_funder(3);
+        let mut addresses = vec![];
+        for funder_account in &funder.funder_accounts {
+            addresses.push(funder_account.account.read().await.address());
+        }
+
+        // Requests go round robin over the funder accounts.
+        for i in 0..6 {
+            assert_eq!(
+                pick_funder_address(&funder).await.unwrap(),
+                addresses[i % 3]
+            );
+        }
+
+        // Accounts without the minimum funds are skipped.
+        funder.funder_accounts[1]
+            .has_minimum_funds
+            .store(false, Ordering::Relaxed);
+        for expected in [0, 2, 2, 0, 2, 0] {
+            assert_eq!(
+                pick_funder_address(&funder).await.unwrap(),
+                addresses[expected]
+            );
+        }
+
+        // With none of them left, requests fail.
+        for funder_account in &funder.funder_accounts {
+            funder_account
+                .has_minimum_funds
+                .store(false, Ordering::Relaxed);
+        }
+        assert_eq!(
+            pick_funder_address(&funder).await.unwrap_err().error_code,
+            AptosTapErrorCode::FunderAccountProblem
+        );
+    }
+
+    #[test]
+    fn test_get_amount() {
+        let funder = new_funder(1);
+        assert_eq!(funder.get_amount(Some(10), false), 10);
+        assert_eq!(funder.get_amount(Some(1000), false), 100);
+        assert_eq!(funder.get_amount(Some(1000), true), 100);
+        assert_eq!(funder.get_amount(None, false), 100);
+    }
+
+    #[tokio::test]
+    async fn test_build_funder() {
+        let config_content = r#"
+node_url: "http://127.0.0.1:8080"
+chain_id: 4
+key: "0x2d5e3b1a5e6b4ba8f47a8d18b4e0f50b8d2b9c2e1d7f4f0a6d9b3c8e5a7f1b21"
+funder_keys:
+  - "0x5b6a9c3e2f1d4e7a8b0c9d2e3f4a5b6c7d8e9f0a1b2c3d4e5f6a7b8c9d0e1f32"
+  - "0x3c4d5e6f7a8b9c0d1e2f3a4b5c6d7e8f9a0b1c2d3e4f5a6b7c8d9e0f1a2b3c43"
+minimum_funds: 10000000
+minimum_fee_payer_funds: 10000000
+amount_to_fund: 100000000
+"#;
+        let mut config: FeePayerFunderConfig = serde_yaml::from_str(config_content).unwrap();
+        let funder = config.build_funder().await.unwrap();
+        assert_eq!(funder.funder_accounts.len(), 2);
+        let funder_address = funder.funder_accounts[0].account.read().await.address();
+        assert_ne!(funder.fee_payer_account.address(), funder_address);
+        assert_eq!(
+            funder_address,
+            account_address_from_private_key(&config.funder_keys[0].private_key())
+        );
+
+        // The fee payer never sends coins itself, so at least one funder key is needed.
+        config.funder_keys.clear();
+        assert!(config.build_funder().await.is_err());
+    }
+}
@@ -3,6 +3,7 @@
 
 mod common;
 mod fake;
+mod fee_payer;
 mod mint;
 mod transfer;
 
@@ -10,13 +11,16 @@ pub use self::{
     common::{ApiConnectionConfig, TransactionSubmissionConfig},
     mint::MintFunderConfig,
 };
-use self::{fake::FakeFunderConfig, transfer::TransferFunderConfig};
+use self::{
+    fake::FakeFunderConfig, fee_payer::FeePayerFunderConfig, transfer::TransferFunderConfig,
+};
 use crate::endpoints::AptosTapError;
 use anyhow::{Context, Result};
 use aptos_sdk::types::{account_address::AccountAddress, transaction::SignedTransaction};
 use async_trait::async_trait;
 use enum_dispatch::enum_dispatch;
 pub use fake::FakeFunder;
+pub use fee_payer::FeePayerFunder;
 pub use mint::MintFunder;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
@@ -75,6 +79,11 @@ pub enum FunderConfig {
     /// This funder creates and funds accounts by using + transferring
     /// coins from a pre-funded account provided in configuration.
     TransferFunder(TransferFunderConfig),
+
+    /// This funder creates and funds accounts with fee payer transactions,
+    /// transferring coins from a pool of pre-funded accounts while a single
+    /// fee payer account pays for the gas.
+    FeePayerFunder(FeePayerFunderConfig),
 }
 
 impl FunderConfig {
@@ -93,6 +102,12 @@ impl FunderConfig {
                     .await
                     .context("Failed to build TransferFunder")?,
             ))),
+            FunderConfig::FeePayerFunder(config) => Ok(Arc::new(Funder::from(
+                config
+                    .build_funder()
+                    .await
+                    .context("Failed to build FeePayerFunder")?,
+            ))),
         }
     }
 }
@@ -101,6 +116,7 @@ impl FunderConfig {
 #[enum_dispatch(FunderTrait)]
 pub enum Funder {
     FakeFunder,
+    FeePayerFunder,
     MintFunder,
     TransferFunder,
 }
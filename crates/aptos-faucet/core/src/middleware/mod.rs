@@ -7,7 +7,7 @@ mod metrics;
 pub use self::{
     log::middleware_log,
     metrics::{
-        bump_rejection_reason_counters, NUM_OUTSTANDING_TRANSACTIONS,
-        TRANSFER_FUNDER_ACCOUNT_BALANCE,
+        bump_rejection_reason_counters, FEE_PAYER_FUNDER_ACCOUNT_BALANCE,
+        NUM_OUTSTANDING_TRANSACTIONS, TRANSFER_FUNDER_ACCOUNT_BALANCE,
     },
 };
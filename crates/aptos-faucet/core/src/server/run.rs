@@ -374,6 +374,7 @@ mod test {
     use anyhow::{bail, Result};
     use aptos_sdk::{
         crypto::{ed25519::Ed25519PrivateKey, HashValue, PrivateKey, Uniform},
+        rest_client::Transaction,
         types::{account_address::AccountAddress, transaction::authenticator::AuthenticationKey},
     };
     use once_cell::sync::OnceCell;
@@ -679,6 +680,52 @@ mod test {
         Ok(())
     }
 
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_sliding_window_ratelimiter() -> Result<()> {
+        init();
+        let config_content = include_str!("../../../configs/testing_sliding_window.yaml");
+        let (port, _handle) = start_server(config_content).await?;
+
+        let fund_request = get_fund_request(Some(10));
+        let send_request = |fund_request: &FundRequest| {
+            reqwest::Client::new()
+                .post(get_fund_endpoint(port))
+                .body(fund_request.to_json_string())
+                .header(CONTENT_TYPE, "application/json")
+                .send()
+        };
+        let get_rejection_reason_codes = |text: String| -> HashSet<RejectionReasonCode> {
+            AptosTapError::parse_from_json_string(&text)
+                .expect("Failed to read response as AptosError")
+                .rejection_reasons
+                .into_iter()
+                .map(|r| r.get_code())
+                .collect()
+        };
+
+        // Assert that the first 2 requests for the account work.
+        unwrap_reqwest_result(send_request(&fund_request).await).await?;
+        unwrap_reqwest_result(send_request(&fund_request).await).await?;
+
+        // But the third does not, because the account used up its budget.
+        let response = send_request(&fund_request).await?;
+        assert_eq!(response.status(), reqwest::StatusCode::TOO_MANY_REQUESTS);
+        assert!(get_rejection_reason_codes(response.text().await?)
+            .contains(&RejectionReasonCode::AccountUsageLimitExhausted));
+
+        // The rejected request didn't use up the budget of the IP, so a request
+        // for another account works.
+        unwrap_reqwest_result(send_request(&get_fund_request(Some(10))).await).await?;
+
+        // But now the IP used up its budget too.
+        let response = send_request(&get_fund_request(Some(10))).await?;
+        assert_eq!(response.status(), reqwest::StatusCode::TOO_MANY_REQUESTS);
+        assert!(get_rejection_reason_codes(response.text().await?)
+            .contains(&RejectionReasonCode::IpUsageLimitExhausted));
+
+        Ok(())
+    }
+
     // We skip this for now since we have no current need to use the TransferFunder.
     #[ignore]
     #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
@@ -771,6 +818,164 @@ mod test {
         Ok(())
     }
 
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_fee_payer_funder() -> Result<()> {
+        // Assert that a local testnet is alive.
+        let aptos_node_api_client = aptos_sdk::rest_client::Client::new(
+            reqwest::Url::from_str("http://127.0.0.1:8080").unwrap(),
+        );
+        aptos_node_api_client
+            .get_index_bcs()
+            .await
+            .context("Local testnet API couldn't be reached at port 8080, have you started one?")?;
+
+        // Create a fee payer key and store it at the path expected by the config
+        // for this test.
+        let private_key = Ed25519PrivateKey::generate(&mut StdRng::from_seed(OsRng.gen()));
+        let serialized_keys = aptos_sdk::bcs::to_bytes(&private_key)?;
+        let mut key_file = std::fs::File::create("/tmp/fee_payer_funder_local.key")?;
+        key_file.write_all(&serialized_keys)?;
+        let fee_payer_address =
+            AuthenticationKey::ed25519(&private_key.public_key()).derived_address();
+
+        let config_content = include_str!("../../../configs/testing_fee_payer_funder_local.yaml");
+        let run_config: RunConfig = serde_yaml::from_str(config_content)?;
+        let funder_addresses: Vec<AccountAddress> = match &run_config.funder_config {
+            FunderConfig::FeePayerFunder(config) => config
+                .funder_keys
+                .iter()
+                .map(|key| AuthenticationKey::ed25519(&key.public_key()).derived_address())
+                .collect(),
+            _ => bail!("Expected the config to use a FeePayerFunder"),
+        };
+
+        // Fund the fee payer and the funder accounts with the mint funder.
+        init();
+        let (mint_port, _mint_handle) = {
+            // Ensure this server and those of the test_mint_funder_* tests don't
+            // start up simultaneously, since they're using the same mint key.
+            let _guard = MUTEX.get().unwrap().lock().await;
+            let config_content = include_str!("../../../configs/testing_mint_funder_local.yaml");
+            start_server(config_content).await?
+        };
+        for address in std::iter::once(fee_payer_address).chain(funder_addresses.clone()) {
+            let response = unwrap_reqwest_result(
+                reqwest::Client::new()
+                    .post(get_fund_endpoint(mint_port))
+                    .body(
+                        FundRequest {
+                            amount: Some(1_000_000_000),
+                            address: Some(address.to_string()),
+                            ..Default::default()
+                        }
+                        .to_json_string(),
+                    )
+                    .header(CONTENT_TYPE, "application/json")
+                    .send()
+                    .await,
+            )
+            .await?;
+            let fund_response = FundResponse::parse_from_json_string(&response.text().await?)
+                .expect("Failed to read response as FundResponse");
+            aptos_node_api_client
+                .wait_for_transaction_by_hash(
+                    HashValue::from_str(&fund_response.txn_hashes[0])?,
+                    get_current_time_secs() + 30,
+                    None,
+                    None,
+                )
+                .await
+                .context("Failed to wait for transaction")?;
+        }
+
+        // Start the server, using the accounts we just funded.
+        let (port, _handle) = start_server(config_content).await?;
+        unwrap_reqwest_result(
+            reqwest::Client::new()
+                .get(get_root_endpoint(port))
+                .send()
+                .await,
+        )
+        .await?;
+
+        // Fund two new accounts. The coins come from a different funder account
+        // each time, while the fee payer pays for the gas.
+        let fee_payer_balance = aptos_node_api_client
+            .get_account_balance(fee_payer_address)
+            .await?
+            .into_inner()
+            .get();
+        let mut senders = HashSet::new();
+        for _ in 0..2 {
+            let fund_request = get_fund_request(Some(10));
+            let response = unwrap_reqwest_result(
+                reqwest::Client::new()
+                    .post(get_fund_endpoint(port))
+                    .body(fund_request.to_json_string())
+                    .header(CONTENT_TYPE, "application/json")
+                    .send()
+                    .await,
+            )
+            .await?;
+            let fund_response = FundResponse::parse_from_json_string(&response.text().await?)
+                .expect("Failed to read response as FundResponse");
+            let response = aptos_node_api_client
+                .wait_for_transaction_by_hash(
+                    HashValue::from_str(&fund_response.txn_hashes[0])?,
+                    get_current_time_secs() + 30,
+                    None,
+                    None,
+                )
+                .await
+                .context("Failed to wait for transaction")?;
+            assert!(
+                response.inner().success(),
+                "Transaction failed: {:#?}",
+                response
+            );
+            let sender = match response.into_inner() {
+                Transaction::UserTransaction(txn) => *txn.request.sender.inner(),
+                txn => bail!("Expected a user transaction, got {:#?}", txn),
+            };
+            senders.insert(sender);
+
+            let receiver_address = AccountAddress::from_hex(fund_request.address.unwrap())?;
+            let response = aptos_node_api_client
+                .get_account_balance(receiver_address)
+                .await?;
+            assert_eq!(response.into_inner().get(), 10);
+
+            // The account exists now, so funding it again is rejected.
+            let response = reqwest::Client::new()
+                .post(get_fund_endpoint(port))
+                .body(
+                    FundRequest {
+                        amount: Some(10),
+                        address: Some(receiver_address.to_string()),
+                        ..Default::default()
+                    }
+                    .to_json_string(),
+                )
+                .header(CONTENT_TYPE, "application/json")
+                .send()
+                .await?;
+            assert_eq!(response.status(), reqwest::StatusCode::FORBIDDEN);
+            let aptos_error = AptosTapError::parse_from_json_string(&response.text().await?)
+                .expect("Failed to read response as AptosError");
+            assert!(aptos_error
+                .rejection_reasons
+                .into_iter()
+                .any(|r| r.get_code() == RejectionReasonCode::AccountAlreadyExists));
+        }
+        assert_eq!(senders, funder_addresses.into_iter().collect());
+        let response = aptos_node_api_client
+            .get_account_balance(fee_payer_address)
+            .await?;
+        assert!(response.into_inner().get() < fee_payer_balance);
+
+        Ok(())
+    }
+
     #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
     async fn test_mint_funder() -> Result<()> {
         // Assert that a local testnet is alive.
@@ -12,4 +12,6 @@ export enum RejectionReasonCode {
     MAGIC_HEADER_INCORRECT = 'MagicHeaderIncorrect',
     CAPTCHA_INVALID = 'CaptchaInvalid',
     AUTH_TOKEN_INVALID = 'AuthTokenInvalid',
+    REFERER_BLOCKLISTED = 'RefererBlocklisted',
+    ACCOUNT_USAGE_LIMIT_EXHAUSTED = 'AccountUsageLimitExhausted',
 }
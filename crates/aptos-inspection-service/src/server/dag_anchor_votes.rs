@@ -0,0 +1,24 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::server::{utils::CONTENT_TYPE_JSON, DagAnchorVotesProvider};
+use hyper::{Body, StatusCode};
+
+/// Handles a new DAG anchor votes request
+pub fn handle_dag_anchor_votes_request(
+    dag_anchor_votes_provider: DagAnchorVotesProvider,
+) -> (StatusCode, Body, String) {
+    (
+        StatusCode::OK,
+        Body::from(get_dag_anchor_votes_json(dag_anchor_votes_provider)),
+        CONTENT_TYPE_JSON.into(),
+    )
+}
+
+/// Returns the votes observed for the latest DAG anchor rounds as a JSON string
+fn get_dag_anchor_votes_json(dag_anchor_votes_provider: DagAnchorVotesProvider) -> String {
+    match dag_anchor_votes_provider() {
+        Ok(anchor_votes) => anchor_votes,
+        Err(error) => format!("Failed to get the DAG anchor votes! Error: {}", error),
+    }
+}
@@ -17,6 +17,7 @@ use std::{
 };
 
 mod configuration;
+mod dag_anchor_votes;
 mod index;
 mod json_encoder;
 mod metrics;
@@ -29,6 +30,7 @@ mod tests;
 
 // The list of endpoints offered by the inspection service
 pub const CONFIGURATION_PATH: &str = "/configuration";
+pub const DAG_ANCHOR_VOTES_PATH: &str = "/dag_anchor_votes";
 pub const FORGE_METRICS_PATH: &str = "/forge_metrics";
 pub const INDEX_PATH: &str = "/";
 pub const JSON_METRICS_PATH: &str = "/json_metrics";
@@ -41,11 +43,16 @@ pub const HEADER_CONTENT_TYPE: &str = "Content-Type";
 pub const INVALID_ENDPOINT_MESSAGE: &str = "The requested endpoint is invalid!";
 pub const UNEXPECTED_ERROR_MESSAGE: &str = "An unexpected error was encountered!";
 
+/// Returns the votes observed for the latest DAG anchor rounds, encoded as JSON. This is
+/// provided by the node so that the inspection service doesn't depend on consensus.
+pub type DagAnchorVotesProvider = Arc<dyn Fn() -> serde_json::Result<String> + Send + Sync>;
+
 /// Starts the inspection service that listens on the configured
 /// address and handles various endpoint requests.
 pub fn start_inspection_service(
     node_config: NodeConfig,
     peers_and_metadata: Arc<PeersAndMetadata>,
+    dag_anchor_votes_provider: DagAnchorVotesProvider,
 ) {
     // Fetch the service port and address
     let service_port = node_config.inspection_service.port;
@@ -72,9 +79,15 @@ pub fn start_inspection_service(
         let make_service = make_service_fn(move |_conn| {
             let node_config = node_config.clone();
             let peers_and_metadata = peers_and_metadata.clone();
+            let dag_anchor_votes_provider = dag_anchor_votes_provider.clone();
             async move {
                 Ok::<_, Infallible>(service_fn(move |request| {
-                    serve_requests(request, node_config.clone(), peers_and_metadata.clone())
+                    serve_requests(
+                        request,
+                        node_config.clone(),
+                        peers_and_metadata.clone(),
+                        dag_anchor_votes_provider.clone(),
+                    )
                 }))
             }
         });
@@ -94,6 +107,7 @@ async fn serve_requests(
     req: Request<Body>,
     node_config: NodeConfig,
     peers_and_metadata: Arc<PeersAndMetadata>,
+    dag_anchor_votes_provider: DagAnchorVotesProvider,
 ) -> Result<Response<Body>, hyper::Error> {
     // Process the request and get the response components
     let (status_code, body, content_type) = match req.uri().path() {
@@ -102,6 +116,11 @@ async fn serve_requests(
             // Exposes the node configuration
             configuration::handle_configuration_request(&node_config)
         },
+        DAG_ANCHOR_VOTES_PATH => {
+            // /dag_anchor_votes
+            // Exposes the votes observed for the latest DAG anchors
+            dag_anchor_votes::handle_dag_anchor_votes_request(dag_anchor_votes_provider)
+        },
         FORGE_METRICS_PATH => {
             // /forge_metrics
             // Exposes forge encoded metrics
@@ -7,8 +7,8 @@ use crate::{
         peer_information::PEER_INFO_DISABLED_MESSAGE, serve_requests,
         system_information::SYS_INFO_DISABLED_MESSAGE, utils::get_all_metrics,
     },
-    CONFIGURATION_PATH, FORGE_METRICS_PATH, INDEX_PATH, JSON_METRICS_PATH, METRICS_PATH,
-    PEER_INFORMATION_PATH, SYSTEM_INFORMATION_PATH,
+    CONFIGURATION_PATH, DAG_ANCHOR_VOTES_PATH, FORGE_METRICS_PATH, INDEX_PATH, JSON_METRICS_PATH,
+    METRICS_PATH, PEER_INFORMATION_PATH, SYSTEM_INFORMATION_PATH,
 };
 use aptos_config::config::NodeConfig;
 use aptos_network::application::storage::PeersAndMetadata;
@@ -18,7 +18,7 @@ use hyper::{body, Body, Method, Request, Response, StatusCode};
 use once_cell::sync::Lazy;
 use prometheus::{proto::MetricFamily, register_int_counter, Counter, IntCounter, Opts, Registry};
 use rusty_fork::rusty_fork_test;
-use std::{io::read_to_string, string::String};
+use std::{io::read_to_string, string::String, sync::Arc};
 
 // This metrics counter only exists in this test context; the rest of the
 // system's metrics counters don't exist, so we need to add this for tests.
@@ -53,6 +53,21 @@ async fn test_inspect_configuration() {
     assert!(response_body_string.contains("expose_configuration: true"));
 }
 
+#[tokio::test]
+async fn test_inspect_dag_anchor_votes() {
+    // Create a validator node config
+    let config = NodeConfig::get_default_validator_config();
+
+    // Ping the DAG anchor votes endpoint
+    let mut response = send_get_request_to_path(&config, DAG_ANCHOR_VOTES_PATH).await;
+    let response_body = body::to_bytes(response.body_mut()).await.unwrap();
+    let response_body_string = read_to_string(response_body.as_ref()).unwrap();
+
+    // Verify that the response contains the votes returned by the provider
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response_body_string, "[]");
+}
+
 #[tokio::test]
 async fn test_inspect_forge_metrics() {
     // Create a VFN config
@@ -82,6 +97,7 @@ async fn test_inspect_index() {
     // Verify that the response contains all the endpoints
     assert_eq!(response.status(), StatusCode::OK);
     assert!(response_body_string.contains(CONFIGURATION_PATH));
+    assert!(response_body_string.contains(DAG_ANCHOR_VOTES_PATH));
     assert!(response_body_string.contains(FORGE_METRICS_PATH));
     assert!(response_body_string.contains(JSON_METRICS_PATH));
     assert!(response_body_string.contains(METRICS_PATH));
@@ -255,6 +271,7 @@ async fn send_get_request_to_path(config: &NodeConfig, endpoint: &str) -> Respon
             .unwrap(),
         config.clone(),
         PeersAndMetadata::new(&[]),
+        Arc::new(|| Ok("[]".into())),
     )
     .await
     .unwrap()
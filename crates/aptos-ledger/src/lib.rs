@@ -60,6 +60,11 @@ impl From<LedgerHIDError> for AptosLedgerError {
     }
 }
 
+/// Returns the derivation path of the Aptos account at `index` on the Ledger device
+pub fn derivation_path(index: u32) -> String {
+    DERIVATIVE_PATH.replace("{index}", &index.to_string())
+}
+
 /// Aptos version in format major.minor.patch
 #[derive(Debug)]
 pub struct Version {
@@ -174,7 +179,7 @@ pub fn fetch_batch_accounts(
 
     let mut accounts = HashMap::new();
     for i in range {
-        let path = DERIVATIVE_PATH.replace("{index}", &i.to_string());
+        let path = derivation_path(i);
         let cdata = serialize_bip32(&path);
 
         match transport.exchange(&APDUCommand {
@@ -1,6 +1,137 @@
 // Copyright © Aptos Foundation
 
 // @generated
+impl serde::Serialize for GetTransactionsInRangeRequest {
+    #[allow(deprecated)]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let mut len = 0;
+        if self.starting_version.is_some() {
+            len += 1;
+        }
+        if self.ending_version.is_some() {
+            len += 1;
+        }
+        if self.filter.is_some() {
+            len += 1;
+        }
+        let mut struct_ser = serializer.serialize_struct("aptos.indexer.v1.GetTransactionsInRangeRequest", len)?;
+        if let Some(v) = self.starting_version.as_ref() {
+            struct_ser.serialize_field("startingVersion", ToString::to_string(&v).as_str())?;
+        }
+        if let Some(v) = self.ending_version.as_ref() {
+            struct_ser.serialize_field("endingVersion", ToString::to_string(&v).as_str())?;
+        }
+        if let Some(v) = self.filter.as_ref() {
+            struct_ser.serialize_field("filter", v)?;
+        }
+        struct_ser.end()
+    }
+}
+impl<'de> serde::Deserialize<'de> for GetTransactionsInRangeRequest {
+    #[allow(deprecated)]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        const FIELDS: &[&str] = &[
+            "starting_version",
+            "startingVersion",
+            "ending_version",
+            "endingVersion",
+            "filter",
+        ];
+
+        #[allow(clippy::enum_variant_names)]
+        enum GeneratedField {
+            StartingVersion,
+            EndingVersion,
+            Filter,
+        }
+        impl<'de> serde::Deserialize<'de> for GeneratedField {
+            fn deserialize<D>(deserializer: D) -> std::result::Result<GeneratedField, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                struct GeneratedVisitor;
+
+                impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+                    type Value = GeneratedField;
+
+                    fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                        write!(formatter, "expected one of: {:?}", &FIELDS)
+                    }
+
+                    #[allow(unused_variables)]
+                    fn visit_str<E>(self, value: &str) -> std::result::Result<GeneratedField, E>
+                    where
+                        E: serde::de::Error,
+                    {
+                        match value {
+                            "startingVersion" | "starting_version" => Ok(GeneratedField::StartingVersion),
+                            "endingVersion" | "ending_version" => Ok(GeneratedField::EndingVersion),
+                            "filter" => Ok(GeneratedField::Filter),
+                            _ => Err(serde::de::Error::unknown_field(value, FIELDS)),
+                        }
+                    }
+                }
+                deserializer.deserialize_identifier(GeneratedVisitor)
+            }
+        }
+        struct GeneratedVisitor;
+        impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+            type Value = GetTransactionsInRangeRequest;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                formatter.write_str("struct aptos.indexer.v1.GetTransactionsInRangeRequest")
+            }
+
+            fn visit_map<V>(self, mut map: V) -> std::result::Result<GetTransactionsInRangeRequest, V::Error>
+                where
+                    V: serde::de::MapAccess<'de>,
+            {
+                let mut starting_version__ = None;
+                let mut ending_version__ = None;
+                let mut filter__ = None;
+                while let Some(k) = map.next_key()? {
+                    match k {
+                        GeneratedField::StartingVersion => {
+                            if starting_version__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("startingVersion"));
+                            }
+                            starting_version__ =
+                                map.next_value::<::std::option::Option<::pbjson::private::NumberDeserialize<_>>>()?.map(|x| x.0)
+                            ;
+                        }
+                        GeneratedField::EndingVersion => {
+                            if ending_version__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("endingVersion"));
+                            }
+                            ending_version__ =
+                                map.next_value::<::std::option::Option<::pbjson::private::NumberDeserialize<_>>>()?.map(|x| x.0)
+                            ;
+                        }
+                        GeneratedField::Filter => {
+                            if filter__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("filter"));
+                            }
+                            filter__ = map.next_value()?;
+                        }
+                    }
+                }
+                Ok(GetTransactionsInRangeRequest {
+                    starting_version: starting_version__,
+                    ending_version: ending_version__,
+                    filter: filter__,
+                })
+            }
+        }
+        deserializer.deserialize_struct("aptos.indexer.v1.GetTransactionsInRangeRequest", FIELDS, GeneratedVisitor)
+    }
+}
 impl serde::Serialize for GetTransactionsRequest {
     #[allow(deprecated)]
     fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
@@ -18,6 +149,9 @@ impl serde::Serialize for GetTransactionsRequest {
         if self.batch_size.is_some() {
             len += 1;
         }
+        if self.filter.is_some() {
+            len += 1;
+        }
         let mut struct_ser = serializer.serialize_struct("aptos.indexer.v1.GetTransactionsRequest", len)?;
         if let Some(v) = self.starting_version.as_ref() {
             struct_ser.serialize_field("startingVersion", ToString::to_string(&v).as_str())?;
@@ -28,6 +162,9 @@ impl serde::Serialize for GetTransactionsRequest {
         if let Some(v) = self.batch_size.as_ref() {
             struct_ser.serialize_field("batchSize", ToString::to_string(&v).as_str())?;
         }
+        if let Some(v) = self.filter.as_ref() {
+            struct_ser.serialize_field("filter", v)?;
+        }
         struct_ser.end()
     }
 }
@@ -44,6 +181,7 @@ impl<'de> serde::Deserialize<'de> for GetTransactionsRequest {
             "transactionsCount",
             "batch_size",
             "batchSize",
+            "filter",
         ];
 
         #[allow(clippy::enum_variant_names)]
@@ -51,6 +189,7 @@ impl<'de> serde::Deserialize<'de> for GetTransactionsRequest {
             StartingVersion,
             TransactionsCount,
             BatchSize,
+            Filter,
         }
         impl<'de> serde::Deserialize<'de> for GeneratedField {
             fn deserialize<D>(deserializer: D) -> std::result::Result<GeneratedField, D::Error>
@@ -75,6 +214,7 @@ impl<'de> serde::Deserialize<'de> for GetTransactionsRequest {
                             "startingVersion" | "starting_version" => Ok(GeneratedField::StartingVersion),
                             "transactionsCount" | "transactions_count" => Ok(GeneratedField::TransactionsCount),
                             "batchSize" | "batch_size" => Ok(GeneratedField::BatchSize),
+                            "filter" => Ok(GeneratedField::Filter),
                             _ => Err(serde::de::Error::unknown_field(value, FIELDS)),
                         }
                     }
@@ -97,6 +237,7 @@ impl<'de> serde::Deserialize<'de> for GetTransactionsRequest {
                 let mut starting_version__ = None;
                 let mut transactions_count__ = None;
                 let mut batch_size__ = None;
+                let mut filter__ = None;
                 while let Some(k) = map.next_key()? {
                     match k {
                         GeneratedField::StartingVersion => {
@@ -123,18 +264,170 @@ impl<'de> serde::Deserialize<'de> for GetTransactionsRequest {
                                 map.next_value::<::std::option::Option<::pbjson::private::NumberDeserialize<_>>>()?.map(|x| x.0)
                             ;
                         }
+                        GeneratedField::Filter => {
+                            if filter__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("filter"));
+                            }
+                            filter__ = map.next_value()?;
+                        }
                     }
                 }
                 Ok(GetTransactionsRequest {
                     starting_version: starting_version__,
                     transactions_count: transactions_count__,
                     batch_size: batch_size__,
+                    filter: filter__,
                 })
             }
         }
         deserializer.deserialize_struct("aptos.indexer.v1.GetTransactionsRequest", FIELDS, GeneratedVisitor)
     }
 }
+impl serde::Serialize for TransactionFilter {
+    #[allow(deprecated)]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let mut len = 0;
+        if !self.event_types.is_empty() {
+            len += 1;
+        }
+        if !self.module_addresses.is_empty() {
+            len += 1;
+        }
+        if !self.entry_functions.is_empty() {
+            len += 1;
+        }
+        if !self.accounts.is_empty() {
+            len += 1;
+        }
+        let mut struct_ser = serializer.serialize_struct("aptos.indexer.v1.TransactionFilter", len)?;
+        if !self.event_types.is_empty() {
+            struct_ser.serialize_field("eventTypes", &self.event_types)?;
+        }
+        if !self.module_addresses.is_empty() {
+            struct_ser.serialize_field("moduleAddresses", &self.module_addresses)?;
+        }
+        if !self.entry_functions.is_empty() {
+            struct_ser.serialize_field("entryFunctions", &self.entry_functions)?;
+        }
+        if !self.accounts.is_empty() {
+            struct_ser.serialize_field("accounts", &self.accounts)?;
+        }
+        struct_ser.end()
+    }
+}
+impl<'de> serde::Deserialize<'de> for TransactionFilter {
+    #[allow(deprecated)]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        const FIELDS: &[&str] = &[
+            "event_types",
+            "eventTypes",
+            "module_addresses",
+            "moduleAddresses",
+            "entry_functions",
+            "entryFunctions",
+            "accounts",
+        ];
+
+        #[allow(clippy::enum_variant_names)]
+        enum GeneratedField {
+            EventTypes,
+            ModuleAddresses,
+            EntryFunctions,
+            Accounts,
+        }
+        impl<'de> serde::Deserialize<'de> for GeneratedField {
+            fn deserialize<D>(deserializer: D) -> std::result::Result<GeneratedField, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                struct GeneratedVisitor;
+
+                impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+                    type Value = GeneratedField;
+
+                    fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                        write!(formatter, "expected one of: {:?}", &FIELDS)
+                    }
+
+                    #[allow(unused_variables)]
+                    fn visit_str<E>(self, value: &str) -> std::result::Result<GeneratedField, E>
+                    where
+                        E: serde::de::Error,
+                    {
+                        match value {
+                            "eventTypes" | "event_types" => Ok(GeneratedField::EventTypes),
+                            "moduleAddresses" | "module_addresses" => Ok(GeneratedField::ModuleAddresses),
+                            "entryFunctions" | "entry_functions" => Ok(GeneratedField::EntryFunctions),
+                            "accounts" => Ok(GeneratedField::Accounts),
+                            _ => Err(serde::de::Error::unknown_field(value, FIELDS)),
+                        }
+                    }
+                }
+                deserializer.deserialize_identifier(GeneratedVisitor)
+            }
+        }
+        struct GeneratedVisitor;
+        impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+            type Value = TransactionFilter;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                formatter.write_str("struct aptos.indexer.v1.TransactionFilter")
+            }
+
+            fn visit_map<V>(self, mut map: V) -> std::result::Result<TransactionFilter, V::Error>
+                where
+                    V: serde::de::MapAccess<'de>,
+            {
+                let mut event_types__ = None;
+                let mut module_addresses__ = None;
+                let mut entry_functions__ = None;
+                let mut accounts__ = None;
+                while let Some(k) = map.next_key()? {
+                    match k {
+                        GeneratedField::EventTypes => {
+                            if event_types__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("eventTypes"));
+                            }
+                            event_types__ = Some(map.next_value()?);
+                        }
+                        GeneratedField::ModuleAddresses => {
+                            if module_addresses__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("moduleAddresses"));
+                            }
+                            module_addresses__ = Some(map.next_value()?);
+                        }
+                        GeneratedField::EntryFunctions => {
+                            if entry_functions__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("entryFunctions"));
+                            }
+                            entry_functions__ = Some(map.next_value()?);
+                        }
+                        GeneratedField::Accounts => {
+                            if accounts__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("accounts"));
+                            }
+                            accounts__ = Some(map.next_value()?);
+                        }
+                    }
+                }
+                Ok(TransactionFilter {
+                    event_types: event_types__.unwrap_or_default(),
+                    module_addresses: module_addresses__.unwrap_or_default(),
+                    entry_functions: entry_functions__.unwrap_or_default(),
+                    accounts: accounts__.unwrap_or_default(),
+                })
+            }
+        }
+        deserializer.deserialize_struct("aptos.indexer.v1.TransactionFilter", FIELDS, GeneratedVisitor)
+    }
+}
 impl serde::Serialize for TransactionsResponse {
     #[allow(deprecated)]
     fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
@@ -149,6 +442,9 @@ impl serde::Serialize for TransactionsResponse {
         if self.chain_id.is_some() {
             len += 1;
         }
+        if self.last_scanned_version.is_some() {
+            len += 1;
+        }
         let mut struct_ser = serializer.serialize_struct("aptos.indexer.v1.TransactionsResponse", len)?;
         if !self.transactions.is_empty() {
             struct_ser.serialize_field("transactions", &self.transactions)?;
@@ -156,6 +452,9 @@ impl serde::Serialize for TransactionsResponse {
         if let Some(v) = self.chain_id.as_ref() {
             struct_ser.serialize_field("chainId", ToString::to_string(&v).as_str())?;
         }
+        if let Some(v) = self.last_scanned_version.as_ref() {
+            struct_ser.serialize_field("lastScannedVersion", ToString::to_string(&v).as_str())?;
+        }
         struct_ser.end()
     }
 }
@@ -169,12 +468,15 @@ impl<'de> serde::Deserialize<'de> for TransactionsResponse {
             "transactions",
             "chain_id",
             "chainId",
+            "last_scanned_version",
+            "lastScannedVersion",
         ];
 
         #[allow(clippy::enum_variant_names)]
         enum GeneratedField {
             Transactions,
             ChainId,
+            LastScannedVersion,
         }
         impl<'de> serde::Deserialize<'de> for GeneratedField {
             fn deserialize<D>(deserializer: D) -> std::result::Result<GeneratedField, D::Error>
@@ -198,6 +500,7 @@ impl<'de> serde::Deserialize<'de> for TransactionsResponse {
                         match value {
                             "transactions" => Ok(GeneratedField::Transactions),
                             "chainId" | "chain_id" => Ok(GeneratedField::ChainId),
+                            "lastScannedVersion" | "last_scanned_version" => Ok(GeneratedField::LastScannedVersion),
                             _ => Err(serde::de::Error::unknown_field(value, FIELDS)),
                         }
                     }
@@ -219,6 +522,7 @@ impl<'de> serde::Deserialize<'de> for TransactionsResponse {
             {
                 let mut transactions__ = None;
                 let mut chain_id__ = None;
+                let mut last_scanned_version__ = None;
                 while let Some(k) = map.next_key()? {
                     match k {
                         GeneratedField::Transactions => {
@@ -235,11 +539,20 @@ impl<'de> serde::Deserialize<'de> for TransactionsResponse {
                                 map.next_value::<::std::option::Option<::pbjson::private::NumberDeserialize<_>>>()?.map(|x| x.0)
                             ;
                         }
+                        GeneratedField::LastScannedVersion => {
+                            if last_scanned_version__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("lastScannedVersion"));
+                            }
+                            last_scanned_version__ =
+                                map.next_value::<::std::option::Option<::pbjson::private::NumberDeserialize<_>>>()?.map(|x| x.0)
+                            ;
+                        }
                     }
                 }
                 Ok(TransactionsResponse {
                     transactions: transactions__.unwrap_or_default(),
                     chain_id: chain_id__,
+                    last_scanned_version: last_scanned_version__,
                 })
             }
         }
@@ -95,6 +95,31 @@ pub mod raw_data_client {
             );
             self.inner.server_streaming(request.into_request(), path, codec).await
         }
+        /** Get transactions of a closed version range from the file store, e.g. to backfill the history for a new processor.
+ The whole range must already be in the file store; the latest transactions are only served by `GetTransactions`.
+*/
+        pub async fn get_transactions_in_range(
+            &mut self,
+            request: impl tonic::IntoRequest<super::GetTransactionsInRangeRequest>,
+        ) -> Result<
+            tonic::Response<tonic::codec::Streaming<super::TransactionsResponse>>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/aptos.indexer.v1.RawData/GetTransactionsInRange",
+            );
+            self.inner.server_streaming(request.into_request(), path, codec).await
+        }
     }
 }
 /// Generated server implementations.
@@ -116,6 +141,22 @@ pub mod raw_data_server {
             &self,
             request: tonic::Request<super::GetTransactionsRequest>,
         ) -> Result<tonic::Response<Self::GetTransactionsStream>, tonic::Status>;
+        /// Server streaming response type for the GetTransactionsInRange method.
+        type GetTransactionsInRangeStream: futures_core::Stream<
+                Item = Result<super::TransactionsResponse, tonic::Status>,
+            >
+            + Send
+            + 'static;
+        /** Get transactions of a closed version range from the file store, e.g. to backfill the history for a new processor.
+ The whole range must already be in the file store; the latest transactions are only served by `GetTransactions`.
+*/
+        async fn get_transactions_in_range(
+            &self,
+            request: tonic::Request<super::GetTransactionsInRangeRequest>,
+        ) -> Result<
+            tonic::Response<Self::GetTransactionsInRangeStream>,
+            tonic::Status,
+        >;
     }
     ///
     #[derive(Debug)]
@@ -219,6 +260,48 @@ pub mod raw_data_server {
                     };
                     Box::pin(fut)
                 }
+                "/aptos.indexer.v1.RawData/GetTransactionsInRange" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetTransactionsInRangeSvc<T: RawData>(pub Arc<T>);
+                    impl<
+                        T: RawData,
+                    > tonic::server::ServerStreamingService<
+                        super::GetTransactionsInRangeRequest,
+                    > for GetTransactionsInRangeSvc<T> {
+                        type Response = super::TransactionsResponse;
+                        type ResponseStream = T::GetTransactionsInRangeStream;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::ResponseStream>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetTransactionsInRangeRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move {
+                                (*inner).get_transactions_in_range(request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = GetTransactionsInRangeSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            );
+                        let res = grpc.server_streaming(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
                 _ => {
                     Box::pin(async move {
                         Ok(
@@ -577,6 +577,23 @@ async fn construction_parse(
                 (AccountAddress::ONE, DELEGATION_POOL_MODULE, DELEGATION_POOL_UNLOCK_FUNCTION) => {
                     parse_delegation_pool_unlock_operation(sender, &type_args, &args)?
                 },
+                (
+                    AccountAddress::ONE,
+                    DELEGATION_POOL_MODULE,
+                    DELEGATION_POOL_SET_OPERATOR_FUNCTION,
+                ) => parse_delegation_pool_set_operator_operation(sender, &type_args, &args)?,
+                (AccountAddress::ONE, STAKE_MODULE, STAKE_ADD_STAKE_FUNCTION) => {
+                    parse_stake_add_stake_operation(sender, &type_args, &args)?
+                },
+                (AccountAddress::ONE, STAKE_MODULE, STAKE_UNLOCK_FUNCTION) => {
+                    parse_stake_unlock_operation(sender, &type_args, &args)?
+                },
+                (AccountAddress::ONE, STAKE_MODULE, STAKE_WITHDRAW_FUNCTION) => {
+                    parse_stake_withdraw_operation(sender, &type_args, &args)?
+                },
+                (AccountAddress::ONE, STAKE_MODULE, STAKE_SET_OPERATOR_FUNCTION) => {
+                    parse_stake_set_operator_operation(sender, &type_args, &args)?
+                },
                 _ => {
                     return Err(ApiError::TransactionParseError(Some(format!(
                         "Unsupported entry function type {:x}::{}::{}",
@@ -997,6 +1014,111 @@ pub fn parse_delegation_pool_withdraw_operation(
     )])
 }
 
+pub fn parse_delegation_pool_set_operator_operation(
+    owner: AccountAddress,
+    type_args: &[TypeTag],
+    args: &[Vec<u8>],
+) -> ApiResult<Vec<Operation>> {
+    if !type_args.is_empty() {
+        return Err(ApiError::TransactionParseError(Some(format!(
+            "set_delegation_pool_operator should not have type arguments: {:?}",
+            type_args
+        ))));
+    }
+
+    let new_operator: AccountAddress = parse_function_arg("set_delegation_pool_operator", args, 0)?;
+
+    Ok(vec![Operation::set_delegation_pool_operator(
+        0,
+        None,
+        owner,
+        AccountIdentifier::base_account(new_operator),
+    )])
+}
+
+pub fn parse_stake_add_stake_operation(
+    owner: AccountAddress,
+    type_args: &[TypeTag],
+    args: &[Vec<u8>],
+) -> ApiResult<Vec<Operation>> {
+    if !type_args.is_empty() {
+        return Err(ApiError::TransactionParseError(Some(format!(
+            "add_stake should not have type arguments: {:?}",
+            type_args
+        ))));
+    }
+
+    let amount: u64 = parse_function_arg("add_stake", args, 0)?;
+
+    Ok(vec![Operation::add_stake(0, None, owner, Some(amount))])
+}
+
+pub fn parse_stake_unlock_operation(
+    owner: AccountAddress,
+    type_args: &[TypeTag],
+    args: &[Vec<u8>],
+) -> ApiResult<Vec<Operation>> {
+    if !type_args.is_empty() {
+        return Err(ApiError::TransactionParseError(Some(format!(
+            "unlock_pool_stake should not have type arguments: {:?}",
+            type_args
+        ))));
+    }
+
+    let amount: u64 = parse_function_arg("unlock_pool_stake", args, 0)?;
+
+    Ok(vec![Operation::unlock_pool_stake(
+        0,
+        None,
+        owner,
+        Some(amount),
+    )])
+}
+
+pub fn parse_stake_withdraw_operation(
+    owner: AccountAddress,
+    type_args: &[TypeTag],
+    args: &[Vec<u8>],
+) -> ApiResult<Vec<Operation>> {
+    if !type_args.is_empty() {
+        return Err(ApiError::TransactionParseError(Some(format!(
+            "withdraw_pool_stake should not have type arguments: {:?}",
+            type_args
+        ))));
+    }
+
+    let amount: u64 = parse_function_arg("withdraw_pool_stake", args, 0)?;
+
+    Ok(vec![Operation::withdraw_pool_stake(
+        0,
+        None,
+        owner,
+        Some(amount),
+    )])
+}
+
+pub fn parse_stake_set_operator_operation(
+    owner: AccountAddress,
+    type_args: &[TypeTag],
+    args: &[Vec<u8>],
+) -> ApiResult<Vec<Operation>> {
+    if !type_args.is_empty() {
+        return Err(ApiError::TransactionParseError(Some(format!(
+            "set_pool_operator should not have type arguments: {:?}",
+            type_args
+        ))));
+    }
+
+    let new_operator: AccountAddress = parse_function_arg("set_pool_operator", args, 0)?;
+
+    Ok(vec![Operation::set_pool_operator(
+        0,
+        None,
+        owner,
+        AccountIdentifier::base_account(new_operator),
+    )])
+}
+
 /// Construction payloads command (OFFLINE)
 ///
 /// Constructs payloads for given known operations
@@ -1185,6 +1307,18 @@ async fn construction_payloads(
                 ))));
             }
         },
+        InternalOperation::SetDelegationPoolOperator(_)
+        | InternalOperation::AddStake(_)
+        | InternalOperation::UnlockPoolStake(_)
+        | InternalOperation::WithdrawPoolStake(_)
+        | InternalOperation::SetPoolOperator(_) => {
+            if operation != metadata.internal_operation {
+                return Err(ApiError::InvalidInput(Some(format!(
+                    "Stake pool operation doesn't match metadata {:?} vs {:?}",
+                    operation, metadata.internal_operation
+                ))));
+            }
+        },
         InternalOperation::WithdrawUndelegated(inner) => {
             if let InternalOperation::WithdrawUndelegated(ref metadata_op) =
                 metadata.internal_operation
@@ -1367,3 +1501,62 @@ async fn construction_submit(
         transaction_identifier: hash.into(),
     })
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_stake_pool_operations_roundtrip() {
+        let owner = AccountAddress::from_hex_literal("0x1234").unwrap();
+        let new_operator =
+            AccountIdentifier::base_account(AccountAddress::from_hex_literal("0x5678").unwrap());
+        let operations = vec![
+            Operation::add_stake(0, None, owner, Some(100)),
+            Operation::unlock_pool_stake(0, None, owner, Some(200)),
+            Operation::withdraw_pool_stake(0, None, owner, Some(300)),
+            Operation::set_pool_operator(0, None, owner, new_operator.clone()),
+            Operation::set_delegation_pool_operator(0, None, owner, new_operator),
+        ];
+
+        for operation in operations {
+            let internal_operation = InternalOperation::extract(&vec![operation.clone()]).unwrap();
+            assert_eq!(internal_operation.sender(), owner);
+            let (payload, sender) = internal_operation.payload().unwrap();
+            assert_eq!(sender, owner);
+
+            let parsed = match payload {
+                TransactionPayload::EntryFunction(inner) => {
+                    let (module, function_name, type_args, args) = inner.into_inner();
+                    match (module.name().as_str(), function_name.as_str()) {
+                        (DELEGATION_POOL_MODULE, DELEGATION_POOL_SET_OPERATOR_FUNCTION) => {
+                            parse_delegation_pool_set_operator_operation(owner, &type_args, &args)
+                        },
+                        (STAKE_MODULE, STAKE_ADD_STAKE_FUNCTION) => {
+                            parse_stake_add_stake_operation(owner, &type_args, &args)
+                        },
+                        (STAKE_MODULE, STAKE_UNLOCK_FUNCTION) => {
+                            parse_stake_unlock_operation(owner, &type_args, &args)
+                        },
+                        (STAKE_MODULE, STAKE_WITHDRAW_FUNCTION) => {
+                            parse_stake_withdraw_operation(owner, &type_args, &args)
+                        },
+                        (STAKE_MODULE, STAKE_SET_OPERATOR_FUNCTION) => {
+                            parse_stake_set_operator_operation(owner, &type_args, &args)
+                        },
+                        other => panic!("Unexpected entry function {:?}", other),
+                    }
+                },
+                payload => panic!("Unexpected payload {:?}", payload),
+            }
+            .unwrap();
+            assert_eq!(parsed, vec![operation]);
+        }
+
+        // The amount is required to build a stake pool operation
+        let mut operation = Operation::add_stake(0, None, owner, None);
+        assert!(InternalOperation::extract(&vec![operation.clone()]).is_err());
+        operation.metadata = None;
+        assert!(InternalOperation::extract(&vec![operation]).is_err());
+    }
+}
@@ -114,25 +114,35 @@ pub enum OperationType {
     DistributeStakingRewards,
     AddDelegatedStake,
     UnlockDelegatedStake,
+    SetDelegationPoolOperator,
+    AddStake,
+    UnlockPoolStake,
+    WithdrawPoolStake,
+    SetPoolOperator,
     // Fee must always be last for ordering
     Fee,
 }
 
 impl OperationType {
     const ADD_DELEGATED_STAKE: &'static str = "add_delegated_stake";
+    const ADD_STAKE: &'static str = "add_stake";
     const CREATE_ACCOUNT: &'static str = "create_account";
     const DEPOSIT: &'static str = "deposit";
     const DISTRIBUTE_STAKING_REWARDS: &'static str = "distribute_staking_rewards";
     const FEE: &'static str = "fee";
     const INITIALIZE_STAKE_POOL: &'static str = "initialize_stake_pool";
     const RESET_LOCKUP: &'static str = "reset_lockup";
+    const SET_DELEGATION_POOL_OPERATOR: &'static str = "set_delegation_pool_operator";
     const SET_OPERATOR: &'static str = "set_operator";
+    const SET_POOL_OPERATOR: &'static str = "set_pool_operator";
     const SET_VOTER: &'static str = "set_voter";
     const STAKING_REWARD: &'static str = "staking_reward";
     const UNLOCK_DELEGATED_STAKE: &'static str = "unlock_delegated_stake";
+    const UNLOCK_POOL_STAKE: &'static str = "unlock_pool_stake";
     const UNLOCK_STAKE: &'static str = "unlock_stake";
     const UPDATE_COMMISSION: &'static str = "update_commission";
     const WITHDRAW: &'static str = "withdraw";
+    const WITHDRAW_POOL_STAKE: &'static str = "withdraw_pool_stake";
     const WITHDRAW_UNDELEGATED_FUNDS: &'static str = "withdraw_undelegated_funds";
 
     pub fn all() -> Vec<OperationType> {
@@ -152,6 +162,11 @@ impl OperationType {
             DistributeStakingRewards,
             AddDelegatedStake,
             UnlockDelegatedStake,
+            SetDelegationPoolOperator,
+            AddStake,
+            UnlockPoolStake,
+            WithdrawPoolStake,
+            SetPoolOperator,
         ]
     }
 }
@@ -176,6 +191,11 @@ impl FromStr for OperationType {
             Self::ADD_DELEGATED_STAKE => Ok(OperationType::AddDelegatedStake),
             Self::UNLOCK_DELEGATED_STAKE => Ok(OperationType::UnlockDelegatedStake),
             Self::WITHDRAW_UNDELEGATED_FUNDS => Ok(OperationType::WithdrawUndelegatedFunds),
+            Self::SET_DELEGATION_POOL_OPERATOR => Ok(OperationType::SetDelegationPoolOperator),
+            Self::ADD_STAKE => Ok(OperationType::AddStake),
+            Self::UNLOCK_POOL_STAKE => Ok(OperationType::UnlockPoolStake),
+            Self::WITHDRAW_POOL_STAKE => Ok(OperationType::WithdrawPoolStake),
+            Self::SET_POOL_OPERATOR => Ok(OperationType::SetPoolOperator),
             _ => Err(ApiError::DeserializationFailed(Some(format!(
                 "Invalid OperationType: {}",
                 s
@@ -202,6 +222,11 @@ impl Display for OperationType {
             AddDelegatedStake => Self::ADD_DELEGATED_STAKE,
             UnlockDelegatedStake => Self::UNLOCK_DELEGATED_STAKE,
             WithdrawUndelegatedFunds => Self::WITHDRAW_UNDELEGATED_FUNDS,
+            SetDelegationPoolOperator => Self::SET_DELEGATION_POOL_OPERATOR,
+            AddStake => Self::ADD_STAKE,
+            UnlockPoolStake => Self::UNLOCK_POOL_STAKE,
+            WithdrawPoolStake => Self::WITHDRAW_POOL_STAKE,
+            SetPoolOperator => Self::SET_POOL_OPERATOR,
             Fee => Self::FEE,
         })
     }
@@ -47,6 +47,13 @@ pub const DISTRIBUTE_STAKING_REWARDS_FUNCTION: &str = "distribute";
 pub const DELEGATION_POOL_ADD_STAKE_FUNCTION: &str = "add_stake";
 pub const DELEGATION_POOL_UNLOCK_FUNCTION: &str = "unlock";
 pub const DELEGATION_POOL_WITHDRAW_FUNCTION: &str = "withdraw";
+pub const DELEGATION_POOL_SET_OPERATOR_FUNCTION: &str = "set_operator";
+
+// Stake Pool
+pub const STAKE_ADD_STAKE_FUNCTION: &str = "add_stake";
+pub const STAKE_UNLOCK_FUNCTION: &str = "unlock";
+pub const STAKE_WITHDRAW_FUNCTION: &str = "withdraw";
+pub const STAKE_SET_OPERATOR_FUNCTION: &str = "set_operator";
 
 pub const DECIMALS_FIELD: &str = "decimal";
 pub const DEPOSIT_EVENTS_FIELD: &str = "deposit_events";
@@ -9,9 +9,11 @@ use crate::{
     common::{is_native_coin, native_coin, native_coin_tag},
     construction::{
         parse_create_stake_pool_operation, parse_delegation_pool_add_stake_operation,
-        parse_delegation_pool_unlock_operation, parse_delegation_pool_withdraw_operation,
-        parse_distribute_staking_rewards_operation, parse_reset_lockup_operation,
-        parse_set_operator_operation, parse_set_voter_operation, parse_unlock_stake_operation,
+        parse_delegation_pool_set_operator_operation, parse_delegation_pool_unlock_operation,
+        parse_delegation_pool_withdraw_operation, parse_distribute_staking_rewards_operation,
+        parse_reset_lockup_operation, parse_set_operator_operation, parse_set_voter_operation,
+        parse_stake_add_stake_operation, parse_stake_set_operator_operation,
+        parse_stake_unlock_operation, parse_stake_withdraw_operation, parse_unlock_stake_operation,
         parse_update_commission_operation,
     },
     error::ApiResult,
@@ -587,6 +589,86 @@ impl Operation {
             )),
         )
     }
+
+    pub fn set_delegation_pool_operator(
+        operation_index: u64,
+        status: Option<OperationStatusType>,
+        owner: AccountAddress,
+        new_operator: AccountIdentifier,
+    ) -> Operation {
+        Operation::new(
+            OperationType::SetDelegationPoolOperator,
+            operation_index,
+            status,
+            AccountIdentifier::base_account(owner),
+            None,
+            Some(OperationMetadata::set_pool_operator(new_operator)),
+        )
+    }
+
+    pub fn add_stake(
+        operation_index: u64,
+        status: Option<OperationStatusType>,
+        owner: AccountAddress,
+        amount: Option<u64>,
+    ) -> Operation {
+        Operation::new(
+            OperationType::AddStake,
+            operation_index,
+            status,
+            AccountIdentifier::base_account(owner),
+            None,
+            Some(OperationMetadata::pool_stake(amount)),
+        )
+    }
+
+    pub fn unlock_pool_stake(
+        operation_index: u64,
+        status: Option<OperationStatusType>,
+        owner: AccountAddress,
+        amount: Option<u64>,
+    ) -> Operation {
+        Operation::new(
+            OperationType::UnlockPoolStake,
+            operation_index,
+            status,
+            AccountIdentifier::base_account(owner),
+            None,
+            Some(OperationMetadata::pool_stake(amount)),
+        )
+    }
+
+    pub fn withdraw_pool_stake(
+        operation_index: u64,
+        status: Option<OperationStatusType>,
+        owner: AccountAddress,
+        amount: Option<u64>,
+    ) -> Operation {
+        Operation::new(
+            OperationType::WithdrawPoolStake,
+            operation_index,
+            status,
+            AccountIdentifier::base_account(owner),
+            None,
+            Some(OperationMetadata::pool_stake(amount)),
+        )
+    }
+
+    pub fn set_pool_operator(
+        operation_index: u64,
+        status: Option<OperationStatusType>,
+        owner: AccountAddress,
+        new_operator: AccountIdentifier,
+    ) -> Operation {
+        Operation::new(
+            OperationType::SetPoolOperator,
+            operation_index,
+            status,
+            AccountIdentifier::base_account(owner),
+            None,
+            Some(OperationMetadata::set_pool_operator(new_operator)),
+        )
+    }
 }
 
 impl std::cmp::PartialOrd for Operation {
@@ -750,6 +832,23 @@ impl OperationMetadata {
             ..Default::default()
         }
     }
+
+    /// Metadata of the operations moving stake in the owner's own stake pool
+    pub fn pool_stake(amount: Option<u64>) -> Self {
+        OperationMetadata {
+            amount: amount.map(U64::from),
+            ..Default::default()
+        }
+    }
+
+    /// Metadata of the operations changing the operator of the owner's own stake or
+    /// delegation pool
+    pub fn set_pool_operator(new_operator: AccountIdentifier) -> Self {
+        OperationMetadata {
+            new_operator: Some(new_operator),
+            ..Default::default()
+        }
+    }
 }
 
 /// Public key used for the rosetta implementation.  All private keys will never be handled
@@ -1117,6 +1216,72 @@ fn parse_failed_operations_from_txn_payload(
                     warn!("Failed to parse delegation_pool::unlock {:?}", inner);
                 }
             },
+            (
+                AccountAddress::ONE,
+                DELEGATION_POOL_MODULE,
+                DELEGATION_POOL_SET_OPERATOR_FUNCTION,
+            ) => {
+                if let Ok(mut ops) = parse_delegation_pool_set_operator_operation(
+                    sender,
+                    inner.ty_args(),
+                    inner.args(),
+                ) {
+                    if let Some(operation) = ops.get_mut(0) {
+                        operation.status = Some(OperationStatusType::Failure.to_string());
+                    }
+                    operations = ops;
+                } else {
+                    warn!("Failed to parse delegation_pool::set_operator {:?}", inner);
+                }
+            },
+            (AccountAddress::ONE, STAKE_MODULE, STAKE_ADD_STAKE_FUNCTION) => {
+                if let Ok(mut ops) =
+                    parse_stake_add_stake_operation(sender, inner.ty_args(), inner.args())
+                {
+                    if let Some(operation) = ops.get_mut(0) {
+                        operation.status = Some(OperationStatusType::Failure.to_string());
+                    }
+                    operations = ops;
+                } else {
+                    warn!("Failed to parse stake::add_stake {:?}", inner);
+                }
+            },
+            (AccountAddress::ONE, STAKE_MODULE, STAKE_UNLOCK_FUNCTION) => {
+                if let Ok(mut ops) =
+                    parse_stake_unlock_operation(sender, inner.ty_args(), inner.args())
+                {
+                    if let Some(operation) = ops.get_mut(0) {
+                        operation.status = Some(OperationStatusType::Failure.to_string());
+                    }
+                    operations = ops;
+                } else {
+                    warn!("Failed to parse stake::unlock {:?}", inner);
+                }
+            },
+            (AccountAddress::ONE, STAKE_MODULE, STAKE_WITHDRAW_FUNCTION) => {
+                if let Ok(mut ops) =
+                    parse_stake_withdraw_operation(sender, inner.ty_args(), inner.args())
+                {
+                    if let Some(operation) = ops.get_mut(0) {
+                        operation.status = Some(OperationStatusType::Failure.to_string());
+                    }
+                    operations = ops;
+                } else {
+                    warn!("Failed to parse stake::withdraw {:?}", inner);
+                }
+            },
+            (AccountAddress::ONE, STAKE_MODULE, STAKE_SET_OPERATOR_FUNCTION) => {
+                if let Ok(mut ops) =
+                    parse_stake_set_operator_operation(sender, inner.ty_args(), inner.args())
+                {
+                    if let Some(operation) = ops.get_mut(0) {
+                        operation.status = Some(OperationStatusType::Failure.to_string());
+                    }
+                    operations = ops;
+                } else {
+                    warn!("Failed to parse stake::set_operator {:?}", inner);
+                }
+            },
             _ => {
                 // If we don't recognize the transaction payload, then we can't parse operations
             },
@@ -1849,6 +2014,11 @@ pub enum InternalOperation {
     DistributeStakingRewards(DistributeStakingRewards),
     AddDelegatedStake(AddDelegatedStake),
     UnlockDelegatedStake(UnlockDelegatedStake),
+    SetDelegationPoolOperator(SetPoolOperator),
+    AddStake(PoolStake),
+    UnlockPoolStake(PoolStake),
+    WithdrawPoolStake(PoolStake),
+    SetPoolOperator(SetPoolOperator),
 }
 
 impl InternalOperation {
@@ -2089,6 +2259,55 @@ impl InternalOperation {
                                 }));
                             }
                         },
+                        Ok(
+                            operation_type @ (OperationType::SetDelegationPoolOperator
+                            | OperationType::SetPoolOperator),
+                        ) => {
+                            if let (
+                                Some(OperationMetadata {
+                                    new_operator: Some(new_operator),
+                                    ..
+                                }),
+                                Some(account),
+                            ) = (&operation.metadata, &operation.account)
+                            {
+                                let set_operator = SetPoolOperator {
+                                    owner: account.account_address()?,
+                                    new_operator: new_operator.account_address()?,
+                                };
+                                return Ok(if operation_type == OperationType::SetPoolOperator {
+                                    Self::SetPoolOperator(set_operator)
+                                } else {
+                                    Self::SetDelegationPoolOperator(set_operator)
+                                });
+                            }
+                        },
+                        Ok(
+                            operation_type @ (OperationType::AddStake
+                            | OperationType::UnlockPoolStake
+                            | OperationType::WithdrawPoolStake),
+                        ) => {
+                            if let (
+                                Some(OperationMetadata {
+                                    amount: Some(amount),
+                                    ..
+                                }),
+                                Some(account),
+                            ) = (&operation.metadata, &operation.account)
+                            {
+                                let pool_stake = PoolStake {
+                                    owner: account.account_address()?,
+                                    amount: amount.0,
+                                };
+                                return Ok(match operation_type {
+                                    OperationType::AddStake => Self::AddStake(pool_stake),
+                                    OperationType::UnlockPoolStake => {
+                                        Self::UnlockPoolStake(pool_stake)
+                                    },
+                                    _ => Self::WithdrawPoolStake(pool_stake),
+                                });
+                            }
+                        },
                         _ => {},
                     }
                 }
@@ -2122,6 +2341,11 @@ impl InternalOperation {
             Self::DistributeStakingRewards(inner) => inner.sender,
             Self::AddDelegatedStake(inner) => inner.delegator,
             Self::UnlockDelegatedStake(inner) => inner.delegator,
+            Self::SetDelegationPoolOperator(inner) => inner.owner,
+            Self::AddStake(inner) => inner.owner,
+            Self::UnlockPoolStake(inner) => inner.owner,
+            Self::WithdrawPoolStake(inner) => inner.owner,
+            Self::SetPoolOperator(inner) => inner.owner,
         }
     }
 
@@ -2224,6 +2448,25 @@ impl InternalOperation {
                 ),
                 withdraw_undelegated.delegator,
             ),
+            InternalOperation::SetDelegationPoolOperator(set_operator) => (
+                aptos_stdlib::delegation_pool_set_operator(set_operator.new_operator),
+                set_operator.owner,
+            ),
+            InternalOperation::AddStake(add_stake) => (
+                aptos_stdlib::stake_add_stake(add_stake.amount),
+                add_stake.owner,
+            ),
+            InternalOperation::UnlockPoolStake(unlock) => {
+                (aptos_stdlib::stake_unlock(unlock.amount), unlock.owner)
+            },
+            InternalOperation::WithdrawPoolStake(withdraw) => (
+                aptos_stdlib::stake_withdraw(withdraw.amount),
+                withdraw.owner,
+            ),
+            InternalOperation::SetPoolOperator(set_operator) => (
+                aptos_stdlib::stake_set_operator(set_operator.new_operator),
+                set_operator.owner,
+            ),
         })
     }
 }
@@ -2424,3 +2667,17 @@ pub struct UnlockDelegatedStake {
     pub pool_address: AccountAddress,
     pub amount: u64,
 }
+
+/// Stake moved in the owner's own stake pool, when adding, unlocking or withdrawing
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct PoolStake {
+    pub owner: AccountAddress,
+    pub amount: u64,
+}
+
+/// Operator change of the owner's own stake pool or delegation pool
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct SetPoolOperator {
+    pub owner: AccountAddress,
+    pub new_operator: AccountAddress,
+}
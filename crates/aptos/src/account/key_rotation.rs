@@ -102,7 +102,8 @@ impl CliCommand<RotateSummary> for RotateKey {
                 )
             })?;
 
-        let (current_private_key, sender_address) = self.txn_options.get_key_and_address()?;
+        // The current key may be on a Ledger device, which then signs the proof below
+        let (current_public_key, sender_address) = self.txn_options.get_public_key_and_address()?;
 
         // Get sequence number for account
         let sequence_number = self.txn_options.sequence_number(sender_address).await?;
@@ -124,7 +125,7 @@ impl CliCommand<RotateSummary> for RotateKey {
 
         // Signs the struct using both the current private key and the next private key
         let rotation_proof_signed_by_current_private_key =
-            current_private_key.sign_arbitrary_message(&rotation_msg.clone());
+            self.txn_options.sign_arbitrary_message(&rotation_msg)?;
         let rotation_proof_signed_by_new_private_key =
             new_private_key.sign_arbitrary_message(&rotation_msg);
 
@@ -133,7 +134,7 @@ impl CliCommand<RotateSummary> for RotateKey {
             .submit_transaction(aptos_stdlib::account_rotate_authentication_key(
                 0,
                 // Existing public key
-                current_private_key.public_key().to_bytes().to_vec(),
+                current_public_key.to_bytes().to_vec(),
                 0,
                 // New public key
                 new_private_key.public_key().to_bytes().to_vec(),
@@ -220,6 +221,7 @@ impl CliCommand<RotateSummary> for RotateKey {
             private_key: Some(new_private_key.clone()),
             public_key: Some(new_private_key.public_key()),
             account: Some(sender_address),
+            derivation_path: None,
             ..self.txn_options.profile_options.profile()?
         };
 
@@ -12,6 +12,7 @@ pub mod key_rotation;
 pub mod list;
 pub mod multisig_account;
 pub mod transfer;
+pub mod watch;
 
 /// Tool for interacting with accounts
 ///
@@ -27,6 +28,7 @@ pub enum AccountTool {
     LookupAddress(key_rotation::LookupAddress),
     RotateKey(key_rotation::RotateKey),
     Transfer(transfer::TransferCoins),
+    Watch(watch::WatchAccount),
 }
 
 impl AccountTool {
@@ -40,6 +42,7 @@ impl AccountTool {
             AccountTool::LookupAddress(tool) => tool.execute_serialized().await,
             AccountTool::RotateKey(tool) => tool.execute_serialized().await,
             AccountTool::Transfer(tool) => tool.execute_serialized().await,
+            AccountTool::Watch(tool) => tool.execute_serialized().await,
         }
     }
 }
@@ -53,6 +56,7 @@ pub enum MultisigAccountTool {
     Execute(multisig_account::Execute),
     ExecuteReject(multisig_account::ExecuteReject),
     ExecuteWithPayload(multisig_account::ExecuteWithPayload),
+    ExportPayload(multisig_account::ExportPayload),
     Reject(multisig_account::Reject),
     VerifyProposal(multisig_account::VerifyProposal),
 }
@@ -66,6 +70,7 @@ impl MultisigAccountTool {
             MultisigAccountTool::Execute(tool) => tool.execute_serialized().await,
             MultisigAccountTool::ExecuteReject(tool) => tool.execute_serialized().await,
             MultisigAccountTool::ExecuteWithPayload(tool) => tool.execute_serialized().await,
+            MultisigAccountTool::ExportPayload(tool) => tool.execute_serialized().await,
             MultisigAccountTool::Reject(tool) => tool.execute_serialized().await,
             MultisigAccountTool::VerifyProposal(tool) => tool.execute_serialized().await,
         }
@@ -3,8 +3,9 @@
 
 use crate::common::{
     types::{
-        CliCommand, CliError, CliTypedResult, EntryFunctionArguments, MultisigAccount,
-        MultisigAccountWithSequenceNumber, TransactionOptions, TransactionSummary,
+        ArgWithTypeJSON, CliCommand, CliError, CliTypedResult, EntryFunctionArguments,
+        EntryFunctionArgumentsJSON, MultisigAccount, MultisigAccountWithSequenceNumber, SaveFile,
+        TransactionOptions, TransactionSummary,
     },
     utils::view_json_option_str,
 };
@@ -18,7 +19,7 @@ use aptos_rest_client::{
 };
 use aptos_types::{
     account_address::AccountAddress,
-    transaction::{Multisig, MultisigTransactionPayload, TransactionPayload},
+    transaction::{EntryFunction, Multisig, MultisigTransactionPayload, TransactionPayload},
 };
 use async_trait::async_trait;
 use bcs::to_bytes;
@@ -26,6 +27,7 @@ use clap::Parser;
 use once_cell::sync::Lazy;
 use serde::Serialize;
 use serde_json::json;
+use std::path::PathBuf;
 
 static GET_TRANSACTION_ENTRY_FUNCTION: Lazy<EntryFunctionId> =
     Lazy::new(|| "0x1::multisig_account::get_transaction".parse().unwrap());
@@ -150,6 +152,76 @@ impl CliCommand<TransactionSummary> for CreateTransaction {
     }
 }
 
+/// Export a multisig transaction payload to a JSON file.
+///
+/// The arguments of the entry function are saved BCS encoded, so the file reproduces the exact
+/// same payload when passed via `--json-file` to CreateTransaction, VerifyProposal or
+/// ExecuteWithPayload. This lets a payload be constructed offline and shared with the other owners
+/// of the multisig, who can then verify it against the proposal before approving.
+#[derive(Debug, Parser)]
+pub struct ExportPayload {
+    #[clap(flatten)]
+    pub(crate) entry_function_args: EntryFunctionArguments,
+    #[clap(flatten)]
+    pub(crate) save_file: SaveFile,
+}
+
+/// Where the payload was exported, along with the hash stored on-chain for it
+#[derive(Clone, Debug, Serialize)]
+pub struct ExportPayloadSummary {
+    pub output_file: PathBuf,
+    pub payload_hash: String,
+}
+
+#[async_trait]
+impl CliCommand<ExportPayloadSummary> for ExportPayload {
+    fn command_name(&self) -> &'static str {
+        "ExportPayloadMultisig"
+    }
+
+    async fn execute(self) -> CliTypedResult<ExportPayloadSummary> {
+        self.save_file.check_file()?;
+        let entry_function: EntryFunction = self.entry_function_args.try_into()?;
+        let payload_hash = HashValue::sha3_256_of(&to_bytes(
+            &MultisigTransactionPayload::EntryFunction(entry_function.clone()),
+        )?)
+        .to_hex_literal();
+        let json = EntryFunctionArgumentsJSON {
+            function_id: format!(
+                "{}::{}::{}",
+                entry_function.module().address().to_hex_literal(),
+                entry_function.module().name(),
+                entry_function.function()
+            ),
+            type_args: entry_function
+                .ty_args()
+                .iter()
+                .map(|type_arg| type_arg.to_string())
+                .collect(),
+            args: entry_function
+                .args()
+                .iter()
+                .map(|arg| ArgWithTypeJSON {
+                    arg_type: "raw".to_string(),
+                    value: serde_json::Value::String(
+                        HexEncodedBytes::from(arg.clone()).to_string(),
+                    ),
+                })
+                .collect(),
+        };
+        self.save_file.save_to_file(
+            "Multisig payload JSON file",
+            serde_json::to_string_pretty(&json)
+                .map_err(|err| CliError::UnexpectedError(format!("{}", err)))?
+                .as_bytes(),
+        )?;
+        Ok(ExportPayloadSummary {
+            output_file: self.save_file.output_file,
+            payload_hash,
+        })
+    }
+}
+
 /// Verify entry function matches on-chain transaction proposal.
 #[derive(Debug, Parser)]
 pub struct VerifyProposal {
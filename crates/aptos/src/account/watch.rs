@@ -0,0 +1,295 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::common::types::{CliCommand, CliError, CliTypedResult, ProfileOptions, RestOptions};
+use aptos_rest_client::{error::RestError, Client};
+use aptos_types::account_address::AccountAddress;
+use async_trait::async_trait;
+use clap::Parser;
+use reqwest::StatusCode;
+use std::time::Duration;
+
+/// Number of events to fetch per request while catching up on new events
+const EVENTS_PER_POLL: u16 = 100;
+
+/// Watch a resource of an account for changes
+///
+/// This polls the node and prints a diff of the resource every time it changes.  With
+/// `--event-field`, it instead prints the new events of the event handle stored in that field of
+/// the resource as they are emitted, e.g. `--resource 0x1::coin::CoinStore<0x1::aptos_coin::AptosCoin>
+/// --event-field deposit_events`.  This runs until interrupted, or until `--max-updates` changes
+/// or events have been printed.
+#[derive(Debug, Parser)]
+pub struct WatchAccount {
+    /// Address of the account to watch
+    ///
+    /// Defaults to the account of the profile
+    #[clap(long, value_parser = crate::common::types::load_account_arg)]
+    pub(crate) address: Option<AccountAddress>,
+
+    /// Resource to watch, e.g. `0x1::account::Account`
+    #[clap(long)]
+    pub(crate) resource: String,
+
+    /// Name of a field of the resource holding an event handle, to watch its events instead
+    #[clap(long)]
+    pub(crate) event_field: Option<String>,
+
+    /// How often to poll the node, in milliseconds
+    #[clap(long, default_value_t = 1000)]
+    pub(crate) poll_interval_ms: u64,
+
+    /// Stop after printing this many changes or events
+    #[clap(long)]
+    pub(crate) max_updates: Option<u64>,
+
+    #[clap(flatten)]
+    pub(crate) rest_options: RestOptions,
+    #[clap(flatten)]
+    pub(crate) profile_options: ProfileOptions,
+}
+
+impl WatchAccount {
+    fn should_stop(&self, updates: u64) -> bool {
+        self.max_updates
+            .map(|max_updates| updates >= max_updates)
+            .unwrap_or(false)
+    }
+
+    /// Returns the resource along with the ledger version it was read at, or
+    /// `None` for the resource if the account doesn't hold it (yet).
+    async fn get_resource(
+        &self,
+        client: &Client,
+        address: AccountAddress,
+    ) -> CliTypedResult<(Option<serde_json::Value>, u64)> {
+        match client.get_account_resource(address, &self.resource).await {
+            Ok(response) => {
+                let version = response.state().version;
+                Ok((response.into_inner().map(|resource| resource.data), version))
+            },
+            Err(RestError::Api(err)) if err.status_code == StatusCode::NOT_FOUND => Ok((
+                None,
+                err.state.map(|state| state.version).unwrap_or_default(),
+            )),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    async fn watch_resource(
+        &self,
+        client: &Client,
+        address: AccountAddress,
+    ) -> CliTypedResult<u64> {
+        let mut updates = 0;
+        let (mut previous, version) = self.get_resource(client, address).await?;
+        match &previous {
+            Some(data) => println!(
+                "[version {}] {}\n{}",
+                version,
+                self.resource,
+                serde_json::to_string_pretty(data)
+                    .map_err(|err| CliError::UnexpectedError(err.to_string()))?
+            ),
+            None => println!(
+                "[version {}] {} does not exist at {}",
+                version,
+                self.resource,
+                address.to_hex_literal()
+            ),
+        }
+
+        while !self.should_stop(updates) {
+            tokio::time::sleep(Duration::from_millis(self.poll_interval_ms)).await;
+            let (current, version) = self.get_resource(client, address).await?;
+            if current == previous {
+                continue;
+            }
+
+            let mut lines = vec![];
+            diff_json(
+                "",
+                previous.as_ref().unwrap_or(&serde_json::Value::Null),
+                current.as_ref().unwrap_or(&serde_json::Value::Null),
+                &mut lines,
+            );
+            println!("[version {}] {}", version, self.resource);
+            for line in lines {
+                println!("  {}", line);
+            }
+            previous = current;
+            updates += 1;
+        }
+        Ok(updates)
+    }
+
+    async fn watch_events(
+        &self,
+        client: &Client,
+        address: AccountAddress,
+        event_field: &str,
+    ) -> CliTypedResult<u64> {
+        // Only print the events emitted from now on, the latest one tells us where to start.
+        let latest_events = client
+            .get_account_events(address, &self.resource, event_field, None, Some(1))
+            .await?
+            .into_inner();
+        let mut next_sequence_number = latest_events
+            .last()
+            .map(|event| event.sequence_number.0 + 1)
+            .unwrap_or(0);
+        println!(
+            "Watching {}::{} at {} from sequence number {}",
+            self.resource,
+            event_field,
+            address.to_hex_literal(),
+            next_sequence_number
+        );
+
+        let mut updates = 0;
+        while !self.should_stop(updates) {
+            tokio::time::sleep(Duration::from_millis(self.poll_interval_ms)).await;
+            let events = client
+                .get_account_events(
+                    address,
+                    &self.resource,
+                    event_field,
+                    Some(next_sequence_number),
+                    Some(EVENTS_PER_POLL),
+                )
+                .await?
+                .into_inner();
+            for event in events {
+                println!(
+                    "[version {}] {}",
+                    event.version,
+                    serde_json::to_string_pretty(&event)
+                        .map_err(|err| CliError::UnexpectedError(err.to_string()))?
+                );
+                next_sequence_number = event.sequence_number.0 + 1;
+                updates += 1;
+                if self.should_stop(updates) {
+                    break;
+                }
+            }
+        }
+        Ok(updates)
+    }
+}
+
+#[async_trait]
+impl CliCommand<String> for WatchAccount {
+    fn command_name(&self) -> &'static str {
+        "WatchAccount"
+    }
+
+    async fn execute(self) -> CliTypedResult<String> {
+        let address = match self.address {
+            Some(address) => address,
+            None => self.profile_options.account_address()?,
+        };
+        let client = self.rest_options.client(&self.profile_options)?;
+
+        let updates = match &self.event_field {
+            Some(event_field) => self.watch_events(&client, address, event_field).await?,
+            None => self.watch_resource(&client, address).await?,
+        };
+        Ok(format!("Stopped watching after {} updates", updates))
+    }
+}
+
+/// Appends a line for each value that differs between `old` and `new`, keyed
+/// by its path in the JSON, e.g. `- coin.value: "10"` then `+ coin.value: "5"`.
+fn diff_json(
+    path: &str,
+    old: &serde_json::Value,
+    new: &serde_json::Value,
+    lines: &mut Vec<String>,
+) {
+    use serde_json::Value;
+
+    // Diff a created or deleted object against an empty one, so we get a line per field.
+    let empty = Value::Object(Default::default());
+    let (old, new) = match (old, new) {
+        (Value::Null, Value::Object(_)) => (&empty, new),
+        (Value::Object(_), Value::Null) => (old, &empty),
+        _ => (old, new),
+    };
+    let child_path = |key: &str| {
+        if path.is_empty() {
+            key.to_string()
+        } else {
+            format!("{}.{}", path, key)
+        }
+    };
+    match (old, new) {
+        (Value::Object(old_map), Value::Object(new_map)) => {
+            for (key, old_value) in old_map {
+                diff_json(
+                    &child_path(key),
+                    old_value,
+                    new_map.get(key).unwrap_or(&Value::Null),
+                    lines,
+                );
+            }
+            for (key, new_value) in new_map {
+                if !old_map.contains_key(key) {
+                    diff_json(&child_path(key), &Value::Null, new_value, lines);
+                }
+            }
+        },
+        (Value::Array(old_vec), Value::Array(new_vec)) => {
+            for index in 0..old_vec.len().max(new_vec.len()) {
+                diff_json(
+                    &child_path(&index.to_string()),
+                    old_vec.get(index).unwrap_or(&Value::Null),
+                    new_vec.get(index).unwrap_or(&Value::Null),
+                    lines,
+                );
+            }
+        },
+        (old, new) if old != new => {
+            if !old.is_null() {
+                lines.push(format!("- {}: {}", path, old));
+            }
+            if !new.is_null() {
+                lines.push(format!("+ {}: {}", path, new));
+            }
+        },
+        _ => {},
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::diff_json;
+    use serde_json::json;
+
+    #[test]
+    fn test_diff_json() {
+        let old = json!({
+            "coin": { "value": "10" },
+            "frozen": false,
+            "guids": ["0x1", "0x2"],
+        });
+        let new = json!({
+            "coin": { "value": "5" },
+            "frozen": false,
+            "guids": ["0x1"],
+            "extra": 1,
+        });
+        let mut lines = vec![];
+        diff_json("", &old, &new, &mut lines);
+        assert_eq!(lines, vec![
+            "- coin.value: \"10\"",
+            "+ coin.value: \"5\"",
+            "- guids.1: \"0x2\"",
+            "+ extra: 1",
+        ]);
+
+        // A resource that was created or deleted shows up as all added or all removed.
+        let mut lines = vec![];
+        diff_json("", &serde_json::Value::Null, &json!({ "a": 1 }), &mut lines);
+        assert_eq!(lines, vec!["+ a: 1"]);
+    }
+}
@@ -49,6 +49,20 @@ pub struct InitTool {
     #[clap(long)]
     pub skip_faucet: bool,
 
+    /// Use an account on a Ledger device instead of a private key
+    ///
+    /// The private key never leaves the device, transactions are signed on it
+    #[clap(long, conflicts_with = "private_key_input")]
+    pub ledger: bool,
+
+    /// Index of the account on the Ledger device, defaults to 0
+    #[clap(long, requires = "ledger", conflicts_with = "derivation_path")]
+    pub derivation_index: Option<u32>,
+
+    /// Derivation path of the account on the Ledger device, e.g. `m/44'/637'/0'/0'/0'`
+    #[clap(long, requires = "ledger")]
+    pub derivation_path: Option<String>,
+
     #[clap(flatten)]
     pub rng_args: RngArgs,
     #[clap(flatten)]
@@ -131,33 +145,26 @@ impl CliCommand<()> for InitTool {
             Network::Custom => self.custom_network(&mut profile_config)?,
         }
 
-        // Private key
-        let private_key = if let Some(private_key) = self
-            .private_key_options
-            .extract_private_key_cli(self.encoding_options.encoding)?
-        {
-            eprintln!("Using command line argument for private key");
-            private_key
+        // Ledger accounts have no private key, only the public key is read from the device
+        let public_key = if self.ledger {
+            let derivation_path = self.derivation_path.clone().unwrap_or_else(|| {
+                aptos_ledger::derivation_path(self.derivation_index.unwrap_or_default())
+            });
+            eprintln!(
+                "Reading the public key of the Ledger account at {}, make sure the Aptos app is open on the device",
+                derivation_path
+            );
+            let public_key = aptos_ledger::get_public_key(&derivation_path, false)?;
+            profile_config.private_key = None;
+            profile_config.derivation_path = Some(derivation_path);
+            public_key
         } else {
-            eprintln!("Enter your private key as a hex literal (0x...) [Current: {} | No input: Generate new key (or keep one if present)]", profile_config.private_key.as_ref().map(|_| "Redacted").unwrap_or("None"));
-            let input = read_line("Private key")?;
-            let input = input.trim();
-            if input.is_empty() {
-                if let Some(private_key) = profile_config.private_key {
-                    eprintln!("No key given, keeping existing key...");
-                    private_key
-                } else {
-                    eprintln!("No key given, generating key...");
-                    self.rng_args
-                        .key_generator()?
-                        .generate_ed25519_private_key()
-                }
-            } else {
-                Ed25519PrivateKey::from_encoded_string(input)
-                    .map_err(|err| CliError::UnableToParse("Ed25519PrivateKey", err.to_string()))?
-            }
+            let private_key = self.private_key(&mut profile_config)?;
+            let public_key = private_key.public_key();
+            profile_config.private_key = Some(private_key);
+            profile_config.derivation_path = None;
+            public_key
         };
-        let public_key = private_key.public_key();
 
         let client = aptos_rest_client::Client::new(
             Url::parse(
@@ -174,7 +181,6 @@ impl CliCommand<()> for InitTool {
         let derived_address = account_address_from_public_key(&public_key);
         let address = lookup_address(&client, derived_address, false).await?;
 
-        profile_config.private_key = Some(private_key);
         profile_config.public_key = Some(public_key);
         profile_config.account = Some(address);
 
@@ -259,6 +265,36 @@ impl CliCommand<()> for InitTool {
 }
 
 impl InitTool {
+    /// Private key from the command line, or prompted for, generating one if none is given
+    fn private_key(&self, profile_config: &mut ProfileConfig) -> CliTypedResult<Ed25519PrivateKey> {
+        let private_key = if let Some(private_key) = self
+            .private_key_options
+            .extract_private_key_cli(self.encoding_options.encoding)?
+        {
+            eprintln!("Using command line argument for private key");
+            private_key
+        } else {
+            eprintln!("Enter your private key as a hex literal (0x...) [Current: {} | No input: Generate new key (or keep one if present)]", profile_config.private_key.as_ref().map(|_| "Redacted").unwrap_or("None"));
+            let input = read_line("Private key")?;
+            let input = input.trim();
+            if input.is_empty() {
+                if let Some(private_key) = profile_config.private_key.take() {
+                    eprintln!("No key given, keeping existing key...");
+                    private_key
+                } else {
+                    eprintln!("No key given, generating key...");
+                    self.rng_args
+                        .key_generator()?
+                        .generate_ed25519_private_key()
+                }
+            } else {
+                Ed25519PrivateKey::from_encoded_string(input)
+                    .map_err(|err| CliError::UnableToParse("Ed25519PrivateKey", err.to_string()))?
+            }
+        };
+        Ok(private_key)
+    }
+
     /// Custom network created, which requires a REST URL
     fn custom_network(&self, profile_config: &mut ProfileConfig) -> CliTypedResult<()> {
         // Rest Endpoint
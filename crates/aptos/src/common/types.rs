@@ -19,10 +19,11 @@ use crate::{
 use anyhow::Context;
 use aptos_crypto::{
     ed25519::{Ed25519PrivateKey, Ed25519PublicKey, Ed25519Signature},
-    x25519, PrivateKey, ValidCryptoMaterial, ValidCryptoMaterialStringExt,
+    signing_message, x25519, PrivateKey, Signature, SigningKey, ValidCryptoMaterial,
+    ValidCryptoMaterialStringExt,
 };
 use aptos_debugger::AptosDebugger;
-use aptos_gas_profiling::FrameName;
+use aptos_gas_profiling::{FrameName, TransactionGasLog};
 use aptos_global_constants::adjust_gas_headroom;
 use aptos_keygen::KeyGen;
 use aptos_logger::Level;
@@ -31,18 +32,20 @@ use aptos_rest_client::{
     error::RestError,
     AptosBaseUrl, Client, Transaction,
 };
-use aptos_sdk::{transaction_builder::TransactionFactory, types::LocalAccount};
+use aptos_sdk::transaction_builder::TransactionFactory;
 use aptos_types::{
     chain_id::ChainId,
     transaction::{
-        authenticator::AuthenticationKey, EntryFunction, MultisigTransactionPayload, Script,
-        SignedTransaction, TransactionArgument, TransactionPayload, TransactionStatus,
+        authenticator::AuthenticationKey, EntryFunction, MultisigTransactionPayload,
+        RawTransaction, Script, SignedTransaction, TransactionArgument, TransactionPayload,
+        TransactionStatus,
     },
 };
 use async_trait::async_trait;
 use clap::{Parser, ValueEnum};
 use hex::FromHexError;
 use move_core_types::{account_address::AccountAddress, language_storage::TypeTag};
+use once_cell::sync::OnceCell;
 use serde::{Deserialize, Serialize};
 #[cfg(unix)]
 use std::os::unix::fs::OpenOptionsExt;
@@ -89,6 +92,8 @@ pub enum CliError {
     ConfigNotFoundError(String),
     #[error("Error accessing '{0}': {1}")]
     IO(String, #[source] std::io::Error),
+    #[error("Ledger error: {0}")]
+    LedgerError(String),
     #[error("Move compilation failed: {0}")]
     MoveCompilationError(String),
     #[error("Move unit tests failed")]
@@ -117,6 +122,7 @@ impl CliError {
             CliError::ConfigLoadError(_, _) => "ConfigLoadError",
             CliError::ConfigNotFoundError(_) => "ConfigNotFoundError",
             CliError::IO(_, _) => "IO",
+            CliError::LedgerError(_) => "LedgerError",
             CliError::MoveCompilationError(_) => "MoveCompilationError",
             CliError::MoveTestError => "MoveTestError",
             CliError::MoveProverError(_) => "MoveProverError",
@@ -183,6 +189,12 @@ impl From<anyhow::Error> for CliError {
     }
 }
 
+impl From<aptos_ledger::AptosLedgerError> for CliError {
+    fn from(e: aptos_ledger::AptosLedgerError) -> Self {
+        CliError::LedgerError(e.to_string())
+    }
+}
+
 impl From<bcs::Error> for CliError {
     fn from(e: bcs::Error) -> Self {
         CliError::UnexpectedError(e.to_string())
@@ -215,6 +227,9 @@ pub struct ProfileConfig {
     /// Account for commands
     #[serde(skip_serializing_if = "Option::is_none")]
     pub account: Option<AccountAddress>,
+    /// Derivation path of the key on a Ledger device, used to sign instead of a private key
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub derivation_path: Option<String>,
     /// URL for the Aptos rest endpoint
     #[serde(skip_serializing_if = "Option::is_none")]
     pub rest_url: Option<String>,
@@ -232,6 +247,8 @@ pub struct ProfileSummary {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub account: Option<AccountAddress>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub derivation_path: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub rest_url: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub faucet_url: Option<String>,
@@ -243,6 +260,7 @@ impl From<&ProfileConfig> for ProfileSummary {
             has_private_key: config.private_key.is_some(),
             public_key: config.public_key.clone(),
             account: config.account,
+            derivation_path: config.derivation_path.clone(),
             rest_url: config.rest_url.clone(),
             faucet_url: config.faucet_url.clone(),
         }
@@ -1355,9 +1373,84 @@ pub struct TransactionOptions {
     pub(crate) prompt_options: PromptOptions,
 
     /// If this option is set, simulate the transaction locally using the debugger and generate
-    /// flamegraphs that reflect the gas usage.
+    /// flamegraphs and an HTML report that reflect the gas usage, per instruction and per
+    /// function call.
     #[clap(long)]
     pub(crate) profile_gas: bool,
+
+    /// If this option is set along with `--profile-gas`, also save a JSON trace of the
+    /// simulated execution, listing the functions called along with the gas they used, the
+    /// resources they read and the writes the transaction made.
+    #[clap(long, requires = "profile_gas")]
+    pub(crate) trace_execution: bool,
+
+    #[clap(skip)]
+    ledger_account: OnceCell<Option<LedgerAccount>>,
+}
+
+/// An account whose key is on a Ledger device, which signs on behalf of the profile
+#[derive(Clone, Debug)]
+struct LedgerAccount {
+    derivation_path: String,
+    public_key: Ed25519PublicKey,
+    address: AccountAddress,
+}
+
+impl LedgerAccount {
+    /// Returns the Ledger account of the profile, if it has a derivation path.  The sender
+    /// account given on the command line overrides the account of the profile.
+    fn from_profile(
+        profile: ProfileConfig,
+        profile_name: &str,
+        sender_account: Option<AccountAddress>,
+    ) -> CliTypedResult<Option<Self>> {
+        let derivation_path = match profile.derivation_path {
+            Some(derivation_path) => derivation_path,
+            None => return Ok(None),
+        };
+        let public_key = profile.public_key.ok_or_else(|| {
+            CliError::ConfigLoadError(
+                profile_name.to_string(),
+                format!(
+                    "missing the public key of the Ledger account at {}",
+                    derivation_path
+                ),
+            )
+        })?;
+        let address = sender_account
+            .or(profile.account)
+            .unwrap_or_else(|| account_address_from_public_key(&public_key));
+        Ok(Some(Self {
+            derivation_path,
+            public_key,
+            address,
+        }))
+    }
+
+    /// Signs the message with the Ledger device
+    fn sign(&self, message: Vec<u8>) -> CliTypedResult<Ed25519Signature> {
+        let signature_bytes = aptos_ledger::sign_txn(&self.derivation_path, message.clone())?;
+        self.check_signature(&message, &signature_bytes)
+    }
+
+    /// Parses a signature made by the Ledger device, and checks it against the public key of
+    /// the profile, to catch a device that holds a different key before submitting
+    fn check_signature(
+        &self,
+        message: &[u8],
+        signature_bytes: &[u8],
+    ) -> CliTypedResult<Ed25519Signature> {
+        let signature = Ed25519Signature::try_from(signature_bytes)?;
+        signature
+            .verify_arbitrary_msg(message, &self.public_key)
+            .map_err(|_| {
+                CliError::LedgerError(format!(
+                    "Signature doesn't match the public key of the profile, is the Ledger account at {} the one of the profile?",
+                    self.derivation_path
+                ))
+            })?;
+        Ok(signature)
+    }
 }
 
 impl TransactionOptions {
@@ -1377,7 +1470,78 @@ impl TransactionOptions {
     }
 
     pub fn sender_address(&self) -> CliTypedResult<AccountAddress> {
-        Ok(self.get_key_and_address()?.1)
+        Ok(self.get_public_key_and_address()?.1)
+    }
+
+    /// Retrieves the public key and the associated address, without requiring the private key
+    /// when the profile signs with a Ledger device
+    pub fn get_public_key_and_address(&self) -> CliTypedResult<(Ed25519PublicKey, AccountAddress)> {
+        if let Some(account) = self.ledger_account()? {
+            return Ok((account.public_key.clone(), account.address));
+        }
+        let (private_key, address) = self.get_key_and_address()?;
+        Ok((private_key.public_key(), address))
+    }
+
+    /// Retrieves the Ledger account of the profile, if it has one.  A private key given on the
+    /// command line takes precedence.  The profile is only loaded once.
+    fn ledger_account(&self) -> CliTypedResult<Option<&LedgerAccount>> {
+        let account = self.ledger_account.get_or_try_init(|| {
+            if self
+                .private_key_options
+                .extract_private_key_cli(self.encoding_options.encoding)?
+                .is_some()
+            {
+                return Ok(None);
+            }
+
+            match CliConfig::load_profile(
+                self.profile_options.profile_name(),
+                ConfigSearchMode::CurrentDirAndParents,
+            )? {
+                Some(profile) => LedgerAccount::from_profile(
+                    profile,
+                    self.profile_options
+                        .profile_name()
+                        .unwrap_or(DEFAULT_PROFILE),
+                    self.sender_account,
+                ),
+                None => Ok(None),
+            }
+        })?;
+        Ok(account.as_ref())
+    }
+
+    /// Signs the transaction with the Ledger device if the profile has a Ledger account, or
+    /// with the private key otherwise
+    fn sign_transaction(&self, raw_txn: RawTransaction) -> CliTypedResult<SignedTransaction> {
+        if let Some(account) = self.ledger_account()? {
+            eprintln!("Please confirm the transaction on your Ledger device");
+            let signature = account.sign(signing_message(&raw_txn)?)?;
+            Ok(SignedTransaction::new(
+                raw_txn,
+                account.public_key.clone(),
+                signature,
+            ))
+        } else {
+            let (private_key, _) = self.get_key_and_address()?;
+            Ok(raw_txn
+                .sign(&private_key, private_key.public_key())
+                .map_err(|err| CliError::UnexpectedError(err.to_string()))?
+                .into_inner())
+        }
+    }
+
+    /// Signs an arbitrary message, e.g. a proof of the key rotation, with the Ledger device if
+    /// the profile has a Ledger account, or with the private key otherwise
+    pub fn sign_arbitrary_message(&self, message: &[u8]) -> CliTypedResult<Ed25519Signature> {
+        if let Some(account) = self.ledger_account()? {
+            eprintln!("Please confirm the message on your Ledger device");
+            account.sign(message.to_vec())
+        } else {
+            let (private_key, _) = self.get_key_and_address()?;
+            Ok(private_key.sign_arbitrary_message(message))
+        }
     }
 
     /// Gets the auth key by account address. We need to fetch the auth key from Rest API rather than creating an
@@ -1406,7 +1570,7 @@ impl TransactionOptions {
         payload: TransactionPayload,
     ) -> CliTypedResult<Transaction> {
         let client = self.rest_client()?;
-        let (sender_key, sender_address) = self.get_key_and_address()?;
+        let (sender_public_key, sender_address) = self.get_public_key_and_address()?;
 
         // Ask to confirm price if the gas unit price is estimated above the lowest value when
         // it is automatically estimated
@@ -1462,7 +1626,7 @@ impl TransactionOptions {
 
             let signed_transaction = SignedTransaction::new(
                 unsigned_transaction,
-                sender_key.public_key(),
+                sender_public_key,
                 Ed25519Signature::try_from([0u8; 64].as_ref()).unwrap(),
             );
 
@@ -1501,9 +1665,13 @@ impl TransactionOptions {
             .with_gas_unit_price(gas_unit_price)
             .with_max_gas_amount(max_gas)
             .with_transaction_expiration_time(self.gas_options.expiration_secs);
-        let sender_account = &mut LocalAccount::new(sender_address, sender_key, sequence_number);
-        let transaction =
-            sender_account.sign_with_transaction_builder(transaction_factory.payload(payload));
+        let transaction = self.sign_transaction(
+            transaction_factory
+                .payload(payload)
+                .sender(sender_address)
+                .sequence_number(sequence_number)
+                .build(),
+        )?;
         let response = client
             .submit_and_wait(&transaction)
             .await
@@ -1528,7 +1696,7 @@ impl TransactionOptions {
         const DEFAULT_GAS_UNIT_PRICE: u64 = 100;
         const DEFAULT_MAX_GAS: u64 = 2_000_000;
 
-        let (sender_key, sender_address) = self.get_key_and_address()?;
+        let sender_address = self.sender_address()?;
         let gas_unit_price = self
             .gas_options
             .gas_unit_price
@@ -1557,9 +1725,13 @@ impl TransactionOptions {
             .with_gas_unit_price(gas_unit_price)
             .with_max_gas_amount(max_gas)
             .with_transaction_expiration_time(self.gas_options.expiration_secs);
-        let sender_account = &mut LocalAccount::new(sender_address, sender_key, sequence_number);
-        let transaction =
-            sender_account.sign_with_transaction_builder(transaction_factory.payload(payload));
+        let transaction = self.sign_transaction(
+            transaction_factory
+                .payload(payload)
+                .sender(sender_address)
+                .sequence_number(sequence_number)
+                .build(),
+        )?;
         let hash = transaction.clone().committed_hash();
 
         // Execute the transaction using the debugger
@@ -1588,71 +1760,23 @@ impl TransactionOptions {
         };
         let raw_file_name = format!("txn-{}-{}", hash, human_readable_name);
 
-        // Create the directory if it does not exist yet.
-        let dir: &Path = Path::new("gas-profiling");
-
-        macro_rules! create_dir {
-            () => {
-                if let Err(err) = std::fs::create_dir(dir) {
-                    if err.kind() != std::io::ErrorKind::AlreadyExists {
-                        return Err(CliError::UnexpectedError(format!(
-                            "failed to create directory {}",
-                            dir.display()
-                        )));
-                    }
-                }
-            };
-        }
-
-        // Generate the execution & IO flamegraph.
+        let dir = Path::new(GAS_PROFILING_DIR);
         println!();
-        match gas_log
-            .exec_io
-            .to_flamegraph(format!("Transaction {} -- Execution & IO", hash))?
-        {
-            Some(graph_bytes) => {
-                create_dir!();
-                let graph_file_path = Path::join(dir, format!("{}.exec_io.svg", raw_file_name));
-                std::fs::write(&graph_file_path, graph_bytes).map_err(|err| {
-                    CliError::UnexpectedError(format!(
-                        "Failed to write flamegraph to file {} : {:?}",
-                        graph_file_path.display(),
-                        err
-                    ))
-                })?;
-                println!(
-                    "Execution & IO Gas flamegraph saved to {}",
-                    graph_file_path.display()
-                );
-            },
-            None => {
-                println!("Skipped generating execution & IO flamegraph");
-            },
-        }
-
-        // Generate the storage fee flamegraph.
-        match gas_log
-            .storage
-            .to_flamegraph(format!("Transaction {} -- Storage Fee", hash))?
-        {
-            Some(graph_bytes) => {
-                create_dir!();
-                let graph_file_path = Path::join(dir, format!("{}.storage.svg", raw_file_name));
-                std::fs::write(&graph_file_path, graph_bytes).map_err(|err| {
-                    CliError::UnexpectedError(format!(
-                        "Failed to write flamegraph to file {} : {:?}",
-                        graph_file_path.display(),
-                        err
-                    ))
-                })?;
-                println!(
-                    "Storage fee flamegraph saved to {}",
-                    graph_file_path.display()
-                );
-            },
-            None => {
-                println!("Skipped generating storage fee flamegraph");
-            },
+        save_gas_profile(&gas_log, &format!("Transaction {}", hash), &raw_file_name)?;
+
+        // Save the execution trace if requested.
+        if self.trace_execution {
+            let trace_file_path = Path::join(dir, format!("{}.trace.json", raw_file_name));
+            let trace = serde_json::to_vec_pretty(&gas_log.to_execution_trace())
+                .map_err(|err| CliError::UnexpectedError(err.to_string()))?;
+            std::fs::write(&trace_file_path, trace).map_err(|err| {
+                CliError::UnexpectedError(format!(
+                    "Failed to write execution trace to file {} : {:?}",
+                    trace_file_path.display(),
+                    err
+                ))
+            })?;
+            println!("Execution trace saved to {}", trace_file_path.display());
         }
 
         println!();
@@ -1694,6 +1818,80 @@ impl TransactionOptions {
     }
 }
 
+/// Directory the gas profiles are saved to, relative to the current directory.
+pub(crate) const GAS_PROFILING_DIR: &str = "gas-profiling";
+
+/// Saves the execution & IO and storage fee flamegraphs of a gas log, along with an HTML
+/// report including both, to `gas-profiling/<raw_file_name>.{exec_io.svg,storage.svg,html}`.
+pub(crate) fn save_gas_profile(
+    gas_log: &TransactionGasLog,
+    title: &str,
+    raw_file_name: &str,
+) -> CliTypedResult<()> {
+    let dir = Path::new(GAS_PROFILING_DIR);
+    create_dir_if_not_exist(dir)?;
+
+    let write_file = |file_path: &Path, bytes: &[u8], what: &str| {
+        std::fs::write(file_path, bytes).map_err(|err| {
+            CliError::UnexpectedError(format!(
+                "Failed to write {} to file {} : {:?}",
+                what,
+                file_path.display(),
+                err
+            ))
+        })
+    };
+
+    // Generate the execution & IO flamegraph.
+    let exec_io_graph = gas_log
+        .exec_io
+        .to_flamegraph(format!("{} -- Execution & IO", title))?;
+    match &exec_io_graph {
+        Some(graph_bytes) => {
+            let graph_file_path = dir.join(format!("{}.exec_io.svg", raw_file_name));
+            write_file(&graph_file_path, graph_bytes, "flamegraph")?;
+            println!(
+                "Execution & IO Gas flamegraph saved to {}",
+                graph_file_path.display()
+            );
+        },
+        None => {
+            println!("Skipped generating execution & IO flamegraph");
+        },
+    }
+
+    // Generate the storage fee flamegraph.
+    let storage_graph = gas_log
+        .storage
+        .to_flamegraph(format!("{} -- Storage Fee", title))?;
+    match &storage_graph {
+        Some(graph_bytes) => {
+            let graph_file_path = dir.join(format!("{}.storage.svg", raw_file_name));
+            write_file(&graph_file_path, graph_bytes, "flamegraph")?;
+            println!(
+                "Storage fee flamegraph saved to {}",
+                graph_file_path.display()
+            );
+        },
+        None => {
+            println!("Skipped generating storage fee flamegraph");
+        },
+    }
+
+    // Generate the HTML report, breaking the gas down per instruction and per function call,
+    // and embedding the flamegraphs rendered above.
+    let report_file_path = dir.join(format!("{}.html", raw_file_name));
+    let report = gas_log.to_html_report(
+        title.to_string(),
+        exec_io_graph.as_deref(),
+        storage_graph.as_deref(),
+    )?;
+    write_file(&report_file_path, report.as_bytes(), "gas report")?;
+    println!("Gas report saved to {}", report_file_path.display());
+
+    Ok(())
+}
+
 #[derive(Parser)]
 pub struct OptionalPoolAddressArgs {
     /// Address of the Staking pool
@@ -2015,3 +2213,118 @@ impl TryInto<ScriptFunctionArguments> for ScriptFunctionArgumentsJSON {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ledger_profile(public_key: Option<Ed25519PublicKey>) -> ProfileConfig {
+        ProfileConfig {
+            public_key,
+            derivation_path: Some(aptos_ledger::derivation_path(0)),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_ledger_account_from_profile() {
+        let public_key = KeyGen::from_os_rng()
+            .generate_ed25519_private_key()
+            .public_key();
+        let derived_address = account_address_from_public_key(&public_key);
+
+        // Profiles without a derivation path sign with their private key.
+        let profile = ProfileConfig {
+            public_key: Some(public_key.clone()),
+            ..Default::default()
+        };
+        assert!(LedgerAccount::from_profile(profile, DEFAULT_PROFILE, None)
+            .unwrap()
+            .is_none());
+
+        assert!(matches!(
+            LedgerAccount::from_profile(ledger_profile(None), DEFAULT_PROFILE, None),
+            Err(CliError::ConfigLoadError(..))
+        ));
+
+        let account =
+            LedgerAccount::from_profile(ledger_profile(Some(public_key.clone())), "ledger", None)
+                .unwrap()
+                .unwrap();
+        assert_eq!(account.derivation_path, "m/44'/637'/0'/0'/0'");
+        assert_eq!(account.public_key, public_key);
+        assert_eq!(account.address, derived_address);
+
+        // A rotated account keeps its address, which can be overridden on the command line.
+        let profile = ProfileConfig {
+            account: Some(AccountAddress::ONE),
+            ..ledger_profile(Some(public_key.clone()))
+        };
+        let account = LedgerAccount::from_profile(profile, "ledger", None)
+            .unwrap()
+            .unwrap();
+        assert_eq!(account.address, AccountAddress::ONE);
+        let account = LedgerAccount::from_profile(
+            ledger_profile(Some(public_key)),
+            "ledger",
+            Some(AccountAddress::TWO),
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(account.address, AccountAddress::TWO);
+    }
+
+    #[test]
+    fn test_ledger_account_check_signature() {
+        let mut keygen = KeyGen::from_os_rng();
+        let private_key = keygen.generate_ed25519_private_key();
+        let account = LedgerAccount::from_profile(
+            ledger_profile(Some(private_key.public_key())),
+            "ledger",
+            None,
+        )
+        .unwrap()
+        .unwrap();
+        let message = b"rotation proof";
+
+        let signature = private_key.sign_arbitrary_message(message);
+        assert_eq!(
+            account
+                .check_signature(message, &signature.to_bytes())
+                .unwrap(),
+            signature
+        );
+
+        // A device holding another key than the profile is caught before submitting.
+        let other_signature = keygen
+            .generate_ed25519_private_key()
+            .sign_arbitrary_message(message);
+        assert!(matches!(
+            account.check_signature(message, &other_signature.to_bytes()),
+            Err(CliError::LedgerError(_))
+        ));
+        assert!(account.check_signature(message, &[0u8; 10]).is_err());
+    }
+
+    #[test]
+    fn test_private_key_takes_precedence_over_ledger() {
+        let private_key = KeyGen::from_os_rng().generate_ed25519_private_key();
+        let txn_options = TransactionOptions {
+            private_key_options: PrivateKeyInputOptions::from_private_key(&private_key).unwrap(),
+            sender_account: Some(AccountAddress::ONE),
+            ..Default::default()
+        };
+
+        // The profile isn't even loaded, and the key given on the command line signs.
+        assert!(txn_options.ledger_account().unwrap().is_none());
+        assert_eq!(
+            txn_options.get_public_key_and_address().unwrap(),
+            (private_key.public_key(), AccountAddress::ONE)
+        );
+        let message = b"rotation proof";
+        assert_eq!(
+            txn_options.sign_arbitrary_message(message).unwrap(),
+            private_key.sign_arbitrary_message(message)
+        );
+    }
+}
@@ -14,8 +14,8 @@ use crate::{
     account::derive_resource_account::ResourceAccountSeed,
     common::{
         types::{
-            load_account_arg, ArgWithTypeJSON, CliConfig, CliError, CliTypedResult,
-            ConfigSearchMode, EntryFunctionArguments, EntryFunctionArgumentsJSON,
+            load_account_arg, save_gas_profile, ArgWithTypeJSON, CliConfig, CliError,
+            CliTypedResult, ConfigSearchMode, EntryFunctionArguments, EntryFunctionArgumentsJSON,
             MoveManifestAccountWrapper, MovePackageDir, ProfileOptions, PromptOptions, RestOptions,
             SaveFile, ScriptFunctionArguments, TransactionOptions, TransactionSummary,
         },
@@ -37,7 +37,12 @@ use aptos_framework::{
     build_model, docgen::DocgenOptions, extended_checks, natives::code::UpgradePolicy,
     prover::ProverOptions, BuildOptions, BuiltPackage,
 };
-use aptos_gas_schedule::{MiscGasParameters, NativeGasParameters};
+use aptos_gas_meter::{StandardGasAlgebra, StandardGasMeter};
+use aptos_gas_profiling::{GasProfiler, TransactionGasLog};
+use aptos_gas_schedule::{
+    AptosGasParameters, InitialGasSchedule, MiscGasParameters, NativeGasParameters,
+    LATEST_GAS_FEATURE_VERSION,
+};
 use aptos_rest_client::aptos_api_types::{
     EntryFunctionId, HexEncodedBytes, IdentifierWrapper, MoveModuleId,
 };
@@ -46,6 +51,7 @@ use aptos_types::{
     account_address::{create_resource_address, AccountAddress},
     transaction::{TransactionArgument, TransactionPayload},
 };
+use aptos_vm_types::storage::StorageGasParameters;
 use async_trait::async_trait;
 use clap::{Parser, Subcommand, ValueEnum};
 use codespan_reporting::{
@@ -57,7 +63,7 @@ use move_cli::{self, base::test::UnitTestResult};
 use move_command_line_common::env::MOVE_HOME;
 use move_core_types::{identifier::Identifier, language_storage::ModuleId, u256::U256};
 use move_package::{source_package::layout::SourcePackageLayout, BuildConfig};
-use move_unit_test::UnitTestingConfig;
+use move_unit_test::{test_runner::set_execute_test_hook, UnitTestingConfig};
 pub use package_hooks::*;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
@@ -66,6 +72,7 @@ use std::{
     fmt::{Display, Formatter},
     path::{Path, PathBuf},
     str::FromStr,
+    sync::{Arc, Mutex},
 };
 pub use stored_package::*;
 use tokio::task;
@@ -440,6 +447,53 @@ pub struct TestPackage {
     /// Dump storage state on failure.
     #[clap(long = "dump")]
     pub dump_state: bool,
+
+    /// Profile the gas used by each test, and save its flamegraphs and an HTML report under
+    /// `gas-profiling/`
+    ///
+    /// The tests are then metered with the default gas schedule instead of counting
+    /// instructions, and bounded by the maximum gas of a transaction instead of `--instructions`.
+    #[clap(long)]
+    pub profile_gas: bool,
+}
+
+/// Makes the unit tests run with the gas profiler over the default gas schedule, collecting the
+/// gas log of each test along with the name of its file.
+fn set_gas_profiling_hook(gas_logs: Arc<Mutex<Vec<(String, TransactionGasLog)>>>) {
+    set_execute_test_hook(Box::new(move |session, module_id, function_name, args| {
+        let gas_params = AptosGasParameters::initial();
+        let balance = gas_params.vm.txn.maximum_number_of_gas_units;
+        let mut gas_meter = GasProfiler::new_function(
+            StandardGasMeter::new(StandardGasAlgebra::new(
+                LATEST_GAS_FEATURE_VERSION,
+                gas_params.vm,
+                StorageGasParameters::free_and_unlimited(),
+                balance,
+            )),
+            module_id.clone(),
+            function_name.to_owned(),
+            vec![],
+        );
+        let result = session.execute_function_bypass_visibility(
+            module_id,
+            function_name,
+            vec![],
+            args,
+            &mut gas_meter,
+        );
+
+        let gas_log = gas_meter.finish();
+        let gas_used =
+            u64::from(gas_log.exec_io.total) / u64::from(gas_log.exec_io.gas_scaling_factor);
+        let raw_file_name = format!(
+            "test-{}-{}-{}",
+            module_id.address().short_str_lossless(),
+            module_id.name(),
+            function_name
+        );
+        gas_logs.lock().unwrap().push((raw_file_name, gas_log));
+        (result, gas_used)
+    }));
 }
 
 #[async_trait]
@@ -476,6 +530,19 @@ impl CliCommand<&'static str> for TestPackage {
                 ));
             }
         }
+        let gas_logs = Arc::new(Mutex::new(vec![]));
+        let natives = if self.profile_gas {
+            set_gas_profiling_hook(gas_logs.clone());
+            let gas_params = AptosGasParameters::initial();
+            aptos_debug_natives::aptos_debug_natives(gas_params.natives, gas_params.vm.misc)
+        } else {
+            // TODO(Gas): we may want to switch to non-zero costs in the future
+            aptos_debug_natives::aptos_debug_natives(
+                NativeGasParameters::zeros(),
+                MiscGasParameters::zeros(),
+            )
+        };
+
         let path = self.move_options.get_package_path()?;
         let result = move_cli::base::test::run_move_unit_tests(
             path.as_path(),
@@ -487,17 +554,23 @@ impl CliCommand<&'static str> for TestPackage {
                 ignore_compile_warnings: self.ignore_compile_warnings,
                 ..UnitTestingConfig::default_with_bound(None)
             },
-            // TODO(Gas): we may want to switch to non-zero costs in the future
-            aptos_debug_natives::aptos_debug_natives(
-                NativeGasParameters::zeros(),
-                MiscGasParameters::zeros(),
-            ),
+            natives,
             None,
             self.compute_coverage,
             &mut std::io::stdout(),
         )
         .map_err(|err| CliError::UnexpectedError(format!("Failed to run tests: {:#}", err)))?;
 
+        // Save the gas profiles if --profile-gas is set, in a stable order as tests run in parallel
+        if self.profile_gas {
+            let mut gas_logs = std::mem::take(&mut *gas_logs.lock().unwrap());
+            gas_logs.sort_by(|(name1, _), (name2, _)| name1.cmp(name2));
+            println!();
+            for (raw_file_name, gas_log) in gas_logs {
+                save_gas_profile(&gas_log, &format!("Test {}", raw_file_name), &raw_file_name)?;
+            }
+        }
+
         // Print coverage summary if --coverage is set
         if self.compute_coverage {
             config.test_mode = false;
@@ -0,0 +1,95 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::common::types::{CliError, CliTypedResult};
+use anyhow::Result;
+use diesel::{Connection, PgConnection};
+use reqwest::Url;
+use std::{
+    fmt::{Display, Formatter},
+    net::SocketAddr,
+    time::Duration,
+};
+use tokio::{net::TcpStream, time::Instant};
+
+const WAIT_INTERVAL_MS: u64 = 100;
+
+/// Checks whether one of the services making up the local testnet is up. We
+/// use these to start each service only once the ones it depends on are ready.
+#[derive(Clone, Debug)]
+pub enum HealthChecker {
+    /// Check that the node API returns the ledger info.
+    NodeApi(Url),
+    /// Check that an HTTP endpoint returns a 2xx, e.g. the faucet.
+    Http(Url, &'static str),
+    /// Check that something accepts connections on the address, e.g. a gRPC server.
+    Tcp(SocketAddr, &'static str),
+    /// Check that the Redis instance at the address responds to a PING.
+    Redis(String),
+    /// Check that we can connect to Postgres with the connection string.
+    Postgres(String),
+}
+
+impl HealthChecker {
+    pub async fn check(&self) -> Result<()> {
+        match self {
+            HealthChecker::NodeApi(url) => {
+                aptos_rest_client::Client::new(url.clone())
+                    .get_index()
+                    .await?;
+            },
+            HealthChecker::Http(url, _) => {
+                reqwest::get(url.clone()).await?.error_for_status()?;
+            },
+            HealthChecker::Tcp(address, _) => {
+                TcpStream::connect(address).await?;
+            },
+            HealthChecker::Redis(address) => {
+                let client = redis::Client::open(format!("redis://{}", address))?;
+                let mut conn = client.get_async_connection().await?;
+                redis::cmd("PING")
+                    .query_async::<_, String>(&mut conn)
+                    .await?;
+            },
+            HealthChecker::Postgres(connection_string) => {
+                let connection_string = connection_string.clone();
+                tokio::task::spawn_blocking(move || PgConnection::establish(&connection_string))
+                    .await??;
+            },
+        }
+        Ok(())
+    }
+
+    /// Wait until the service is healthy, failing if it isn't after `max_wait`.
+    pub async fn wait(&self, max_wait: Duration) -> CliTypedResult<()> {
+        let start = Instant::now();
+        loop {
+            let err = match self.check().await {
+                Ok(()) => return Ok(()),
+                Err(err) => err,
+            };
+            if start.elapsed() >= max_wait {
+                return Err(CliError::UnexpectedError(format!(
+                    "{} did not become healthy within {} seconds: {:#}",
+                    self,
+                    max_wait.as_secs(),
+                    err
+                )));
+            }
+            tokio::time::sleep(Duration::from_millis(WAIT_INTERVAL_MS)).await;
+        }
+    }
+}
+
+impl Display for HealthChecker {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HealthChecker::NodeApi(url) => write!(f, "Node API at {}", url),
+            HealthChecker::Http(url, name) => write!(f, "{} at {}", name, url),
+            HealthChecker::Tcp(address, name) => write!(f, "{} at {}", name, address),
+            HealthChecker::Redis(address) => write!(f, "Redis at {}", address),
+            // Don't print the connection string, it may contain a password.
+            HealthChecker::Postgres(_) => write!(f, "Postgres"),
+        }
+    }
+}
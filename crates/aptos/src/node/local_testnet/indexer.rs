@@ -0,0 +1,131 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::common::types::{CliError, CliTypedResult};
+use aptos_indexer_grpc_cache_worker::IndexerGrpcCacheWorkerConfig;
+use aptos_indexer_grpc_data_service::{IndexerGrpcDataServiceConfig, NonTlsConfig};
+use aptos_indexer_grpc_file_store::IndexerGrpcFileStoreWorkerConfig;
+use aptos_indexer_grpc_parser::{
+    processors::{coin_processor, default_processor, stake_processor, token_processor},
+    worker::MIGRATIONS,
+    IndexerGrpcProcessorConfig,
+};
+use aptos_indexer_grpc_server_framework::RunnableConfig;
+use aptos_indexer_grpc_utils::config::{
+    CompressionConfig, IndexerGrpcFileStoreConfig, LocalFileStore,
+};
+use diesel::{Connection, PgConnection};
+use diesel_migrations::MigrationHarness;
+use std::{future::Future, net::SocketAddr, path::PathBuf, pin::Pin};
+
+/// Auth token the local processors use to connect to the local data service.
+const LOCAL_AUTH_TOKEN: &str = "local_testnet";
+
+/// A service of the local testnet. These should never complete.
+pub type ServiceFuture = Pin<Box<dyn Future<Output = ()> + Send>>;
+
+/// The processors we run if the user doesn't pick any.
+pub fn all_processor_names() -> Vec<String> {
+    [
+        default_processor::NAME,
+        coin_processor::NAME,
+        stake_processor::NAME,
+        token_processor::NAME,
+    ]
+    .iter()
+    .map(|name| name.to_string())
+    .collect()
+}
+
+/// Spawns the service, returning a future that completes if the service stops.
+pub fn spawn_service<C: RunnableConfig>(name: String, config: C) -> ServiceFuture {
+    let handle = tokio::spawn(async move { config.run().await });
+    Box::pin(async move {
+        eprintln!("{} stopped unexpectedly {:#?}", name, handle.await);
+    })
+}
+
+/// Spawns the indexer gRPC stack: the cache worker reading the transaction
+/// stream of the node into Redis, the file store worker archiving the cache
+/// to disk, and the data service serving both to processors.
+pub fn spawn_indexer_grpc(
+    node_stream_address: SocketAddr,
+    data_service_listen_address: SocketAddr,
+    redis_address: String,
+    file_store_path: PathBuf,
+) -> Vec<ServiceFuture> {
+    let file_store_config = IndexerGrpcFileStoreConfig::LocalFileStore(LocalFileStore {
+        local_file_store_path: file_store_path,
+    });
+    vec![
+        spawn_service(
+            "Indexer gRPC cache worker".to_string(),
+            IndexerGrpcCacheWorkerConfig {
+                fullnode_grpc_address: node_stream_address.to_string(),
+                file_store_config: file_store_config.clone(),
+                redis_main_instance_address: redis_address.clone(),
+                compression_config: CompressionConfig::default(),
+            },
+        ),
+        spawn_service(
+            "Indexer gRPC file store".to_string(),
+            IndexerGrpcFileStoreWorkerConfig {
+                file_store_config: file_store_config.clone(),
+                redis_main_instance_address: redis_address.clone(),
+            },
+        ),
+        spawn_service(
+            "Indexer gRPC data service".to_string(),
+            IndexerGrpcDataServiceConfig {
+                data_service_grpc_tls_config: None,
+                data_service_grpc_non_tls_config: Some(NonTlsConfig {
+                    data_service_grpc_listen_address: data_service_listen_address.to_string(),
+                }),
+                whitelisted_auth_tokens: vec![LOCAL_AUTH_TOKEN.to_string()],
+                file_store_config,
+                redis_read_replica_address: redis_address,
+                compression_config: CompressionConfig::default(),
+            },
+        ),
+    ]
+}
+
+/// Runs the processor migrations once up front, so the processors don't all
+/// try to run them at the same time when they start.
+pub async fn run_processor_migrations(postgres_connection_string: String) -> CliTypedResult<()> {
+    tokio::task::spawn_blocking(move || {
+        let mut conn = PgConnection::establish(&postgres_connection_string)
+            .map_err(|err| CliError::UnexpectedError(format!("{:#}", err)))?;
+        conn.run_pending_migrations(MIGRATIONS)
+            .map(|_| ())
+            .map_err(|err| {
+                CliError::UnexpectedError(format!("Failed to run processor migrations: {:#}", err))
+            })
+    })
+    .await
+    .map_err(|err| CliError::UnexpectedError(err.to_string()))?
+}
+
+/// Spawns a processor reading from the local data service into Postgres.
+pub fn spawn_processor(
+    processor_name: String,
+    data_service_address: SocketAddr,
+    postgres_connection_string: String,
+) -> ServiceFuture {
+    spawn_service(
+        format!("Processor {}", processor_name),
+        IndexerGrpcProcessorConfig {
+            processor_name,
+            postgres_connection_string,
+            indexer_grpc_data_service_address: data_service_address.to_string(),
+            indexer_grpc_http2_ping_interval_in_secs: None,
+            indexer_grpc_http2_ping_timeout_in_secs: None,
+            auth_token: LOCAL_AUTH_TOKEN.to_string(),
+            starting_version: None,
+            ending_version: None,
+            number_concurrent_processing_tasks: None,
+            ans_address: None,
+            nft_points_contract: None,
+        },
+    )
+}
@@ -0,0 +1,365 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+mod health_checker;
+mod indexer;
+
+use self::{health_checker::HealthChecker, indexer::ServiceFuture};
+use crate::{
+    common::{
+        types::{CliCommand, CliError, CliTypedResult, ConfigSearchMode, PromptOptions},
+        utils::prompt_yes_with_override,
+    },
+    config::GlobalConfig,
+};
+use aptos_config::config::NodeConfig;
+use aptos_faucet_core::server::{FunderKeyEnum, RunConfig};
+use async_trait::async_trait;
+use clap::Parser;
+use futures::FutureExt;
+use rand::{rngs::StdRng, SeedableRng};
+use reqwest::Url;
+use std::{
+    net::{Ipv4Addr, SocketAddr},
+    path::{Path, PathBuf},
+    thread,
+    time::Duration,
+};
+use tokio::time::Instant;
+
+const MAX_WAIT_S: u64 = 30;
+const WAIT_INTERVAL_MS: u64 = 100;
+const TESTNET_FOLDER: &str = "testnet";
+
+/// Run local testnet
+///
+/// This local testnet will run it's own Genesis and run as a single node
+/// network locally.  Optionally, a faucet, the indexer gRPC stack and indexer
+/// processors can be added, giving you a full local stack to build against.
+#[derive(Parser)]
+pub struct RunLocalTestnet {
+    /// An overridable config template for the test node
+    ///
+    /// If provided, the config will be used, and any needed configuration for the local testnet
+    /// will override the config's values
+    #[clap(long, value_parser)]
+    config_path: Option<PathBuf>,
+
+    /// The directory to save all files for the node
+    ///
+    /// Defaults to .aptos/testnet
+    #[clap(long, value_parser)]
+    test_dir: Option<PathBuf>,
+
+    /// Path to node configuration file override for local test mode.
+    ///
+    /// If provided, the default node config will be overridden by the config in the given file.
+    /// Cannot be used with --config-path
+    #[clap(long, value_parser, conflicts_with("config_path"))]
+    test_config_override: Option<PathBuf>,
+
+    /// Random seed for key generation in test mode
+    ///
+    /// This allows you to have deterministic keys for testing
+    #[clap(long, value_parser = aptos_node::load_seed)]
+    seed: Option<[u8; 32]>,
+
+    /// Clean the state and start with a new chain at genesis
+    ///
+    /// This will wipe the aptosdb in `test-dir` to remove any incompatible changes, and start
+    /// the chain fresh.  Note, that you will need to publish the module again and distribute funds
+    /// from the faucet accordingly
+    #[clap(long)]
+    force_restart: bool,
+
+    /// Run a faucet alongside the node
+    ///
+    /// Allows you to run a faucet alongside the node to create and fund accounts for testing
+    #[clap(long)]
+    with_faucet: bool,
+
+    /// Port to run the faucet on
+    ///
+    /// When running, you'll be able to use the faucet at `http://localhost:<port>/mint` e.g.
+    /// `http//localhost:8080/mint`
+    #[clap(long, default_value = "8081")]
+    faucet_port: u16,
+
+    /// Disable the delegation of faucet minting to a dedicated account
+    #[clap(long)]
+    do_not_delegate: bool,
+
+    /// Run the indexer gRPC stack alongside the node
+    ///
+    /// The node serves its transaction stream, which the cache worker reads into the Redis at
+    /// `--redis-address`, and the data service serves to processors at
+    /// `localhost:<indexer-grpc-port>`.  You need to run Redis yourself, e.g. with
+    /// `docker run -p 6379:6379 redis`
+    #[clap(long)]
+    with_indexer_grpc: bool,
+
+    /// Port to run the indexer gRPC data service on
+    #[clap(long, default_value = "50051")]
+    indexer_grpc_port: u16,
+
+    /// Port for the node to serve its transaction stream on, for the indexer gRPC cache worker
+    #[clap(long, default_value = "50052")]
+    node_indexer_grpc_port: u16,
+
+    /// Address of the Redis instance the indexer gRPC stack uses as its cache
+    #[clap(long, default_value = "127.0.0.1:6379")]
+    redis_address: String,
+
+    /// Run indexer processors alongside the node, writing to the Postgres database at
+    /// `--postgres-connection-string`
+    ///
+    /// You need to run Postgres yourself, e.g. with
+    /// `docker run -p 5432:5432 -e POSTGRES_HOST_AUTH_METHOD=trust postgres`
+    #[clap(long, requires = "with_indexer_grpc")]
+    with_indexer_processors: bool,
+
+    /// Connection string of the Postgres database the indexer processors write to
+    #[clap(long, default_value = "postgres://postgres@127.0.0.1:5432/postgres")]
+    postgres_connection_string: String,
+
+    /// Indexer processors to run, separated by spaces
+    ///
+    /// Defaults to all of them: default_processor, coin_processor, stake_processor and
+    /// token_processor
+    #[clap(long, num_args = 0..)]
+    processors: Vec<String>,
+
+    #[clap(flatten)]
+    prompt_options: PromptOptions,
+}
+
+impl RunLocalTestnet {
+    /// Wait for the node to write its config, then for its API to come up.
+    async fn wait_for_node_api(test_dir: &Path) -> CliTypedResult<Url> {
+        let max_wait = Duration::from_secs(MAX_WAIT_S);
+        let wait_interval = Duration::from_millis(WAIT_INTERVAL_MS);
+
+        // Load the config to get the rest port
+        let config_path = test_dir.join("0").join("node.yaml");
+
+        // We have to wait for the node to be configured in the other thread
+        let mut config = None;
+        let start = Instant::now();
+        while start.elapsed() < max_wait {
+            if let Ok(loaded_config) = NodeConfig::load_from_path(&config_path) {
+                config = Some(loaded_config);
+                break;
+            }
+            tokio::time::sleep(wait_interval).await;
+        }
+
+        // Retrieve the port from the local node
+        let port = if let Some(config) = config {
+            config.api.address.port()
+        } else {
+            return Err(CliError::UnexpectedError(
+                "Failed to find node configuration".to_string(),
+            ));
+        };
+
+        // Check that the REST API is ready
+        let rest_url = Url::parse(&format!("http://localhost:{}", port)).map_err(|err| {
+            CliError::UnexpectedError(format!("Failed to parse localhost URL {}", err))
+        })?;
+        HealthChecker::NodeApi(rest_url.clone())
+            .wait(max_wait)
+            .await?;
+        Ok(rest_url)
+    }
+}
+
+#[async_trait]
+impl CliCommand<()> for RunLocalTestnet {
+    fn command_name(&self) -> &'static str {
+        "RunLocalTestnet"
+    }
+
+    async fn execute(mut self) -> CliTypedResult<()> {
+        let max_wait = Duration::from_secs(MAX_WAIT_S);
+        let rng = self
+            .seed
+            .map(StdRng::from_seed)
+            .unwrap_or_else(StdRng::from_entropy);
+
+        let global_config = GlobalConfig::load()?;
+        let test_dir = match self.test_dir {
+            Some(test_dir) => test_dir,
+            None => global_config
+                .get_config_location(ConfigSearchMode::CurrentDirAndParents)?
+                .join(TESTNET_FOLDER),
+        };
+
+        let processor_names = if self.processors.is_empty() {
+            indexer::all_processor_names()
+        } else {
+            self.processors.clone()
+        };
+        let all_processor_names = indexer::all_processor_names();
+        if let Some(name) = processor_names
+            .iter()
+            .find(|name| !all_processor_names.contains(name))
+        {
+            return Err(CliError::CommandArgumentError(format!(
+                "Unknown processor {}, expected one of: {}",
+                name,
+                all_processor_names.join(", ")
+            )));
+        }
+
+        // Check the services we don't run ourselves are up before starting anything
+        if self.with_indexer_grpc {
+            HealthChecker::Redis(self.redis_address.clone())
+                .wait(max_wait)
+                .await?;
+        }
+        if self.with_indexer_processors {
+            HealthChecker::Postgres(self.postgres_connection_string.clone())
+                .wait(max_wait)
+                .await?;
+        }
+
+        // Remove the current test directory and start with a new node
+        if self.force_restart && test_dir.exists() {
+            prompt_yes_with_override(
+                "Are you sure you want to delete the existing chain?",
+                self.prompt_options,
+            )?;
+            std::fs::remove_dir_all(test_dir.as_path()).map_err(|err| {
+                CliError::IO(format!("Failed to delete {}", test_dir.display()), err)
+            })?;
+        }
+
+        let node_stream_address = self
+            .with_indexer_grpc
+            .then(|| SocketAddr::from((Ipv4Addr::LOCALHOST, self.node_indexer_grpc_port)));
+        let data_service_address = SocketAddr::from((Ipv4Addr::LOCALHOST, self.indexer_grpc_port));
+
+        // Spawn the node in a separate thread
+        let config_path = self.config_path.clone();
+        let test_dir_copy = test_dir.clone();
+        let node_thread_handle = thread::spawn(move || {
+            let result = aptos_node::setup_test_environment_and_start_node(
+                config_path,
+                self.test_config_override,
+                Some(test_dir_copy),
+                false,
+                false,
+                node_stream_address
+                    .map(|address| SocketAddr::from((Ipv4Addr::UNSPECIFIED, address.port()))),
+                aptos_cached_packages::head_release_bundle(),
+                rng,
+            );
+            eprintln!("Node stopped unexpectedly {:#?}", result);
+        });
+
+        // Collect futures that should never end.
+        let mut futures: Vec<ServiceFuture> = Vec::new();
+
+        // This future just waits for the node thread.
+        let node_future = async move {
+            loop {
+                if node_thread_handle.is_finished() {
+                    return;
+                }
+                tokio::time::sleep(Duration::from_millis(500)).await;
+            }
+        };
+        futures.push(Box::pin(node_future));
+
+        // Everything else needs the node API to be up first
+        let mut ready_checkers = vec![];
+        if self.with_faucet || self.with_indexer_grpc {
+            let rest_url = Self::wait_for_node_api(&test_dir).await?;
+            ready_checkers.push(HealthChecker::NodeApi(rest_url.clone()));
+
+            // Run faucet if selected
+            if self.with_faucet {
+                // Build the config for the faucet service.
+                let faucet_config = RunConfig::build_for_cli(
+                    rest_url,
+                    self.faucet_port,
+                    FunderKeyEnum::KeyFile(test_dir.join("mint.key")),
+                    self.do_not_delegate,
+                    None,
+                );
+
+                // Start the faucet
+                let faucet_handle = tokio::spawn(faucet_config.run());
+                futures.push(Box::pin(faucet_handle.map(|result| {
+                    eprintln!("Faucet stopped unexpectedly {:#?}", result);
+                })));
+                ready_checkers.push(HealthChecker::Http(
+                    Url::parse(&format!("http://localhost:{}", self.faucet_port)).map_err(
+                        |err| {
+                            CliError::UnexpectedError(format!(
+                                "Failed to parse localhost URL {}",
+                                err
+                            ))
+                        },
+                    )?,
+                    "Faucet",
+                ));
+            }
+        }
+
+        // Run the indexer gRPC stack once the node is serving its transaction stream
+        if let Some(node_stream_address) = node_stream_address {
+            HealthChecker::Tcp(node_stream_address, "Node transaction stream")
+                .wait(max_wait)
+                .await?;
+
+            let file_store_path = test_dir.join("indexer-grpc").join("file-store");
+            std::fs::create_dir_all(&file_store_path).map_err(|err| {
+                CliError::IO(
+                    format!("Failed to create {}", file_store_path.display()),
+                    err,
+                )
+            })?;
+            futures.extend(indexer::spawn_indexer_grpc(
+                node_stream_address,
+                SocketAddr::from((Ipv4Addr::UNSPECIFIED, self.indexer_grpc_port)),
+                self.redis_address.clone(),
+                file_store_path,
+            ));
+
+            let data_service_checker =
+                HealthChecker::Tcp(data_service_address, "Indexer gRPC data service");
+            data_service_checker.wait(max_wait).await?;
+            ready_checkers.push(data_service_checker);
+        }
+
+        // Run the processors once the data service is up
+        if self.with_indexer_processors {
+            indexer::run_processor_migrations(self.postgres_connection_string.clone()).await?;
+            for processor_name in processor_names {
+                futures.push(indexer::spawn_processor(
+                    processor_name,
+                    data_service_address,
+                    self.postgres_connection_string.clone(),
+                ));
+            }
+        }
+
+        // Confirm all the services came up before telling the user they're ready
+        for checker in ready_checkers {
+            checker.wait(max_wait).await?;
+            println!("{} is ready", checker);
+        }
+        if self.with_indexer_processors {
+            println!("Indexer processors are writing to Postgres");
+        }
+
+        // Wait for all the futures. We should never get past this point unless
+        // something goes wrong or the user signals for the process to end.
+        futures::future::select_all(futures).await;
+
+        Err(CliError::UnexpectedError(
+            "One of the components stopped unexpectedly".to_string(),
+        ))
+    }
+}
@@ -547,6 +547,9 @@ impl CliTestFramework {
             prompt_options: PromptOptions::yes(),
             encoding_options: EncodingOptions::default(),
             skip_faucet: false,
+            ledger: false,
+            derivation_index: None,
+            derivation_path: None,
         }
         .execute()
         .await
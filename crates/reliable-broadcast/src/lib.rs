@@ -1,6 +1,7 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
+use anyhow::anyhow;
 use aptos_consensus_types::common::Author;
 use aptos_time_service::{TimeService, TimeServiceTrait};
 use async_trait::async_trait;
@@ -84,14 +85,16 @@ where
                 fut.push(send_message(receiver, message.clone(), None));
             }
             while let Some((receiver, result)) = fut.next().await {
+                // keep re-sending to the peer until it responds with an ack that's accepted
+                let result = result
+                    .and_then(|msg| {
+                        S::Ack::try_from(msg)
+                            .map_err(|_| anyhow!("unexpected response from {}", receiver))
+                    })
+                    .and_then(|ack| aggregating.add(receiver, ack));
                 match result {
-                    Ok(msg) => {
-                        if let Ok(ack) = msg.try_into() {
-                            if let Ok(Some(aggregated)) = aggregating.add(receiver, ack) {
-                                return aggregated;
-                            }
-                        }
-                    },
+                    Ok(Some(aggregated)) => return aggregated,
+                    Ok(None) => (),
                     Err(_) => {
                         let backoff_strategy = backoff_policies
                             .get_mut(&receiver)
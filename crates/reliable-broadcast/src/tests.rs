@@ -61,6 +61,36 @@ where
     }
 }
 
+/// Rejects the first ack from every peer
+struct FlakyBroadcastStatus {
+    threshold: usize,
+    rejected: HashSet<Author>,
+    received: HashSet<Author>,
+}
+
+impl<M> BroadcastStatus<M> for FlakyBroadcastStatus
+where
+    M: RBMessage,
+    TestAck: TryFrom<M> + Into<M>,
+    TestMessage: TryFrom<M> + Into<M>,
+{
+    type Ack = TestAck;
+    type Aggregated = HashSet<Author>;
+    type Message = TestMessage;
+
+    fn add(&mut self, peer: Author, _ack: Self::Ack) -> anyhow::Result<Option<Self::Aggregated>> {
+        if self.rejected.insert(peer) {
+            bail!("rejected ack");
+        }
+        self.received.insert(peer);
+        if self.received.len() == self.threshold {
+            Ok(Some(self.received.clone()))
+        } else {
+            Ok(None)
+        }
+    }
+}
+
 struct TestRBSender<M> {
     failures: Mutex<HashMap<Author, u8>>,
     received: Mutex<HashMap<Author, TestMessage>>,
@@ -131,6 +161,27 @@ async fn test_reliable_broadcast() {
     assert_eq!(fut.await, validators.into_iter().collect());
 }
 
+#[tokio::test]
+async fn test_reliable_broadcast_retries_rejected_acks() {
+    let (_, validator_verifier) = random_validator_verifier(5, None, false);
+    let validators = validator_verifier.get_ordered_account_addresses();
+    let sender = Arc::new(TestRBSender::<TestRBMessage>::new(HashMap::new()));
+    let rb = ReliableBroadcast::new(
+        validators.clone(),
+        sender,
+        FixedInterval::from_millis(10),
+        TimeService::real(),
+    );
+    let message = TestMessage(vec![42; validators.len()]);
+    let aggregating = FlakyBroadcastStatus {
+        threshold: validators.len(),
+        rejected: HashSet::new(),
+        received: HashSet::new(),
+    };
+    let fut = rb.broadcast(message, aggregating);
+    assert_eq!(fut.await, validators.into_iter().collect());
+}
+
 #[tokio::test]
 async fn test_chaining_reliable_broadcast() {
     let (_, validator_verifier) = random_validator_verifier(5, None, false);
@@ -1,19 +1,23 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::emitter::{load_shape::LoadShape, SequenceNumberResync};
 use anyhow::{bail, format_err, Result};
 use aptos::common::types::EncodingType;
 use aptos_config::keys::ConfigKey;
 use aptos_crypto::ed25519::Ed25519PrivateKey;
 use aptos_sdk::types::chain_id::ChainId;
-use aptos_transaction_generator_lib::args::TransactionTypeArg;
+use aptos_transaction_generator_lib::{
+    args::TransactionTypeArg, AccountPoolRefill, AccountShard, GasPriceDistribution, SignerKeyType,
+};
 use clap::{ArgGroup, Parser};
 use serde::{Deserialize, Serialize};
 use std::{
     convert::TryFrom,
     fs::File,
     io::{BufRead, BufReader},
-    path::Path,
+    net::SocketAddr,
+    path::{Path, PathBuf},
 };
 use url::Url;
 
@@ -71,7 +75,8 @@ pub struct ClusterArgs {
     #[clap(long, conflicts_with = "targets")]
     pub targets_file: Option<String>,
 
-    /// If set, try to use public peers instead of localhost.
+    /// Reuse the accounts of previous runs instead of creating new ones: the ones saved to
+    /// --accounts-file if given, otherwise the ones derived from a fixed seed.
     #[clap(long)]
     pub reuse_accounts: bool,
 
@@ -121,6 +126,14 @@ pub struct EmitArgs {
     #[clap(long)]
     pub target_tps: Option<usize>,
 
+    /// Shape of the load over time, peaking at --target-tps (or --mempool-backlog):
+    /// `constant`, `burst:<period_secs>:<burst_ratio>`, `sine:<period_secs>`, `ramp:<ramp_secs>`
+    /// or `steps:<start_tps>:<step_tps>:<step_secs>:<max_tps>`.
+    /// Each step of `steps` is reported as a separate phase, and replaces --duration;
+    /// its max TPS needs to be the --target-tps.
+    #[clap(long)]
+    pub load_shape: Option<LoadShape>,
+
     #[clap(long, default_value_t = 30)]
     pub txn_expiration_time_secs: u64,
 
@@ -137,6 +150,95 @@ pub struct EmitArgs {
     )]
     pub transaction_type: Vec<TransactionTypeArg>,
 
+    /// Yaml config of an entry function to call, see `EntryFunctionWorkload`.
+    /// Used instead of --transaction-type.
+    #[clap(long)]
+    pub entry_function_workload: Option<PathBuf>,
+
+    /// Entry function to call, as `<address>::<module>::<function>`, with --type-args and
+    /// --args. Used instead of --transaction-type, for a one-off load test of a deployed contract.
+    #[clap(long, conflicts_with = "entry_function_workload")]
+    pub entry_function: Option<String>,
+
+    /// Type arguments of --entry-function, e.g. `0x1::aptos_coin::AptosCoin`
+    #[clap(long = "type-args", num_args = 0.., requires = "entry_function")]
+    pub entry_function_type_args: Vec<String>,
+
+    /// Arguments of --entry-function, as `<type>:<value>`, e.g. `u64:5`, `address:0x1`,
+    /// `string:hello`, `hex:0x0102`, `bool:true`, or randomized per transaction:
+    /// `sender`, `random_address`, `random_u64:<min>:<max>`, `random_string:<length>`,
+    /// `random_bytes:<length>`
+    #[clap(long = "args", num_args = 0.., requires = "entry_function")]
+    pub entry_function_args: Vec<String>,
+
+    /// Percentage (0-100) of transactions writing one of the hot resources
+    /// (one per module, see --module-working-set-size), the rest don't conflict.
+    /// Used instead of --transaction-type, to sweep conflict rates.
+    #[clap(long, value_parser = clap::value_parser!(u64).range(0..=100), conflicts_with_all = &["entry_function_workload", "entry_function"])]
+    pub conflict_percentage: Option<u64>,
+
+    /// REST endpoint of the network (e.g. mainnet) to replay historical transactions from.
+    /// Only entry function calls into framework modules are replayed.
+    /// Used instead of --transaction-type.
+    #[clap(long, value_parser = parse_target, conflicts_with_all = &["entry_function_workload", "entry_function", "conflict_percentage"])]
+    pub replay_from: Option<Url>,
+
+    /// First version of the replayed range
+    #[clap(long, default_value_t = 0, requires = "replay_from")]
+    pub replay_start_version: u64,
+
+    /// Number of transactions in the replayed range, replay wraps around it
+    #[clap(long, default_value_t = 10_000, requires = "replay_from")]
+    pub replay_num_transactions: u64,
+
+    /// Number of local accounts historical senders are mapped onto
+    #[clap(long, default_value_t = 1_000, requires = "replay_from")]
+    pub replay_num_accounts: usize,
+
+    /// Yaml config of named workload mixes and the schedule to switch between them,
+    /// see `WorkloadSchedule`. Each scheduled mix is a phase of the run.
+    /// Used instead of --transaction-type, and of --duration.
+    #[clap(long, conflicts_with_all = &["entry_function_workload", "entry_function", "conflict_percentage", "replay_from"])]
+    pub workload_schedule: Option<PathBuf>,
+
+    /// Number of recipients of each transaction of a batch transfer
+    /// (aptos_account::batch_transfer) workload. Used instead of --transaction-type,
+    /// throughput is also reported in effective transfers per second.
+    #[clap(long, value_parser = clap::value_parser!(u64).range(1..), conflicts_with_all = &["entry_function_workload", "entry_function", "conflict_percentage", "replay_from", "workload_schedule"])]
+    pub batch_transfer_size: Option<u64>,
+
+    /// File with the addresses to send to, one per line (e.g. active accounts exported from
+    /// mainnet), instead of the accounts of the run. Applies to all workloads of the run,
+    /// which need to be batch transfers (e.g. with --batch-transfer-size) or token v2 mint
+    /// and transfers, as those succeed for addresses without an account.
+    #[clap(long, conflicts_with_all = &["entry_function_workload", "entry_function", "conflict_percentage", "replay_from"])]
+    pub recipients_file: Option<PathBuf>,
+
+    /// Number of distinct storage slots written by each transaction of a write set size
+    /// workload, of --write-set-slot-size bytes each. Used instead of --transaction-type,
+    /// throughput is also reported in written slots and bytes per second.
+    #[clap(long, value_parser = clap::value_parser!(u64).range(1..), conflicts_with_all = &["entry_function_workload", "entry_function", "conflict_percentage", "replay_from", "workload_schedule", "batch_transfer_size", "recipients_file"])]
+    pub write_set_num_slots: Option<u64>,
+
+    /// Size in bytes of each slot written with --write-set-num-slots
+    #[clap(long, default_value_t = 100, requires = "write_set_num_slots")]
+    pub write_set_slot_size: u64,
+
+    /// Number of events emitted by each transaction of an event heavy workload, of
+    /// --event-size bytes each. Used instead of --transaction-type, throughput is also
+    /// reported in emitted events and bytes per second.
+    #[clap(long, value_parser = clap::value_parser!(u64).range(1..), conflicts_with_all = &["entry_function_workload", "entry_function", "conflict_percentage", "replay_from", "workload_schedule", "batch_transfer_size", "recipients_file", "write_set_num_slots"])]
+    pub events_per_txn: Option<u64>,
+
+    /// Size in bytes of the data of each event emitted with --events-per-txn
+    #[clap(long, default_value_t = 100, requires = "events_per_txn")]
+    pub event_size: u64,
+
+    /// Number of secondary signers of each transaction of a multi agent workload, taken from
+    /// accounts created for it before the run starts. Used instead of --transaction-type.
+    #[clap(long, value_parser = clap::value_parser!(u64).range(1..), conflicts_with_all = &["entry_function_workload", "entry_function", "conflict_percentage", "replay_from", "workload_schedule", "batch_transfer_size", "recipients_file", "write_set_num_slots", "events_per_txn"])]
+    pub multi_agent_secondary_signers: Option<u64>,
+
     /// Number of copies of the modules that will be published,
     /// under separate accounts, creating independent contracts,
     /// removing contention.
@@ -152,12 +254,128 @@ pub struct EmitArgs {
     #[clap(long)]
     pub sender_use_account_pool: Option<bool>,
 
+    /// Number of sponsor accounts paying the gas of the generated transactions
+    /// as fee payers. Transactions are not sponsored when not set.
+    #[clap(long)]
+    pub num_sponsors: Option<usize>,
+
+    /// How to refill the account pool when it drains:
+    /// `disabled`, `recycle` used accounts, or `mint:<balance>` new ones.
+    #[clap(long)]
+    pub account_pool_refill: Option<AccountPoolRefill>,
+
+    /// Key type of the accounts signing the transactions instead of the emitter's ed25519 ones:
+    /// `ed25519` or `multi-ed25519:<num_keys>:<threshold>`. Sponsoring only applies to ed25519.
+    #[clap(long)]
+    pub signer_key_type: Option<SignerKeyType>,
+
+    /// Which senders to re-sync sequence numbers of from the chain after their transactions
+    /// expired: `emitter-accounts`, or `all-senders` (including account pool and other
+    /// accounts held by the workloads).
+    #[clap(long)]
+    pub sequence_number_resync: Option<SequenceNumberResync>,
+
+    /// Seconds to emit for before measuring, on top of the duration of the run, so the stats
+    /// aren't skewed by cold caches and the emitter ramping up its workers.
+    /// The --load-shape starts after it, the warm-up emitting at the load the shape starts with.
+    #[clap(long)]
+    pub warmup_secs: Option<u64>,
+
+    /// File the emitter accounts (keys and sequence numbers) are saved to once created,
+    /// and loaded from with --reuse-accounts, which skips creating and funding them.
+    /// Holds private keys, only meant for test networks.
+    #[clap(long)]
+    pub accounts_file: Option<PathBuf>,
+
+    /// Shard of the accounts of --accounts-file this emitter uses, as `<index>/<count>`
+    /// (contiguous ranges) or `hash:<index>/<count>` (by address), so several emitters can
+    /// share a pool created once by an unsharded run, without sequence number collisions.
+    /// Needs --reuse-accounts, and the file isn't written to.
+    #[clap(long, requires = "accounts_file")]
+    pub account_shard: Option<AccountShard>,
+
+    /// Address (e.g. `127.0.0.1:9107`) to serve the control API on, to adjust the emission
+    /// while it runs: `GET /status`, and `POST` to `/pause`, `/resume`, `/load?value=<load>`
+    /// (target TPS or mempool backlog) and `/next_phase` (switch to the mix of the next phase).
+    #[clap(long)]
+    pub control_api_address: Option<SocketAddr>,
+
+    /// Percentage of the generated transactions replaced with invalid ones (bad signature,
+    /// insufficient balance, expired or wrong chain id), rejected by validation.
+    #[clap(long, value_parser = clap::value_parser!(u64).range(0..=100))]
+    pub invalid_transaction_percentage: Option<u64>,
+
+    /// Percentage of the transactions whose expected on-chain effects (success, emitted
+    /// events, incremented counters, depending on the workload) are checked after commit,
+    /// through the REST API. Results are logged when the emission ends, along with the
+    /// min/median/max gas used by the sampled transactions of each workload, which is also
+    /// part of the stats of each phase (and of the json --results-output).
+    #[clap(long, value_parser = clap::value_parser!(u64).range(0..=100))]
+    pub verification_percentage: Option<u64>,
+
+    /// GraphQL API of an indexer of the network. Once the emission stops and the indexer
+    /// caught up, it is compared with the chain: the number of transactions sent and the APT
+    /// balance of emitting accounts, and the owner of the tokens minted or transferred by the
+    /// token transactions sampled with --verification-percentage. Mismatches are logged.
+    #[clap(long)]
+    pub indexer_graphql_url: Option<Url>,
+
+    /// Max time to wait for the indexer to catch up with the chain, before comparing them
+    #[clap(long, default_value_t = 120, requires = "indexer_graphql_url")]
+    pub indexer_catch_up_timeout_secs: u64,
+
+    /// Number of emitting accounts compared in the indexer with --indexer-graphql-url
+    #[clap(long, default_value_t = 20, requires = "indexer_graphql_url")]
+    pub indexer_validation_accounts: usize,
+
+    /// Attempts of each setup transaction (account creation and funding, publishing),
+    /// replacing the default of retrying every 10s for as long as the transaction is valid
+    #[clap(long, value_parser = clap::value_parser!(u64).range(1..))]
+    pub init_max_retries: Option<u64>,
+
+    /// How long the first attempt of a setup transaction waits for it to commit
+    #[clap(long, default_value_t = 10, requires = "init_max_retries")]
+    pub init_retry_wait_secs: u64,
+
+    /// Factor the wait for a setup transaction grows by on each attempt
+    #[clap(long, default_value_t = 1.0, requires = "init_max_retries")]
+    pub init_retry_backoff: f64,
+
+    /// Cap of the wait for a setup transaction grown by --init-retry-backoff
+    #[clap(long, default_value_t = 120, requires = "init_max_retries")]
+    pub init_max_retry_wait_secs: u64,
+
+    /// Give up right away on setup transactions rejected by validation or failing execution,
+    /// only retrying those the endpoint failed to take or that didn't commit in time
+    #[clap(long, requires = "init_max_retries")]
+    pub init_retry_transient_errors_only: bool,
+
+    /// Max setup transactions (account creation and funding, publishing) submitted and
+    /// waited on at the same time through each endpoint, so a slow endpoint queues its own
+    /// transactions instead of absorbing the retries
+    #[clap(long, value_parser = clap::value_parser!(u64).range(1..))]
+    pub endpoint_max_in_flight: Option<u64>,
+
+    /// Max setup transactions submitted per second through each endpoint
+    #[clap(long, value_parser = clap::value_parser!(u64).range(1..))]
+    pub endpoint_submissions_per_sec: Option<u64>,
+
+    /// Submit setup transactions through the batch endpoint, in batches of up to this size,
+    /// retrying the ones failing in a batch on their own
+    #[clap(long, value_parser = clap::value_parser!(u64).range(1..))]
+    pub init_submit_batch_size: Option<u64>,
+
     #[clap(long, num_args = 0..)]
     pub transaction_weights: Vec<usize>,
 
     #[clap(long, num_args = 0..)]
     pub transaction_phases: Vec<usize>,
 
+    /// Gas unit price distribution of each transaction type:
+    /// `fixed:<price>`, `uniform:<min>:<max>` or `pareto:<min>:<alpha>:<max>`.
+    #[clap(long, num_args = 0..)]
+    pub transaction_gas_prices: Vec<GasPriceDistribution>,
+
     #[clap(long)]
     pub gas_price: Option<u64>,
 
@@ -176,6 +394,17 @@ pub struct EmitArgs {
     #[clap(long)]
     pub max_transactions_per_account: Option<usize>,
 
+    /// File to write the results of each phase to, for comparing runs.
+    /// Written as JSON or CSV, depending on the extension (.json or .csv).
+    #[clap(long)]
+    pub results_output: Option<PathBuf>,
+
+    /// Seed all the randomness of the workload (transaction mix, arguments, accounts)
+    /// is derived from, to reproduce a run. Random when not set, it is logged
+    /// and part of the results.
+    #[clap(long)]
+    pub seed: Option<u64>,
+
     // In cases you want to run txn emitter from multiple machines,
     // and want to make sure that initialization succeeds
     // (account minting and txn-specific initialization), before the
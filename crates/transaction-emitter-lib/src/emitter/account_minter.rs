@@ -16,7 +16,10 @@ use aptos_sdk::{
         AccountKey, LocalAccount,
     },
 };
-use aptos_transaction_generator_lib::{CounterState, ReliableTransactionSubmitter, SEND_AMOUNT};
+use aptos_transaction_generator_lib::{
+    load_accounts, save_accounts, AccountShard, CounterState, ReliableTransactionSubmitter,
+    SEND_AMOUNT,
+};
 use core::{
     cmp::min,
     result::Result::{Err, Ok},
@@ -62,7 +65,33 @@ impl<'t> AccountMinter<'t> {
         mode_params: &EmitModeParams,
         total_requested_accounts: usize,
     ) -> Result<Vec<LocalAccount>> {
-        let mut accounts = vec![];
+        let mut accounts = match &req.accounts_file {
+            Some(path) if req.reuse_accounts && path.exists() => {
+                load_stored_accounts(
+                    txn_executor,
+                    path,
+                    req.account_shard,
+                    total_requested_accounts,
+                )
+                .await?
+            },
+            _ => vec![],
+        };
+        if let Some(shard) = req.account_shard {
+            // creating the missing accounts would overwrite the file shared with other shards
+            if accounts.len() < total_requested_accounts {
+                bail!(
+                    "Shard {} has {} accounts, {} needed. Create the shared pool first with an \
+                     unsharded run with --accounts-file, and reuse it with --reuse-accounts",
+                    shard,
+                    accounts.len(),
+                    total_requested_accounts
+                );
+            }
+        }
+        if accounts.len() >= total_requested_accounts {
+            return Ok(accounts);
+        }
         let expected_num_seed_accounts = (total_requested_accounts / 50)
             .clamp(1, (total_requested_accounts as f32).sqrt() as usize + 1);
         let num_accounts = total_requested_accounts - accounts.len(); // Only minting extra accounts
@@ -255,6 +284,10 @@ impl<'t> AccountMinter<'t> {
             start.elapsed().as_secs(),
             request_counters.show_simple(),
         );
+        if let Some(path) = &req.accounts_file {
+            save_accounts(path, &accounts)?;
+            info!("Saved {} accounts to {:?}", accounts.len(), path);
+        }
         Ok(accounts)
     }
 
@@ -382,6 +415,41 @@ impl<'t> AccountMinter<'t> {
     }
 }
 
+/// Loads up to `num_accounts` accounts saved by a previous run (of the shard, if any),
+/// re-syncing their sequence numbers from the chain. They are not re-funded, so their
+/// balance is whatever is left.
+async fn load_stored_accounts(
+    txn_executor: &dyn ReliableTransactionSubmitter,
+    path: &Path,
+    shard: Option<AccountShard>,
+    num_accounts: usize,
+) -> Result<Vec<LocalAccount>> {
+    let mut accounts = load_accounts(path)?;
+    if let Some(shard) = shard {
+        accounts = shard.select(accounts);
+        info!("Using {} accounts of shard {}", accounts.len(), shard);
+    }
+    accounts.truncate(num_accounts);
+    info!(
+        "Re-syncing sequence numbers of {} accounts loaded from {:?}",
+        accounts.len(),
+        path
+    );
+    let sequence_numbers = futures::stream::iter(
+        accounts
+            .iter()
+            .map(|account| txn_executor.query_sequence_number(account.address())),
+    )
+    .buffered(CREATION_PARALLELISM)
+    .collect::<Vec<_>>()
+    .await;
+    for (account, sequence_number) in accounts.iter_mut().zip(sequence_numbers) {
+        *account.sequence_number_mut() = sequence_number
+            .with_context(|| format!("Stored account {} not found", account.address()))?;
+    }
+    Ok(accounts)
+}
+
 fn gen_rng_for_reusable_account(count: usize) -> Vec<StdRng> {
     // use same seed for reuse account creation and reuse
     // TODO: Investigate why we use the same seed and then consider changing
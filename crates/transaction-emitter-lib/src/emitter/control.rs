@@ -0,0 +1,205 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::Result;
+use aptos_logger::info;
+use futures::FutureExt;
+use hyper::{
+    service::{make_service_fn, service_fn},
+    Body, Method, Request, Response, Server, StatusCode,
+};
+use serde::Serialize;
+use std::{
+    convert::Infallible,
+    net::SocketAddr,
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc,
+    },
+};
+use tokio::{sync::Notify, task::JoinHandle};
+
+// Endpoints of the control API
+pub const STATUS_PATH: &str = "/status";
+pub const PAUSE_PATH: &str = "/pause";
+pub const RESUME_PATH: &str = "/resume";
+pub const LOAD_PATH: &str = "/load";
+pub const NEXT_PHASE_PATH: &str = "/next_phase";
+
+/// Adjustments to a running emission, made through the control API, so long running
+/// tests don't need to be restarted to change their load.
+#[derive(Debug)]
+pub struct EmitControl {
+    paused: AtomicBool,
+    /// Target TPS (or mempool backlog) the emission was started with, and sized for
+    initial_load: usize,
+    load: AtomicUsize,
+    next_phase: Notify,
+}
+
+#[derive(Debug, Serialize)]
+struct ControlStatus {
+    paused: bool,
+    initial_load: usize,
+    load: usize,
+}
+
+impl EmitControl {
+    pub fn new(initial_load: usize) -> Self {
+        Self {
+            paused: AtomicBool::new(false),
+            initial_load,
+            load: AtomicUsize::new(initial_load),
+            next_phase: Notify::new(),
+        }
+    }
+
+    pub fn pause(&self) {
+        self.paused.store(true, Ordering::Relaxed);
+    }
+
+    pub fn resume(&self) {
+        self.paused.store(false, Ordering::Relaxed);
+    }
+
+    /// Changes the target TPS (or mempool backlog). Workers and accounts are not added,
+    /// so going much above the initial load is limited by what they can submit.
+    pub fn set_load(&self, load: usize) {
+        self.load.store(load, Ordering::Relaxed);
+    }
+
+    /// Factor the configured load of the workers is scaled by, 0 when paused
+    pub fn load_factor(&self) -> f64 {
+        if self.paused.load(Ordering::Relaxed) || self.initial_load == 0 {
+            return 0.0;
+        }
+        self.load.load(Ordering::Relaxed) as f64 / self.initial_load as f64
+    }
+
+    /// Ends the current phase early, switching to the transaction mix of the next one
+    pub fn request_next_phase(&self) {
+        self.next_phase.notify_one();
+    }
+
+    /// Drops a request made while no phase was waiting for one (e.g. during the warm-up,
+    /// or the last phase), which would otherwise end the next phase as soon as it starts.
+    pub fn start_phase(&self) {
+        let _ = self.next_phase.notified().now_or_never();
+    }
+
+    pub async fn next_phase_requested(&self) {
+        self.next_phase.notified().await
+    }
+
+    fn status(&self) -> ControlStatus {
+        ControlStatus {
+            paused: self.paused.load(Ordering::Relaxed),
+            initial_load: self.initial_load,
+            load: self.load.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Serves the control API on `address` until the returned task is aborted:
+/// `GET /status`, and `POST` to `/pause`, `/resume`, `/load?value=<load>`, `/next_phase`.
+pub fn start_control_api(control: Arc<EmitControl>, address: SocketAddr) -> Result<JoinHandle<()>> {
+    let make_service = make_service_fn(move |_conn| {
+        let control = control.clone();
+        async move {
+            Ok::<_, Infallible>(service_fn(move |request| {
+                serve_request(request, control.clone())
+            }))
+        }
+    });
+    let server = Server::try_bind(&address)?.serve(make_service);
+    info!("Emitter control API listening on {}", address);
+    Ok(tokio::spawn(async move {
+        if let Err(e) = server.await {
+            info!("Emitter control API stopped: {:?}", e);
+        }
+    }))
+}
+
+async fn serve_request(
+    request: Request<Body>,
+    control: Arc<EmitControl>,
+) -> Result<Response<Body>, Infallible> {
+    let (status_code, body) = match (request.method(), request.uri().path()) {
+        (&Method::GET, STATUS_PATH) => (
+            StatusCode::OK,
+            serde_json::to_string(&control.status()).unwrap(),
+        ),
+        (&Method::POST, PAUSE_PATH) => {
+            control.pause();
+            info!("Emission paused through the control API");
+            (StatusCode::OK, "paused".to_string())
+        },
+        (&Method::POST, RESUME_PATH) => {
+            control.resume();
+            info!("Emission resumed through the control API");
+            (StatusCode::OK, "resumed".to_string())
+        },
+        (&Method::POST, LOAD_PATH) => match request
+            .uri()
+            .query()
+            .and_then(|query| query.strip_prefix("value="))
+            .and_then(|value| value.parse::<usize>().ok())
+        {
+            Some(load) => {
+                control.set_load(load);
+                info!("Emission load set to {} through the control API", load);
+                (StatusCode::OK, format!("load set to {}", load))
+            },
+            None => (
+                StatusCode::BAD_REQUEST,
+                "Expected /load?value=<load>".to_string(),
+            ),
+        },
+        (&Method::POST, NEXT_PHASE_PATH) => {
+            control.request_next_phase();
+            info!("Next phase requested through the control API");
+            (StatusCode::OK, "next phase requested".to_string())
+        },
+        _ => (StatusCode::NOT_FOUND, "Unknown endpoint".to_string()),
+    };
+    Ok(Response::builder()
+        .status(status_code)
+        .body(Body::from(body))
+        .unwrap())
+}
+
+#[cfg(test)]
+mod test {
+    use super::EmitControl;
+    use futures::FutureExt;
+
+    #[test]
+    fn test_next_phase_request_during_phase() {
+        let control = EmitControl::new(100);
+        control.start_phase();
+        control.request_next_phase();
+        assert!(control.next_phase_requested().now_or_never().is_some());
+        // consumed by the phase it ended
+        assert!(control.next_phase_requested().now_or_never().is_none());
+    }
+
+    #[test]
+    fn test_next_phase_request_before_phase_start() {
+        let control = EmitControl::new(100);
+        control.request_next_phase();
+        control.start_phase();
+        assert!(control.next_phase_requested().now_or_never().is_none());
+    }
+
+    #[test]
+    fn test_load_factor() {
+        let control = EmitControl::new(100);
+        assert_eq!(control.load_factor(), 1.0);
+        control.set_load(50);
+        assert_eq!(control.load_factor(), 0.5);
+        control.pause();
+        assert_eq!(control.load_factor(), 0.0);
+        control.resume();
+        assert_eq!(control.load_factor(), 0.5);
+    }
+}
@@ -0,0 +1,355 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use super::RETRY_POLICY;
+use anyhow::{bail, Context, Result};
+use aptos_logger::{info, sample, sample::SampleRate, warn};
+use aptos_rest_client::Client as RestClient;
+use aptos_sdk::{
+    move_types::account_address::AccountAddress, types::transaction::SignedTransaction,
+};
+use aptos_transaction_generator_lib::{expected_token_owner, CommittedTransaction};
+use chrono::NaiveDateTime;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::time::{Duration, Instant};
+use tokio::time;
+use url::Url;
+
+/// Processors not updated for this long before the last updated one are considered stopped
+/// (e.g. removed from the indexer), their status stays in the table but isn't waited for
+const STALE_PROCESSOR_SECS: i64 = 300;
+
+/// Indexer compared with the chain after the emission, through its GraphQL API
+#[derive(Debug, Clone)]
+pub struct IndexerValidationConfig {
+    pub graphql_url: Url,
+    /// How long to wait for the indexer to process the transactions of the run
+    pub catch_up_timeout: Duration,
+    /// Number of emitting accounts whose transaction counts and balances are compared
+    pub num_accounts: usize,
+}
+
+/// Outcome of the comparison of the indexer with the chain
+#[derive(Debug, Default, Clone, Copy)]
+pub struct IndexerValidationResults {
+    /// Version up to which the indexer was compared, None if it didn't catch up in time
+    pub version: Option<u64>,
+    pub accounts_checked: usize,
+    /// Accounts whose sent transactions count or balance differ
+    pub account_mismatches: usize,
+    pub tokens_checked: usize,
+    /// Minted or transferred tokens missing from the indexer, or with another owner
+    pub token_mismatches: usize,
+    pub query_errors: usize,
+}
+
+#[derive(Deserialize)]
+struct GraphQlResponse {
+    data: Option<Value>,
+    #[serde(default)]
+    errors: Vec<Value>,
+}
+
+struct IndexerClient {
+    client: reqwest::Client,
+    graphql_url: Url,
+}
+
+impl IndexerClient {
+    async fn query(&self, query: &str, variables: Value) -> Result<Value> {
+        let response: GraphQlResponse = self
+            .client
+            .post(self.graphql_url.clone())
+            .json(&json!({ "query": query, "variables": variables }))
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        if !response.errors.is_empty() {
+            bail!("GraphQL query failed: {:?}", response.errors);
+        }
+        response.data.context("No data in GraphQL response")
+    }
+
+    /// Last version processed by all the running processors
+    async fn processed_version(&self) -> Result<u64> {
+        let data = self
+            .query(
+                "query { processor_status { processor last_success_version last_updated } }",
+                json!({}),
+            )
+            .await?;
+        let statuses = data["processor_status"]
+            .as_array()
+            .context("No processor_status in response")?
+            .iter()
+            .map(|status| {
+                let last_updated = status["last_updated"]
+                    .as_str()
+                    .context("No last_updated in processor status")?;
+                Ok(ProcessorStatus {
+                    last_success_version: json_u64(&status["last_success_version"])?,
+                    last_updated: NaiveDateTime::parse_from_str(
+                        last_updated,
+                        "%Y-%m-%dT%H:%M:%S%.f",
+                    )?,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        min_running_processed_version(&statuses).context("No processor running")
+    }
+
+    async fn num_sent_transactions(&self, address: AccountAddress) -> Result<u64> {
+        let data = self
+            .query(
+                "query ($address: String) { user_transactions_aggregate(where: {sender: {_eq: $address}}) { aggregate { count } } }",
+                json!({ "address": standard_address(address) }),
+            )
+            .await?;
+        json_u64(&data["user_transactions_aggregate"]["aggregate"]["count"])
+    }
+
+    async fn apt_balance(&self, address: AccountAddress) -> Result<Option<u64>> {
+        let data = self
+            .query(
+                "query ($address: String) { current_coin_balances(where: {owner_address: {_eq: $address}, coin_type: {_eq: \"0x1::aptos_coin::AptosCoin\"}}) { amount } }",
+                json!({ "address": standard_address(address) }),
+            )
+            .await?;
+        data["current_coin_balances"]
+            .as_array()
+            .context("No current_coin_balances in response")?
+            .first()
+            .map(|balance| json_u64(&balance["amount"]))
+            .transpose()
+    }
+
+    async fn token_owner(&self, token: AccountAddress) -> Result<Option<AccountAddress>> {
+        let data = self
+            .query(
+                "query ($token: String) { current_token_ownerships_v2(where: {token_data_id: {_eq: $token}, amount: {_gt: 0}}) { owner_address } }",
+                json!({ "token": standard_address(token) }),
+            )
+            .await?;
+        data["current_token_ownerships_v2"]
+            .as_array()
+            .context("No current_token_ownerships_v2 in response")?
+            .first()
+            .map(|ownership| {
+                let owner = ownership["owner_address"]
+                    .as_str()
+                    .context("No owner_address in ownership")?;
+                Ok(AccountAddress::from_hex_literal(owner)?)
+            })
+            .transpose()
+    }
+}
+
+struct ProcessorStatus {
+    last_success_version: u64,
+    last_updated: NaiveDateTime,
+}
+
+/// Minimum of the versions processed by the processors updated recently, compared with the
+/// last update of any processor, so the indexer clock doesn't need to match ours
+fn min_running_processed_version(statuses: &[ProcessorStatus]) -> Option<u64> {
+    let last_updated = statuses.iter().map(|status| status.last_updated).max()?;
+    statuses
+        .iter()
+        .filter(|status| (last_updated - status.last_updated).num_seconds() <= STALE_PROCESSOR_SECS)
+        .map(|status| status.last_success_version)
+        .min()
+}
+
+/// Indexer addresses are 0x prefixed and zero padded
+fn standard_address(address: AccountAddress) -> String {
+    format!("0x{}", address.to_hex())
+}
+
+/// Numeric columns are serialized as numbers or strings, depending on their type
+fn json_u64(value: &Value) -> Result<u64> {
+    match value {
+        Value::Number(number) => number.as_u64().context("Expected a u64"),
+        Value::String(string) => Ok(string.parse()?),
+        _ => bail!("Expected a u64, got {}", value),
+    }
+}
+
+/// Waits for the indexer to process the ledger as of now, then compares with the chain, at the
+/// version it processed:
+/// - for `addresses`, the number of transactions they sent and their APT balance,
+/// - for the committed token transactions of `committed_txns`, the owner of the token.
+pub async fn validate_indexer(
+    config: &IndexerValidationConfig,
+    rest_client: &RestClient,
+    addresses: &[AccountAddress],
+    committed_txns: &[(SignedTransaction, CommittedTransaction)],
+) -> Result<IndexerValidationResults> {
+    let indexer = IndexerClient {
+        client: reqwest::Client::new(),
+        graphql_url: config.graphql_url.clone(),
+    };
+    let ledger_version = RETRY_POLICY
+        .retry(move || rest_client.get_ledger_information())
+        .await?
+        .into_inner()
+        .version;
+    info!(
+        "Waiting up to {}s for the indexer to process version {}",
+        config.catch_up_timeout.as_secs(),
+        ledger_version
+    );
+    let deadline = Instant::now() + config.catch_up_timeout;
+    let version = loop {
+        let processed_version = indexer.processed_version().await?;
+        if processed_version >= ledger_version {
+            break processed_version;
+        }
+        if Instant::now() >= deadline {
+            warn!(
+                "Indexer only processed version {} of {}, not validating it",
+                processed_version, ledger_version
+            );
+            return Ok(IndexerValidationResults::default());
+        }
+        time::sleep(Duration::from_secs(5)).await;
+    };
+
+    let mut results = IndexerValidationResults {
+        version: Some(version),
+        ..Default::default()
+    };
+    for address in addresses.iter().take(config.num_accounts) {
+        match check_account(&indexer, rest_client, *address, version).await {
+            Ok(matches) => {
+                results.accounts_checked += 1;
+                if !matches {
+                    results.account_mismatches += 1;
+                }
+            },
+            Err(e) => {
+                results.query_errors += 1;
+                sample!(
+                    SampleRate::Duration(Duration::from_secs(10)),
+                    warn!(
+                        "Failed to check account {} in the indexer: {:?}",
+                        address, e
+                    )
+                );
+            },
+        }
+    }
+    for (txn, committed) in committed_txns {
+        if !committed.success || committed.version > version {
+            continue;
+        }
+        let (token, expected_owner) = match expected_token_owner(txn) {
+            Some(expected) => expected,
+            None => continue,
+        };
+        match indexer.token_owner(token).await {
+            Ok(owner) => {
+                results.tokens_checked += 1;
+                if owner != Some(expected_owner) {
+                    results.token_mismatches += 1;
+                    sample!(
+                        SampleRate::Duration(Duration::from_secs(10)),
+                        warn!(
+                            "Token {} owned by {:?} in the indexer, expected {} (txn version {})",
+                            token, owner, expected_owner, committed.version
+                        )
+                    );
+                }
+            },
+            Err(e) => {
+                results.query_errors += 1;
+                sample!(
+                    SampleRate::Duration(Duration::from_secs(10)),
+                    warn!("Failed to check token {} in the indexer: {:?}", token, e)
+                );
+            },
+        }
+    }
+    Ok(results)
+}
+
+/// Whether the indexer agrees with the chain on the account at `version`
+async fn check_account(
+    indexer: &IndexerClient,
+    rest_client: &RestClient,
+    address: AccountAddress,
+    version: u64,
+) -> Result<bool> {
+    let sequence_number = match RETRY_POLICY
+        .retry(move || {
+            rest_client.get_account_resource_at_version(address, "0x1::account::Account", version)
+        })
+        .await?
+        .into_inner()
+    {
+        Some(account) => json_u64(&account.data["sequence_number"])?,
+        None => 0,
+    };
+    let balance = RETRY_POLICY
+        .retry(move || rest_client.get_account_balance_at_version(address, version))
+        .await?
+        .into_inner()
+        .get();
+    let indexed_sequence_number = indexer.num_sent_transactions(address).await?;
+    let indexed_balance = indexer.apt_balance(address).await?;
+    if indexed_sequence_number != sequence_number || indexed_balance != Some(balance) {
+        sample!(
+            SampleRate::Duration(Duration::from_secs(10)),
+            warn!(
+                "Account {} at version {}: {} sent txns and balance {} on chain, {} and {:?} in the indexer",
+                address, version, sequence_number, balance, indexed_sequence_number, indexed_balance
+            )
+        );
+        return Ok(false);
+    }
+    Ok(true)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn status(last_success_version: u64, last_updated: &str) -> ProcessorStatus {
+        ProcessorStatus {
+            last_success_version,
+            last_updated: NaiveDateTime::parse_from_str(last_updated, "%Y-%m-%dT%H:%M:%S%.f")
+                .unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_min_running_processed_version() {
+        assert_eq!(min_running_processed_version(&[]), None);
+        // all running, the slowest is waited for
+        assert_eq!(
+            min_running_processed_version(&[
+                status(120, "2023-07-10T18:45:25.123456"),
+                status(100, "2023-07-10T18:45:20"),
+            ]),
+            Some(100)
+        );
+        // a processor removed from the indexer a day ago is ignored
+        assert_eq!(
+            min_running_processed_version(&[
+                status(120, "2023-07-10T18:45:25.123456"),
+                status(110, "2023-07-10T18:41:00"),
+                status(5, "2023-07-09T18:45:25"),
+            ]),
+            Some(110)
+        );
+    }
+
+    #[test]
+    fn test_json_u64() {
+        assert_eq!(json_u64(&json!(5)).unwrap(), 5);
+        assert_eq!(json_u64(&json!("5")).unwrap(), 5);
+        assert!(json_u64(&json!(null)).is_err());
+    }
+}
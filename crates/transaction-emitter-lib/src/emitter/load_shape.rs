@@ -0,0 +1,265 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::{bail, Result};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::{f64::consts::PI, str::FromStr, time::Duration};
+
+/// Shape of the load over time, as a fraction of the configured load
+/// (target TPS or mempool backlog), which is the peak of the shape.
+/// Workers scale the number of transactions they generate in each
+/// of their iterations by the fraction at that time.
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub enum LoadShape {
+    /// Steady load
+    #[default]
+    Constant,
+    /// Full load for the first `burst_ratio` of every period, no load for the rest
+    Burst { period_secs: u64, burst_ratio: f64 },
+    /// Load oscillating between none and full, starting at none
+    Sine { period_secs: u64 },
+    /// Load growing linearly from none to full, held once reached
+    RampThenHold { ramp_secs: u64 },
+    /// TPS starting at `start_tps`, increased by `step_tps` every `step_secs`
+    /// until `max_tps` (the configured target TPS) is reached, to find the
+    /// TPS at which latency degrades in a single run
+    Steps {
+        start_tps: usize,
+        step_tps: usize,
+        step_secs: u64,
+        max_tps: usize,
+    },
+}
+
+impl LoadShape {
+    /// Fraction (between 0 and 1) of the configured load to generate
+    /// at `elapsed` since the start of the emission.
+    pub fn load_fraction(&self, elapsed: Duration) -> f64 {
+        let elapsed = elapsed.as_secs_f64();
+        match *self {
+            LoadShape::Constant => 1.0,
+            LoadShape::Burst {
+                period_secs,
+                burst_ratio,
+            } => {
+                if (elapsed % period_secs as f64) < burst_ratio * period_secs as f64 {
+                    1.0
+                } else {
+                    0.0
+                }
+            },
+            LoadShape::Sine { period_secs } => {
+                (1.0 - (2.0 * PI * elapsed / period_secs as f64).cos()) / 2.0
+            },
+            LoadShape::RampThenHold { ramp_secs } => {
+                if elapsed >= ramp_secs as f64 {
+                    1.0
+                } else {
+                    elapsed / ramp_secs as f64
+                }
+            },
+            LoadShape::Steps {
+                start_tps,
+                step_tps,
+                step_secs,
+                max_tps,
+            } => {
+                let step = (elapsed / step_secs as f64) as usize;
+                start_tps
+                    .saturating_add(step.saturating_mul(step_tps))
+                    .min(max_tps) as f64
+                    / max_tps as f64
+            },
+        }
+    }
+
+    /// Duration and TPS of each step of a `Steps` shape, the last one being the
+    /// first to reach its max TPS. Empty for the other shapes.
+    pub fn steps(&self) -> Vec<(Duration, usize)> {
+        match *self {
+            LoadShape::Steps {
+                start_tps,
+                step_tps,
+                step_secs,
+                max_tps,
+            } => {
+                let num_steps = (max_tps - start_tps + step_tps - 1) / step_tps + 1;
+                (0..num_steps)
+                    .map(|step| {
+                        (
+                            Duration::from_secs(step_secs),
+                            (start_tps + step * step_tps).min(max_tps),
+                        )
+                    })
+                    .collect()
+            },
+            _ => vec![],
+        }
+    }
+
+    /// Scales `num` by the load fraction at `elapsed`, rounding randomly so that
+    /// the expected value matches the fraction exactly.
+    pub fn scale<R: Rng>(&self, num: usize, elapsed: Duration, rng: &mut R) -> usize {
+        self.scale_by(num, 1.0, elapsed, rng)
+    }
+
+    /// Same as `scale`, with the load fraction multiplied by `factor`
+    pub fn scale_by<R: Rng>(
+        &self,
+        num: usize,
+        factor: f64,
+        elapsed: Duration,
+        rng: &mut R,
+    ) -> usize {
+        let scaled = num as f64 * self.load_fraction(elapsed) * factor;
+        let rounded_down = scaled.floor();
+        if rng.gen_bool(scaled - rounded_down) {
+            rounded_down as usize + 1
+        } else {
+            rounded_down as usize
+        }
+    }
+}
+
+impl FromStr for LoadShape {
+    type Err = anyhow::Error;
+
+    /// Parses `constant`, `burst:<period_secs>:<burst_ratio>`, `sine:<period_secs>`
+    /// `ramp:<ramp_secs>` or `steps:<start_tps>:<step_tps>:<step_secs>:<max_tps>`
+    fn from_str(s: &str) -> Result<Self> {
+        let parts: Vec<_> = s.split(':').collect();
+        let shape = match parts.as_slice() {
+            ["constant"] => LoadShape::Constant,
+            ["burst", period_secs, burst_ratio] => LoadShape::Burst {
+                period_secs: period_secs.parse()?,
+                burst_ratio: burst_ratio.parse()?,
+            },
+            ["sine", period_secs] => LoadShape::Sine {
+                period_secs: period_secs.parse()?,
+            },
+            ["ramp", ramp_secs] => LoadShape::RampThenHold {
+                ramp_secs: ramp_secs.parse()?,
+            },
+            ["steps", start_tps, step_tps, step_secs, max_tps] => LoadShape::Steps {
+                start_tps: start_tps.parse()?,
+                step_tps: step_tps.parse()?,
+                step_secs: step_secs.parse()?,
+                max_tps: max_tps.parse()?,
+            },
+            _ => bail!(
+                "Invalid load shape {}, expected constant, burst:<period_secs>:<burst_ratio>, sine:<period_secs>, ramp:<ramp_secs> or steps:<start_tps>:<step_tps>:<step_secs>:<max_tps>",
+                s
+            ),
+        };
+        match shape {
+            LoadShape::Burst {
+                period_secs,
+                burst_ratio,
+            } if period_secs == 0 || !(0.0..=1.0).contains(&burst_ratio) => {
+                bail!("Burst needs a non-zero period and a ratio between 0 and 1")
+            },
+            LoadShape::Sine { period_secs: 0 } => bail!("Sine needs a non-zero period"),
+            LoadShape::Steps {
+                start_tps,
+                step_tps,
+                step_secs,
+                max_tps,
+            } if start_tps == 0 || step_tps == 0 || step_secs == 0 || start_tps > max_tps => {
+                bail!("Steps need non-zero TPS and durations, and to start at most at the max TPS")
+            },
+            _ => Ok(shape),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            "constant".parse::<LoadShape>().unwrap(),
+            LoadShape::Constant
+        );
+        assert_eq!(
+            "burst:60:0.25".parse::<LoadShape>().unwrap(),
+            LoadShape::Burst {
+                period_secs: 60,
+                burst_ratio: 0.25
+            }
+        );
+        assert_eq!("sine:120".parse::<LoadShape>().unwrap(), LoadShape::Sine {
+            period_secs: 120
+        });
+        assert_eq!(
+            "ramp:300".parse::<LoadShape>().unwrap(),
+            LoadShape::RampThenHold { ramp_secs: 300 }
+        );
+        assert_eq!(
+            "steps:1000:500:120:3000".parse::<LoadShape>().unwrap(),
+            LoadShape::Steps {
+                start_tps: 1000,
+                step_tps: 500,
+                step_secs: 120,
+                max_tps: 3000
+            }
+        );
+        assert!("burst:60:2".parse::<LoadShape>().is_err());
+        assert!("steps:1000:0:120:3000".parse::<LoadShape>().is_err());
+        assert!("steps:4000:500:120:3000".parse::<LoadShape>().is_err());
+        assert!("sine:0".parse::<LoadShape>().is_err());
+        assert!("square".parse::<LoadShape>().is_err());
+    }
+
+    #[test]
+    fn test_load_fraction() {
+        let burst = LoadShape::Burst {
+            period_secs: 60,
+            burst_ratio: 0.25,
+        };
+        assert_eq!(burst.load_fraction(Duration::from_secs(10)), 1.0);
+        assert_eq!(burst.load_fraction(Duration::from_secs(20)), 0.0);
+        assert_eq!(burst.load_fraction(Duration::from_secs(70)), 1.0);
+
+        let sine = LoadShape::Sine { period_secs: 60 };
+        assert!(sine.load_fraction(Duration::from_secs(0)) < 1e-9);
+        assert!((sine.load_fraction(Duration::from_secs(30)) - 1.0).abs() < 1e-9);
+
+        let ramp = LoadShape::RampThenHold { ramp_secs: 100 };
+        assert_eq!(ramp.load_fraction(Duration::from_secs(25)), 0.25);
+        assert_eq!(ramp.load_fraction(Duration::from_secs(500)), 1.0);
+
+        let steps = LoadShape::Steps {
+            start_tps: 1000,
+            step_tps: 750,
+            step_secs: 60,
+            max_tps: 2000,
+        };
+        assert_eq!(steps.load_fraction(Duration::from_secs(59)), 0.5);
+        assert_eq!(steps.load_fraction(Duration::from_secs(60)), 0.875);
+        assert_eq!(steps.load_fraction(Duration::from_secs(150)), 1.0);
+        assert_eq!(steps.steps(), vec![
+            (Duration::from_secs(60), 1000),
+            (Duration::from_secs(60), 1750),
+            (Duration::from_secs(60), 2000),
+        ]);
+        assert!(LoadShape::Constant.steps().is_empty());
+    }
+
+    #[test]
+    fn test_scale() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let ramp = LoadShape::RampThenHold { ramp_secs: 100 };
+        let total: usize = (0..1000)
+            .map(|_| ramp.scale(10, Duration::from_secs(25), &mut rng))
+            .sum();
+        assert!((2000..3000).contains(&total));
+        assert_eq!(
+            LoadShape::Constant.scale(10, Duration::from_secs(25), &mut rng),
+            10
+        );
+    }
+}
@@ -2,18 +2,26 @@
 // SPDX-License-Identifier: Apache-2.0
 
 pub mod account_minter;
+pub mod control;
+pub mod indexer_validation;
+pub mod load_shape;
 pub mod stats;
 pub mod submission_worker;
 pub mod transaction_executor;
 
 use crate::emitter::{
     account_minter::AccountMinter,
+    control::{start_control_api, EmitControl},
+    indexer_validation::{validate_indexer, IndexerValidationConfig},
+    load_shape::LoadShape,
     stats::{DynamicStatsTracking, TxnStats},
     submission_worker::SubmissionWorker,
-    transaction_executor::RestApiReliableTransactionSubmitter,
+    transaction_executor::{
+        EndpointLimits, RestApiReliableTransactionSubmitter, SubmissionRetryPolicy,
+    },
 };
 use again::RetryPolicy;
-use anyhow::{ensure, format_err, Result};
+use anyhow::{bail, ensure, format_err, Result};
 use aptos_config::config::DEFAULT_MAX_SUBMIT_TRANSACTION_BATCH_SIZE;
 use aptos_logger::{debug, error, info, sample, sample::SampleRate, warn};
 use aptos_rest_client::{aptos_api_types::AptosErrorCode, error::RestError, Client as RestClient};
@@ -22,14 +30,23 @@ use aptos_sdk::{
     transaction_builder::{aptos_stdlib, TransactionFactory},
     types::{transaction::SignedTransaction, LocalAccount},
 };
-use aptos_transaction_generator_lib::{create_txn_generator_creator, TransactionType};
+use aptos_transaction_generator_lib::{
+    create_txn_generator_creator, AccountPoolRefill, AccountShard, GasUsage,
+    InvalidTransactionWrapperCreator, SignerKeyType, TransactionType, TransactionVerificationQueue,
+    VerificationResults,
+};
 use futures::future::{try_join_all, FutureExt};
 use once_cell::sync::Lazy;
 use rand::{rngs::StdRng, seq::IteratorRandom, Rng};
 use rand_core::SeedableRng;
+use serde::{Deserialize, Serialize};
 use std::{
     cmp::{max, min},
     collections::{HashMap, HashSet},
+    fmt,
+    net::SocketAddr,
+    path::PathBuf,
+    str::FromStr,
     sync::{
         atomic::{AtomicBool, Ordering},
         Arc,
@@ -69,6 +86,37 @@ pub struct EmitModeParams {
     pub wait_millis: u64,
     pub check_account_sequence_only_once_fraction: f32,
     pub check_account_sequence_sleep: Duration,
+    pub load_shape: LoadShape,
+    /// The load shape starts after the warm-up, which emits at the load the shape starts with
+    pub warmup_duration: Duration,
+    pub sequence_number_resync: SequenceNumberResync,
+}
+
+/// Which senders get their sequence number re-synced from the chain, when their transactions
+/// didn't all commit (e.g. expired). Accounts of the emitter always are, accounts held by the
+/// transaction generators (account pool, replay accounts, ...) otherwise keep their local
+/// sequence number, and have all their following transactions rejected.
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq, Serialize)]
+pub enum SequenceNumberResync {
+    EmitterAccounts,
+    #[default]
+    AllSenders,
+}
+
+impl FromStr for SequenceNumberResync {
+    type Err = anyhow::Error;
+
+    /// Parses `emitter-accounts` or `all-senders`
+    fn from_str(s: &str) -> Result<Self> {
+        Ok(match s {
+            "emitter-accounts" => Self::EmitterAccounts,
+            "all-senders" => Self::AllSenders,
+            _ => bail!(
+                "Invalid sequence number resync {}, expected emitter-accounts or all-senders",
+                s
+            ),
+        })
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -120,18 +168,29 @@ pub struct EmitJobRequest {
     mode: EmitJobMode,
 
     transaction_mix_per_phase: Vec<Vec<(TransactionType, usize)>>,
+    /// Duration of each phase, the run is split evenly across phases when empty
+    phase_durations: Vec<Duration>,
 
     max_gas_per_txn: u64,
     gas_price: u64,
     init_gas_price_multiplier: u64,
 
     reuse_accounts: bool,
+    /// Accounts are saved to this file once created, and loaded from it when reusing accounts
+    accounts_file: Option<PathBuf>,
+    /// Shard of the accounts file to use, when sharing it with other emitters
+    account_shard: Option<AccountShard>,
     mint_to_root: bool,
 
     txn_expiration_time_secs: u64,
     init_expiration_multiplier: f64,
 
     init_retry_interval: Duration,
+    /// Replaces the fixed retries of setup transactions every `init_retry_interval`, for
+    /// as long as they can be valid
+    init_retry_policy: Option<SubmissionRetryPolicy>,
+    /// Submits setup transactions through the batch endpoint, in batches of up to this size
+    init_submit_batch_size: Option<usize>,
 
     max_transactions_per_account: usize,
 
@@ -142,6 +201,37 @@ pub struct EmitJobRequest {
     coordination_delay_between_instances: Duration,
 
     latency_polling_interval: Duration,
+
+    /// When non-zero, transactions are sponsored by a fee payer from a pool of this size
+    num_sponsors: usize,
+
+    account_pool_refill: AccountPoolRefill,
+
+    /// Key type of the accounts signing the transactions
+    signer_key_type: SignerKeyType,
+
+    load_shape: LoadShape,
+
+    sequence_number_resync: SequenceNumberResync,
+
+    /// Emission at the start of the run whose transactions are excluded from the stats,
+    /// and don't count towards the duration of the run
+    warmup_duration: Duration,
+
+    /// Address to serve the control API on, for adjusting the emission while it runs
+    control_api_address: Option<SocketAddr>,
+
+    /// Percentage of the generated transactions replaced with invalid ones
+    invalid_transaction_percentage: usize,
+
+    /// Percentage of the transactions whose on-chain effects are verified after commit
+    verification_percentage: usize,
+
+    /// Indexer compared with the chain once the emission stops
+    indexer_validation: Option<IndexerValidationConfig>,
+
+    /// Limits of the setup transactions submitted to each endpoint
+    endpoint_limits: EndpointLimits,
 }
 
 impl Default for EmitJobRequest {
@@ -152,20 +242,36 @@ impl Default for EmitJobRequest {
                 mempool_backlog: 3000,
             },
             transaction_mix_per_phase: vec![vec![(TransactionType::default(), 1)]],
+            phase_durations: Vec::new(),
             max_gas_per_txn: aptos_global_constants::MAX_GAS_AMOUNT,
             gas_price: aptos_global_constants::GAS_UNIT_PRICE,
             init_gas_price_multiplier: 10,
             reuse_accounts: false,
+            accounts_file: None,
+            account_shard: None,
             mint_to_root: false,
             txn_expiration_time_secs: 60,
             init_expiration_multiplier: 3.0,
             init_retry_interval: Duration::from_secs(10),
+            init_retry_policy: None,
+            init_submit_batch_size: None,
             max_transactions_per_account: 20,
             expected_max_txns: MAX_TXNS,
             expected_gas_per_txn: aptos_global_constants::MAX_GAS_AMOUNT,
             prompt_before_spending: false,
             coordination_delay_between_instances: Duration::from_secs(0),
             latency_polling_interval: Duration::from_millis(300),
+            num_sponsors: 0,
+            account_pool_refill: AccountPoolRefill::Disabled,
+            signer_key_type: SignerKeyType::Ed25519,
+            load_shape: LoadShape::Constant,
+            sequence_number_resync: SequenceNumberResync::default(),
+            warmup_duration: Duration::ZERO,
+            control_api_address: None,
+            invalid_transaction_percentage: 0,
+            verification_percentage: 0,
+            indexer_validation: None,
+            endpoint_limits: EndpointLimits::default(),
         }
     }
 }
@@ -228,6 +334,11 @@ impl EmitJobRequest {
         self
     }
 
+    pub fn phase_durations(mut self, phase_durations: Vec<Duration>) -> Self {
+        self.phase_durations = phase_durations;
+        self
+    }
+
     pub fn get_num_phases(&self) -> usize {
         self.transaction_mix_per_phase.len()
     }
@@ -242,6 +353,16 @@ impl EmitJobRequest {
         self
     }
 
+    pub fn accounts_file(mut self, accounts_file: PathBuf) -> Self {
+        self.accounts_file = Some(accounts_file);
+        self
+    }
+
+    pub fn account_shard(mut self, account_shard: AccountShard) -> Self {
+        self.account_shard = Some(account_shard);
+        self
+    }
+
     pub fn txn_expiration_time_secs(mut self, txn_expiration_time_secs: u64) -> Self {
         self.txn_expiration_time_secs = txn_expiration_time_secs;
         self
@@ -265,6 +386,71 @@ impl EmitJobRequest {
         self
     }
 
+    pub fn num_sponsors(mut self, num_sponsors: usize) -> Self {
+        self.num_sponsors = num_sponsors;
+        self
+    }
+
+    pub fn account_pool_refill(mut self, account_pool_refill: AccountPoolRefill) -> Self {
+        self.account_pool_refill = account_pool_refill;
+        self
+    }
+
+    pub fn signer_key_type(mut self, signer_key_type: SignerKeyType) -> Self {
+        self.signer_key_type = signer_key_type;
+        self
+    }
+
+    pub fn load_shape(mut self, load_shape: LoadShape) -> Self {
+        self.load_shape = load_shape;
+        self
+    }
+
+    pub fn sequence_number_resync(mut self, sequence_number_resync: SequenceNumberResync) -> Self {
+        self.sequence_number_resync = sequence_number_resync;
+        self
+    }
+
+    pub fn warmup_duration(mut self, warmup_duration: Duration) -> Self {
+        self.warmup_duration = warmup_duration;
+        self
+    }
+
+    pub fn control_api_address(mut self, control_api_address: SocketAddr) -> Self {
+        self.control_api_address = Some(control_api_address);
+        self
+    }
+
+    pub fn invalid_transaction_percentage(mut self, invalid_transaction_percentage: usize) -> Self {
+        self.invalid_transaction_percentage = invalid_transaction_percentage;
+        self
+    }
+
+    pub fn verification_percentage(mut self, verification_percentage: usize) -> Self {
+        self.verification_percentage = verification_percentage;
+        self
+    }
+
+    pub fn indexer_validation(mut self, indexer_validation: IndexerValidationConfig) -> Self {
+        self.indexer_validation = Some(indexer_validation);
+        self
+    }
+
+    pub fn init_retry_policy(mut self, init_retry_policy: SubmissionRetryPolicy) -> Self {
+        self.init_retry_policy = Some(init_retry_policy);
+        self
+    }
+
+    pub fn init_submit_batch_size(mut self, init_submit_batch_size: usize) -> Self {
+        self.init_submit_batch_size = Some(init_submit_batch_size);
+        self
+    }
+
+    pub fn endpoint_limits(mut self, endpoint_limits: EndpointLimits) -> Self {
+        self.endpoint_limits = endpoint_limits;
+        self
+    }
+
     pub fn calculate_mode_params(&self) -> EmitModeParams {
         let clients_count = self.rest_clients.len();
 
@@ -303,6 +489,9 @@ impl EmitJobRequest {
                     endpoints: clients_count,
                     check_account_sequence_only_once_fraction: 0.0,
                     check_account_sequence_sleep: self.latency_polling_interval,
+                    load_shape: self.load_shape,
+                    warmup_duration: self.warmup_duration,
+                    sequence_number_resync: self.sequence_number_resync,
                 }
             },
             EmitJobMode::ConstTps { tps }
@@ -391,6 +580,9 @@ impl EmitJobRequest {
                     endpoints: clients_count,
                     check_account_sequence_only_once_fraction: 1.0 - sample_latency_fraction,
                     check_account_sequence_sleep: self.latency_polling_interval,
+                    load_shape: self.load_shape,
+                    warmup_duration: self.warmup_duration,
+                    sequence_number_resync: self.sequence_number_resync,
                 }
             },
         }
@@ -461,12 +653,88 @@ struct Worker {
     join_handle: JoinHandle<Vec<LocalAccount>>,
 }
 
-#[derive(Debug)]
+/// Transactions sampled for post-commit verification, verified in the background
+/// once expired, and the rest when the job stops, once expired too
+struct PostCommitVerification {
+    queue: Arc<TransactionVerificationQueue>,
+    reader: Arc<RestApiReliableTransactionSubmitter>,
+    task: JoinHandle<()>,
+}
+
+impl PostCommitVerification {
+    fn start(
+        queue: Arc<TransactionVerificationQueue>,
+        reader: Arc<RestApiReliableTransactionSubmitter>,
+        stop: Arc<AtomicBool>,
+    ) -> Self {
+        let task = tokio::spawn({
+            let queue = queue.clone();
+            let reader = reader.clone();
+            async move {
+                while !stop.load(Ordering::Relaxed) {
+                    time::sleep(Duration::from_secs(1)).await;
+                    let now_secs = aptos_infallible::duration_since_epoch().as_secs();
+                    queue.verify_pending(reader.as_ref(), Some(now_secs)).await;
+                }
+            }
+        });
+        Self {
+            queue,
+            reader,
+            task,
+        }
+    }
+
+    async fn finish(self) -> (VerificationResults, Vec<GasUsage>) {
+        self.task.await.expect("Verification task failed");
+        // the outcome of pending transactions is only final once they expired
+        if let Some(expiration_secs) = self.queue.max_pending_expiration_secs() {
+            let now_secs = aptos_infallible::duration_since_epoch().as_secs();
+            if expiration_secs >= now_secs {
+                info!(
+                    "Waiting {}s for the sampled transactions to expire before verifying them",
+                    expiration_secs + 1 - now_secs
+                );
+                time::sleep(Duration::from_secs(expiration_secs + 1 - now_secs)).await;
+            }
+        }
+        self.queue.verify_pending(self.reader.as_ref(), None).await;
+        (self.queue.results(), self.queue.gas_usage())
+    }
+}
+
+/// Comparison of the indexer with the chain, once the emission stopped
+struct IndexerValidation {
+    config: IndexerValidationConfig,
+    rest_client: RestClient,
+    /// Emitting accounts to compare
+    addresses: Vec<AccountAddress>,
+}
+
 pub struct EmitJob {
     workers: Vec<Worker>,
     stop: Arc<AtomicBool>,
     stats: Arc<DynamicStatsTracking>,
     phase_starts: Vec<Instant>,
+    control: Arc<EmitControl>,
+    control_api: Option<JoinHandle<()>>,
+    verification: Option<PostCommitVerification>,
+    indexer_validation: Option<IndexerValidation>,
+}
+
+impl fmt::Debug for EmitJob {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EmitJob")
+            .field("workers", &self.workers)
+            .field("stop", &self.stop)
+            .field("stats", &self.stats)
+            .field("phase_starts", &self.phase_starts)
+            .field("control", &self.control)
+            .field("control_api", &self.control_api)
+            .field("verification", &self.verification.is_some())
+            .field("indexer_validation", &self.indexer_validation.is_some())
+            .finish()
+    }
 }
 
 impl EmitJob {
@@ -481,16 +749,99 @@ impl EmitJob {
         self.stats.get_cur_phase()
     }
 
+    pub fn control(&self) -> Arc<EmitControl> {
+        self.control.clone()
+    }
+
+    /// Starts measuring, the first phase starting now
+    pub fn end_warmup(&mut self) {
+        self.stats.end_warmup();
+        self.phase_starts[0] = Instant::now();
+    }
+
+    /// Outcome of the post-commit verification of the transactions verified so far
+    pub fn verification_results(&self) -> Option<VerificationResults> {
+        self.verification
+            .as_ref()
+            .map(|verification| verification.queue.results())
+    }
+
+    /// Gas used per workload by the sampled transactions committed so far
+    pub fn gas_usage(&self) -> Option<Vec<GasUsage>> {
+        self.verification
+            .as_ref()
+            .map(|verification| verification.queue.gas_usage())
+    }
+
     pub async fn stop_and_accumulate(self) -> Vec<TxnStats> {
         self.stop.store(true, Ordering::Relaxed);
+        if let Some(control_api) = &self.control_api {
+            control_api.abort();
+        }
         for worker in self.workers {
             let _accounts = worker
                 .join_handle
                 .await
                 .expect("TxnEmitter worker thread failed");
         }
+        let mut stats = self.stats.accumulate(&self.phase_starts);
+        let mut committed_txns = Vec::new();
+        if let Some(verification) = self.verification {
+            let queue = verification.queue.clone();
+            let (results, gas_usage) = verification.finish().await;
+            info!(
+                "Post-commit verification: {} passed, {} failed, {} not committed, {} read errors",
+                results.passed, results.failed, results.not_committed, results.read_errors
+            );
+            if results.failed > 0 {
+                warn!(
+                    "{} transactions didn't have the expected on-chain effects",
+                    results.failed
+                );
+            }
+            for usage in gas_usage {
+                info!(
+                    "Gas used by {} in phase {} ({} samples): min {}, median {}, max {}",
+                    usage.workload,
+                    usage.phase,
+                    usage.num_samples,
+                    usage.min,
+                    usage.median,
+                    usage.max
+                );
+                if let Some(phase_stats) = stats.get_mut(usage.phase) {
+                    phase_stats.gas_usage.push(usage);
+                }
+            }
+            committed_txns = queue.committed_transactions();
+        }
+        if let Some(validation) = self.indexer_validation {
+            match validate_indexer(
+                &validation.config,
+                &validation.rest_client,
+                &validation.addresses,
+                &committed_txns,
+            )
+            .await
+            {
+                Ok(results) => {
+                    if let Some(version) = results.version {
+                        info!(
+                            "Indexer validation at version {}: {} of {} accounts and {} of {} tokens mismatched, {} query errors",
+                            version,
+                            results.account_mismatches,
+                            results.accounts_checked,
+                            results.token_mismatches,
+                            results.tokens_checked,
+                            results.query_errors
+                        );
+                    }
+                },
+                Err(e) => warn!("Indexer validation failed: {:?}", e),
+            }
+        }
 
-        self.stats.accumulate(&self.phase_starts)
+        stats
     }
 
     pub fn peek_and_accumulate(&self) -> Vec<TxnStats> {
@@ -559,6 +910,9 @@ impl TxnEmitter {
         stats_tracking_phases: usize,
     ) -> Result<EmitJob> {
         ensure!(req.gas_price > 0, "gas_price is required to be non zero");
+        for (transaction_type, _) in req.transaction_mix_per_phase.iter().flatten() {
+            transaction_type.validate()?;
+        }
 
         let mode_params = req.calculate_mode_params();
         let workers_per_endpoint = mode_params.workers_per_endpoint;
@@ -591,24 +945,50 @@ impl TxnEmitter {
             init_txn_factory.clone(),
             StdRng::from_seed(seed),
         );
-        let init_retries: usize =
-            usize::try_from(init_expiration_time / req.init_retry_interval.as_secs()).unwrap();
+        let init_retry_policy = req.init_retry_policy.unwrap_or_else(|| {
+            let init_retries =
+                usize::try_from(init_expiration_time / req.init_retry_interval.as_secs()).unwrap();
+            SubmissionRetryPolicy::fixed(init_retries, req.init_retry_interval)
+        });
         info!(
-            "Using reliable/retriable init transaction executor with {} retries, every {}s",
-            init_retries,
-            req.init_retry_interval.as_secs_f32()
+            "Using reliable/retriable init transaction executor with {:?}",
+            init_retry_policy
         );
-        let txn_executor = RestApiReliableTransactionSubmitter {
-            rest_clients: req.rest_clients.clone(),
-            max_retries: init_retries,
-            retry_after: req.init_retry_interval,
-        };
+        let mut txn_executor = RestApiReliableTransactionSubmitter::new(
+            req.rest_clients.clone(),
+            init_retry_policy,
+            req.endpoint_limits,
+        );
+        if let Some(init_submit_batch_size) = req.init_submit_batch_size {
+            txn_executor = txn_executor.with_submit_batch_size(init_submit_batch_size);
+        }
         let mut all_accounts = account_minter
             .create_accounts(&txn_executor, &req, &mode_params, num_accounts)
             .await?;
         let stop = Arc::new(AtomicBool::new(false));
-        let stats = Arc::new(DynamicStatsTracking::new(stats_tracking_phases));
+        let stats = Arc::new(DynamicStatsTracking::new(
+            stats_tracking_phases,
+            !req.warmup_duration.is_zero(),
+        ));
+        let control = Arc::new(EmitControl::new(match req.mode {
+            EmitJobMode::MaxLoad { mempool_backlog } => mempool_backlog,
+            EmitJobMode::ConstTps { tps }
+            | EmitJobMode::WaveTps {
+                average_tps: tps, ..
+            } => tps,
+        }));
         let tokio_handle = Handle::current();
+        let verification_queue = (req.verification_percentage > 0).then(|| {
+            let queue = TransactionVerificationQueue::new(req.verification_percentage);
+            // the committed token transactions are checked against the indexer
+            Arc::new(
+                if req.indexer_validation.is_some() {
+                    queue.keep_committed()
+                } else {
+                    queue
+                },
+            )
+        });
 
         let (mut txn_generator_creator, _, _) = create_txn_generator_creator(
             &req.transaction_mix_per_phase,
@@ -618,8 +998,20 @@ impl TxnEmitter {
             &txn_factory,
             &init_txn_factory,
             stats.get_cur_phase_obj(),
+            req.num_sponsors,
+            req.account_pool_refill,
+            req.signer_key_type,
+            verification_queue.clone(),
+            self.from_rng(),
         )
         .await;
+        if req.invalid_transaction_percentage > 0 {
+            txn_generator_creator = Box::new(InvalidTransactionWrapperCreator::new(
+                txn_generator_creator,
+                req.invalid_transaction_percentage,
+                self.from_rng(),
+            ));
+        }
 
         if !req.coordination_delay_between_instances.is_zero() {
             info!(
@@ -648,6 +1040,19 @@ impl TxnEmitter {
 
         let all_start_sleep_durations = mode_params.get_all_start_sleep_durations(self.from_rng());
 
+        let indexer_validation = req
+            .indexer_validation
+            .clone()
+            .map(|config| IndexerValidation {
+                addresses: all_accounts
+                    .iter()
+                    .take(config.num_accounts)
+                    .map(|account| account.address())
+                    .collect(),
+                rest_client: req.rest_clients[0].clone(),
+                config,
+            });
+
         // Creating workers is slow with many workers (TODO check why)
         // so we create them all first, before starting them - so they start at the right time for
         // traffic pattern to be correct.
@@ -674,6 +1079,7 @@ impl TxnEmitter {
                     txn_generator,
                     all_start_sleep_durations[worker_index],
                     check_account_sequence_only_once_for.contains(&worker_index),
+                    control.clone(),
                     self.from_rng(),
                 );
                 submission_workers.push(worker);
@@ -681,6 +1087,10 @@ impl TxnEmitter {
         }
 
         info!("Tx emitter workers created");
+        let control_api = req
+            .control_api_address
+            .map(|address| start_control_api(control.clone(), address))
+            .transpose()?;
         let phase_start = Instant::now();
         let workers = submission_workers
             .into_iter()
@@ -689,12 +1099,27 @@ impl TxnEmitter {
             })
             .collect();
         info!("Tx emitter workers started");
+        let verification = verification_queue.map(|queue| {
+            PostCommitVerification::start(
+                queue,
+                Arc::new(RestApiReliableTransactionSubmitter::new(
+                    req.rest_clients.clone(),
+                    init_retry_policy,
+                    req.endpoint_limits,
+                )),
+                stop.clone(),
+            )
+        });
 
         Ok(EmitJob {
             workers,
             stop,
             stats,
             phase_starts: vec![phase_start],
+            control,
+            control_api,
+            verification,
+            indexer_validation,
         })
     }
 
@@ -704,34 +1129,61 @@ impl TxnEmitter {
         emit_job_request: EmitJobRequest,
         duration: Duration,
         print_stats_interval: Option<u64>,
-    ) -> Result<TxnStats> {
+    ) -> Result<Vec<TxnStats>> {
         let phases = emit_job_request.transaction_mix_per_phase.len();
+        let phase_durations = if emit_job_request.phase_durations.is_empty() {
+            vec![duration.checked_div(phases as u32).unwrap(); phases]
+        } else {
+            assert_eq!(
+                emit_job_request.phase_durations.len(),
+                phases,
+                "Transaction mixes and durations need to be the same length"
+            );
+            emit_job_request.phase_durations.clone()
+        };
 
+        let warmup_duration = emit_job_request.warmup_duration;
         let mut job = self
             .start_job(source_account, emit_job_request, phases)
             .await?;
+        if !warmup_duration.is_zero() {
+            info!(
+                "Warming up for {} secs, not measured",
+                warmup_duration.as_secs()
+            );
+            time::sleep(warmup_duration).await;
+            job.end_warmup();
+        }
         info!(
             "Starting emitting txns for {} secs in {} phases",
             duration.as_secs(),
             phases
         );
 
-        let per_phase_duration = duration.checked_div(phases as u32).unwrap();
-        for phase in 0..phases {
+        for (phase, phase_duration) in phase_durations.into_iter().enumerate() {
             if phase > 0 {
                 info!("Starting next phase");
                 job.start_next_phase();
             }
-            if let Some(interval_secs) = print_stats_interval {
-                job.periodic_stat(per_phase_duration, interval_secs).await;
-            } else {
-                time::sleep(per_phase_duration).await;
+            let control = job.control();
+            control.start_phase();
+            tokio::select! {
+                _ = async {
+                    if let Some(interval_secs) = print_stats_interval {
+                        job.periodic_stat(phase_duration, interval_secs).await;
+                    } else {
+                        time::sleep(phase_duration).await;
+                    }
+                } => {},
+                _ = control.next_phase_requested() => {
+                    info!("Ending phase {} early, as requested", phase);
+                },
             }
         }
         info!("Ran for {} secs, stopping job...", duration.as_secs());
         let stats = job.stop_job().await;
         info!("Stopped job");
-        Ok(stats.into_iter().next().unwrap())
+        Ok(stats)
     }
 
     pub async fn emit_txn_for(
@@ -742,6 +1194,7 @@ impl TxnEmitter {
     ) -> Result<TxnStats> {
         self.emit_txn_for_impl(source_account, emit_job_request, duration, None)
             .await
+            .map(|stats| stats.into_iter().next().unwrap())
     }
 
     pub async fn emit_txn_for_with_stats(
@@ -751,6 +1204,24 @@ impl TxnEmitter {
         duration: Duration,
         interval_secs: u64,
     ) -> Result<TxnStats> {
+        self.emit_txn_for_with_phase_stats(
+            source_account,
+            emit_job_request,
+            duration,
+            interval_secs,
+        )
+        .await
+        .map(|stats| stats.into_iter().next().unwrap())
+    }
+
+    /// Same as emit_txn_for_with_stats, but returns the stats of each phase.
+    pub async fn emit_txn_for_with_phase_stats(
+        self,
+        source_account: &mut LocalAccount,
+        emit_job_request: EmitJobRequest,
+        duration: Duration,
+        interval_secs: u64,
+    ) -> Result<Vec<TxnStats>> {
         self.emit_txn_for_impl(
             source_account,
             emit_job_request,
@@ -802,11 +1273,12 @@ async fn wait_for_accounts_sequence(
                     let prev_sequence_number = latest_fetched_counts
                         .insert(address, sequence_number)
                         .unwrap_or(*start_seq_num);
-                    assert!(prev_sequence_number <= sequence_number);
-                    sum_of_completion_timestamps_millis +=
-                        millis_elapsed * (sequence_number - prev_sequence_number) as u128;
+                    // a stale generator account can be behind the start
+                    sum_of_completion_timestamps_millis += millis_elapsed
+                        * sequence_number.saturating_sub(prev_sequence_number) as u128;
 
-                    if *end_seq_num == sequence_number {
+                    // accounts shared between workers can get past the end
+                    if *end_seq_num <= sequence_number {
                         pending_addresses.remove(&address);
                     }
                 }
@@ -877,7 +1349,6 @@ fn update_seq_num_and_get_num_expired(
         match latest_fetched_counts.get(&account.address()) {
             Some(count) => {
                 if *count != account.sequence_number() {
-                    assert!(account.sequence_number() > *count);
                     debug!(
                         "Stale sequence_number for {}, expected {}, setting to {}",
                         account.address(),
@@ -904,11 +1375,12 @@ fn update_seq_num_and_get_num_expired(
         .map(
             |(address, (start_seq_num, end_seq_num))| match latest_fetched_counts.get(address) {
                 Some(count) => {
-                    assert!(*count <= *end_seq_num);
-                    assert!(*count >= *start_seq_num);
+                    // generator accounts can be ahead (shared with other workers)
+                    // or behind (stale before the resync) of what was sent in this loop
+                    let count = (*count).clamp(*start_seq_num, *end_seq_num);
                     (
-                        (*count - *start_seq_num) as usize,
-                        (*end_seq_num - *count) as usize,
+                        (count - *start_seq_num) as usize,
+                        (*end_seq_num - count) as usize,
                     )
                 },
                 None => (0, (end_seq_num - start_seq_num) as usize),
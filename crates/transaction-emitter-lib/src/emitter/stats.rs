@@ -1,11 +1,16 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
+use anyhow::{bail, Result};
+use aptos_transaction_generator_lib::GasUsage;
+use serde::Serialize;
 use std::{
     fmt,
+    fs::File,
     ops::{Add, Sub},
+    path::Path,
     sync::{
-        atomic::{AtomicU64, AtomicUsize, Ordering},
+        atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering},
         Arc,
     },
     time::{Duration, Instant},
@@ -21,6 +26,9 @@ pub struct TxnStats {
     pub latency_samples: u64,
     pub latency_buckets: AtomicHistogramSnapshot,
     pub lasted: Duration,
+    /// Gas used per workload by the sampled transactions of the phase, only filled in once
+    /// the job stopped, and when transactions are sampled for verification
+    pub gas_usage: Vec<GasUsage>,
 }
 
 #[derive(Debug, Clone, Default)]
@@ -72,6 +80,75 @@ impl TxnStats {
             p99_latency: self.latency_buckets.percentile(99, 100),
         }
     }
+
+    pub fn phase_result(&self, phase: usize, seed: u64) -> PhaseResult {
+        let rate = self.rate();
+        PhaseResult {
+            phase,
+            seed,
+            lasted_secs: self.lasted.as_secs_f64(),
+            submitted: self.submitted,
+            committed: self.committed,
+            expired: self.expired,
+            failed_submission: self.failed_submission,
+            committed_tps: self.committed as f64 / self.lasted.as_secs_f64().max(1.0),
+            latency_samples: self.latency_samples,
+            avg_latency_ms: rate.latency,
+            p50_latency_ms: rate.p50_latency,
+            p90_latency_ms: rate.p90_latency,
+            p99_latency_ms: rate.p99_latency,
+            gas_usage: self.gas_usage.clone(),
+        }
+    }
+}
+
+/// Machine readable results of a single phase, for comparing runs.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PhaseResult {
+    pub phase: usize,
+    /// Seed of the run, to reproduce it
+    pub seed: u64,
+    pub lasted_secs: f64,
+    pub submitted: u64,
+    pub committed: u64,
+    pub expired: u64,
+    pub failed_submission: u64,
+    pub committed_tps: f64,
+    pub latency_samples: u64,
+    pub avg_latency_ms: u64,
+    pub p50_latency_ms: u64,
+    pub p90_latency_ms: u64,
+    pub p99_latency_ms: u64,
+    /// Only written to json, csv has no nested values
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub gas_usage: Vec<GasUsage>,
+}
+
+/// Writes the results of each phase to `path`, as JSON or CSV depending on its extension.
+pub fn write_phase_results(stats: &[TxnStats], seed: u64, path: &Path) -> Result<()> {
+    let results: Vec<_> = stats
+        .iter()
+        .enumerate()
+        .map(|(phase, stats)| stats.phase_result(phase, seed))
+        .collect();
+    match path.extension().and_then(|extension| extension.to_str()) {
+        Some("json") => serde_json::to_writer_pretty(File::create(path)?, &results)?,
+        Some("csv") => {
+            let mut writer = csv::Writer::from_path(path)?;
+            for result in results {
+                writer.serialize(PhaseResult {
+                    gas_usage: vec![],
+                    ..result
+                })?;
+            }
+            writer.flush()?;
+        },
+        _ => bail!(
+            "Unsupported results file {}, expected a .json or .csv extension",
+            path.display()
+        ),
+    }
+    Ok(())
 }
 
 impl fmt::Display for TxnStats {
@@ -97,6 +174,7 @@ impl Sub for &TxnStats {
             latency_samples: self.latency_samples - other.latency_samples,
             latency_buckets: &self.latency_buckets - &other.latency_buckets,
             lasted: self.lasted - other.lasted,
+            gas_usage: self.gas_usage.clone(),
         }
     }
 }
@@ -114,6 +192,7 @@ impl Add for &TxnStats {
             latency_samples: self.latency_samples + other.latency_samples,
             latency_buckets: &self.latency_buckets + &other.latency_buckets,
             lasted: self.lasted + other.lasted,
+            gas_usage: [self.gas_usage.clone(), other.gas_usage.clone()].concat(),
         }
     }
 }
@@ -140,6 +219,7 @@ impl StatsAccumulator {
             latency_samples: self.latency_samples.load(Ordering::Relaxed),
             latency_buckets: self.latencies.snapshot(),
             lasted,
+            gas_usage: vec![],
         }
     }
 }
@@ -280,10 +360,15 @@ pub struct DynamicStatsTracking {
     num_phases: usize,
     cur_phase: Arc<AtomicUsize>,
     stats: Vec<StatsAccumulator>,
+    /// Stats of the warm-up, which are never reported
+    warmup: StatsAccumulator,
+    warming_up: AtomicBool,
 }
 
 impl DynamicStatsTracking {
-    pub fn new(num_phases: usize) -> DynamicStatsTracking {
+    /// With `warming_up`, stats are tracked separately and not reported until `end_warmup`
+    /// is called, so nothing submitted before then counts towards the first phase.
+    pub fn new(num_phases: usize, warming_up: bool) -> DynamicStatsTracking {
         assert!(num_phases >= 1);
         Self {
             num_phases,
@@ -291,9 +376,17 @@ impl DynamicStatsTracking {
             stats: (0..num_phases)
                 .map(|_| StatsAccumulator::default())
                 .collect(),
+            warmup: StatsAccumulator::default(),
+            warming_up: AtomicBool::new(warming_up),
         }
     }
 
+    /// Accumulators handed out before this keep tracking the warm-up, so transactions of
+    /// submission loops started during it don't count, whenever they commit.
+    pub fn end_warmup(&self) {
+        self.warming_up.store(false, Ordering::Release);
+    }
+
     pub fn start_next_phase(&self) -> usize {
         let cur_phase = self.cur_phase.fetch_add(1, Ordering::Relaxed) + 1;
         assert!(cur_phase < self.num_phases);
@@ -301,6 +394,9 @@ impl DynamicStatsTracking {
     }
 
     pub fn get_cur(&self) -> &StatsAccumulator {
+        if self.warming_up.load(Ordering::Acquire) {
+            return &self.warmup;
+        }
         self.stats.get(self.get_cur_phase()).unwrap()
     }
 
@@ -335,10 +431,14 @@ impl DynamicStatsTracking {
 #[cfg(test)]
 mod test {
     use crate::emitter::stats::{
-        AtomicHistogramAccumulator, AtomicHistogramSnapshot, TxnStats, DEFAULT_HISTOGRAM_CAPACITY,
-        DEFAULT_HISTOGRAM_STEP_WIDTH,
+        AtomicHistogramAccumulator, AtomicHistogramSnapshot, DynamicStatsTracking, TxnStats,
+        DEFAULT_HISTOGRAM_CAPACITY, DEFAULT_HISTOGRAM_STEP_WIDTH,
+    };
+    use aptos_transaction_generator_lib::GasUsage;
+    use std::{
+        sync::atomic::Ordering,
+        time::{Duration, Instant},
     };
-    use std::time::Duration;
 
     #[test]
     pub fn test_default_atomic_histogram() {
@@ -406,8 +506,70 @@ mod test {
             latency_samples: 0,
             latency_buckets: histogram.snapshot(),
             lasted: Duration::from_secs(10),
+            gas_usage: vec![],
         };
         let res = stat.latency_buckets.percentile(9, 10);
         assert_eq!(res, 900);
     }
+
+    #[test]
+    pub fn test_phase_result() {
+        let histogram = AtomicHistogramAccumulator::default();
+        for i in 1..11 {
+            histogram.record_data_point(i as u64 * 100, 1);
+        }
+        let stat = TxnStats {
+            submitted: 12,
+            committed: 10,
+            expired: 2,
+            failed_submission: 0,
+            latency: 5500,
+            latency_samples: 10,
+            latency_buckets: histogram.snapshot(),
+            lasted: Duration::from_secs(5),
+            gas_usage: vec![GasUsage {
+                phase: 1,
+                workload: "CoinTransfer".to_string(),
+                num_samples: 3,
+                min: 8,
+                median: 9,
+                max: 12,
+            }],
+        };
+        let result = stat.phase_result(1, 42);
+        assert_eq!(result.phase, 1);
+        assert_eq!(result.seed, 42);
+        assert_eq!(result.committed_tps, 2.0);
+        assert_eq!(result.avg_latency_ms, 550);
+        assert_eq!(result.p90_latency_ms, 900);
+        assert_eq!(result.gas_usage, stat.gas_usage);
+        let json = serde_json::to_value(&result).unwrap();
+        assert_eq!(json["gas_usage"][0]["median"], 9);
+    }
+
+    #[test]
+    pub fn test_warmup_not_counted() {
+        let stats = DynamicStatsTracking::new(2, true);
+        let warmup_loop = stats.get_cur();
+        warmup_loop.submitted.fetch_add(10, Ordering::Relaxed);
+
+        stats.end_warmup();
+        // a loop started during the warm-up commits after it ended
+        warmup_loop.committed.fetch_add(10, Ordering::Relaxed);
+        let measured_loop = stats.get_cur();
+        measured_loop.submitted.fetch_add(5, Ordering::Relaxed);
+        measured_loop.committed.fetch_add(4, Ordering::Relaxed);
+
+        let phase_stats = stats.accumulate(&[Instant::now()]);
+        assert_eq!(phase_stats.len(), 1);
+        assert_eq!(phase_stats[0].submitted, 5);
+        assert_eq!(phase_stats[0].committed, 4);
+    }
+
+    #[test]
+    pub fn test_no_warmup() {
+        let stats = DynamicStatsTracking::new(1, false);
+        stats.get_cur().submitted.fetch_add(3, Ordering::Relaxed);
+        assert_eq!(stats.accumulate(&[Instant::now()])[0].submitted, 3);
+    }
 }
@@ -3,8 +3,9 @@
 
 use crate::{
     emitter::{
+        control::EmitControl,
         stats::{DynamicStatsTracking, StatsAccumulator},
-        update_seq_num_and_get_num_expired, wait_for_accounts_sequence,
+        update_seq_num_and_get_num_expired, wait_for_accounts_sequence, SequenceNumberResync,
     },
     EmitModeParams,
 };
@@ -25,7 +26,7 @@ use futures::future::join_all;
 use itertools::Itertools;
 use rand::seq::IteratorRandom;
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     sync::{atomic::AtomicU64, Arc},
     time::Instant,
 };
@@ -40,6 +41,7 @@ pub struct SubmissionWorker {
     txn_generator: Box<dyn TransactionGenerator>,
     start_sleep_duration: Duration,
     skip_latency_stats: bool,
+    control: Arc<EmitControl>,
     rng: ::rand::rngs::StdRng,
 }
 
@@ -53,6 +55,7 @@ impl SubmissionWorker {
         txn_generator: Box<dyn TransactionGenerator>,
         start_sleep_duration: Duration,
         skip_latency_stats: bool,
+        control: Arc<EmitControl>,
         rng: ::rand::rngs::StdRng,
     ) -> Self {
         Self {
@@ -64,6 +67,7 @@ impl SubmissionWorker {
             txn_generator,
             start_sleep_duration,
             skip_latency_stats,
+            control,
             rng,
         }
     }
@@ -98,7 +102,11 @@ impl SubmissionWorker {
             // always add expected cycle duration, to not drift from expected pace.
             wait_until += wait_duration;
 
-            let requests = self.gen_requests();
+            let requests = self.gen_requests(
+                loop_start_time
+                    .duration_since(start_instant)
+                    .saturating_sub(self.params.warmup_duration),
+            );
             if !requests.is_empty() {
                 let mut account_to_start_and_end_seq_num = HashMap::new();
                 for req in requests.iter() {
@@ -115,6 +123,15 @@ impl SubmissionWorker {
                         })
                         .or_insert((cur, cur + 1));
                 }
+                // generators can take back the sequence numbers of our transactions that are
+                // meant to be rejected (e.g. invalid ones), those are not waited for
+                for account in &self.accounts {
+                    if let Some((start, end)) =
+                        account_to_start_and_end_seq_num.get_mut(&account.address())
+                    {
+                        *end = (*end).min(account.sequence_number()).max(*start);
+                    }
+                }
                 // Some transaction generators use burner accounts, and will have different
                 // number of accounts per transaction, so useful to very rarely log.
                 sample!(
@@ -243,6 +260,13 @@ impl SubmissionWorker {
             )
             .await;
 
+        if self.params.sequence_number_resync == SequenceNumberResync::AllSenders {
+            self.resync_generator_accounts(
+                &account_to_start_and_end_seq_num,
+                &latest_fetched_counts,
+            );
+        }
+
         let (num_committed, num_expired) = update_seq_num_and_get_num_expired(
             &mut self.accounts,
             account_to_start_and_end_seq_num,
@@ -289,7 +313,46 @@ impl SubmissionWorker {
         }
     }
 
-    fn gen_requests(&mut self) -> Vec<SignedTransaction> {
+    /// Senders not owned by this worker are held by the transaction generator, which
+    /// re-syncs those with expired transactions (our accounts are updated separately).
+    fn resync_generator_accounts(
+        &mut self,
+        account_to_start_and_end_seq_num: &HashMap<AccountAddress, (u64, u64)>,
+        latest_fetched_counts: &HashMap<AccountAddress, u64>,
+    ) {
+        let own_addresses: HashSet<_> = self.accounts.iter().map(|a| a.address()).collect();
+        let stale: HashMap<_, _> = account_to_start_and_end_seq_num
+            .iter()
+            .filter(|(address, _)| !own_addresses.contains(*address))
+            .filter_map(|(address, (_, end_seq_num))| {
+                // when the fetch failed, we don't know where the account is
+                let count = latest_fetched_counts.get(address)?;
+                (count < end_seq_num).then_some((*address, (*end_seq_num, *count)))
+            })
+            .collect();
+        if !stale.is_empty() {
+            sample!(
+                SampleRate::Duration(Duration::from_secs(120)),
+                info!(
+                    "[{:?}] Re-syncing sequence numbers of {} generator accounts with expired transactions",
+                    self.client.path_prefix_string(),
+                    stale.len(),
+                )
+            );
+            self.txn_generator.resync_sequence_numbers(&stale);
+        }
+    }
+
+    fn gen_requests(&mut self, elapsed: Duration) -> Vec<SignedTransaction> {
+        let transactions_per_account = self.params.load_shape.scale_by(
+            self.params.transactions_per_account,
+            self.control.load_factor(),
+            elapsed,
+            &mut self.rng,
+        );
+        if transactions_per_account == 0 {
+            return Vec::new();
+        }
         let batch_size = max(
             1,
             min(
@@ -306,7 +369,7 @@ impl SubmissionWorker {
             .into_iter()
             .flat_map(|account| {
                 self.txn_generator
-                    .generate_transactions(account, self.params.transactions_per_account)
+                    .generate_transactions(account, transactions_per_account)
             })
             .collect()
     }
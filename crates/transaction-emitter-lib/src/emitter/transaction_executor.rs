@@ -3,28 +3,196 @@
 
 use super::RETRY_POLICY;
 use anyhow::{Context, Result};
+use aptos_infallible::Mutex;
 use aptos_logger::{debug, sample, sample::SampleRate, warn};
-use aptos_rest_client::{aptos_api_types::TransactionInfo, error::RestError, Client as RestClient};
+use aptos_rest_client::{
+    aptos_api_types::{AptosErrorCode, Transaction, TransactionInfo},
+    error::RestError,
+    Client as RestClient,
+};
 use aptos_sdk::{
-    move_types::account_address::AccountAddress, types::transaction::SignedTransaction,
+    crypto::HashValue, move_types::account_address::AccountAddress,
+    types::transaction::SignedTransaction,
+};
+use aptos_transaction_generator_lib::{
+    CommittedTransaction, CounterState, ReliableTransactionSubmitter, TransactionOutputReader,
 };
-use aptos_transaction_generator_lib::{CounterState, ReliableTransactionSubmitter};
 use async_trait::async_trait;
 use futures::future::join_all;
 use rand::{rngs::StdRng, seq::SliceRandom, thread_rng, Rng, SeedableRng};
 use std::{
+    collections::{HashMap, HashSet},
     sync::atomic::AtomicUsize,
     time::{Duration, Instant},
 };
+use tokio::sync::{Semaphore, SemaphorePermit};
+
+/// Limits of the transactions the reliable submitter sends to each endpoint, so a slow
+/// endpoint queues its own transactions instead of piling up retries and skewing latencies
+#[derive(Debug, Clone, Copy, Default)]
+pub struct EndpointLimits {
+    /// Transactions submitted and waited on at the same time
+    pub max_in_flight: Option<usize>,
+    /// Submissions per second, in bursts of up to a second of them
+    pub submissions_per_sec: Option<u64>,
+}
+
+struct TokenBucket {
+    tokens: f64,
+    capacity: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    fn new(per_sec: u64) -> Self {
+        Self {
+            tokens: per_sec as f64,
+            capacity: per_sec as f64,
+            last_refill: Instant::now(),
+        }
+    }
+
+    /// Takes a token, or returns how long to wait for the next one
+    fn take(&mut self) -> Option<Duration> {
+        let now = Instant::now();
+        self.tokens = (self.tokens
+            + now.duration_since(self.last_refill).as_secs_f64() * self.capacity)
+            .min(self.capacity);
+        self.last_refill = now;
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            None
+        } else {
+            Some(Duration::from_secs_f64((1.0 - self.tokens) / self.capacity))
+        }
+    }
+}
+
+struct EndpointLimiter {
+    in_flight: Option<Semaphore>,
+    bucket: Option<Mutex<TokenBucket>>,
+}
+
+impl EndpointLimiter {
+    fn new(limits: EndpointLimits) -> Self {
+        Self {
+            in_flight: limits.max_in_flight.map(Semaphore::new),
+            bucket: limits
+                .submissions_per_sec
+                .map(|per_sec| Mutex::new(TokenBucket::new(per_sec))),
+        }
+    }
+
+    /// Waits for the endpoint to accept another submission, in flight until the permit drops
+    async fn acquire(&self) -> Option<SemaphorePermit<'_>> {
+        if let Some(bucket) = &self.bucket {
+            loop {
+                let wait = bucket.lock().take();
+                match wait {
+                    Some(wait) => tokio::time::sleep(wait).await,
+                    None => break,
+                }
+            }
+        }
+        match &self.in_flight {
+            Some(in_flight) => Some(in_flight.acquire().await.expect("Semaphore never closed")),
+            None => None,
+        }
+    }
+}
+
+/// Which failures of a setup transaction are worth another attempt
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RetryableErrors {
+    /// Every failure, even rejections the next attempt will get too
+    All,
+    /// Failures another attempt can fix: endpoints unreachable or overloaded, or
+    /// transactions not committed in time. Transactions rejected by validation (e.g.
+    /// invalid signature, insufficient balance), or committed but failed, are given up on.
+    Transient,
+}
+
+/// How the reliable submitter retries setup transactions (funding, publishing, initializing
+/// workloads) that didn't commit
+#[derive(Debug, Clone, Copy)]
+pub struct SubmissionRetryPolicy {
+    /// Attempts, before checking one last time whether the transaction committed
+    pub max_retries: usize,
+    /// How long the first attempt waits for the transaction to commit
+    pub initial_wait: Duration,
+    /// Factor the wait grows by on each attempt, 1.0 keeping it fixed
+    pub backoff_multiplier: f64,
+    pub max_wait: Duration,
+    pub retryable_errors: RetryableErrors,
+}
+
+impl SubmissionRetryPolicy {
+    /// Every failure retried, waiting the same time on each attempt
+    pub fn fixed(max_retries: usize, wait: Duration) -> Self {
+        Self {
+            max_retries,
+            initial_wait: wait,
+            backoff_multiplier: 1.0,
+            max_wait: wait,
+            retryable_errors: RetryableErrors::All,
+        }
+    }
+
+    fn wait(&self, attempt: usize) -> Duration {
+        // capped before converting back, as the uncapped wait can overflow a Duration
+        Duration::from_secs_f64(
+            (self.initial_wait.as_secs_f64() * self.backoff_multiplier.powi(attempt as i32))
+                .min(self.max_wait.as_secs_f64()),
+        )
+    }
+}
 
 // Reliable/retrying transaction executor, used for initializing
 pub struct RestApiReliableTransactionSubmitter {
     pub rest_clients: Vec<RestClient>,
-    pub max_retries: usize,
-    pub retry_after: Duration,
+    pub retry_policy: SubmissionRetryPolicy,
+    /// By path prefix of the endpoint, empty without limits
+    endpoint_limiters: HashMap<String, EndpointLimiter>,
+    /// When set, the first attempt of the transactions is submitted through the batch
+    /// endpoint, in batches of up to this size
+    submit_batch_size: Option<usize>,
 }
 
 impl RestApiReliableTransactionSubmitter {
+    pub fn new(
+        rest_clients: Vec<RestClient>,
+        retry_policy: SubmissionRetryPolicy,
+        endpoint_limits: EndpointLimits,
+    ) -> Self {
+        let endpoint_limiters = if endpoint_limits.max_in_flight.is_some()
+            || endpoint_limits.submissions_per_sec.is_some()
+        {
+            rest_clients
+                .iter()
+                .map(|client| {
+                    (
+                        client.path_prefix_string(),
+                        EndpointLimiter::new(endpoint_limits),
+                    )
+                })
+                .collect()
+        } else {
+            HashMap::new()
+        };
+        Self {
+            rest_clients,
+            retry_policy,
+            endpoint_limiters,
+            submit_batch_size: None,
+        }
+    }
+
+    pub fn with_submit_batch_size(mut self, submit_batch_size: usize) -> Self {
+        assert!(submit_batch_size > 0);
+        self.submit_batch_size = Some(submit_batch_size);
+        self
+    }
+
     fn random_rest_client(&self) -> &RestClient {
         let mut rng = thread_rng();
         self.rest_clients.choose(&mut rng).unwrap()
@@ -37,13 +205,99 @@ impl RestApiReliableTransactionSubmitter {
         self.rest_clients.choose(rng).unwrap()
     }
 
+    // All transactions from the same sender, need to be submitted to the same client
+    // in the same retry round, so that they are not placed in parking lot.
+    // Do so by selecting a client via seeded random selection.
+    fn rest_client_for_attempt(
+        &self,
+        txn: &SignedTransaction,
+        attempt: usize,
+        run_seed: u64,
+    ) -> &RestClient {
+        let seed = [
+            attempt.to_le_bytes().to_vec(),
+            run_seed.to_le_bytes().to_vec(),
+            txn.sender().to_vec(),
+        ]
+        .concat();
+        let mut seeded_rng = StdRng::from_seed(*aptos_crypto::HashValue::sha3_256_of(&seed));
+        self.random_rest_client_from_rng(&mut seeded_rng)
+    }
+
+    async fn acquire_endpoint(&self, rest_client: &RestClient) -> Option<SemaphorePermit<'_>> {
+        match self
+            .endpoint_limiters
+            .get(&rest_client.path_prefix_string())
+        {
+            Some(limiter) => limiter.acquire().await,
+            None => None,
+        }
+    }
+
+    /// Submits the first attempt of the transactions in batches, each to the endpoint it
+    /// would be submitted to on its own. Returns which ones the endpoints accepted, the
+    /// others are submitted on their own.
+    async fn submit_first_attempt_in_batches(
+        &self,
+        txns: &[SignedTransaction],
+        run_seed: u64,
+        batch_size: usize,
+    ) -> Vec<bool> {
+        let mut by_client: HashMap<String, (&RestClient, Vec<usize>)> = HashMap::new();
+        for (i, txn) in txns.iter().enumerate() {
+            let rest_client = self.rest_client_for_attempt(txn, 0, run_seed);
+            by_client
+                .entry(rest_client.path_prefix_string())
+                .or_insert_with(|| (rest_client, Vec::new()))
+                .1
+                .push(i);
+        }
+        let results = join_all(by_client.values().flat_map(|(rest_client, indices)| {
+            indices.chunks(batch_size).map(move |chunk| async move {
+                let _permit = self.acquire_endpoint(rest_client).await;
+                let batch = chunk.iter().map(|i| txns[*i].clone()).collect::<Vec<_>>();
+                (chunk, rest_client.submit_batch_bcs(&batch).await)
+            })
+        }))
+        .await;
+
+        let mut accepted = vec![false; txns.len()];
+        for (chunk, result) in results {
+            match result {
+                Ok(response) => {
+                    let failed = response
+                        .inner()
+                        .transaction_failures
+                        .iter()
+                        .map(|failure| failure.transaction_index)
+                        .collect::<HashSet<_>>();
+                    for (batch_index, i) in chunk.iter().enumerate() {
+                        accepted[*i] = !failed.contains(&batch_index);
+                    }
+                },
+                Err(err) => {
+                    sample!(
+                        SampleRate::Duration(Duration::from_secs(60)),
+                        warn!(
+                            "Failed to submit a batch of {} txns, submitting them one by one: {:?}",
+                            chunk.len(),
+                            err
+                        )
+                    );
+                },
+            }
+        }
+        accepted
+    }
+
     async fn submit_check_and_retry(
         &self,
         txn: &SignedTransaction,
         counters: &CounterState,
         run_seed: u64,
+        first_attempt_submitted: bool,
     ) -> Result<()> {
-        for i in 0..self.max_retries {
+        for i in 0..self.retry_policy.max_retries {
             sample!(
                 SampleRate::Duration(Duration::from_secs(60)),
                 debug!(
@@ -52,25 +306,20 @@ impl RestApiReliableTransactionSubmitter {
                 )
             );
 
-            // All transactions from the same sender, need to be submitted to the same client
-            // in the same retry round, so that they are not placed in parking lot.
-            // Do so by selecting a client via seeded random selection.
-            let seed = [
-                i.to_le_bytes().to_vec(),
-                run_seed.to_le_bytes().to_vec(),
-                txn.sender().to_vec(),
-            ]
-            .concat();
-            let mut seeded_rng = StdRng::from_seed(*aptos_crypto::HashValue::sha3_256_of(&seed));
-            let rest_client = self.random_rest_client_from_rng(&mut seeded_rng);
+            let rest_client = self.rest_client_for_attempt(txn, i, run_seed);
+            let _permit = self.acquire_endpoint(rest_client).await;
             let mut failed_submit = false;
             let mut failed_wait = false;
+            let mut failed_permanently = false;
             let result = submit_and_check(
                 rest_client,
                 txn,
-                self.retry_after,
+                i == 0 && first_attempt_submitted,
+                self.retry_policy.wait(i),
+                self.retry_policy.retryable_errors,
                 &mut failed_submit,
                 &mut failed_wait,
+                &mut failed_permanently,
             )
             .await;
 
@@ -113,6 +362,9 @@ impl RestApiReliableTransactionSubmitter {
                 }
                 return Ok(());
             };
+            if failed_permanently {
+                return result;
+            }
         }
 
         // if submission timeouts, it might still get committed:
@@ -186,20 +438,56 @@ async fn warn_detailed_error(
     );
 }
 
+/// Rejections that the next submission of the same transaction gets too
+fn is_rejected(error: &RestError) -> bool {
+    match error {
+        RestError::Api(api_error) => [
+            AptosErrorCode::VmError,
+            AptosErrorCode::InvalidInput,
+            AptosErrorCode::InvalidTransactionUpdate,
+        ]
+        .iter()
+        .any(|code| api_error.error.error_code as u32 == *code as u32),
+        _ => false,
+    }
+}
+
+/// Whether the transaction is committed already. A transaction committing after an earlier
+/// attempt stopped waiting on it is rejected when submitted again (with
+/// SEQUENCE_NUMBER_TOO_OLD), without having failed.
+async fn is_committed(rest_client: &RestClient, txn: &SignedTransaction) -> bool {
+    rest_client
+        .get_transaction_by_hash(txn.clone().committed_hash())
+        .await
+        .map_or(false, |response| !response.inner().is_pending())
+}
+
 async fn submit_and_check(
     rest_client: &RestClient,
     txn: &SignedTransaction,
+    already_submitted: bool,
     wait_duration: Duration,
+    retryable_errors: RetryableErrors,
     failed_submit: &mut bool,
     failed_wait: &mut bool,
+    failed_permanently: &mut bool,
 ) -> Result<()> {
     let start = Instant::now();
-    if let Err(err) = rest_client.submit_bcs(txn).await {
+    if already_submitted {
+        // submitted in a batch
+    } else if let Err(err) = rest_client.submit_bcs(txn).await {
         sample!(
             SampleRate::Duration(Duration::from_secs(60)),
             warn_detailed_error("submitting", rest_client, txn, Err(&err)).await
         );
         *failed_submit = true;
+        if retryable_errors == RetryableErrors::Transient
+            && is_rejected(&err)
+            && !is_committed(rest_client, txn).await
+        {
+            *failed_permanently = true;
+            Err(err)?;
+        }
         // even if txn fails submitting, it might get committed, so wait to see if that is the case.
     }
     match rest_client
@@ -227,6 +515,7 @@ async fn submit_and_check(
                     warn_detailed_error("waiting on a", rest_client, txn, Ok(transaction_info))
                         .await
                 );
+                *failed_permanently = retryable_errors == RetryableErrors::Transient;
                 anyhow::bail!(
                     "Transaction failed execution with VM status {}",
                     transaction_info.vm_status
@@ -265,11 +554,17 @@ impl ReliableTransactionSubmitter for RestApiReliableTransactionSubmitter {
         counters: &CounterState,
     ) -> Result<()> {
         let run_seed: u64 = thread_rng().gen();
+        let submitted = match self.submit_batch_size {
+            Some(batch_size) => {
+                self.submit_first_attempt_in_batches(txns, run_seed, batch_size)
+                    .await
+            },
+            None => vec![false; txns.len()],
+        };
 
-        join_all(
-            txns.iter()
-                .map(|txn| self.submit_check_and_retry(txn, counters, run_seed)),
-        )
+        join_all(txns.iter().zip(submitted).map(|(txn, submitted)| {
+            self.submit_check_and_retry(txn, counters, run_seed, submitted)
+        }))
         .await
         .into_iter()
         .collect::<Result<Vec<()>, anyhow::Error>>()
@@ -287,10 +582,10 @@ impl ReliableTransactionSubmitter for RestApiReliableTransactionSubmitter {
     fn create_counter_state(&self) -> CounterState {
         CounterState {
             submit_failures: std::iter::repeat_with(|| AtomicUsize::new(0))
-                .take(self.max_retries)
+                .take(self.retry_policy.max_retries)
                 .collect(),
             wait_failures: std::iter::repeat_with(|| AtomicUsize::new(0))
-                .take(self.max_retries)
+                .take(self.retry_policy.max_retries)
                 .collect(),
             successes: AtomicUsize::new(0),
             by_client: self
@@ -310,3 +605,251 @@ impl ReliableTransactionSubmitter for RestApiReliableTransactionSubmitter {
         }
     }
 }
+
+fn is_not_found(error: &RestError, error_code: AptosErrorCode) -> bool {
+    match error {
+        // error codes don't implement PartialEq
+        RestError::Api(api_error) => api_error.error.error_code as u32 == error_code as u32,
+        _ => false,
+    }
+}
+
+#[async_trait]
+impl TransactionOutputReader for RestApiReliableTransactionSubmitter {
+    async fn get_committed_transaction(
+        &self,
+        hash: HashValue,
+    ) -> Result<Option<CommittedTransaction>> {
+        let txn = match RETRY_POLICY
+            .retry_if(
+                move || self.random_rest_client().get_transaction_by_hash(hash),
+                |e: &RestError| !is_not_found(e, AptosErrorCode::TransactionNotFound),
+            )
+            .await
+        {
+            Ok(response) => response.into_inner(),
+            Err(e) if is_not_found(&e, AptosErrorCode::TransactionNotFound) => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        Ok(match txn {
+            Transaction::UserTransaction(txn) => Some(CommittedTransaction {
+                version: txn.info.version.0,
+                success: txn.info.success,
+                vm_status: txn.info.vm_status,
+                gas_used: txn.info.gas_used.0,
+                event_types: txn
+                    .events
+                    .iter()
+                    .map(|event| event.typ.to_string())
+                    .collect(),
+            }),
+            _ => None,
+        })
+    }
+
+    async fn get_resource_field_u64(
+        &self,
+        address: AccountAddress,
+        resource_type: &str,
+        field: &str,
+        version: u64,
+    ) -> Result<Option<u64>> {
+        let resource = match RETRY_POLICY
+            .retry_if(
+                move || {
+                    self.random_rest_client().get_account_resource_at_version(
+                        address,
+                        resource_type,
+                        version,
+                    )
+                },
+                |e: &RestError| !is_not_found(e, AptosErrorCode::ResourceNotFound),
+            )
+            .await
+        {
+            Ok(response) => response.into_inner(),
+            Err(e) if is_not_found(&e, AptosErrorCode::ResourceNotFound) => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        resource
+            .map(|resource| {
+                // u64 values are serialized as strings in json
+                let value = resource
+                    .data
+                    .get(field)
+                    .and_then(|value| value.as_str())
+                    .with_context(|| format!("No u64 field {} in {}", field, resource_type))?;
+                Ok(value.parse()?)
+            })
+            .transpose()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use aptos_rest_client::{
+        aptos_api_types::{
+            AptosError, StateCheckpointTransaction, X_APTOS_BLOCK_HEIGHT, X_APTOS_CHAIN_ID,
+            X_APTOS_EPOCH, X_APTOS_LEDGER_OLDEST_VERSION, X_APTOS_LEDGER_TIMESTAMP,
+            X_APTOS_LEDGER_VERSION, X_APTOS_OLDEST_BLOCK_HEIGHT,
+        },
+        error::AptosErrorResponse,
+    };
+    use aptos_sdk::{
+        transaction_builder::{aptos_stdlib, TransactionFactory},
+        types::{chain_id::ChainId, LocalAccount},
+    };
+    use httpmock::{MockServer, Then};
+    use reqwest::{StatusCode, Url};
+
+    fn api_error(error_code: AptosErrorCode) -> RestError {
+        RestError::Api(AptosErrorResponse {
+            error: AptosError {
+                message: "SEQUENCE_NUMBER_TOO_OLD".to_string(),
+                error_code,
+                vm_error_code: Some(3),
+            },
+            state: None,
+            status_code: StatusCode::BAD_REQUEST,
+        })
+    }
+
+    fn with_ledger_state(then: Then) -> Then {
+        [
+            (X_APTOS_CHAIN_ID, "4"),
+            (X_APTOS_EPOCH, "1"),
+            (X_APTOS_LEDGER_VERSION, "10"),
+            (X_APTOS_LEDGER_OLDEST_VERSION, "0"),
+            (X_APTOS_LEDGER_TIMESTAMP, "1"),
+            (X_APTOS_BLOCK_HEIGHT, "5"),
+            (X_APTOS_OLDEST_BLOCK_HEIGHT, "0"),
+        ]
+        .into_iter()
+        .fold(then, |then, (name, value)| then.header(name, value))
+    }
+
+    /// Server rejecting the transaction as already submitted, and returning it as committed
+    /// if `committed`
+    fn server_with_rejected_transaction(txn: &SignedTransaction, committed: bool) -> MockServer {
+        let server = MockServer::start();
+        server.mock(|when, then| {
+            when.method("POST").path("/v1/transactions");
+            then.status(400)
+                .json_body_obj(&AptosError::new_with_error_code(
+                    "SEQUENCE_NUMBER_TOO_OLD",
+                    AptosErrorCode::VmError,
+                ));
+        });
+        let by_hash = format!(
+            "/v1/transactions/by_hash/{}",
+            txn.clone().committed_hash().to_hex_literal()
+        );
+        server.mock(|when, then| {
+            when.method("GET").path(by_hash);
+            if committed {
+                with_ledger_state(then.status(200)).json_body_obj(
+                    &Transaction::StateCheckpointTransaction(StateCheckpointTransaction {
+                        info: TransactionInfo {
+                            version: 9u64.into(),
+                            hash: txn.clone().committed_hash().into(),
+                            state_change_hash: HashValue::zero().into(),
+                            event_root_hash: HashValue::zero().into(),
+                            state_checkpoint_hash: None,
+                            gas_used: 1u64.into(),
+                            success: true,
+                            vm_status: "Executed successfully".to_string(),
+                            accumulator_root_hash: HashValue::zero().into(),
+                            changes: vec![],
+                            block_height: None,
+                            epoch: None,
+                        },
+                        timestamp: 1u64.into(),
+                    }),
+                );
+            } else {
+                then.status(404)
+                    .json_body_obj(&AptosError::new_with_error_code(
+                        "Transaction not found",
+                        AptosErrorCode::TransactionNotFound,
+                    ));
+            }
+        });
+        server
+    }
+
+    async fn resubmit(server: &MockServer, txn: &SignedTransaction) -> (Result<()>, bool) {
+        let rest_client = RestClient::new(Url::parse(&server.base_url()).unwrap());
+        let (mut failed_submit, mut failed_wait, mut failed_permanently) = (false, false, false);
+        let result = submit_and_check(
+            &rest_client,
+            txn,
+            false,
+            Duration::from_secs(1),
+            RetryableErrors::Transient,
+            &mut failed_submit,
+            &mut failed_wait,
+            &mut failed_permanently,
+        )
+        .await;
+        assert!(failed_submit);
+        (result, failed_permanently)
+    }
+
+    fn transaction() -> SignedTransaction {
+        let mut account = LocalAccount::generate(&mut rand::thread_rng());
+        account.sign_with_transaction_builder(
+            TransactionFactory::new(ChainId::test())
+                .payload(aptos_stdlib::aptos_coin_transfer(AccountAddress::ONE, 1)),
+        )
+    }
+
+    #[test]
+    fn test_is_rejected() {
+        assert!(is_rejected(&api_error(AptosErrorCode::VmError)));
+        assert!(is_rejected(&api_error(AptosErrorCode::InvalidInput)));
+        assert!(!is_rejected(&api_error(AptosErrorCode::MempoolIsFull)));
+        assert!(!is_rejected(&RestError::Timeout("submitting")));
+    }
+
+    #[tokio::test]
+    async fn test_rejected_after_committing() {
+        let txn = transaction();
+        let server = server_with_rejected_transaction(&txn, true);
+        let (result, failed_permanently) = resubmit(&server, &txn).await;
+        assert!(result.is_ok());
+        assert!(!failed_permanently);
+    }
+
+    #[tokio::test]
+    async fn test_rejected_without_committing() {
+        let txn = transaction();
+        let server = server_with_rejected_transaction(&txn, false);
+        let (result, failed_permanently) = resubmit(&server, &txn).await;
+        assert!(result.is_err());
+        assert!(failed_permanently);
+    }
+
+    #[test]
+    fn test_token_bucket() {
+        let mut bucket = TokenBucket::new(10);
+        // starts with a second of submissions
+        for _ in 0..10 {
+            assert_eq!(bucket.take(), None);
+        }
+        let wait = bucket.take().unwrap();
+        assert!(wait > Duration::ZERO && wait <= Duration::from_millis(100));
+
+        // refills with the time passed, up to the capacity
+        bucket.last_refill -= Duration::from_millis(500);
+        for _ in 0..5 {
+            assert_eq!(bucket.take(), None);
+        }
+        assert!(bucket.take().is_some());
+        bucket.last_refill -= Duration::from_secs(10);
+        for _ in 0..10 {
+            assert_eq!(bucket.take(), None);
+        }
+        assert!(bucket.take().is_some());
+    }
+}
@@ -14,8 +14,12 @@ pub use args::{ClusterArgs, CoinSourceArgs, EmitArgs};
 // We export these if you want finer grained control.
 pub use cluster::Cluster;
 pub use emitter::{
+    control::EmitControl,
+    indexer_validation::IndexerValidationConfig,
+    load_shape::LoadShape,
     query_sequence_number, query_sequence_numbers,
     stats::{TxnStats, TxnStatsRate},
-    EmitJob, EmitJobMode, EmitJobRequest, EmitModeParams, TxnEmitter,
+    transaction_executor::{EndpointLimits, RetryableErrors, SubmissionRetryPolicy},
+    EmitJob, EmitJobMode, EmitJobRequest, EmitModeParams, SequenceNumberResync, TxnEmitter,
 };
 pub use wrappers::{emit_transactions, emit_transactions_with_cluster};
@@ -4,15 +4,30 @@
 use crate::{
     args::{ClusterArgs, EmitArgs},
     cluster::Cluster,
-    emitter::{stats::TxnStats, EmitJobMode, EmitJobRequest, TxnEmitter},
+    emitter::{
+        indexer_validation::IndexerValidationConfig,
+        load_shape::LoadShape,
+        stats::{write_phase_results, TxnStats},
+        transaction_executor::{EndpointLimits, RetryableErrors, SubmissionRetryPolicy},
+        EmitJobMode, EmitJobRequest, TxnEmitter,
+    },
     instance::Instance,
 };
 use anyhow::{bail, Context, Result};
 use aptos_logger::{error, info};
+use aptos_rest_client::Client as RestClient;
 use aptos_sdk::transaction_builder::TransactionFactory;
-use aptos_transaction_generator_lib::args::TransactionTypeArg;
-use rand::{rngs::StdRng, SeedableRng};
-use std::time::{Duration, Instant};
+use aptos_transaction_generator_lib::{
+    args::TransactionTypeArg, EntryFunctionWorkload, EntryPoints, RecipientList, ReplayWorkload,
+    TransactionType, WorkloadSchedule,
+};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use std::{
+    cmp::min,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use url::Url;
 
 pub async fn emit_transactions(
     cluster_args: &ClusterArgs,
@@ -73,23 +88,195 @@ pub async fn emit_transactions_with_cluster(
 ) -> Result<TxnStats> {
     let emitter_mode = EmitJobMode::create(args.mempool_backlog, args.target_tps);
 
-    let duration = Duration::from_secs(args.duration);
+    let workload_schedule = args
+        .workload_schedule
+        .as_ref()
+        .map(|path| WorkloadSchedule::load(path))
+        .transpose()?;
+    // each step of a stepped load is a stats phase, so the TPS and latency at each step are reported
+    let steps = args
+        .load_shape
+        .map(|shape| shape.steps())
+        .unwrap_or_default();
+    if let Some(LoadShape::Steps { max_tps, .. }) = args.load_shape {
+        if args.target_tps != Some(max_tps) {
+            bail!("Stepped load shape needs --target-tps set to its max TPS");
+        }
+        if workload_schedule.is_some() {
+            bail!("Stepped load shape cannot be combined with --workload-schedule");
+        }
+        for (step, (step_duration, step_tps)) in steps.iter().enumerate() {
+            info!(
+                "Step {}: {} TPS for {}s",
+                step,
+                step_tps,
+                step_duration.as_secs()
+            );
+        }
+    }
+    let duration = match &workload_schedule {
+        Some(workload_schedule) => workload_schedule.phase_durations().iter().sum(),
+        None if !steps.is_empty() => steps.iter().map(|(step_duration, _)| *step_duration).sum(),
+        None => Duration::from_secs(args.duration),
+    };
     let client = cluster.random_instance().rest_client();
     let mut coin_source_account = cluster.load_coin_source_account(&client).await?;
+    let seed = args.seed.unwrap_or_else(|| StdRng::from_entropy().gen());
+    info!("Seed: {}", seed);
     let emitter = TxnEmitter::new(
         TransactionFactory::new(cluster.chain_id)
             .with_transaction_expiration_time(args.txn_expiration_time_secs)
             .with_gas_unit_price(aptos_global_constants::GAS_UNIT_PRICE),
-        StdRng::from_entropy(),
+        StdRng::seed_from_u64(seed),
     );
 
-    let transaction_mix_per_phase = TransactionTypeArg::args_to_transaction_mix_per_phase(
-        &args.transaction_type,
-        &args.transaction_weights,
-        &args.transaction_phases,
-        args.module_working_set_size.unwrap_or(1),
-        args.sender_use_account_pool.unwrap_or(false),
-    );
+    let transaction_mix_per_phase = if let Some(path) = &args.entry_function_workload {
+        vec![vec![(
+            TransactionType::CallEntryFunction {
+                workload: EntryFunctionWorkload::load(path)?,
+                use_account_pool: args.sender_use_account_pool.unwrap_or(false),
+            },
+            1,
+        )]]
+    } else if let Some(entry_function) = &args.entry_function {
+        vec![vec![(
+            TransactionType::CallEntryFunction {
+                workload: EntryFunctionWorkload::from_cli(
+                    entry_function,
+                    &args.entry_function_type_args,
+                    &args.entry_function_args,
+                )?,
+                use_account_pool: args.sender_use_account_pool.unwrap_or(false),
+            },
+            1,
+        )]]
+    } else if let Some(conflict_percentage) = args.conflict_percentage {
+        vec![vec![(
+            TransactionType::ConflictRate {
+                num_modules: args.module_working_set_size.unwrap_or(1),
+                conflict_percentage: conflict_percentage as usize,
+                use_account_pool: args.sender_use_account_pool.unwrap_or(false),
+            },
+            1,
+        )]]
+    } else if let Some(workload_schedule) = &workload_schedule {
+        for (phase, (name, phase_duration)) in workload_schedule
+            .phase_names()
+            .into_iter()
+            .zip(workload_schedule.phase_durations())
+            .enumerate()
+        {
+            info!(
+                "Phase {}: mix {} for {} secs",
+                phase,
+                name,
+                phase_duration.as_secs()
+            );
+        }
+        workload_schedule.transaction_mix_per_phase(
+            args.module_working_set_size.unwrap_or(1),
+            args.sender_use_account_pool.unwrap_or(false),
+        )
+    } else if let Some(batch_transfer_size) = args.batch_transfer_size {
+        vec![vec![(
+            TransactionType::BatchTransfer {
+                batch_size: batch_transfer_size as usize,
+            },
+            1,
+        )]]
+    } else if let Some(write_set_num_slots) = args.write_set_num_slots {
+        // within the default limits of 1MB per write and 10MB per transaction
+        if args.write_set_slot_size > 1 << 20
+            || write_set_num_slots.saturating_mul(args.write_set_slot_size) > 10 << 20
+        {
+            bail!(
+                "Write set of {} slots of {} bytes is above the limits of a transaction",
+                write_set_num_slots,
+                args.write_set_slot_size
+            );
+        }
+        vec![vec![(
+            TransactionType::CallCustomModules {
+                entry_point: EntryPoints::WriteSlots {
+                    num_slots: write_set_num_slots,
+                    slot_size: args.write_set_slot_size,
+                    // offsets of consecutive transactions of an account rarely overlap,
+                    // so most of the writes allocate new slots at first
+                    max_offset: 1_000_000,
+                },
+                num_modules: args.module_working_set_size.unwrap_or(1),
+                use_account_pool: args.sender_use_account_pool.unwrap_or(false),
+            },
+            1,
+        )]]
+    } else if let Some(events_per_txn) = args.events_per_txn {
+        // within the default limits of 1MB per event and 10MB per transaction
+        if args.event_size > 1 << 20 || events_per_txn.saturating_mul(args.event_size) > 10 << 20 {
+            bail!(
+                "{} events of {} bytes are above the limits of a transaction",
+                events_per_txn,
+                args.event_size
+            );
+        }
+        vec![vec![(
+            TransactionType::CallCustomModules {
+                entry_point: EntryPoints::EmitSizedEvents {
+                    count: events_per_txn,
+                    event_size: args.event_size,
+                },
+                num_modules: args.module_working_set_size.unwrap_or(1),
+                use_account_pool: args.sender_use_account_pool.unwrap_or(false),
+            },
+            1,
+        )]]
+    } else if let Some(num_secondary_signers) = args.multi_agent_secondary_signers {
+        vec![vec![(
+            TransactionType::MultiAgent {
+                num_secondary_signers: num_secondary_signers as usize,
+            },
+            1,
+        )]]
+    } else if let Some(replay_from) = &args.replay_from {
+        let workload = fetch_replay_workload(
+            replay_from,
+            args.replay_start_version,
+            args.replay_num_transactions,
+        )
+        .await?;
+        vec![vec![(
+            TransactionType::Replay {
+                workload: Arc::new(workload),
+                num_accounts: args.replay_num_accounts,
+            },
+            1,
+        )]]
+    } else {
+        TransactionTypeArg::args_to_transaction_mix_per_phase(
+            &args.transaction_type,
+            &args.transaction_weights,
+            &args.transaction_phases,
+            &args.transaction_gas_prices,
+            args.module_working_set_size.unwrap_or(1),
+            args.sender_use_account_pool.unwrap_or(false),
+        )
+    };
+    let transaction_mix_per_phase = if let Some(path) = &args.recipients_file {
+        let recipients = RecipientList::load(path)?;
+        info!("Sending to {} recipients", recipients.addresses().len());
+        transaction_mix_per_phase
+            .into_iter()
+            .map(|transaction_mix| {
+                transaction_mix
+                    .into_iter()
+                    .map(|(transaction_type, weight)| {
+                        (transaction_type.with_recipients(recipients.clone()), weight)
+                    })
+                    .collect()
+            })
+            .collect()
+    } else {
+        transaction_mix_per_phase
+    };
     let mut emit_job_request =
         EmitJobRequest::new(cluster.all_instances().map(Instance::rest_client).collect())
             .mode(emitter_mode)
@@ -101,6 +288,67 @@ pub async fn emit_transactions_with_cluster(
     if reuse_accounts {
         emit_job_request = emit_job_request.reuse_accounts();
     }
+    if let Some(accounts_file) = &args.accounts_file {
+        emit_job_request = emit_job_request.accounts_file(accounts_file.clone());
+    }
+    if let Some(account_shard) = args.account_shard {
+        emit_job_request = emit_job_request.account_shard(account_shard);
+    }
+    if let Some(control_api_address) = args.control_api_address {
+        emit_job_request = emit_job_request.control_api_address(control_api_address);
+    }
+    if let Some(invalid_transaction_percentage) = args.invalid_transaction_percentage {
+        emit_job_request = emit_job_request
+            .invalid_transaction_percentage(invalid_transaction_percentage as usize);
+    }
+    if let Some(verification_percentage) = args.verification_percentage {
+        emit_job_request =
+            emit_job_request.verification_percentage(verification_percentage as usize);
+    }
+    if let Some(graphql_url) = args.indexer_graphql_url.clone() {
+        emit_job_request = emit_job_request.indexer_validation(IndexerValidationConfig {
+            graphql_url,
+            catch_up_timeout: Duration::from_secs(args.indexer_catch_up_timeout_secs),
+            num_accounts: args.indexer_validation_accounts,
+        });
+    }
+    if let Some(init_max_retries) = args.init_max_retries {
+        if args.init_retry_backoff.is_nan() || args.init_retry_backoff < 1.0 {
+            bail!(
+                "--init-retry-backoff needs to be at least 1, got {}",
+                args.init_retry_backoff
+            );
+        }
+        emit_job_request = emit_job_request.init_retry_policy(SubmissionRetryPolicy {
+            max_retries: init_max_retries as usize,
+            initial_wait: Duration::from_secs(args.init_retry_wait_secs),
+            backoff_multiplier: args.init_retry_backoff,
+            max_wait: Duration::from_secs(args.init_max_retry_wait_secs),
+            retryable_errors: if args.init_retry_transient_errors_only {
+                RetryableErrors::Transient
+            } else {
+                RetryableErrors::All
+            },
+        });
+    }
+    emit_job_request = emit_job_request.endpoint_limits(EndpointLimits {
+        max_in_flight: args.endpoint_max_in_flight.map(|max| max as usize),
+        submissions_per_sec: args.endpoint_submissions_per_sec,
+    });
+    if let Some(init_submit_batch_size) = args.init_submit_batch_size {
+        emit_job_request = emit_job_request.init_submit_batch_size(init_submit_batch_size as usize);
+    }
+    if let Some(workload_schedule) = &workload_schedule {
+        emit_job_request = emit_job_request.phase_durations(workload_schedule.phase_durations());
+    }
+    if !steps.is_empty() {
+        emit_job_request = emit_job_request.phase_durations(
+            steps
+                .iter()
+                .map(|(step_duration, _)| *step_duration)
+                .collect(),
+        );
+    }
     if let Some(max_transactions_per_account) = args.max_transactions_per_account {
         emit_job_request =
             emit_job_request.max_transactions_per_account(max_transactions_per_account);
@@ -124,17 +372,98 @@ pub async fn emit_transactions_with_cluster(
     if let Some(expected_gas_per_txn) = args.expected_gas_per_txn {
         emit_job_request = emit_job_request.expected_gas_per_txn(expected_gas_per_txn);
     }
+    if let Some(num_sponsors) = args.num_sponsors {
+        emit_job_request = emit_job_request.num_sponsors(num_sponsors);
+    }
+    if let Some(account_pool_refill) = args.account_pool_refill {
+        emit_job_request = emit_job_request.account_pool_refill(account_pool_refill);
+    }
+    if let Some(signer_key_type) = args.signer_key_type {
+        emit_job_request = emit_job_request.signer_key_type(signer_key_type);
+    }
+    if let Some(load_shape) = args.load_shape {
+        emit_job_request = emit_job_request.load_shape(load_shape);
+    }
+    if let Some(sequence_number_resync) = args.sequence_number_resync {
+        emit_job_request = emit_job_request.sequence_number_resync(sequence_number_resync);
+    }
+    if let Some(warmup_secs) = args.warmup_secs {
+        emit_job_request = emit_job_request.warmup_duration(Duration::from_secs(warmup_secs));
+    }
     if !cluster.coin_source_is_root {
         emit_job_request = emit_job_request.prompt_before_spending();
     }
 
     let stats = emitter
-        .emit_txn_for_with_stats(
+        .emit_txn_for_with_phase_stats(
             &mut coin_source_account,
             emit_job_request,
             duration,
-            (args.duration / 10).clamp(1, 10),
+            (duration.as_secs() / 10).clamp(1, 10),
         )
         .await?;
-    Ok(stats)
+    if let Some(results_output) = &args.results_output {
+        write_phase_results(&stats, seed, results_output)
+            .with_context(|| format!("Failed to write results to {}", results_output.display()))?;
+        info!("Wrote results to {}", results_output.display());
+    }
+    if let Some(batch_transfer_size) = args.batch_transfer_size {
+        info!(
+            "Effective transfers: {} transfers/s",
+            stats[0].rate().committed * batch_transfer_size
+        );
+    }
+    if let Some(write_set_num_slots) = args.write_set_num_slots {
+        let written_slots = stats[0].rate().committed * write_set_num_slots;
+        info!(
+            "Effective writes: {} slots/s, {} bytes/s",
+            written_slots,
+            written_slots * args.write_set_slot_size
+        );
+    }
+    if let Some(events_per_txn) = args.events_per_txn {
+        let emitted_events = stats[0].rate().committed * events_per_txn;
+        info!(
+            "Effective events: {} events/s, {} bytes/s",
+            emitted_events,
+            emitted_events * args.event_size
+        );
+    }
+    Ok(stats.into_iter().next().unwrap())
+}
+
+/// Fetch the historical transactions to replay, page by page
+async fn fetch_replay_workload(
+    url: &Url,
+    start_version: u64,
+    num_transactions: u64,
+) -> Result<ReplayWorkload> {
+    const PAGE_SIZE: u16 = 100;
+    let client = RestClient::new(url.clone());
+    let mut txns = Vec::new();
+    let mut version = start_version;
+    while version < start_version + num_transactions {
+        let limit = min(PAGE_SIZE as u64, start_version + num_transactions - version) as u16;
+        let page = client
+            .get_transactions_bcs(Some(version), Some(limit))
+            .await
+            .with_context(|| format!("Failed to fetch transactions at version {}", version))?
+            .into_inner();
+        if page.is_empty() {
+            break;
+        }
+        version += page.len() as u64;
+        txns.extend(page.into_iter().map(|txn| txn.transaction));
+    }
+    let workload = ReplayWorkload::from_transactions(txns);
+    info!(
+        "Fetched {} transactions to replay from {}, starting at version {}",
+        workload.len(),
+        url,
+        start_version
+    );
+    if workload.is_empty() {
+        bail!("No framework entry function transactions to replay");
+    }
+    Ok(workload)
 }
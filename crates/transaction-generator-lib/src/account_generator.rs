@@ -1,6 +1,9 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
-use crate::{create_account_transaction, TransactionGenerator, TransactionGeneratorCreator};
+use crate::{
+    create_account_transaction, new_rng, AccountsPool, TransactionGenerator,
+    TransactionGeneratorCreator,
+};
 use aptos_infallible::RwLock;
 use aptos_logger::{info, sample, sample::SampleRate};
 use aptos_sdk::{
@@ -8,14 +11,14 @@ use aptos_sdk::{
     transaction_builder::TransactionFactory,
     types::{transaction::SignedTransaction, LocalAccount},
 };
-use rand::{rngs::StdRng, Rng, SeedableRng};
+use rand::{rngs::StdRng, Rng};
 use std::{sync::Arc, time::Duration};
 
 pub struct AccountGenerator {
     rng: StdRng,
     txn_factory: TransactionFactory,
     addresses_pool: Arc<RwLock<Vec<AccountAddress>>>,
-    accounts_pool: Arc<RwLock<Vec<LocalAccount>>>,
+    accounts_pool: Arc<dyn AccountsPool>,
     add_created_accounts_to_pool: bool,
     max_working_set: usize,
     creation_balance: u64,
@@ -26,7 +29,7 @@ impl AccountGenerator {
         rng: StdRng,
         txn_factory: TransactionFactory,
         addresses_pool: Arc<RwLock<Vec<AccountAddress>>>,
-        accounts_pool: Arc<RwLock<Vec<LocalAccount>>>,
+        accounts_pool: Arc<dyn AccountsPool>,
         add_created_accounts_to_pool: bool,
         max_working_set: usize,
         creation_balance: u64,
@@ -71,6 +74,32 @@ fn add_to_sized_pool<T>(
     }
 }
 
+/// Same as `add_to_sized_pool`, for the pool of accounts
+fn add_to_sized_accounts_pool(
+    pool: &dyn AccountsPool,
+    addition: Vec<LocalAccount>,
+    max_working_set: usize,
+    rng: &mut StdRng,
+) {
+    if pool.len() < max_working_set {
+        pool.add_n(addition);
+        sample!(
+            SampleRate::Duration(Duration::from_secs(120)),
+            info!("Accounts working set increased to {}", pool.len())
+        );
+    } else {
+        pool.replace_random_n(addition, rng);
+        sample!(
+            SampleRate::Duration(Duration::from_secs(120)),
+            info!(
+                "Already at limit {} > {}, so exchanged accounts in working set",
+                pool.len(),
+                max_working_set
+            )
+        );
+    }
+}
+
 impl TransactionGenerator for AccountGenerator {
     fn generate_transactions(
         &mut self,
@@ -95,7 +124,7 @@ impl TransactionGenerator for AccountGenerator {
         }
 
         if self.add_created_accounts_to_pool {
-            add_to_sized_pool(
+            add_to_sized_accounts_pool(
                 self.accounts_pool.as_ref(),
                 new_accounts,
                 self.max_working_set,
@@ -115,24 +144,25 @@ impl TransactionGenerator for AccountGenerator {
 pub struct AccountGeneratorCreator {
     txn_factory: TransactionFactory,
     addresses_pool: Arc<RwLock<Vec<AccountAddress>>>,
-    accounts_pool: Arc<RwLock<Vec<LocalAccount>>>,
+    accounts_pool: Arc<dyn AccountsPool>,
     add_created_accounts_to_pool: bool,
     max_working_set: usize,
     creation_balance: u64,
+    rng: StdRng,
 }
 
 impl AccountGeneratorCreator {
     pub fn new(
         txn_factory: TransactionFactory,
         addresses_pool: Arc<RwLock<Vec<AccountAddress>>>,
-        accounts_pool: Arc<RwLock<Vec<LocalAccount>>>,
+        accounts_pool: Arc<dyn AccountsPool>,
         add_created_accounts_to_pool: bool,
         max_working_set: usize,
         creation_balance: u64,
+        rng: StdRng,
     ) -> Self {
         if add_created_accounts_to_pool {
             addresses_pool.write().reserve(max_working_set);
-            accounts_pool.write().reserve(max_working_set);
         }
 
         Self {
@@ -142,6 +172,7 @@ impl AccountGeneratorCreator {
             add_created_accounts_to_pool,
             max_working_set,
             creation_balance,
+            rng,
         }
     }
 }
@@ -149,7 +180,7 @@ impl AccountGeneratorCreator {
 impl TransactionGeneratorCreator for AccountGeneratorCreator {
     fn create_transaction_generator(&mut self) -> Box<dyn TransactionGenerator> {
         Box::new(AccountGenerator::new(
-            StdRng::from_entropy(),
+            new_rng(&mut self.rng),
             self.txn_factory.clone(),
             self.addresses_pool.clone(),
             self.accounts_pool.clone(),
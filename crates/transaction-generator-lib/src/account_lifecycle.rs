@@ -0,0 +1,122 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{new_rng, TransactionGenerator, TransactionGeneratorCreator, SEND_AMOUNT};
+use aptos_sdk::{
+    transaction_builder::{aptos_stdlib, TransactionFactory},
+    types::{transaction::SignedTransaction, LocalAccount},
+};
+use rand::rngs::StdRng;
+use std::collections::VecDeque;
+
+/// Workload taking brand-new accounts through their whole life, so every transaction
+/// allocates or touches cold storage slots, instead of reusing a warmed-up pool:
+/// the source account creates the account (registering its coin store) and funds it,
+/// then the new account transfers to the source, and retires by sending back its funds,
+/// except for what pays for its own gas. It is dropped afterwards.
+///
+/// Each lifecycle step is a pair of transactions from the same sender, so
+/// `num_to_create` is the number of steps.
+pub struct AccountLifecycleGenerator {
+    rng: StdRng,
+    txn_factory: TransactionFactory,
+    funded: VecDeque<FundedAccount>,
+}
+
+/// Account created and funded, retired once its funding transaction expired
+struct FundedAccount {
+    account: LocalAccount,
+    balance: u64,
+    expiration_secs: u64,
+}
+
+impl AccountLifecycleGenerator {
+    /// Gas of the two transactions of the new account, kept when retiring
+    fn gas_reserve(&self) -> u64 {
+        2 * self.txn_factory.get_max_gas_amount() * self.txn_factory.get_gas_unit_price()
+    }
+
+    /// Funded accounts are only used once their funding transaction expired,
+    /// so it is committed (or never will be), whichever batch it was in
+    fn pop_ready(&mut self) -> Option<FundedAccount> {
+        let now_secs = aptos_infallible::duration_since_epoch().as_secs();
+        match self.funded.front() {
+            Some(funded) if funded.expiration_secs < now_secs => self.funded.pop_front(),
+            _ => None,
+        }
+    }
+}
+
+impl TransactionGenerator for AccountLifecycleGenerator {
+    fn generate_transactions(
+        &mut self,
+        account: &mut LocalAccount,
+        num_to_create: usize,
+    ) -> Vec<SignedTransaction> {
+        let num_steps = (num_to_create / 2).max(1);
+        let mut requests = Vec::with_capacity(num_steps * 2);
+        for _ in 0..num_steps {
+            match self.pop_ready() {
+                Some(FundedAccount {
+                    account: mut retiring,
+                    balance,
+                    ..
+                }) => {
+                    requests.push(
+                        retiring.sign_with_transaction_builder(self.txn_factory.payload(
+                            aptos_stdlib::aptos_account_transfer(account.address(), SEND_AMOUNT),
+                        )),
+                    );
+                    // all that is left besides the gas reserve
+                    let remaining = balance - SEND_AMOUNT - self.gas_reserve();
+                    requests.push(retiring.sign_with_transaction_builder(
+                        self.txn_factory.payload(aptos_stdlib::aptos_coin_transfer(
+                            account.address(),
+                            remaining,
+                        )),
+                    ));
+                },
+                None => {
+                    let new_account = LocalAccount::generate(&mut self.rng);
+                    requests.push(
+                        account.sign_with_transaction_builder(self.txn_factory.payload(
+                            aptos_stdlib::aptos_account_create_account(new_account.address()),
+                        )),
+                    );
+                    let balance = 2 * SEND_AMOUNT + self.gas_reserve();
+                    let fund = account.sign_with_transaction_builder(self.txn_factory.payload(
+                        aptos_stdlib::aptos_coin_transfer(new_account.address(), balance),
+                    ));
+                    self.funded.push_back(FundedAccount {
+                        account: new_account,
+                        balance,
+                        expiration_secs: fund.expiration_timestamp_secs(),
+                    });
+                    requests.push(fund);
+                },
+            }
+        }
+        requests
+    }
+}
+
+pub struct AccountLifecycleGeneratorCreator {
+    txn_factory: TransactionFactory,
+    rng: StdRng,
+}
+
+impl AccountLifecycleGeneratorCreator {
+    pub fn new(txn_factory: TransactionFactory, rng: StdRng) -> Self {
+        Self { txn_factory, rng }
+    }
+}
+
+impl TransactionGeneratorCreator for AccountLifecycleGeneratorCreator {
+    fn create_transaction_generator(&mut self) -> Box<dyn TransactionGenerator> {
+        Box::new(AccountLifecycleGenerator {
+            rng: new_rng(&mut self.rng),
+            txn_factory: self.txn_factory.clone(),
+            funded: VecDeque::new(),
+        })
+    }
+}
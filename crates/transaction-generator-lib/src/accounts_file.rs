@@ -0,0 +1,272 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::{bail, Context, Result};
+use aptos_sdk::{
+    crypto::{ed25519::Ed25519PrivateKey, ValidCryptoMaterialStringExt},
+    move_types::account_address::AccountAddress,
+    types::LocalAccount,
+};
+use serde::{Deserialize, Serialize};
+use std::{fmt, fs, path::Path, str::FromStr, sync::Arc};
+
+/// Account as stored on disk, so later runs can reuse it instead of creating
+/// and funding a new one. The sequence number is only a hint, it needs to be
+/// re-synced from the chain when loading.
+#[derive(Debug, Deserialize, Serialize)]
+struct StoredAccount {
+    address: AccountAddress,
+    /// Hex encoded ed25519 private key
+    private_key: String,
+    sequence_number: u64,
+}
+
+/// Writes the keys and sequence numbers of the accounts to a yaml file.
+/// The file holds private keys, so it should only be used with test funds.
+pub fn save_accounts(path: &Path, accounts: &[LocalAccount]) -> Result<()> {
+    let stored = accounts
+        .iter()
+        .map(|account| {
+            Ok(StoredAccount {
+                address: account.address(),
+                private_key: account.private_key().to_encoded_string()?,
+                sequence_number: account.sequence_number(),
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+    fs::write(path, serde_yaml::to_string(&stored)?)
+        .with_context(|| format!("Failed to write accounts to {:?}", path))
+}
+
+/// Reads accounts written by `save_accounts`, with the sequence numbers they had then.
+pub fn load_accounts(path: &Path) -> Result<Vec<LocalAccount>> {
+    let yaml = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read accounts from {:?}", path))?;
+    let stored: Vec<StoredAccount> = serde_yaml::from_str(&yaml)?;
+    stored
+        .into_iter()
+        .map(|account| {
+            let private_key = Ed25519PrivateKey::from_encoded_string(&account.private_key)
+                .with_context(|| format!("Invalid private key of {}", account.address))?;
+            Ok(LocalAccount::new(
+                account.address,
+                private_key,
+                account.sequence_number,
+            ))
+        })
+        .collect()
+}
+
+/// How the accounts of a shared pool are split between shards
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub enum AccountShardMode {
+    /// Contiguous ranges of the accounts, in the order of the file
+    Range,
+    /// By hash of the address, independent of the order of the file
+    Hash,
+}
+
+/// Shard `index` out of `count` of a shared account pool, so several emitter processes
+/// (e.g. on different machines) can use the same funded pool without sending from the
+/// same accounts, and so without sequence number collisions.
+///
+/// Parsed from `<index>/<count>` for a range shard, or `hash:<index>/<count>`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct AccountShard {
+    pub index: usize,
+    pub count: usize,
+    pub mode: AccountShardMode,
+}
+
+impl AccountShard {
+    /// Keeps the accounts of this shard, shards being disjoint and covering all accounts
+    pub fn select(&self, accounts: Vec<LocalAccount>) -> Vec<LocalAccount> {
+        match self.mode {
+            AccountShardMode::Range => {
+                let start = accounts.len() * self.index / self.count;
+                let end = accounts.len() * (self.index + 1) / self.count;
+                accounts.into_iter().skip(start).take(end - start).collect()
+            },
+            AccountShardMode::Hash => accounts
+                .into_iter()
+                .filter(|account| self.contains_address(account.address()))
+                .collect(),
+        }
+    }
+
+    fn contains_address(&self, address: AccountAddress) -> bool {
+        let bytes = address.into_bytes();
+        let mut hash = [0u8; 8];
+        hash.copy_from_slice(&bytes[bytes.len() - 8..]);
+        u64::from_le_bytes(hash) % self.count as u64 == self.index as u64
+    }
+}
+
+impl FromStr for AccountShard {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let (mode, shard) = match s.strip_prefix("hash:") {
+            Some(shard) => (AccountShardMode::Hash, shard),
+            None => (AccountShardMode::Range, s),
+        };
+        let (index, count) = shard
+            .split_once('/')
+            .with_context(|| format!("Expected [hash:]<index>/<count>, got {}", s))?;
+        let (index, count) = (index.parse::<usize>()?, count.parse::<usize>()?);
+        if index >= count {
+            bail!(
+                "Shard index {} needs to be below the count {}",
+                index,
+                count
+            );
+        }
+        Ok(Self { index, count, mode })
+    }
+}
+
+impl fmt::Display for AccountShard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.mode == AccountShardMode::Hash {
+            write!(f, "hash:")?;
+        }
+        write!(f, "{}/{}", self.index, self.count)
+    }
+}
+
+/// Addresses transfers are sent to instead of the accounts of the run, e.g. active accounts
+/// exported from mainnet, so the transfers read and write cold, realistically spread state.
+/// Read from a file with an address per line, empty lines and `#` comments being skipped.
+pub struct RecipientList {
+    addresses: Vec<AccountAddress>,
+}
+
+impl RecipientList {
+    pub fn from_str_lines(lines: &str) -> Result<Self> {
+        let mut addresses = Vec::new();
+        for (i, line) in lines.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            addresses.push(
+                AccountAddress::from_str(line)
+                    .with_context(|| format!("Invalid address {:?} on line {}", line, i + 1))?,
+            );
+        }
+        if addresses.is_empty() {
+            bail!("No recipient addresses");
+        }
+        Ok(Self { addresses })
+    }
+
+    pub fn load(path: &Path) -> Result<Arc<Self>> {
+        let lines = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read recipients from {:?}", path))?;
+        let recipients = Self::from_str_lines(&lines)
+            .with_context(|| format!("Invalid recipients file {:?}", path))?;
+        Ok(Arc::new(recipients))
+    }
+
+    pub fn addresses(&self) -> &[AccountAddress] {
+        &self.addresses
+    }
+}
+
+// the list can be large, and is part of the logged workload
+impl fmt::Debug for RecipientList {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "RecipientList({} addresses)", self.addresses.len())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use aptos_temppath::TempPath;
+    use rand::{rngs::StdRng, SeedableRng};
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_save_load_accounts() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let accounts: Vec<_> = (0..3)
+            .map(|i| {
+                let mut account = LocalAccount::generate(&mut rng);
+                *account.sequence_number_mut() = i * 10;
+                account
+            })
+            .collect();
+        let path = TempPath::new();
+        save_accounts(path.path(), &accounts).unwrap();
+
+        let loaded = load_accounts(path.path()).unwrap();
+        assert_eq!(loaded.len(), accounts.len());
+        for (account, loaded) in accounts.iter().zip(loaded.iter()) {
+            assert_eq!(loaded.address(), account.address());
+            assert_eq!(
+                loaded.private_key().to_bytes(),
+                account.private_key().to_bytes()
+            );
+            assert_eq!(loaded.sequence_number(), account.sequence_number());
+        }
+    }
+
+    #[test]
+    fn test_account_shards_disjoint() {
+        // the same accounts for every shard, private keys can't be cloned
+        let accounts = || {
+            let mut rng = StdRng::seed_from_u64(0);
+            (0..100)
+                .map(|_| LocalAccount::generate(&mut rng))
+                .collect::<Vec<_>>()
+        };
+        for mode in ["", "hash:"] {
+            let mut seen = HashSet::new();
+            for index in 0..7 {
+                let shard = AccountShard::from_str(&format!("{}{}/7", mode, index)).unwrap();
+                assert_eq!(shard.to_string(), format!("{}{}/7", mode, index));
+                let selected = shard.select(accounts());
+                if mode.is_empty() {
+                    assert!(selected.len() == 14 || selected.len() == 15);
+                }
+                for account in selected {
+                    assert!(seen.insert(account.address()), "{} in two shards", shard);
+                }
+            }
+            assert_eq!(seen.len(), 100);
+        }
+    }
+
+    #[test]
+    fn test_parse_account_shard() {
+        assert_eq!(AccountShard::from_str("2/3").unwrap(), AccountShard {
+            index: 2,
+            count: 3,
+            mode: AccountShardMode::Range,
+        });
+        assert_eq!(AccountShard::from_str("hash:0/1").unwrap(), AccountShard {
+            index: 0,
+            count: 1,
+            mode: AccountShardMode::Hash,
+        });
+        for invalid in ["3/3", "1", "a/2", "hash:1/0", "range:0/2"] {
+            assert!(AccountShard::from_str(invalid).is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_recipient_list_from_str_lines() {
+        let recipients =
+            RecipientList::from_str_lines("# exported from mainnet\n0x1\n\n  0xcafe  \n# done\n")
+                .unwrap();
+        assert_eq!(recipients.addresses(), &[
+            AccountAddress::ONE,
+            AccountAddress::from_hex_literal("0xcafe").unwrap()
+        ]);
+
+        let err = RecipientList::from_str_lines("0x1\nnot an address\n").unwrap_err();
+        assert!(format!("{:#}", err).contains("line 2"));
+        assert!(RecipientList::from_str_lines("# nothing\n\n").is_err());
+    }
+}
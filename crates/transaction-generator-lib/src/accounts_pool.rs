@@ -0,0 +1,268 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::resync_accounts;
+use aptos_infallible::Mutex;
+use aptos_sdk::{move_types::account_address::AccountAddress, types::LocalAccount};
+use rand::{rngs::StdRng, Rng};
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+/// Accounts shared by the generators of a run, taken out to be used (e.g. only once,
+/// for unique senders), and possibly returned once used.
+pub trait AccountsPool: Sync + Send {
+    /// Takes `n` accounts, or none if the pool holds fewer
+    fn take_n(&self, n: usize) -> Vec<LocalAccount>;
+
+    /// Takes `n` accounts, or all the accounts left if the pool holds fewer
+    fn take_up_to_n(&self, n: usize) -> Vec<LocalAccount>;
+
+    /// Adds accounts to the pool, behind the ones in it, so they are taken last
+    fn return_n(&self, accounts: Vec<LocalAccount>);
+
+    /// Adds accounts to the pool, in front of the ones in it, so they are taken next
+    fn add_n(&self, accounts: Vec<LocalAccount>);
+
+    /// Exchanges random accounts of the pool (which are dropped) with `accounts`,
+    /// keeping the size of the pool
+    fn replace_random_n(&self, accounts: Vec<LocalAccount>, rng: &mut StdRng);
+
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Fixes the sequence numbers of the accounts in the pool, see `resync_accounts`
+    fn resync_sequence_numbers(&self, stale: &HashMap<AccountAddress, (u64, u64)>);
+}
+
+/// Pool behind a single lock
+#[derive(Default)]
+pub struct LocalAccountsPool {
+    accounts: Mutex<VecDeque<LocalAccount>>,
+}
+
+impl LocalAccountsPool {
+    pub fn new(accounts: Vec<LocalAccount>) -> Self {
+        Self {
+            accounts: Mutex::new(accounts.into()),
+        }
+    }
+}
+
+impl AccountsPool for LocalAccountsPool {
+    fn take_n(&self, n: usize) -> Vec<LocalAccount> {
+        let mut accounts = self.accounts.lock();
+        if accounts.len() < n {
+            return Vec::new();
+        }
+        let num_left = accounts.len() - n;
+        accounts.drain(num_left..).collect()
+    }
+
+    fn take_up_to_n(&self, n: usize) -> Vec<LocalAccount> {
+        let mut accounts = self.accounts.lock();
+        let num_left = accounts.len().saturating_sub(n);
+        accounts.drain(num_left..).collect()
+    }
+
+    fn return_n(&self, returned: Vec<LocalAccount>) {
+        let mut accounts = self.accounts.lock();
+        for account in returned.into_iter().rev() {
+            accounts.push_front(account);
+        }
+    }
+
+    fn add_n(&self, added: Vec<LocalAccount>) {
+        self.accounts.lock().extend(added);
+    }
+
+    fn replace_random_n(&self, mut replacements: Vec<LocalAccount>, rng: &mut StdRng) {
+        let mut accounts = self.accounts.lock();
+        if accounts.len() <= replacements.len() {
+            *accounts = replacements.into();
+            return;
+        }
+        let start = rng.gen_range(0, accounts.len() - replacements.len());
+        accounts.make_contiguous()[start..start + replacements.len()]
+            .swap_with_slice(&mut replacements);
+    }
+
+    fn len(&self) -> usize {
+        self.accounts.lock().len()
+    }
+
+    fn resync_sequence_numbers(&self, stale: &HashMap<AccountAddress, (u64, u64)>) {
+        resync_accounts(self.accounts.lock().iter_mut(), stale);
+    }
+}
+
+/// Pool split into shards behind their own locks, so the many workers of a run taking and
+/// returning accounts don't all contend on the same lock. Shards are used round robin, a
+/// request not served by one shard takes from the following ones.
+pub struct ShardedAccountsPool {
+    shards: Vec<LocalAccountsPool>,
+    next_shard: AtomicUsize,
+}
+
+impl ShardedAccountsPool {
+    pub fn new(accounts: Vec<LocalAccount>, num_shards: usize) -> Self {
+        assert!(num_shards > 0, "Accounts pool needs at least one shard");
+        let mut shards: Vec<Vec<LocalAccount>> = (0..num_shards).map(|_| Vec::new()).collect();
+        for (i, account) in accounts.into_iter().enumerate() {
+            shards[i % num_shards].push(account);
+        }
+        Self {
+            shards: shards.into_iter().map(LocalAccountsPool::new).collect(),
+            next_shard: AtomicUsize::new(0),
+        }
+    }
+
+    fn next_shard(&self) -> usize {
+        self.next_shard.fetch_add(1, Ordering::Relaxed) % self.shards.len()
+    }
+}
+
+impl AccountsPool for ShardedAccountsPool {
+    fn take_n(&self, n: usize) -> Vec<LocalAccount> {
+        let taken = self.take_up_to_n(n);
+        if taken.len() < n {
+            self.return_n(taken);
+            return Vec::new();
+        }
+        taken
+    }
+
+    fn take_up_to_n(&self, n: usize) -> Vec<LocalAccount> {
+        let first_shard = self.next_shard();
+        let mut taken = Vec::with_capacity(n);
+        for i in 0..self.shards.len() {
+            if taken.len() == n {
+                break;
+            }
+            let shard = &self.shards[(first_shard + i) % self.shards.len()];
+            taken.append(&mut shard.take_up_to_n(n - taken.len()));
+        }
+        taken
+    }
+
+    fn return_n(&self, accounts: Vec<LocalAccount>) {
+        self.shards[self.next_shard()].return_n(accounts);
+    }
+
+    fn add_n(&self, accounts: Vec<LocalAccount>) {
+        self.shards[self.next_shard()].add_n(accounts);
+    }
+
+    fn replace_random_n(&self, accounts: Vec<LocalAccount>, rng: &mut StdRng) {
+        let first_shard = rng.gen_range(0, self.shards.len());
+        // from a shard holding more accounts, so its size is kept
+        match (0..self.shards.len())
+            .map(|i| &self.shards[(first_shard + i) % self.shards.len()])
+            .find(|shard| shard.len() > accounts.len())
+        {
+            Some(shard) => shard.replace_random_n(accounts, rng),
+            None => self.add_n(accounts),
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.shards.iter().map(|shard| shard.len()).sum()
+    }
+
+    fn resync_sequence_numbers(&self, stale: &HashMap<AccountAddress, (u64, u64)>) {
+        for shard in &self.shards {
+            shard.resync_sequence_numbers(stale);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rand::SeedableRng;
+
+    fn generate(rng: &mut StdRng, n: usize) -> Vec<LocalAccount> {
+        (0..n).map(|_| LocalAccount::generate(rng)).collect()
+    }
+
+    fn addresses(accounts: &[LocalAccount]) -> Vec<AccountAddress> {
+        accounts.iter().map(|account| account.address()).collect()
+    }
+
+    #[test]
+    fn test_local_accounts_pool() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let accounts = generate(&mut rng, 5);
+        let initial = addresses(&accounts);
+        let pool = LocalAccountsPool::new(accounts);
+
+        assert!(pool.take_n(6).is_empty());
+        assert_eq!(pool.len(), 5);
+        let taken = pool.take_n(2);
+        assert_eq!(addresses(&taken), initial[3..].to_vec());
+
+        // returned ones are taken last, added ones next
+        pool.return_n(taken);
+        let added = generate(&mut rng, 2);
+        let added_addresses = addresses(&added);
+        pool.add_n(added);
+        assert_eq!(addresses(&pool.take_up_to_n(2)), added_addresses);
+        assert_eq!(
+            addresses(&pool.take_up_to_n(10)),
+            [&initial[3..], &initial[..3]].concat()
+        );
+        assert!(pool.is_empty());
+    }
+
+    #[test]
+    fn test_replace_random_n() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let pool = LocalAccountsPool::new(generate(&mut rng, 10));
+        let replacements = generate(&mut rng, 3);
+        let replacement_addresses = addresses(&replacements);
+        pool.replace_random_n(replacements, &mut rng);
+
+        let in_pool = addresses(&pool.take_up_to_n(10));
+        assert_eq!(in_pool.len(), 10);
+        for address in replacement_addresses {
+            assert!(in_pool.contains(&address));
+        }
+    }
+
+    #[test]
+    fn test_sharded_accounts_pool() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let accounts = generate(&mut rng, 10);
+        let initial = addresses(&accounts);
+        let pool = ShardedAccountsPool::new(accounts, 4);
+        assert_eq!(pool.len(), 10);
+
+        // served by several shards
+        let taken = pool.take_n(7);
+        assert_eq!(taken.len(), 7);
+        assert!(pool.take_n(4).is_empty());
+        assert_eq!(pool.len(), 3);
+
+        pool.return_n(taken);
+        pool.add_n(generate(&mut rng, 2));
+        pool.replace_random_n(generate(&mut rng, 1), &mut rng);
+        assert_eq!(pool.len(), 12);
+
+        let mut remaining = addresses(&pool.take_up_to_n(20));
+        assert_eq!(remaining.len(), 12);
+        remaining.sort();
+        remaining.dedup();
+        assert_eq!(remaining.len(), 12);
+        assert!(
+            initial
+                .iter()
+                .filter(|address| remaining.contains(address))
+                .count()
+                >= 9
+        );
+    }
+}
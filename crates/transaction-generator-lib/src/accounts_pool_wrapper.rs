@@ -1,10 +1,55 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{get_account_to_burn_from_pool, TransactionGenerator, TransactionGeneratorCreator};
-use aptos_infallible::RwLock;
-use aptos_sdk::types::{transaction::SignedTransaction, LocalAccount};
-use std::sync::Arc;
+use crate::{
+    create_account_transaction, new_rng, AccountsPool, TransactionGenerator,
+    TransactionGeneratorCreator,
+};
+use anyhow::{bail, Result};
+use aptos_logger::{sample, sample::SampleRate, warn};
+use aptos_sdk::{
+    move_types::account_address::AccountAddress,
+    transaction_builder::TransactionFactory,
+    types::{transaction::SignedTransaction, LocalAccount},
+};
+use rand::rngs::StdRng;
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, str::FromStr, sync::Arc, time::Duration};
+
+/// How the pool is kept from draining, when the wrapper burns accounts faster
+/// than account creation adds them.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub enum AccountPoolRefill {
+    /// Only accounts added by other workloads (i.e. account creation) are used
+    #[default]
+    Disabled,
+    /// Used accounts are put back into the pool, behind the unused ones.
+    /// Accounts whose transaction failed to commit have a stale sequence number,
+    /// and their next transactions are rejected until it is re-synced by the emitter.
+    Recycle,
+    /// Missing accounts are created by the generator's own account, with the given balance.
+    /// They are added to the pool on the next call, once their creation is committed.
+    Mint { creation_balance: u64 },
+}
+
+impl FromStr for AccountPoolRefill {
+    type Err = anyhow::Error;
+
+    /// Parses `disabled`, `recycle` or `mint:<creation_balance>`
+    fn from_str(s: &str) -> Result<Self> {
+        Ok(match s.split_once(':') {
+            None if s == "disabled" => Self::Disabled,
+            None if s == "recycle" => Self::Recycle,
+            Some(("mint", creation_balance)) => Self::Mint {
+                creation_balance: creation_balance.parse()?,
+            },
+            _ => bail!(
+                "Invalid account pool refill {}, expected disabled, recycle or mint:<balance>",
+                s
+            ),
+        })
+    }
+}
 
 /// Wrapper that allows inner transaction generator to have unique accounts
 /// for all transactions (instead of having 5-20 transactions per account, as default)
@@ -12,18 +57,30 @@ use std::sync::Arc;
 /// and burning (removing accounts from the pool) them - basically using them only once.
 /// (we cannot use more as sequence number is not updated on failure)
 pub struct AccountsPoolWrapperGenerator {
+    rng: StdRng,
+    txn_factory: TransactionFactory,
     creator: Box<dyn TransactionGenerator>,
-    accounts_pool: Arc<RwLock<Vec<LocalAccount>>>,
+    accounts_pool: Arc<dyn AccountsPool>,
+    refill: AccountPoolRefill,
+    /// Accounts minted in the previous call, not yet added to the pool
+    minted: Vec<LocalAccount>,
 }
 
 impl AccountsPoolWrapperGenerator {
     pub fn new(
+        rng: StdRng,
+        txn_factory: TransactionFactory,
         creator: Box<dyn TransactionGenerator>,
-        accounts_pool: Arc<RwLock<Vec<LocalAccount>>>,
+        accounts_pool: Arc<dyn AccountsPool>,
+        refill: AccountPoolRefill,
     ) -> Self {
         Self {
+            rng,
+            txn_factory,
             creator,
             accounts_pool,
+            refill,
+            minted: Vec::new(),
         }
     }
 }
@@ -31,34 +88,78 @@ impl AccountsPoolWrapperGenerator {
 impl TransactionGenerator for AccountsPoolWrapperGenerator {
     fn generate_transactions(
         &mut self,
-        _account: &mut LocalAccount,
+        account: &mut LocalAccount,
         num_to_create: usize,
     ) -> Vec<SignedTransaction> {
-        let mut accounts_to_burn =
-            get_account_to_burn_from_pool(&self.accounts_pool, num_to_create);
-        if accounts_to_burn.is_empty() {
-            return Vec::new();
+        if !self.minted.is_empty() {
+            let minted = std::mem::take(&mut self.minted);
+            self.accounts_pool.add_n(minted);
         }
-        accounts_to_burn
+
+        let mut accounts_to_burn = self.accounts_pool.take_up_to_n(num_to_create);
+        let mut requests: Vec<_> = accounts_to_burn
             .iter_mut()
-            .flat_map(|account| self.creator.generate_transactions(account, 1))
-            .collect()
+            .flat_map(|pool_account| self.creator.generate_transactions(pool_account, 1))
+            .collect();
+
+        match self.refill {
+            AccountPoolRefill::Disabled => {},
+            AccountPoolRefill::Recycle => self.accounts_pool.return_n(accounts_to_burn),
+            AccountPoolRefill::Mint { creation_balance } => {
+                let num_missing = num_to_create - accounts_to_burn.len();
+                for _ in 0..num_missing {
+                    let new_account = LocalAccount::generate(&mut self.rng);
+                    requests.push(create_account_transaction(
+                        account,
+                        new_account.address(),
+                        &self.txn_factory,
+                        creation_balance,
+                    ));
+                    self.minted.push(new_account);
+                }
+            },
+        }
+
+        if requests.is_empty() {
+            sample!(
+                SampleRate::Duration(Duration::from_secs(10)),
+                warn!("Accounts pool is empty, no transactions generated");
+            );
+        }
+        requests
+    }
+
+    fn resync_sequence_numbers(&mut self, stale: &HashMap<AccountAddress, (u64, u64)>) {
+        // burned accounts are not used again, only recycled ones need to be fixed
+        if self.refill == AccountPoolRefill::Recycle {
+            self.accounts_pool.resync_sequence_numbers(stale);
+        }
+        self.creator.resync_sequence_numbers(stale);
     }
 }
 
 pub struct AccountsPoolWrapperCreator {
+    txn_factory: TransactionFactory,
     creator: Box<dyn TransactionGeneratorCreator>,
-    accounts_pool: Arc<RwLock<Vec<LocalAccount>>>,
+    accounts_pool: Arc<dyn AccountsPool>,
+    refill: AccountPoolRefill,
+    rng: StdRng,
 }
 
 impl AccountsPoolWrapperCreator {
     pub fn new(
+        txn_factory: TransactionFactory,
         creator: Box<dyn TransactionGeneratorCreator>,
-        accounts_pool: Arc<RwLock<Vec<LocalAccount>>>,
+        accounts_pool: Arc<dyn AccountsPool>,
+        refill: AccountPoolRefill,
+        rng: StdRng,
     ) -> Self {
         Self {
+            txn_factory,
             creator,
             accounts_pool,
+            refill,
+            rng,
         }
     }
 }
@@ -66,8 +167,11 @@ impl AccountsPoolWrapperCreator {
 impl TransactionGeneratorCreator for AccountsPoolWrapperCreator {
     fn create_transaction_generator(&mut self) -> Box<dyn TransactionGenerator> {
         Box::new(AccountsPoolWrapperGenerator::new(
+            new_rng(&mut self.rng),
+            self.txn_factory.clone(),
             self.creator.create_transaction_generator(),
             self.accounts_pool.clone(),
+            self.refill,
         ))
     }
 }
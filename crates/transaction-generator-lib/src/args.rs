@@ -1,7 +1,7 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{EntryPoints, TransactionType};
+use crate::{EntryPoints, GasPriceDistribution, TransactionType};
 use clap::{Parser, ValueEnum};
 use serde::{Deserialize, Serialize};
 
@@ -17,7 +17,10 @@ pub enum TransactionTypeArg {
     NonConflictingCoinTransfer,
     AccountGeneration,
     AccountGenerationLargePool,
+    AccountLifecycle,
     PublishPackage,
+    PublishAndUpgradeSmallPackage,
+    PublishAndUpgradeLargePackage,
     AccountResource32B,
     AccountResource1KB,
     AccountResource10KB,
@@ -30,6 +33,26 @@ pub enum TransactionTypeArg {
     TokenV1FTMintAndStore,
     TokenV1FTMintAndTransfer,
     TokenV2AmbassadorMint,
+    TokenV2MintAndTransfer,
+    TokenV2MintSoulBound,
+    TokenV2MintAndBurn,
+    MarketplaceBuy,
+    FungibleAssetTransfer,
+    FungibleAssetTransferConflicting,
+    Multisig2Of3,
+    ScriptPayload,
+    ScriptPayloadLargeArgs,
+    ObjectTransfer,
+    SmartTableSmallKeySpace,
+    SmartTableLargeKeySpace,
+    ResourceGroupsMutateSmall,
+    ResourceGroupsMutateLarge,
+    DelegationPool,
+    GovernanceVote,
+    LiquidityPoolSwap,
+    CallDependency,
+    ArgumentFuzzingSimple,
+    ArgumentFuzzingFrameworkUsecases,
 }
 
 impl TransactionTypeArg {
@@ -67,9 +90,24 @@ impl TransactionTypeArg {
                 max_account_working_set: 50_000_000,
                 creation_balance: 200_000_000,
             },
+            TransactionTypeArg::AccountLifecycle => TransactionType::AccountLifecycle,
             TransactionTypeArg::PublishPackage => TransactionType::PublishPackage {
                 use_account_pool: sender_use_account_pool,
             },
+            TransactionTypeArg::PublishAndUpgradeSmallPackage => {
+                TransactionType::PublishAndUpgradePackage {
+                    package_size: 5_000,
+                    upgrade_percentage: 10,
+                    use_account_pool: sender_use_account_pool,
+                }
+            },
+            TransactionTypeArg::PublishAndUpgradeLargePackage => {
+                TransactionType::PublishAndUpgradePackage {
+                    package_size: 50_000,
+                    upgrade_percentage: 10,
+                    use_account_pool: sender_use_account_pool,
+                }
+            },
             TransactionTypeArg::AccountResource32B => TransactionType::CallCustomModules {
                 entry_point: EntryPoints::BytesMakeOrChange {
                     data_length: Some(32),
@@ -157,6 +195,107 @@ impl TransactionTypeArg {
                 num_modules: module_working_set_size,
                 use_account_pool: sender_use_account_pool,
             },
+            TransactionTypeArg::TokenV2MintAndTransfer => TransactionType::TokenV2MintAndTransfer {
+                collection_size: 1000,
+                soulbound_percentage: 0,
+            },
+            TransactionTypeArg::TokenV2MintSoulBound => TransactionType::TokenV2MintAndTransfer {
+                collection_size: 1000,
+                soulbound_percentage: 100,
+            },
+            TransactionTypeArg::TokenV2MintAndBurn => TransactionType::TokenV2MintAndBurn {
+                live_tokens_per_account: 10,
+            },
+            TransactionTypeArg::MarketplaceBuy => TransactionType::CallCustomModules {
+                entry_point: EntryPoints::MarketplaceBuy { num_listings: 100 },
+                num_modules: module_working_set_size,
+                use_account_pool: sender_use_account_pool,
+            },
+            TransactionTypeArg::FungibleAssetTransfer => TransactionType::FungibleAssetTransfer {
+                num_modules: module_working_set_size,
+                conflict_percentage: 0,
+                use_account_pool: sender_use_account_pool,
+            },
+            TransactionTypeArg::FungibleAssetTransferConflicting => {
+                TransactionType::FungibleAssetTransfer {
+                    num_modules: module_working_set_size,
+                    conflict_percentage: 50,
+                    use_account_pool: sender_use_account_pool,
+                }
+            },
+            TransactionTypeArg::Multisig2Of3 => TransactionType::Multisig {
+                num_owners: 3,
+                num_signatures_required: 2,
+            },
+            TransactionTypeArg::ScriptPayload => TransactionType::ScriptPayload {
+                max_num_args: 10,
+                max_arg_size: 100,
+            },
+            TransactionTypeArg::ScriptPayloadLargeArgs => TransactionType::ScriptPayload {
+                max_num_args: 4,
+                max_arg_size: 10_000,
+            },
+            TransactionTypeArg::ObjectTransfer => TransactionType::ObjectTransfer {
+                num_modules: module_working_set_size,
+                payload_size: 100,
+                use_account_pool: sender_use_account_pool,
+            },
+            TransactionTypeArg::SmartTableSmallKeySpace => TransactionType::CallCustomModules {
+                entry_point: EntryPoints::SmartTableRandom { num_keys: 1_000 },
+                num_modules: module_working_set_size,
+                use_account_pool: sender_use_account_pool,
+            },
+            TransactionTypeArg::SmartTableLargeKeySpace => TransactionType::CallCustomModules {
+                entry_point: EntryPoints::SmartTableRandom {
+                    num_keys: 1_000_000,
+                },
+                num_modules: module_working_set_size,
+                use_account_pool: sender_use_account_pool,
+            },
+            TransactionTypeArg::ResourceGroupsMutateSmall => TransactionType::CallCustomModules {
+                entry_point: EntryPoints::ResourceGroupsMutate { member_size: 32 },
+                num_modules: module_working_set_size,
+                use_account_pool: sender_use_account_pool,
+            },
+            TransactionTypeArg::ResourceGroupsMutateLarge => TransactionType::CallCustomModules {
+                entry_point: EntryPoints::ResourceGroupsMutate {
+                    member_size: 10_000,
+                },
+                num_modules: module_working_set_size,
+                use_account_pool: sender_use_account_pool,
+            },
+            TransactionTypeArg::DelegationPool => TransactionType::DelegationPool {
+                num_pools: 10,
+                num_delegators: 1_000,
+                num_stake_owners: 100,
+            },
+            TransactionTypeArg::GovernanceVote => TransactionType::CallCustomModules {
+                entry_point: EntryPoints::GovernanceVote,
+                num_modules: module_working_set_size,
+                use_account_pool: sender_use_account_pool,
+            },
+            TransactionTypeArg::LiquidityPoolSwap => TransactionType::CallCustomModules {
+                entry_point: EntryPoints::LiquidityPoolSwap,
+                num_modules: module_working_set_size,
+                use_account_pool: sender_use_account_pool,
+            },
+            TransactionTypeArg::CallDependency => TransactionType::CallCustomModules {
+                entry_point: EntryPoints::CallDependency,
+                num_modules: module_working_set_size,
+                use_account_pool: sender_use_account_pool,
+            },
+            TransactionTypeArg::ArgumentFuzzingSimple => TransactionType::ArgumentFuzzing {
+                package_name: "simple",
+                num_modules: module_working_set_size,
+                use_account_pool: sender_use_account_pool,
+            },
+            TransactionTypeArg::ArgumentFuzzingFrameworkUsecases => {
+                TransactionType::ArgumentFuzzing {
+                    package_name: "framework_usecases",
+                    num_modules: module_working_set_size,
+                    use_account_pool: sender_use_account_pool,
+                }
+            },
         }
     }
 
@@ -164,14 +303,33 @@ impl TransactionTypeArg {
         transaction_types: &[TransactionTypeArg],
         transaction_weights: &[usize],
         transaction_phases: &[usize],
+        transaction_gas_prices: &[GasPriceDistribution],
         module_working_set_size: usize,
         sender_use_account_pool: bool,
     ) -> Vec<Vec<(TransactionType, usize)>> {
-        let arg_transaction_types = transaction_types
+        let mut arg_transaction_types = transaction_types
             .iter()
             .map(|t| t.materialize(module_working_set_size, sender_use_account_pool))
             .collect::<Vec<_>>();
 
+        if !transaction_gas_prices.is_empty() {
+            assert_eq!(
+                transaction_gas_prices.len(),
+                arg_transaction_types.len(),
+                "Transaction types and gas prices need to be the same length"
+            );
+            arg_transaction_types = arg_transaction_types
+                .into_iter()
+                .zip(transaction_gas_prices.iter())
+                .map(
+                    |(transaction_type, gas_price)| TransactionType::CustomGasPrice {
+                        transaction_type: Box::new(transaction_type),
+                        gas_price: *gas_price,
+                    },
+                )
+                .collect();
+        }
+
         let arg_transaction_weights = if transaction_weights.is_empty() {
             vec![1; arg_transaction_types.len()]
         } else {
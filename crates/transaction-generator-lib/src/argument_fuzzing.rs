@@ -0,0 +1,304 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    call_custom_modules::{TransactionGeneratorWorker, UserModuleTransactionGenerator},
+    publishing::publish_util::Package,
+    ReliableTransactionSubmitter,
+};
+use aptos_logger::info;
+use aptos_sdk::{
+    move_types::{
+        account_address::AccountAddress, identifier::Identifier, language_storage::ModuleId,
+    },
+    transaction_builder::TransactionFactory,
+    types::{
+        transaction::{EntryFunction, SignedTransaction, TransactionPayload},
+        LocalAccount,
+    },
+};
+use async_trait::async_trait;
+use move_binary_format::{access::ModuleAccess, file_format::SignatureToken, CompiledModule};
+use rand::{distributions::Alphanumeric, rngs::StdRng, seq::SliceRandom, Rng};
+use std::{collections::HashMap, sync::Arc};
+
+/// Max length of the generated vectors and strings
+const MAX_FUZZED_LENGTH: usize = 16;
+
+/// Argument types that can be passed to an entry function, and so generated
+#[derive(Debug, Clone)]
+enum FuzzedArg {
+    Bool,
+    U8,
+    U16,
+    U32,
+    U64,
+    U128,
+    U256,
+    Address,
+    /// `0x1::string::String`, needs to be valid utf8
+    String,
+    Vector(Box<FuzzedArg>),
+}
+
+impl FuzzedArg {
+    /// None if values of the type can't be generated, e.g. other structs or generics
+    fn from_token(module: &CompiledModule, token: &SignatureToken) -> Option<Self> {
+        Some(match token {
+            SignatureToken::Bool => Self::Bool,
+            SignatureToken::U8 => Self::U8,
+            SignatureToken::U16 => Self::U16,
+            SignatureToken::U32 => Self::U32,
+            SignatureToken::U64 => Self::U64,
+            SignatureToken::U128 => Self::U128,
+            SignatureToken::U256 => Self::U256,
+            SignatureToken::Address => Self::Address,
+            SignatureToken::Vector(inner) => {
+                Self::Vector(Box::new(Self::from_token(module, inner)?))
+            },
+            SignatureToken::Struct(idx) => {
+                let handle = module.struct_handle_at(*idx);
+                let module_handle = module.module_handle_at(handle.module);
+                if *module.address_identifier_at(module_handle.address) == AccountAddress::ONE
+                    && module.identifier_at(module_handle.name).as_str() == "string"
+                    && module.identifier_at(handle.name).as_str() == "String"
+                {
+                    Self::String
+                } else {
+                    return None;
+                }
+            },
+            _ => return None,
+        })
+    }
+
+    /// Appends a random BCS encoded value of the type to `out`
+    fn generate(&self, rng: &mut StdRng, addresses: &[AccountAddress], out: &mut Vec<u8>) {
+        match self {
+            Self::Bool => out.push(rng.gen_range(0u8, 2u8)),
+            Self::U8 => out.extend(random_uint(rng, 1)),
+            Self::U16 => out.extend(random_uint(rng, 2)),
+            Self::U32 => out.extend(random_uint(rng, 4)),
+            Self::U64 => out.extend(random_uint(rng, 8)),
+            Self::U128 => out.extend(random_uint(rng, 16)),
+            Self::U256 => out.extend(random_uint(rng, 32)),
+            Self::Address => {
+                let address = if rng.gen_bool(0.5) {
+                    *addresses.choose(rng).unwrap()
+                } else {
+                    AccountAddress::new(rng.gen())
+                };
+                out.extend(address.into_bytes());
+            },
+            Self::String => {
+                let len = rng.gen_range(0, MAX_FUZZED_LENGTH + 1);
+                write_uleb128(len, out);
+                for _ in 0..len {
+                    out.push(rng.sample(Alphanumeric) as u8);
+                }
+            },
+            Self::Vector(inner) => {
+                let len = rng.gen_range(0, MAX_FUZZED_LENGTH + 1);
+                write_uleb128(len, out);
+                for _ in 0..len {
+                    inner.generate(rng, addresses, out);
+                }
+            },
+        }
+    }
+}
+
+/// Little endian integer of `num_bytes`, biased towards the edge values
+fn random_uint(rng: &mut StdRng, num_bytes: usize) -> Vec<u8> {
+    match rng.gen_range(0, 4) {
+        0 => vec![0; num_bytes],
+        1 => vec![u8::MAX; num_bytes],
+        2 => {
+            let mut bytes = vec![0; num_bytes];
+            bytes[0] = rng.gen_range(1u8, 100u8);
+            bytes
+        },
+        _ => (0..num_bytes).map(|_| rng.gen()).collect(),
+    }
+}
+
+/// Length prefix of BCS sequences
+fn write_uleb128(mut value: usize, out: &mut Vec<u8>) {
+    while value >= 0x80 {
+        out.push((value as u8 & 0x7F) | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+/// Entry function of a published module, with the types of its (non signer) arguments
+#[derive(Debug, Clone)]
+struct FuzzTarget {
+    module_id: ModuleId,
+    function: Identifier,
+    args: Vec<FuzzedArg>,
+}
+
+impl FuzzTarget {
+    /// Entry functions of the module that can be called with generated arguments:
+    /// not generic, with at most a leading signer, and arguments of supported types
+    fn from_module(module: &CompiledModule) -> Vec<Self> {
+        module
+            .function_defs()
+            .iter()
+            .filter(|def| def.is_entry)
+            .filter_map(|def| {
+                let handle = module.function_handle_at(def.function);
+                if !handle.type_parameters.is_empty() {
+                    return None;
+                }
+                let mut params = module.signature_at(handle.parameters).0.as_slice();
+                if let Some(SignatureToken::Signer) = params.first().map(strip_reference) {
+                    params = &params[1..];
+                }
+                let args = params
+                    .iter()
+                    .map(|param| FuzzedArg::from_token(module, param))
+                    .collect::<Option<Vec<_>>>()?;
+                Some(Self {
+                    module_id: module.self_id(),
+                    function: module.identifier_at(handle.name).to_owned(),
+                    args,
+                })
+            })
+            .collect()
+    }
+
+    fn payload(&self, rng: &mut StdRng, addresses: &[AccountAddress]) -> TransactionPayload {
+        let args = self
+            .args
+            .iter()
+            .map(|arg| {
+                let mut bytes = Vec::new();
+                arg.generate(rng, addresses, &mut bytes);
+                bytes
+            })
+            .collect();
+        TransactionPayload::EntryFunction(EntryFunction::new(
+            self.module_id.clone(),
+            self.function.clone(),
+            vec![],
+            args,
+        ))
+    }
+}
+
+fn strip_reference(token: &SignatureToken) -> &SignatureToken {
+    match token {
+        SignatureToken::Reference(inner) => inner,
+        token => token,
+    }
+}
+
+/// Calls random entry functions of the published package, enumerated from its compiled
+/// modules, with random arguments of the right types (edge values of integers, random
+/// lengths, addresses of the sender, the publisher or random ones). Most calls are expected
+/// to abort, the point is exercising the argument validation of the VM and the modules under
+/// load, not their success.
+#[derive(Default)]
+pub struct ArgumentFuzzingGenerator {
+    /// Entry functions of each published package, by publisher
+    targets: HashMap<AccountAddress, Vec<FuzzTarget>>,
+}
+
+#[async_trait]
+impl UserModuleTransactionGenerator for ArgumentFuzzingGenerator {
+    fn initialize_package(
+        &mut self,
+        package: &Package,
+        publisher: &mut LocalAccount,
+        _txn_factory: &TransactionFactory,
+        _rng: &mut StdRng,
+    ) -> Vec<SignedTransaction> {
+        let Package::Simple(modules, _) = package;
+        let targets: Vec<_> = modules
+            .iter()
+            .flat_map(|(_, module)| FuzzTarget::from_module(module))
+            .collect();
+        assert!(
+            !targets.is_empty(),
+            "No entry function of the package can be fuzzed"
+        );
+        self.targets.insert(publisher.address(), targets);
+        vec![]
+    }
+
+    async fn create_generator_fn(
+        &self,
+        _init_accounts: &mut [LocalAccount],
+        _txn_factory: &TransactionFactory,
+        _txn_executor: &dyn ReliableTransactionSubmitter,
+        _rng: &mut StdRng,
+    ) -> Arc<TransactionGeneratorWorker> {
+        let targets = Arc::new(self.targets.clone());
+        if let Some(package_targets) = targets.values().next() {
+            info!(
+                "Fuzzing the arguments of {} entry functions: {:?}",
+                package_targets.len(),
+                package_targets
+                    .iter()
+                    .map(|target| format!("{}::{}", target.module_id.name(), target.function))
+                    .collect::<Vec<_>>()
+            );
+        }
+        Arc::new(
+            move |account, _state, _package, publisher, txn_factory, rng| {
+                let target = targets[&publisher.address()].choose(rng).unwrap();
+                let payload = target.payload(rng, &[account.address(), publisher.address()]);
+                account.sign_with_transaction_builder(txn_factory.payload(payload))
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use aptos_sdk::bcs;
+    use rand::SeedableRng;
+    use serde::de::DeserializeOwned;
+
+    /// Generated values are complete BCS encodings of `T`
+    fn check_generated<T: DeserializeOwned>(arg: FuzzedArg) {
+        let mut rng = StdRng::seed_from_u64(0);
+        let addresses = [AccountAddress::ONE, AccountAddress::TWO];
+        for _ in 0..100 {
+            let mut bytes = Vec::new();
+            arg.generate(&mut rng, &addresses, &mut bytes);
+            bcs::from_bytes::<T>(&bytes)
+                .unwrap_or_else(|e| panic!("Invalid {:?} {:?}: {}", arg, bytes, e));
+        }
+    }
+
+    #[test]
+    fn test_generated_args_are_bcs() {
+        check_generated::<bool>(FuzzedArg::Bool);
+        check_generated::<u8>(FuzzedArg::U8);
+        check_generated::<u16>(FuzzedArg::U16);
+        check_generated::<u32>(FuzzedArg::U32);
+        check_generated::<u64>(FuzzedArg::U64);
+        check_generated::<u128>(FuzzedArg::U128);
+        check_generated::<[u8; 32]>(FuzzedArg::U256);
+        check_generated::<AccountAddress>(FuzzedArg::Address);
+        check_generated::<String>(FuzzedArg::String);
+        check_generated::<Vec<u8>>(FuzzedArg::Vector(Box::new(FuzzedArg::U8)));
+        check_generated::<Vec<Vec<String>>>(FuzzedArg::Vector(Box::new(FuzzedArg::Vector(
+            Box::new(FuzzedArg::String),
+        ))));
+    }
+
+    #[test]
+    fn test_write_uleb128() {
+        for len in [0, 1, 127, 128, 255, 300, 16_383, 16_384, 1_000_000] {
+            let mut prefix = Vec::new();
+            write_uleb128(len, &mut prefix);
+            let encoded = bcs::to_bytes(&vec![0u8; len]).unwrap();
+            assert_eq!(prefix, encoded[..encoded.len() - len], "{}", len);
+        }
+    }
+}
@@ -1,13 +1,13 @@
 // Copyright © Aptos Foundation
 
-use crate::{TransactionGenerator, TransactionGeneratorCreator};
+use crate::{new_rng, TransactionGenerator, TransactionGeneratorCreator};
 use aptos_infallible::RwLock;
 use aptos_sdk::{
     move_types::account_address::AccountAddress,
     transaction_builder::{aptos_stdlib, TransactionFactory},
     types::{transaction::SignedTransaction, LocalAccount},
 };
-use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
+use rand::{rngs::StdRng, seq::SliceRandom};
 use std::sync::Arc;
 
 pub struct BatchTransferTransactionGenerator {
@@ -50,12 +50,11 @@ impl TransactionGenerator for BatchTransferTransactionGenerator {
                 .choose_multiple(&mut self.rng, self.batch_size)
                 .cloned()
                 .collect::<Vec<_>>();
+            // the pool can hold fewer addresses than the batch size
+            let amounts = vec![self.send_amount; receivers.len()];
             requests.push(
                 account.sign_with_transaction_builder(self.txn_factory.payload(
-                    aptos_stdlib::aptos_account_batch_transfer(receivers, vec![
-                        self.send_amount;
-                        self.batch_size
-                    ]),
+                    aptos_stdlib::aptos_account_batch_transfer(receivers, amounts),
                 )),
             );
         }
@@ -69,6 +68,7 @@ pub struct BatchTransferTransactionGeneratorCreator {
     amount: u64,
     all_addresses: Arc<RwLock<Vec<AccountAddress>>>,
     batch_size: usize,
+    rng: StdRng,
 }
 
 impl BatchTransferTransactionGeneratorCreator {
@@ -77,12 +77,14 @@ impl BatchTransferTransactionGeneratorCreator {
         amount: u64,
         all_addresses: Arc<RwLock<Vec<AccountAddress>>>,
         batch_size: usize,
+        rng: StdRng,
     ) -> Self {
         Self {
             txn_factory,
             amount,
             all_addresses,
             batch_size,
+            rng,
         }
     }
 }
@@ -90,7 +92,7 @@ impl BatchTransferTransactionGeneratorCreator {
 impl TransactionGeneratorCreator for BatchTransferTransactionGeneratorCreator {
     fn create_transaction_generator(&mut self) -> Box<dyn TransactionGenerator> {
         Box::new(BatchTransferTransactionGenerator::new(
-            StdRng::from_entropy(),
+            new_rng(&mut self.rng),
             self.batch_size,
             self.amount,
             self.txn_factory.clone(),
@@ -3,22 +3,43 @@
 
 use super::{publishing::publish_util::Package, ReliableTransactionSubmitter};
 use crate::{
-    create_account_transaction, publishing::publish_util::PackageHandler, TransactionGenerator,
-    TransactionGeneratorCreator,
+    create_account_transaction, new_rng,
+    publishing::publish_util::{package_dependencies, PackageHandler},
+    TransactionGenerator, TransactionGeneratorCreator,
 };
+use aptos_infallible::Mutex;
 use aptos_logger::info;
 use aptos_sdk::{
+    move_types::account_address::AccountAddress,
     transaction_builder::TransactionFactory,
     types::{transaction::SignedTransaction, LocalAccount},
 };
 use async_trait::async_trait;
-use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
-use std::sync::Arc;
+use rand::{rngs::StdRng, seq::SliceRandom};
+use std::{any::Any, collections::HashMap, sync::Arc};
+
+/// What a workload remembers about an account and a package between the transactions it
+/// generates for them, e.g. the step of a multi-step journey the account is at with the
+/// package ("listed token X, next is delisting it"). Empty for pairs not used yet, and reset
+/// when transactions of the account didn't commit, as the journey is then out of sync with
+/// the chain. See `account_state`.
+pub type AccountState = Option<Box<dyn Any + Send>>;
+
+/// State of type `T` of the account and package, starting from `T::default()`
+pub fn account_state<T: Default + Send + 'static>(state: &mut AccountState) -> &mut T {
+    if !matches!(state, Some(boxed) if boxed.is::<T>()) {
+        *state = Some(Box::<T>::default());
+    }
+    state.as_mut().unwrap().downcast_mut::<T>().unwrap()
+}
 
 // Fn + Send + Sync, as it will be called from multiple threads simultaneously
-// if you need any coordination, use Arc<RwLock<X>> fields
+// if you need any coordination, use Arc<RwLock<X>> fields.
+// State of a single account with the package is passed in as AccountState instead, an
+// account is only used by one thread at a time.
 pub type TransactionGeneratorWorker = dyn Fn(
         &mut LocalAccount,
+        &mut AccountState,
         &Package,
         &LocalAccount,
         &TransactionFactory,
@@ -56,25 +77,32 @@ pub trait UserModuleTransactionGenerator: Sync + Send {
     ) -> Arc<TransactionGeneratorWorker>;
 }
 
+/// Account states of a workload by account and package publisher, shared by its generators
+/// as accounts can move between them (e.g. through the accounts pool)
+type AccountStates = Arc<Mutex<HashMap<(AccountAddress, AccountAddress), AccountState>>>;
+
 pub struct CustomModulesDelegationGenerator {
     rng: StdRng,
     txn_factory: TransactionFactory,
     packages: Arc<Vec<(Package, LocalAccount)>>,
     txn_generator: Arc<TransactionGeneratorWorker>,
+    account_states: AccountStates,
 }
 
 impl CustomModulesDelegationGenerator {
-    pub fn new(
+    fn new(
         rng: StdRng,
         txn_factory: TransactionFactory,
         packages: Arc<Vec<(Package, LocalAccount)>>,
         txn_generator: Arc<TransactionGeneratorWorker>,
+        account_states: AccountStates,
     ) -> Self {
         Self {
             rng,
             txn_factory,
             packages,
             txn_generator,
+            account_states,
         }
     }
 }
@@ -89,23 +117,41 @@ impl TransactionGenerator for CustomModulesDelegationGenerator {
 
         for _ in 0..num_to_create {
             let (package, publisher) = self.packages.choose(&mut self.rng).unwrap();
+            let key = (account.address(), publisher.address());
+            // taken out while generating, so the lock is not held meanwhile
+            let mut state = self.account_states.lock().remove(&key).flatten();
             let request = (self.txn_generator)(
                 account,
+                &mut state,
                 package,
                 publisher,
                 &self.txn_factory,
                 &mut self.rng,
             );
+            if state.is_some() {
+                self.account_states.lock().insert(key, state);
+            }
             requests.push(request);
         }
         requests
     }
+
+    fn resync_sequence_numbers(&mut self, stale: &HashMap<AccountAddress, (u64, u64)>) {
+        if stale.is_empty() {
+            return;
+        }
+        self.account_states
+            .lock()
+            .retain(|(address, _), _| !stale.contains_key(address));
+    }
 }
 
 pub struct CustomModulesDelegationGeneratorCreator {
     txn_factory: TransactionFactory,
     packages: Arc<Vec<(Package, LocalAccount)>>,
     txn_generator: Arc<TransactionGeneratorWorker>,
+    account_states: AccountStates,
+    rng: StdRng,
 }
 
 impl CustomModulesDelegationGeneratorCreator {
@@ -117,25 +163,53 @@ impl CustomModulesDelegationGeneratorCreator {
         num_modules: usize,
         package_name: &str,
         workload: &mut dyn UserModuleTransactionGenerator,
+        mut rng: StdRng,
     ) -> Self {
-        let mut rng = StdRng::from_entropy();
         assert!(accounts.len() >= num_modules);
         let mut requests_create = Vec::with_capacity(accounts.len());
         let mut requests_publish = Vec::with_capacity(accounts.len());
         let mut requests_initialize = Vec::with_capacity(accounts.len());
+        let dependency_names = package_dependencies(package_name);
+        let mut requests_publish_dependencies = vec![Vec::new(); dependency_names.len()];
         let mut package_handler = PackageHandler::new(package_name);
         let mut packages = Vec::new();
+        let publisher_balance =
+            2 * init_txn_factory.get_gas_unit_price() * init_txn_factory.get_max_gas_amount();
         for account in accounts.iter_mut().take(num_modules) {
+            // each package gets its own copy of the dependencies, each one under another account
+            let mut dependencies: Vec<(Package, AccountAddress)> = Vec::new();
+            for (i, dependency_name) in dependency_names.iter().enumerate() {
+                let mut dependency_publisher = LocalAccount::generate(&mut rng);
+                requests_create.push(create_account_transaction(
+                    account,
+                    dependency_publisher.address(),
+                    &init_txn_factory,
+                    publisher_balance,
+                ));
+                let mut dependency =
+                    Package::by_name(dependency_name).update(dependency_publisher.address(), 0);
+                for (linked, linked_publisher) in &dependencies {
+                    dependency.link_dependency(linked, *linked_publisher);
+                }
+                requests_publish_dependencies[i].push(
+                    dependency.publish_transaction(&mut dependency_publisher, &init_txn_factory),
+                );
+                dependencies.push((dependency, dependency_publisher.address()));
+            }
+
             let mut publisher = LocalAccount::generate(&mut rng);
             let publisher_address = publisher.address();
             requests_create.push(create_account_transaction(
                 account,
                 publisher_address,
                 &init_txn_factory,
-                2 * init_txn_factory.get_gas_unit_price() * init_txn_factory.get_max_gas_amount(),
+                publisher_balance,
             ));
 
-            let package = package_handler.pick_package(&mut rng, &mut publisher);
+            let mut package = package_handler.pick_package(&mut rng, &mut publisher);
+            for (dependency, dependency_publisher) in &dependencies {
+                package.link_dependency(dependency, *dependency_publisher);
+            }
             requests_publish.push(package.publish_transaction(&mut publisher, &init_txn_factory));
 
             requests_initialize.append(&mut workload.initialize_package(
@@ -153,6 +227,18 @@ impl CustomModulesDelegationGeneratorCreator {
             .await
             .unwrap();
 
+        // dependencies need to be published before the packages using them
+        for (dependency_name, requests) in
+            dependency_names.iter().zip(requests_publish_dependencies)
+        {
+            info!(
+                "Publishing {} {} dependency packages",
+                requests.len(),
+                dependency_name
+            );
+            txn_executor.execute_transactions(&requests).await.unwrap();
+        }
+
         info!("Publishing {} packages", requests_publish.len());
         txn_executor
             .execute_transactions(&requests_publish)
@@ -180,6 +266,8 @@ impl CustomModulesDelegationGeneratorCreator {
             txn_factory,
             packages: Arc::new(packages),
             txn_generator,
+            account_states: Arc::new(Mutex::new(HashMap::new())),
+            rng,
         }
     }
 }
@@ -187,10 +275,99 @@ impl CustomModulesDelegationGeneratorCreator {
 impl TransactionGeneratorCreator for CustomModulesDelegationGeneratorCreator {
     fn create_transaction_generator(&mut self) -> Box<dyn TransactionGenerator> {
         Box::new(CustomModulesDelegationGenerator::new(
-            StdRng::from_entropy(),
+            new_rng(&mut self.rng),
             self.txn_factory.clone(),
             self.packages.clone(),
             self.txn_generator.clone(),
+            self.account_states.clone(),
         ))
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use aptos_sdk::{
+        bcs,
+        transaction_builder::aptos_stdlib,
+        types::{chain_id::ChainId, transaction::TransactionPayload},
+    };
+    use rand::SeedableRng;
+
+    /// Recipient and amount of the coin transfers sent by the stateful worker below
+    fn transfers(txns: &[SignedTransaction]) -> Vec<(AccountAddress, u64)> {
+        txns.iter()
+            .map(|txn| match txn.payload() {
+                TransactionPayload::EntryFunction(entry_function) => (
+                    bcs::from_bytes(&entry_function.args()[0]).unwrap(),
+                    bcs::from_bytes(&entry_function.args()[1]).unwrap(),
+                ),
+                _ => panic!("unexpected payload"),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_account_state_by_account_and_package() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let packages: Vec<_> = (0..2)
+            .map(|_| {
+                let publisher = LocalAccount::generate(&mut rng);
+                (
+                    Package::by_name("simple").update(publisher.address(), 0),
+                    publisher,
+                )
+            })
+            .collect();
+        // counts the transactions of each account with each package, sending that many coins
+        // to the publisher
+        let txn_generator: Arc<TransactionGeneratorWorker> =
+            Arc::new(|account, state, _package, publisher, txn_factory, _rng| {
+                let step = account_state::<u64>(state);
+                *step += 1;
+                account.sign_with_transaction_builder(txn_factory.payload(
+                    aptos_stdlib::aptos_coin_transfer(publisher.address(), *step),
+                ))
+            });
+        let account_states = Arc::new(Mutex::new(HashMap::new()));
+        let mut generator = CustomModulesDelegationGenerator::new(
+            StdRng::seed_from_u64(1),
+            TransactionFactory::new(ChainId::test()),
+            Arc::new(packages),
+            txn_generator,
+            account_states.clone(),
+        );
+        let mut account = LocalAccount::generate(&mut rng);
+        let mut other = LocalAccount::generate(&mut rng);
+
+        let steps_by_publisher = |txns: &[SignedTransaction]| {
+            let mut steps = HashMap::<AccountAddress, Vec<u64>>::new();
+            for (publisher, step) in transfers(txns) {
+                steps.entry(publisher).or_default().push(step);
+            }
+            steps
+        };
+        let mut txns = generator.generate_transactions(&mut account, 10);
+        txns.extend(generator.generate_transactions(&mut account, 10));
+        let steps = steps_by_publisher(&txns);
+        assert_eq!(steps.len(), 2);
+        for steps in steps.values() {
+            assert_eq!(steps, &(1..=steps.len() as u64).collect::<Vec<_>>());
+        }
+        assert_eq!(account_states.lock().len(), 2);
+
+        // other accounts start their own journeys
+        let other_txns = generator.generate_transactions(&mut other, 10);
+        for steps in steps_by_publisher(&other_txns).values() {
+            assert_eq!(steps[0], 1);
+        }
+
+        // and they start over once the account is out of sync with the chain
+        generator.resync_sequence_numbers(&HashMap::from([(account.address(), (20, 10))]));
+        assert_eq!(account_states.lock().len(), 2);
+        let txns = generator.generate_transactions(&mut account, 10);
+        for steps in steps_by_publisher(&txns).values() {
+            assert_eq!(steps[0], 1);
+        }
+    }
+}
@@ -0,0 +1,74 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use super::{
+    publishing::{module_simple::EntryPoints, publish_util::Package},
+    ReliableTransactionSubmitter,
+};
+use crate::call_custom_modules::{TransactionGeneratorWorker, UserModuleTransactionGenerator};
+use aptos_sdk::{
+    transaction_builder::TransactionFactory,
+    types::{transaction::SignedTransaction, LocalAccount},
+};
+use async_trait::async_trait;
+use rand::{rngs::StdRng, Rng};
+use std::sync::Arc;
+
+/// Mix of conflicting and non-conflicting transactions, to characterize parallel
+/// execution across a sweep of conflict rates with a single knob.
+/// `conflict_percentage` of transactions increment the global counter of their publisher
+/// (the hot resources, one per module), the rest only write a resource of their sender.
+pub struct ConflictRateTransactionGenerator {
+    pub conflict_percentage: usize,
+}
+
+impl ConflictRateTransactionGenerator {
+    const CONFLICTING: EntryPoints = EntryPoints::StepDst;
+    const NON_CONFLICTING: EntryPoints = EntryPoints::BytesMakeOrChange {
+        data_length: Some(32),
+    };
+}
+
+#[async_trait]
+impl UserModuleTransactionGenerator for ConflictRateTransactionGenerator {
+    fn initialize_package(
+        &mut self,
+        _package: &Package,
+        _publisher: &mut LocalAccount,
+        _txn_factory: &TransactionFactory,
+        _rng: &mut StdRng,
+    ) -> Vec<SignedTransaction> {
+        // publisher's counter is created by init_module
+        vec![]
+    }
+
+    async fn create_generator_fn(
+        &self,
+        _init_accounts: &mut [LocalAccount],
+        _txn_factory: &TransactionFactory,
+        _txn_executor: &dyn ReliableTransactionSubmitter,
+        _rng: &mut StdRng,
+    ) -> Arc<TransactionGeneratorWorker> {
+        assert!(self.conflict_percentage <= 100);
+        let conflict_percentage = self.conflict_percentage;
+
+        Arc::new(
+            move |account, _state, package, publisher, txn_factory, rng| {
+                let payload = if rng.gen_range(0, 100) < conflict_percentage {
+                    Self::CONFLICTING.create_payload(
+                        package.get_module_id(Self::CONFLICTING.module_name()),
+                        Some(rng),
+                        Some(&publisher.address()),
+                    )
+                } else {
+                    Self::NON_CONFLICTING.create_payload(
+                        package.get_module_id(Self::NON_CONFLICTING.module_name()),
+                        Some(rng),
+                        None,
+                    )
+                };
+                account.sign_with_transaction_builder(txn_factory.payload(payload))
+            },
+        )
+    }
+}
@@ -0,0 +1,239 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    create_funded_accounts, resync_accounts, ReliableTransactionSubmitter, TransactionGenerator,
+    TransactionGeneratorCreator,
+};
+use aptos_infallible::Mutex;
+use aptos_logger::info;
+use aptos_sdk::{
+    move_types::account_address::AccountAddress,
+    transaction_builder::{aptos_stdlib, TransactionFactory},
+    types::{
+        account_address::create_resource_address,
+        transaction::{SignedTransaction, TransactionPayload},
+        LocalAccount,
+    },
+};
+use rand::rngs::StdRng;
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+};
+
+/// Salt of the resource accounts hosting delegation pools, see `delegation_pool.move`
+const DELEGATION_POOL_SALT: &[u8] = b"aptos_framework::delegation_pool";
+
+/// Smallest stake a delegator can have active or pending inactive (`MIN_COINS_ON_SHARES_POOL`)
+const MIN_STAKE: u64 = 1_000_000_000;
+
+const OPERATOR_COMMISSION_PERCENTAGE: u64 = 10;
+
+/// An account staking, either as a delegator on a delegation pool, or as the owner of its
+/// own stake pool, and the next operation it sends
+struct Staker {
+    account: LocalAccount,
+    /// Delegation pool the account delegates to, None when it stakes on its own stake pool
+    pool_address: Option<AccountAddress>,
+    next_operation: usize,
+}
+
+impl Staker {
+    /// Cycles through adding stake (enough to keep the minimum after the add stake fee),
+    /// unlocking half of it, and withdrawing it. Withdrawing only moves the stake unlocked
+    /// once the lockup of the pool ends, so stake accumulates in all states across epochs.
+    fn next_payload(&mut self) -> TransactionPayload {
+        let payload = match (self.pool_address, self.next_operation % 3) {
+            (Some(pool_address), 0) => {
+                aptos_stdlib::delegation_pool_add_stake(pool_address, 2 * MIN_STAKE)
+            },
+            (Some(pool_address), 1) => {
+                aptos_stdlib::delegation_pool_unlock(pool_address, MIN_STAKE)
+            },
+            (Some(pool_address), _) => {
+                aptos_stdlib::delegation_pool_withdraw(pool_address, MIN_STAKE)
+            },
+            (None, 0) => aptos_stdlib::stake_add_stake(2 * MIN_STAKE),
+            (None, 1) => aptos_stdlib::stake_unlock(MIN_STAKE),
+            (None, _) => aptos_stdlib::stake_withdraw(MIN_STAKE),
+        };
+        self.next_operation += 1;
+        payload
+    }
+}
+
+/// Delegators adding, unlocking and withdrawing stake on delegation pools, and owners doing
+/// the same on their own stake pools. Neither pool joins the validator set (that needs a
+/// validator config and the minimum validator stake), so they earn no rewards, but the
+/// operations go through the stake pool synchronization and lockup handling of every epoch.
+/// Stakers are shared by all workers, and taken in turns; the accounts passed in by the
+/// emitter are not used.
+pub struct DelegationPoolGenerator {
+    txn_factory: TransactionFactory,
+    stakers: Arc<Vec<Mutex<Staker>>>,
+    next_staker: Arc<AtomicUsize>,
+}
+
+impl TransactionGenerator for DelegationPoolGenerator {
+    fn generate_transactions(
+        &mut self,
+        _account: &mut LocalAccount,
+        num_to_create: usize,
+    ) -> Vec<SignedTransaction> {
+        (0..num_to_create)
+            .map(|_| {
+                let index = self.next_staker.fetch_add(1, Ordering::Relaxed) % self.stakers.len();
+                let mut staker = self.stakers[index].lock();
+                let payload = staker.next_payload();
+                staker
+                    .account
+                    .sign_with_transaction_builder(self.txn_factory.payload(payload))
+            })
+            .collect()
+    }
+
+    fn resync_sequence_numbers(&mut self, stale: &HashMap<AccountAddress, (u64, u64)>) {
+        for staker in self.stakers.iter() {
+            resync_accounts([&mut staker.lock().account], stale);
+        }
+    }
+}
+
+pub struct DelegationPoolGeneratorCreator {
+    txn_factory: TransactionFactory,
+    stakers: Arc<Vec<Mutex<Staker>>>,
+    next_staker: Arc<AtomicUsize>,
+}
+
+impl DelegationPoolGeneratorCreator {
+    /// Creates `num_pools` delegation pools (owned by new accounts), `num_delegators`
+    /// delegators spread over them, and `num_stake_owners` accounts with a stake pool of
+    /// their own, all funded from the source accounts.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn new(
+        txn_factory: TransactionFactory,
+        init_txn_factory: TransactionFactory,
+        source_accounts: &mut [LocalAccount],
+        txn_executor: &dyn ReliableTransactionSubmitter,
+        num_pools: usize,
+        num_delegators: usize,
+        num_stake_owners: usize,
+        rng: &mut StdRng,
+    ) -> Self {
+        assert!(
+            num_delegators == 0 || num_pools > 0,
+            "Need pools to delegate to"
+        );
+        assert!(
+            num_delegators + num_stake_owners > 0,
+            "Need delegators or stake owners"
+        );
+        let mut stakers = Vec::with_capacity(num_delegators + num_stake_owners);
+
+        if num_delegators > 0 {
+            let mut owners = create_funded_accounts(
+                "delegation pool owner",
+                num_pools,
+                source_accounts,
+                txn_executor,
+                &init_txn_factory,
+                rng,
+            )
+            .await;
+            info!("Initializing {} delegation pools", num_pools);
+            txn_executor
+                .execute_transactions(
+                    &owners
+                        .iter_mut()
+                        .map(|owner| {
+                            owner.sign_with_transaction_builder(init_txn_factory.payload(
+                                aptos_stdlib::delegation_pool_initialize_delegation_pool(
+                                    OPERATOR_COMMISSION_PERCENTAGE,
+                                    vec![],
+                                ),
+                            ))
+                        })
+                        .collect::<Vec<_>>(),
+                )
+                .await
+                .unwrap();
+            let pool_addresses: Vec<_> = owners
+                .iter()
+                .map(|owner| create_resource_address(owner.address(), DELEGATION_POOL_SALT))
+                .collect();
+
+            let delegators = create_funded_accounts(
+                "delegator",
+                num_delegators,
+                source_accounts,
+                txn_executor,
+                &init_txn_factory,
+                rng,
+            )
+            .await;
+            stakers.extend(
+                delegators
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, account)| Staker {
+                        account,
+                        pool_address: Some(pool_addresses[i % num_pools]),
+                        next_operation: 0,
+                    }),
+            );
+        }
+
+        if num_stake_owners > 0 {
+            let mut stake_owners = create_funded_accounts(
+                "stake owner",
+                num_stake_owners,
+                source_accounts,
+                txn_executor,
+                &init_txn_factory,
+                rng,
+            )
+            .await;
+            info!("Initializing {} stake pools", num_stake_owners);
+            txn_executor
+                .execute_transactions(
+                    &stake_owners
+                        .iter_mut()
+                        .map(|owner| {
+                            let address = owner.address();
+                            // the owner is its own operator and voter, stake is added later
+                            owner.sign_with_transaction_builder(init_txn_factory.payload(
+                                aptos_stdlib::stake_initialize_stake_owner(0, address, address),
+                            ))
+                        })
+                        .collect::<Vec<_>>(),
+                )
+                .await
+                .unwrap();
+            stakers.extend(stake_owners.into_iter().map(|account| Staker {
+                account,
+                pool_address: None,
+                next_operation: 0,
+            }));
+        }
+
+        Self {
+            txn_factory,
+            stakers: Arc::new(stakers.into_iter().map(Mutex::new).collect()),
+            next_staker: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+}
+
+impl TransactionGeneratorCreator for DelegationPoolGeneratorCreator {
+    fn create_transaction_generator(&mut self) -> Box<dyn TransactionGenerator> {
+        Box::new(DelegationPoolGenerator {
+            txn_factory: self.txn_factory.clone(),
+            stakers: self.stakers.clone(),
+            next_staker: self.next_staker.clone(),
+        })
+    }
+}
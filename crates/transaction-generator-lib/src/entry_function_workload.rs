@@ -0,0 +1,463 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    create_account_transaction, new_rng, ReliableTransactionSubmitter, TransactionGenerator,
+    TransactionGeneratorCreator,
+};
+use anyhow::{bail, Context, Result};
+use aptos_logger::info;
+use aptos_sdk::{
+    bcs,
+    move_types::{
+        account_address::AccountAddress,
+        identifier::Identifier,
+        language_storage::{ModuleId, TypeTag},
+    },
+    transaction_builder::TransactionFactory,
+    types::{
+        transaction::{EntryFunction, SignedTransaction, TransactionPayload},
+        LocalAccount,
+    },
+};
+use rand::{distributions::Alphanumeric, rngs::StdRng, Rng, RngCore};
+use serde::{Deserialize, Serialize};
+use std::{fs, path::Path, str::FromStr, sync::Arc};
+
+/// Workload calling a single entry function of an already published module, fully described
+/// by a yaml config, e.g.:
+///
+/// ```yaml
+/// module_address: "0x1"
+/// module_name: aptos_account
+/// function: transfer
+/// args:
+///   - type: random_address
+///   - type: random_u64
+///     min: 1
+///     max: 100
+/// ```
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct EntryFunctionWorkload {
+    pub module_address: AccountAddress,
+    pub module_name: String,
+    pub function: String,
+    /// Parsed as move type tags, e.g. `0x1::aptos_coin::AptosCoin`
+    #[serde(default)]
+    pub type_args: Vec<String>,
+    #[serde(default)]
+    pub args: Vec<ArgTemplate>,
+    #[serde(default)]
+    pub signer_mode: SignerMode,
+}
+
+/// Template of a single argument, BCS encoded anew for every transaction
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ArgTemplate {
+    Bool {
+        value: bool,
+    },
+    U8 {
+        value: u8,
+    },
+    U64 {
+        value: u64,
+    },
+    U128 {
+        value: u128,
+    },
+    /// Uniformly sampled from `[min, max]`
+    RandomU64 {
+        min: u64,
+        max: u64,
+    },
+    Address {
+        value: AccountAddress,
+    },
+    /// Address of the account sending the transaction
+    SenderAddress,
+    RandomAddress,
+    String {
+        value: String,
+    },
+    /// Alphanumeric string of the given length
+    RandomString {
+        length: usize,
+    },
+    Bytes {
+        value: Vec<u8>,
+    },
+    RandomBytes {
+        length: usize,
+    },
+}
+
+impl ArgTemplate {
+    pub fn generate(&self, sender: AccountAddress, rng: &mut StdRng) -> Vec<u8> {
+        match self {
+            ArgTemplate::Bool { value } => bcs::to_bytes(value),
+            ArgTemplate::U8 { value } => bcs::to_bytes(value),
+            ArgTemplate::U64 { value } => bcs::to_bytes(value),
+            ArgTemplate::U128 { value } => bcs::to_bytes(value),
+            ArgTemplate::RandomU64 { min, max } => bcs::to_bytes(&rng.gen_range(*min, *max + 1)),
+            ArgTemplate::Address { value } => bcs::to_bytes(value),
+            ArgTemplate::SenderAddress => bcs::to_bytes(&sender),
+            ArgTemplate::RandomAddress => bcs::to_bytes(&AccountAddress::new(rng.gen())),
+            ArgTemplate::String { value } => bcs::to_bytes(value),
+            ArgTemplate::RandomString { length } => bcs::to_bytes(
+                &rng.sample_iter(&Alphanumeric)
+                    .take(*length)
+                    .map(char::from)
+                    .collect::<String>(),
+            ),
+            ArgTemplate::Bytes { value } => bcs::to_bytes(value),
+            ArgTemplate::RandomBytes { length } => {
+                let mut bytes = vec![0u8; *length];
+                rng.fill_bytes(&mut bytes);
+                bcs::to_bytes(&bytes)
+            },
+        }
+        .expect("Argument must serialize")
+    }
+}
+
+/// Parses an argument given on the command line as `<type>:<value>`, e.g. `u64:5`,
+/// `address:0x1`, `string:hello`, `hex:0x0102`, or a randomized one, e.g. `sender`,
+/// `random_address`, `random_u64:<min>:<max>`, `random_string:<length>`,
+/// `random_bytes:<length>`.
+impl FromStr for ArgTemplate {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let (arg_type, value) = s.split_once(':').unwrap_or((s, ""));
+        let arg = match arg_type {
+            "bool" => ArgTemplate::Bool {
+                value: value.parse()?,
+            },
+            "u8" => ArgTemplate::U8 {
+                value: value.parse()?,
+            },
+            "u64" => ArgTemplate::U64 {
+                value: value.parse()?,
+            },
+            "u128" => ArgTemplate::U128 {
+                value: value.parse()?,
+            },
+            "random_u64" => {
+                let (min, max) = value
+                    .split_once(':')
+                    .with_context(|| format!("Expected random_u64:<min>:<max>, got {}", s))?;
+                ArgTemplate::RandomU64 {
+                    min: min.parse()?,
+                    max: max.parse()?,
+                }
+            },
+            "address" => ArgTemplate::Address {
+                value: AccountAddress::from_str(value)?,
+            },
+            "sender" => ArgTemplate::SenderAddress,
+            "random_address" => ArgTemplate::RandomAddress,
+            "string" => ArgTemplate::String {
+                value: value.to_string(),
+            },
+            "random_string" => ArgTemplate::RandomString {
+                length: value.parse()?,
+            },
+            "hex" => ArgTemplate::Bytes {
+                value: parse_hex(value)?,
+            },
+            "random_bytes" => ArgTemplate::RandomBytes {
+                length: value.parse()?,
+            },
+            _ => bail!("Unknown argument type {} in {}", arg_type, s),
+        };
+        Ok(arg)
+    }
+}
+
+fn parse_hex(value: &str) -> Result<Vec<u8>> {
+    let digits = value.strip_prefix("0x").unwrap_or(value);
+    if digits.len() % 2 != 0 || !digits.is_ascii() {
+        bail!("Invalid hex {}", value);
+    }
+    (0..digits.len())
+        .step_by(2)
+        .map(|i| {
+            u8::from_str_radix(&digits[i..i + 2], 16)
+                .with_context(|| format!("Invalid hex {}", value))
+        })
+        .collect()
+}
+
+/// Who signs the transactions, besides the sender
+#[derive(Debug, Copy, Clone, Default, Deserialize, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SignerMode {
+    #[default]
+    Sender,
+    /// Multi agent transactions, co-signed by accounts created during the initialization
+    MultiAgent { num_additional_signers: usize },
+}
+
+impl EntryFunctionWorkload {
+    pub fn from_yaml_str(yaml: &str) -> Result<Self> {
+        let workload: Self = serde_yaml::from_str(yaml)?;
+        workload.validate()?;
+        Ok(workload)
+    }
+
+    pub fn load(path: &Path) -> Result<Arc<Self>> {
+        let yaml = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read workload config {:?}", path))?;
+        let workload = Self::from_yaml_str(&yaml)
+            .with_context(|| format!("Invalid workload config {:?}", path))?;
+        Ok(Arc::new(workload))
+    }
+
+    /// Builds the workload from command line arguments: the function as
+    /// `<address>::<module>::<function>`, type arguments as move type tags, and arguments as
+    /// parsed by `ArgTemplate::from_str`.
+    pub fn from_cli(
+        entry_function: &str,
+        type_args: &[String],
+        args: &[String],
+    ) -> Result<Arc<Self>> {
+        let parts = entry_function.split("::").collect::<Vec<_>>();
+        if parts.len() != 3 {
+            bail!(
+                "Expected <address>::<module>::<function>, got {}",
+                entry_function
+            );
+        }
+        let workload = Self {
+            module_address: AccountAddress::from_str(parts[0])?,
+            module_name: parts[1].to_string(),
+            function: parts[2].to_string(),
+            type_args: type_args.to_vec(),
+            args: args
+                .iter()
+                .map(|arg| ArgTemplate::from_str(arg))
+                .collect::<Result<Vec<_>>>()?,
+            signer_mode: SignerMode::Sender,
+        };
+        workload
+            .validate()
+            .with_context(|| format!("Invalid entry function {}", entry_function))?;
+        Ok(Arc::new(workload))
+    }
+
+    fn validate(&self) -> Result<()> {
+        self.entry_function()?;
+        for arg in &self.args {
+            if let ArgTemplate::RandomU64 { min, max } = arg {
+                if min > max || *max == u64::MAX {
+                    bail!("Invalid random_u64 range [{}, {}]", min, max);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn entry_function(&self) -> Result<(ModuleId, Identifier, Vec<TypeTag>)> {
+        let module_id = ModuleId::new(
+            self.module_address,
+            Identifier::new(self.module_name.as_str())?,
+        );
+        let function = Identifier::new(self.function.as_str())?;
+        let type_args = self
+            .type_args
+            .iter()
+            .map(|type_arg| TypeTag::from_str(type_arg))
+            .collect::<Result<Vec<_>>>()?;
+        Ok((module_id, function, type_args))
+    }
+
+    pub fn create_payload(&self, sender: AccountAddress, rng: &mut StdRng) -> TransactionPayload {
+        let (module_id, function, type_args) = self
+            .entry_function()
+            .expect("Workload is validated on load");
+        TransactionPayload::EntryFunction(EntryFunction::new(
+            module_id,
+            function,
+            type_args,
+            self.args
+                .iter()
+                .map(|arg| arg.generate(sender, rng))
+                .collect(),
+        ))
+    }
+}
+
+pub struct EntryFunctionTransactionGenerator {
+    rng: StdRng,
+    txn_factory: TransactionFactory,
+    workload: Arc<EntryFunctionWorkload>,
+    additional_signers: Arc<Vec<LocalAccount>>,
+}
+
+impl TransactionGenerator for EntryFunctionTransactionGenerator {
+    fn generate_transactions(
+        &mut self,
+        account: &mut LocalAccount,
+        num_to_create: usize,
+    ) -> Vec<SignedTransaction> {
+        let mut requests = Vec::with_capacity(num_to_create);
+        for _ in 0..num_to_create {
+            let payload = self
+                .workload
+                .create_payload(account.address(), &mut self.rng);
+            let builder = self.txn_factory.payload(payload);
+            requests.push(match self.workload.signer_mode {
+                SignerMode::Sender => account.sign_with_transaction_builder(builder),
+                SignerMode::MultiAgent { .. } => account.sign_multi_agent_with_transaction_builder(
+                    self.additional_signers.iter().collect(),
+                    builder,
+                ),
+            });
+        }
+        requests
+    }
+}
+
+pub struct EntryFunctionTransactionGeneratorCreator {
+    txn_factory: TransactionFactory,
+    workload: Arc<EntryFunctionWorkload>,
+    additional_signers: Arc<Vec<LocalAccount>>,
+    rng: StdRng,
+}
+
+impl EntryFunctionTransactionGeneratorCreator {
+    pub async fn new(
+        txn_factory: TransactionFactory,
+        init_txn_factory: TransactionFactory,
+        accounts: &mut [LocalAccount],
+        txn_executor: &dyn ReliableTransactionSubmitter,
+        workload: Arc<EntryFunctionWorkload>,
+        mut rng: StdRng,
+    ) -> Self {
+        let additional_signers = match workload.signer_mode {
+            SignerMode::Sender => vec![],
+            SignerMode::MultiAgent {
+                num_additional_signers,
+            } => {
+                let new_accounts = (0..num_additional_signers)
+                    .map(|_| LocalAccount::generate(&mut rng))
+                    .collect::<Vec<_>>();
+                let sender = accounts.get_mut(0).unwrap();
+                info!(
+                    "Creating {} additional signer accounts for {}::{}",
+                    new_accounts.len(),
+                    workload.module_name,
+                    workload.function
+                );
+                txn_executor
+                    .execute_transactions(
+                        &new_accounts
+                            .iter()
+                            .map(|to| {
+                                create_account_transaction(
+                                    sender,
+                                    to.address(),
+                                    &init_txn_factory,
+                                    0,
+                                )
+                            })
+                            .collect::<Vec<_>>(),
+                    )
+                    .await
+                    .unwrap();
+                new_accounts
+            },
+        };
+        Self {
+            txn_factory,
+            workload,
+            additional_signers: Arc::new(additional_signers),
+            rng,
+        }
+    }
+}
+
+impl TransactionGeneratorCreator for EntryFunctionTransactionGeneratorCreator {
+    fn create_transaction_generator(&mut self) -> Box<dyn TransactionGenerator> {
+        Box::new(EntryFunctionTransactionGenerator {
+            rng: new_rng(&mut self.rng),
+            txn_factory: self.txn_factory.clone(),
+            workload: self.workload.clone(),
+            additional_signers: self.additional_signers.clone(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_arg_template() {
+        assert!(matches!(
+            ArgTemplate::from_str("bool:true").unwrap(),
+            ArgTemplate::Bool { value: true }
+        ));
+        assert!(matches!(
+            ArgTemplate::from_str("u64:5").unwrap(),
+            ArgTemplate::U64 { value: 5 }
+        ));
+        assert!(matches!(
+            ArgTemplate::from_str("random_u64:1:100").unwrap(),
+            ArgTemplate::RandomU64 { min: 1, max: 100 }
+        ));
+        assert!(matches!(
+            ArgTemplate::from_str("address:0x1").unwrap(),
+            ArgTemplate::Address { value } if value == AccountAddress::ONE
+        ));
+        assert!(matches!(
+            ArgTemplate::from_str("sender").unwrap(),
+            ArgTemplate::SenderAddress
+        ));
+        assert!(matches!(
+            ArgTemplate::from_str("string:a:b").unwrap(),
+            ArgTemplate::String { value } if value == "a:b"
+        ));
+        assert!(matches!(
+            ArgTemplate::from_str("hex:0x0aFF").unwrap(),
+            ArgTemplate::Bytes { value } if value == vec![0x0a, 0xff]
+        ));
+        assert!(matches!(
+            ArgTemplate::from_str("random_bytes:32").unwrap(),
+            ArgTemplate::RandomBytes { length: 32 }
+        ));
+
+        for invalid in [
+            "u8:256",
+            "u64",
+            "random_u64:5",
+            "address:0xg",
+            "hex:0x012",
+            "hex:zz",
+            "float:1.0",
+        ] {
+            assert!(ArgTemplate::from_str(invalid).is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_from_cli() {
+        let workload = EntryFunctionWorkload::from_cli("0x1::aptos_account::transfer", &[], &[
+            "random_address".to_string(),
+            "u64:1".to_string(),
+        ])
+        .unwrap();
+        assert_eq!(workload.module_address, AccountAddress::ONE);
+        assert_eq!(workload.args.len(), 2);
+
+        assert!(EntryFunctionWorkload::from_cli("0x1::aptos_account", &[], &[]).is_err());
+        assert!(
+            EntryFunctionWorkload::from_cli("0x1::aptos_account::transfer", &[], &[
+                "random_u64:5:1".to_string()
+            ],)
+            .is_err()
+        );
+    }
+}
@@ -0,0 +1,135 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    create_funded_accounts, new_rng, ReliableTransactionSubmitter, TransactionGenerator,
+    TransactionGeneratorCreator,
+};
+use aptos_logger::{sample, sample::SampleRate, warn};
+use aptos_sdk::{
+    move_types::account_address::AccountAddress,
+    transaction_builder::TransactionFactory,
+    types::{
+        transaction::{authenticator::TransactionAuthenticator, SignedTransaction},
+        LocalAccount,
+    },
+};
+use rand::{rngs::StdRng, seq::SliceRandom};
+use std::{collections::HashMap, sync::Arc, time::Duration};
+
+/// Wrapper that turns the transactions of the inner generator into sponsored ones, with the
+/// gas paid by a fee payer picked from the sponsor pool. Only transactions signed by the sender
+/// alone can be re-signed, the others (e.g. multi agent ones) are submitted as they are.
+pub struct FeePayerWrapperGenerator {
+    rng: StdRng,
+    generator: Box<dyn TransactionGenerator>,
+    sponsors: Arc<Vec<LocalAccount>>,
+}
+
+impl FeePayerWrapperGenerator {
+    pub fn new(
+        rng: StdRng,
+        generator: Box<dyn TransactionGenerator>,
+        sponsors: Arc<Vec<LocalAccount>>,
+    ) -> Self {
+        Self {
+            rng,
+            generator,
+            sponsors,
+        }
+    }
+}
+
+impl TransactionGenerator for FeePayerWrapperGenerator {
+    fn generate_transactions(
+        &mut self,
+        account: &mut LocalAccount,
+        num_to_create: usize,
+    ) -> Vec<SignedTransaction> {
+        self.generator
+            .generate_transactions(account, num_to_create)
+            .into_iter()
+            .map(|txn| {
+                if txn.sender() != account.address()
+                    || !matches!(
+                        txn.authenticator_ref(),
+                        TransactionAuthenticator::Ed25519 { .. }
+                    )
+                {
+                    sample!(
+                        SampleRate::Duration(Duration::from_secs(10)),
+                        warn!("Cannot sponsor a transaction not signed by the sender alone");
+                    );
+                    return txn;
+                }
+                let sponsor = self.sponsors.choose(&mut self.rng).unwrap();
+                txn.into_raw_transaction()
+                    .sign_fee_payer(
+                        account.private_key(),
+                        vec![],
+                        vec![],
+                        sponsor.address(),
+                        sponsor.private_key(),
+                    )
+                    .expect("Signing fee payer txn failed")
+                    .into_inner()
+            })
+            .collect()
+    }
+
+    fn resync_sequence_numbers(&mut self, stale: &HashMap<AccountAddress, (u64, u64)>) {
+        self.generator.resync_sequence_numbers(stale);
+    }
+}
+
+pub struct FeePayerWrapperCreator {
+    creator: Box<dyn TransactionGeneratorCreator>,
+    sponsors: Arc<Vec<LocalAccount>>,
+    rng: StdRng,
+}
+
+impl FeePayerWrapperCreator {
+    pub fn new(
+        creator: Box<dyn TransactionGeneratorCreator>,
+        sponsors: Arc<Vec<LocalAccount>>,
+        rng: StdRng,
+    ) -> Self {
+        Self {
+            creator,
+            sponsors,
+            rng,
+        }
+    }
+}
+
+impl TransactionGeneratorCreator for FeePayerWrapperCreator {
+    fn create_transaction_generator(&mut self) -> Box<dyn TransactionGenerator> {
+        Box::new(FeePayerWrapperGenerator::new(
+            new_rng(&mut self.rng),
+            self.creator.create_transaction_generator(),
+            self.sponsors.clone(),
+        ))
+    }
+}
+
+/// Create and fund the sponsor pool, sponsors receive half of the first source account's
+/// balance as they pay the gas of every sponsored transaction.
+pub async fn create_sponsors(
+    num_sponsors: usize,
+    source_accounts: &mut [LocalAccount],
+    txn_executor: &dyn ReliableTransactionSubmitter,
+    init_txn_factory: &TransactionFactory,
+    rng: &mut StdRng,
+) -> Arc<Vec<LocalAccount>> {
+    Arc::new(
+        create_funded_accounts(
+            "sponsor",
+            num_sponsors,
+            source_accounts,
+            txn_executor,
+            init_txn_factory,
+            rng,
+        )
+        .await,
+    )
+}
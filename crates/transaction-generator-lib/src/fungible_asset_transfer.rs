@@ -0,0 +1,125 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use super::{publishing::publish_util::Package, ReliableTransactionSubmitter};
+use crate::call_custom_modules::{TransactionGeneratorWorker, UserModuleTransactionGenerator};
+use aptos_infallible::RwLock;
+use aptos_sdk::{
+    bcs,
+    move_types::{account_address::AccountAddress, ident_str, language_storage::ModuleId},
+    transaction_builder::TransactionFactory,
+    types::{
+        transaction::{EntryFunction, SignedTransaction, TransactionPayload},
+        LocalAccount,
+    },
+};
+use async_trait::async_trait;
+use rand::{rngs::StdRng, seq::SliceRandom, Rng};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
+
+const MODULE_NAME: &str = "fungible_asset_example";
+const MINT_AMOUNT: u64 = 1_000_000_000;
+const TRANSFER_AMOUNT: u64 = 1;
+
+/// Accounts with a funded primary store of one fungible asset
+#[derive(Default)]
+struct Holders {
+    funded: HashSet<AccountAddress>,
+    addresses: Vec<AccountAddress>,
+}
+
+/// Transfers of a fungible asset (FA standard) created by each publisher. The first
+/// transaction of a sender funds its primary store, the following ones transfer to another
+/// holder, or to the publisher for `conflict_percentage` of them.
+pub struct FungibleAssetTransferGenerator {
+    pub conflict_percentage: usize,
+}
+
+#[async_trait]
+impl UserModuleTransactionGenerator for FungibleAssetTransferGenerator {
+    fn initialize_package(
+        &mut self,
+        package: &Package,
+        publisher: &mut LocalAccount,
+        txn_factory: &TransactionFactory,
+        _rng: &mut StdRng,
+    ) -> Vec<SignedTransaction> {
+        let payload = TransactionPayload::EntryFunction(EntryFunction::new(
+            package.get_module_id(MODULE_NAME),
+            ident_str!("initialize").to_owned(),
+            vec![],
+            vec![],
+        ));
+        vec![publisher.sign_with_transaction_builder(txn_factory.payload(payload))]
+    }
+
+    async fn create_generator_fn(
+        &self,
+        _init_accounts: &mut [LocalAccount],
+        _txn_factory: &TransactionFactory,
+        _txn_executor: &dyn ReliableTransactionSubmitter,
+        _rng: &mut StdRng,
+    ) -> Arc<TransactionGeneratorWorker> {
+        let conflict_percentage = self.conflict_percentage;
+        // holders of the asset of each publisher
+        let holders = Arc::new(RwLock::new(HashMap::<AccountAddress, Holders>::new()));
+
+        Arc::new(
+            move |account, _state, package, publisher, txn_factory, rng| {
+                let module_id = package.get_module_id(MODULE_NAME);
+                let sender = account.address();
+                let maybe_receiver = {
+                    let mut holders = holders.write();
+                    let holders = holders.entry(publisher.address()).or_default();
+                    if holders.funded.insert(sender) {
+                        holders.addresses.push(sender);
+                        None
+                    } else if rng.gen_range(0, 100) < conflict_percentage {
+                        Some(publisher.address())
+                    } else {
+                        holders.addresses.choose(rng).copied()
+                    }
+                };
+
+                match maybe_receiver {
+                    Some(receiver) => account.sign_with_transaction_builder(
+                        txn_factory.payload(transfer(module_id, publisher.address(), receiver)),
+                    ),
+                    None => account.sign_multi_agent_with_transaction_builder(
+                        vec![publisher],
+                        txn_factory.payload(mint(module_id)),
+                    ),
+                }
+            },
+        )
+    }
+}
+
+fn mint(module_id: ModuleId) -> TransactionPayload {
+    TransactionPayload::EntryFunction(EntryFunction::new(
+        module_id,
+        ident_str!("mint").to_owned(),
+        vec![],
+        vec![bcs::to_bytes(&MINT_AMOUNT).unwrap()],
+    ))
+}
+
+fn transfer(
+    module_id: ModuleId,
+    publisher: AccountAddress,
+    receiver: AccountAddress,
+) -> TransactionPayload {
+    TransactionPayload::EntryFunction(EntryFunction::new(
+        module_id,
+        ident_str!("transfer").to_owned(),
+        vec![],
+        vec![
+            bcs::to_bytes(&publisher).unwrap(),
+            bcs::to_bytes(&receiver).unwrap(),
+            bcs::to_bytes(&TRANSFER_AMOUNT).unwrap(),
+        ],
+    ))
+}
@@ -0,0 +1,178 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{new_rng, TransactionGenerator, TransactionGeneratorCreator};
+use anyhow::{bail, Result};
+use aptos_logger::{sample, sample::SampleRate, warn};
+use aptos_sdk::{
+    move_types::account_address::AccountAddress,
+    types::{
+        transaction::{authenticator::TransactionAuthenticator, RawTransaction, SignedTransaction},
+        LocalAccount,
+    },
+};
+use rand::{rngs::StdRng, Rng};
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, str::FromStr, time::Duration};
+
+/// Distribution gas unit prices of a workload are sampled from
+#[derive(Debug, Copy, Clone, PartialEq, Deserialize, Serialize)]
+pub enum GasPriceDistribution {
+    Fixed(u64),
+    /// Uniform between `min` and `max`, inclusive
+    Uniform {
+        min: u64,
+        max: u64,
+    },
+    /// Pareto with scale `min` and shape `alpha`, capped at `max`.
+    /// Most transactions pay close to `min`, a long tail pays a lot more.
+    Pareto {
+        min: u64,
+        alpha: f64,
+        max: u64,
+    },
+}
+
+impl GasPriceDistribution {
+    pub fn sample<R: Rng>(&self, rng: &mut R) -> u64 {
+        match *self {
+            GasPriceDistribution::Fixed(price) => price,
+            GasPriceDistribution::Uniform { min, max } => rng.gen_range(min, max + 1),
+            GasPriceDistribution::Pareto { min, alpha, max } => {
+                // inverse transform sampling, 1 - U is in (0, 1]
+                let uniform: f64 = 1.0 - rng.gen::<f64>();
+                ((min as f64 / uniform.powf(1.0 / alpha)) as u64).min(max)
+            },
+        }
+    }
+}
+
+impl FromStr for GasPriceDistribution {
+    type Err = anyhow::Error;
+
+    /// Parses `fixed:<price>`, `uniform:<min>:<max>` or `pareto:<min>:<alpha>:<max>`
+    fn from_str(s: &str) -> Result<Self> {
+        let parts: Vec<_> = s.split(':').collect();
+        let distribution = match parts.as_slice() {
+            ["fixed", price] => GasPriceDistribution::Fixed(price.parse()?),
+            ["uniform", min, max] => GasPriceDistribution::Uniform {
+                min: min.parse()?,
+                max: max.parse()?,
+            },
+            ["pareto", min, alpha, max] => GasPriceDistribution::Pareto {
+                min: min.parse()?,
+                alpha: alpha.parse()?,
+                max: max.parse()?,
+            },
+            _ => bail!(
+                "Invalid gas price distribution {}, expected fixed:<price>, uniform:<min>:<max> or pareto:<min>:<alpha>:<max>",
+                s
+            ),
+        };
+        match distribution {
+            GasPriceDistribution::Uniform { min, max } if min > max => {
+                bail!("Uniform gas price needs min <= max")
+            },
+            GasPriceDistribution::Pareto { min, alpha, max } if min > max || alpha <= 0.0 => {
+                bail!("Pareto gas price needs min <= max and a positive alpha")
+            },
+            _ => Ok(distribution),
+        }
+    }
+}
+
+/// Wrapper that re-signs the transactions of the inner generator with a gas unit price
+/// sampled from the distribution. Only transactions signed by the sender alone can be
+/// re-signed, the others (e.g. multi agent ones) keep the price of the transaction factory.
+pub struct GasPriceWrapperGenerator {
+    rng: StdRng,
+    generator: Box<dyn TransactionGenerator>,
+    gas_price: GasPriceDistribution,
+}
+
+impl GasPriceWrapperGenerator {
+    pub fn new(
+        rng: StdRng,
+        generator: Box<dyn TransactionGenerator>,
+        gas_price: GasPriceDistribution,
+    ) -> Self {
+        Self {
+            rng,
+            generator,
+            gas_price,
+        }
+    }
+}
+
+impl TransactionGenerator for GasPriceWrapperGenerator {
+    fn generate_transactions(
+        &mut self,
+        account: &mut LocalAccount,
+        num_to_create: usize,
+    ) -> Vec<SignedTransaction> {
+        self.generator
+            .generate_transactions(account, num_to_create)
+            .into_iter()
+            .map(|txn| {
+                if txn.sender() != account.address()
+                    || !matches!(
+                        txn.authenticator_ref(),
+                        TransactionAuthenticator::Ed25519 { .. }
+                    )
+                {
+                    sample!(
+                        SampleRate::Duration(Duration::from_secs(10)),
+                        warn!("Cannot change gas price of a transaction not signed by the sender alone");
+                    );
+                    return txn;
+                }
+                RawTransaction::new(
+                    txn.sender(),
+                    txn.sequence_number(),
+                    txn.payload().clone(),
+                    txn.max_gas_amount(),
+                    self.gas_price.sample(&mut self.rng),
+                    txn.expiration_timestamp_secs(),
+                    txn.chain_id(),
+                )
+                .sign(account.private_key(), account.public_key().clone())
+                .expect("Signing txn failed")
+                .into_inner()
+            })
+            .collect()
+    }
+
+    fn resync_sequence_numbers(&mut self, stale: &HashMap<AccountAddress, (u64, u64)>) {
+        self.generator.resync_sequence_numbers(stale);
+    }
+}
+
+pub struct GasPriceWrapperCreator {
+    creator: Box<dyn TransactionGeneratorCreator>,
+    gas_price: GasPriceDistribution,
+    rng: StdRng,
+}
+
+impl GasPriceWrapperCreator {
+    pub fn new(
+        creator: Box<dyn TransactionGeneratorCreator>,
+        gas_price: GasPriceDistribution,
+        rng: StdRng,
+    ) -> Self {
+        Self {
+            creator,
+            gas_price,
+            rng,
+        }
+    }
+}
+
+impl TransactionGeneratorCreator for GasPriceWrapperCreator {
+    fn create_transaction_generator(&mut self) -> Box<dyn TransactionGenerator> {
+        Box::new(GasPriceWrapperGenerator::new(
+            new_rng(&mut self.rng),
+            self.creator.create_transaction_generator(),
+            self.gas_price,
+        ))
+    }
+}
@@ -0,0 +1,156 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{new_rng, TransactionGenerator, TransactionGeneratorCreator};
+use aptos_sdk::{
+    crypto::{ed25519::Ed25519PrivateKey, Uniform},
+    move_types::account_address::AccountAddress,
+    types::{
+        chain_id::ChainId,
+        transaction::{authenticator::TransactionAuthenticator, RawTransaction, SignedTransaction},
+        LocalAccount,
+    },
+};
+use rand::{rngs::StdRng, Rng};
+use std::collections::HashMap;
+
+/// Max gas unit price accepted by validation, so the max fee of the transaction
+/// is above the balance of any account funded by the emitter
+const UNAFFORDABLE_GAS_UNIT_PRICE: u64 = 10_000_000_000;
+
+/// Ways the injected transactions are invalid, each rejected by a different check
+#[derive(Debug, Copy, Clone)]
+enum InvalidTransactionKind {
+    /// Signed by another key than the one of the sender
+    BadSignature,
+    /// Max fee above the balance of the sender
+    InsufficientBalance,
+    /// Expiration in the past
+    Expired,
+    WrongChainId,
+}
+
+impl InvalidTransactionKind {
+    fn random(rng: &mut StdRng) -> Self {
+        match rng.gen_range(0, 4) {
+            0 => Self::BadSignature,
+            1 => Self::InsufficientBalance,
+            2 => Self::Expired,
+            _ => Self::WrongChainId,
+        }
+    }
+}
+
+/// Wrapper replacing `invalid_percentage` of the transactions of the inner generator with
+/// invalid ones, to measure the overhead of rejecting them and its effect on valid traffic.
+/// Only the last transactions of the account in a batch are replaced, taking back their
+/// sequence numbers, so the following transactions of the account are not blocked.
+pub struct InvalidTransactionWrapperGenerator {
+    rng: StdRng,
+    generator: Box<dyn TransactionGenerator>,
+    invalid_percentage: usize,
+}
+
+impl InvalidTransactionWrapperGenerator {
+    fn make_invalid(
+        &mut self,
+        account: &LocalAccount,
+        txn: SignedTransaction,
+    ) -> SignedTransaction {
+        let kind = InvalidTransactionKind::random(&mut self.rng);
+        let raw_txn = RawTransaction::new(
+            txn.sender(),
+            txn.sequence_number(),
+            txn.payload().clone(),
+            txn.max_gas_amount(),
+            match kind {
+                InvalidTransactionKind::InsufficientBalance => UNAFFORDABLE_GAS_UNIT_PRICE,
+                _ => txn.gas_unit_price(),
+            },
+            match kind {
+                InvalidTransactionKind::Expired => 1,
+                _ => txn.expiration_timestamp_secs(),
+            },
+            match kind {
+                InvalidTransactionKind::WrongChainId => ChainId::new(255),
+                _ => txn.chain_id(),
+            },
+        );
+        match kind {
+            InvalidTransactionKind::BadSignature => {
+                let other_key = Ed25519PrivateKey::generate(&mut self.rng);
+                raw_txn
+                    .sign(&other_key, account.public_key().clone())
+                    .expect("Signing a txn can't fail")
+                    .into_inner()
+            },
+            _ => account.sign_transaction(raw_txn),
+        }
+    }
+}
+
+impl TransactionGenerator for InvalidTransactionWrapperGenerator {
+    fn generate_transactions(
+        &mut self,
+        account: &mut LocalAccount,
+        num_to_create: usize,
+    ) -> Vec<SignedTransaction> {
+        let mut txns = self.generator.generate_transactions(account, num_to_create);
+        let num_invalid = (0..txns.len())
+            .filter(|_| self.rng.gen_range(0, 100) < self.invalid_percentage)
+            .count();
+        for i in (0..txns.len()).rev().take(num_invalid) {
+            let txn = &txns[i];
+            if txn.sender() != account.address()
+                || txn.sequence_number() + 1 != account.sequence_number()
+                || !matches!(
+                    txn.authenticator_ref(),
+                    TransactionAuthenticator::Ed25519 { .. }
+                )
+            {
+                break;
+            }
+            *account.sequence_number_mut() -= 1;
+            txns[i] = self.make_invalid(account, txns[i].clone());
+        }
+        txns
+    }
+
+    fn resync_sequence_numbers(&mut self, stale: &HashMap<AccountAddress, (u64, u64)>) {
+        self.generator.resync_sequence_numbers(stale);
+    }
+}
+
+pub struct InvalidTransactionWrapperCreator {
+    creator: Box<dyn TransactionGeneratorCreator>,
+    invalid_percentage: usize,
+    rng: StdRng,
+}
+
+impl InvalidTransactionWrapperCreator {
+    pub fn new(
+        creator: Box<dyn TransactionGeneratorCreator>,
+        invalid_percentage: usize,
+        rng: StdRng,
+    ) -> Self {
+        assert!(
+            invalid_percentage <= 100,
+            "Invalid transaction percentage needs to be at most 100"
+        );
+        Self {
+            creator,
+            invalid_percentage,
+            rng,
+        }
+    }
+}
+
+impl TransactionGeneratorCreator for InvalidTransactionWrapperCreator {
+    fn create_transaction_generator(&mut self) -> Box<dyn TransactionGenerator> {
+        Box::new(InvalidTransactionWrapperGenerator {
+            rng: new_rng(&mut self.rng),
+            generator: self.creator.create_transaction_generator(),
+            invalid_percentage: self.invalid_percentage,
+        })
+    }
+}
@@ -3,9 +3,9 @@
 
 #![forbid(unsafe_code)]
 
-use anyhow::Result;
-use aptos_infallible::RwLock;
-use aptos_logger::{sample, sample::SampleRate, warn};
+use anyhow::{ensure, Result};
+use aptos_infallible::{Mutex, RwLock};
+use aptos_logger::info;
 use aptos_sdk::{
     move_types::account_address::AccountAddress,
     transaction_builder::{aptos_stdlib, TransactionFactory},
@@ -13,42 +13,106 @@ use aptos_sdk::{
 };
 use args::TransactionTypeArg;
 use async_trait::async_trait;
+use rand::{rngs::StdRng, SeedableRng};
 use std::{
     collections::HashMap,
     sync::{
         atomic::{AtomicUsize, Ordering},
         Arc,
     },
-    time::Duration,
 };
 
 mod account_generator;
+mod account_lifecycle;
+mod accounts_file;
+mod accounts_pool;
 mod accounts_pool_wrapper;
 pub mod args;
+mod argument_fuzzing;
 mod batch_transfer;
 mod call_custom_modules;
+mod conflict_rate;
+mod delegation_pool;
+mod entry_function_workload;
 mod entry_points;
+mod fee_payer_wrapper;
+mod fungible_asset_transfer;
+mod gas_price_wrapper;
+mod invalid_transaction_wrapper;
+mod multi_agent;
+mod multisig_generator;
+mod object_transfer;
 mod p2p_transaction_generator;
 pub mod publish_modules;
 mod publishing;
+mod replay;
+mod script_payload;
+mod signer_key_wrapper;
+mod token_v2_generator;
 mod transaction_mix_generator;
+mod verifier;
+mod workload_schedule;
 use self::{
     account_generator::AccountGeneratorCreator,
+    account_lifecycle::AccountLifecycleGeneratorCreator,
     call_custom_modules::CustomModulesDelegationGeneratorCreator,
+    multi_agent::MultiAgentTransactionGeneratorCreator,
+    multisig_generator::MultisigTransactionGeneratorCreator,
     p2p_transaction_generator::P2PTransactionGeneratorCreator,
-    publish_modules::PublishPackageCreator,
+    publish_modules::{PublishPackageCreator, PublishUpgradeCreator},
+    token_v2_generator::{TokenV2MintAndBurnGeneratorCreator, TokenV2TransactionGeneratorCreator},
     transaction_mix_generator::PhasedTxnMixGeneratorCreator,
 };
 use crate::{
     accounts_pool_wrapper::AccountsPoolWrapperCreator,
+    argument_fuzzing::ArgumentFuzzingGenerator,
     batch_transfer::BatchTransferTransactionGeneratorCreator,
-    entry_points::EntryPointTransactionGenerator, p2p_transaction_generator::SamplingMode,
+    conflict_rate::ConflictRateTransactionGenerator,
+    delegation_pool::DelegationPoolGeneratorCreator,
+    entry_function_workload::EntryFunctionTransactionGeneratorCreator,
+    entry_points::EntryPointTransactionGenerator,
+    fee_payer_wrapper::{create_sponsors, FeePayerWrapperCreator},
+    fungible_asset_transfer::FungibleAssetTransferGenerator,
+    gas_price_wrapper::GasPriceWrapperCreator,
+    object_transfer::ObjectTransferGenerator,
+    p2p_transaction_generator::SamplingMode,
+    replay::ReplayTransactionGeneratorCreator,
+    script_payload::ScriptPayloadGeneratorCreator,
+    signer_key_wrapper::{create_signers, MultiEd25519Account, SignerKeyWrapperCreator},
+    verifier::VerificationWrapperCreator,
 };
+pub use accounts_file::{
+    load_accounts, save_accounts, AccountShard, AccountShardMode, RecipientList,
+};
+pub use accounts_pool::{AccountsPool, LocalAccountsPool, ShardedAccountsPool};
+pub use accounts_pool_wrapper::AccountPoolRefill;
+pub use call_custom_modules::{account_state, AccountState};
+pub use entry_function_workload::{ArgTemplate, EntryFunctionWorkload, SignerMode};
+pub use gas_price_wrapper::GasPriceDistribution;
+pub use invalid_transaction_wrapper::InvalidTransactionWrapperCreator;
 pub use publishing::module_simple::EntryPoints;
+pub use replay::ReplayWorkload;
+pub use signer_key_wrapper::SignerKeyType;
+pub use token_v2_generator::expected_token_owner;
+pub use verifier::{
+    CommittedTransaction, EventEmittedVerifier, GasUsage, ResourceIncrementedVerifier,
+    SuccessVerifier, TransactionOutputReader, TransactionVerificationQueue, TransactionVerifier,
+    VerificationResults,
+};
+pub use workload_schedule::{ScheduledMix, WeightedTransactionType, WorkloadSchedule};
 
 pub const SEND_AMOUNT: u64 = 1;
 
-#[derive(Debug, Copy, Clone)]
+/// Shards of the accounts pool shared by the workers, see `ShardedAccountsPool`
+const ACCOUNTS_POOL_SHARDS: usize = 16;
+
+/// Rng of a creator or generator, derived from the rng of the one creating it. Creators and
+/// generators created in the same order from rngs with the same seed get the same rngs.
+pub fn new_rng(parent: &mut StdRng) -> StdRng {
+    StdRng::from_rng(parent).unwrap()
+}
+
+#[derive(Debug, Clone)]
 pub enum TransactionType {
     NonConflictingCoinTransfer {
         invalid_transaction_ratio: usize,
@@ -63,9 +127,16 @@ pub enum TransactionType {
         max_account_working_set: usize,
         creation_balance: u64,
     },
+    /// New accounts created, funded, transferring and retired, see `AccountLifecycleGenerator`
+    AccountLifecycle,
     PublishPackage {
         use_account_pool: bool,
     },
+    PublishAndUpgradePackage {
+        package_size: usize,
+        upgrade_percentage: usize,
+        use_account_pool: bool,
+    },
     CallCustomModules {
         entry_point: EntryPoints,
         num_modules: usize,
@@ -74,6 +145,72 @@ pub enum TransactionType {
     BatchTransfer {
         batch_size: usize,
     },
+    CallEntryFunction {
+        workload: Arc<EntryFunctionWorkload>,
+        use_account_pool: bool,
+    },
+    TokenV2MintAndTransfer {
+        collection_size: usize,
+        soulbound_percentage: usize,
+    },
+    TokenV2MintAndBurn {
+        live_tokens_per_account: usize,
+    },
+    FungibleAssetTransfer {
+        num_modules: usize,
+        conflict_percentage: usize,
+        use_account_pool: bool,
+    },
+    Multisig {
+        num_owners: usize,
+        num_signatures_required: usize,
+    },
+    /// Signed by the sender and secondary signers created for the workload
+    MultiAgent {
+        num_secondary_signers: usize,
+    },
+    ConflictRate {
+        num_modules: usize,
+        conflict_percentage: usize,
+        use_account_pool: bool,
+    },
+    Replay {
+        workload: Arc<ReplayWorkload>,
+        num_accounts: usize,
+    },
+    ScriptPayload {
+        max_num_args: usize,
+        max_arg_size: usize,
+    },
+    ObjectTransfer {
+        num_modules: usize,
+        payload_size: u64,
+        use_account_pool: bool,
+    },
+    /// Delegators on delegation pools and owners of stake pools, see `DelegationPoolGenerator`
+    DelegationPool {
+        num_pools: usize,
+        num_delegators: usize,
+        num_stake_owners: usize,
+    },
+    /// Entry functions of the package called with random arguments, see `ArgumentFuzzingGenerator`
+    ArgumentFuzzing {
+        package_name: &'static str,
+        num_modules: usize,
+        use_account_pool: bool,
+    },
+    /// Inner workload, with gas unit prices sampled from the distribution
+    CustomGasPrice {
+        transaction_type: Box<TransactionType>,
+        gas_price: GasPriceDistribution,
+    },
+    /// Inner workload, sending to the addresses of the list instead of to the accounts of
+    /// the run. Only for workloads that can send to accounts that don't exist yet, see
+    /// `TransactionType::sends_to_any_address`.
+    ToRecipients {
+        transaction_type: Box<TransactionType>,
+        recipients: Arc<RecipientList>,
+    },
 }
 
 impl Default for TransactionType {
@@ -82,12 +219,196 @@ impl Default for TransactionType {
     }
 }
 
+impl TransactionType {
+    /// Short name of the workload, to report per workload metrics under
+    pub fn workload_name(&self) -> String {
+        match self {
+            TransactionType::NonConflictingCoinTransfer { .. } => {
+                "NonConflictingCoinTransfer".to_string()
+            },
+            TransactionType::CoinTransfer { .. } => "CoinTransfer".to_string(),
+            TransactionType::AccountGeneration { .. } => "AccountGeneration".to_string(),
+            TransactionType::AccountLifecycle => "AccountLifecycle".to_string(),
+            TransactionType::PublishPackage { .. } => "PublishPackage".to_string(),
+            TransactionType::PublishAndUpgradePackage { .. } => {
+                "PublishAndUpgradePackage".to_string()
+            },
+            TransactionType::CallCustomModules { entry_point, .. } => format!("{:?}", entry_point),
+            TransactionType::BatchTransfer { batch_size } => {
+                format!("BatchTransfer({})", batch_size)
+            },
+            TransactionType::CallEntryFunction { workload, .. } => format!(
+                "{}::{}::{}",
+                workload.module_address.to_hex_literal(),
+                workload.module_name,
+                workload.function
+            ),
+            TransactionType::TokenV2MintAndTransfer { .. } => "TokenV2MintAndTransfer".to_string(),
+            TransactionType::TokenV2MintAndBurn { .. } => "TokenV2MintAndBurn".to_string(),
+            TransactionType::FungibleAssetTransfer { .. } => "FungibleAssetTransfer".to_string(),
+            TransactionType::Multisig { .. } => "Multisig".to_string(),
+            TransactionType::MultiAgent {
+                num_secondary_signers,
+            } => format!("MultiAgent({})", num_secondary_signers),
+            TransactionType::ConflictRate { .. } => "ConflictRate".to_string(),
+            TransactionType::Replay { .. } => "Replay".to_string(),
+            TransactionType::ScriptPayload { .. } => "ScriptPayload".to_string(),
+            TransactionType::ObjectTransfer { .. } => "ObjectTransfer".to_string(),
+            TransactionType::DelegationPool { .. } => "DelegationPool".to_string(),
+            TransactionType::ArgumentFuzzing { package_name, .. } => {
+                format!("ArgumentFuzzing({})", package_name)
+            },
+            TransactionType::CustomGasPrice {
+                transaction_type, ..
+            } => transaction_type.workload_name(),
+            TransactionType::ToRecipients {
+                transaction_type, ..
+            } => format!("ToRecipients({})", transaction_type.workload_name()),
+        }
+    }
+
+    /// Expected effects of the committed transactions of the workload,
+    /// None if they are not expected to succeed
+    pub fn verifier(&self) -> Option<Arc<dyn TransactionVerifier>> {
+        Some(match self {
+            TransactionType::NonConflictingCoinTransfer {
+                invalid_transaction_ratio,
+                ..
+            }
+            | TransactionType::CoinTransfer {
+                invalid_transaction_ratio,
+                ..
+            } => {
+                if *invalid_transaction_ratio > 0 {
+                    return None;
+                }
+                Arc::new(EventEmittedVerifier {
+                    event_type_suffix: "::coin::DepositEvent",
+                })
+            },
+            TransactionType::BatchTransfer { .. } => Arc::new(EventEmittedVerifier {
+                event_type_suffix: "::coin::DepositEvent",
+            }),
+            TransactionType::CallCustomModules {
+                entry_point: EntryPoints::EmitEvents { count },
+                ..
+            } if *count > 0 => Arc::new(EventEmittedVerifier {
+                event_type_suffix: "::simple::SimpleEvent",
+            }),
+            TransactionType::CallCustomModules {
+                entry_point: EntryPoints::EmitSizedEvents { count, .. },
+                ..
+            } if *count > 0 => Arc::new(EventEmittedVerifier {
+                event_type_suffix: "::events_example::SizedEvent",
+            }),
+            TransactionType::CallCustomModules {
+                entry_point: EntryPoints::LiquidityPoolSwap,
+                ..
+            } => Arc::new(ResourceIncrementedVerifier {
+                struct_name: "Pool",
+                field: "num_swaps",
+            }),
+            // replayed and fuzzed transactions are not all expected to succeed
+            TransactionType::Replay { .. } | TransactionType::ArgumentFuzzing { .. } => {
+                return None
+            },
+            TransactionType::CustomGasPrice {
+                transaction_type, ..
+            }
+            | TransactionType::ToRecipients {
+                transaction_type, ..
+            } => return transaction_type.verifier(),
+            _ => Arc::new(SuccessVerifier),
+        })
+    }
+
+    /// Checks the parameters of the workload, before any account is created for it
+    pub fn validate(&self) -> Result<()> {
+        match self {
+            TransactionType::Multisig {
+                num_owners,
+                num_signatures_required,
+            } => ensure!(
+                (1..=*num_owners).contains(num_signatures_required),
+                "Multisig needs between 1 and num_owners ({}) signatures, got {}",
+                num_owners,
+                num_signatures_required
+            ),
+            TransactionType::CustomGasPrice {
+                transaction_type, ..
+            } => transaction_type.validate()?,
+            TransactionType::ToRecipients {
+                transaction_type, ..
+            } => {
+                ensure!(
+                    transaction_type.sends_to_any_address(),
+                    "{} cannot send to a recipient list",
+                    transaction_type.workload_name()
+                );
+                transaction_type.validate()?
+            },
+            _ => {},
+        }
+        Ok(())
+    }
+
+    /// Whether the workload sends to other accounts, and its transactions succeed for
+    /// addresses without an account (the batch transfers create it, tokens are objects)
+    pub fn sends_to_any_address(&self) -> bool {
+        matches!(
+            self,
+            TransactionType::BatchTransfer { .. } | TransactionType::TokenV2MintAndTransfer { .. }
+        )
+    }
+
+    /// The workload sending to the addresses of the list, keeping its custom gas price
+    pub fn with_recipients(self, recipients: Arc<RecipientList>) -> Self {
+        match self {
+            TransactionType::CustomGasPrice {
+                transaction_type,
+                gas_price,
+            } => TransactionType::CustomGasPrice {
+                transaction_type: Box::new(transaction_type.with_recipients(recipients)),
+                gas_price,
+            },
+            transaction_type => TransactionType::ToRecipients {
+                transaction_type: Box::new(transaction_type),
+                recipients,
+            },
+        }
+    }
+}
+
 pub trait TransactionGenerator: Sync + Send {
     fn generate_transactions(
         &mut self,
         account: &mut LocalAccount,
         num_to_create: usize,
     ) -> Vec<SignedTransaction>;
+
+    /// Called with senders whose transactions didn't all commit (e.g. expired), mapped to
+    /// the sequence number they were expected to reach and the one fetched from the chain.
+    /// Generators signing with accounts of their own reset those still at the expected
+    /// sequence number, as all their following transactions would be rejected otherwise.
+    fn resync_sequence_numbers(&mut self, _stale: &HashMap<AccountAddress, (u64, u64)>) {}
+}
+
+/// Resets accounts still at the expected sequence number to the one fetched from the chain,
+/// see `TransactionGenerator::resync_sequence_numbers`.
+pub fn resync_accounts<'a>(
+    accounts: impl IntoIterator<Item = &'a mut LocalAccount>,
+    stale: &HashMap<AccountAddress, (u64, u64)>,
+) {
+    if stale.is_empty() {
+        return;
+    }
+    for account in accounts {
+        if let Some((expected, fetched)) = stale.get(&account.address()) {
+            if account.sequence_number() == *expected {
+                *account.sequence_number_mut() = *fetched;
+            }
+        }
+    }
 }
 
 #[async_trait]
@@ -171,6 +492,7 @@ impl CounterState {
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 pub async fn create_txn_generator_creator(
     transaction_mix_per_phase: &[Vec<(TransactionType, usize)>],
     source_accounts: &mut [LocalAccount],
@@ -179,10 +501,15 @@ pub async fn create_txn_generator_creator(
     txn_factory: &TransactionFactory,
     init_txn_factory: &TransactionFactory,
     cur_phase: Arc<AtomicUsize>,
+    num_sponsors: usize,
+    account_pool_refill: AccountPoolRefill,
+    signer_key_type: SignerKeyType,
+    verification_queue: Option<Arc<TransactionVerificationQueue>>,
+    mut rng: StdRng,
 ) -> (
     Box<dyn TransactionGeneratorCreator>,
     Arc<RwLock<Vec<AccountAddress>>>,
-    Arc<RwLock<Vec<LocalAccount>>>,
+    Arc<dyn AccountsPool>,
 ) {
     let addresses_pool = Arc::new(RwLock::new(
         source_accounts
@@ -191,19 +518,89 @@ pub async fn create_txn_generator_creator(
             .map(|d| d.address())
             .collect::<Vec<_>>(),
     ));
-    let accounts_pool = Arc::new(RwLock::new(initial_burner_accounts));
+    let accounts_pool: Arc<dyn AccountsPool> = Arc::new(ShardedAccountsPool::new(
+        initial_burner_accounts,
+        ACCOUNTS_POOL_SHARDS,
+    ));
+    let sponsors = if num_sponsors > 0 {
+        Some(
+            create_sponsors(
+                num_sponsors,
+                source_accounts,
+                txn_executor,
+                init_txn_factory,
+                &mut rng,
+            )
+            .await,
+        )
+    } else {
+        None
+    };
+    let signers = create_signers(
+        signer_key_type,
+        source_accounts,
+        txn_executor,
+        init_txn_factory,
+        &mut rng,
+    )
+    .await;
 
     let mut txn_generator_creator_mix_per_phase: Vec<
         Vec<(Box<dyn TransactionGeneratorCreator>, usize)>,
     > = Vec::new();
 
+    // Gas price is set before changing the signer and sponsoring, as all re-sign the
+    // transaction, and the new signatures cover the gas price. Transactions of signers
+    // other than ed25519 ones are not sponsored.
+    fn wrap_signing(
+        inner: Box<dyn TransactionGeneratorCreator>,
+        gas_price: Option<GasPriceDistribution>,
+        sponsors: &Option<Arc<Vec<LocalAccount>>>,
+        signers: &Option<Arc<Vec<Mutex<MultiEd25519Account>>>>,
+        rng: &mut StdRng,
+    ) -> Box<dyn TransactionGeneratorCreator> {
+        let inner: Box<dyn TransactionGeneratorCreator> = match gas_price {
+            Some(gas_price) => {
+                Box::new(GasPriceWrapperCreator::new(inner, gas_price, new_rng(rng)))
+            },
+            None => inner,
+        };
+        let inner: Box<dyn TransactionGeneratorCreator> = match signers {
+            Some(signers) => Box::new(SignerKeyWrapperCreator::new(inner, signers.clone())),
+            None => inner,
+        };
+        match sponsors {
+            Some(sponsors) => Box::new(FeePayerWrapperCreator::new(
+                inner,
+                sponsors.clone(),
+                new_rng(rng),
+            )),
+            None => inner,
+        }
+    }
+
+    // Transactions are sponsored before being handed out to the pool wrapper, which
+    // calls into the inner generator with the account from the pool.
     fn wrap_accounts_pool(
         inner: Box<dyn TransactionGeneratorCreator>,
         use_account_pool: bool,
-        accounts_pool: Arc<RwLock<Vec<LocalAccount>>>,
+        accounts_pool: Arc<dyn AccountsPool>,
+        gas_price: Option<GasPriceDistribution>,
+        sponsors: &Option<Arc<Vec<LocalAccount>>>,
+        signers: &Option<Arc<Vec<Mutex<MultiEd25519Account>>>>,
+        txn_factory: &TransactionFactory,
+        refill: AccountPoolRefill,
+        rng: &mut StdRng,
     ) -> Box<dyn TransactionGeneratorCreator> {
+        let inner = wrap_signing(inner, gas_price, sponsors, signers, rng);
         if use_account_pool {
-            Box::new(AccountsPoolWrapperCreator::new(inner, accounts_pool))
+            Box::new(AccountsPoolWrapperCreator::new(
+                txn_factory.clone(),
+                inner,
+                accounts_pool,
+                refill,
+                new_rng(rng),
+            ))
         } else {
             inner
         }
@@ -213,6 +610,27 @@ pub async fn create_txn_generator_creator(
         let mut txn_generator_creator_mix: Vec<(Box<dyn TransactionGeneratorCreator>, usize)> =
             Vec::new();
         for (transaction_type, weight) in transaction_mix {
+            let workload_name = transaction_type.workload_name();
+            let verifier = transaction_type.verifier();
+            // workloads with a custom gas price are created as their inner workload, and wrapped
+            let (transaction_type, gas_price) = match transaction_type {
+                TransactionType::CustomGasPrice {
+                    transaction_type,
+                    gas_price,
+                } => (transaction_type.as_ref(), Some(*gas_price)),
+                _ => (transaction_type, None),
+            };
+            // and the ones sending to a recipient list with it instead of the addresses pool
+            let (transaction_type, destinations) = match transaction_type {
+                TransactionType::ToRecipients {
+                    transaction_type,
+                    recipients,
+                } => (
+                    transaction_type.as_ref(),
+                    Arc::new(RwLock::new(recipients.addresses().to_vec())),
+                ),
+                _ => (transaction_type, addresses_pool.clone()),
+            };
             let txn_generator_creator: Box<dyn TransactionGeneratorCreator> = match transaction_type
             {
                 TransactionType::NonConflictingCoinTransfer {
@@ -225,9 +643,16 @@ pub async fn create_txn_generator_creator(
                         addresses_pool.clone(),
                         *invalid_transaction_ratio,
                         SamplingMode::BurnAndRecycle(addresses_pool.read().len() / 2),
+                        new_rng(&mut rng),
                     )),
                     *sender_use_account_pool,
                     accounts_pool.clone(),
+                    gas_price,
+                    &sponsors,
+                    &signers,
+                    txn_factory,
+                    account_pool_refill,
+                    &mut rng,
                 ),
                 TransactionType::CoinTransfer {
                     invalid_transaction_ratio,
@@ -239,26 +664,79 @@ pub async fn create_txn_generator_creator(
                         addresses_pool.clone(),
                         *invalid_transaction_ratio,
                         SamplingMode::Basic,
+                        new_rng(&mut rng),
                     )),
                     *sender_use_account_pool,
                     accounts_pool.clone(),
+                    gas_price,
+                    &sponsors,
+                    &signers,
+                    txn_factory,
+                    account_pool_refill,
+                    &mut rng,
                 ),
                 TransactionType::AccountGeneration {
                     add_created_accounts_to_pool,
                     max_account_working_set,
                     creation_balance,
-                } => Box::new(AccountGeneratorCreator::new(
-                    txn_factory.clone(),
-                    addresses_pool.clone(),
-                    accounts_pool.clone(),
-                    *add_created_accounts_to_pool,
-                    *max_account_working_set,
-                    *creation_balance,
-                )),
+                } => wrap_signing(
+                    Box::new(AccountGeneratorCreator::new(
+                        txn_factory.clone(),
+                        addresses_pool.clone(),
+                        accounts_pool.clone(),
+                        *add_created_accounts_to_pool,
+                        *max_account_working_set,
+                        *creation_balance,
+                        new_rng(&mut rng),
+                    )),
+                    gas_price,
+                    &sponsors,
+                    &signers,
+                    &mut rng,
+                ),
+                TransactionType::AccountLifecycle => wrap_signing(
+                    Box::new(AccountLifecycleGeneratorCreator::new(
+                        txn_factory.clone(),
+                        new_rng(&mut rng),
+                    )),
+                    gas_price,
+                    &sponsors,
+                    &signers,
+                    &mut rng,
+                ),
                 TransactionType::PublishPackage { use_account_pool } => wrap_accounts_pool(
-                    Box::new(PublishPackageCreator::new(txn_factory.clone())),
+                    Box::new(PublishPackageCreator::new(
+                        txn_factory.clone(),
+                        new_rng(&mut rng),
+                    )),
+                    *use_account_pool,
+                    accounts_pool.clone(),
+                    gas_price,
+                    &sponsors,
+                    &signers,
+                    txn_factory,
+                    account_pool_refill,
+                    &mut rng,
+                ),
+                TransactionType::PublishAndUpgradePackage {
+                    package_size,
+                    upgrade_percentage,
+                    use_account_pool,
+                } => wrap_accounts_pool(
+                    Box::new(PublishUpgradeCreator::new(
+                        txn_factory.clone(),
+                        *package_size,
+                        *upgrade_percentage,
+                        new_rng(&mut rng),
+                    )),
                     *use_account_pool,
                     accounts_pool.clone(),
+                    gas_price,
+                    &sponsors,
+                    &signers,
+                    txn_factory,
+                    account_pool_refill,
+                    &mut rng,
                 ),
                 TransactionType::CallCustomModules {
                     entry_point,
@@ -276,21 +754,294 @@ pub async fn create_txn_generator_creator(
                             &mut EntryPointTransactionGenerator {
                                 entry_point: *entry_point,
                             },
+                            new_rng(&mut rng),
                         )
                         .await,
                     ),
                     *use_account_pool,
                     accounts_pool.clone(),
+                    gas_price,
+                    &sponsors,
+                    &signers,
+                    txn_factory,
+                    account_pool_refill,
+                    &mut rng,
                 ),
-                TransactionType::BatchTransfer { batch_size } => {
+                TransactionType::BatchTransfer { batch_size } => wrap_signing(
                     Box::new(BatchTransferTransactionGeneratorCreator::new(
                         txn_factory.clone(),
                         SEND_AMOUNT,
-                        addresses_pool.clone(),
+                        destinations,
                         *batch_size,
-                    ))
+                        new_rng(&mut rng),
+                    )),
+                    gas_price,
+                    &sponsors,
+                    &signers,
+                    &mut rng,
+                ),
+                TransactionType::CallEntryFunction {
+                    workload,
+                    use_account_pool,
+                } => wrap_accounts_pool(
+                    Box::new(
+                        EntryFunctionTransactionGeneratorCreator::new(
+                            txn_factory.clone(),
+                            init_txn_factory.clone(),
+                            source_accounts,
+                            txn_executor,
+                            workload.clone(),
+                            new_rng(&mut rng),
+                        )
+                        .await,
+                    ),
+                    *use_account_pool,
+                    accounts_pool.clone(),
+                    gas_price,
+                    &sponsors,
+                    &signers,
+                    txn_factory,
+                    account_pool_refill,
+                    &mut rng,
+                ),
+                TransactionType::TokenV2MintAndTransfer {
+                    collection_size,
+                    soulbound_percentage,
+                } => wrap_signing(
+                    Box::new(TokenV2TransactionGeneratorCreator::new(
+                        txn_factory.clone(),
+                        destinations,
+                        *collection_size,
+                        *soulbound_percentage,
+                        new_rng(&mut rng),
+                    )),
+                    gas_price,
+                    &sponsors,
+                    &signers,
+                    &mut rng,
+                ),
+                TransactionType::TokenV2MintAndBurn {
+                    live_tokens_per_account,
+                } => wrap_signing(
+                    Box::new(TokenV2MintAndBurnGeneratorCreator::new(
+                        txn_factory.clone(),
+                        *live_tokens_per_account,
+                    )),
+                    gas_price,
+                    &sponsors,
+                    &signers,
+                    &mut rng,
+                ),
+                TransactionType::FungibleAssetTransfer {
+                    num_modules,
+                    conflict_percentage,
+                    use_account_pool,
+                } => wrap_accounts_pool(
+                    Box::new(
+                        CustomModulesDelegationGeneratorCreator::new(
+                            txn_factory.clone(),
+                            init_txn_factory.clone(),
+                            source_accounts,
+                            txn_executor,
+                            *num_modules,
+                            "framework_usecases",
+                            &mut FungibleAssetTransferGenerator {
+                                conflict_percentage: *conflict_percentage,
+                            },
+                            new_rng(&mut rng),
+                        )
+                        .await,
+                    ),
+                    *use_account_pool,
+                    accounts_pool.clone(),
+                    gas_price,
+                    &sponsors,
+                    &signers,
+                    txn_factory,
+                    account_pool_refill,
+                    &mut rng,
+                ),
+                TransactionType::Replay {
+                    workload,
+                    num_accounts,
+                } => Box::new(
+                    ReplayTransactionGeneratorCreator::new(
+                        txn_factory.clone(),
+                        init_txn_factory.clone(),
+                        source_accounts,
+                        txn_executor,
+                        workload.clone(),
+                        *num_accounts,
+                        &mut rng,
+                    )
+                    .await,
+                ),
+                TransactionType::DelegationPool {
+                    num_pools,
+                    num_delegators,
+                    num_stake_owners,
+                } => Box::new(
+                    DelegationPoolGeneratorCreator::new(
+                        txn_factory.clone(),
+                        init_txn_factory.clone(),
+                        source_accounts,
+                        txn_executor,
+                        *num_pools,
+                        *num_delegators,
+                        *num_stake_owners,
+                        &mut rng,
+                    )
+                    .await,
+                ),
+                TransactionType::ScriptPayload {
+                    max_num_args,
+                    max_arg_size,
+                } => wrap_signing(
+                    Box::new(ScriptPayloadGeneratorCreator::new(
+                        txn_factory.clone(),
+                        *max_num_args,
+                        *max_arg_size,
+                        new_rng(&mut rng),
+                    )),
+                    gas_price,
+                    &sponsors,
+                    &signers,
+                    &mut rng,
+                ),
+                TransactionType::ObjectTransfer {
+                    num_modules,
+                    payload_size,
+                    use_account_pool,
+                } => wrap_accounts_pool(
+                    Box::new(
+                        CustomModulesDelegationGeneratorCreator::new(
+                            txn_factory.clone(),
+                            init_txn_factory.clone(),
+                            source_accounts,
+                            txn_executor,
+                            *num_modules,
+                            "framework_usecases",
+                            &mut ObjectTransferGenerator {
+                                payload_size: *payload_size,
+                            },
+                            new_rng(&mut rng),
+                        )
+                        .await,
+                    ),
+                    *use_account_pool,
+                    accounts_pool.clone(),
+                    gas_price,
+                    &sponsors,
+                    &signers,
+                    txn_factory,
+                    account_pool_refill,
+                    &mut rng,
+                ),
+                TransactionType::CustomGasPrice { .. } => {
+                    panic!("Custom gas price cannot be nested")
                 },
+                TransactionType::ToRecipients { .. } => {
+                    panic!("Recipient lists cannot be nested")
+                },
+                TransactionType::Multisig {
+                    num_owners,
+                    num_signatures_required,
+                } => Box::new(MultisigTransactionGeneratorCreator::new(
+                    txn_factory.clone(),
+                    accounts_pool.clone(),
+                    *num_owners,
+                    *num_signatures_required,
+                )),
+                TransactionType::MultiAgent {
+                    num_secondary_signers,
+                } => wrap_signing(
+                    Box::new(
+                        MultiAgentTransactionGeneratorCreator::new(
+                            txn_factory.clone(),
+                            init_txn_factory.clone(),
+                            source_accounts,
+                            txn_executor,
+                            *num_secondary_signers,
+                            new_rng(&mut rng),
+                        )
+                        .await,
+                    ),
+                    gas_price,
+                    &sponsors,
+                    &signers,
+                    &mut rng,
+                ),
+                TransactionType::ConflictRate {
+                    num_modules,
+                    conflict_percentage,
+                    use_account_pool,
+                } => wrap_accounts_pool(
+                    Box::new(
+                        CustomModulesDelegationGeneratorCreator::new(
+                            txn_factory.clone(),
+                            init_txn_factory.clone(),
+                            source_accounts,
+                            txn_executor,
+                            *num_modules,
+                            "simple",
+                            &mut ConflictRateTransactionGenerator {
+                                conflict_percentage: *conflict_percentage,
+                            },
+                            new_rng(&mut rng),
+                        )
+                        .await,
+                    ),
+                    *use_account_pool,
+                    accounts_pool.clone(),
+                    gas_price,
+                    &sponsors,
+                    &signers,
+                    txn_factory,
+                    account_pool_refill,
+                    &mut rng,
+                ),
+                TransactionType::ArgumentFuzzing {
+                    package_name,
+                    num_modules,
+                    use_account_pool,
+                } => wrap_accounts_pool(
+                    Box::new(
+                        CustomModulesDelegationGeneratorCreator::new(
+                            txn_factory.clone(),
+                            init_txn_factory.clone(),
+                            source_accounts,
+                            txn_executor,
+                            *num_modules,
+                            package_name,
+                            &mut ArgumentFuzzingGenerator::default(),
+                            new_rng(&mut rng),
+                        )
+                        .await,
+                    ),
+                    *use_account_pool,
+                    accounts_pool.clone(),
+                    gas_price,
+                    &sponsors,
+                    &signers,
+                    txn_factory,
+                    account_pool_refill,
+                    &mut rng,
+                ),
             };
+            // around the wrappers of the workload, so the queued transactions are the signed
+            // ones. Those the emitter replaces with invalid transactions count as not committed.
+            let txn_generator_creator: Box<dyn TransactionGeneratorCreator> =
+                match &verification_queue {
+                    Some(queue) => Box::new(VerificationWrapperCreator::new(
+                        txn_generator_creator,
+                        cur_phase.clone(),
+                        workload_name,
+                        verifier,
+                        queue.clone(),
+                        new_rng(&mut rng),
+                    )),
+                    None => txn_generator_creator,
+                };
             txn_generator_creator_mix.push((txn_generator_creator, *weight));
         }
         txn_generator_creator_mix_per_phase.push(txn_generator_creator_mix)
@@ -300,28 +1051,71 @@ pub async fn create_txn_generator_creator(
         Box::new(PhasedTxnMixGeneratorCreator::new(
             txn_generator_creator_mix_per_phase,
             cur_phase,
+            new_rng(&mut rng),
         )),
         addresses_pool,
         accounts_pool,
     )
 }
 
-fn get_account_to_burn_from_pool(
-    accounts_pool: &Arc<RwLock<Vec<LocalAccount>>>,
-    needed: usize,
+/// Create `num_accounts` new accounts, sharing half of the first source account's balance,
+/// for workloads that sign with accounts of their own.
+async fn create_funded_accounts(
+    description: &str,
+    num_accounts: usize,
+    source_accounts: &mut [LocalAccount],
+    txn_executor: &dyn ReliableTransactionSubmitter,
+    init_txn_factory: &TransactionFactory,
+    rng: &mut StdRng,
 ) -> Vec<LocalAccount> {
-    let mut accounts_pool = accounts_pool.write();
-    let num_in_pool = accounts_pool.len();
-    if num_in_pool < needed {
-        sample!(
-            SampleRate::Duration(Duration::from_secs(10)),
-            warn!("Cannot fetch enough accounts from pool, left in pool {}, needed {}", num_in_pool, needed);
-        );
-        return Vec::new();
-    }
-    accounts_pool
-        .drain((num_in_pool - needed)..)
-        .collect::<Vec<_>>()
+    let accounts: Vec<_> = (0..num_accounts)
+        .map(|_| LocalAccount::generate(rng))
+        .collect();
+    fund_new_accounts(
+        description,
+        &accounts
+            .iter()
+            .map(|account| account.address())
+            .collect::<Vec<_>>(),
+        source_accounts,
+        txn_executor,
+        init_txn_factory,
+    )
+    .await;
+    accounts
+}
+
+/// Same as `create_funded_accounts`, for addresses of keys other than a single ed25519 one.
+async fn fund_new_accounts(
+    description: &str,
+    addresses: &[AccountAddress],
+    source_accounts: &mut [LocalAccount],
+    txn_executor: &dyn ReliableTransactionSubmitter,
+    init_txn_factory: &TransactionFactory,
+) {
+    let source = source_accounts.get_mut(0).unwrap();
+    let balance = txn_executor
+        .get_account_balance(source.address())
+        .await
+        .unwrap();
+    let creation_balance = balance / 2 / addresses.len() as u64;
+    info!(
+        "Creating {} {} accounts with {} balance each",
+        addresses.len(),
+        description,
+        creation_balance
+    );
+    txn_executor
+        .execute_transactions(
+            &addresses
+                .iter()
+                .map(|address| {
+                    create_account_transaction(source, *address, init_txn_factory, creation_balance)
+                })
+                .collect::<Vec<_>>(),
+        )
+        .await
+        .unwrap();
 }
 
 pub fn create_account_transaction(
@@ -338,3 +1132,78 @@ pub fn create_account_transaction(
         },
     ))
 }
+
+#[cfg(test)]
+mod test {
+    use super::{resync_accounts, GasPriceDistribution, RecipientList, TransactionType};
+    use aptos_sdk::types::LocalAccount;
+    use rand::{rngs::StdRng, SeedableRng};
+    use std::{collections::HashMap, sync::Arc};
+
+    #[test]
+    fn test_resync_accounts() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut accounts: Vec<_> = (0..3).map(|_| LocalAccount::generate(&mut rng)).collect();
+        *accounts[0].sequence_number_mut() = 5;
+        *accounts[1].sequence_number_mut() = 7;
+        *accounts[2].sequence_number_mut() = 5;
+
+        // nothing to resync
+        resync_accounts(accounts.iter_mut(), &HashMap::new());
+        assert_eq!(accounts[0].sequence_number(), 5);
+
+        let stale = HashMap::from([
+            (accounts[0].address(), (5, 3)),
+            // signed more transactions since, left as is
+            (accounts[1].address(), (5, 3)),
+        ]);
+        resync_accounts(accounts.iter_mut(), &stale);
+        assert_eq!(accounts[0].sequence_number(), 3);
+        assert_eq!(accounts[1].sequence_number(), 7);
+        assert_eq!(accounts[2].sequence_number(), 5);
+    }
+
+    #[test]
+    fn test_with_recipients() {
+        let recipients = Arc::new(RecipientList::from_str_lines("0x1\n0x2").unwrap());
+
+        let batch_transfer =
+            TransactionType::BatchTransfer { batch_size: 10 }.with_recipients(recipients.clone());
+        assert_eq!(
+            batch_transfer.workload_name(),
+            "ToRecipients(BatchTransfer(10))"
+        );
+        assert!(batch_transfer.validate().is_ok());
+        assert!(batch_transfer.verifier().is_some());
+
+        // the gas price stays the outermost, as the workloads are created
+        let token_transfer = TransactionType::CustomGasPrice {
+            transaction_type: Box::new(TransactionType::TokenV2MintAndTransfer {
+                collection_size: 10,
+                soulbound_percentage: 0,
+            }),
+            gas_price: GasPriceDistribution::Fixed(100),
+        }
+        .with_recipients(recipients.clone());
+        match &token_transfer {
+            TransactionType::CustomGasPrice {
+                transaction_type, ..
+            } => assert!(matches!(
+                transaction_type.as_ref(),
+                TransactionType::ToRecipients { .. }
+            )),
+            _ => panic!("unexpected workload {}", token_transfer.workload_name()),
+        }
+        assert!(token_transfer.validate().is_ok());
+
+        // coin transfers fail for recipients without an account
+        assert!(TransactionType::default()
+            .with_recipients(recipients.clone())
+            .validate()
+            .is_err());
+        assert!(batch_transfer
+            .with_recipients(recipients)
+            .validate()
+            .is_err());
+    }
+}
@@ -0,0 +1,173 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    create_funded_accounts, new_rng, ReliableTransactionSubmitter, TransactionGenerator,
+    TransactionGeneratorCreator,
+};
+use aptos_sdk::{
+    transaction_builder::TransactionFactory,
+    types::{
+        transaction::{Script, SignedTransaction},
+        LocalAccount,
+    },
+};
+use move_binary_format::file_format::{empty_script, Signature, SignatureIndex, SignatureToken};
+use rand::{rngs::StdRng, seq::SliceRandom};
+use std::sync::Arc;
+
+/// Script taking `num_signers` `&signer` arguments and doing nothing with them, so any number
+/// of signers can be passed without an entry function of that arity
+fn script_code(num_signers: usize) -> Vec<u8> {
+    let mut script = empty_script();
+    let signer = SignatureToken::Reference(Box::new(SignatureToken::Signer));
+    script.signatures.push(Signature(vec![signer; num_signers]));
+    script.parameters = SignatureIndex(1);
+    let mut code = vec![];
+    script
+        .serialize(&mut code)
+        .expect("Serializing script failed");
+    code
+}
+
+/// Sets of secondary signers created per transaction signer, transactions take a random one
+/// of them
+const NUM_SIGNER_SETS: usize = 10;
+
+/// Multi agent transactions of the sender and `num_secondary_signers` accounts, to measure
+/// the cost of verifying the signatures and running the prologue of multi agent transactions
+/// as the number of signers grows. Secondary signers don't use their sequence number, so
+/// they are shared by all transactions, and picked at random from the ones created on setup.
+pub struct MultiAgentTransactionGenerator {
+    rng: StdRng,
+    txn_factory: TransactionFactory,
+    secondary_signers: Arc<Vec<LocalAccount>>,
+    num_secondary_signers: usize,
+    script: Arc<Vec<u8>>,
+}
+
+impl TransactionGenerator for MultiAgentTransactionGenerator {
+    fn generate_transactions(
+        &mut self,
+        account: &mut LocalAccount,
+        num_to_create: usize,
+    ) -> Vec<SignedTransaction> {
+        (0..num_to_create)
+            .map(|_| {
+                let secondary_signers = self
+                    .secondary_signers
+                    .choose_multiple(&mut self.rng, self.num_secondary_signers)
+                    .collect();
+                let script = Script::new(self.script.to_vec(), vec![], vec![]);
+                account.sign_multi_agent_with_transaction_builder(
+                    secondary_signers,
+                    self.txn_factory.script(script),
+                )
+            })
+            .collect()
+    }
+}
+
+pub struct MultiAgentTransactionGeneratorCreator {
+    txn_factory: TransactionFactory,
+    secondary_signers: Arc<Vec<LocalAccount>>,
+    num_secondary_signers: usize,
+    script: Arc<Vec<u8>>,
+    rng: StdRng,
+}
+
+impl MultiAgentTransactionGeneratorCreator {
+    /// Creates the secondary signers on chain, before the workload starts
+    pub async fn new(
+        txn_factory: TransactionFactory,
+        init_txn_factory: TransactionFactory,
+        source_accounts: &mut [LocalAccount],
+        txn_executor: &dyn ReliableTransactionSubmitter,
+        num_secondary_signers: usize,
+        mut rng: StdRng,
+    ) -> Self {
+        let secondary_signers = create_funded_accounts(
+            "multi agent secondary signer",
+            num_secondary_signers * NUM_SIGNER_SETS,
+            source_accounts,
+            txn_executor,
+            &init_txn_factory,
+            &mut rng,
+        )
+        .await;
+        Self::with_secondary_signers(txn_factory, secondary_signers, num_secondary_signers, rng)
+    }
+
+    fn with_secondary_signers(
+        txn_factory: TransactionFactory,
+        secondary_signers: Vec<LocalAccount>,
+        num_secondary_signers: usize,
+        rng: StdRng,
+    ) -> Self {
+        assert!(num_secondary_signers > 0);
+        assert!(secondary_signers.len() >= num_secondary_signers);
+        Self {
+            txn_factory,
+            secondary_signers: Arc::new(secondary_signers),
+            num_secondary_signers,
+            script: Arc::new(script_code(num_secondary_signers + 1)),
+            rng,
+        }
+    }
+}
+
+impl TransactionGeneratorCreator for MultiAgentTransactionGeneratorCreator {
+    fn create_transaction_generator(&mut self) -> Box<dyn TransactionGenerator> {
+        Box::new(MultiAgentTransactionGenerator {
+            rng: new_rng(&mut self.rng),
+            txn_factory: self.txn_factory.clone(),
+            secondary_signers: self.secondary_signers.clone(),
+            num_secondary_signers: self.num_secondary_signers,
+            script: self.script.clone(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use aptos_sdk::types::{chain_id::ChainId, transaction::TransactionPayload};
+    use rand::SeedableRng;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_secondary_signers() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let secondary_signers: Vec<_> = (0..6).map(|_| LocalAccount::generate(&mut rng)).collect();
+        let addresses: HashSet<_> = secondary_signers
+            .iter()
+            .map(|signer| signer.address())
+            .collect();
+        let mut creator = MultiAgentTransactionGeneratorCreator::with_secondary_signers(
+            TransactionFactory::new(ChainId::test()),
+            secondary_signers,
+            3,
+            rng.clone(),
+        );
+        let mut account = LocalAccount::generate(&mut rng);
+
+        let txns = creator
+            .create_transaction_generator()
+            .generate_transactions(&mut account, 10);
+        assert_eq!(txns.len(), 10);
+        assert_eq!(account.sequence_number(), 10);
+        for txn in txns {
+            let signers = txn.authenticator().secondary_signer_addreses();
+            assert_eq!(signers.len(), 3);
+            assert_eq!(signers.iter().collect::<HashSet<_>>().len(), 3);
+            assert!(signers.iter().all(|signer| addresses.contains(signer)));
+            assert!(txn.signature_is_valid());
+            match txn.payload() {
+                TransactionPayload::Script(script) => {
+                    assert_eq!(script.code(), script_code(4).as_slice())
+                },
+                _ => panic!("unexpected payload"),
+            }
+        }
+    }
+}
@@ -0,0 +1,239 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{resync_accounts, AccountsPool, TransactionGenerator, TransactionGeneratorCreator};
+use aptos_logger::{sample, sample::SampleRate, warn};
+use aptos_sdk::{
+    bcs,
+    move_types::account_address::AccountAddress,
+    transaction_builder::{aptos_stdlib, TransactionFactory},
+    types::{
+        account_address::create_multisig_account_address,
+        transaction::{
+            EntryFunction, Multisig, MultisigTransactionPayload, SignedTransaction,
+            TransactionPayload,
+        },
+        LocalAccount,
+    },
+};
+use std::{collections::HashMap, sync::Arc, time::Duration};
+
+/// Octas the multisig account is funded with, each executed transaction spends one
+const MULTISIG_FUNDING: u64 = 1_000_000;
+
+enum Stage {
+    /// The account is set up, the next transaction can be proposed
+    Ready,
+    /// The transaction is proposed (and approved) by the first owner
+    Proposed,
+    /// Enough owners approved the transaction, it can be executed
+    Approved,
+}
+
+/// A k-of-n multisig account and its owners
+struct MultisigGroup {
+    owners: Vec<LocalAccount>,
+    multisig_address: AccountAddress,
+    /// Sequence number of the multisig transaction in flight
+    sequence_number: u64,
+    stage: Stage,
+}
+
+impl MultisigGroup {
+    fn executed_payload(&self) -> EntryFunction {
+        match aptos_stdlib::aptos_account_transfer(self.owners[0].address(), 1) {
+            TransactionPayload::EntryFunction(entry_function) => entry_function,
+            _ => unreachable!("aptos_account_transfer is an entry function"),
+        }
+    }
+}
+
+/// Sets up k-of-n multisig accounts with owners taken from the account pool (which needs
+/// to hold funded accounts), then cycles each of them through proposing, approving and
+/// executing a multisig transaction. Every call moves each account one stage forward, as
+/// the stages have to be committed in order.
+pub struct MultisigTransactionGenerator {
+    txn_factory: TransactionFactory,
+    accounts_pool: Arc<dyn AccountsPool>,
+    num_owners: usize,
+    num_signatures_required: usize,
+    groups: Vec<MultisigGroup>,
+}
+
+impl MultisigTransactionGenerator {
+    pub fn new(
+        txn_factory: TransactionFactory,
+        accounts_pool: Arc<dyn AccountsPool>,
+        num_owners: usize,
+        num_signatures_required: usize,
+    ) -> Self {
+        Self {
+            txn_factory,
+            accounts_pool,
+            num_owners,
+            num_signatures_required,
+            groups: Vec::new(),
+        }
+    }
+
+    fn set_up_group(&mut self) -> Option<(MultisigGroup, Vec<SignedTransaction>)> {
+        let mut owners = self.accounts_pool.take_n(self.num_owners);
+        if owners.is_empty() {
+            sample!(
+                SampleRate::Duration(Duration::from_secs(10)),
+                warn!(
+                    "Cannot fetch enough accounts from pool, left in pool {}, needed {}",
+                    self.accounts_pool.len(),
+                    self.num_owners
+                );
+            );
+            return None;
+        }
+        let additional_owners = owners[1..].iter().map(|owner| owner.address()).collect();
+        let creator = &mut owners[0];
+        let multisig_address =
+            create_multisig_account_address(creator.address(), creator.sequence_number());
+        let txns = vec![
+            creator.sign_with_transaction_builder(self.txn_factory.payload(
+                aptos_stdlib::multisig_account_create_with_owners(
+                    additional_owners,
+                    self.num_signatures_required as u64,
+                    vec![],
+                    vec![],
+                ),
+            )),
+            creator.sign_with_transaction_builder(self.txn_factory.payload(
+                aptos_stdlib::aptos_account_transfer(multisig_address, MULTISIG_FUNDING),
+            )),
+        ];
+        Some((
+            MultisigGroup {
+                owners,
+                multisig_address,
+                sequence_number: 1,
+                stage: Stage::Ready,
+            },
+            txns,
+        ))
+    }
+
+    fn advance(&self, group: &mut MultisigGroup) -> Vec<SignedTransaction> {
+        match group.stage {
+            Stage::Ready => {
+                let payload = bcs::to_bytes(&MultisigTransactionPayload::EntryFunction(
+                    group.executed_payload(),
+                ))
+                .expect("Multisig payload must serialize");
+                group.stage = Stage::Proposed;
+                vec![
+                    group.owners[0].sign_with_transaction_builder(self.txn_factory.payload(
+                        aptos_stdlib::multisig_account_create_transaction(
+                            group.multisig_address,
+                            payload,
+                        ),
+                    )),
+                ]
+            },
+            Stage::Proposed => {
+                let (multisig_address, sequence_number) =
+                    (group.multisig_address, group.sequence_number);
+                group.stage = Stage::Approved;
+                // the proposer approves implicitly
+                group.owners[1..self.num_signatures_required]
+                    .iter_mut()
+                    .map(|owner| {
+                        owner.sign_with_transaction_builder(self.txn_factory.payload(
+                            aptos_stdlib::multisig_account_approve_transaction(
+                                multisig_address,
+                                sequence_number,
+                            ),
+                        ))
+                    })
+                    .collect()
+            },
+            Stage::Approved => {
+                let multisig_address = group.multisig_address;
+                group.sequence_number += 1;
+                group.stage = Stage::Ready;
+                vec![
+                    group.owners[0].sign_with_transaction_builder(self.txn_factory.payload(
+                        TransactionPayload::Multisig(Multisig {
+                            multisig_address,
+                            transaction_payload: None,
+                        }),
+                    )),
+                ]
+            },
+        }
+    }
+}
+
+impl TransactionGenerator for MultisigTransactionGenerator {
+    fn generate_transactions(
+        &mut self,
+        _account: &mut LocalAccount,
+        num_to_create: usize,
+    ) -> Vec<SignedTransaction> {
+        let mut groups = std::mem::take(&mut self.groups);
+        let mut requests: Vec<_> = groups
+            .iter_mut()
+            .flat_map(|group| self.advance(group))
+            .collect();
+        while groups.len() < num_to_create {
+            match self.set_up_group() {
+                Some((group, mut txns)) => {
+                    groups.push(group);
+                    requests.append(&mut txns);
+                },
+                None => break,
+            }
+        }
+        self.groups = groups;
+        requests
+    }
+
+    fn resync_sequence_numbers(&mut self, stale: &HashMap<AccountAddress, (u64, u64)>) {
+        resync_accounts(
+            self.groups
+                .iter_mut()
+                .flat_map(|group| group.owners.iter_mut()),
+            stale,
+        );
+    }
+}
+
+pub struct MultisigTransactionGeneratorCreator {
+    txn_factory: TransactionFactory,
+    accounts_pool: Arc<dyn AccountsPool>,
+    num_owners: usize,
+    num_signatures_required: usize,
+}
+
+impl MultisigTransactionGeneratorCreator {
+    pub fn new(
+        txn_factory: TransactionFactory,
+        accounts_pool: Arc<dyn AccountsPool>,
+        num_owners: usize,
+        num_signatures_required: usize,
+    ) -> Self {
+        // checked by `TransactionType::validate`
+        assert!((1..=num_owners).contains(&num_signatures_required));
+        Self {
+            txn_factory,
+            accounts_pool,
+            num_owners,
+            num_signatures_required,
+        }
+    }
+}
+
+impl TransactionGeneratorCreator for MultisigTransactionGeneratorCreator {
+    fn create_transaction_generator(&mut self) -> Box<dyn TransactionGenerator> {
+        Box::new(MultisigTransactionGenerator::new(
+            self.txn_factory.clone(),
+            self.accounts_pool.clone(),
+            self.num_owners,
+            self.num_signatures_required,
+        ))
+    }
+}
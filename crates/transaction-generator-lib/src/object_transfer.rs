@@ -0,0 +1,187 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use super::{publishing::publish_util::Package, ReliableTransactionSubmitter};
+use crate::call_custom_modules::{
+    account_state, TransactionGeneratorWorker, UserModuleTransactionGenerator,
+};
+use aptos_infallible::RwLock;
+use aptos_sdk::{
+    bcs,
+    move_types::{account_address::AccountAddress, ident_str, language_storage::ModuleId},
+    transaction_builder::TransactionFactory,
+    types::{
+        transaction::{EntryFunction, SignedTransaction, TransactionPayload},
+        LocalAccount,
+    },
+};
+use async_trait::async_trait;
+use rand::{rngs::StdRng, seq::SliceRandom};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
+
+const MODULE_NAME: &str = "objects";
+
+/// Accounts holding objects of one publisher's module
+#[derive(Default)]
+struct Holders {
+    created: HashSet<AccountAddress>,
+    addresses: Vec<AccountAddress>,
+}
+
+/// Objects created by accounts and passed around between them. The first transaction of
+/// a sender with a package creates an object, the following ones transfer its last received
+/// object to another holder, adding a resource of `payload_size` bytes to the object on its
+/// first transfer. Exercises objects and their resource group, stored at the object address.
+pub struct ObjectTransferGenerator {
+    pub payload_size: u64,
+}
+
+#[async_trait]
+impl UserModuleTransactionGenerator for ObjectTransferGenerator {
+    fn initialize_package(
+        &mut self,
+        _package: &Package,
+        _publisher: &mut LocalAccount,
+        _txn_factory: &TransactionFactory,
+        _rng: &mut StdRng,
+    ) -> Vec<SignedTransaction> {
+        vec![]
+    }
+
+    async fn create_generator_fn(
+        &self,
+        _init_accounts: &mut [LocalAccount],
+        _txn_factory: &TransactionFactory,
+        _txn_executor: &dyn ReliableTransactionSubmitter,
+        _rng: &mut StdRng,
+    ) -> Arc<TransactionGeneratorWorker> {
+        let payload_size = self.payload_size;
+        // holders of the objects of each publisher
+        let holders = Arc::new(RwLock::new(HashMap::<AccountAddress, Holders>::new()));
+
+        Arc::new(
+            move |account, state, package, publisher, txn_factory, rng| {
+                let payload = next_payload(
+                    account_state::<bool>(state),
+                    &holders,
+                    package.get_module_id(MODULE_NAME),
+                    account.address(),
+                    publisher.address(),
+                    payload_size,
+                    rng,
+                );
+                account.sign_with_transaction_builder(txn_factory.payload(payload))
+            },
+        )
+    }
+}
+
+/// Creates an object for the sender if it didn't with the package of the publisher yet, or
+/// since it went out of sync with the chain, and transfers one to another holder otherwise
+fn next_payload(
+    created: &mut bool,
+    holders: &RwLock<HashMap<AccountAddress, Holders>>,
+    module_id: ModuleId,
+    sender: AccountAddress,
+    publisher: AccountAddress,
+    payload_size: u64,
+    rng: &mut StdRng,
+) -> TransactionPayload {
+    let maybe_recipient = {
+        let mut holders = holders.write();
+        let holders = holders.entry(publisher).or_default();
+        if holders.created.insert(sender) {
+            holders.addresses.push(sender);
+        }
+        if *created {
+            holders.addresses.choose(rng).copied()
+        } else {
+            None
+        }
+    };
+    *created = true;
+
+    match maybe_recipient {
+        Some(recipient) => transfer(module_id, recipient, payload_size),
+        None => create(module_id),
+    }
+}
+
+fn create(module_id: ModuleId) -> TransactionPayload {
+    TransactionPayload::EntryFunction(EntryFunction::new(
+        module_id,
+        ident_str!("create").to_owned(),
+        vec![],
+        vec![],
+    ))
+}
+
+fn transfer(
+    module_id: ModuleId,
+    recipient: AccountAddress,
+    payload_size: u64,
+) -> TransactionPayload {
+    TransactionPayload::EntryFunction(EntryFunction::new(
+        module_id,
+        ident_str!("transfer").to_owned(),
+        vec![],
+        vec![
+            bcs::to_bytes(&recipient).unwrap(),
+            bcs::to_bytes(&payload_size).unwrap(),
+        ],
+    ))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rand::SeedableRng;
+
+    fn entry_function(payload: &TransactionPayload) -> &EntryFunction {
+        match payload {
+            TransactionPayload::EntryFunction(entry_function) => entry_function,
+            _ => panic!("unexpected payload"),
+        }
+    }
+
+    #[test]
+    fn test_create_then_transfer() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let holders = RwLock::new(HashMap::new());
+        let module_id = ModuleId::new(AccountAddress::ONE, ident_str!("objects").to_owned());
+        let (sender, publisher, other_publisher) = (
+            AccountAddress::ONE,
+            AccountAddress::TWO,
+            AccountAddress::from_hex_literal("0x3").unwrap(),
+        );
+        let mut next = |created: &mut bool, publisher: AccountAddress| {
+            let payload = next_payload(
+                created,
+                &holders,
+                module_id.clone(),
+                sender,
+                publisher,
+                10,
+                &mut rng,
+            );
+            entry_function(&payload).function().to_string()
+        };
+
+        let mut created = false;
+        assert_eq!(next(&mut created, publisher), "create");
+        assert!(created);
+        assert_eq!(next(&mut created, publisher), "transfer");
+        assert_eq!(next(&mut created, publisher), "transfer");
+        // the journey with another package starts with its own state
+        assert_eq!(next(&mut false, other_publisher), "create");
+        // and so does the one of an account out of sync with the chain
+        assert_eq!(next(&mut false, publisher), "create");
+
+        let holders = holders.read();
+        assert_eq!(holders[&publisher].addresses, vec![sender]);
+        assert_eq!(holders[&other_publisher].addresses, vec![sender]);
+    }
+}
@@ -1,17 +1,19 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
-use crate::{TransactionGenerator, TransactionGeneratorCreator};
+use crate::{new_rng, TransactionGenerator, TransactionGeneratorCreator};
 use aptos_infallible::RwLock;
 use aptos_sdk::{
     move_types::account_address::AccountAddress,
     transaction_builder::{aptos_stdlib, TransactionFactory},
     types::{chain_id::ChainId, transaction::SignedTransaction, LocalAccount},
 };
+#[cfg(test)]
+use rand::SeedableRng;
 use rand::{
     distributions::{Distribution, Standard},
     prelude::SliceRandom,
     rngs::StdRng,
-    Rng, RngCore, SeedableRng,
+    Rng, RngCore,
 };
 use std::{
     cmp::{max, min},
@@ -300,6 +302,7 @@ pub struct P2PTransactionGeneratorCreator {
     all_addresses: Arc<RwLock<Vec<AccountAddress>>>,
     invalid_transaction_ratio: usize,
     sampling_mode: SamplingMode,
+    rng: StdRng,
 }
 
 impl P2PTransactionGeneratorCreator {
@@ -309,6 +312,7 @@ impl P2PTransactionGeneratorCreator {
         all_addresses: Arc<RwLock<Vec<AccountAddress>>>,
         invalid_transaction_ratio: usize,
         sampling_mode: SamplingMode,
+        rng: StdRng,
     ) -> Self {
         Self {
             txn_factory,
@@ -316,13 +320,14 @@ impl P2PTransactionGeneratorCreator {
             all_addresses,
             invalid_transaction_ratio,
             sampling_mode,
+            rng,
         }
     }
 }
 
 impl TransactionGeneratorCreator for P2PTransactionGeneratorCreator {
     fn create_transaction_generator(&mut self) -> Box<dyn TransactionGenerator> {
-        let rng = StdRng::from_entropy();
+        let rng = new_rng(&mut self.rng);
         let sampler: Box<dyn Sampler<AccountAddress>> = match self.sampling_mode {
             SamplingMode::Basic => Box::new(BasicSampler::new()),
             SamplingMode::BurnAndRecycle(recycle_batch_size) => {
@@ -1,15 +1,18 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 use crate::{
-    publishing::publish_util::PackageHandler, TransactionGenerator, TransactionGeneratorCreator,
+    new_rng,
+    publishing::publish_util::{Package, PackageHandler},
+    TransactionGenerator, TransactionGeneratorCreator,
 };
 use aptos_infallible::RwLock;
 use aptos_sdk::{
+    move_types::account_address::AccountAddress,
     transaction_builder::TransactionFactory,
     types::{transaction::SignedTransaction, LocalAccount},
 };
-use rand::{rngs::StdRng, SeedableRng};
-use std::sync::Arc;
+use rand::{rngs::StdRng, Rng};
+use std::{collections::HashMap, sync::Arc};
 
 pub struct PublishPackageGenerator {
     rng: StdRng,
@@ -66,13 +69,15 @@ impl TransactionGenerator for PublishPackageGenerator {
 pub struct PublishPackageCreator {
     txn_factory: TransactionFactory,
     package_handler: Arc<RwLock<PackageHandler>>,
+    rng: StdRng,
 }
 
 impl PublishPackageCreator {
-    pub fn new(txn_factory: TransactionFactory) -> Self {
+    pub fn new(txn_factory: TransactionFactory, rng: StdRng) -> Self {
         Self {
             txn_factory,
             package_handler: Arc::new(RwLock::new(PackageHandler::new("simple"))),
+            rng,
         }
     }
 }
@@ -80,9 +85,88 @@ impl PublishPackageCreator {
 impl TransactionGeneratorCreator for PublishPackageCreator {
     fn create_transaction_generator(&mut self) -> Box<dyn TransactionGenerator> {
         Box::new(PublishPackageGenerator::new(
-            StdRng::from_entropy(),
+            new_rng(&mut self.rng),
             self.package_handler.clone(),
             self.txn_factory.clone(),
         ))
     }
 }
+
+/// Publishes a package of about `package_size` bytes per account, then upgrades it
+/// (compatibly) for `upgrade_percentage` of the following transactions, and calls into it
+/// for the others. Measures publishing and loading of modules, with their cache invalidation.
+pub struct PublishUpgradeGenerator {
+    rng: StdRng,
+    package_handler: Arc<RwLock<PackageHandler>>,
+    txn_factory: TransactionFactory,
+    upgrade_percentage: usize,
+    published: HashMap<AccountAddress, Package>,
+}
+
+impl TransactionGenerator for PublishUpgradeGenerator {
+    fn generate_transactions(
+        &mut self,
+        account: &mut LocalAccount,
+        num_to_create: usize,
+    ) -> Vec<SignedTransaction> {
+        let mut requests = Vec::with_capacity(num_to_create);
+        for _ in 0..num_to_create {
+            let publish = !self.published.contains_key(&account.address())
+                || self.rng.gen_range(0, 100) < self.upgrade_percentage;
+            if publish {
+                // an upgrade when the account already published the package
+                let package = self
+                    .package_handler
+                    .write()
+                    .pick_package(&mut self.rng, account);
+                requests.push(package.publish_transaction(account, &self.txn_factory));
+                self.published.insert(account.address(), package);
+            } else {
+                requests.push(self.published[&account.address()].use_random_transaction(
+                    &mut self.rng,
+                    account,
+                    &self.txn_factory,
+                ));
+            }
+        }
+        requests
+    }
+}
+
+pub struct PublishUpgradeCreator {
+    txn_factory: TransactionFactory,
+    package_handler: Arc<RwLock<PackageHandler>>,
+    upgrade_percentage: usize,
+    rng: StdRng,
+}
+
+impl PublishUpgradeCreator {
+    pub fn new(
+        txn_factory: TransactionFactory,
+        package_size: usize,
+        upgrade_percentage: usize,
+        rng: StdRng,
+    ) -> Self {
+        assert!(upgrade_percentage <= 100);
+        Self {
+            txn_factory,
+            package_handler: Arc::new(RwLock::new(
+                PackageHandler::new("simple").with_package_size(package_size),
+            )),
+            upgrade_percentage,
+            rng,
+        }
+    }
+}
+
+impl TransactionGeneratorCreator for PublishUpgradeCreator {
+    fn create_transaction_generator(&mut self) -> Box<dyn TransactionGenerator> {
+        Box::new(PublishUpgradeGenerator {
+            rng: new_rng(&mut self.rng),
+            package_handler: self.package_handler.clone(),
+            txn_factory: self.txn_factory.clone(),
+            upgrade_percentage: self.upgrade_percentage,
+            published: HashMap::new(),
+        })
+    }
+}
@@ -86,6 +86,27 @@ pub fn scramble(module: &mut CompiledModule, fn_count: usize, rng: &mut StdRng)
     }
 }
 
+pub fn resize(module: &mut CompiledModule, size: usize) {
+    // pad `const RANDOM` in Simple.move, so the serialized module is about `size` bytes.
+    // That is the only vector<u64> in the constant pool
+    let random_type = SignatureToken::Vector(Box::new(SignatureToken::U64));
+    let set_random = |module: &mut CompiledModule, v: Vec<u64>| {
+        for constant in &mut module.constant_pool {
+            if constant.type_ == random_type {
+                constant.data = bcs::to_bytes(&v).expect("U64 vector must serialize");
+                break;
+            }
+        }
+    };
+    set_random(module, vec![]);
+    let mut binary = vec![];
+    module
+        .serialize(&mut binary)
+        .expect("Module must serialize");
+    let const_len = size.saturating_sub(binary.len()) / 8;
+    set_random(module, (0..const_len as u64).collect());
+}
+
 pub enum MultiSigConfig {
     None,
     Random(usize),
@@ -168,6 +189,63 @@ pub enum EntryPoints {
     TokenV1MintAndTransferFT,
 
     TokenV2AmbassadorMint,
+
+    /// Premint and list tokens on the marketplace
+    MarketplaceInitialize {
+        num_listings: u64,
+    },
+    /// Buy a random listing, which stays listed for the buyer.
+    /// Should be called only after MarketplaceInitialize is called
+    MarketplaceBuy {
+        num_listings: u64,
+    },
+
+    /// Create the SmartTable of the publisher
+    SmartTableInitialize,
+    /// Insert, read or remove a random key out of `num_keys` in the SmartTable of the publisher.
+    /// Should be called only after SmartTableInitialize is called
+    SmartTableRandom {
+        num_keys: u64,
+    },
+
+    /// Create the resource group of the publisher, each member holding `member_size` bytes
+    ResourceGroupsInitialize {
+        member_size: u64,
+    },
+    /// Mutate a random member of the resource group of the publisher.
+    /// Should be called only after ResourceGroupsInitialize is called
+    ResourceGroupsMutate {
+        member_size: u64,
+    },
+
+    /// Set up the governance proposal of the publisher
+    GovernanceInitialize,
+    /// Stake one more octa and vote with it on the proposal of the publisher (or propose the
+    /// next one). Should be called only after GovernanceInitialize is called
+    GovernanceVote,
+
+    /// Create the constant-product pool of the publisher
+    LiquidityPoolInitialize,
+    /// Swap a random amount, in a random direction, in the pool of the publisher.
+    /// Should be called only after LiquidityPoolInitialize is called
+    LiquidityPoolSwap,
+
+    /// Write `num_slots` storage slots of the sender, each holding `slot_size` bytes,
+    /// from a random offset out of `max_offset`
+    WriteSlots {
+        num_slots: u64,
+        slot_size: u64,
+        max_offset: u64,
+    },
+
+    /// Emit `count` events of the sender, each holding `event_size` bytes
+    EmitSizedEvents {
+        count: u64,
+        event_size: u64,
+    },
+
+    /// Call a module of another package, published under another account
+    CallDependency,
 }
 
 impl EntryPoints {
@@ -199,8 +277,21 @@ impl EntryPoints {
             | EntryPoints::TokenV1MintAndTransferNFTParallel
             | EntryPoints::TokenV1MintAndTransferNFTSequential
             | EntryPoints::TokenV1MintAndStoreFT
-            | EntryPoints::TokenV1MintAndTransferFT => "framework_usecases",
+            | EntryPoints::TokenV1MintAndTransferFT
+            | EntryPoints::MarketplaceInitialize { .. }
+            | EntryPoints::MarketplaceBuy { .. }
+            | EntryPoints::SmartTableInitialize
+            | EntryPoints::SmartTableRandom { .. }
+            | EntryPoints::ResourceGroupsInitialize { .. }
+            | EntryPoints::ResourceGroupsMutate { .. }
+            | EntryPoints::GovernanceInitialize
+            | EntryPoints::GovernanceVote
+            | EntryPoints::LiquidityPoolInitialize
+            | EntryPoints::LiquidityPoolSwap
+            | EntryPoints::WriteSlots { .. }
+            | EntryPoints::EmitSizedEvents { .. } => "framework_usecases",
             EntryPoints::TokenV2AmbassadorMint => "ambassador_token",
+            EntryPoints::CallDependency => "dependency_user",
         }
     }
 
@@ -234,6 +325,21 @@ impl EntryPoints {
             | EntryPoints::TokenV1MintAndStoreFT
             | EntryPoints::TokenV1MintAndTransferFT => "token_v1",
             EntryPoints::TokenV2AmbassadorMint => "ambassador",
+            EntryPoints::MarketplaceInitialize { .. } | EntryPoints::MarketplaceBuy { .. } => {
+                "marketplace"
+            },
+            EntryPoints::SmartTableInitialize | EntryPoints::SmartTableRandom { .. } => {
+                "smart_table_example"
+            },
+            EntryPoints::ResourceGroupsInitialize { .. }
+            | EntryPoints::ResourceGroupsMutate { .. } => "resource_groups_example",
+            EntryPoints::GovernanceInitialize | EntryPoints::GovernanceVote => "governance_example",
+            EntryPoints::LiquidityPoolInitialize | EntryPoints::LiquidityPoolSwap => {
+                "liquidity_pool_example"
+            },
+            EntryPoints::WriteSlots { .. } => "write_set_example",
+            EntryPoints::EmitSizedEvents { .. } => "events_example",
+            EntryPoints::CallDependency => "dependency_user",
         }
     }
 
@@ -373,6 +479,89 @@ impl EntryPoints {
                     ],
                 )
             },
+            EntryPoints::MarketplaceInitialize { num_listings } => {
+                get_payload(module_id, ident_str!("initialize").to_owned(), vec![
+                    bcs::to_bytes(num_listings).unwrap(),
+                ])
+            },
+            EntryPoints::MarketplaceBuy { num_listings } => {
+                let rng: &mut StdRng = rng.expect("Must provide RNG");
+                get_payload(module_id, ident_str!("buy").to_owned(), vec![
+                    bcs::to_bytes(other.expect("Must provide other")).unwrap(),
+                    bcs::to_bytes(&rng.gen_range(0u64, *num_listings)).unwrap(),
+                ])
+            },
+            EntryPoints::SmartTableInitialize => {
+                get_payload_void(module_id, ident_str!("initialize").to_owned())
+            },
+            EntryPoints::SmartTableRandom { num_keys } => {
+                let rng: &mut StdRng = rng.expect("Must provide RNG");
+                // inserts outweigh removes, so the table keeps growing (and splitting buckets)
+                // until the keys are mostly present
+                let func = match rng.gen_range(0, 10) {
+                    0..=4 => ident_str!("upsert"),
+                    5..=7 => ident_str!("read"),
+                    _ => ident_str!("remove"),
+                };
+                get_payload(module_id, func.to_owned(), vec![
+                    bcs::to_bytes(other.expect("Must provide other")).unwrap(),
+                    bcs::to_bytes(&rng.gen_range(0u64, *num_keys)).unwrap(),
+                ])
+            },
+            EntryPoints::ResourceGroupsInitialize { member_size } => {
+                get_payload(module_id, ident_str!("initialize").to_owned(), vec![
+                    bcs::to_bytes(member_size).unwrap(),
+                ])
+            },
+            EntryPoints::ResourceGroupsMutate { .. } => {
+                let rng: &mut StdRng = rng.expect("Must provide RNG");
+                get_payload(module_id, ident_str!("mutate").to_owned(), vec![
+                    bcs::to_bytes(other.expect("Must provide other")).unwrap(),
+                    bcs::to_bytes(&rng.gen_range(0u64, 4)).unwrap(),
+                ])
+            },
+            EntryPoints::GovernanceInitialize => {
+                get_payload_void(module_id, ident_str!("initialize").to_owned())
+            },
+            EntryPoints::GovernanceVote => {
+                let rng: &mut StdRng = rng.expect("Must provide RNG");
+                get_payload(module_id, ident_str!("vote").to_owned(), vec![
+                    bcs::to_bytes(other.expect("Must provide other")).unwrap(),
+                    bcs::to_bytes(&rng.gen::<bool>()).unwrap(),
+                ])
+            },
+            EntryPoints::LiquidityPoolInitialize => {
+                get_payload_void(module_id, ident_str!("initialize").to_owned())
+            },
+            EntryPoints::LiquidityPoolSwap => {
+                let rng: &mut StdRng = rng.expect("Must provide RNG");
+                get_payload(module_id, ident_str!("swap").to_owned(), vec![
+                    bcs::to_bytes(other.expect("Must provide other")).unwrap(),
+                    bcs::to_bytes(&rng.gen_range(1u64, 1000u64)).unwrap(),
+                    bcs::to_bytes(&rng.gen::<bool>()).unwrap(),
+                ])
+            },
+            EntryPoints::WriteSlots {
+                num_slots,
+                slot_size,
+                max_offset,
+            } => {
+                let rng: &mut StdRng = rng.expect("Must provide RNG");
+                get_payload(module_id, ident_str!("write_slots").to_owned(), vec![
+                    bcs::to_bytes(&rng.gen_range(0u64, *max_offset)).unwrap(),
+                    bcs::to_bytes(num_slots).unwrap(),
+                    bcs::to_bytes(slot_size).unwrap(),
+                ])
+            },
+            EntryPoints::EmitSizedEvents { count, event_size } => {
+                get_payload(module_id, ident_str!("emit_events").to_owned(), vec![
+                    bcs::to_bytes(count).unwrap(),
+                    bcs::to_bytes(event_size).unwrap(),
+                ])
+            },
+            EntryPoints::CallDependency => {
+                get_payload_void(module_id, ident_str!("call_dependency").to_owned())
+            },
         }
     }
 
@@ -386,6 +575,19 @@ impl EntryPoints {
             | EntryPoints::TokenV1MintAndTransferFT => {
                 Some(EntryPoints::TokenV1InitializeCollection)
             },
+            EntryPoints::MarketplaceBuy { num_listings } => {
+                Some(EntryPoints::MarketplaceInitialize {
+                    num_listings: *num_listings,
+                })
+            },
+            EntryPoints::SmartTableRandom { .. } => Some(EntryPoints::SmartTableInitialize),
+            EntryPoints::ResourceGroupsMutate { member_size } => {
+                Some(EntryPoints::ResourceGroupsInitialize {
+                    member_size: *member_size,
+                })
+            },
+            EntryPoints::GovernanceVote => Some(EntryPoints::GovernanceInitialize),
+            EntryPoints::LiquidityPoolSwap => Some(EntryPoints::LiquidityPoolInitialize),
             _ => None,
         }
     }
@@ -9,7 +9,9 @@ use aptos_sdk::{
     transaction_builder::{aptos_stdlib, TransactionFactory},
     types::{account_address::AccountAddress, transaction::SignedTransaction, LocalAccount},
 };
-use move_binary_format::{access::ModuleAccess, CompiledModule};
+use move_binary_format::{
+    access::ModuleAccess, file_format::AddressIdentifierIndex, CompiledModule,
+};
 use rand::{rngs::StdRng, Rng};
 
 // Information used to track a publisher and what allows to identify and
@@ -45,6 +47,8 @@ impl PackageTracker {
 pub struct PackageHandler {
     packages: Vec<PackageTracker>,
     is_simple: bool,
+    /// Byte size of the simple module, random when not set
+    package_size: Option<usize>,
 }
 
 impl Default for PackageHandler {
@@ -63,9 +67,15 @@ impl PackageHandler {
         PackageHandler {
             packages,
             is_simple: name == "simple",
+            package_size: None,
         }
     }
 
+    pub fn with_package_size(mut self, package_size: usize) -> Self {
+        self.package_size = Some(package_size);
+        self
+    }
+
     // Return a `Package` to be published. Packages are tracked by publisher so if
     // the same `LocalAccount` is used, the package will be an upgrade of the existing one
     // otherwise a "new" package will be generated (new suffix)
@@ -101,6 +111,9 @@ impl PackageHandler {
                 package.version(rng);
             }
             package.scramble(tracker.publishers[idx].fn_count, rng);
+            if let Some(package_size) = self.package_size {
+                package.resize(package_size);
+            }
         }
         // info!("PACKAGE: {:#?}", package);
         package
@@ -115,10 +128,18 @@ pub enum Package {
 
 impl Package {
     pub fn by_name(name: &str) -> Self {
-        let (modules, metadata) = Self::load_package(
-            &raw_module_data::PACKAGE_TO_METADATA[name],
-            &raw_module_data::PACKAGE_TO_MODULES[name],
-        );
+        let (modules, metadata) = match (
+            raw_module_data::PACKAGE_TO_METADATA.get(name),
+            raw_module_data::PACKAGE_TO_MODULES.get(name),
+        ) {
+            (Some(package_bytes), Some(modules_bytes)) => {
+                Self::load_package(package_bytes, modules_bytes)
+            },
+            _ => panic!(
+                "Package {} is not in raw_module_data.rs, regenerate it with `cargo run -p module-publish`",
+                name
+            ),
+        };
         Self::Simple(modules, metadata)
     }
 
@@ -147,6 +168,27 @@ impl Package {
         }
     }
 
+    // Points the references of the package to the modules of `dependency` (as published
+    // originally, under the same address) at `dependency_publisher`, where it is published
+    // instead. A no-op if the package doesn't depend on it.
+    pub fn link_dependency(&mut self, dependency: &Package, dependency_publisher: AccountAddress) {
+        match (self, dependency) {
+            (
+                Self::Simple(modules, metadata),
+                Self::Simple(dependency_modules, dependency_metadata),
+            ) => {
+                for (_, module) in modules.iter_mut() {
+                    link_dependency(module, dependency_modules, dependency_publisher);
+                }
+                for dep in &mut metadata.deps {
+                    if dep.package_name == dependency_metadata.name {
+                        dep.account = dependency_publisher;
+                    }
+                }
+            },
+        }
+    }
+
     // Change package "version"
     pub fn version(&mut self, rng: &mut StdRng) {
         module_simple::version(self.get_mut_module("simple"), rng)
@@ -158,6 +200,11 @@ impl Package {
         module_simple::scramble(self.get_mut_module("simple"), fn_count, rng)
     }
 
+    // Pads the package to about `size` bytes
+    pub fn resize(&mut self, size: usize) {
+        module_simple::resize(self.get_mut_module("simple"), size)
+    }
+
     // Return a transaction to publish the current package
     pub fn publish_transaction(
         &self,
@@ -257,6 +304,46 @@ fn update(
     (new_modules, metadata)
 }
 
+fn link_dependency(
+    module: &mut CompiledModule,
+    dependency_modules: &[(String, CompiledModule)],
+    dependency_publisher: AccountAddress,
+) {
+    let self_handle_idx = module.self_handle_idx().0 as usize;
+    for handle_idx in 0..module.module_handles.len() {
+        let name = module.identifiers[module.module_handles[handle_idx].name.0 as usize].as_str();
+        if handle_idx == self_handle_idx
+            || !dependency_modules
+                .iter()
+                .any(|(dep_name, _)| dep_name == name)
+        {
+            continue;
+        }
+        // addresses need to be unique, reuse it if it is already there
+        let address_idx = match module
+            .address_identifiers
+            .iter()
+            .position(|address| address == &dependency_publisher)
+        {
+            Some(idx) => idx,
+            None => {
+                module.address_identifiers.push(dependency_publisher);
+                module.address_identifiers.len() - 1
+            },
+        };
+        module.module_handles[handle_idx].address = AddressIdentifierIndex(address_idx as u16);
+    }
+}
+
+/// Packages (in publishing order) the given package depends on, which are published
+/// with it, each under an account of its own (see `Package::link_dependency`).
+pub fn package_dependencies(package_name: &str) -> &'static [&'static str] {
+    match package_name {
+        "dependency_user" => &["dependency_base"],
+        _ => &[],
+    }
+}
+
 fn publish_transaction(
     txn_factory: &TransactionFactory,
     publisher: &mut LocalAccount,
@@ -0,0 +1,210 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    create_funded_accounts, resync_accounts, ReliableTransactionSubmitter, TransactionGenerator,
+    TransactionGeneratorCreator,
+};
+use aptos_infallible::Mutex;
+use aptos_logger::info;
+use aptos_sdk::{
+    bcs,
+    move_types::account_address::AccountAddress,
+    transaction_builder::TransactionFactory,
+    types::{
+        transaction::{EntryFunction, SignedTransaction, Transaction, TransactionPayload},
+        LocalAccount,
+    },
+};
+use rand::rngs::StdRng;
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+};
+
+/// Entry function calls of a range of historical (e.g. mainnet) transactions.
+/// Only calls into framework modules (special addresses) are kept, as other modules
+/// are not published on the benchmarked network.
+#[derive(Debug)]
+pub struct ReplayWorkload {
+    txns: Vec<(AccountAddress, EntryFunction)>,
+    /// Historical senders, in order of their first transaction
+    senders: Vec<AccountAddress>,
+}
+
+impl ReplayWorkload {
+    pub fn from_transactions(txns: impl IntoIterator<Item = Transaction>) -> Self {
+        let txns: Vec<_> = txns
+            .into_iter()
+            .filter_map(|txn| match txn {
+                Transaction::UserTransaction(txn) => {
+                    let sender = txn.sender();
+                    match txn.into_raw_transaction().into_payload() {
+                        TransactionPayload::EntryFunction(entry_function)
+                            if entry_function.module().address().is_special() =>
+                        {
+                            Some((sender, entry_function))
+                        },
+                        _ => None,
+                    }
+                },
+                _ => None,
+            })
+            .collect();
+        let mut seen = HashSet::new();
+        let senders = txns
+            .iter()
+            .map(|(sender, _)| *sender)
+            .filter(|sender| seen.insert(*sender))
+            .collect();
+        Self { txns, senders }
+    }
+
+    pub fn len(&self) -> usize {
+        self.txns.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.txns.is_empty()
+    }
+}
+
+/// Historical senders mapped onto local accounts
+struct Replayers {
+    accounts: Vec<Mutex<LocalAccount>>,
+    addresses: Vec<AccountAddress>,
+    sender_to_account: HashMap<AccountAddress, usize>,
+}
+
+impl Replayers {
+    /// Arguments referring to a historical sender are pointed to its local account,
+    /// so transfers between senders, for example, stay between replaying accounts.
+    fn map_args(&self, entry_function: &EntryFunction) -> EntryFunction {
+        let args = entry_function
+            .args()
+            .iter()
+            .map(|arg| {
+                AccountAddress::from_bytes(arg)
+                    .ok()
+                    .and_then(|address| self.sender_to_account.get(&address))
+                    .map_or_else(
+                        || arg.clone(),
+                        |index| bcs::to_bytes(&self.addresses[*index]).unwrap(),
+                    )
+            })
+            .collect();
+        EntryFunction::new(
+            entry_function.module().clone(),
+            entry_function.function().to_owned(),
+            entry_function.ty_args().to_vec(),
+            args,
+        )
+    }
+}
+
+/// Replays the historical transactions in order (wrapping around), each one signed by the
+/// local account its sender is mapped to. The accounts passed in by the emitter are not used.
+/// Rate is controlled by the emitter (e.g. --target-tps).
+pub struct ReplayTransactionGenerator {
+    txn_factory: TransactionFactory,
+    workload: Arc<ReplayWorkload>,
+    replayers: Arc<Replayers>,
+    next_txn: Arc<AtomicUsize>,
+}
+
+impl TransactionGenerator for ReplayTransactionGenerator {
+    fn generate_transactions(
+        &mut self,
+        _account: &mut LocalAccount,
+        num_to_create: usize,
+    ) -> Vec<SignedTransaction> {
+        (0..num_to_create)
+            .map(|_| {
+                let index = self.next_txn.fetch_add(1, Ordering::Relaxed) % self.workload.len();
+                let (sender, entry_function) = &self.workload.txns[index];
+                let payload =
+                    TransactionPayload::EntryFunction(self.replayers.map_args(entry_function));
+                self.replayers.accounts[self.replayers.sender_to_account[sender]]
+                    .lock()
+                    .sign_with_transaction_builder(self.txn_factory.payload(payload))
+            })
+            .collect()
+    }
+
+    fn resync_sequence_numbers(&mut self, stale: &HashMap<AccountAddress, (u64, u64)>) {
+        // accounts are shared between the generators of all workers
+        for account in &self.replayers.accounts {
+            resync_accounts([&mut *account.lock()], stale);
+        }
+    }
+}
+
+pub struct ReplayTransactionGeneratorCreator {
+    txn_factory: TransactionFactory,
+    workload: Arc<ReplayWorkload>,
+    replayers: Arc<Replayers>,
+    next_txn: Arc<AtomicUsize>,
+}
+
+impl ReplayTransactionGeneratorCreator {
+    /// Creates up to `num_accounts` local accounts, historical senders share them
+    /// when there are more senders than accounts.
+    pub async fn new(
+        txn_factory: TransactionFactory,
+        init_txn_factory: TransactionFactory,
+        source_accounts: &mut [LocalAccount],
+        txn_executor: &dyn ReliableTransactionSubmitter,
+        workload: Arc<ReplayWorkload>,
+        num_accounts: usize,
+        rng: &mut StdRng,
+    ) -> Self {
+        assert!(!workload.is_empty(), "No transactions to replay");
+        assert!(num_accounts > 0, "Need accounts to replay with");
+        let num_accounts = num_accounts.min(workload.senders.len());
+        info!(
+            "Replaying {} transactions from {} senders with {} accounts",
+            workload.len(),
+            workload.senders.len(),
+            num_accounts
+        );
+        let accounts = create_funded_accounts(
+            "replay",
+            num_accounts,
+            source_accounts,
+            txn_executor,
+            &init_txn_factory,
+            rng,
+        )
+        .await;
+        let sender_to_account = workload
+            .senders
+            .iter()
+            .enumerate()
+            .map(|(i, sender)| (*sender, i % num_accounts))
+            .collect();
+        Self {
+            txn_factory,
+            workload,
+            replayers: Arc::new(Replayers {
+                addresses: accounts.iter().map(|account| account.address()).collect(),
+                accounts: accounts.into_iter().map(Mutex::new).collect(),
+                sender_to_account,
+            }),
+            next_txn: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+}
+
+impl TransactionGeneratorCreator for ReplayTransactionGeneratorCreator {
+    fn create_transaction_generator(&mut self) -> Box<dyn TransactionGenerator> {
+        Box::new(ReplayTransactionGenerator {
+            txn_factory: self.txn_factory.clone(),
+            workload: self.workload.clone(),
+            replayers: self.replayers.clone(),
+            next_txn: self.next_txn.clone(),
+        })
+    }
+}
@@ -0,0 +1,102 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{new_rng, TransactionGenerator, TransactionGeneratorCreator};
+use aptos_sdk::{
+    transaction_builder::TransactionFactory,
+    types::{
+        transaction::{Script, SignedTransaction, TransactionArgument},
+        LocalAccount,
+    },
+};
+use move_binary_format::file_format::{empty_script, Signature, SignatureIndex, SignatureToken};
+use rand::{rngs::StdRng, Rng, RngCore};
+use std::sync::Arc;
+
+/// Script taking `num_args` `vector<u8>` arguments and doing nothing with them,
+/// so only loading, verifying and validating the arguments of a script is measured.
+fn script_code(num_args: usize) -> Vec<u8> {
+    let mut script = empty_script();
+    // signatures need to be unique, the empty one is already there
+    if num_args > 0 {
+        let arg = SignatureToken::Vector(Box::new(SignatureToken::U8));
+        script.signatures.push(Signature(vec![arg; num_args]));
+        script.parameters = SignatureIndex(1);
+    }
+    let mut code = vec![];
+    script
+        .serialize(&mut code)
+        .expect("Serializing script failed");
+    code
+}
+
+/// Submits script payloads instead of entry function calls, as scripts go through
+/// a different validation and execution path.
+/// Each transaction has between 0 and `max_num_args` arguments (each number of arguments
+/// is a separate script), each one between 0 and `max_arg_size` bytes.
+pub struct ScriptPayloadGenerator {
+    rng: StdRng,
+    txn_factory: TransactionFactory,
+    scripts: Arc<Vec<Vec<u8>>>,
+    max_arg_size: usize,
+}
+
+impl TransactionGenerator for ScriptPayloadGenerator {
+    fn generate_transactions(
+        &mut self,
+        account: &mut LocalAccount,
+        num_to_create: usize,
+    ) -> Vec<SignedTransaction> {
+        (0..num_to_create)
+            .map(|_| {
+                let num_args = self.rng.gen_range(0, self.scripts.len());
+                let args = (0..num_args)
+                    .map(|_| {
+                        let mut arg = vec![0u8; self.rng.gen_range(0, self.max_arg_size + 1)];
+                        self.rng.fill_bytes(&mut arg);
+                        TransactionArgument::U8Vector(arg)
+                    })
+                    .collect();
+                account.sign_with_transaction_builder(self.txn_factory.script(Script::new(
+                    self.scripts[num_args].clone(),
+                    vec![],
+                    args,
+                )))
+            })
+            .collect()
+    }
+}
+
+pub struct ScriptPayloadGeneratorCreator {
+    txn_factory: TransactionFactory,
+    scripts: Arc<Vec<Vec<u8>>>,
+    max_arg_size: usize,
+    rng: StdRng,
+}
+
+impl ScriptPayloadGeneratorCreator {
+    pub fn new(
+        txn_factory: TransactionFactory,
+        max_num_args: usize,
+        max_arg_size: usize,
+        rng: StdRng,
+    ) -> Self {
+        Self {
+            txn_factory,
+            scripts: Arc::new((0..=max_num_args).map(script_code).collect()),
+            max_arg_size,
+            rng,
+        }
+    }
+}
+
+impl TransactionGeneratorCreator for ScriptPayloadGeneratorCreator {
+    fn create_transaction_generator(&mut self) -> Box<dyn TransactionGenerator> {
+        Box::new(ScriptPayloadGenerator {
+            rng: new_rng(&mut self.rng),
+            txn_factory: self.txn_factory.clone(),
+            scripts: self.scripts.clone(),
+            max_arg_size: self.max_arg_size,
+        })
+    }
+}
@@ -0,0 +1,285 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    fund_new_accounts, ReliableTransactionSubmitter, TransactionGenerator,
+    TransactionGeneratorCreator,
+};
+use anyhow::{bail, Result};
+use aptos_infallible::Mutex;
+use aptos_logger::{sample, sample::SampleRate, warn};
+use aptos_sdk::{
+    crypto::{
+        ed25519::Ed25519PrivateKey,
+        multi_ed25519::{MultiEd25519PrivateKey, MultiEd25519PublicKey},
+        SigningKey, Uniform,
+    },
+    move_types::account_address::AccountAddress,
+    transaction_builder::TransactionFactory,
+    types::{
+        transaction::{
+            authenticator::{AuthenticationKey, TransactionAuthenticator},
+            RawTransaction, SignedTransaction,
+        },
+        LocalAccount,
+    },
+};
+use rand::rngs::StdRng;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    str::FromStr,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+/// Key type of the accounts signing the generated transactions
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub enum SignerKeyType {
+    /// Transactions are signed by the accounts the generators are called with
+    #[default]
+    Ed25519,
+    /// Transactions are re-signed by k-of-n multi-ed25519 accounts
+    MultiEd25519 { num_keys: u8, threshold: u8 },
+}
+
+impl FromStr for SignerKeyType {
+    type Err = anyhow::Error;
+
+    /// Parses `ed25519` or `multi-ed25519:<num_keys>:<threshold>`
+    fn from_str(s: &str) -> Result<Self> {
+        let parts: Vec<_> = s.split(':').collect();
+        let key_type = match parts.as_slice() {
+            ["ed25519"] => Self::Ed25519,
+            ["multi-ed25519", num_keys, threshold] => Self::MultiEd25519 {
+                num_keys: num_keys.parse()?,
+                threshold: threshold.parse()?,
+            },
+            _ => bail!(
+                "Invalid signer key type {}, expected ed25519 or multi-ed25519:<num_keys>:<threshold>",
+                s
+            ),
+        };
+        if let Self::MultiEd25519 {
+            num_keys,
+            threshold,
+        } = key_type
+        {
+            if threshold == 0 || threshold > num_keys {
+                bail!("Threshold needs to be between 1 and the number of keys");
+            }
+        }
+        Ok(key_type)
+    }
+}
+
+/// Account authenticated by a multi-ed25519 key, signing with the first `threshold` keys
+pub struct MultiEd25519Account {
+    private_key: MultiEd25519PrivateKey,
+    public_key: MultiEd25519PublicKey,
+    address: AccountAddress,
+    sequence_number: u64,
+}
+
+impl MultiEd25519Account {
+    fn generate(rng: &mut StdRng, num_keys: u8, threshold: u8) -> Self {
+        let private_key = MultiEd25519PrivateKey::new(
+            (0..num_keys)
+                .map(|_| Ed25519PrivateKey::generate(rng))
+                .collect(),
+            threshold,
+        )
+        .expect("Invalid multi-ed25519 key");
+        let public_key = MultiEd25519PublicKey::from(&private_key);
+        Self {
+            address: AuthenticationKey::multi_ed25519(&public_key).derived_address(),
+            private_key,
+            public_key,
+            sequence_number: 0,
+        }
+    }
+
+    /// Signs the transaction as our own, keeping everything but sender and sequence number
+    fn resign(&mut self, txn: SignedTransaction) -> SignedTransaction {
+        let raw_txn = RawTransaction::new(
+            self.address,
+            self.sequence_number,
+            txn.payload().clone(),
+            txn.max_gas_amount(),
+            txn.gas_unit_price(),
+            txn.expiration_timestamp_secs(),
+            txn.chain_id(),
+        );
+        self.sequence_number += 1;
+        let signature = self
+            .private_key
+            .sign(&raw_txn)
+            .expect("Signing multi-ed25519 txn failed");
+        SignedTransaction::new_multisig(raw_txn, self.public_key.clone(), signature)
+    }
+}
+
+/// Wrapper that re-signs the transactions of the inner generator with accounts of another
+/// key type, taken in turns from a pool shared by all workers, so the cost of verifying
+/// their signatures is measured. Only batches signed by the sender alone can be re-signed,
+/// the others (e.g. multi agent ones) are submitted as they are.
+pub struct SignerKeyWrapperGenerator {
+    generator: Box<dyn TransactionGenerator>,
+    signers: Arc<Vec<Mutex<MultiEd25519Account>>>,
+    next_signer: Arc<AtomicUsize>,
+}
+
+impl TransactionGenerator for SignerKeyWrapperGenerator {
+    fn generate_transactions(
+        &mut self,
+        account: &mut LocalAccount,
+        num_to_create: usize,
+    ) -> Vec<SignedTransaction> {
+        let sequence_number = account.sequence_number();
+        let txns = self.generator.generate_transactions(account, num_to_create);
+        if txns.iter().any(|txn| {
+            txn.sender() == account.address()
+                && !matches!(
+                    txn.authenticator_ref(),
+                    TransactionAuthenticator::Ed25519 { .. }
+                )
+        }) {
+            sample!(
+                SampleRate::Duration(Duration::from_secs(10)),
+                warn!("Cannot change the signer of a transaction not signed by the sender alone");
+            );
+            return txns;
+        }
+
+        // the account's transactions are all replaced, it didn't send any
+        *account.sequence_number_mut() = sequence_number;
+        txns.into_iter()
+            .map(|txn| {
+                if txn.sender() != account.address() {
+                    return txn;
+                }
+                let index = self.next_signer.fetch_add(1, Ordering::Relaxed) % self.signers.len();
+                self.signers[index].lock().resign(txn)
+            })
+            .collect()
+    }
+
+    fn resync_sequence_numbers(&mut self, stale: &HashMap<AccountAddress, (u64, u64)>) {
+        for signer in self.signers.iter() {
+            let mut signer = signer.lock();
+            if let Some((expected, fetched)) = stale.get(&signer.address) {
+                if signer.sequence_number == *expected {
+                    signer.sequence_number = *fetched;
+                }
+            }
+        }
+        self.generator.resync_sequence_numbers(stale);
+    }
+}
+
+pub struct SignerKeyWrapperCreator {
+    creator: Box<dyn TransactionGeneratorCreator>,
+    signers: Arc<Vec<Mutex<MultiEd25519Account>>>,
+    next_signer: Arc<AtomicUsize>,
+}
+
+impl SignerKeyWrapperCreator {
+    pub fn new(
+        creator: Box<dyn TransactionGeneratorCreator>,
+        signers: Arc<Vec<Mutex<MultiEd25519Account>>>,
+    ) -> Self {
+        Self {
+            creator,
+            signers,
+            next_signer: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+}
+
+impl TransactionGeneratorCreator for SignerKeyWrapperCreator {
+    fn create_transaction_generator(&mut self) -> Box<dyn TransactionGenerator> {
+        Box::new(SignerKeyWrapperGenerator {
+            generator: self.creator.create_transaction_generator(),
+            signers: self.signers.clone(),
+            next_signer: self.next_signer.clone(),
+        })
+    }
+}
+
+/// Create and fund a signer of the given key type for each source account,
+/// none when transactions are signed by the source accounts themselves.
+pub async fn create_signers(
+    key_type: SignerKeyType,
+    source_accounts: &mut [LocalAccount],
+    txn_executor: &dyn ReliableTransactionSubmitter,
+    init_txn_factory: &TransactionFactory,
+    rng: &mut StdRng,
+) -> Option<Arc<Vec<Mutex<MultiEd25519Account>>>> {
+    let (num_keys, threshold) = match key_type {
+        SignerKeyType::Ed25519 => return None,
+        SignerKeyType::MultiEd25519 {
+            num_keys,
+            threshold,
+        } => (num_keys, threshold),
+    };
+    let signers: Vec<_> = (0..source_accounts.len())
+        .map(|_| MultiEd25519Account::generate(rng, num_keys, threshold))
+        .collect();
+    fund_new_accounts(
+        "multi-ed25519 signer",
+        &signers
+            .iter()
+            .map(|signer| signer.address)
+            .collect::<Vec<_>>(),
+        source_accounts,
+        txn_executor,
+        init_txn_factory,
+    )
+    .await;
+    Some(Arc::new(signers.into_iter().map(Mutex::new).collect()))
+}
+
+#[cfg(test)]
+mod test {
+    use super::SignerKeyType;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_parse_signer_key_type() {
+        assert_eq!(
+            SignerKeyType::from_str("ed25519").unwrap(),
+            SignerKeyType::Ed25519
+        );
+        assert_eq!(
+            SignerKeyType::from_str("multi-ed25519:3:2").unwrap(),
+            SignerKeyType::MultiEd25519 {
+                num_keys: 3,
+                threshold: 2
+            }
+        );
+        assert_eq!(
+            SignerKeyType::from_str("multi-ed25519:2:2").unwrap(),
+            SignerKeyType::MultiEd25519 {
+                num_keys: 2,
+                threshold: 2
+            }
+        );
+
+        for invalid in [
+            "",
+            "secp256k1",
+            "ed25519:1",
+            "multi-ed25519",
+            "multi-ed25519:3",
+            "multi-ed25519:3:0",
+            "multi-ed25519:2:3",
+            "multi-ed25519:x:1",
+            "multi-ed25519:3:2:1",
+        ] {
+            assert!(SignerKeyType::from_str(invalid).is_err(), "{}", invalid);
+        }
+    }
+}
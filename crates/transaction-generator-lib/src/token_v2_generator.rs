@@ -0,0 +1,463 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{new_rng, TransactionGenerator, TransactionGeneratorCreator};
+use aptos_infallible::RwLock;
+use aptos_sdk::{
+    bcs,
+    crypto::hash::DefaultHasher,
+    move_types::{
+        account_address::AccountAddress,
+        ident_str,
+        language_storage::{ModuleId, StructTag, TypeTag},
+    },
+    transaction_builder::{
+        aptos_stdlib, aptos_stdlib::aptos_token_objects_stdlib, TransactionFactory,
+    },
+    types::{
+        transaction::{
+            authenticator::{AuthenticationKey, AuthenticationKeyPreimage},
+            EntryFunction, SignedTransaction, TransactionPayload,
+        },
+        LocalAccount,
+    },
+};
+use rand::{rngs::StdRng, seq::SliceRandom, Rng};
+use serde::Serialize;
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::Arc,
+};
+
+const URI: &[u8] = b"https://aptoslabs.com";
+const BURNABLE_COLLECTION_NAME: &[u8] = b"Burnable collection";
+
+/// Progress of a sender acting as the creator of its own collections
+#[derive(Default)]
+struct CreatorState {
+    collection_created: bool,
+    collection_index: usize,
+    num_minted: usize,
+    /// Transferable tokens minted into the creator, waiting to be transferred
+    minted_tokens: Vec<AccountAddress>,
+}
+
+/// Exercises the token objects standard (0x4::aptos_token). Every sender creates a collection
+/// of `collection_size` tokens and mints them, either soul bound straight into a random account,
+/// or into itself and then transfers them to a random account. A full collection is followed by
+/// a new one.
+pub struct TokenV2TransactionGenerator {
+    rng: StdRng,
+    txn_factory: TransactionFactory,
+    all_addresses: Arc<RwLock<Vec<AccountAddress>>>,
+    collection_size: usize,
+    soulbound_percentage: usize,
+    creators: HashMap<AccountAddress, CreatorState>,
+}
+
+impl TokenV2TransactionGenerator {
+    pub fn new(
+        rng: StdRng,
+        txn_factory: TransactionFactory,
+        all_addresses: Arc<RwLock<Vec<AccountAddress>>>,
+        collection_size: usize,
+        soulbound_percentage: usize,
+    ) -> Self {
+        Self {
+            rng,
+            txn_factory,
+            all_addresses,
+            collection_size,
+            soulbound_percentage,
+            creators: HashMap::new(),
+        }
+    }
+
+    fn generate_transaction(&mut self, account: &mut LocalAccount) -> SignedTransaction {
+        let creator = account.address();
+        let sequence_number = account.sequence_number();
+        let receiver = *self.all_addresses.read().choose(&mut self.rng).unwrap();
+        let soulbound = self.rng.gen_range(0, 100) < self.soulbound_percentage;
+
+        let state = self.creators.entry(creator).or_default();
+        let payload = if !state.collection_created {
+            state.collection_created = true;
+            create_collection(
+                collection_name(state.collection_index),
+                self.collection_size as u64,
+                false,
+            )
+        } else if let Some(token) = state.minted_tokens.pop() {
+            aptos_stdlib::object_transfer_call(token, receiver)
+        } else if state.num_minted < self.collection_size {
+            let collection = collection_name(state.collection_index);
+            let name = format!("Token {}", state.num_minted).into_bytes();
+            state.num_minted += 1;
+            if soulbound {
+                aptos_token_objects_stdlib::aptos_token_mint_soul_bound(
+                    collection,
+                    vec![],
+                    name,
+                    URI.to_vec(),
+                    vec![],
+                    vec![],
+                    vec![],
+                    receiver,
+                )
+            } else {
+                state
+                    .minted_tokens
+                    .push(minted_token_address(creator, sequence_number));
+                aptos_token_objects_stdlib::aptos_token_mint(
+                    collection,
+                    vec![],
+                    name,
+                    URI.to_vec(),
+                    vec![],
+                    vec![],
+                    vec![],
+                )
+            }
+        } else {
+            state.collection_index += 1;
+            state.num_minted = 0;
+            create_collection(
+                collection_name(state.collection_index),
+                self.collection_size as u64,
+                false,
+            )
+        };
+        account.sign_with_transaction_builder(self.txn_factory.payload(payload))
+    }
+}
+
+impl TransactionGenerator for TokenV2TransactionGenerator {
+    fn generate_transactions(
+        &mut self,
+        account: &mut LocalAccount,
+        num_to_create: usize,
+    ) -> Vec<SignedTransaction> {
+        (0..num_to_create)
+            .map(|_| self.generate_transaction(account))
+            .collect()
+    }
+}
+
+fn collection_name(collection_index: usize) -> Vec<u8> {
+    format!("Collection {}", collection_index).into_bytes()
+}
+
+fn create_collection(
+    name: Vec<u8>,
+    max_supply: u64,
+    tokens_burnable_by_creator: bool,
+) -> TransactionPayload {
+    aptos_token_objects_stdlib::aptos_token_create_collection(
+        b"Token objects benchmark".to_vec(),
+        max_supply,
+        name,
+        URI.to_vec(),
+        false,
+        false,
+        false,
+        false,
+        false,
+        false,
+        false,
+        tokens_burnable_by_creator,
+        false,
+        0,
+        1,
+    )
+}
+
+/// Mirrors the VM's `SessionId`, whose hash is the transaction hash AUIDs are derived from.
+#[derive(Serialize)]
+enum SessionId {
+    Txn {
+        sender: AccountAddress,
+        sequence_number: u64,
+        script_hash: Vec<u8>,
+    },
+}
+
+/// With AUIDs enabled the token is the first object `aptos_token::mint` creates, so its address
+/// only depends on the sender and the sequence number of the mint transaction. If the mint
+/// fails, the transfer that follows it fails too.
+fn minted_token_address(creator: AccountAddress, sequence_number: u64) -> AccountAddress {
+    let mut hasher = DefaultHasher::new(b"SessionId");
+    hasher.update(
+        &bcs::to_bytes(&SessionId::Txn {
+            sender: creator,
+            sequence_number,
+            script_hash: vec![],
+        })
+        .expect("SessionId must serialize"),
+    );
+    AuthenticationKey::from_preimage(&AuthenticationKeyPreimage::auid(
+        hasher.finish().to_vec(),
+        1,
+    ))
+    .derived_address()
+}
+
+/// Token and the account expected to own it once the transaction is committed, for the
+/// transactions of this workload fixing the final owner: soul bound mints and transfers.
+/// Transferable mints are followed by their transfer, so have no final owner.
+pub fn expected_token_owner(txn: &SignedTransaction) -> Option<(AccountAddress, AccountAddress)> {
+    let entry_function = match txn.payload() {
+        TransactionPayload::EntryFunction(entry_function) => entry_function,
+        _ => return None,
+    };
+    let module = entry_function.module();
+    let function = entry_function.function().as_str();
+    let args = entry_function.args();
+    if *module.address() == AccountAddress::ONE
+        && module.name().as_str() == "object"
+        && function == "transfer_call"
+    {
+        let token = bcs::from_bytes(args.first()?).ok()?;
+        let receiver = bcs::from_bytes(args.get(1)?).ok()?;
+        Some((token, receiver))
+    } else if module.name().as_str() == "aptos_token" && function == "mint_soul_bound" {
+        let receiver = bcs::from_bytes(args.last()?).ok()?;
+        Some((
+            minted_token_address(txn.sender(), txn.sequence_number()),
+            receiver,
+        ))
+    } else {
+        None
+    }
+}
+
+pub struct TokenV2TransactionGeneratorCreator {
+    txn_factory: TransactionFactory,
+    all_addresses: Arc<RwLock<Vec<AccountAddress>>>,
+    collection_size: usize,
+    soulbound_percentage: usize,
+    rng: StdRng,
+}
+
+impl TokenV2TransactionGeneratorCreator {
+    pub fn new(
+        txn_factory: TransactionFactory,
+        all_addresses: Arc<RwLock<Vec<AccountAddress>>>,
+        collection_size: usize,
+        soulbound_percentage: usize,
+        rng: StdRng,
+    ) -> Self {
+        assert!(collection_size > 0);
+        assert!(soulbound_percentage <= 100);
+        Self {
+            txn_factory,
+            all_addresses,
+            collection_size,
+            soulbound_percentage,
+            rng,
+        }
+    }
+}
+
+impl TransactionGeneratorCreator for TokenV2TransactionGeneratorCreator {
+    fn create_transaction_generator(&mut self) -> Box<dyn TransactionGenerator> {
+        Box::new(TokenV2TransactionGenerator::new(
+            new_rng(&mut self.rng),
+            self.txn_factory.clone(),
+            self.all_addresses.clone(),
+            self.collection_size,
+            self.soulbound_percentage,
+        ))
+    }
+}
+
+/// Progress of a sender minting and burning tokens of its own collection
+#[derive(Default)]
+struct MintAndBurnState {
+    collection_created: bool,
+    num_minted: usize,
+    /// Tokens minted and not burned yet, oldest first
+    live_tokens: VecDeque<AccountAddress>,
+}
+
+/// Token objects lifecycle: every sender creates a collection burnable by its creator, mints
+/// tokens into it and, once it holds `live_tokens_per_account` of them, burns the oldest one
+/// for each new mint. Burning deletes the token object (its resource group), so unlike the
+/// other workloads the write sets are heavy on slot deletions and storage fee refunds.
+/// The burn is sent by the minting account, so it is sequenced after the mint, and fails
+/// if the mint did.
+pub struct TokenV2MintAndBurnGenerator {
+    txn_factory: TransactionFactory,
+    live_tokens_per_account: usize,
+    aptos_token_module: ModuleId,
+    token_type: TypeTag,
+    creators: HashMap<AccountAddress, MintAndBurnState>,
+}
+
+impl TokenV2MintAndBurnGenerator {
+    pub fn new(txn_factory: TransactionFactory, live_tokens_per_account: usize) -> Self {
+        let token_objects_address = AccountAddress::from_hex_literal("0x4").unwrap();
+        Self {
+            txn_factory,
+            live_tokens_per_account,
+            aptos_token_module: ModuleId::new(
+                token_objects_address,
+                ident_str!("aptos_token").to_owned(),
+            ),
+            token_type: TypeTag::Struct(Box::new(StructTag {
+                address: token_objects_address,
+                module: ident_str!("token").to_owned(),
+                name: ident_str!("Token").to_owned(),
+                type_params: vec![],
+            })),
+            creators: HashMap::new(),
+        }
+    }
+
+    fn generate_transaction(&mut self, account: &mut LocalAccount) -> SignedTransaction {
+        let creator = account.address();
+        let sequence_number = account.sequence_number();
+
+        let state = self.creators.entry(creator).or_default();
+        let payload = if !state.collection_created {
+            state.collection_created = true;
+            create_collection(BURNABLE_COLLECTION_NAME.to_vec(), u64::MAX, true)
+        } else if state.live_tokens.len() >= self.live_tokens_per_account {
+            let token = state.live_tokens.pop_front().unwrap();
+            TransactionPayload::EntryFunction(EntryFunction::new(
+                self.aptos_token_module.clone(),
+                ident_str!("burn").to_owned(),
+                vec![self.token_type.clone()],
+                vec![bcs::to_bytes(&token).unwrap()],
+            ))
+        } else {
+            let name = format!("Token {}", state.num_minted).into_bytes();
+            state.num_minted += 1;
+            state
+                .live_tokens
+                .push_back(minted_token_address(creator, sequence_number));
+            aptos_token_objects_stdlib::aptos_token_mint(
+                BURNABLE_COLLECTION_NAME.to_vec(),
+                vec![],
+                name,
+                URI.to_vec(),
+                vec![],
+                vec![],
+                vec![],
+            )
+        };
+        account.sign_with_transaction_builder(self.txn_factory.payload(payload))
+    }
+}
+
+impl TransactionGenerator for TokenV2MintAndBurnGenerator {
+    fn generate_transactions(
+        &mut self,
+        account: &mut LocalAccount,
+        num_to_create: usize,
+    ) -> Vec<SignedTransaction> {
+        (0..num_to_create)
+            .map(|_| self.generate_transaction(account))
+            .collect()
+    }
+}
+
+pub struct TokenV2MintAndBurnGeneratorCreator {
+    txn_factory: TransactionFactory,
+    live_tokens_per_account: usize,
+}
+
+impl TokenV2MintAndBurnGeneratorCreator {
+    pub fn new(txn_factory: TransactionFactory, live_tokens_per_account: usize) -> Self {
+        assert!(live_tokens_per_account > 0);
+        Self {
+            txn_factory,
+            live_tokens_per_account,
+        }
+    }
+}
+
+impl TransactionGeneratorCreator for TokenV2MintAndBurnGeneratorCreator {
+    fn create_transaction_generator(&mut self) -> Box<dyn TransactionGenerator> {
+        Box::new(TokenV2MintAndBurnGenerator::new(
+            self.txn_factory.clone(),
+            self.live_tokens_per_account,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use aptos_sdk::types::chain_id::ChainId;
+    use rand::SeedableRng;
+
+    fn entry_function(txn: &SignedTransaction) -> &EntryFunction {
+        match txn.payload() {
+            TransactionPayload::EntryFunction(entry_function) => entry_function,
+            payload => panic!("Expected an entry function, got {:?}", payload),
+        }
+    }
+
+    #[test]
+    fn test_mint_and_burn() {
+        let mut account = LocalAccount::generate(&mut StdRng::seed_from_u64(0));
+        let mut generator =
+            TokenV2MintAndBurnGenerator::new(TransactionFactory::new(ChainId::test()), 2);
+        let txns = generator.generate_transactions(&mut account, 6);
+
+        let functions: Vec<_> = txns
+            .iter()
+            .map(|txn| entry_function(txn).function().as_str())
+            .collect();
+        assert_eq!(functions, vec![
+            "create_collection",
+            "mint",
+            "mint",
+            "burn",
+            "mint",
+            "burn"
+        ]);
+        // the oldest live tokens are burned, at the addresses of the objects the mints create
+        for (burn, mint) in [(3, 1), (5, 2)] {
+            let burned = entry_function(&txns[burn]);
+            assert_eq!(burned.ty_args(), &[generator.token_type.clone()]);
+            assert_eq!(burned.args(), &[bcs::to_bytes(&minted_token_address(
+                account.address(),
+                txns[mint].sequence_number()
+            ))
+            .unwrap()]);
+        }
+        assert_eq!(
+            generator.creators[&account.address()].live_tokens,
+            VecDeque::from(vec![minted_token_address(
+                account.address(),
+                txns[4].sequence_number()
+            )])
+        );
+    }
+
+    #[test]
+    fn test_burnable_collection() {
+        let collection = create_collection(BURNABLE_COLLECTION_NAME.to_vec(), u64::MAX, true);
+        let not_burnable = create_collection(BURNABLE_COLLECTION_NAME.to_vec(), u64::MAX, false);
+        match (collection, not_burnable) {
+            (
+                TransactionPayload::EntryFunction(collection),
+                TransactionPayload::EntryFunction(not_burnable),
+            ) => {
+                // only differing in the tokens_burnable_by_creator flag
+                let differing: Vec<_> = collection
+                    .args()
+                    .iter()
+                    .zip(not_burnable.args())
+                    .enumerate()
+                    .filter(|(_, (a, b))| a != b)
+                    .map(|(i, _)| i)
+                    .collect();
+                assert_eq!(differing, vec![11]);
+                assert_eq!(collection.args()[11], bcs::to_bytes(&true).unwrap());
+            },
+            _ => panic!("Expected entry functions"),
+        }
+    }
+}
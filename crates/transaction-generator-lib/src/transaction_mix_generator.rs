@@ -1,11 +1,17 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
-use crate::{TransactionGenerator, TransactionGeneratorCreator};
-use aptos_sdk::types::{transaction::SignedTransaction, LocalAccount};
-use rand::{rngs::StdRng, Rng, SeedableRng};
-use std::sync::{
-    atomic::{AtomicUsize, Ordering},
-    Arc,
+use crate::{new_rng, TransactionGenerator, TransactionGeneratorCreator};
+use aptos_sdk::{
+    move_types::account_address::AccountAddress,
+    types::{transaction::SignedTransaction, LocalAccount},
+};
+use rand::{rngs::StdRng, Rng};
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
 };
 
 pub struct PhasedTxnMixGenerator {
@@ -60,21 +66,31 @@ impl TransactionGenerator for PhasedTxnMixGenerator {
             picked, self.total_weight_per_phase[phase], phase,
         );
     }
+
+    fn resync_sequence_numbers(&mut self, stale: &HashMap<AccountAddress, (u64, u64)>) {
+        // transactions of previous phases might still be in flight
+        for (gen, _) in self.txn_mix_per_phase.iter_mut().flatten() {
+            gen.resync_sequence_numbers(stale);
+        }
+    }
 }
 
 pub struct PhasedTxnMixGeneratorCreator {
     txn_mix_per_phase_creators: Vec<Vec<(Box<dyn TransactionGeneratorCreator>, usize)>>,
     phase: Arc<AtomicUsize>,
+    rng: StdRng,
 }
 
 impl PhasedTxnMixGeneratorCreator {
     pub fn new(
         txn_mix_per_phase_creators: Vec<Vec<(Box<dyn TransactionGeneratorCreator>, usize)>>,
         phase: Arc<AtomicUsize>,
+        rng: StdRng,
     ) -> Self {
         Self {
             txn_mix_per_phase_creators,
             phase,
+            rng,
         }
     }
 }
@@ -91,7 +107,7 @@ impl TransactionGeneratorCreator for PhasedTxnMixGeneratorCreator {
         }
 
         Box::new(PhasedTxnMixGenerator::new(
-            StdRng::from_entropy(),
+            new_rng(&mut self.rng),
             txn_mix_per_phase,
             self.phase.clone(),
         ))
@@ -0,0 +1,625 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{new_rng, TransactionGenerator, TransactionGeneratorCreator};
+use anyhow::{bail, Result};
+use aptos_infallible::Mutex;
+use aptos_logger::{sample, sample::SampleRate, warn};
+use aptos_sdk::{
+    crypto::HashValue,
+    move_types::account_address::AccountAddress,
+    types::{
+        transaction::{SignedTransaction, TransactionPayload},
+        LocalAccount,
+    },
+};
+use async_trait::async_trait;
+use rand::{rngs::StdRng, Rng};
+use serde::Serialize;
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+/// Sampled transactions waiting to be verified, past which new ones are not sampled
+const MAX_PENDING_VERIFICATIONS: usize = 100_000;
+
+/// Effects of a committed transaction, as read back from the chain
+#[derive(Debug, Clone)]
+pub struct CommittedTransaction {
+    pub version: u64,
+    pub success: bool,
+    pub vm_status: String,
+    pub gas_used: u64,
+    /// Move type of each emitted event, e.g. `0x1::coin::DepositEvent`
+    pub event_types: Vec<String>,
+}
+
+/// Read access to committed transactions and the state they produced
+#[async_trait]
+pub trait TransactionOutputReader: Sync + Send {
+    /// None if the transaction is not committed
+    async fn get_committed_transaction(
+        &self,
+        hash: HashValue,
+    ) -> Result<Option<CommittedTransaction>>;
+
+    /// Value of a u64 field of a resource at the given version,
+    /// None if the resource doesn't exist
+    async fn get_resource_field_u64(
+        &self,
+        address: AccountAddress,
+        resource_type: &str,
+        field: &str,
+        version: u64,
+    ) -> Result<Option<u64>>;
+}
+
+/// Check of the expected on-chain effects of a committed transaction of a workload,
+/// run after commit on a sample of its transactions.
+#[async_trait]
+pub trait TransactionVerifier: Sync + Send {
+    /// Returns an error describing the mismatch if the effects are not the expected ones
+    async fn verify(
+        &self,
+        txn: &SignedTransaction,
+        committed: &CommittedTransaction,
+        reader: &dyn TransactionOutputReader,
+    ) -> Result<()>;
+}
+
+/// Only checks the transaction was executed successfully
+pub struct SuccessVerifier;
+
+#[async_trait]
+impl TransactionVerifier for SuccessVerifier {
+    async fn verify(
+        &self,
+        _txn: &SignedTransaction,
+        committed: &CommittedTransaction,
+        _reader: &dyn TransactionOutputReader,
+    ) -> Result<()> {
+        if !committed.success {
+            bail!("Failed with VM status {}", committed.vm_status);
+        }
+        Ok(())
+    }
+}
+
+/// Checks an event was emitted, of a type ending with `event_type_suffix`, e.g.
+/// `::coin::DepositEvent` (the address of custom modules differs across publishers)
+pub struct EventEmittedVerifier {
+    pub event_type_suffix: &'static str,
+}
+
+#[async_trait]
+impl TransactionVerifier for EventEmittedVerifier {
+    async fn verify(
+        &self,
+        txn: &SignedTransaction,
+        committed: &CommittedTransaction,
+        reader: &dyn TransactionOutputReader,
+    ) -> Result<()> {
+        SuccessVerifier.verify(txn, committed, reader).await?;
+        if !committed
+            .event_types
+            .iter()
+            .any(|event_type| event_type.ends_with(self.event_type_suffix))
+        {
+            bail!(
+                "No event of type *{} emitted, got {:?}",
+                self.event_type_suffix,
+                committed.event_types
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Checks a u64 field of a resource of the called module, stored under the module address,
+/// was incremented by the transaction, by reading it before and at its version
+pub struct ResourceIncrementedVerifier {
+    pub struct_name: &'static str,
+    pub field: &'static str,
+}
+
+#[async_trait]
+impl TransactionVerifier for ResourceIncrementedVerifier {
+    async fn verify(
+        &self,
+        txn: &SignedTransaction,
+        committed: &CommittedTransaction,
+        reader: &dyn TransactionOutputReader,
+    ) -> Result<()> {
+        SuccessVerifier.verify(txn, committed, reader).await?;
+        let module_id = match txn.payload() {
+            TransactionPayload::EntryFunction(entry_function) => entry_function.module(),
+            _ => bail!("Expected an entry function payload"),
+        };
+        let resource_type = format!(
+            "{}::{}::{}",
+            module_id.address().to_hex_literal(),
+            module_id.name(),
+            self.struct_name
+        );
+        let address = *module_id.address();
+        let before = reader
+            .get_resource_field_u64(address, &resource_type, self.field, committed.version - 1)
+            .await?
+            .unwrap_or(0);
+        let after = reader
+            .get_resource_field_u64(address, &resource_type, self.field, committed.version)
+            .await?;
+        match after {
+            Some(after) if after > before => Ok(()),
+            _ => bail!(
+                "{}.{} not incremented, {} before, {:?} after",
+                resource_type,
+                self.field,
+                before,
+                after
+            ),
+        }
+    }
+}
+
+/// Outcome of the verified transactions so far
+#[derive(Debug, Default, Clone, Copy)]
+pub struct VerificationResults {
+    pub passed: usize,
+    pub failed: usize,
+    /// Sampled transactions that didn't commit (e.g. expired), so weren't verified
+    pub not_committed: usize,
+    /// Failures to read the output, so weren't verified
+    pub read_errors: usize,
+}
+
+/// Gas used by the sampled committed transactions of a workload, in a phase
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct GasUsage {
+    pub phase: usize,
+    pub workload: String,
+    pub num_samples: usize,
+    pub min: u64,
+    pub median: u64,
+    pub max: u64,
+}
+
+impl GasUsage {
+    fn from_samples(phase: usize, workload: String, mut samples: Vec<u64>) -> Self {
+        samples.sort_unstable();
+        Self {
+            phase,
+            workload,
+            num_samples: samples.len(),
+            min: samples[0],
+            median: samples[samples.len() / 2],
+            max: samples[samples.len() - 1],
+        }
+    }
+}
+
+/// Sampled transaction waiting to be read back once committed
+struct PendingTransaction {
+    txn: SignedTransaction,
+    /// Phase the transaction was generated in, and name of its workload,
+    /// the gas used is reported per workload of each phase
+    phase: usize,
+    workload: Arc<String>,
+    /// None if the workload has no expected effects to verify, only its gas is reported
+    verifier: Option<Arc<dyn TransactionVerifier>>,
+}
+
+/// Transactions sampled for verification by the wrapper, with the verifier of their workload,
+/// verified once committed. The gas used by the committed ones is recorded per workload.
+pub struct TransactionVerificationQueue {
+    sample_percentage: usize,
+    pending: Mutex<Vec<PendingTransaction>>,
+    gas_used: Mutex<HashMap<(usize, Arc<String>), Vec<u64>>>,
+    /// Committed sampled transactions, kept for later checks (e.g. against an indexer)
+    committed: Option<Mutex<Vec<(SignedTransaction, CommittedTransaction)>>>,
+    passed: AtomicUsize,
+    failed: AtomicUsize,
+    not_committed: AtomicUsize,
+    read_errors: AtomicUsize,
+}
+
+impl TransactionVerificationQueue {
+    pub fn new(sample_percentage: usize) -> Self {
+        assert!(
+            sample_percentage <= 100,
+            "Verification percentage needs to be at most 100"
+        );
+        Self {
+            sample_percentage,
+            pending: Mutex::new(Vec::new()),
+            gas_used: Mutex::new(HashMap::new()),
+            committed: None,
+            passed: AtomicUsize::new(0),
+            failed: AtomicUsize::new(0),
+            not_committed: AtomicUsize::new(0),
+            read_errors: AtomicUsize::new(0),
+        }
+    }
+
+    /// Keeps the committed sampled transactions once read, see `committed_transactions`
+    pub fn keep_committed(mut self) -> Self {
+        self.committed = Some(Mutex::new(Vec::new()));
+        self
+    }
+
+    fn push(&self, pending_txn: PendingTransaction) {
+        let mut pending = self.pending.lock();
+        if pending.len() < MAX_PENDING_VERIFICATIONS {
+            pending.push(pending_txn);
+        }
+    }
+
+    /// Latest expiration of the pending transactions, after which all their outcomes are final
+    pub fn max_pending_expiration_secs(&self) -> Option<u64> {
+        self.pending
+            .lock()
+            .iter()
+            .map(|pending_txn| pending_txn.txn.expiration_timestamp_secs())
+            .max()
+    }
+
+    /// Verifies the pending transactions expired before `now_secs`, whose outcome is final,
+    /// or all of them if `now_secs` is None.
+    pub async fn verify_pending(
+        &self,
+        reader: &dyn TransactionOutputReader,
+        now_secs: Option<u64>,
+    ) {
+        let to_verify = {
+            let mut pending = self.pending.lock();
+            let (due, not_due): (Vec<_>, Vec<_>) = pending.drain(..).partition(|pending_txn| {
+                now_secs.map_or(true, |now_secs| {
+                    pending_txn.txn.expiration_timestamp_secs() < now_secs
+                })
+            });
+            *pending = not_due;
+            due
+        };
+        for PendingTransaction {
+            txn,
+            phase,
+            workload,
+            verifier,
+        } in to_verify
+        {
+            let hash = txn.clone().committed_hash();
+            let committed = match reader.get_committed_transaction(hash).await {
+                Ok(Some(committed)) => committed,
+                Ok(None) => {
+                    self.not_committed.fetch_add(1, Ordering::Relaxed);
+                    continue;
+                },
+                Err(e) => {
+                    self.read_errors.fetch_add(1, Ordering::Relaxed);
+                    sample!(
+                        SampleRate::Duration(Duration::from_secs(60)),
+                        warn!("Failed to read the output of txn {}: {:?}", hash, e)
+                    );
+                    continue;
+                },
+            };
+            self.gas_used
+                .lock()
+                .entry((phase, workload))
+                .or_default()
+                .push(committed.gas_used);
+            if let Some(kept) = &self.committed {
+                let mut kept = kept.lock();
+                if kept.len() < MAX_PENDING_VERIFICATIONS {
+                    kept.push((txn.clone(), committed.clone()));
+                }
+            }
+            let verifier = match verifier {
+                Some(verifier) => verifier,
+                None => continue,
+            };
+            match verifier.verify(&txn, &committed, reader).await {
+                Ok(()) => {
+                    self.passed.fetch_add(1, Ordering::Relaxed);
+                },
+                Err(e) => {
+                    self.failed.fetch_add(1, Ordering::Relaxed);
+                    sample!(
+                        SampleRate::Duration(Duration::from_secs(10)),
+                        warn!(
+                            "Verification of txn {} at version {} failed: {:?}",
+                            hash, committed.version, e
+                        )
+                    );
+                },
+            }
+        }
+    }
+
+    pub fn results(&self) -> VerificationResults {
+        VerificationResults {
+            passed: self.passed.load(Ordering::Relaxed),
+            failed: self.failed.load(Ordering::Relaxed),
+            not_committed: self.not_committed.load(Ordering::Relaxed),
+            read_errors: self.read_errors.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Committed sampled transactions read so far, empty unless `keep_committed` was set
+    pub fn committed_transactions(&self) -> Vec<(SignedTransaction, CommittedTransaction)> {
+        self.committed
+            .as_ref()
+            .map_or_else(Vec::new, |kept| kept.lock().clone())
+    }
+
+    /// Min, median and max gas used by the committed transactions read so far,
+    /// per workload of each phase, sorted by phase and workload name
+    pub fn gas_usage(&self) -> Vec<GasUsage> {
+        let mut gas_usage: Vec<_> = self
+            .gas_used
+            .lock()
+            .iter()
+            .filter(|(_, samples)| !samples.is_empty())
+            .map(|((phase, workload), samples)| {
+                GasUsage::from_samples(*phase, workload.to_string(), samples.clone())
+            })
+            .collect();
+        gas_usage.sort_by(|a, b| (a.phase, &a.workload).cmp(&(b.phase, &b.workload)));
+        gas_usage
+    }
+}
+
+/// Wrapper queueing `sample_percentage` of the transactions of the inner generator for
+/// verification, and gas reporting under the name of the workload and the current phase.
+/// Needs to be the outermost wrapper of the workload, as re-signing the transactions changes
+/// their hashes.
+pub struct VerificationWrapperGenerator {
+    rng: StdRng,
+    generator: Box<dyn TransactionGenerator>,
+    cur_phase: Arc<AtomicUsize>,
+    workload: Arc<String>,
+    verifier: Option<Arc<dyn TransactionVerifier>>,
+    queue: Arc<TransactionVerificationQueue>,
+}
+
+impl TransactionGenerator for VerificationWrapperGenerator {
+    fn generate_transactions(
+        &mut self,
+        account: &mut LocalAccount,
+        num_to_create: usize,
+    ) -> Vec<SignedTransaction> {
+        let txns = self.generator.generate_transactions(account, num_to_create);
+        for txn in &txns {
+            if self.rng.gen_range(0, 100) < self.queue.sample_percentage {
+                self.queue.push(PendingTransaction {
+                    txn: txn.clone(),
+                    phase: self.cur_phase.load(Ordering::Relaxed),
+                    workload: self.workload.clone(),
+                    verifier: self.verifier.clone(),
+                });
+            }
+        }
+        txns
+    }
+
+    fn resync_sequence_numbers(&mut self, stale: &HashMap<AccountAddress, (u64, u64)>) {
+        self.generator.resync_sequence_numbers(stale);
+    }
+}
+
+pub struct VerificationWrapperCreator {
+    creator: Box<dyn TransactionGeneratorCreator>,
+    cur_phase: Arc<AtomicUsize>,
+    workload: Arc<String>,
+    verifier: Option<Arc<dyn TransactionVerifier>>,
+    queue: Arc<TransactionVerificationQueue>,
+    rng: StdRng,
+}
+
+impl VerificationWrapperCreator {
+    pub fn new(
+        creator: Box<dyn TransactionGeneratorCreator>,
+        cur_phase: Arc<AtomicUsize>,
+        workload: String,
+        verifier: Option<Arc<dyn TransactionVerifier>>,
+        queue: Arc<TransactionVerificationQueue>,
+        rng: StdRng,
+    ) -> Self {
+        Self {
+            creator,
+            cur_phase,
+            workload: Arc::new(workload),
+            verifier,
+            queue,
+            rng,
+        }
+    }
+}
+
+impl TransactionGeneratorCreator for VerificationWrapperCreator {
+    fn create_transaction_generator(&mut self) -> Box<dyn TransactionGenerator> {
+        Box::new(VerificationWrapperGenerator {
+            rng: new_rng(&mut self.rng),
+            generator: self.creator.create_transaction_generator(),
+            cur_phase: self.cur_phase.clone(),
+            workload: self.workload.clone(),
+            verifier: self.verifier.clone(),
+            queue: self.queue.clone(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use aptos_sdk::{
+        move_types::{identifier::Identifier, language_storage::ModuleId},
+        transaction_builder::{aptos_stdlib, TransactionFactory},
+        types::{chain_id::ChainId, transaction::EntryFunction},
+    };
+    use rand::SeedableRng;
+
+    /// Outputs of the committed transactions, and the values of a counter at each version
+    #[derive(Default)]
+    struct MockReader {
+        committed: HashMap<HashValue, CommittedTransaction>,
+        counter: HashMap<u64, u64>,
+    }
+
+    #[async_trait]
+    impl TransactionOutputReader for MockReader {
+        async fn get_committed_transaction(
+            &self,
+            hash: HashValue,
+        ) -> Result<Option<CommittedTransaction>> {
+            Ok(self.committed.get(&hash).cloned())
+        }
+
+        async fn get_resource_field_u64(
+            &self,
+            _address: AccountAddress,
+            _resource_type: &str,
+            _field: &str,
+            version: u64,
+        ) -> Result<Option<u64>> {
+            Ok(self.counter.get(&version).copied())
+        }
+    }
+
+    fn committed(success: bool, event_types: &[&str]) -> CommittedTransaction {
+        CommittedTransaction {
+            version: 10,
+            success,
+            vm_status: if success {
+                "Executed successfully".to_string()
+            } else {
+                "Move abort".to_string()
+            },
+            gas_used: 5,
+            event_types: event_types.iter().map(|s| s.to_string()).collect(),
+        }
+    }
+
+    fn sign(payload: TransactionPayload, expiration_timestamp_secs: u64) -> SignedTransaction {
+        let mut account = LocalAccount::generate(&mut StdRng::seed_from_u64(0));
+        account.sign_with_transaction_builder(
+            TransactionFactory::new(ChainId::test())
+                .payload(payload)
+                .expiration_timestamp_secs(expiration_timestamp_secs),
+        )
+    }
+
+    fn increment_payload() -> TransactionPayload {
+        TransactionPayload::EntryFunction(EntryFunction::new(
+            ModuleId::new(
+                AccountAddress::from_hex_literal("0xcafe").unwrap(),
+                Identifier::new("counter").unwrap(),
+            ),
+            Identifier::new("increment").unwrap(),
+            vec![],
+            vec![],
+        ))
+    }
+
+    #[tokio::test]
+    async fn test_event_emitted_verifier() {
+        let reader = MockReader::default();
+        let txn = sign(aptos_stdlib::aptos_coin_transfer(AccountAddress::ONE, 1), 0);
+        let verifier = EventEmittedVerifier {
+            event_type_suffix: "::coin::DepositEvent",
+        };
+
+        let deposited = committed(true, &[
+            "0x1::coin::WithdrawEvent",
+            "0x1::coin::DepositEvent",
+        ]);
+        assert!(verifier.verify(&txn, &deposited, &reader).await.is_ok());
+        let withdrawn = committed(true, &["0x1::coin::WithdrawEvent"]);
+        assert!(verifier.verify(&txn, &withdrawn, &reader).await.is_err());
+        let aborted = committed(false, &["0x1::coin::DepositEvent"]);
+        assert!(verifier.verify(&txn, &aborted, &reader).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_resource_incremented_verifier() {
+        let txn = sign(increment_payload(), 0);
+        let verifier = ResourceIncrementedVerifier {
+            struct_name: "Counter",
+            field: "count",
+        };
+
+        let mut reader = MockReader::default();
+        reader.counter.insert(9, 1);
+        reader.counter.insert(10, 2);
+        assert!(verifier
+            .verify(&txn, &committed(true, &[]), &reader)
+            .await
+            .is_ok());
+        reader.counter.insert(10, 1);
+        assert!(verifier
+            .verify(&txn, &committed(true, &[]), &reader)
+            .await
+            .is_err());
+        // created by the transaction
+        reader.counter.remove(&9);
+        assert!(verifier
+            .verify(&txn, &committed(true, &[]), &reader)
+            .await
+            .is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_verify_pending() {
+        let queue = TransactionVerificationQueue::new(100);
+        let workload = Arc::new("counter".to_string());
+        let verifier: Arc<dyn TransactionVerifier> = Arc::new(SuccessVerifier);
+        let expired = sign(increment_payload(), 100);
+        let failed = sign(
+            aptos_stdlib::aptos_coin_transfer(AccountAddress::ONE, 1),
+            150,
+        );
+        let not_expired = sign(
+            aptos_stdlib::aptos_coin_transfer(AccountAddress::ONE, 2),
+            300,
+        );
+        for txn in [&expired, &failed, &not_expired] {
+            queue.push(PendingTransaction {
+                txn: txn.clone(),
+                phase: 0,
+                workload: workload.clone(),
+                verifier: Some(verifier.clone()),
+            });
+        }
+
+        let mut reader = MockReader::default();
+        reader
+            .committed
+            .insert(expired.clone().committed_hash(), committed(true, &[]));
+        reader
+            .committed
+            .insert(failed.clone().committed_hash(), committed(false, &[]));
+
+        queue.verify_pending(&reader, Some(200)).await;
+        let results = queue.results();
+        assert_eq!((results.passed, results.failed), (1, 1));
+        assert_eq!(results.not_committed, 0);
+        assert_eq!(queue.max_pending_expiration_secs(), Some(300));
+
+        queue.verify_pending(&reader, None).await;
+        assert_eq!(queue.results().not_committed, 1);
+        assert_eq!(queue.max_pending_expiration_secs(), None);
+        assert_eq!(queue.gas_usage(), vec![GasUsage {
+            phase: 0,
+            workload: "counter".to_string(),
+            num_samples: 2,
+            min: 5,
+            median: 5,
+            max: 5,
+        }]);
+    }
+}
@@ -0,0 +1,123 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{args::TransactionTypeArg, TransactionType};
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, fs, path::Path, time::Duration};
+
+/// Named workload mixes, switched between at scheduled times within a single run,
+/// so state built by one mix can be stressed by the next, e.g.:
+///
+/// ```yaml
+/// mixes:
+///   transfers:
+///     - transaction_type: CoinTransfer
+///   mint_heavy:
+///     - transaction_type: TokenV2AmbassadorMint
+///       weight: 3
+///     - transaction_type: CoinTransfer
+/// schedule:
+///   - mix: transfers
+///     duration_secs: 300
+///   - mix: mint_heavy
+///     duration_secs: 300
+/// ```
+///
+/// Each entry of the schedule is a separate phase of the run.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct WorkloadSchedule {
+    pub mixes: BTreeMap<String, Vec<WeightedTransactionType>>,
+    pub schedule: Vec<ScheduledMix>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct WeightedTransactionType {
+    pub transaction_type: TransactionTypeArg,
+    #[serde(default = "default_weight")]
+    pub weight: usize,
+}
+
+fn default_weight() -> usize {
+    1
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ScheduledMix {
+    pub mix: String,
+    pub duration_secs: u64,
+}
+
+impl WorkloadSchedule {
+    pub fn from_yaml_str(yaml: &str) -> Result<Self> {
+        let schedule: Self = serde_yaml::from_str(yaml)?;
+        schedule.validate()?;
+        Ok(schedule)
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let yaml = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read workload schedule {:?}", path))?;
+        Self::from_yaml_str(&yaml).with_context(|| format!("Invalid workload schedule {:?}", path))
+    }
+
+    fn validate(&self) -> Result<()> {
+        if self.schedule.is_empty() {
+            bail!("Empty schedule");
+        }
+        for (name, mix) in &self.mixes {
+            if mix.is_empty() || mix.iter().all(|entry| entry.weight == 0) {
+                bail!(
+                    "Mix {} has no transaction type with a non-zero weight",
+                    name
+                );
+            }
+        }
+        for entry in &self.schedule {
+            if !self.mixes.contains_key(&entry.mix) {
+                bail!("Unknown mix {} in schedule", entry.mix);
+            }
+            if entry.duration_secs == 0 {
+                bail!("Mix {} is scheduled for 0 secs", entry.mix);
+            }
+        }
+        Ok(())
+    }
+
+    pub fn transaction_mix_per_phase(
+        &self,
+        module_working_set_size: usize,
+        sender_use_account_pool: bool,
+    ) -> Vec<Vec<(TransactionType, usize)>> {
+        self.schedule
+            .iter()
+            .map(|entry| {
+                self.mixes[&entry.mix]
+                    .iter()
+                    .map(|weighted| {
+                        (
+                            weighted
+                                .transaction_type
+                                .materialize(module_working_set_size, sender_use_account_pool),
+                            weighted.weight,
+                        )
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    pub fn phase_durations(&self) -> Vec<Duration> {
+        self.schedule
+            .iter()
+            .map(|entry| Duration::from_secs(entry.duration_secs))
+            .collect()
+    }
+
+    pub fn phase_names(&self) -> Vec<&str> {
+        self.schedule
+            .iter()
+            .map(|entry| entry.mix.as_str())
+            .collect()
+    }
+}
@@ -6,7 +6,7 @@ pub mod worker;
 
 use anyhow::{Ok, Result};
 use aptos_indexer_grpc_server_framework::RunnableConfig;
-use aptos_indexer_grpc_utils::config::IndexerGrpcFileStoreConfig;
+use aptos_indexer_grpc_utils::config::{CompressionConfig, IndexerGrpcFileStoreConfig};
 use serde::{Deserialize, Serialize};
 use worker::Worker;
 
@@ -16,6 +16,8 @@ pub struct IndexerGrpcCacheWorkerConfig {
     pub fullnode_grpc_address: String,
     pub file_store_config: IndexerGrpcFileStoreConfig,
     pub redis_main_instance_address: String,
+    #[serde(default)]
+    pub compression_config: CompressionConfig,
 }
 
 #[async_trait::async_trait]
@@ -25,8 +27,9 @@ impl RunnableConfig for IndexerGrpcCacheWorkerConfig {
             self.fullnode_grpc_address.clone(),
             self.redis_main_instance_address.clone(),
             self.file_store_config.clone(),
+            self.compression_config.clone(),
         )
-        .await;
+        .await?;
         worker.run().await;
         Ok(())
     }
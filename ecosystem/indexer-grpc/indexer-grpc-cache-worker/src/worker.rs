@@ -7,7 +7,8 @@ use crate::metrics::{
 };
 use aptos_indexer_grpc_utils::{
     cache_operator::CacheOperator,
-    config::IndexerGrpcFileStoreConfig,
+    compression::TransactionCodec,
+    config::{CompressionConfig, IndexerGrpcFileStoreConfig},
     create_grpc_client,
     file_store_operator::{
         FileStoreMetadata, FileStoreOperator, GcsFileStoreOperator, LocalFileStoreOperator,
@@ -20,7 +21,6 @@ use aptos_protos::internal::fullnode::v1::{
     GetTransactionsFromNodeRequest, TransactionsFromNodeResponse,
 };
 use futures::{self, StreamExt};
-use prost::Message;
 use tracing::{error, info};
 
 type ChainID = u32;
@@ -33,6 +33,8 @@ pub struct Worker {
     fullnode_grpc_address: String,
     /// File store config
     file_store: IndexerGrpcFileStoreConfig,
+    /// Encodes the transactions pushed to the cache, compressed or not.
+    transaction_codec: TransactionCodec,
 }
 
 /// GRPC data status enum is to identify the data frame.
@@ -61,14 +63,16 @@ impl Worker {
         fullnode_grpc_address: String,
         redis_main_instance_address: String,
         file_store: IndexerGrpcFileStoreConfig,
-    ) -> Self {
+        compression_config: CompressionConfig,
+    ) -> anyhow::Result<Self> {
         let redis_client = redis::Client::open(format!("redis://{}", redis_main_instance_address))
             .expect("Create redis client failed.");
-        Self {
+        Ok(Self {
             redis_client,
             file_store,
             fullnode_grpc_address: format!("http://{}", fullnode_grpc_address),
-        }
+            transaction_codec: TransactionCodec::new(&compression_config)?,
+        })
     }
 
     /// The main loop of the worker is:
@@ -124,7 +128,13 @@ impl Worker {
                 .unwrap();
 
             // 3&4. Infinite streaming until error happens. Either stream ends or worker crashes.
-            process_streaming_response(conn, file_store_metadata, response.into_inner()).await;
+            process_streaming_response(
+                conn,
+                file_store_metadata,
+                &self.transaction_codec,
+                response.into_inner(),
+            )
+            .await;
         }
     }
 }
@@ -132,6 +142,7 @@ impl Worker {
 async fn process_transactions_from_node_response(
     response: TransactionsFromNodeResponse,
     cache_operator: &mut CacheOperator<redis::aio::ConnectionManager>,
+    transaction_codec: &TransactionCodec,
 ) -> anyhow::Result<GrpcDataStatus> {
     match response.response.unwrap() {
         Response::Status(status) => {
@@ -166,11 +177,10 @@ async fn process_transactions_from_node_response(
                         Some(ref timestamp) => timestamp.seconds as u64,
                         None => 0,
                     };
-                    let mut encoded_proto_data = vec![];
-                    tx.encode(&mut encoded_proto_data)
+                    let encoded_proto_data = transaction_codec
+                        .encode(&tx)
                         .expect("Encode transaction failed.");
-                    let base64_encoded_proto_data = base64::encode(encoded_proto_data);
-                    (tx.version, base64_encoded_proto_data, timestamp_in_seconds)
+                    (tx.version, encoded_proto_data, timestamp_in_seconds)
                 })
                 .collect::<Vec<(u64, String, u64)>>();
 
@@ -233,6 +243,7 @@ async fn setup_cache_with_init_signal(
 async fn process_streaming_response(
     conn: redis::aio::ConnectionManager,
     file_store_metadata: Option<FileStoreMetadata>,
+    transaction_codec: &TransactionCodec,
     mut resp_stream: impl futures_core::Stream<Item = Result<TransactionsFromNodeResponse, tonic::Status>>
         + std::marker::Unpin,
 ) {
@@ -273,7 +284,13 @@ async fn process_streaming_response(
             panic!("[Indexer Cache] Chain id mismatch happens during data streaming.");
         }
 
-        match process_transactions_from_node_response(received, &mut cache_operator).await {
+        match process_transactions_from_node_response(
+            received,
+            &mut cache_operator,
+            transaction_codec,
+        )
+        .await
+        {
             Ok(status) => match status {
                 GrpcDataStatus::ChunkDataOk {
                     start_version,
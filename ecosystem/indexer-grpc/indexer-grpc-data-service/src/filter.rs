@@ -0,0 +1,286 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_protos::{
+    indexer::v1::TransactionFilter as TransactionFilterRequest,
+    transaction::v1::{
+        multisig_transaction_payload, transaction::TxnData, transaction_payload, Event, Transaction,
+    },
+};
+use std::collections::HashSet;
+use tonic::Status;
+
+/// Transaction filter of a request, with the addresses normalized to the short form used in the
+/// transactions, e.g. `0x1`, so that it can be evaluated on every transaction cheaply.
+#[derive(Debug, Default)]
+pub struct TransactionFilter {
+    event_types: HashSet<String>,
+    module_addresses: HashSet<String>,
+    entry_functions: HashSet<String>,
+    accounts: HashSet<String>,
+}
+
+impl TransactionFilter {
+    /// Validates and normalizes the filter of a request. Returns `None` if the filter has no
+    /// criteria, i.e. all the transactions are streamed.
+    pub fn new(request: &TransactionFilterRequest) -> Result<Option<Self>, Status> {
+        let filter = Self {
+            event_types: request
+                .event_types
+                .iter()
+                .map(|event_type| normalize_qualified_name(event_type))
+                .collect::<Result<_, _>>()?,
+            module_addresses: request
+                .module_addresses
+                .iter()
+                .map(|address| normalize_address(address))
+                .collect::<Result<_, _>>()?,
+            entry_functions: request
+                .entry_functions
+                .iter()
+                .map(|function| normalize_qualified_name(function))
+                .collect::<Result<_, _>>()?,
+            accounts: request
+                .accounts
+                .iter()
+                .map(|address| normalize_address(address))
+                .collect::<Result<_, _>>()?,
+        };
+        if filter.event_types.is_empty()
+            && filter.module_addresses.is_empty()
+            && filter.entry_functions.is_empty()
+            && filter.accounts.is_empty()
+        {
+            Ok(None)
+        } else {
+            Ok(Some(filter))
+        }
+    }
+
+    /// Returns true if the transaction matches any of the criteria of the filter.
+    pub fn matches(&self, transaction: &Transaction) -> bool {
+        let (events, user_transaction) = match &transaction.txn_data {
+            Some(TxnData::BlockMetadata(txn)) => (txn.events.as_slice(), None),
+            Some(TxnData::Genesis(txn)) => (txn.events.as_slice(), None),
+            Some(TxnData::User(txn)) => (txn.events.as_slice(), Some(txn)),
+            Some(TxnData::StateCheckpoint(_)) | None => (&[][..], None),
+        };
+
+        if events.iter().any(|event| self.matches_event(event)) {
+            return true;
+        }
+
+        let request = match user_transaction.and_then(|txn| txn.request.as_ref()) {
+            Some(request) => request,
+            None => return false,
+        };
+        if self.accounts.contains(&request.sender) {
+            return true;
+        }
+        let entry_function = request
+            .payload
+            .as_ref()
+            .and_then(|payload| match &payload.payload {
+                Some(transaction_payload::Payload::EntryFunctionPayload(payload)) => Some(payload),
+                Some(transaction_payload::Payload::MultisigPayload(payload)) => payload
+                    .transaction_payload
+                    .as_ref()
+                    .and_then(|payload| match &payload.payload {
+                        Some(multisig_transaction_payload::Payload::EntryFunctionPayload(
+                            payload,
+                        )) => Some(payload),
+                        None => None,
+                    }),
+                _ => None,
+            });
+        match entry_function {
+            Some(payload) => {
+                self.entry_functions
+                    .contains(&payload.entry_function_id_str)
+                    || self
+                        .module_addresses
+                        .contains(address_of(&payload.entry_function_id_str))
+            },
+            None => false,
+        }
+    }
+
+    fn matches_event(&self, event: &Event) -> bool {
+        // Generic type parameters are ignored unless they are part of the filter.
+        let base_type = event
+            .type_str
+            .split_once('<')
+            .map(|(base_type, _)| base_type)
+            .unwrap_or(&event.type_str);
+        self.event_types.contains(&event.type_str)
+            || self.event_types.contains(base_type)
+            || self.module_addresses.contains(address_of(&event.type_str))
+            || event
+                .key
+                .as_ref()
+                .map(|key| self.accounts.contains(&key.account_address))
+                .unwrap_or(false)
+    }
+}
+
+/// Returns the address part of a qualified name, e.g. `0x1` for `0x1::coin::transfer`.
+fn address_of(qualified_name: &str) -> &str {
+    qualified_name
+        .split_once("::")
+        .map(|(address, _)| address)
+        .unwrap_or(qualified_name)
+}
+
+/// Normalizes an address to the short form, e.g. `0x01` to `0x1`.
+fn normalize_address(address: &str) -> Result<String, Status> {
+    let hex = address.strip_prefix("0x").unwrap_or(address);
+    if hex.is_empty() || hex.len() > 64 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(Status::invalid_argument(format!(
+            "Invalid address in transaction filter: {}",
+            address
+        )));
+    }
+    let trimmed = hex.trim_start_matches('0');
+    Ok(format!("0x{}", if trimmed.is_empty() { "0" } else { trimmed }).to_lowercase())
+}
+
+/// Normalizes the address of a qualified name, e.g. `0x01::coin::transfer` to `0x1::coin::transfer`.
+fn normalize_qualified_name(qualified_name: &str) -> Result<String, Status> {
+    match qualified_name.split_once("::") {
+        Some((address, rest)) if rest.contains("::") => {
+            Ok(format!("{}::{}", normalize_address(address)?, rest))
+        },
+        _ => Err(Status::invalid_argument(format!(
+            "Invalid name in transaction filter, expected `<address>::<module>::<name>`: {}",
+            qualified_name
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aptos_protos::transaction::v1::{
+        EntryFunctionPayload, EventKey, TransactionPayload, UserTransaction, UserTransactionRequest,
+    };
+
+    fn user_transaction(sender: &str, function: &str, event_types: &[&str]) -> Transaction {
+        Transaction {
+            txn_data: Some(TxnData::User(UserTransaction {
+                request: Some(UserTransactionRequest {
+                    sender: sender.to_string(),
+                    payload: Some(TransactionPayload {
+                        payload: Some(transaction_payload::Payload::EntryFunctionPayload(
+                            EntryFunctionPayload {
+                                entry_function_id_str: function.to_string(),
+                                ..EntryFunctionPayload::default()
+                            },
+                        )),
+                        ..TransactionPayload::default()
+                    }),
+                    ..UserTransactionRequest::default()
+                }),
+                events: event_types
+                    .iter()
+                    .map(|event_type| Event {
+                        key: Some(EventKey {
+                            creation_number: 0,
+                            account_address: "0xcafe".to_string(),
+                        }),
+                        type_str: event_type.to_string(),
+                        ..Event::default()
+                    })
+                    .collect(),
+            })),
+            ..Transaction::default()
+        }
+    }
+
+    fn filter(request: TransactionFilterRequest) -> TransactionFilter {
+        TransactionFilter::new(&request).unwrap().unwrap()
+    }
+
+    #[test]
+    fn test_empty_filter() {
+        assert!(TransactionFilter::new(&TransactionFilterRequest::default())
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn test_invalid_filter() {
+        assert!(TransactionFilter::new(&TransactionFilterRequest {
+            accounts: vec!["0xzz".to_string()],
+            ..TransactionFilterRequest::default()
+        })
+        .is_err());
+        assert!(TransactionFilter::new(&TransactionFilterRequest {
+            entry_functions: vec!["0x1::transfer".to_string()],
+            ..TransactionFilterRequest::default()
+        })
+        .is_err());
+    }
+
+    #[test]
+    fn test_filter_matches() {
+        let transaction = user_transaction("0xbeef", "0x3::token::direct_transfer_script", &[
+            "0x3::token::DepositEvent",
+            "0x1::coin::WithdrawEvent<0x1::aptos_coin::AptosCoin>",
+        ]);
+
+        for matching in [
+            TransactionFilterRequest {
+                event_types: vec!["0x03::token::DepositEvent".to_string()],
+                ..TransactionFilterRequest::default()
+            },
+            TransactionFilterRequest {
+                event_types: vec!["0x1::coin::WithdrawEvent".to_string()],
+                ..TransactionFilterRequest::default()
+            },
+            TransactionFilterRequest {
+                module_addresses: vec![
+                    "0x0000000000000000000000000000000000000000000000000000000000000003"
+                        .to_string(),
+                ],
+                ..TransactionFilterRequest::default()
+            },
+            TransactionFilterRequest {
+                entry_functions: vec!["0x3::token::direct_transfer_script".to_string()],
+                ..TransactionFilterRequest::default()
+            },
+            TransactionFilterRequest {
+                accounts: vec!["0xBEEF".to_string()],
+                ..TransactionFilterRequest::default()
+            },
+            TransactionFilterRequest {
+                accounts: vec!["0xcafe".to_string()],
+                ..TransactionFilterRequest::default()
+            },
+        ] {
+            assert!(
+                filter(matching.clone()).matches(&transaction),
+                "{:?}",
+                matching
+            );
+        }
+
+        for not_matching in [
+            TransactionFilterRequest {
+                event_types: vec!["0x3::token::WithdrawEvent".to_string()],
+                ..TransactionFilterRequest::default()
+            },
+            TransactionFilterRequest {
+                module_addresses: vec!["0x4".to_string()],
+                entry_functions: vec!["0x1::coin::transfer".to_string()],
+                accounts: vec!["0x1".to_string()],
+                ..TransactionFilterRequest::default()
+            },
+        ] {
+            assert!(
+                !filter(not_matching.clone()).matches(&transaction),
+                "{:?}",
+                not_matching
+            );
+        }
+    }
+}
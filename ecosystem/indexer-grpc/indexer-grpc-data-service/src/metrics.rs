@@ -27,6 +27,16 @@ pub static PROCESSED_VERSIONS_COUNT: Lazy<IntCounterVec> = Lazy::new(|| {
     .unwrap()
 });
 
+/// Number of transactions that served by data service for backfill requests.
+pub static BACKFILL_PROCESSED_VERSIONS_COUNT: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "indexer_grpc_data_service_backfill_processed_versions",
+        "Number of transactions that have been processed by data service for backfill requests",
+        &["request_token", "processor_name"],
+    )
+    .unwrap()
+});
+
 /// Number of errors that data service has encountered.
 pub static ERROR_COUNT: Lazy<IntCounterVec> = Lazy::new(|| {
     register_int_counter_vec!(
@@ -1,16 +1,20 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::metrics::{
-    CONNECTION_COUNT, ERROR_COUNT, LATEST_PROCESSED_VERSION, PROCESSED_BATCH_SIZE,
-    PROCESSED_LATENCY_IN_SECS, PROCESSED_LATENCY_IN_SECS_ALL, PROCESSED_VERSIONS_COUNT,
-    SHORT_CONNECTION_COUNT,
+use crate::{
+    filter::TransactionFilter,
+    metrics::{
+        BACKFILL_PROCESSED_VERSIONS_COUNT, CONNECTION_COUNT, ERROR_COUNT, LATEST_PROCESSED_VERSION,
+        PROCESSED_BATCH_SIZE, PROCESSED_LATENCY_IN_SECS, PROCESSED_LATENCY_IN_SECS_ALL,
+        PROCESSED_VERSIONS_COUNT, SHORT_CONNECTION_COUNT,
+    },
 };
 use aptos_indexer_grpc_utils::{
     build_protobuf_encoded_transaction_wrappers,
     cache_operator::{CacheBatchGetStatus, CacheOperator},
     chunk_transactions,
-    config::IndexerGrpcFileStoreConfig,
+    compression::TransactionCodec,
+    config::{CompressionConfig, IndexerGrpcFileStoreConfig},
     constants::{
         BLOB_STORAGE_SIZE, GRPC_AUTH_TOKEN_HEADER, GRPC_REQUEST_NAME_HEADER, MESSAGE_SIZE_LIMIT,
     },
@@ -19,11 +23,13 @@ use aptos_indexer_grpc_utils::{
 };
 use aptos_moving_average::MovingAverage;
 use aptos_protos::{
-    indexer::v1::{raw_data_server::RawData, GetTransactionsRequest, TransactionsResponse},
+    indexer::v1::{
+        raw_data_server::RawData, GetTransactionsInRangeRequest, GetTransactionsRequest,
+        TransactionsResponse,
+    },
     transaction::v1::Transaction,
 };
 use futures::Stream;
-use prost::Message;
 use serde::{Deserialize, Serialize};
 use std::{pin::Pin, sync::Arc, time::Duration};
 use tokio::sync::mpsc::{channel, error::SendTimeoutError};
@@ -59,19 +65,47 @@ const RESPONSE_CHANNEL_SEND_TIMEOUT: Duration = Duration::from_secs(120);
 
 const SHORT_CONNECTION_DURATION_IN_SECS: u64 = 10;
 
+// Backfill requests only read from the file store and each response channel item holds up to a file,
+// so they get a smaller buffer than the live stream.
+const MAX_BACKFILL_RESPONSE_CHANNEL_SIZE: usize = 10;
+// A backfill request fails after this many failed attempts at fetching a file from the file store.
+const BACKFILL_FILE_FETCH_ATTEMPTS: usize = 3;
+
 pub struct RawDataServerWrapper {
     pub redis_client: Arc<redis::Client>,
     pub file_store_config: IndexerGrpcFileStoreConfig,
+    pub transaction_codec: Arc<TransactionCodec>,
 }
 
 impl RawDataServerWrapper {
-    pub fn new(redis_address: String, file_store_config: IndexerGrpcFileStoreConfig) -> Self {
-        Self {
+    pub fn new(
+        redis_address: String,
+        file_store_config: IndexerGrpcFileStoreConfig,
+        compression_config: &CompressionConfig,
+    ) -> anyhow::Result<Self> {
+        Ok(Self {
             redis_client: Arc::new(
                 redis::Client::open(format!("redis://{}", redis_address))
                     .expect("Create redis client failed."),
             ),
             file_store_config,
+            transaction_codec: Arc::new(TransactionCodec::new(compression_config)?),
+        })
+    }
+
+    fn file_store_operator(&self) -> Box<dyn FileStoreOperator> {
+        match &self.file_store_config {
+            IndexerGrpcFileStoreConfig::GcsFileStore(gcs_file_store) => {
+                Box::new(GcsFileStoreOperator::new(
+                    gcs_file_store.gcs_file_store_bucket_name.clone(),
+                    gcs_file_store
+                        .gcs_file_store_service_account_key_path
+                        .clone(),
+                ))
+            },
+            IndexerGrpcFileStoreConfig::LocalFileStore(local_file_store) => Box::new(
+                LocalFileStoreOperator::new(local_file_store.local_file_store_path.clone()),
+            ),
         }
     }
 }
@@ -89,6 +123,7 @@ enum TransactionsDataStatus {
 /// RawDataServerWrapper handles the get transactions requests from cache and file store.
 #[tonic::async_trait]
 impl RawData for RawDataServerWrapper {
+    type GetTransactionsInRangeStream = ResponseStream;
     type GetTransactionsStream = ResponseStream;
 
     /// GetTransactionsStream is a streaming GRPC endpoint:
@@ -99,6 +134,7 @@ impl RawData for RawDataServerWrapper {
     ///    1.4  If error happens, retry after a short sleep.
     /// 2. Push data into channel to stream to the client.
     ///    2.1. If the channel is full, do not fetch and retry after a short sleep.
+    ///    2.2. If the request has a filter, only the matching transactions are pushed.
     async fn get_transactions(
         &self,
         req: Request<GetTransactionsRequest>,
@@ -121,21 +157,13 @@ impl RawData for RawDataServerWrapper {
                 return Result::Err(Status::aborted("Starting version is not set"));
             },
         };
-
-        let file_store_operator: Box<dyn FileStoreOperator> = match &self.file_store_config {
-            IndexerGrpcFileStoreConfig::GcsFileStore(gcs_file_store) => {
-                Box::new(GcsFileStoreOperator::new(
-                    gcs_file_store.gcs_file_store_bucket_name.clone(),
-                    gcs_file_store
-                        .gcs_file_store_service_account_key_path
-                        .clone(),
-                ))
-            },
-            IndexerGrpcFileStoreConfig::LocalFileStore(local_file_store) => Box::new(
-                LocalFileStoreOperator::new(local_file_store.local_file_store_path.clone()),
-            ),
+        let filter = match &request.filter {
+            Some(filter) => TransactionFilter::new(filter)?,
+            None => None,
         };
 
+        let file_store_operator = self.file_store_operator();
+
         // Adds tracing context for the request.
         let serving_span = tracing::span!(
             tracing::Level::INFO,
@@ -148,6 +176,7 @@ impl RawData for RawDataServerWrapper {
         );
 
         let redis_client = self.redis_client.clone();
+        let transaction_codec = self.transaction_codec.clone();
         tokio::spawn(
             async move {
                 let mut connection_start_time = Some(std::time::Instant::now());
@@ -254,16 +283,18 @@ impl RawData for RawDataServerWrapper {
                     // 2. Push the data to the response channel, i.e. stream the data to the client.
                     let current_batch_size = transaction_data.as_slice().len();
                     let end_of_batch_version = transaction_data.as_slice().last().unwrap().1;
-                    let resp_items =
-                        get_transactions_responses_builder(transaction_data, chain_id as u32);
+                    let resp_items = get_transactions_responses_builder(
+                        transaction_data,
+                        chain_id as u32,
+                        filter.as_ref(),
+                        &transaction_codec,
+                    );
+                    // With a filter, the whole batch may be filtered out, in which case only its last
+                    // version is sent, so that the client knows how far the stream got.
                     let data_latency_in_secs = resp_items
                         .last()
-                        .unwrap()
-                        .transactions
-                        .last()
-                        .unwrap()
-                        .timestamp
-                        .as_ref()
+                        .and_then(|resp_item| resp_item.transactions.last())
+                        .and_then(|transaction| transaction.timestamp.as_ref())
                         .map(time_diff_since_pb_timestamp_in_secs);
 
                     match channel_send_multiple_with_timeout(resp_items, tx.clone()).await {
@@ -339,29 +370,266 @@ impl RawData for RawDataServerWrapper {
             Box::pin(output_stream) as Self::GetTransactionsStream
         ))
     }
+
+    /// GetTransactionsInRange is a streaming GRPC endpoint serving a closed version range from the
+    /// file store only, independently of the cache, e.g. to backfill the history for a new processor:
+    /// 1. Checks that the whole range is in the file store.
+    /// 2. Fetches the files of the range one at a time.
+    ///    2.1. If error happens, retry a few times after a short sleep, then end the stream with an error.
+    ///    2.2. If transactions of the range are missing, end the stream with an error.
+    /// 3. Push data into its own channel to stream to the client.
+    ///    3.1. If the channel is full, do not fetch the next file until it is not full; if it stays
+    ///         full for too long, end the stream with an error.
+    ///    3.2. If the request has a filter, only the matching transactions are pushed.
+    async fn get_transactions_in_range(
+        &self,
+        req: Request<GetTransactionsInRangeRequest>,
+    ) -> Result<Response<Self::GetTransactionsInRangeStream>, Status> {
+        // Get request identity. The request is already authenticated by the interceptor.
+        let request_metadata = match get_request_metadata(&req) {
+            Ok(request_metadata) => request_metadata,
+            _ => return Result::Err(Status::aborted("Invalid request token")),
+        };
+        let request = req.into_inner();
+
+        let (starting_version, ending_version) =
+            match (request.starting_version, request.ending_version) {
+                (Some(starting_version), Some(ending_version))
+                    if starting_version <= ending_version =>
+                {
+                    (starting_version, ending_version)
+                },
+                (Some(_), Some(_)) => {
+                    return Result::Err(Status::invalid_argument(
+                        "Starting version is after the ending version",
+                    ));
+                },
+                _ => {
+                    return Result::Err(Status::invalid_argument(
+                        "Starting version and ending version must be set",
+                    ));
+                },
+            };
+        let filter = match &request.filter {
+            Some(filter) => TransactionFilter::new(filter)?,
+            None => None,
+        };
+
+        let file_store_operator = self.file_store_operator();
+        let transaction_codec = self.transaction_codec.clone();
+        let file_store_metadata = match file_store_operator.get_file_store_metadata().await {
+            Some(file_store_metadata) => file_store_metadata,
+            None => {
+                return Result::Err(Status::unavailable(
+                    "[Indexer Data] File store is not ready; please retry.",
+                ));
+            },
+        };
+        // The file store metadata version is the first version not uploaded yet.
+        if ending_version >= file_store_metadata.version {
+            return Result::Err(Status::out_of_range(format!(
+                "Transactions from version {} are not in the file store yet; use GetTransactions for the latest transactions.",
+                file_store_metadata.version
+            )));
+        }
+        let chain_id = file_store_metadata.chain_id;
+
+        // Response channel to stream the data to the client.
+        let (tx, rx) = channel(MAX_BACKFILL_RESPONSE_CHANNEL_SIZE);
+
+        // Adds tracing context for the request.
+        let serving_span = tracing::span!(
+            tracing::Level::INFO,
+            "Data Backfilling",
+            request_id = request_metadata.request_id.as_str(),
+            request_remote_addr = request_metadata.request_remote_addr.as_str(),
+            request_token = request_metadata.request_token.as_str(),
+            request_name = request_metadata.request_name.as_str(),
+            request_source = request_metadata.request_source.as_str(),
+        );
+
+        tokio::spawn(
+            stream_transactions_in_range(
+                file_store_operator,
+                transaction_codec,
+                chain_id,
+                starting_version,
+                ending_version,
+                filter,
+                request_metadata,
+                tx,
+            )
+            .instrument(serving_span),
+        );
+
+        let output_stream = ReceiverStream::new(rx);
+        Ok(Response::new(
+            Box::pin(output_stream) as Self::GetTransactionsInRangeStream
+        ))
+    }
+}
+
+/// Streams the transactions of a closed version range from the file store, for a backfill request.
+/// If the range can't be served completely, the stream ends with an error status, so that clients can
+/// tell a partial range from a complete one.
+#[allow(clippy::too_many_arguments)]
+async fn stream_transactions_in_range(
+    file_store_operator: Box<dyn FileStoreOperator>,
+    transaction_codec: Arc<TransactionCodec>,
+    chain_id: u64,
+    starting_version: u64,
+    ending_version: u64,
+    filter: Option<TransactionFilter>,
+    request_metadata: RequestMetadata,
+    tx: tokio::sync::mpsc::Sender<Result<TransactionsResponse, Status>>,
+) {
+    info!(
+        chain_id = chain_id,
+        starting_version = starting_version,
+        ending_version = ending_version,
+        "[Indexer Data] New backfill request received."
+    );
+
+    let mut current_version = starting_version;
+    while current_version <= ending_version {
+        // 1. Fetch the file holding the current version.
+        let file_starting_version = current_version - current_version % BLOB_STORAGE_SIZE as u64;
+        let transactions =
+            match backfill_data_fetch(file_starting_version, file_store_operator.as_ref()).await {
+                Ok(transactions) => transactions,
+                Err(e) => {
+                    ERROR_COUNT
+                        .with_label_values(&["backfill_data_fetch_failed"])
+                        .inc();
+                    error!(
+                        chain_id = chain_id,
+                        current_version = current_version,
+                        "[Indexer Data] Failed to fetch data from file store. {:?}",
+                        e
+                    );
+                    // Connection will be dropped anyway, so we ignore the error here.
+                    let _result = tx
+                        .send_timeout(
+                            Err(Status::unavailable(
+                                "[Indexer Data] Cannot fetch data from file store; please retry.",
+                            )),
+                            RESPONSE_CHANNEL_SEND_TIMEOUT,
+                        )
+                        .await;
+                    return;
+                },
+            };
+        let transaction_data: Vec<EncodedTransactionWithVersion> =
+            build_protobuf_encoded_transaction_wrappers(transactions, file_starting_version)
+                .into_iter()
+                .filter(|(_, version)| *version >= current_version && *version <= ending_version)
+                .collect();
+        if transaction_data.is_empty() {
+            data_gap_handling(current_version);
+            // End the data stream, telling the client that the range is incomplete.
+            let _result = tx
+                .send_timeout(
+                    Err(Status::internal(format!(
+                        "[Indexer Data] Transactions from version {} are missing from the file store; the range is incomplete.",
+                        current_version
+                    ))),
+                    RESPONSE_CHANNEL_SEND_TIMEOUT,
+                )
+                .await;
+            return;
+        }
+
+        // 2. Push the data to the response channel, i.e. stream the data to the client.
+        let current_batch_size = transaction_data.len();
+        let end_of_batch_version = transaction_data.last().unwrap().1;
+        let resp_items = get_transactions_responses_builder(
+            transaction_data,
+            chain_id as u32,
+            filter.as_ref(),
+            &transaction_codec,
+        );
+        match channel_send_multiple_with_timeout(resp_items, tx.clone()).await {
+            Ok(_) => {
+                BACKFILL_PROCESSED_VERSIONS_COUNT
+                    .with_label_values(&[
+                        request_metadata.request_token.as_str(),
+                        request_metadata.request_name.as_str(),
+                    ])
+                    .inc_by(current_batch_size as u64);
+            },
+            Err(SendTimeoutError::Timeout(_)) => {
+                warn!("[Indexer Data] Receiver is full; exiting.");
+                // The client only gets this once it catches up, unless the connection is dropped.
+                let _result = tx
+                    .send_timeout(
+                        Err(Status::aborted(format!(
+                            "[Indexer Data] Receiver is too slow; the range is incomplete from version {}.",
+                            current_version
+                        ))),
+                        RESPONSE_CHANNEL_SEND_TIMEOUT,
+                    )
+                    .await;
+                return;
+            },
+            Err(SendTimeoutError::Closed(_)) => {
+                warn!("[Indexer Data] Receiver is closed; exiting.");
+                break;
+            },
+        }
+        // 3. Update the current version.
+        current_version = end_of_batch_version + 1;
+        info!(
+            current_version = current_version,
+            end_version = end_of_batch_version,
+            batch_size = current_batch_size,
+            "[Indexer Data] Sending backfill batch."
+        );
+    }
+    info!("[Indexer Data] Backfill finished.");
 }
 
 /// Builds the response for the get transactions request. Partial batch is ok, i.e., a batch with transactions < 1000.
+/// If a filter is given, only the transactions matching it are included, and the responses carry the
+/// last version scanned; if none matches, a single response without transactions carries it.
 fn get_transactions_responses_builder(
     data: Vec<EncodedTransactionWithVersion>,
     chain_id: u32,
+    filter: Option<&TransactionFilter>,
+    transaction_codec: &TransactionCodec,
 ) -> Vec<TransactionsResponse> {
+    let end_of_batch_version = data.last().map(|(_, version)| *version);
     let transactions: Vec<Transaction> = data
         .into_iter()
         .map(|(encoded, _)| {
-            let decoded_transaction = base64::decode(encoded).unwrap();
-            let transaction = Transaction::decode(&*decoded_transaction);
-            transaction.unwrap()
+            transaction_codec
+                .decode(&encoded)
+                .expect("[Indexer Data] Failed to decode transaction.")
         })
+        .filter(|transaction| filter.map_or(true, |filter| filter.matches(transaction)))
         .collect();
     let chunks = chunk_transactions(transactions, MESSAGE_SIZE_LIMIT);
-    chunks
+    let mut responses = chunks
         .into_iter()
         .map(|chunk| TransactionsResponse {
             chain_id: Some(chain_id as u64),
+            last_scanned_version: filter
+                .and(chunk.last())
+                .map(|transaction| transaction.version),
             transactions: chunk,
         })
-        .collect::<Vec<TransactionsResponse>>()
+        .collect::<Vec<TransactionsResponse>>();
+    if filter.is_some() {
+        // The transactions after the last match were scanned too.
+        match responses.last_mut() {
+            Some(response) => response.last_scanned_version = end_of_batch_version,
+            None => responses.push(TransactionsResponse {
+                chain_id: Some(chain_id as u64),
+                last_scanned_version: end_of_batch_version,
+                transactions: vec![],
+            }),
+        }
+    }
+    responses
 }
 
 /// Fetches data from cache or the file store. It returns the data if it is ready in the cache or file store.
@@ -402,6 +670,36 @@ async fn data_fetch(
     }
 }
 
+/// Fetches a file from the file store for a backfill request, retrying after a short sleep on errors.
+async fn backfill_data_fetch(
+    file_starting_version: u64,
+    file_store_operator: &dyn FileStoreOperator,
+) -> anyhow::Result<Vec<String>> {
+    let mut attempt = 1;
+    loop {
+        match file_store_operator
+            .get_transactions(file_starting_version)
+            .await
+        {
+            Ok(transactions) => return Ok(transactions),
+            Err(e) if attempt < BACKFILL_FILE_FETCH_ATTEMPTS => {
+                warn!(
+                    file_starting_version = file_starting_version,
+                    attempt = attempt,
+                    "[Indexer Data] Failed to fetch data from file store; retrying. {:?}",
+                    e
+                );
+                tokio::time::sleep(Duration::from_millis(
+                    TRANSIENT_DATA_ERROR_RETRY_SLEEP_DURATION_MS,
+                ))
+                .await;
+                attempt += 1;
+            },
+            Err(e) => return Err(e),
+        }
+    }
+}
+
 /// Handles the case when the data is not ready in the cache, i.e., beyond the current head.
 async fn ahead_of_cache_data_handling() {
     // TODO: add exponential backoff.
@@ -436,7 +734,7 @@ async fn data_fetch_error_handling(err: anyhow::Error, current_version: u64, cha
 }
 
 /// Gets the request metadata. Useful for logging.
-fn get_request_metadata(req: &Request<GetTransactionsRequest>) -> tonic::Result<RequestMetadata> {
+fn get_request_metadata<T>(req: &Request<T>) -> tonic::Result<RequestMetadata> {
     // Request id.
     let request_id = Uuid::new_v4().to_string();
 
@@ -491,3 +789,256 @@ async fn channel_send_multiple_with_timeout(
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aptos_indexer_grpc_utils::file_store_operator::{
+        FileStoreMetadata, TransactionsFile, VerificationMetadata,
+    };
+    use aptos_protos::{
+        indexer::v1::TransactionFilter as TransactionFilterRequest,
+        transaction::v1::{transaction::TxnData, UserTransaction, UserTransactionRequest},
+    };
+    use std::collections::HashMap;
+
+    fn encoded_transactions(
+        codec: &TransactionCodec,
+        senders: &[(u64, &str)],
+    ) -> Vec<EncodedTransactionWithVersion> {
+        senders
+            .iter()
+            .map(|(version, sender)| {
+                let transaction = Transaction {
+                    version: *version,
+                    txn_data: Some(TxnData::User(UserTransaction {
+                        request: Some(UserTransactionRequest {
+                            sender: sender.to_string(),
+                            ..UserTransactionRequest::default()
+                        }),
+                        ..UserTransaction::default()
+                    })),
+                    ..Transaction::default()
+                };
+                (codec.encode(&transaction).unwrap(), *version)
+            })
+            .collect()
+    }
+
+    fn filter() -> TransactionFilter {
+        TransactionFilter::new(&TransactionFilterRequest {
+            accounts: vec!["0xbeef".to_string()],
+            ..TransactionFilterRequest::default()
+        })
+        .unwrap()
+        .unwrap()
+    }
+
+    #[test]
+    fn test_responses_without_filter() {
+        let codec = TransactionCodec::with_dictionary(false, None);
+        let data = encoded_transactions(&codec, &[(10, "0xbeef"), (11, "0xcafe")]);
+
+        let responses = get_transactions_responses_builder(data, 1, None, &codec);
+        assert_eq!(responses.len(), 1);
+        assert_eq!(responses[0].chain_id, Some(1));
+        assert_eq!(responses[0].last_scanned_version, None);
+        let versions: Vec<_> = responses[0]
+            .transactions
+            .iter()
+            .map(|t| t.version)
+            .collect();
+        assert_eq!(versions, vec![10, 11]);
+    }
+
+    #[test]
+    fn test_filtered_responses_carry_the_last_scanned_version() {
+        let codec = TransactionCodec::with_dictionary(false, None);
+        let data = encoded_transactions(&codec, &[(10, "0xbeef"), (11, "0xcafe"), (12, "0xcafe")]);
+
+        let responses = get_transactions_responses_builder(data, 1, Some(&filter()), &codec);
+        assert_eq!(responses.len(), 1);
+        let versions: Vec<_> = responses[0]
+            .transactions
+            .iter()
+            .map(|t| t.version)
+            .collect();
+        assert_eq!(versions, vec![10]);
+        // The transactions after the match were scanned as well.
+        assert_eq!(responses[0].last_scanned_version, Some(12));
+    }
+
+    #[test]
+    fn test_filtered_out_batch_sends_the_last_scanned_version() {
+        let codec = TransactionCodec::with_dictionary(false, None);
+        let data = encoded_transactions(&codec, &[(10, "0xcafe"), (11, "0xcafe")]);
+
+        let responses = get_transactions_responses_builder(data, 1, Some(&filter()), &codec);
+        assert_eq!(responses, vec![TransactionsResponse {
+            transactions: vec![],
+            chain_id: Some(1),
+            last_scanned_version: Some(11),
+        }]);
+    }
+
+    /// File store holding the given files, by starting version.
+    struct TestFileStore {
+        files: HashMap<u64, Vec<String>>,
+    }
+
+    #[async_trait::async_trait]
+    impl FileStoreOperator for TestFileStore {
+        async fn verify_storage_bucket_existence(&self) {}
+
+        async fn get_transactions(&self, version: u64) -> anyhow::Result<Vec<String>> {
+            self.files
+                .get(&version)
+                .cloned()
+                .ok_or_else(|| anyhow::anyhow!("Transactions file not found"))
+        }
+
+        async fn get_file_store_metadata(&self) -> Option<FileStoreMetadata> {
+            unimplemented!()
+        }
+
+        async fn create_default_file_store_metadata_if_absent(
+            &mut self,
+            _expected_chain_id: u64,
+        ) -> anyhow::Result<FileStoreMetadata> {
+            unimplemented!()
+        }
+
+        async fn update_file_store_metadata(
+            &mut self,
+            _chain_id: u64,
+            _version: u64,
+        ) -> anyhow::Result<()> {
+            unimplemented!()
+        }
+
+        async fn upload_transactions(
+            &mut self,
+            _chain_id: u64,
+            _transactions: Vec<EncodedTransactionWithVersion>,
+        ) -> anyhow::Result<()> {
+            unimplemented!()
+        }
+
+        async fn get_raw_transactions(&self, _version: u64) -> anyhow::Result<TransactionsFile> {
+            unimplemented!()
+        }
+
+        async fn get_or_create_verification_metadata(
+            &self,
+            _chain_id: u64,
+        ) -> anyhow::Result<VerificationMetadata> {
+            unimplemented!()
+        }
+
+        async fn update_verification_metadata(
+            &mut self,
+            _chain_id: u64,
+            _next_version_to_verify: u64,
+        ) -> anyhow::Result<()> {
+            unimplemented!()
+        }
+    }
+
+    /// Starts streaming the range from files of `file_size` transactions starting at the given
+    /// versions, returning the receiving end of the stream.
+    fn stream_range(
+        file_versions: &[u64],
+        file_size: u64,
+        starting_version: u64,
+        ending_version: u64,
+        channel_size: usize,
+    ) -> tokio::sync::mpsc::Receiver<Result<TransactionsResponse, Status>> {
+        let codec = TransactionCodec::with_dictionary(false, None);
+        let files = file_versions
+            .iter()
+            .map(|file_version| {
+                let senders: Vec<_> = (*file_version..file_version + file_size)
+                    .map(|version| (version, "0xbeef"))
+                    .collect();
+                let file = encoded_transactions(&codec, &senders)
+                    .into_iter()
+                    .map(|(encoded, _)| encoded)
+                    .collect();
+                (*file_version, file)
+            })
+            .collect();
+        let (tx, rx) = channel(channel_size);
+        tokio::spawn(stream_transactions_in_range(
+            Box::new(TestFileStore { files }),
+            Arc::new(codec),
+            1,
+            starting_version,
+            ending_version,
+            None,
+            RequestMetadata {
+                request_id: "id".to_string(),
+                request_remote_addr: "addr".to_string(),
+                request_token: "token".to_string(),
+                request_name: "name".to_string(),
+                request_source: "source".to_string(),
+            },
+            tx,
+        ));
+        rx
+    }
+
+    fn versions(response: Option<Result<TransactionsResponse, Status>>) -> Vec<u64> {
+        response
+            .unwrap()
+            .unwrap()
+            .transactions
+            .iter()
+            .map(|t| t.version)
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_stream_range_across_files() {
+        let size = BLOB_STORAGE_SIZE as u64;
+        let mut rx = stream_range(&[0, size], size, size - 2, size + 1, 10);
+
+        assert_eq!(versions(rx.recv().await), vec![size - 2, size - 1]);
+        assert_eq!(versions(rx.recv().await), vec![size, size + 1]);
+        // The complete range ends the stream without an error.
+        assert!(rx.recv().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_stream_range_ends_with_an_error_on_data_gap() {
+        // The only file holds 5 transactions instead of a full file.
+        let mut rx = stream_range(&[0], 5, 0, 9, 10);
+
+        assert_eq!(versions(rx.recv().await), vec![0, 1, 2, 3, 4]);
+        let status = rx.recv().await.unwrap().unwrap_err();
+        assert_eq!(status.code(), tonic::Code::Internal);
+        assert!(status.message().contains("version 5"));
+        assert!(rx.recv().await.is_none());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_stream_range_ends_with_an_error_on_send_timeout() {
+        let size = BLOB_STORAGE_SIZE as u64;
+        let mut rx = stream_range(&[0, size], size, size - 1, size, 1);
+
+        // The client doesn't read until sending the second batch timed out.
+        tokio::time::sleep(RESPONSE_CHANNEL_SEND_TIMEOUT + Duration::from_secs(1)).await;
+        assert_eq!(versions(rx.recv().await), vec![size - 1]);
+        let status = rx.recv().await.unwrap().unwrap_err();
+        assert_eq!(status.code(), tonic::Code::Aborted);
+        assert!(rx.recv().await.is_none());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_stream_range_ends_with_an_error_on_missing_file() {
+        let mut rx = stream_range(&[], 0, 0, 9, 10);
+
+        let status = rx.recv().await.unwrap().unwrap_err();
+        assert_eq!(status.code(), tonic::Code::Unavailable);
+        assert!(rx.recv().await.is_none());
+    }
+}
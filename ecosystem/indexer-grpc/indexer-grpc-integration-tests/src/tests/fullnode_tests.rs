@@ -8,7 +8,7 @@ use aptos_indexer_grpc_server_framework::{
 };
 use aptos_indexer_grpc_utils::{
     cache_operator::CacheOperator,
-    config::{IndexerGrpcFileStoreConfig, LocalFileStore},
+    config::{CompressionConfig, IndexerGrpcFileStoreConfig, LocalFileStore},
     constants::BLOB_STORAGE_SIZE,
     file_store_operator::{FileStoreOperator, LocalFileStoreOperator},
 };
@@ -227,6 +227,7 @@ async fn test_cold_start_cache_worker_progress() {
             local_file_store_path: tmp_dir.path().to_path_buf(),
         }),
         redis_main_instance_address: REDIS_PRIMARY_URL.to_string(),
+        compression_config: CompressionConfig::default(),
     };
 
     let (_cache_worker_port, _cache_worker_handle) =
@@ -293,6 +294,7 @@ async fn test_cold_start_file_store_worker_progress() {
             local_file_store_path: tmp_dir.path().to_path_buf(),
         }),
         redis_main_instance_address: REDIS_PRIMARY_URL.to_string(),
+        compression_config: CompressionConfig::default(),
     };
 
     let file_store_worker_config = IndexerGrpcFileStoreWorkerConfig {
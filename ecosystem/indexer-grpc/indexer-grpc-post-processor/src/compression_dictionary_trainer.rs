@@ -0,0 +1,79 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::ensure;
+use aptos_indexer_grpc_utils::{
+    compression::{train_dictionary, TransactionCodec, DEFAULT_DICTIONARY_SIZE},
+    config::{CompressionConfig, IndexerGrpcFileStoreConfig},
+    constants::BLOB_STORAGE_SIZE,
+    file_store_operator::{FileStoreOperator, GcsFileStoreOperator, LocalFileStoreOperator},
+};
+use std::path::PathBuf;
+
+/// Trains the zstd dictionary used to compress the transactions in the cache and file store on a
+/// sample of the transactions in the file store.
+pub struct CompressionDictionaryTrainer {
+    pub file_store_config: IndexerGrpcFileStoreConfig,
+    pub starting_version: u64,
+    pub num_files: usize,
+    pub dictionary_path: PathBuf,
+    pub compression_config: CompressionConfig,
+}
+
+impl CompressionDictionaryTrainer {
+    pub fn new(
+        file_store_config: IndexerGrpcFileStoreConfig,
+        starting_version: u64,
+        num_files: usize,
+        dictionary_path: PathBuf,
+        compression_config: CompressionConfig,
+    ) -> Self {
+        Self {
+            file_store_config,
+            starting_version,
+            num_files,
+            dictionary_path,
+            compression_config,
+        }
+    }
+
+    pub async fn run(&self) -> anyhow::Result<()> {
+        ensure!(
+            self.starting_version % BLOB_STORAGE_SIZE as u64 == 0,
+            "Starting version has to be a multiple of BLOB_STORAGE_SIZE."
+        );
+        let file_store_operator: Box<dyn FileStoreOperator> = match &self.file_store_config {
+            IndexerGrpcFileStoreConfig::GcsFileStore(gcs_file_store) => {
+                Box::new(GcsFileStoreOperator::new(
+                    gcs_file_store.gcs_file_store_bucket_name.clone(),
+                    gcs_file_store
+                        .gcs_file_store_service_account_key_path
+                        .clone(),
+                ))
+            },
+            IndexerGrpcFileStoreConfig::LocalFileStore(local_file_store) => Box::new(
+                LocalFileStoreOperator::new(local_file_store.local_file_store_path.clone()),
+            ),
+        };
+        // The files may already be compressed, e.g. when retraining the dictionary.
+        let transaction_codec = TransactionCodec::new(&self.compression_config)?;
+        file_store_operator.verify_storage_bucket_existence().await;
+
+        let mut transactions = Vec::with_capacity(self.num_files * BLOB_STORAGE_SIZE);
+        for file_index in 0..self.num_files {
+            let version = self.starting_version + (file_index * BLOB_STORAGE_SIZE) as u64;
+            for encoded_transaction in file_store_operator.get_transactions(version).await? {
+                transactions.push(transaction_codec.decode(&encoded_transaction)?);
+            }
+        }
+        let dictionary = train_dictionary(&transactions, DEFAULT_DICTIONARY_SIZE)?;
+        tokio::fs::write(&self.dictionary_path, &dictionary).await?;
+        tracing::info!(
+            num_transactions = transactions.len(),
+            dictionary_size = dictionary.len(),
+            "Trained compression dictionary at {}",
+            self.dictionary_path.display()
+        );
+        Ok(())
+    }
+}
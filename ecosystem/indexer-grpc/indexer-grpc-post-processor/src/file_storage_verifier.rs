@@ -3,25 +3,30 @@
 use crate::metrics::VERIFICATION_ERROR_COUNT;
 use anyhow::{bail, ensure};
 use aptos_indexer_grpc_utils::{
-    config::IndexerGrpcFileStoreConfig,
+    compression::TransactionCodec,
+    config::{CompressionConfig, IndexerGrpcFileStoreConfig},
     constants::BLOB_STORAGE_SIZE,
     file_store_operator::{
         FileStoreOperator, GcsFileStoreOperator, LocalFileStoreOperator, TransactionsFile,
     },
 };
-use aptos_protos::transaction::v1::Transaction;
-use prost::Message;
 
 pub struct FileStorageVerifier {
     pub file_store_config: IndexerGrpcFileStoreConfig,
     pub chain_id: u64,
+    pub compression_config: CompressionConfig,
 }
 
 impl FileStorageVerifier {
-    pub fn new(file_store_config: IndexerGrpcFileStoreConfig, chain_id: u64) -> Self {
+    pub fn new(
+        file_store_config: IndexerGrpcFileStoreConfig,
+        chain_id: u64,
+        compression_config: CompressionConfig,
+    ) -> Self {
         Self {
             file_store_config,
             chain_id,
+            compression_config,
         }
     }
 
@@ -39,6 +44,7 @@ impl FileStorageVerifier {
                 LocalFileStoreOperator::new(local_file_store.local_file_store_path.clone()),
             ),
         };
+        let transaction_codec = TransactionCodec::new(&self.compression_config)?;
         // Verify the existence of the storage bucket.
         file_store_operator.verify_storage_bucket_existence().await;
         // Get or create verification metadata file.
@@ -96,8 +102,7 @@ impl FileStorageVerifier {
                 );
             }
             for (index, txn) in txn_file.transactions.iter().enumerate() {
-                let txn_bytes = base64::decode(txn)?;
-                let txn: Transaction = Transaction::decode(&*txn_bytes)?;
+                let txn = transaction_codec.decode(txn)?;
                 if txn.version != txn_file.starting_version + index as u64 {
                     VERIFICATION_ERROR_COUNT.inc();
                     bail!(
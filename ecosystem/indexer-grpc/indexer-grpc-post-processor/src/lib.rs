@@ -1,6 +1,7 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
+pub mod compression_dictionary_trainer;
 pub mod file_storage_verifier;
 pub mod metrics;
 pub mod pfn_ledger_checker;
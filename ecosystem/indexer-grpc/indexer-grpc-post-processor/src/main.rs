@@ -3,13 +3,15 @@
 
 use anyhow::Result;
 use aptos_indexer_grpc_post_processor::{
+    compression_dictionary_trainer::CompressionDictionaryTrainer,
     file_storage_verifier::FileStorageVerifier, metrics::TASK_FAILURE_COUNT,
     pfn_ledger_checker::PfnLedgerChecker,
 };
 use aptos_indexer_grpc_server_framework::{RunnableConfig, ServerArgs};
-use aptos_indexer_grpc_utils::config::IndexerGrpcFileStoreConfig;
+use aptos_indexer_grpc_utils::config::{CompressionConfig, IndexerGrpcFileStoreConfig};
 use clap::Parser;
 use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
 use tracing::info;
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -26,6 +28,23 @@ pub struct IndexerGrpcPFNCheckerConfig {
 pub struct IndexerGrpcFileStorageVerifierConfig {
     pub file_store_config: IndexerGrpcFileStoreConfig,
     pub chain_id: u64,
+    #[serde(default)]
+    pub compression_config: CompressionConfig,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct IndexerGrpcCompressionDictionaryTrainerConfig {
+    pub file_store_config: IndexerGrpcFileStoreConfig,
+    // Version of the first file to train on; a multiple of 1000.
+    pub starting_version: u64,
+    // Number of files, i.e. thousands of transactions, to train on.
+    pub num_files: usize,
+    // Where to write the trained dictionary.
+    pub dictionary_path: PathBuf,
+    // Compression of the files to train on.
+    #[serde(default)]
+    pub compression_config: CompressionConfig,
 }
 
 // TODO: change this to match pattern.
@@ -34,6 +53,7 @@ pub struct IndexerGrpcFileStorageVerifierConfig {
 pub struct IndexerGrpcPostProcessorConfig {
     pub pfn_checker_config: Option<IndexerGrpcPFNCheckerConfig>,
     pub file_storage_verifier: Option<IndexerGrpcFileStorageVerifierConfig>,
+    pub compression_dictionary_trainer: Option<IndexerGrpcCompressionDictionaryTrainerConfig>,
 }
 
 #[async_trait::async_trait]
@@ -75,8 +95,11 @@ impl RunnableConfig for IndexerGrpcPostProcessorConfig {
             tasks.push(tokio::spawn({
                 let config = config.clone();
                 async move {
-                    let checker =
-                        FileStorageVerifier::new(config.file_store_config.clone(), config.chain_id);
+                    let checker = FileStorageVerifier::new(
+                        config.file_store_config.clone(),
+                        config.chain_id,
+                        config.compression_config.clone(),
+                    );
                     info!("Starting FileStorageVerifier");
                     if let Err(err) = checker.run().await {
                         tracing::error!("FileStorageVerifier failed: {:?}", err);
@@ -88,8 +111,31 @@ impl RunnableConfig for IndexerGrpcPostProcessorConfig {
             }));
         }
 
+        if let Some(config) = &self.compression_dictionary_trainer {
+            tasks.push(tokio::spawn({
+                let config = config.clone();
+                async move {
+                    let trainer = CompressionDictionaryTrainer::new(
+                        config.file_store_config.clone(),
+                        config.starting_version,
+                        config.num_files,
+                        config.dictionary_path.clone(),
+                        config.compression_config.clone(),
+                    );
+                    info!("Starting CompressionDictionaryTrainer");
+                    if let Err(err) = trainer.run().await {
+                        tracing::error!("CompressionDictionaryTrainer failed: {:?}", err);
+                        TASK_FAILURE_COUNT
+                            .with_label_values(&["compression_dictionary_trainer"])
+                            .inc();
+                    }
+                }
+            }));
+        }
+
         let _ = futures::future::join_all(tasks).await;
-        unreachable!("All tasks should run forever");
+        // Only the compression dictionary trainer finishes; the other tasks run forever.
+        Ok(())
     }
 
     fn get_server_name(&self) -> String {
@@ -173,6 +173,7 @@ async fn handle_indexer_grpc(
         starting_version: Some(starting_version),
         transactions_count: None,
         batch_size: None,
+        filter: None,
     });
 
     request
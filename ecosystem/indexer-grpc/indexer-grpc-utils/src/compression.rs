@@ -0,0 +1,151 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::config::CompressionConfig;
+use anyhow::Context;
+use aptos_protos::transaction::v1::Transaction;
+use prost::Message;
+use std::io::Read;
+use zstd::dict::{DecoderDictionary, EncoderDictionary};
+
+/// zstd compression level of the transactions; higher levels barely help with a dictionary.
+const ZSTD_COMPRESSION_LEVEL: i32 = 3;
+/// Magic number at the start of every zstd frame. Protobuf encoded transactions start with the
+/// tag of the timestamp field instead, so compressed and uncompressed transactions can be told
+/// apart, e.g. in the file store files uploaded before compression is enabled.
+const ZSTD_MAGIC_NUMBER: [u8; 4] = [0x28, 0xB5, 0x2F, 0xFD];
+/// Default maximum size of a trained dictionary, same as the zstd CLI.
+pub const DEFAULT_DICTIONARY_SIZE: usize = 110 * 1024;
+
+/// Encodes transactions to the strings stored in the cache and file store, i.e. base64 of the
+/// protobuf encoded transaction, optionally compressed with zstd, and decodes them back.
+pub struct TransactionCodec {
+    compression_enabled: bool,
+    encoder_dictionary: Option<EncoderDictionary<'static>>,
+    decoder_dictionary: Option<DecoderDictionary<'static>>,
+}
+
+impl TransactionCodec {
+    pub fn new(config: &CompressionConfig) -> anyhow::Result<Self> {
+        let dictionary = match &config.dictionary_path {
+            Some(path) => Some(std::fs::read(path).with_context(|| {
+                format!(
+                    "[Indexer Compression] Failed to read dictionary {}",
+                    path.display()
+                )
+            })?),
+            None => None,
+        };
+        Ok(Self::with_dictionary(config.enabled, dictionary.as_deref()))
+    }
+
+    pub fn with_dictionary(compression_enabled: bool, dictionary: Option<&[u8]>) -> Self {
+        Self {
+            compression_enabled,
+            encoder_dictionary: dictionary
+                .map(|dictionary| EncoderDictionary::copy(dictionary, ZSTD_COMPRESSION_LEVEL)),
+            decoder_dictionary: dictionary.map(DecoderDictionary::copy),
+        }
+    }
+
+    /// Encodes the transaction, compressing it if compression is enabled.
+    pub fn encode(&self, transaction: &Transaction) -> anyhow::Result<String> {
+        let encoded_proto_data = transaction.encode_to_vec();
+        if !self.compression_enabled {
+            return Ok(base64::encode(encoded_proto_data));
+        }
+        let compressed_proto_data = match &self.encoder_dictionary {
+            Some(dictionary) => zstd::bulk::Compressor::with_prepared_dictionary(dictionary)?
+                .compress(&encoded_proto_data)?,
+            None => zstd::bulk::compress(&encoded_proto_data, ZSTD_COMPRESSION_LEVEL)?,
+        };
+        Ok(base64::encode(compressed_proto_data))
+    }
+
+    /// Decodes the transaction, whether it's compressed or not.
+    pub fn decode(&self, encoded: &str) -> anyhow::Result<Transaction> {
+        let bytes = base64::decode(encoded)?;
+        if !bytes.starts_with(&ZSTD_MAGIC_NUMBER) {
+            return Ok(Transaction::decode(bytes.as_slice())?);
+        }
+        let mut encoded_proto_data = vec![];
+        match &self.decoder_dictionary {
+            Some(dictionary) => {
+                zstd::stream::read::Decoder::with_prepared_dictionary(bytes.as_slice(), dictionary)?
+                    .read_to_end(&mut encoded_proto_data)
+            },
+            None => zstd::stream::read::Decoder::with_buffer(bytes.as_slice())?
+                .read_to_end(&mut encoded_proto_data),
+        }
+        .context(
+            "[Indexer Compression] Failed to decompress transaction; is the dictionary configured?",
+        )?;
+        Ok(Transaction::decode(encoded_proto_data.as_slice())?)
+    }
+}
+
+/// Trains a zstd dictionary on the protobuf encoding of the given transactions. zstd needs at
+/// least a few thousand samples for a useful dictionary.
+pub fn train_dictionary(transactions: &[Transaction], max_size: usize) -> anyhow::Result<Vec<u8>> {
+    let samples: Vec<Vec<u8>> = transactions
+        .iter()
+        .map(|transaction| transaction.encode_to_vec())
+        .collect();
+    zstd::dict::from_samples(&samples, max_size)
+        .context("[Indexer Compression] Failed to train dictionary")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aptos_protos::util::timestamp::Timestamp;
+
+    fn transaction() -> Transaction {
+        Transaction {
+            version: 42,
+            epoch: 2,
+            block_height: 10,
+            timestamp: Some(Timestamp {
+                seconds: 1,
+                nanos: 0,
+            }),
+            ..Transaction::default()
+        }
+    }
+
+    #[test]
+    fn test_uncompressed_round_trip() {
+        let codec = TransactionCodec::with_dictionary(false, None);
+        let encoded = codec.encode(&transaction()).unwrap();
+        assert_eq!(encoded, base64::encode(transaction().encode_to_vec()));
+        assert_eq!(codec.decode(&encoded).unwrap(), transaction());
+    }
+
+    #[test]
+    fn test_compressed_round_trip() {
+        let codec = TransactionCodec::with_dictionary(true, None);
+        let encoded = codec.encode(&transaction()).unwrap();
+        assert!(base64::decode(&encoded)
+            .unwrap()
+            .starts_with(&ZSTD_MAGIC_NUMBER));
+        assert_eq!(codec.decode(&encoded).unwrap(), transaction());
+    }
+
+    #[test]
+    fn test_compressed_round_trip_with_dictionary() {
+        // Any content works as a raw dictionary.
+        let dictionary = transaction().encode_to_vec().repeat(16);
+        let codec = TransactionCodec::with_dictionary(true, Some(&dictionary));
+        let encoded = codec.encode(&transaction()).unwrap();
+        assert_eq!(codec.decode(&encoded).unwrap(), transaction());
+    }
+
+    #[test]
+    fn test_decode_uncompressed_with_compression_enabled() {
+        let encoded = TransactionCodec::with_dictionary(false, None)
+            .encode(&transaction())
+            .unwrap();
+        let codec = TransactionCodec::with_dictionary(true, None);
+        assert_eq!(codec.decode(&encoded).unwrap(), transaction());
+    }
+}
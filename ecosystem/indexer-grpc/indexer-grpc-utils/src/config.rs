@@ -31,3 +31,17 @@ impl Default for IndexerGrpcFileStoreConfig {
         })
     }
 }
+
+/// Compression of the transactions stored in the cache and file store.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(deny_unknown_fields)]
+pub struct CompressionConfig {
+    // Whether new transactions are compressed with zstd. Only used by the cache worker; readers
+    // handle both compressed and uncompressed transactions.
+    #[serde(default)]
+    pub enabled: bool,
+    // Optional zstd dictionary trained on protobuf transactions. Readers need the dictionary
+    // that the cache worker compressed the transactions with.
+    #[serde(default)]
+    pub dictionary_path: Option<PathBuf>,
+}
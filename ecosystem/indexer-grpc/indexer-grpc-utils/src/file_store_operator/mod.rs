@@ -27,7 +27,7 @@ pub struct TransactionsFile {
     // The version of the first transaction in the file.
     // It must be the same as the starting_version in the file name.
     pub starting_version: u64,
-    // Each transaction is a encoded string for Transaction protobuf.
+    // Each transaction is a encoded string for Transaction protobuf, optionally compressed; see `TransactionCodec`.
     // Expected size of each vector is BLOB_STORAGE_SIZE, i.e., 1_000.
     pub transactions: Vec<String>,
 }
@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 pub mod cache_operator;
+pub mod compression;
 pub mod config;
 pub mod constants;
 pub mod file_store_operator;
@@ -36,11 +36,12 @@ use aptos_metrics_core::Histogram;
 use aptos_sdk::types::LocalAccount;
 use aptos_storage_interface::DbReaderWriter;
 use aptos_transaction_generator_lib::{
-    create_txn_generator_creator, TransactionGeneratorCreator, TransactionType,
-    TransactionType::NonConflictingCoinTransfer,
+    create_txn_generator_creator, AccountPoolRefill, SignerKeyType, TransactionGeneratorCreator,
+    TransactionType, TransactionType::NonConflictingCoinTransfer,
 };
 use db_reliable_submitter::DbReliableTransactionSubmitter;
 use pipeline::PipelineConfig;
+use rand::{rngs::StdRng, SeedableRng};
 use std::{
     collections::HashMap,
     fs,
@@ -362,6 +363,11 @@ where
             &transaction_factory,
             &transaction_factory,
             phase,
+            0,
+            AccountPoolRefill::Disabled,
+            SignerKeyType::Ed25519,
+            None,
+            StdRng::from_entropy(),
         )
         .await
     });
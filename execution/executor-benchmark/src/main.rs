@@ -3,7 +3,8 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use aptos_config::config::{
-    EpochSnapshotPrunerConfig, LedgerPrunerConfig, PrunerConfig, StateMerklePrunerConfig,
+    EpochSnapshotPrunerConfig, LedgerPrunerConfig, PrunerConfig, StateKvPrunerConfig,
+    StateMerklePrunerConfig,
 };
 use aptos_executor::block_executor::TransactionBlockExecutor;
 use aptos_executor_benchmark::{native_executor::NativeExecutor, pipeline::PipelineConfig};
@@ -38,6 +39,9 @@ struct PrunerOpt {
     #[clap(long)]
     enable_ledger_pruner: bool,
 
+    #[clap(long)]
+    enable_state_kv_pruner: bool,
+
     #[clap(long, default_value_t = 100000)]
     state_prune_window: u64,
 
@@ -47,9 +51,16 @@ struct PrunerOpt {
     #[clap(long, default_value_t = 100000)]
     ledger_prune_window: u64,
 
+    /// Defaults to the ledger prune window
+    #[clap(long)]
+    state_kv_prune_window: Option<u64>,
+
     #[clap(long, default_value_t = 500)]
     ledger_pruning_batch_size: usize,
 
+    #[clap(long, default_value_t = 500)]
+    state_kv_pruning_batch_size: usize,
+
     #[clap(long, default_value_t = 500)]
     state_pruning_batch_size: usize,
 
@@ -76,6 +87,11 @@ impl PrunerOpt {
                 batch_size: self.ledger_pruning_batch_size,
                 user_pruning_window_offset: 0,
             },
+            state_kv_pruner_config: StateKvPrunerConfig {
+                enable: self.enable_state_kv_pruner,
+                prune_window: self.state_kv_prune_window,
+                batch_size: self.state_kv_pruning_batch_size,
+            },
         }
     }
 }
@@ -274,6 +290,7 @@ where
                     &transaction_type,
                     &transaction_weights,
                     &[],
+                    &[],
                     module_working_set_size,
                     false,
                 );
@@ -64,6 +64,54 @@ pub trait ChunkExecutorTrait: Send + Sync {
         epoch_change_li: Option<&LedgerInfoWithSignatures>,
     ) -> Result<()>;
 
+    /// Verifies the transactions of a chunk against the provided proofs and ledger info. This
+    /// doesn't depend on the previously executed chunks, so chunks can be verified concurrently,
+    /// ahead of their execution with `execute_verified_chunk`. By default, the verification is
+    /// left to the execution.
+    fn verify_chunk_proof(
+        &self,
+        _txn_list_with_proof: &TransactionListWithProof,
+        _verified_target_li: &LedgerInfoWithSignatures,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    /// Similar to `verify_chunk_proof`, but for transaction outputs applied with
+    /// `apply_verified_chunk`.
+    fn verify_output_chunk_proof(
+        &self,
+        _txn_output_list_with_proof: &TransactionOutputListWithProof,
+        _verified_target_li: &LedgerInfoWithSignatures,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    /// Similar to `execute_chunk`, but for transactions already verified with
+    /// `verify_chunk_proof`, so their proofs aren't verified against the ledger info again.
+    fn execute_verified_chunk(
+        &self,
+        txn_list_with_proof: TransactionListWithProof,
+        verified_target_li: &LedgerInfoWithSignatures,
+        epoch_change_li: Option<&LedgerInfoWithSignatures>,
+    ) -> Result<()> {
+        self.execute_chunk(txn_list_with_proof, verified_target_li, epoch_change_li)
+    }
+
+    /// Similar to `apply_chunk`, but for transaction outputs already verified with
+    /// `verify_output_chunk_proof`.
+    fn apply_verified_chunk(
+        &self,
+        txn_output_list_with_proof: TransactionOutputListWithProof,
+        verified_target_li: &LedgerInfoWithSignatures,
+        epoch_change_li: Option<&LedgerInfoWithSignatures>,
+    ) -> Result<()> {
+        self.apply_chunk(
+            txn_output_list_with_proof,
+            verified_target_li,
+            epoch_change_li,
+        )
+    }
+
     /// Commit a previously executed chunk. Returns a chunk commit notification.
     fn commit_chunk(&self) -> Result<ChunkCommitNotification>;
 
@@ -75,7 +75,12 @@ impl<V: VMExecutor> ChunkExecutorTrait for ChunkExecutor<V> {
             .read()
             .as_ref()
             .expect("not reset")
-            .execute_chunk(txn_list_with_proof, verified_target_li, epoch_change_li)
+            .execute_chunk(
+                txn_list_with_proof,
+                verified_target_li,
+                epoch_change_li,
+                true, /* verify_proof */
+            )
     }
 
     fn apply_chunk(
@@ -88,6 +93,59 @@ impl<V: VMExecutor> ChunkExecutorTrait for ChunkExecutor<V> {
             txn_output_list_with_proof,
             verified_target_li,
             epoch_change_li,
+            true, /* verify_proof */
+        )
+    }
+
+    fn verify_chunk_proof(
+        &self,
+        txn_list_with_proof: &TransactionListWithProof,
+        verified_target_li: &LedgerInfoWithSignatures,
+    ) -> Result<()> {
+        verify_chunk_proof(txn_list_with_proof, verified_target_li)
+    }
+
+    fn verify_output_chunk_proof(
+        &self,
+        txn_output_list_with_proof: &TransactionOutputListWithProof,
+        verified_target_li: &LedgerInfoWithSignatures,
+    ) -> Result<()> {
+        txn_output_list_with_proof.verify(
+            verified_target_li.ledger_info(),
+            txn_output_list_with_proof.first_transaction_output_version,
+        )
+    }
+
+    fn execute_verified_chunk(
+        &self,
+        txn_list_with_proof: TransactionListWithProof,
+        verified_target_li: &LedgerInfoWithSignatures,
+        epoch_change_li: Option<&LedgerInfoWithSignatures>,
+    ) -> Result<()> {
+        self.maybe_initialize()?;
+        self.inner
+            .read()
+            .as_ref()
+            .expect("not reset")
+            .execute_chunk(
+                txn_list_with_proof,
+                verified_target_li,
+                epoch_change_li,
+                false, /* verify_proof */
+            )
+    }
+
+    fn apply_verified_chunk(
+        &self,
+        txn_output_list_with_proof: TransactionOutputListWithProof,
+        verified_target_li: &LedgerInfoWithSignatures,
+        epoch_change_li: Option<&LedgerInfoWithSignatures>,
+    ) -> Result<()> {
+        self.inner.read().as_ref().expect("not reset").apply_chunk(
+            txn_output_list_with_proof,
+            verified_target_li,
+            epoch_change_li,
+            false, /* verify_proof */
         )
     }
 
@@ -184,6 +242,7 @@ impl<V: VMExecutor> ChunkExecutorInner<V> {
         txn_list_with_proof: TransactionListWithProof,
         verified_target_li: &LedgerInfoWithSignatures,
         epoch_change_li: Option<&LedgerInfoWithSignatures>,
+        verify_proof: bool,
     ) -> Result<()> {
         let _timer = APTOS_EXECUTOR_EXECUTE_CHUNK_SECONDS.start_timer();
 
@@ -197,6 +256,7 @@ impl<V: VMExecutor> ChunkExecutorInner<V> {
             first_version_in_request,
             &latest_view,
             num_txns,
+            verify_proof,
         )?;
 
         // Execute transactions.
@@ -233,6 +293,7 @@ impl<V: VMExecutor> ChunkExecutorInner<V> {
         txn_output_list_with_proof: TransactionOutputListWithProof,
         verified_target_li: &LedgerInfoWithSignatures,
         epoch_change_li: Option<&LedgerInfoWithSignatures>,
+        verify_proof: bool,
     ) -> Result<()> {
         let _timer = APTOS_EXECUTOR_APPLY_CHUNK_SECONDS.start_timer();
 
@@ -240,9 +301,11 @@ impl<V: VMExecutor> ChunkExecutorInner<V> {
         let first_version_in_request = txn_output_list_with_proof.first_transaction_output_version;
         let (_persisted_view, latest_view) = self.commit_queue.lock().persisted_and_latest_view();
 
-        // Verify input transaction list.
-        txn_output_list_with_proof
-            .verify(verified_target_li.ledger_info(), first_version_in_request)?;
+        // Verify input transaction list, unless it has already been verified.
+        if verify_proof {
+            txn_output_list_with_proof
+                .verify(verified_target_li.ledger_info(), first_version_in_request)?;
+        }
 
         // Skip transactions already in ledger.
         let txns_to_skip = txn_output_list_with_proof.proof.verify_extends_ledger(
@@ -289,8 +352,20 @@ impl<V: VMExecutor> ChunkExecutorInner<V> {
     }
 }
 
-/// Verifies the transaction list proof against the ledger info and returns transactions
-/// that are not already applied in the ledger.
+/// Verifies the transaction list proof against the ledger info.
+#[cfg(not(feature = "consensus-only-perf-test"))]
+fn verify_chunk_proof(
+    txn_list_with_proof: &TransactionListWithProof,
+    verified_target_li: &LedgerInfoWithSignatures,
+) -> Result<()> {
+    txn_list_with_proof.verify(
+        verified_target_li.ledger_info(),
+        txn_list_with_proof.first_transaction_version,
+    )
+}
+
+/// Verifies the transaction list proof against the ledger info (unless it has already been
+/// verified) and returns transactions that are not already applied in the ledger.
 #[cfg(not(feature = "consensus-only-perf-test"))]
 fn verify_chunk(
     txn_list_with_proof: TransactionListWithProof,
@@ -298,6 +373,7 @@ fn verify_chunk(
     first_version_in_request: Option<u64>,
     latest_view: &ExecutedTrees,
     num_txns: usize,
+    verify_proof: bool,
 ) -> Result<
     (
         aptos_types::proof::TransactionInfoListWithProof,
@@ -307,7 +383,9 @@ fn verify_chunk(
     anyhow::Error,
 > {
     // Verify input transaction list
-    txn_list_with_proof.verify(verified_target_li.ledger_info(), first_version_in_request)?;
+    if verify_proof {
+        txn_list_with_proof.verify(verified_target_li.ledger_info(), first_version_in_request)?;
+    }
 
     let txn_list = txn_list_with_proof.transactions;
     let txn_info_with_proof = txn_list_with_proof.proof;
@@ -344,6 +422,7 @@ fn verify_chunk(
     _first_version_in_request: Option<u64>,
     _latest_view: &ExecutedTrees,
     _num_txns: usize,
+    _verify_proof: bool,
 ) -> Result<
     (
         aptos_types::proof::TransactionInfoListWithProof,
@@ -360,6 +439,15 @@ fn verify_chunk(
     ))
 }
 
+/// In consensus-only mode, the transaction list proof is *not* verified (see `verify_chunk`).
+#[cfg(feature = "consensus-only-perf-test")]
+fn verify_chunk_proof(
+    _txn_list_with_proof: &TransactionListWithProof,
+    _verified_target_li: &LedgerInfoWithSignatures,
+) -> Result<()> {
+    Ok(())
+}
+
 impl<V: VMExecutor> TransactionReplayer for ChunkExecutor<V> {
     fn replay(
         &self,
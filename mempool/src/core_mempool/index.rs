@@ -4,19 +4,12 @@
 
 /// This module provides various indexes used by Mempool.
 use crate::core_mempool::transaction::{MempoolTransaction, SequenceInfo, TimelineState};
-use crate::{
-    counters,
-    logging::{LogEntry, LogSchema},
-    shared_mempool::types::MultiBucketTimelineIndexIds,
-};
+use crate::shared_mempool::types::MultiBucketTimelineIndexIds;
 use aptos_consensus_types::common::TransactionSummary;
-use aptos_logger::prelude::*;
 use aptos_types::account_address::AccountAddress;
-use rand::seq::SliceRandom;
 use std::{
     cmp::Ordering,
     collections::{btree_set::Iter, BTreeMap, BTreeSet, HashMap},
-    iter::Rev,
     ops::Bound,
     time::Duration,
 };
@@ -27,31 +20,69 @@ pub type AccountTransactions = BTreeMap<u64, MempoolTransaction>;
 /// It's used to form the transaction block for Consensus.
 /// Transactions are ordered by gas price. Second level ordering is done by expiration time.
 ///
+/// The queue is split into lanes by gas unit price band, each with its own capacity, so that a
+/// flood of cheap transactions can't crowd out the expensive ones: when Mempool is full, the
+/// transactions of the lowest lane are evicted first.
+///
 /// We don't store the full content of transactions in the index.
 /// Instead we use `OrderedQueueKey` - logical reference to the transaction in the main store.
 pub struct PriorityIndex {
+    // Ordered by gas unit price band.
+    lanes: Vec<PriorityLane>,
+}
+
+struct PriorityLane {
+    min_ranking_score: u64,
+    min_ranking_score_to_string: String,
+    capacity: usize,
     data: BTreeSet<OrderedQueueKey>,
 }
 
-pub type PriorityQueueIter<'a> = Rev<Iter<'a, OrderedQueueKey>>;
+pub type PriorityQueueIter<'a> = Box<dyn Iterator<Item = &'a OrderedQueueKey> + 'a>;
 
 impl PriorityIndex {
-    pub(crate) fn new() -> Self {
-        Self {
-            data: BTreeSet::new(),
+    pub(crate) fn new(lane_mins: Vec<u64>, lane_capacities: Vec<usize>) -> anyhow::Result<Self> {
+        anyhow::ensure!(!lane_mins.is_empty(), "Must not be empty");
+        anyhow::ensure!(lane_mins[0] == 0, "First lane must start at 0");
+        anyhow::ensure!(
+            lane_mins.len() == lane_capacities.len(),
+            "Every lane must have a capacity"
+        );
+
+        let mut prev = None;
+        let mut lanes = vec![];
+        for (min_ranking_score, capacity) in lane_mins.into_iter().zip(lane_capacities) {
+            if let Some(prev) = prev {
+                anyhow::ensure!(
+                    prev < min_ranking_score,
+                    "Values must be sorted and not repeat"
+                );
+            }
+            prev = Some(min_ranking_score);
+            lanes.push(PriorityLane {
+                min_ranking_score,
+                min_ranking_score_to_string: min_ranking_score.to_string(),
+                capacity,
+                data: BTreeSet::new(),
+            });
         }
+        Ok(Self { lanes })
     }
 
     pub(crate) fn insert(&mut self, txn: &MempoolTransaction) {
-        self.data.insert(self.make_key(txn));
+        let key = self.make_key(txn);
+        self.get_lane(txn.ranking_score).data.insert(key);
     }
 
     pub(crate) fn remove(&mut self, txn: &MempoolTransaction) {
-        self.data.remove(&self.make_key(txn));
+        let key = self.make_key(txn);
+        self.get_lane(txn.ranking_score).data.remove(&key);
     }
 
     pub(crate) fn contains(&self, txn: &MempoolTransaction) -> bool {
-        self.data.contains(&self.make_key(txn))
+        self.lanes[self.get_lane_index(txn.ranking_score)]
+            .data
+            .contains(&self.make_key(txn))
     }
 
     fn make_key(&self, txn: &MempoolTransaction) -> OrderedQueueKey {
@@ -63,12 +94,50 @@ impl PriorityIndex {
         }
     }
 
+    #[inline]
+    fn get_lane_index(&self, ranking_score: u64) -> usize {
+        self.lanes
+            .binary_search_by_key(&ranking_score, |lane| lane.min_ranking_score)
+            .unwrap_or_else(|i| i - 1)
+    }
+
+    fn get_lane(&mut self, ranking_score: u64) -> &mut PriorityLane {
+        let index = self.get_lane_index(ranking_score);
+        &mut self.lanes[index]
+    }
+
+    /// Whether the lane of a transaction with the given ranking score is at capacity.
+    pub(crate) fn is_lane_full(&self, ranking_score: u64) -> bool {
+        let lane = &self.lanes[self.get_lane_index(ranking_score)];
+        lane.data.len() >= lane.capacity
+    }
+
+    /// Returns the transaction to evict to make room for a transaction with the given ranking
+    /// score, i.e. the lowest priority transaction of the lowest non-empty lane below its lane.
+    pub(crate) fn get_evictable(&self, ranking_score: u64) -> Option<&OrderedQueueKey> {
+        self.lanes[..self.get_lane_index(ranking_score)]
+            .iter()
+            .find_map(|lane| lane.data.iter().next())
+    }
+
     pub(crate) fn iter(&self) -> PriorityQueueIter {
-        self.data.iter().rev()
+        Box::new(
+            self.lanes
+                .iter()
+                .rev()
+                .flat_map(|lane| lane.data.iter().rev()),
+        )
     }
 
     pub(crate) fn size(&self) -> usize {
-        self.data.len()
+        self.lanes.iter().map(|lane| lane.data.len()).sum()
+    }
+
+    pub(crate) fn get_lane_sizes(&self) -> Vec<(&str, usize)> {
+        self.lanes
+            .iter()
+            .map(|lane| (lane.min_ranking_score_to_string.as_str(), lane.data.len()))
+            .collect()
     }
 }
 
@@ -386,88 +455,69 @@ impl MultiBucketTimelineIndex {
 
 /// ParkingLotIndex keeps track of "not_ready" transactions, e.g., transactions that
 /// can't be included in the next block because their sequence number is too high.
-/// We keep a separate index to be able to efficiently evict them when Mempool is full.
+/// We keep a separate index to be able to efficiently evict them when Mempool is full, starting
+/// with the account with the most parked transactions.
 pub struct ParkingLotIndex {
     // DS invariants:
     // 1. for each entry (account, txns) in `data`, `txns` is never empty
-    // 2. for all accounts, data.get(account_indices.get(`account`)) == (account, sequence numbers of account's txns)
-    data: Vec<(AccountAddress, BTreeSet<u64>)>,
-    account_indices: HashMap<AccountAddress, usize>,
+    // 2. for each entry (account, txns) in `data`, `accounts_by_size` contains (txns.len(), account),
+    //    and it contains nothing else
+    data: HashMap<AccountAddress, BTreeSet<u64>>,
+    accounts_by_size: BTreeSet<(usize, AccountAddress)>,
     size: usize,
 }
 
 impl ParkingLotIndex {
     pub(crate) fn new() -> Self {
         Self {
-            data: vec![],
-            account_indices: HashMap::new(),
+            data: HashMap::new(),
+            accounts_by_size: BTreeSet::new(),
             size: 0,
         }
     }
 
     pub(crate) fn insert(&mut self, txn: &MempoolTransaction) {
-        let sender = &txn.txn.sender();
+        let sender = txn.txn.sender();
         let sequence_number = txn.txn.sequence_number();
-        let is_new_entry = match self.account_indices.get(sender) {
-            Some(index) => {
-                if let Some((_account, seq_nums)) = self.data.get_mut(*index) {
-                    seq_nums.insert(sequence_number)
-                } else {
-                    counters::CORE_MEMPOOL_INVARIANT_VIOLATION_COUNT.inc();
-                    error!(
-                        LogSchema::new(LogEntry::InvariantViolated),
-                        "Parking lot invariant violated: for account {}, account index exists but missing entry in data",
-                        sender
-                    );
-                    return;
-                }
-            },
-            None => {
-                let seq_nums = [sequence_number].iter().cloned().collect::<BTreeSet<_>>();
-                self.data.push((*sender, seq_nums));
-                self.account_indices.insert(*sender, self.data.len() - 1);
-                true
-            },
-        };
-        if is_new_entry {
+        let txns = self.data.entry(sender).or_default();
+        let account_size = txns.len();
+        if txns.insert(sequence_number) {
+            self.accounts_by_size.remove(&(account_size, sender));
+            self.accounts_by_size.insert((account_size + 1, sender));
             self.size += 1;
         }
     }
 
     pub(crate) fn remove(&mut self, txn: &MempoolTransaction) {
-        let sender = &txn.txn.sender();
-        if let Some(index) = self.account_indices.get(sender).cloned() {
-            if let Some((_account, txns)) = self.data.get_mut(index) {
-                if txns.remove(&txn.txn.sequence_number()) {
-                    self.size -= 1;
-                }
+        let sender = txn.txn.sender();
+        if let Some(txns) = self.data.get_mut(&sender) {
+            let account_size = txns.len();
+            if txns.remove(&txn.txn.sequence_number()) {
+                self.size -= 1;
+                self.accounts_by_size.remove(&(account_size, sender));
 
                 // maintain DS invariant
                 if txns.is_empty() {
                     // remove account with no more txns
-                    self.data.swap_remove(index);
-                    self.account_indices.remove(sender);
-
-                    // update DS for account that was swapped in `swap_remove`
-                    if let Some((swapped_account, _)) = self.data.get(index) {
-                        self.account_indices.insert(*swapped_account, index);
-                    }
+                    self.data.remove(&sender);
+                } else {
+                    self.accounts_by_size.insert((account_size - 1, sender));
                 }
             }
         }
     }
 
     pub(crate) fn contains(&self, account: &AccountAddress, seq_num: &u64) -> bool {
-        self.account_indices
+        self.data
             .get(account)
-            .and_then(|idx| self.data.get(*idx))
-            .map_or(false, |(_account, txns)| txns.contains(seq_num))
+            .map_or(false, |txns| txns.contains(seq_num))
     }
 
-    /// Returns a random "non-ready" transaction (with highest sequence number for that account).
+    /// Returns the "non-ready" transaction with the highest sequence number of the account with
+    /// the most "non-ready" transactions.
     pub(crate) fn get_poppable(&self) -> Option<TxnPointer> {
-        let mut rng = rand::thread_rng();
-        self.data.choose(&mut rng).and_then(|(sender, txns)| {
+        let (_, sender) = self.accounts_by_size.iter().next_back()?;
+        self.data.get(sender).and_then(|txns| {
             txns.iter().next_back().map(|seq_num| TxnPointer {
                 sender: *sender,
                 sequence_number: *seq_num,
@@ -475,6 +525,24 @@ impl ParkingLotIndex {
         })
     }
 
+    /// Number of "non-ready" transactions of the account.
+    pub(crate) fn account_size(&self, account: &AccountAddress) -> usize {
+        self.data.get(account).map_or(0, |txns| txns.len())
+    }
+
+    /// Number of accounts with "non-ready" transactions.
+    pub(crate) fn num_accounts(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Largest number of "non-ready" transactions of an account.
+    pub(crate) fn max_account_size(&self) -> usize {
+        self.accounts_by_size
+            .iter()
+            .next_back()
+            .map_or(0, |(account_size, _)| *account_size)
+    }
+
     pub(crate) fn size(&self) -> usize {
         self.size
     }
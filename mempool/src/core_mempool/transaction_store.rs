@@ -74,6 +74,7 @@ pub struct TransactionStore {
     capacity: usize,
     capacity_bytes: usize,
     capacity_per_user: usize,
+    parking_lot_capacity_per_user: usize,
     max_batch_bytes: u64,
 
     // eager expiration
@@ -93,7 +94,11 @@ impl TransactionStore {
             expiration_time_index: TTLIndex::new(Box::new(|t: &MempoolTransaction| {
                 Duration::from_secs(t.txn.expiration_timestamp_secs())
             })),
-            priority_index: PriorityIndex::new(),
+            priority_index: PriorityIndex::new(
+                config.priority_lanes.clone(),
+                config.priority_lane_capacities.clone(),
+            )
+            .unwrap(),
             timeline_index: MultiBucketTimelineIndex::new(config.broadcast_buckets.clone())
                 .unwrap(),
             parking_lot_index: ParkingLotIndex::new(),
@@ -106,6 +111,7 @@ impl TransactionStore {
             capacity: config.capacity,
             capacity_bytes: config.capacity_bytes,
             capacity_per_user: config.capacity_per_user,
+            parking_lot_capacity_per_user: config.parking_lot_capacity_per_user,
             max_batch_bytes: config.shared_mempool_max_batch_bytes,
 
             // eager expiration
@@ -248,6 +254,15 @@ impl TransactionStore {
             ));
         }
 
+        if self.priority_index.is_lane_full(txn.ranking_score)
+            && self.check_txn_ready(&txn, acc_seq_num)
+        {
+            return MempoolStatus::new(MempoolStatusCode::MempoolIsFull).with_message(format!(
+                "Mempool is full for gas unit price {}",
+                txn.ranking_score,
+            ));
+        }
+
         self.clean_committed_transactions(&address, acc_seq_num);
 
         self.transactions
@@ -265,6 +280,19 @@ impl TransactionStore {
                     ),
                 );
             }
+            let num_parked_txns = self.parking_lot_index.account_size(&address);
+            if num_parked_txns >= self.parking_lot_capacity_per_user
+                && !self.check_txn_ready(&txn, acc_seq_num)
+            {
+                counters::CORE_MEMPOOL_PARKING_LOT_REJECTED_TXNS.inc();
+                return MempoolStatus::new(MempoolStatusCode::TooManyTransactions).with_message(
+                    format!(
+                        "Mempool over capacity for non-ready transactions of account. Number of non-ready transactions from account: {} Capacity per account: {}",
+                        num_parked_txns,
+                        self.parking_lot_capacity_per_user,
+                    ),
+                );
+            }
 
             // insert into storage and other indexes
             self.system_ttl_index.insert(&txn);
@@ -301,11 +329,20 @@ impl TransactionStore {
             counters::PARKING_LOT_INDEX_LABEL,
             self.parking_lot_index.size(),
         );
+        counters::core_mempool_index_size(
+            counters::PARKING_LOT_ACCOUNTS_LABEL,
+            self.parking_lot_index.num_accounts(),
+        );
+        counters::core_mempool_index_size(
+            counters::PARKING_LOT_MAX_ACCOUNT_SIZE_LABEL,
+            self.parking_lot_index.max_account_size(),
+        );
         counters::core_mempool_index_size(
             counters::TIMELINE_INDEX_LABEL,
             self.timeline_index.size(),
         );
         counters::core_mempool_timeline_index_size(&self.timeline_index.get_sizes());
+        counters::core_mempool_priority_lane_size(&self.priority_index.get_lane_sizes());
         counters::core_mempool_index_size(
             counters::TRANSACTION_HASH_INDEX_LABEL,
             self.hash_index.len(),
@@ -314,7 +351,8 @@ impl TransactionStore {
     }
 
     /// Checks if Mempool is full.
-    /// If it's full, tries to free some space by evicting transactions from the ParkingLot.
+    /// If it's full, tries to free some space by evicting transactions from the ParkingLot, or else
+    /// from a lower priority lane than the transaction's.
     /// We only evict on attempt to insert a transaction that would be ready for broadcast upon insertion.
     fn check_is_full_after_eviction(
         &mut self,
@@ -323,6 +361,7 @@ impl TransactionStore {
     ) -> bool {
         if self.is_full() && self.check_txn_ready(txn, curr_sequence_number) {
             // try to free some space in Mempool from ParkingLot by evicting a non-ready txn
+            // of the account with the most non-ready txns
             if let Some(txn_pointer) = self.parking_lot_index.get_poppable() {
                 if let Some(txn) = self
                     .transactions
@@ -337,11 +376,35 @@ impl TransactionStore {
                     );
                     self.index_remove(&txn);
                 }
+            } else {
+                self.evict_from_lower_priority_lane(txn.ranking_score);
             }
         }
         self.is_full()
     }
 
+    /// Evicts the lowest priority transaction of the lanes below the lane of the given ranking
+    /// score, along with the following transactions of its account, which can't be ready without it.
+    fn evict_from_lower_priority_lane(&mut self, ranking_score: u64) {
+        let (address, sequence_number) = match self.priority_index.get_evictable(ranking_score) {
+            Some(key) => (key.address, key.sequence_number.transaction_sequence_number),
+            None => return,
+        };
+        let evicted_txns = match self.transactions.get_mut(&address) {
+            Some(txns) => txns.split_off(&sequence_number),
+            None => return,
+        };
+        for txn in evicted_txns.values() {
+            debug!(
+                LogSchema::new(LogEntry::MempoolFullEvictedTxn).txns(TxnsLog::new_txn(
+                    txn.get_sender(),
+                    txn.sequence_info.transaction_sequence_number
+                ))
+            );
+            self.index_remove(txn);
+        }
+    }
+
     fn is_full(&self) -> bool {
         self.system_ttl_index.size() >= self.capacity || self.size_bytes >= self.capacity_bytes
     }
@@ -18,6 +18,8 @@ pub const EXPIRATION_TIME_INDEX_LABEL: &str = "expiration";
 pub const SYSTEM_TTL_INDEX_LABEL: &str = "system_ttl";
 pub const TIMELINE_INDEX_LABEL: &str = "timeline";
 pub const PARKING_LOT_INDEX_LABEL: &str = "parking_lot";
+pub const PARKING_LOT_ACCOUNTS_LABEL: &str = "parking_lot_accounts";
+pub const PARKING_LOT_MAX_ACCOUNT_SIZE_LABEL: &str = "parking_lot_max_account_size";
 pub const TRANSACTION_HASH_INDEX_LABEL: &str = "transaction_hash";
 pub const SIZE_BYTES_LABEL: &str = "size_bytes";
 
@@ -163,6 +165,33 @@ pub fn core_mempool_timeline_index_size(bucket_min_size_pairs: &Vec<(&str, usize
     }
 }
 
+/// Counter tracking size of each lane in priority index
+static CORE_MEMPOOL_PRIORITY_LANE_SIZE: Lazy<IntGaugeVec> = Lazy::new(|| {
+    register_int_gauge_vec!(
+        "aptos_core_mempool_priority_lane_size",
+        "Size of each lane in core mempool priority index",
+        &["lane"]
+    )
+    .unwrap()
+});
+
+pub fn core_mempool_priority_lane_size(lane_min_size_pairs: &Vec<(&str, usize)>) {
+    for &(lane_min, size) in lane_min_size_pairs {
+        CORE_MEMPOOL_PRIORITY_LANE_SIZE
+            .with_label_values(&[lane_min])
+            .set(size as i64)
+    }
+}
+
+/// Counter tracking number of txns rejected because of the per account capacity of the parking lot
+pub static CORE_MEMPOOL_PARKING_LOT_REJECTED_TXNS: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "aptos_core_mempool_parking_lot_rejected_txns_count",
+        "Number of txns rejected because their account has too many non-ready txns in core mempool"
+    )
+    .unwrap()
+});
+
 /// Counter tracking number of txns removed from core mempool
 pub static CORE_MEMPOOL_REMOVED_TXNS: Lazy<IntCounter> = Lazy::new(|| {
     register_int_counter!(
@@ -48,7 +48,9 @@
 //! Such event “unblocks” local transaction and txn4 will be moved to OrderedQueue.
 //!
 //! Mempool only holds a limited number of transactions to prevent OOMing the system. Additionally
-//! there's a limit of number of transactions per account to prevent different abuses/attacks
+//! there's a limit of number of transactions per account, and a lower one for "non-ready"
+//! transactions, to prevent different abuses/attacks. When Mempool is full, "non-ready"
+//! transactions of the account with the most of them are evicted first.
 //!
 //! Transactions in Mempool have two types of expirations: systemTTL and client-specified
 //! expiration. Once we hit either of those, the transaction is removed from Mempool. SystemTTL is
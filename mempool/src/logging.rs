@@ -151,6 +151,7 @@ impl<'a> LogSchema<'a> {
 pub enum LogEntry {
     NewPeer,
     LostPeer,
+    PrioritizedPeers,
     CoordinatorRuntime,
     GCRuntime,
     ReconfigUpdate,
@@ -162,7 +163,6 @@ pub enum LogEntry {
     BroadcastTransaction,
     BroadcastACK,
     ReceiveACK,
-    InvariantViolated,
     AddTxn,
     RemoveTxn,
     MempoolFullEvictedTxn,
@@ -10,7 +10,7 @@ use crate::{
     shared_mempool::{
         tasks,
         types::{
-            notify_subscribers, MultiBatchId, PeerSyncState, SharedMempool,
+            notify_subscribers, MultiBatchId, PeerBroadcastStats, PeerSyncState, SharedMempool,
             SharedMempoolNotification,
         },
     },
@@ -33,7 +33,7 @@ use itertools::Itertools;
 use serde::{Deserialize, Serialize};
 use std::{
     cmp::Ordering,
-    collections::{hash_map::RandomState, BTreeMap, BTreeSet, HashMap},
+    collections::{hash_map::RandomState, BTreeMap, BTreeSet, HashMap, HashSet},
     hash::{BuildHasher, Hasher},
     ops::Add,
     sync::Arc,
@@ -98,8 +98,8 @@ impl<NetworkClient: NetworkClientInterface<MempoolSyncMsg>> MempoolNetworkInterf
             sync_states: Arc::new(RwLock::new(HashMap::new())),
             prioritized_peers: Arc::new(Mutex::new(Vec::new())),
             role,
+            prioritized_peers_comparator: PrioritizedPeersComparator::new(&mempool_config),
             mempool_config,
-            prioritized_peers_comparator: PrioritizedPeersComparator::new(),
         }
     }
 
@@ -145,6 +145,18 @@ impl<NetworkClient: NetworkClientInterface<MempoolSyncMsg>> MempoolNetworkInterf
         }
     }
 
+    /// Updates the ping latencies of the upstream peers reported by peer monitoring
+    fn update_ping_latencies(&self, all_connected_peers: &HashMap<PeerNetworkId, PeerMetadata>) {
+        let mut sync_states = self.sync_states.write();
+        for (peer, state) in sync_states.iter_mut() {
+            if let Some(metadata) = all_connected_peers.get(peer) {
+                state.broadcast_stats.ping_latency_secs = metadata
+                    .get_peer_monitoring_metadata()
+                    .average_ping_latency_secs;
+            }
+        }
+    }
+
     /// Update peers based on updated view of connected peers. Return (peers newly added that need
     /// to start broadcasts, peers that will be disabled from broadcasts).
     pub fn update_peers(
@@ -153,11 +165,12 @@ impl<NetworkClient: NetworkClientInterface<MempoolSyncMsg>> MempoolNetworkInterf
     ) -> (Vec<PeerNetworkId>, Vec<PeerNetworkId>) {
         // Get the upstream peers to add or disable, using a read lock
         let (to_add, to_disable) = self.get_upstream_peers_to_add_and_disable(all_connected_peers);
-        if to_add.is_empty() && to_disable.is_empty() {
-            return (vec![], vec![]);
-        }
         // If there are updates, apply using a write lock
-        self.add_and_disable_upstream_peers(&to_add, &to_disable);
+        if !to_add.is_empty() || !to_disable.is_empty() {
+            self.add_and_disable_upstream_peers(&to_add, &to_disable);
+        }
+        // The peers are reprioritized even without updates, as their latencies keep changing
+        self.update_ping_latencies(all_connected_peers);
         self.update_prioritized_peers();
 
         (to_add.iter().map(|(peer, _)| *peer).collect(), to_disable)
@@ -169,24 +182,51 @@ impl<NetworkClient: NetworkClientInterface<MempoolSyncMsg>> MempoolNetworkInterf
             return;
         }
 
-        // Retrieve just what's needed for the peer ordering
+        // The peers currently broadcast to, see `check_peer_prioritized`
+        let broadcast_peers: HashSet<_> = self
+            .prioritized_peers
+            .lock()
+            .iter()
+            .take(self.mempool_config.default_failovers + 1)
+            .copied()
+            .collect();
+
+        // Retrieve just what's needed for the peer ordering, decaying the stats of the peers
+        // that aren't broadcast to, so that they are eventually retried
         let peers: Vec<_> = {
             self.sync_states
-                .read()
-                .iter()
-                .map(|(peer, state)| (*peer, state.metadata.role))
+                .write()
+                .iter_mut()
+                .map(|(peer, state)| {
+                    let is_broadcast_peer = broadcast_peers.contains(peer);
+                    if !is_broadcast_peer {
+                        state.broadcast_stats.decay();
+                    }
+                    (
+                        *peer,
+                        state.metadata.role,
+                        state.broadcast_stats.clone(),
+                        is_broadcast_peer,
+                    )
+                })
                 .collect()
         };
 
-        // Order peers by network and by type
+        // Order peers by network, health, type and responsiveness
         // Origin doesn't matter at this point, only inserted ones into peer_states are upstream
         // Validators will always have the full set
         let mut prioritized_peers = self.prioritized_peers.lock();
         let peers: Vec<_> = peers
             .iter()
             .sorted_by(|peer_a, peer_b| self.prioritized_peers_comparator.compare(peer_a, peer_b))
-            .map(|(peer, _)| *peer)
+            .map(|(peer, _, _, _)| *peer)
             .collect();
+        if *prioritized_peers != peers {
+            debug!(
+                LogSchema::new(LogEntry::PrioritizedPeers),
+                prioritized_peers = format!("{:?}", peers)
+            );
+        }
         let _ = std::mem::replace(&mut *prioritized_peers, peers);
     }
 
@@ -238,6 +278,7 @@ impl<NetworkClient: NetworkClientInterface<MempoolSyncMsg>> MempoolNetworkInterf
             counters::SHARED_MEMPOOL_BROADCAST_RTT
                 .with_label_values(&[network_id.as_str()])
                 .observe(rtt.as_secs_f64());
+            sync_state.broadcast_stats.record_ack(rtt, backoff);
 
             counters::shared_mempool_pending_broadcasts(&peer).dec();
         } else {
@@ -375,6 +416,8 @@ impl<NetworkClient: NetworkClientInterface<MempoolSyncMsg>> MempoolNetworkInterf
             match std::cmp::max(expired_batch_id, retry_batch_id) {
                 Some(id) => {
                     let metric_label = if Some(id) == expired_batch_id {
+                        // The peer didn't ack the batch in time
+                        state.broadcast_stats.record_failure();
                         Some(counters::EXPIRED_BROADCAST_LABEL)
                     } else {
                         Some(counters::RETRY_BROADCAST_LABEL)
@@ -418,6 +461,9 @@ impl<NetworkClient: NetworkClientInterface<MempoolSyncMsg>> MempoolNetworkInterf
 
         if let Err(e) = self.network_client.send_to_peer(request, peer) {
             counters::network_send_fail_inc(counters::BROADCAST_TXNS);
+            if let Some(state) = self.sync_states.write().get_mut(&peer) {
+                state.broadcast_stats.record_failure();
+            }
             return Err(BroadcastError::NetworkError(peer, e.into()));
         }
         Ok(())
@@ -513,46 +559,78 @@ impl<NetworkClient: NetworkClientInterface<MempoolSyncMsg>> MempoolNetworkInterf
 #[derive(Clone, Debug)]
 struct PrioritizedPeersComparator {
     random_state: RandomState,
+    ack_timeout: Duration,
+    max_failure_percentage: u64,
+    hysteresis_percentage: u64,
 }
 
 impl PrioritizedPeersComparator {
-    fn new() -> Self {
+    fn new(mempool_config: &MempoolConfig) -> Self {
         Self {
             random_state: RandomState::new(),
+            ack_timeout: Duration::from_millis(mempool_config.shared_mempool_ack_timeout_ms),
+            max_failure_percentage: mempool_config.broadcast_peer_max_failure_percentage,
+            hysteresis_percentage: mempool_config.broadcast_peer_priority_hysteresis_percentage,
+        }
+    }
+
+    /// Peers already broadcast to stay healthy up to a higher failure rate, by the hysteresis
+    /// margin
+    fn is_healthy(&self, stats: &PeerBroadcastStats, is_broadcast_peer: bool) -> bool {
+        let max_failure_percentage = if is_broadcast_peer {
+            self.max_failure_percentage + self.hysteresis_percentage
+        } else {
+            self.max_failure_percentage
+        };
+        stats.is_healthy(max_failure_percentage)
+    }
+
+    /// Peers already broadcast to have their score lowered (i.e. improved) by the hysteresis
+    /// margin
+    fn score(&self, stats: &PeerBroadcastStats, is_broadcast_peer: bool) -> f64 {
+        let score = stats.score(self.ack_timeout);
+        if is_broadcast_peer {
+            score * 100u64.saturating_sub(self.hysteresis_percentage) as f64 / 100.0
+        } else {
+            score
         }
     }
 
-    /// Provides ordering for peers to send transactions to
+    /// Provides ordering for peers to send transactions to. The last element of the peers tells
+    /// whether they are currently broadcast to.
     fn compare(
         &self,
-        peer_a: &(PeerNetworkId, PeerRole),
-        peer_b: &(PeerNetworkId, PeerRole),
+        peer_a: &(PeerNetworkId, PeerRole, PeerBroadcastStats, bool),
+        peer_b: &(PeerNetworkId, PeerRole, PeerBroadcastStats, bool),
     ) -> Ordering {
-        let peer_network_id_a = peer_a.0;
-        let peer_network_id_b = peer_b.0;
+        let (peer_network_id_a, role_a, stats_a, is_broadcast_peer_a) = peer_a;
+        let (peer_network_id_b, role_b, stats_b, is_broadcast_peer_b) = peer_b;
 
         // Sort by NetworkId
-        match peer_network_id_a
+        peer_network_id_a
             .network_id()
             .cmp(&peer_network_id_b.network_id())
-        {
-            Ordering::Equal => {
-                // Then sort by Role
-                let role_a = peer_a.1;
-                let role_b = peer_b.1;
-                match role_a.cmp(&role_b) {
-                    // Tiebreak by hash_peer_id.
-                    Ordering::Equal => {
-                        let hash_a = self.hash_peer_id(&peer_network_id_a.peer_id());
-                        let hash_b = self.hash_peer_id(&peer_network_id_b.peer_id());
-
-                        hash_a.cmp(&hash_b)
-                    },
-                    ordering => ordering,
-                }
-            },
-            ordering => ordering,
-        }
+            // Then put healthy peers first, so that failing peers are failed over
+            .then_with(|| {
+                let healthy_a = self.is_healthy(stats_a, *is_broadcast_peer_a);
+                let healthy_b = self.is_healthy(stats_b, *is_broadcast_peer_b);
+                healthy_b.cmp(&healthy_a)
+            })
+            // Then sort by Role
+            .then_with(|| role_a.cmp(role_b))
+            // Then put the most responsive peers first
+            .then_with(|| {
+                let score_a = self.score(stats_a, *is_broadcast_peer_a);
+                let score_b = self.score(stats_b, *is_broadcast_peer_b);
+                score_a.partial_cmp(&score_b).unwrap_or(Ordering::Equal)
+            })
+            // Tiebreak by hash_peer_id.
+            .then_with(|| {
+                let hash_a = self.hash_peer_id(&peer_network_id_a.peer_id());
+                let hash_b = self.hash_peer_id(&peer_network_id_b.peer_id());
+
+                hash_a.cmp(&hash_b)
+            })
     }
 
     /// Stable within a mempool instance but random between instances.
@@ -571,25 +649,33 @@ mod test {
 
     #[test]
     fn check_peer_prioritization() {
-        let comparator = PrioritizedPeersComparator::new();
+        let comparator = PrioritizedPeersComparator::new(&MempoolConfig::default());
 
         let peer_id_1 = PeerId::from_hex_literal("0x1").unwrap();
         let peer_id_2 = PeerId::from_hex_literal("0x2").unwrap();
         let val_1 = (
             PeerNetworkId::new(NetworkId::Vfn, peer_id_1),
             PeerRole::Validator,
+            PeerBroadcastStats::default(),
+            false,
         );
         let val_2 = (
             PeerNetworkId::new(NetworkId::Vfn, peer_id_2),
             PeerRole::Validator,
+            PeerBroadcastStats::default(),
+            false,
         );
         let vfn_1 = (
             PeerNetworkId::new(NetworkId::Public, peer_id_1),
             PeerRole::ValidatorFullNode,
+            PeerBroadcastStats::default(),
+            false,
         );
         let preferred_1 = (
             PeerNetworkId::new(NetworkId::Public, peer_id_1),
             PeerRole::PreferredUpstream,
+            PeerBroadcastStats::default(),
+            false,
         );
 
         // NetworkId ordering
@@ -610,4 +696,160 @@ mod test {
         // Same the only equal case
         assert_eq!(Ordering::Equal, comparator.compare(&val_1, &val_1));
     }
+
+    #[test]
+    fn check_peer_prioritization_by_responsiveness() {
+        let comparator = PrioritizedPeersComparator::new(&MempoolConfig::default());
+
+        let peer_id_1 = PeerId::from_hex_literal("0x1").unwrap();
+        let peer_id_2 = PeerId::from_hex_literal("0x2").unwrap();
+        let peer = |peer_id, stats| {
+            (
+                PeerNetworkId::new(NetworkId::Public, peer_id),
+                PeerRole::Upstream,
+                stats,
+                false,
+            )
+        };
+
+        // Lower ack latency is preferred
+        let mut fast_stats = PeerBroadcastStats::default();
+        fast_stats.record_ack(Duration::from_millis(50), false);
+        let mut slow_stats = PeerBroadcastStats::default();
+        slow_stats.record_ack(Duration::from_millis(500), false);
+        let fast = peer(peer_id_1, fast_stats.clone());
+        let slow = peer(peer_id_2, slow_stats);
+        assert_eq!(Ordering::Less, comparator.compare(&fast, &slow));
+        assert_eq!(Ordering::Greater, comparator.compare(&slow, &fast));
+
+        // Peer monitoring latency is used until a broadcast is acked
+        let pinged = peer(peer_id_2, PeerBroadcastStats {
+            ping_latency_secs: Some(0.01),
+            ..PeerBroadcastStats::default()
+        });
+        let unknown = peer(peer_id_1, PeerBroadcastStats::default());
+        assert_eq!(Ordering::Less, comparator.compare(&pinged, &unknown));
+
+        // Failing peers are failed over, even if they are fast and preferred
+        let mut failing_stats = fast_stats;
+        for _ in 0..10 {
+            failing_stats.record_failure();
+        }
+        let failing = (
+            PeerNetworkId::new(NetworkId::Public, peer_id_1),
+            PeerRole::PreferredUpstream,
+            failing_stats.clone(),
+            false,
+        );
+        assert_eq!(Ordering::Greater, comparator.compare(&failing, &slow));
+
+        // And recover once they ack broadcasts again
+        for _ in 0..10 {
+            failing_stats.record_ack(Duration::from_millis(50), false);
+        }
+        let recovered = (
+            PeerNetworkId::new(NetworkId::Public, peer_id_1),
+            PeerRole::PreferredUpstream,
+            failing_stats,
+            false,
+        );
+        assert_eq!(Ordering::Less, comparator.compare(&recovered, &slow));
+
+        // The network still comes first
+        let failing_vfn = (
+            PeerNetworkId::new(NetworkId::Vfn, peer_id_1),
+            PeerRole::Validator,
+            failing.2,
+            false,
+        );
+        assert_eq!(Ordering::Less, comparator.compare(&failing_vfn, &fast));
+    }
+
+    #[test]
+    fn check_peer_prioritization_hysteresis() {
+        let comparator = PrioritizedPeersComparator::new(&MempoolConfig::default());
+
+        let peer_id_1 = PeerId::from_hex_literal("0x1").unwrap();
+        let peer_id_2 = PeerId::from_hex_literal("0x2").unwrap();
+        let peer = |peer_id, ack_latency_ms, is_broadcast_peer| {
+            let mut stats = PeerBroadcastStats::default();
+            stats.record_ack(Duration::from_millis(ack_latency_ms), false);
+            (
+                PeerNetworkId::new(NetworkId::Public, peer_id),
+                PeerRole::Upstream,
+                stats,
+                is_broadcast_peer,
+            )
+        };
+
+        // A slightly faster peer doesn't replace the peer broadcast to
+        let current = peer(peer_id_1, 100, true);
+        let slightly_faster = peer(peer_id_2, 90, false);
+        assert_eq!(
+            Ordering::Less,
+            comparator.compare(&current, &slightly_faster)
+        );
+
+        // But a much faster one does
+        let much_faster = peer(peer_id_2, 50, false);
+        assert_eq!(
+            Ordering::Greater,
+            comparator.compare(&current, &much_faster)
+        );
+
+        // Same for failures, the peer broadcast to stays healthy a bit longer
+        let mut failing_stats = current.2;
+        while failing_stats.is_healthy(comparator.max_failure_percentage) {
+            failing_stats.record_failure();
+        }
+        assert!(comparator.is_healthy(&failing_stats, true));
+        assert!(!comparator.is_healthy(&failing_stats, false));
+    }
+
+    #[test]
+    fn check_peer_broadcast_stats_decay() {
+        let comparator = PrioritizedPeersComparator::new(&MempoolConfig::default());
+
+        let peer_id_1 = PeerId::from_hex_literal("0x1").unwrap();
+        let peer_id_2 = PeerId::from_hex_literal("0x2").unwrap();
+        let mut healthy_stats = PeerBroadcastStats::default();
+        healthy_stats.record_ack(Duration::from_millis(200), false);
+        let healthy = (
+            PeerNetworkId::new(NetworkId::Public, peer_id_2),
+            PeerRole::Upstream,
+            healthy_stats,
+            true,
+        );
+
+        // A demoted peer gets no broadcasts, so no new observations
+        let mut demoted_stats = PeerBroadcastStats {
+            ping_latency_secs: Some(0.01),
+            ..PeerBroadcastStats::default()
+        };
+        demoted_stats.record_ack(Duration::from_secs(1), false);
+        for _ in 0..10 {
+            demoted_stats.record_failure();
+        }
+        let demoted = |stats: &PeerBroadcastStats| {
+            (
+                PeerNetworkId::new(NetworkId::Public, peer_id_1),
+                PeerRole::Upstream,
+                stats.clone(),
+                false,
+            )
+        };
+        assert_eq!(
+            Ordering::Greater,
+            comparator.compare(&demoted(&demoted_stats), &healthy)
+        );
+
+        // But it is prioritized again once its stats decayed enough
+        let mut updates = 0;
+        while comparator.compare(&demoted(&demoted_stats), &healthy) == Ordering::Greater {
+            demoted_stats.decay();
+            updates += 1;
+        }
+        assert!(updates > 10 && updates < 1_000);
+        assert!(demoted_stats.ack_latency_secs.unwrap() < 0.2);
+    }
 }
@@ -36,7 +36,7 @@ use std::{
     pin::Pin,
     sync::Arc,
     task::Waker,
-    time::{Instant, SystemTime},
+    time::{Duration, Instant, SystemTime},
 };
 use tokio::runtime::Handle;
 
@@ -242,6 +242,7 @@ pub type MempoolEventsReceiver = mpsc::Receiver<MempoolClientRequest>;
 pub(crate) struct PeerSyncState {
     pub timeline_id: MultiBucketTimelineIndexIds,
     pub broadcast_info: BroadcastInfo,
+    pub broadcast_stats: PeerBroadcastStats,
     pub metadata: ConnectionMetadata,
 }
 
@@ -250,6 +251,7 @@ impl PeerSyncState {
         PeerSyncState {
             timeline_id: MultiBucketTimelineIndexIds::new(num_broadcast_buckets),
             broadcast_info: BroadcastInfo::new(),
+            broadcast_stats: PeerBroadcastStats::default(),
             metadata,
         }
     }
@@ -384,3 +386,73 @@ impl BroadcastInfo {
         }
     }
 }
+
+/// Weight of the latest observation in the moving averages of `PeerBroadcastStats`.
+const BROADCAST_STATS_EWMA_WEIGHT: f64 = 0.2;
+/// Weight of the decay applied to the stats of a peer on every peer update it isn't broadcast to.
+const BROADCAST_STATS_DECAY_WEIGHT: f64 = 0.02;
+
+/// Observed responsiveness of a remote peer to broadcasts, used to prioritize upstream peers.
+#[derive(Clone, Debug, Default)]
+pub struct PeerBroadcastStats {
+    // Moving average of the time between sending a broadcast and receiving its ack.
+    pub ack_latency_secs: Option<f64>,
+    // Average ping latency of the peer, as reported by peer monitoring.
+    pub ping_latency_secs: Option<f64>,
+    // Moving average of the fraction of broadcasts that failed, i.e. that were not sent, timed
+    // out waiting for an ack, or were acked with a backoff.
+    pub failure_rate: f64,
+}
+
+impl PeerBroadcastStats {
+    pub fn record_ack(&mut self, rtt: Duration, backoff: bool) {
+        let rtt_secs = rtt.as_secs_f64();
+        self.ack_latency_secs = Some(match self.ack_latency_secs {
+            Some(latency) => ewma(latency, rtt_secs),
+            None => rtt_secs,
+        });
+        self.record_outcome(backoff);
+    }
+
+    pub fn record_failure(&mut self) {
+        self.record_outcome(true);
+    }
+
+    fn record_outcome(&mut self, failed: bool) {
+        self.failure_rate = ewma(self.failure_rate, if failed { 1.0 } else { 0.0 });
+    }
+
+    /// Peers that are not broadcast to don't get any new observation, so their stats would stay
+    /// frozen and they would never be prioritized again. Instead, their failure rate slowly decays,
+    /// and their ack latency slowly converges to their ping latency, until they are retried.
+    pub fn decay(&mut self) {
+        self.failure_rate -= BROADCAST_STATS_DECAY_WEIGHT * self.failure_rate;
+        if let (Some(ack_latency), Some(ping_latency)) =
+            (self.ack_latency_secs, self.ping_latency_secs)
+        {
+            self.ack_latency_secs =
+                Some(ack_latency + BROADCAST_STATS_DECAY_WEIGHT * (ping_latency - ack_latency));
+        }
+    }
+
+    /// Whether the peer fails few enough broadcasts to be broadcast to ahead of other peers.
+    pub fn is_healthy(&self, max_failure_percentage: u64) -> bool {
+        self.failure_rate * 100.0 <= max_failure_percentage as f64
+    }
+
+    /// Expected time, in seconds, for a broadcast to the peer to be acked, where every failure
+    /// costs an ack timeout. Lower is better. Peers without any latency observation yet are
+    /// assumed to be as slow as the ack timeout.
+    pub fn score(&self, ack_timeout: Duration) -> f64 {
+        let ack_timeout_secs = ack_timeout.as_secs_f64();
+        let latency_secs = self
+            .ack_latency_secs
+            .or(self.ping_latency_secs)
+            .unwrap_or(ack_timeout_secs);
+        latency_secs + self.failure_rate * ack_timeout_secs
+    }
+}
+
+fn ewma(average: f64, observation: f64) -> f64 {
+    average + BROADCAST_STATS_EWMA_WEIGHT * (observation - average)
+}
@@ -655,6 +655,84 @@ fn test_parking_lot_evict_only_for_ready_txn_insertion() {
     }
 }
 
+#[test]
+fn test_parking_lot_evict_largest_account() {
+    let mut config = NodeConfig::generate_random_config();
+    config.mempool.capacity = 4;
+    let mut pool = CoreMempool::new(&config);
+    for seq in &[2, 3, 4] {
+        add_txn(&mut pool, TestTransaction::new(0, *seq, 1)).unwrap();
+    }
+    add_txn(&mut pool, TestTransaction::new(1, 2, 1)).unwrap();
+
+    // Mempool is full. The highest non-ready txn of the account with the most is evicted.
+    add_txn(&mut pool, TestTransaction::new(2, 0, 1)).unwrap();
+    assert_eq!(pool.get_parking_lot_size(), 3);
+    let transactions = pool.get_transaction_store().get_transactions();
+    assert!(!transactions[&TestTransaction::get_address(0)].contains_key(&4));
+    assert!(transactions[&TestTransaction::get_address(1)].contains_key(&2));
+}
+
+#[test]
+fn test_parking_lot_capacity_per_user() {
+    let mut config = NodeConfig::generate_random_config();
+    config.mempool.parking_lot_capacity_per_user = 2;
+    let mut pool = CoreMempool::new(&config);
+    for seq in &[1, 2] {
+        add_txn(&mut pool, TestTransaction::new(1, *seq, 1)).unwrap();
+    }
+
+    // The account can't park more transactions, but can still add ready ones.
+    assert!(add_txn(&mut pool, TestTransaction::new(1, 4, 1)).is_err());
+    add_txn(&mut pool, TestTransaction::new(1, 0, 1)).unwrap();
+    add_txn(&mut pool, TestTransaction::new(1, 4, 1)).unwrap();
+
+    // Other accounts are not affected.
+    add_txn(&mut pool, TestTransaction::new(0, 1, 1)).unwrap();
+}
+
+#[test]
+fn test_priority_lane_capacity() {
+    let mut config = NodeConfig::generate_random_config();
+    config.mempool.priority_lanes = vec![0, 100];
+    config.mempool.priority_lane_capacities = vec![1, 10];
+    let mut pool = CoreMempool::new(&config);
+
+    // The lowest lane only fits one ready transaction.
+    add_txn(&mut pool, TestTransaction::new(0, 0, 1)).unwrap();
+    assert!(add_txn(&mut pool, TestTransaction::new(1, 0, 99)).is_err());
+
+    // Non-ready transactions don't count towards the lane capacity.
+    add_txn(&mut pool, TestTransaction::new(0, 2, 1)).unwrap();
+
+    // Higher lanes are not affected.
+    add_txn(&mut pool, TestTransaction::new(2, 0, 100)).unwrap();
+}
+
+#[test]
+fn test_priority_lane_eviction() {
+    let mut config = NodeConfig::generate_random_config();
+    config.mempool.capacity = 2;
+    config.mempool.priority_lanes = vec![0, 100];
+    config.mempool.priority_lane_capacities = vec![10, 10];
+    let mut pool = CoreMempool::new(&config);
+    add_txn(&mut pool, TestTransaction::new(0, 0, 1)).unwrap();
+    add_txn(&mut pool, TestTransaction::new(1, 0, 5)).unwrap();
+
+    // Mempool is full, and transactions of the same lane don't evict each other.
+    assert!(add_txn(&mut pool, TestTransaction::new(2, 0, 10)).is_err());
+
+    // The lowest priority transaction of the lowest lane is evicted for a higher lane transaction.
+    add_txn(&mut pool, TestTransaction::new(3, 0, 100)).unwrap();
+    let gas_prices: Vec<_> = pool
+        .get_batch(10, 5120, true, false, vec![])
+        .iter()
+        .map(SignedTransaction::gas_unit_price)
+        .sorted()
+        .collect();
+    assert_eq!(gas_prices, vec![5, 100]);
+}
+
 #[test]
 fn test_gc_ready_transaction() {
     let mut pool = setup_mempool().0;
@@ -5,7 +5,10 @@ use crate::{validator_set::extract_validator_set_updates, DiscoveryError};
 use aptos_config::{config::PeerSet, network_id::NetworkContext};
 use aptos_logger::info;
 use aptos_time_service::{Interval, TimeService, TimeServiceTrait};
-use aptos_types::{account_address::AccountAddress, on_chain_config::ValidatorSet};
+use aptos_types::{
+    account_address::AccountAddress,
+    on_chain_config::{Features, ValidatorSet},
+};
 use futures::{executor::block_on, Stream};
 use std::{
     pin::Pin,
@@ -52,9 +55,18 @@ impl Stream for RestStream {
         Poll::Ready(match response {
             Ok(inner) => {
                 let validator_set = inner.into_inner();
+                // Missing features are treated as all disabled, same as on chain discovery
+                let quic_enabled = block_on(self.rest_client.get_account_resource_bcs::<Features>(
+                    AccountAddress::ONE,
+                    "0x1::features::Features",
+                ))
+                .map_or(false, |features| {
+                    features.into_inner().is_quic_transport_enabled()
+                });
                 Some(Ok(extract_validator_set_updates(
                     self.network_context,
                     validator_set,
+                    quic_enabled,
                 )))
             },
             Err(err) => {
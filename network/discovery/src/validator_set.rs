@@ -15,7 +15,11 @@ use aptos_event_notifications::ReconfigNotificationListener;
 use aptos_logger::prelude::*;
 use aptos_network::{counters::inc_by_with_context, logging::NetworkSchema};
 use aptos_short_hex_str::AsShortHexStr;
-use aptos_types::on_chain_config::{OnChainConfigPayload, OnChainConfigProvider, ValidatorSet};
+use aptos_types::{
+    network_address::{NetworkAddress, Protocol},
+    on_chain_config::{Features, OnChainConfigPayload, OnChainConfigProvider, ValidatorSet},
+    PeerId,
+};
 use futures::Stream;
 use std::{
     collections::HashSet,
@@ -73,7 +77,12 @@ impl<P: OnChainConfigProvider> ValidatorSetStream<P> {
             .get()
             .expect("failed to get ValidatorSet from payload");
 
-        let peer_set = extract_validator_set_updates(self.network_context, node_set);
+        // Missing features are treated as all disabled, same as before the features existed
+        let quic_enabled = payload
+            .get::<Features>()
+            .map_or(false, |features| features.is_quic_transport_enabled());
+
+        let peer_set = extract_validator_set_updates(self.network_context, node_set, quic_enabled);
         // Ensure that the public key matches what's onchain for this peer
         self.find_key_mismatches(
             peer_set
@@ -106,9 +115,12 @@ impl<P: OnChainConfigProvider> Stream for ValidatorSetStream<P> {
 }
 
 /// Extracts a set of ConnectivityRequests from a ValidatorSet which are appropriate for a network with type role.
+/// QUIC addresses are dropped unless `quic_enabled`, i.e. until the `QUIC_TRANSPORT` feature is
+/// enabled on chain, so no node dials them before every node can.
 pub(crate) fn extract_validator_set_updates(
     network_context: NetworkContext,
     node_set: ValidatorSet,
+    quic_enabled: bool,
 ) -> PeerSet {
     let is_validator = network_context.network_id().is_validator_network();
 
@@ -139,6 +151,11 @@ pub(crate) fn extract_validator_set_updates(
                 )
             })
             .unwrap_or_default();
+            let addrs = if quic_enabled {
+                addrs
+            } else {
+                filter_quic_addresses(&network_context, peer_id, addrs)
+            };
 
             let peer_role = if is_validator {
                 PeerRole::Validator
@@ -150,6 +167,34 @@ pub(crate) fn extract_validator_set_updates(
         .collect()
 }
 
+/// Drops the QUIC addresses of a peer, keeping the others in order
+fn filter_quic_addresses(
+    network_context: &NetworkContext,
+    peer_id: PeerId,
+    addrs: Vec<NetworkAddress>,
+) -> Vec<NetworkAddress> {
+    let (quic_addrs, addrs): (Vec<_>, Vec<_>) = addrs.into_iter().partition(|addr| {
+        addr.as_slice()
+            .iter()
+            .any(|proto| matches!(proto, Protocol::Quic(_)))
+    });
+    if !quic_addrs.is_empty() {
+        inc_by_with_context(
+            &DISCOVERY_COUNTS,
+            network_context,
+            "quic_disabled",
+            quic_addrs.len() as u64,
+        );
+        warn!(
+            NetworkSchema::new(network_context),
+            "OnChainDiscovery: Ignoring QUIC addresses, the QUIC transport isn't enabled: peer: {}, addrs: {:?}",
+            peer_id,
+            quic_addrs
+        );
+    }
+    addrs
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -221,6 +266,39 @@ mod tests {
         check_network_key_mismatch_metric(1, &network_context);
     }
 
+    #[test]
+    fn quic_addresses_only_used_once_enabled() {
+        let consensus_pubkey = bls12381::PrivateKey::generate_for_testing().public_key();
+        let pubkey = test_pubkey([0u8; 32]);
+        let peer_id = aptos_types::account_address::from_identity_public_key(pubkey);
+        let tcp_address: NetworkAddress = "/ip4/127.0.0.1/tcp/6180".parse().unwrap();
+        let quic_address: NetworkAddress = "/ip4/127.0.0.1/quic/6180".parse().unwrap();
+        let addresses = vec![
+            quic_address.append_prod_protos(pubkey, HANDSHAKE_VERSION),
+            tcp_address.append_prod_protos(pubkey, HANDSHAKE_VERSION),
+        ];
+        let encoded_addresses = bcs::to_bytes(&addresses).unwrap();
+        let validator_set = ValidatorSet::new(vec![ValidatorInfo::new(
+            peer_id,
+            0,
+            ValidatorConfig::new(
+                consensus_pubkey,
+                encoded_addresses.clone(),
+                encoded_addresses,
+                0,
+            ),
+        )]);
+        let network_context = NetworkContext::mock();
+
+        // Without the feature, only the TCP address is kept
+        let peer_set = extract_validator_set_updates(network_context, validator_set.clone(), false);
+        assert_eq!(peer_set[&peer_id].addresses, addresses[1..].to_vec());
+        assert!(peer_set[&peer_id].keys.contains(&pubkey));
+
+        let peer_set = extract_validator_set_updates(network_context, validator_set, true);
+        assert_eq!(peer_set[&peer_id].addresses, addresses);
+    }
+
     fn check_network_key_mismatch_metric(expected: i64, network_context: &NetworkContext) {
         assert_eq!(
             expected,
@@ -22,7 +22,9 @@ pub mod boxed;
 #[cfg(any(test, feature = "testing", feature = "fuzzing"))]
 pub mod memory;
 pub mod proxy_protocol;
+pub mod quic;
 pub mod tcp;
+pub mod tcp_or_quic;
 
 /// Origin of how a Connection was established.
 #[derive(Clone, Copy, Deserialize, Eq, Hash, PartialEq, Serialize)]
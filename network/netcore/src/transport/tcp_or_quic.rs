@@ -0,0 +1,160 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! TCP or QUIC Transport
+//!
+//! Listens on either a TCP or a QUIC address, and dials both, depending on the address of the
+//! peer. This lets a network move from TCP to QUIC one node at a time: nodes keep reaching the
+//! peers that still listen on TCP while others already listen on QUIC.
+use crate::transport::{
+    quic::{QuicSocket, QuicTransport},
+    tcp::{TcpSocket, TcpTransport},
+    Transport,
+};
+use aptos_types::{
+    network_address::{parse_dns_quic, parse_dns_tcp, parse_ip_quic, parse_ip_tcp, NetworkAddress},
+    PeerId,
+};
+use futures::{
+    future::{Either, Future, FutureExt, TryFutureExt},
+    stream::{Stream, StreamExt, TryStreamExt},
+};
+use std::{io, pin::Pin};
+
+/// A TCP or a QUIC socket.
+pub type TcpOrQuicSocket = Either<TcpSocket, QuicSocket>;
+
+type BoxFuture = Pin<Box<dyn Future<Output = io::Result<TcpOrQuicSocket>> + Send + 'static>>;
+
+/// Transport to build TCP connections to TCP addresses, and QUIC connections to QUIC addresses
+#[derive(Clone, Debug, Default)]
+pub struct TcpOrQuicTransport {
+    pub tcp: TcpTransport,
+    pub quic: QuicTransport,
+}
+
+impl TcpOrQuicTransport {
+    pub fn new(tcp: TcpTransport, quic: QuicTransport) -> Self {
+        Self { tcp, quic }
+    }
+}
+
+impl Transport for TcpOrQuicTransport {
+    type Error = io::Error;
+    type Inbound = BoxFuture;
+    type Listener =
+        Pin<Box<dyn Stream<Item = io::Result<(Self::Inbound, NetworkAddress)>> + Send + 'static>>;
+    type Outbound = BoxFuture;
+    type Output = TcpOrQuicSocket;
+
+    fn listen_on(
+        &self,
+        addr: NetworkAddress,
+    ) -> Result<(Self::Listener, NetworkAddress), Self::Error> {
+        if parse_ip_quic(addr.as_slice()).is_some() {
+            let (listener, listen_addr) = self.quic.listen_on(addr)?;
+            let listener = listener.map_ok(|(inbound, dialer_addr)| {
+                (inbound.map_ok(Either::Right).boxed(), dialer_addr)
+            });
+            Ok((listener.boxed(), listen_addr))
+        } else {
+            // The TCP transport rejects any other address
+            let (listener, listen_addr) = self.tcp.listen_on(addr)?;
+            let listener = listener.map_ok(|(inbound, dialer_addr)| {
+                (inbound.map_ok(Either::Left).boxed(), dialer_addr)
+            });
+            Ok((listener.boxed(), listen_addr))
+        }
+    }
+
+    fn dial(&self, peer_id: PeerId, addr: NetworkAddress) -> Result<Self::Outbound, Self::Error> {
+        let protos = addr.as_slice();
+        if parse_ip_tcp(protos).is_some() || parse_dns_tcp(protos).is_some() {
+            Ok(self.tcp.dial(peer_id, addr)?.map_ok(Either::Left).boxed())
+        } else if parse_ip_quic(protos).is_some() || parse_dns_quic(protos).is_some() {
+            Ok(self.quic.dial(peer_id, addr)?.map_ok(Either::Right).boxed())
+        } else {
+            Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("Invalid NetworkAddress: '{}'", addr),
+            ))
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use aptos_types::network_address::Protocol;
+    use futures::{
+        future::join,
+        io::{AsyncReadExt, AsyncWriteExt},
+    };
+
+    /// Listens with `listener` on `addr`, dials it with `dialer` and sends a message through
+    async fn listen_and_dial(
+        listener: &TcpOrQuicTransport,
+        dialer: &TcpOrQuicTransport,
+        addr: &str,
+    ) -> io::Result<NetworkAddress> {
+        let (listener, addr) = listener.listen_on(addr.parse().unwrap())?;
+        let dial = dialer.dial(PeerId::random(), addr.clone())?;
+        let listener = listener.into_future().then(|(maybe_result, _stream)| {
+            let (incoming, _addr) = maybe_result.unwrap().unwrap();
+            incoming
+        });
+
+        // The QUIC listener only sees the stream once the dialer writes to it.
+        let outgoing = async move {
+            let mut out = dial.await?;
+            out.write_all(b"Earth").await?;
+            out.flush().await?;
+            io::Result::Ok(out)
+        };
+        let incoming = async move {
+            let mut inc = listener.await?;
+            let mut buf = [0; 5];
+            inc.read_exact(&mut buf).await?;
+            assert_eq!(&buf, b"Earth");
+            io::Result::Ok(inc)
+        };
+
+        let (outgoing, incoming) = join(outgoing, incoming).await;
+        outgoing?;
+        incoming?;
+        Ok(addr)
+    }
+
+    #[tokio::test]
+    async fn dial_tcp_and_quic() {
+        let t = TcpOrQuicTransport::default();
+
+        let addr = listen_and_dial(&t, &t, "/ip4/127.0.0.1/tcp/0")
+            .await
+            .unwrap();
+        assert!(matches!(addr.as_slice(), [
+            Protocol::Ip4(_),
+            Protocol::Tcp(_)
+        ]));
+
+        // The same transport dials QUIC peers too, so TCP and QUIC nodes can reach each other.
+        let addr = listen_and_dial(&TcpOrQuicTransport::default(), &t, "/ip4/127.0.0.1/quic/0")
+            .await
+            .unwrap();
+        assert!(matches!(addr.as_slice(), [
+            Protocol::Ip4(_),
+            Protocol::Quic(_)
+        ]));
+    }
+
+    #[test]
+    fn unsupported_multiaddrs() {
+        let t = TcpOrQuicTransport::default();
+
+        let result = t.listen_on("/memory/0".parse().unwrap());
+        assert!(result.is_err());
+
+        let result = t.dial(PeerId::random(), "/memory/22".parse().unwrap());
+        assert!(result.is_err());
+    }
+}
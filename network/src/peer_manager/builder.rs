@@ -24,7 +24,9 @@ use aptos_logger::prelude::*;
 #[cfg(any(test, feature = "testing", feature = "fuzzing"))]
 use aptos_netcore::transport::memory::MemoryTransport;
 use aptos_netcore::transport::{
-    tcp::{TCPBufferCfg, TcpSocket, TcpTransport},
+    quic::QuicTransport,
+    tcp::TCPBufferCfg,
+    tcp_or_quic::{TcpOrQuicSocket, TcpOrQuicTransport},
     Transport,
 };
 use aptos_time_service::TimeService;
@@ -141,12 +143,13 @@ impl PeerManagerContext {
 #[cfg(any(test, feature = "testing", feature = "fuzzing"))]
 type MemoryPeerManager =
     PeerManager<AptosNetTransport<MemoryTransport>, NoiseStream<aptos_memsocket::MemorySocket>>;
-type TcpPeerManager = PeerManager<AptosNetTransport<TcpTransport>, NoiseStream<TcpSocket>>;
+type TcpOrQuicPeerManager =
+    PeerManager<AptosNetTransport<TcpOrQuicTransport>, NoiseStream<TcpOrQuicSocket>>;
 
 enum TransportPeerManager {
     #[cfg(any(test, feature = "testing", feature = "fuzzing"))]
     Memory(MemoryPeerManager),
-    Tcp(TcpPeerManager),
+    TcpOrQuic(TcpOrQuicPeerManager),
 }
 
 pub struct PeerManagerBuilder {
@@ -272,10 +275,12 @@ impl PeerManagerBuilder {
         aptos_tcp_transport.set_tcp_buffers(&tcp_cfg);
 
         self.peer_manager = match self.listen_address.as_slice() {
-            [Ip4(_), Tcp(_)] | [Ip6(_), Tcp(_)] => {
-                Some(TransportPeerManager::Tcp(self.build_with_transport(
+            // Whichever the listen address, peers are dialed over TCP or QUIC depending on
+            // their addresses, so a network can move to QUIC one node at a time
+            [Ip4(_), Tcp(_)] | [Ip6(_), Tcp(_)] | [Ip4(_), Quic(_)] | [Ip6(_), Quic(_)] => {
+                Some(TransportPeerManager::TcpOrQuic(self.build_with_transport(
                     AptosNetTransport::new(
-                        aptos_tcp_transport,
+                        TcpOrQuicTransport::new(aptos_tcp_transport, QuicTransport::default()),
                         self.network_context,
                         self.time_service.clone(),
                         key,
@@ -305,7 +310,8 @@ impl PeerManagerBuilder {
             ))),
             _ => panic!(
                 "{} Unsupported listen_address: '{}', expected '/memory/<port>', \
-                 '/ip4/<addr>/tcp/<port>', or '/ip6/<addr>/tcp/<port>'.",
+                 '/ip4/<addr>/tcp/<port>', '/ip6/<addr>/tcp/<port>', '/ip4/<addr>/quic/<port>', \
+                 or '/ip6/<addr>/quic/<port>'.",
                 self.network_context, self.listen_address
             ),
         };
@@ -375,7 +381,7 @@ impl PeerManagerBuilder {
         {
             #[cfg(any(test, feature = "testing", feature = "fuzzing"))]
             TransportPeerManager::Memory(pm) => self.start_peer_manager(pm, executor),
-            TransportPeerManager::Tcp(pm) => self.start_peer_manager(pm, executor),
+            TransportPeerManager::TcpOrQuic(pm) => self.start_peer_manager(pm, executor),
         }
     }
 
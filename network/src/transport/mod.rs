@@ -24,7 +24,9 @@ use aptos_short_hex_str::AsShortHexStr;
 use aptos_time_service::{timeout, TimeService, TimeServiceTrait};
 use aptos_types::{
     chain_id::ChainId,
-    network_address::{parse_dns_tcp, parse_ip_tcp, parse_memory, NetworkAddress},
+    network_address::{
+        parse_dns_quic, parse_dns_tcp, parse_ip_quic, parse_ip_tcp, parse_memory, NetworkAddress,
+    },
     PeerId,
 };
 use futures::{
@@ -482,13 +484,15 @@ where
         let (base_transport_protos, base_transport_suffix) = parse_ip_tcp(protos)
             .map(|x| (&protos[..2], x.1))
             .or_else(|| parse_dns_tcp(protos).map(|x| (&protos[..2], x.1)))
+            .or_else(|| parse_ip_quic(protos).map(|x| (&protos[..2], x.1)))
+            .or_else(|| parse_dns_quic(protos).map(|x| (&protos[..2], x.1)))
             .or_else(|| parse_memory(protos).map(|x| (&protos[..1], x.1)))
             .ok_or_else(|| {
                 io::Error::new(
                     io::ErrorKind::InvalidInput,
                     format!(
                         "Unexpected dialing network address: '{}', expected: \
-                         memory, ip+tcp, or dns+tcp",
+                         memory, ip+tcp, dns+tcp, ip+quic, or dns+quic",
                         addr
                     ),
                 )
@@ -534,6 +538,10 @@ where
     /// `/dns/<ipaddr>/tcp/<port>` or
     /// `/dns4/<ipaddr>/tcp/<port>` or
     /// `/dns6/<ipaddr>/tcp/<port>`
+    ///
+    /// If the base transport is `QuicTransport`, then `/<base_transport>` is the
+    /// same as for `TcpTransport`, with `/quic/<port>` in place of `/tcp/<port>`.
+    /// `TcpOrQuicTransport` takes either.
     pub fn dial(
         &self,
         peer_id: PeerId,
@@ -580,6 +588,13 @@ where
     ///
     /// `/ip4/<ipaddr>/tcp/<port>` or
     /// `/ip6/<ipaddr>/tcp/<port>`
+    ///
+    /// If the base transport is `QuicTransport`, then we expect:
+    ///
+    /// `/ip4/<ipaddr>/quic/<port>` or
+    /// `/ip6/<ipaddr>/quic/<port>`
+    ///
+    /// If the base transport is `TcpOrQuicTransport`, then we expect either.
     pub fn listen_on(
         &self,
         addr: NetworkAddress,
@@ -12,7 +12,9 @@ use aptos_crypto::{test_utils::TEST_SEED, traits::Uniform, x25519};
 use aptos_infallible::RwLock;
 use aptos_netcore::{
     framing::{read_u16frame, write_u16frame},
-    transport::{memory, ConnectionOrigin, Transport},
+    transport::{
+        memory, quic::QuicTransport, tcp_or_quic::TcpOrQuicTransport, ConnectionOrigin, Transport,
+    },
 };
 use aptos_time_service::MockTimeService;
 use aptos_types::{
@@ -227,6 +229,16 @@ fn expect_ip4_tcp_noise_addr(addr: &NetworkAddress) {
     );
 }
 
+/// Check that the network address matches the format
+/// `"/ip4/<ipaddr>/quic/<port>/noise-ik/<pubkey>/handshake/<version>"`
+fn expect_ip4_quic_noise_addr(addr: &NetworkAddress) {
+    assert!(
+        matches!(addr.as_slice(), [Ip4(_), Quic(_), NoiseIK(_), Handshake(_)]),
+        "addr: '{}'",
+        addr
+    );
+}
+
 fn test_transport_success<TTransport>(
     base_transport: TTransport,
     auth: Auth,
@@ -581,3 +593,46 @@ fn test_tcp_transport_rejects_unauthed_dialer() {
         expect_ip4_tcp_noise_addr,
     );
 }
+
+//////////////////////////////////////
+// AptosNetTransport<QuicTransport> //
+//////////////////////////////////////
+
+#[test]
+fn test_quic_transport_mutual_auth() {
+    test_transport_success(
+        QuicTransport::default(),
+        Auth::Mutual,
+        "/ip4/127.0.0.1/quic/0",
+        expect_ip4_quic_noise_addr,
+    );
+}
+
+#[test]
+fn test_quic_transport_rejects_unauthed_dialer() {
+    test_transport_rejects_unauthed_dialer(
+        QuicTransport::default(),
+        "/ip4/127.0.0.1/quic/0",
+        expect_ip4_quic_noise_addr,
+    );
+}
+
+///////////////////////////////////////////
+// AptosNetTransport<TcpOrQuicTransport> //
+///////////////////////////////////////////
+
+#[test]
+fn test_tcp_or_quic_transport_mutual_auth() {
+    test_transport_success(
+        TcpOrQuicTransport::new(APTOS_TCP_TRANSPORT.clone(), QuicTransport::default()),
+        Auth::Mutual,
+        "/ip4/127.0.0.1/tcp/0",
+        expect_ip4_tcp_noise_addr,
+    );
+    test_transport_success(
+        TcpOrQuicTransport::new(APTOS_TCP_TRANSPORT.clone(), QuicTransport::default()),
+        Auth::Mutual,
+        "/ip4/127.0.0.1/quic/0",
+        expect_ip4_quic_noise_addr,
+    );
+}
@@ -642,6 +642,23 @@ impl<
                 .set_ledger_info_to_sync(target_ledger_info.clone());
         }
 
+        // If we're resuming a previous snapshot sync, load the transaction output
+        // at the target (if it was saved) to avoid fetching it again.
+        if existing_snapshot_progress
+            && self.state_value_syncer.transaction_output_to_sync.is_none()
+        {
+            let transaction_output_to_sync = self
+                .metadata_storage
+                .get_snapshot_sync_target_output(&target_ledger_info)?;
+            if let Some(transaction_output_to_sync) = transaction_output_to_sync {
+                info!(LogSchema::new(LogEntry::Bootstrapper).message(
+                    "Resuming the state snapshot sync with the saved target transaction output."
+                ));
+                self.state_value_syncer
+                    .set_transaction_output_to_sync(transaction_output_to_sync);
+            }
+        }
+
         // Fetch the data that we're missing
         let target_ledger_info_version = target_ledger_info.ledger_info().version();
         let data_stream = if self.state_value_syncer.transaction_output_to_sync.is_none() {
@@ -1246,6 +1263,12 @@ impl<
                     Some(expected_start_version),
                 ) {
                     Ok(()) => {
+                        // Save the transaction output so that the snapshot sync
+                        // can resume without it (e.g., after a reboot).
+                        self.metadata_storage.save_snapshot_sync_target_output(
+                            &ledger_info_to_sync,
+                            &transaction_outputs_with_proof,
+                        )?;
                         self.state_value_syncer
                             .set_transaction_output_to_sync(transaction_outputs_with_proof);
                     },
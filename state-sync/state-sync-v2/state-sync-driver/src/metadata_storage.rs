@@ -12,7 +12,9 @@ use aptos_schemadb::{
     schema::{KeyCodec, ValueCodec},
     ColumnFamilyName, Options, SchemaBatch, DB,
 };
-use aptos_types::ledger_info::LedgerInfoWithSignatures;
+use aptos_types::{
+    ledger_info::LedgerInfoWithSignatures, transaction::TransactionOutputListWithProof,
+};
 use serde::{Deserialize, Serialize};
 use std::{path::Path, sync::Arc, time::Instant};
 
@@ -39,8 +41,26 @@ pub trait MetadataStorageInterface {
     /// started. If no snapshot sync started, None is returned.
     fn previous_snapshot_sync_target(&self) -> Result<Option<LedgerInfoWithSignatures>, Error>;
 
+    /// Returns the (verified) transaction output at the target of the state snapshot
+    /// sync, as saved by `save_snapshot_sync_target_output`. If no transaction output
+    /// was saved for the specified target, None is returned.
+    fn get_snapshot_sync_target_output(
+        &self,
+        target_ledger_info: &LedgerInfoWithSignatures,
+    ) -> Result<Option<TransactionOutputListWithProof>, Error>;
+
+    /// Saves the (verified) transaction output at the target of the state snapshot sync,
+    /// so that an interrupted snapshot sync can resume without refetching it. This also
+    /// marks the snapshot sync for the specified target as started.
+    fn save_snapshot_sync_target_output(
+        &self,
+        target_ledger_info: &LedgerInfoWithSignatures,
+        target_output_with_proof: &TransactionOutputListWithProof,
+    ) -> Result<(), Error>;
+
     /// Updates the last persisted state value index for the state snapshot
-    /// sync at the specified target ledger info.
+    /// sync at the specified target ledger info. If the snapshot sync completed,
+    /// the saved target output is deleted.
     fn update_last_persisted_state_value_index(
         &self,
         target_ledger_info: &LedgerInfoWithSignatures,
@@ -93,27 +113,68 @@ impl PersistentMetadataStorage {
         Self { database }
     }
 
+    /// Returns the metadata value for the given key. Returns None if no value is found.
+    fn get_metadata_value(
+        &self,
+        metadata_key: &MetadataKey,
+    ) -> Result<Option<MetadataValue>, Error> {
+        self.database
+            .get::<MetadataSchema>(metadata_key)
+            .map_err(|error| {
+                Error::StorageError(format!(
+                    "Failed to read metadata value for key: {:?}. Error: {:?}",
+                    metadata_key, error
+                ))
+            })
+    }
+
     /// Returns the existing snapshot sync progress. Returns None if no progress is found.
     fn get_snapshot_progress(&self) -> Result<Option<StateSnapshotProgress>, Error> {
         let metadata_key = MetadataKey::StateSnapshotSync;
-        let maybe_metadata_value =
-            self.database
-                .get::<MetadataSchema>(&metadata_key)
-                .map_err(|error| {
-                    Error::StorageError(format!(
-                        "Failed to read metadata value for key: {:?}. Error: {:?}",
-                        metadata_key, error
-                    ))
-                })?;
-        match maybe_metadata_value {
-            Some(metadata_value) => {
-                let MetadataValue::StateSnapshotSync(snapshot_progress) = metadata_value;
+        match self.get_metadata_value(&metadata_key)? {
+            Some(MetadataValue::StateSnapshotSync(snapshot_progress)) => {
                 Ok(Some(snapshot_progress))
             },
+            Some(metadata_value) => Err(Error::StorageError(format!(
+                "Unexpected metadata value for key: {:?}. Value: {:?}",
+                metadata_key, metadata_value
+            ))),
             None => Ok(None),
         }
     }
 
+    /// Returns the existing snapshot sync target output. Returns None if no output is found.
+    fn get_snapshot_target_output(&self) -> Result<Option<StateSnapshotTargetOutput>, Error> {
+        let metadata_key = MetadataKey::StateSnapshotTargetOutput;
+        match self.get_metadata_value(&metadata_key)? {
+            Some(MetadataValue::StateSnapshotTargetOutput(target_output)) => {
+                Ok(Some(target_output))
+            },
+            Some(metadata_value) => Err(Error::StorageError(format!(
+                "Unexpected metadata value for key: {:?}. Value: {:?}",
+                metadata_key, metadata_value
+            ))),
+            None => Ok(None),
+        }
+    }
+
+    /// Ensures that if any previous snapshot progress exists, it has the given target
+    fn ensure_snapshot_progress_target(
+        &self,
+        target_ledger_info: &LedgerInfoWithSignatures,
+    ) -> Result<Option<StateSnapshotProgress>, Error> {
+        let snapshot_progress = self.get_snapshot_progress()?;
+        if let Some(snapshot_progress) = &snapshot_progress {
+            if target_ledger_info != &snapshot_progress.target_ledger_info {
+                return Err(Error::StorageError(format!("Failed to update the state snapshot progress! \
+                The given target does not match the previously stored target. Given target: {:?}, stored target: {:?}",
+                    target_ledger_info, snapshot_progress.target_ledger_info
+                )));
+            }
+        }
+        Ok(snapshot_progress)
+    }
+
     /// Returns the snapshot sync progress recorded for the specified version.
     /// Returns an error if no progress was found.
     fn get_snapshot_progress_at_target(
@@ -137,21 +198,34 @@ impl PersistentMetadataStorage {
         }
     }
 
-    /// Write the key value pair to the database
-    fn commit_key_value(
+    /// Write the key value pairs to the database, and delete the given
+    /// keys (atomically)
+    fn commit_key_values(
         &self,
-        metadata_key: MetadataKey,
-        metadata_value: MetadataValue,
+        key_values: Vec<(MetadataKey, MetadataValue)>,
+        keys_to_delete: Vec<MetadataKey>,
     ) -> Result<(), Error> {
         // Create the schema batch
         let batch = SchemaBatch::new();
-        batch
-            .put::<MetadataSchema>(&metadata_key, &metadata_value)
-            .map_err(|error| {
-                Error::StorageError(format!(
-                    "Failed to batch put the metadata key and value. Key: {:?}, Value: {:?}. Error: {:?}", metadata_key, metadata_value, error
-                ))
-            })?;
+        for (metadata_key, metadata_value) in key_values {
+            batch
+                .put::<MetadataSchema>(&metadata_key, &metadata_value)
+                .map_err(|error| {
+                    Error::StorageError(format!(
+                        "Failed to batch put the metadata key and value. Key: {:?}, Value: {:?}. Error: {:?}", metadata_key, metadata_value, error
+                    ))
+                })?;
+        }
+        for metadata_key in keys_to_delete {
+            batch
+                .delete::<MetadataSchema>(&metadata_key)
+                .map_err(|error| {
+                    Error::StorageError(format!(
+                        "Failed to batch delete the metadata key: {:?}. Error: {:?}",
+                        metadata_key, error
+                    ))
+                })?;
+        }
 
         // Write the schema batch to the database
         self.database.write_schemas(batch).map_err(|error| {
@@ -204,14 +278,7 @@ impl MetadataStorageInterface for PersistentMetadataStorage {
         snapshot_sync_completed: bool,
     ) -> Result<(), Error> {
         // Ensure that if any previous snapshot progress exists, it has the same target
-        if let Some(snapshot_progress) = self.get_snapshot_progress()? {
-            if target_ledger_info != &snapshot_progress.target_ledger_info {
-                return Err(Error::StorageError(format!("Failed to update the last persisted state value index! \
-                The given target does not match the previously stored target. Given target: {:?}, stored target: {:?}",
-                    target_ledger_info, snapshot_progress.target_ledger_info
-                )));
-            }
-        }
+        self.ensure_snapshot_progress_target(target_ledger_info)?;
 
         // Create the key/value pair
         let metadata_key = MetadataKey::StateSnapshotSync;
@@ -221,8 +288,57 @@ impl MetadataStorageInterface for PersistentMetadataStorage {
             target_ledger_info: target_ledger_info.clone(),
         });
 
+        // Once the snapshot sync completes, the target output is no longer needed
+        let keys_to_delete = if snapshot_sync_completed {
+            vec![MetadataKey::StateSnapshotTargetOutput]
+        } else {
+            vec![]
+        };
+
         // Insert the new key/value pair
-        self.commit_key_value(metadata_key, metadata_value)
+        self.commit_key_values(vec![(metadata_key, metadata_value)], keys_to_delete)
+    }
+
+    fn get_snapshot_sync_target_output(
+        &self,
+        target_ledger_info: &LedgerInfoWithSignatures,
+    ) -> Result<Option<TransactionOutputListWithProof>, Error> {
+        Ok(self
+            .get_snapshot_target_output()?
+            .filter(|target_output| &target_output.target_ledger_info == target_ledger_info)
+            .map(|target_output| target_output.target_output_with_proof))
+    }
+
+    fn save_snapshot_sync_target_output(
+        &self,
+        target_ledger_info: &LedgerInfoWithSignatures,
+        target_output_with_proof: &TransactionOutputListWithProof,
+    ) -> Result<(), Error> {
+        // Ensure that if any previous snapshot progress exists, it has the same target
+        let snapshot_progress = self.ensure_snapshot_progress_target(target_ledger_info)?;
+
+        // Create the key/value pairs, starting the snapshot progress (if required)
+        let mut key_values = vec![(
+            MetadataKey::StateSnapshotTargetOutput,
+            MetadataValue::StateSnapshotTargetOutput(StateSnapshotTargetOutput {
+                target_ledger_info: target_ledger_info.clone(),
+                target_output_with_proof: target_output_with_proof.clone(),
+            }),
+        )];
+        if snapshot_progress.is_none() {
+            // No state values have been persisted yet, so the sync (re)starts at index 0
+            key_values.push((
+                MetadataKey::StateSnapshotSync,
+                MetadataValue::StateSnapshotSync(StateSnapshotProgress {
+                    last_persisted_state_value_index: 0,
+                    snapshot_sync_completed: false,
+                    target_ledger_info: target_ledger_info.clone(),
+                }),
+            ));
+        }
+
+        // Insert the new key/value pairs
+        self.commit_key_values(key_values, vec![])
     }
 }
 
@@ -234,6 +350,13 @@ pub struct StateSnapshotProgress {
     pub snapshot_sync_completed: bool,
 }
 
+/// A simple struct for recording the transaction output at the state snapshot sync target
+#[derive(Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct StateSnapshotTargetOutput {
+    pub target_ledger_info: LedgerInfoWithSignatures,
+    pub target_output_with_proof: TransactionOutputListWithProof,
+}
+
 /// The raw schema format used by the database
 pub mod database_schema {
     use super::*;
@@ -251,7 +374,8 @@ pub mod database_schema {
     #[derive(Debug, Deserialize, Eq, PartialEq, Serialize)]
     #[repr(u8)]
     pub enum MetadataKey {
-        StateSnapshotSync, // A state snapshot sync that was started
+        StateSnapshotSync,         // A state snapshot sync that was started
+        StateSnapshotTargetOutput, // The transaction output at the state snapshot sync target
     }
 
     /// A metadata value that can be inserted into the database
@@ -259,6 +383,7 @@ pub mod database_schema {
     #[repr(u8)]
     pub enum MetadataValue {
         StateSnapshotSync(StateSnapshotProgress), // A state snapshot sync progress marker
+        StateSnapshotTargetOutput(StateSnapshotTargetOutput), // A state snapshot sync target output
     }
 
     impl KeyCodec<MetadataSchema> for MetadataKey {
@@ -201,10 +201,11 @@ impl<
         // Create a shared pending data chunk counter
         let pending_transaction_chunks = Arc::new(AtomicU64::new(0));
 
-        // Spawn the executor that executes/applies storage data chunks
+        // Spawn the executor that verifies and executes/applies storage data chunks
         let runtime = runtime.map(|runtime| runtime.handle().clone());
         let executor_handle = spawn_executor(
             chunk_executor.clone(),
+            driver_config.max_parallel_chunk_verifications as usize,
             error_notification_sender.clone(),
             executor_listener,
             committer_notifier,
@@ -386,19 +387,32 @@ enum StorageDataChunk {
     ),
 }
 
-/// Spawns a dedicated executor that executes/applies storage data chunks
+/// Spawns a dedicated executor that verifies and executes/applies storage data chunks.
+/// The chunk proofs are verified concurrently (the verification doesn't depend on the
+/// previous chunks), but the chunks are executed/applied sequentially and in order.
 fn spawn_executor<ChunkExecutor: ChunkExecutorTrait + 'static>(
     chunk_executor: Arc<ChunkExecutor>,
+    max_parallel_chunk_verifications: usize,
     error_notification_sender: mpsc::UnboundedSender<ErrorNotification>,
-    mut executor_listener: mpsc::Receiver<StorageDataChunk>,
+    executor_listener: mpsc::Receiver<StorageDataChunk>,
     mut committer_notifier: mpsc::Sender<NotificationId>,
     pending_transaction_chunks: Arc<AtomicU64>,
     runtime: Option<Handle>,
 ) -> JoinHandle<()> {
     // Create an executor
     let executor = async move {
-        while let Some(storage_data_chunk) = executor_listener.next().await {
-            // Execute/apply the storage data chunk
+        // Verify the storage data chunks ahead of their execution/application
+        let verifier = chunk_executor.clone();
+        let mut verified_data_chunks = executor_listener
+            .map(|storage_data_chunk| {
+                verify_storage_data_chunk(verifier.clone(), storage_data_chunk)
+            })
+            .buffered(max_parallel_chunk_verifications.max(1));
+
+        while let Some((storage_data_chunk, verification_result)) =
+            verified_data_chunks.next().await
+        {
+            // Execute/apply the storage data chunk (if it was verified)
             let (notification_id, result) = match storage_data_chunk {
                 StorageDataChunk::Transactions(
                     notification_id,
@@ -411,13 +425,18 @@ fn spawn_executor<ChunkExecutor: ChunkExecutorTrait + 'static>(
                         metrics::STORAGE_SYNCHRONIZER_EXECUTE_CHUNK,
                     );
                     let num_transactions = transactions_with_proof.transactions.len();
-                    let result = execute_transaction_chunk(
-                        chunk_executor.clone(),
-                        transactions_with_proof,
-                        target_ledger_info,
-                        end_of_epoch_ledger_info,
-                    )
-                    .await;
+                    let result = match verification_result {
+                        Ok(()) => {
+                            execute_transaction_chunk(
+                                chunk_executor.clone(),
+                                transactions_with_proof,
+                                target_ledger_info,
+                                end_of_epoch_ledger_info,
+                            )
+                            .await
+                        },
+                        Err(error) => Err(error),
+                    };
                     if result.is_ok() {
                         info!(
                             LogSchema::new(LogEntry::StorageSynchronizer).message(&format!(
@@ -454,13 +473,18 @@ fn spawn_executor<ChunkExecutor: ChunkExecutorTrait + 'static>(
                         metrics::STORAGE_SYNCHRONIZER_APPLY_CHUNK,
                     );
                     let num_outputs = outputs_with_proof.transactions_and_outputs.len();
-                    let result = apply_output_chunk(
-                        chunk_executor.clone(),
-                        outputs_with_proof,
-                        target_ledger_info,
-                        end_of_epoch_ledger_info,
-                    )
-                    .await;
+                    let result = match verification_result {
+                        Ok(()) => {
+                            apply_output_chunk(
+                                chunk_executor.clone(),
+                                outputs_with_proof,
+                                target_ledger_info,
+                                end_of_epoch_ledger_info,
+                            )
+                            .await
+                        },
+                        Err(error) => Err(error),
+                    };
                     if result.is_ok() {
                         info!(
                             LogSchema::new(LogEntry::StorageSynchronizer).message(&format!(
@@ -772,7 +796,7 @@ async fn apply_output_chunk<ChunkExecutor: ChunkExecutorTrait + 'static>(
     end_of_epoch_ledger_info: Option<LedgerInfoWithSignatures>,
 ) -> anyhow::Result<()> {
     tokio::task::spawn_blocking(move || {
-        chunk_executor.apply_chunk(
+        chunk_executor.apply_verified_chunk(
             outputs_with_proof,
             &target_ledger_info,
             end_of_epoch_ledger_info.as_ref(),
@@ -792,7 +816,7 @@ async fn execute_transaction_chunk<ChunkExecutor: ChunkExecutorTrait + 'static>(
     end_of_epoch_ledger_info: Option<LedgerInfoWithSignatures>,
 ) -> anyhow::Result<()> {
     tokio::task::spawn_blocking(move || {
-        chunk_executor.execute_chunk(
+        chunk_executor.execute_verified_chunk(
             transactions_with_proof,
             &target_ledger_info,
             end_of_epoch_ledger_info.as_ref(),
@@ -802,6 +826,36 @@ async fn execute_transaction_chunk<ChunkExecutor: ChunkExecutorTrait + 'static>(
     .expect("Spawn_blocking(execute_transaction_chunk) failed!")
 }
 
+/// Spawns a dedicated task that verifies the proofs of the given storage
+/// data chunk, and returns the chunk alongside the verification result.
+/// We use `spawn_blocking` so that the heavy synchronous function doesn't
+/// block the async thread.
+async fn verify_storage_data_chunk<ChunkExecutor: ChunkExecutorTrait + 'static>(
+    chunk_executor: Arc<ChunkExecutor>,
+    storage_data_chunk: StorageDataChunk,
+) -> (StorageDataChunk, anyhow::Result<()>) {
+    tokio::task::spawn_blocking(move || {
+        let _timer = metrics::start_timer(
+            &metrics::STORAGE_SYNCHRONIZER_LATENCIES,
+            metrics::STORAGE_SYNCHRONIZER_VERIFY_CHUNK,
+        );
+        let result = match &storage_data_chunk {
+            StorageDataChunk::Transactions(_, transactions_with_proof, target_ledger_info, _) => {
+                chunk_executor.verify_chunk_proof(transactions_with_proof, target_ledger_info)
+            },
+            StorageDataChunk::TransactionOutputs(_, outputs_with_proof, target_ledger_info, _) => {
+                chunk_executor.verify_output_chunk_proof(outputs_with_proof, target_ledger_info)
+            },
+            // State values are handled by the state snapshot receiver
+            StorageDataChunk::States(..) => Ok(()),
+        };
+        let result = result.map_err(|error| error.context("Failed to verify the chunk proof!"));
+        (storage_data_chunk, result)
+    })
+    .await
+    .expect("Spawn_blocking(verify_storage_data_chunk) failed!")
+}
+
 /// Spawns a dedicated task that commits a data chunk. We use
 /// `spawn_blocking` so that the heavy synchronous function doesn't
 /// block the async thread.
@@ -816,6 +816,69 @@ async fn test_snapshot_sync_existing_state() {
         .unwrap();
 }
 
+#[tokio::test]
+async fn test_snapshot_sync_existing_state_saved_output() {
+    // Create test data
+    let synced_version = GENESIS_TRANSACTION_VERSION; // Genesis is the highest synced
+    let highest_version = 1000000;
+    let highest_ledger_info = create_random_epoch_ending_ledger_info(highest_version, 1);
+    let last_persisted_index = 4567;
+
+    // Create a driver configuration with a genesis waypoint and state syncing
+    let mut driver_configuration = create_full_node_driver_configuration();
+    driver_configuration.config.bootstrapping_mode = BootstrappingMode::DownloadLatestStates;
+
+    // Create the mock streaming client (the saved output shouldn't be fetched again)
+    let mut mock_streaming_client = create_mock_streaming_client();
+    let (_notification_sender_1, data_stream_listener_1) = create_data_stream_listener();
+    mock_streaming_client
+        .expect_get_all_transaction_outputs()
+        .never();
+    mock_streaming_client
+        .expect_get_all_state_values()
+        .times(1)
+        .with(eq(highest_version), eq(Some(last_persisted_index)))
+        .return_once(move |_, _| Ok(data_stream_listener_1));
+
+    // Create the mock metadata storage with a saved target output
+    let mut metadata_storage = MockMetadataStorage::new();
+    let highest_ledger_info_clone = highest_ledger_info.clone();
+    metadata_storage
+        .expect_previous_snapshot_sync_target()
+        .returning(move || Ok(Some(highest_ledger_info_clone.clone())));
+    metadata_storage
+        .expect_is_snapshot_sync_complete()
+        .returning(|_| Ok(false));
+    metadata_storage
+        .expect_get_snapshot_sync_target_output()
+        .with(eq(highest_ledger_info.clone()))
+        .returning(|_| Ok(Some(create_output_list_with_proof())));
+    metadata_storage
+        .expect_get_last_persisted_state_value_index()
+        .returning(move |_| Ok(last_persisted_index));
+
+    // Create the bootstrapper
+    let mut bootstrapper = create_bootstrapper_with_storage(
+        driver_configuration,
+        mock_streaming_client,
+        metadata_storage,
+        synced_version,
+        true,
+    );
+
+    // Insert an epoch ending ledger info into the verified states of the bootstrapper
+    manipulate_verified_epoch_states(&mut bootstrapper, true, true, Some(highest_version));
+
+    // Create a global data summary
+    let mut global_data_summary = create_global_summary(1);
+    global_data_summary.advertised_data.synced_ledger_infos = vec![highest_ledger_info.clone()];
+
+    // Drive progress to resume the state value stream
+    drive_progress(&mut bootstrapper, &global_data_summary, false)
+        .await
+        .unwrap();
+}
+
 #[tokio::test]
 async fn test_snapshot_sync_fresh_state() {
     // Create test data
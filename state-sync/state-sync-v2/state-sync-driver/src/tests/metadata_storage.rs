@@ -5,8 +5,12 @@ use crate::{
     metadata_storage::{
         database_schema::{MetadataKey, MetadataSchema, MetadataValue},
         MetadataStorageInterface, PersistentMetadataStorage, StateSnapshotProgress,
+        StateSnapshotTargetOutput,
+    },
+    tests::utils::{
+        create_epoch_ending_ledger_info, create_ledger_info_at_version,
+        create_output_list_with_proof,
     },
-    tests::utils::{create_epoch_ending_ledger_info, create_ledger_info_at_version},
 };
 use aptos_schemadb::schema::fuzzing::assert_encode_decode;
 use aptos_temppath::TempPath;
@@ -101,6 +105,17 @@ fn test_metadata_schema_encode_decode() {
     );
 }
 
+#[test]
+fn test_metadata_schema_encode_decode_target_output() {
+    assert_encode_decode::<MetadataSchema>(
+        &MetadataKey::StateSnapshotTargetOutput,
+        &MetadataValue::StateSnapshotTargetOutput(StateSnapshotTargetOutput {
+            target_ledger_info: create_epoch_ending_ledger_info(),
+            target_output_with_proof: create_output_list_with_proof(),
+        }),
+    );
+}
+
 #[test]
 fn test_multiple_reads_and_writes() {
     // Create a new metadata storage
@@ -167,3 +182,79 @@ fn test_writes_to_different_targets() {
         .update_last_persisted_state_value_index(&target_ledger_info, 10101, false)
         .unwrap_err();
 }
+
+#[test]
+fn test_save_target_output() {
+    // Create a new metadata storage
+    let tmp_dir = TempPath::new();
+    let metadata_storage = PersistentMetadataStorage::new(tmp_dir.path());
+
+    // Verify no target output is saved
+    let target_ledger_info = create_ledger_info_at_version(12345);
+    assert_none!(metadata_storage
+        .get_snapshot_sync_target_output(&target_ledger_info)
+        .unwrap());
+
+    // Save the target output
+    let target_output_with_proof = create_output_list_with_proof();
+    metadata_storage
+        .save_snapshot_sync_target_output(&target_ledger_info, &target_output_with_proof)
+        .unwrap();
+
+    // Drop the handle to the storage (mimic a reboot)
+    drop(metadata_storage);
+
+    // Create another storage (it should reopen the existing file) and verify the
+    // target output is saved and the snapshot sync has started at index 0.
+    let metadata_storage = PersistentMetadataStorage::new(tmp_dir.path());
+    assert_eq!(
+        Some(target_output_with_proof.clone()),
+        metadata_storage
+            .get_snapshot_sync_target_output(&target_ledger_info)
+            .unwrap()
+    );
+    assert_eq!(
+        Some(target_ledger_info.clone()),
+        metadata_storage.previous_snapshot_sync_target().unwrap()
+    );
+    assert_eq!(
+        0,
+        metadata_storage
+            .get_last_persisted_state_value_index(&target_ledger_info)
+            .unwrap()
+    );
+
+    // Persist some state values and verify saving the target output again doesn't reset the progress
+    metadata_storage
+        .update_last_persisted_state_value_index(&target_ledger_info, 10101, false)
+        .unwrap();
+    metadata_storage
+        .save_snapshot_sync_target_output(&target_ledger_info, &target_output_with_proof)
+        .unwrap();
+    assert_eq!(
+        10101,
+        metadata_storage
+            .get_last_persisted_state_value_index(&target_ledger_info)
+            .unwrap()
+    );
+
+    // Verify no target output is returned for a different target, and that it can't be saved
+    let other_target_ledger_info = create_ledger_info_at_version(200);
+    assert_none!(metadata_storage
+        .get_snapshot_sync_target_output(&other_target_ledger_info)
+        .unwrap());
+    metadata_storage
+        .save_snapshot_sync_target_output(&other_target_ledger_info, &target_output_with_proof)
+        .unwrap_err();
+
+    // Complete the snapshot sync and verify the target output is deleted
+    metadata_storage
+        .update_last_persisted_state_value_index(&target_ledger_info, 20202, true)
+        .unwrap();
+    assert_none!(metadata_storage
+        .get_snapshot_sync_target_output(&target_ledger_info)
+        .unwrap());
+    assert!(metadata_storage
+        .is_snapshot_sync_complete(&target_ledger_info)
+        .unwrap());
+}
@@ -148,6 +148,18 @@ mock! {
             epoch_change_li: Option<&'a LedgerInfoWithSignatures>,
         ) -> anyhow::Result<()>;
 
+        fn verify_chunk_proof(
+            &self,
+            txn_list_with_proof: &TransactionListWithProof,
+            verified_target_li: &LedgerInfoWithSignatures,
+        ) -> Result<()>;
+
+        fn verify_output_chunk_proof(
+            &self,
+            txn_output_list_with_proof: &TransactionOutputListWithProof,
+            verified_target_li: &LedgerInfoWithSignatures,
+        ) -> Result<()>;
+
         fn commit_chunk(&self) -> Result<ChunkCommitNotification>;
 
         fn reset(&self) -> Result<()>;
@@ -328,6 +340,17 @@ mock! {
 
         fn previous_snapshot_sync_target(&self) -> Result<Option<LedgerInfoWithSignatures>, Error>;
 
+        fn get_snapshot_sync_target_output(
+            &self,
+            target_ledger_info: &LedgerInfoWithSignatures,
+        ) -> Result<Option<TransactionOutputListWithProof>, Error>;
+
+        fn save_snapshot_sync_target_output(
+            &self,
+            target_ledger_info: &LedgerInfoWithSignatures,
+            target_output_with_proof: &TransactionOutputListWithProof,
+        ) -> Result<(), Error>;
+
         fn update_last_persisted_state_value_index(
             &self,
             target_ledger_info: &LedgerInfoWithSignatures,
@@ -48,6 +48,10 @@ async fn test_apply_transaction_outputs() {
 
     // Setup the mock executor
     let mut chunk_executor = create_mock_executor();
+    chunk_executor
+        .expect_verify_output_chunk_proof()
+        .with(always(), always())
+        .returning(|_, _| Ok(()));
     chunk_executor
         .expect_apply_chunk()
         .with(always(), always(), always())
@@ -114,6 +118,10 @@ async fn test_apply_transaction_outputs() {
 async fn test_apply_transaction_outputs_error() {
     // Setup the mock executor
     let mut chunk_executor = create_mock_executor();
+    chunk_executor
+        .expect_verify_output_chunk_proof()
+        .with(always(), always())
+        .returning(|_, _| Ok(()));
     chunk_executor
         .expect_apply_chunk()
         .with(always(), always(), always())
@@ -144,6 +152,10 @@ async fn test_apply_transaction_outputs_error() {
 async fn test_commit_chunk_error() {
     // Setup the mock executor
     let mut chunk_executor = create_mock_executor();
+    chunk_executor
+        .expect_verify_chunk_proof()
+        .with(always(), always())
+        .returning(|_, _| Ok(()));
     chunk_executor
         .expect_execute_chunk()
         .with(always(), always(), always())
@@ -181,6 +193,10 @@ async fn test_execute_transactions() {
 
     // Setup the mock executor
     let mut chunk_executor = create_mock_executor();
+    chunk_executor
+        .expect_verify_chunk_proof()
+        .with(always(), always())
+        .returning(|_, _| Ok(()));
     chunk_executor
         .expect_execute_chunk()
         .with(always(), always(), always())
@@ -247,6 +263,10 @@ async fn test_execute_transactions() {
 async fn test_execute_transactions_error() {
     // Setup the mock executor
     let mut chunk_executor = create_mock_executor();
+    chunk_executor
+        .expect_verify_chunk_proof()
+        .with(always(), always())
+        .returning(|_, _| Ok(()));
     chunk_executor
         .expect_execute_chunk()
         .with(always(), always(), always())
@@ -273,6 +293,96 @@ async fn test_execute_transactions_error() {
     verify_no_pending_data(&storage_synchronizer);
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn test_execute_transactions_verification_error() {
+    // Setup the mock executor to fail the verification
+    let mut chunk_executor = create_mock_executor();
+    chunk_executor
+        .expect_verify_chunk_proof()
+        .with(always(), always())
+        .returning(|_, _| Err(format_err!("Failed to verify chunk!")));
+    chunk_executor.expect_execute_chunk().never();
+
+    // Create the storage synchronizer
+    let (_, mut error_listener, _, _, _, mut storage_synchronizer, _, _) =
+        create_storage_synchronizer(chunk_executor, create_mock_reader_writer(None, None));
+
+    // Attempt to execute a chunk of transactions
+    let notification_id = 100;
+    storage_synchronizer
+        .execute_transactions(
+            notification_id,
+            create_transaction_list_with_proof(),
+            create_epoch_ending_ledger_info(),
+            None,
+        )
+        .await
+        .unwrap();
+
+    // Verify we get an error notification and that there's no pending data
+    verify_error_notification(&mut error_listener, notification_id).await;
+    verify_no_pending_data(&storage_synchronizer);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_execute_transactions_in_order() {
+    // Setup the mock executor to verify chunks concurrently but execute them in order
+    let num_chunks = 10;
+    let executed_chunks = Arc::new(Mutex::new(vec![]));
+    let mut chunk_executor = create_mock_executor();
+    chunk_executor
+        .expect_verify_chunk_proof()
+        .with(always(), always())
+        .returning(|txn_list_with_proof, _| {
+            // Make the earlier chunks slower to verify
+            let first_version = txn_list_with_proof.first_transaction_version.unwrap();
+            std::thread::sleep(Duration::from_millis(10 * (10 - first_version)));
+            Ok(())
+        });
+    let executed = executed_chunks.clone();
+    chunk_executor
+        .expect_execute_chunk()
+        .with(always(), always(), always())
+        .returning(move |txn_list_with_proof, _, _| {
+            executed
+                .lock()
+                .push(txn_list_with_proof.first_transaction_version.unwrap());
+            Ok(())
+        });
+    chunk_executor.expect_commit_chunk().returning(|| {
+        Ok(ChunkCommitNotification {
+            committed_events: vec![],
+            committed_transactions: vec![],
+            reconfiguration_occurred: false,
+        })
+    });
+
+    // Create the storage synchronizer
+    let (_, _, _, _, _, mut storage_synchronizer, _, _) =
+        create_storage_synchronizer(chunk_executor, create_mock_reader_writer(None, None));
+
+    // Execute the chunks of transactions
+    for version in 0..num_chunks {
+        let mut transaction_list_with_proof = create_transaction_list_with_proof();
+        transaction_list_with_proof.first_transaction_version = Some(version);
+        storage_synchronizer
+            .execute_transactions(
+                version,
+                transaction_list_with_proof,
+                create_epoch_ending_ledger_info(),
+                None,
+            )
+            .await
+            .unwrap();
+    }
+
+    // Verify the chunks were executed in order
+    while storage_synchronizer.pending_storage_data() {
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    assert_eq!(*executed_chunks.lock(), (0..num_chunks).collect::<Vec<_>>());
+}
+
 #[tokio::test(flavor = "multi_thread")]
 #[should_panic]
 async fn test_initialize_state_synchronizer_missing_info() {
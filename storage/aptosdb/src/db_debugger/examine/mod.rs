@@ -1,20 +1,32 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
+mod print_db_stats;
 mod print_db_versions;
+mod print_ledger_info;
+mod print_state_value;
+mod print_transaction;
 
 use anyhow::Result;
 
 #[derive(clap::Subcommand)]
 #[clap(about = "Examine databases.")]
 pub enum Cmd {
+    PrintDbStats(print_db_stats::Cmd),
     PrintDbVersions(print_db_versions::Cmd),
+    PrintLedgerInfo(print_ledger_info::Cmd),
+    PrintStateValue(print_state_value::Cmd),
+    PrintTransaction(print_transaction::Cmd),
 }
 
 impl Cmd {
     pub fn run(self) -> Result<()> {
         match self {
+            Self::PrintDbStats(cmd) => cmd.run(),
             Self::PrintDbVersions(cmd) => cmd.run(),
+            Self::PrintLedgerInfo(cmd) => cmd.run(),
+            Self::PrintStateValue(cmd) => cmd.run(),
+            Self::PrintTransaction(cmd) => cmd.run(),
         }
     }
 }
@@ -0,0 +1,175 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    db_options::{
+        event_db_column_families, ledger_db_column_families, ledger_metadata_db_column_families,
+        state_kv_db_column_families, state_merkle_db_column_families,
+        transaction_accumulator_db_column_families, transaction_db_column_families,
+        transaction_info_db_column_families, write_set_db_column_families,
+    },
+    ledger_db::{
+        EVENT_DB_NAME, LEDGER_DB_NAME, LEDGER_METADATA_DB_NAME, TRANSACTION_ACCUMULATOR_DB_NAME,
+        TRANSACTION_DB_NAME, TRANSACTION_INFO_DB_NAME, WRITE_SET_DB_NAME,
+    },
+    schema::{
+        ledger_info::LedgerInfoSchema, transaction::TransactionSchema,
+        transaction_info::TransactionInfoSchema, version_data::VersionDataSchema,
+        write_set::WriteSetSchema,
+    },
+    state_kv_db::STATE_KV_METADATA_DB_NAME,
+    state_merkle_db::STATE_MERKLE_METADATA_DB_NAME,
+    AptosDB, NUM_STATE_SHARDS, STATE_MERKLE_DB_NAME,
+};
+use anyhow::Result;
+use aptos_config::config::RocksdbConfigs;
+use aptos_schemadb::{schema::Schema, ColumnFamilyName, ReadOptions, DB};
+use clap::Parser;
+use std::path::PathBuf;
+
+#[derive(Parser)]
+#[clap(
+    about = "Print the size and estimated number of keys of each column family, and the key \
+             ranges of the major schemas."
+)]
+pub struct Cmd {
+    #[clap(long, value_parser)]
+    db_dir: PathBuf,
+
+    #[clap(long)]
+    split_ledger_db: bool,
+
+    #[clap(long)]
+    use_sharded_state_merkle_db: bool,
+}
+
+impl Cmd {
+    pub fn run(self) -> Result<()> {
+        let rocksdb_config = RocksdbConfigs {
+            split_ledger_db: self.split_ledger_db,
+            use_sharded_state_merkle_db: self.use_sharded_state_merkle_db,
+            ..Default::default()
+        };
+        let (ledger_db, state_merkle_db, state_kv_db) = AptosDB::open_dbs(
+            &self.db_dir,
+            rocksdb_config,
+            /*readonly=*/ true,
+            /*max_num_nodes_per_lru_cache_shard=*/ 0,
+        )?;
+
+        if self.split_ledger_db {
+            Self::print_cf_stats(
+                LEDGER_METADATA_DB_NAME,
+                ledger_db.metadata_db(),
+                ledger_metadata_db_column_families(),
+            )?;
+            Self::print_cf_stats(
+                EVENT_DB_NAME,
+                ledger_db.event_db(),
+                event_db_column_families(),
+            )?;
+            Self::print_cf_stats(
+                TRANSACTION_ACCUMULATOR_DB_NAME,
+                ledger_db.transaction_accumulator_db(),
+                transaction_accumulator_db_column_families(),
+            )?;
+            Self::print_cf_stats(
+                TRANSACTION_DB_NAME,
+                ledger_db.transaction_db(),
+                transaction_db_column_families(),
+            )?;
+            Self::print_cf_stats(
+                TRANSACTION_INFO_DB_NAME,
+                ledger_db.transaction_info_db(),
+                transaction_info_db_column_families(),
+            )?;
+            Self::print_cf_stats(
+                WRITE_SET_DB_NAME,
+                ledger_db.write_set_db(),
+                write_set_db_column_families(),
+            )?;
+            Self::print_cf_stats(
+                STATE_KV_METADATA_DB_NAME,
+                state_kv_db.metadata_db(),
+                state_kv_db_column_families(),
+            )?;
+            for shard_id in 0..NUM_STATE_SHARDS as u8 {
+                Self::print_cf_stats(
+                    &format!("state_kv_db_shard_{}", shard_id),
+                    state_kv_db.db_shard(shard_id),
+                    state_kv_db_column_families(),
+                )?;
+            }
+        } else {
+            // Without the split, the state K/V data lives in the ledger db as well.
+            Self::print_cf_stats(
+                LEDGER_DB_NAME,
+                ledger_db.metadata_db(),
+                ledger_db_column_families(),
+            )?;
+        }
+
+        if self.use_sharded_state_merkle_db {
+            Self::print_cf_stats(
+                STATE_MERKLE_METADATA_DB_NAME,
+                state_merkle_db.metadata_db(),
+                state_merkle_db_column_families(),
+            )?;
+            for shard_id in 0..NUM_STATE_SHARDS as u8 {
+                Self::print_cf_stats(
+                    &format!("state_merkle_db_shard_{}", shard_id),
+                    state_merkle_db.db_shard(shard_id),
+                    state_merkle_db_column_families(),
+                )?;
+            }
+        } else {
+            Self::print_cf_stats(
+                STATE_MERKLE_DB_NAME,
+                state_merkle_db.metadata_db(),
+                state_merkle_db_column_families(),
+            )?;
+        }
+
+        println!("Key ranges:");
+        Self::print_key_range::<LedgerInfoSchema>("LedgerInfo (epoch)", ledger_db.metadata_db())?;
+        Self::print_key_range::<VersionDataSchema>("VersionData", ledger_db.metadata_db())?;
+        Self::print_key_range::<TransactionSchema>("Transaction", ledger_db.transaction_db())?;
+        Self::print_key_range::<TransactionInfoSchema>(
+            "TransactionInfo",
+            ledger_db.transaction_info_db(),
+        )?;
+        Self::print_key_range::<WriteSetSchema>("WriteSet", ledger_db.write_set_db())?;
+
+        Ok(())
+    }
+
+    fn print_cf_stats(db_name: &str, db: &DB, cf_names: Vec<ColumnFamilyName>) -> Result<()> {
+        println!("{}:", db_name);
+        let mut total_size = 0;
+        for cf_name in cf_names {
+            let size = db.get_property(cf_name, "rocksdb.total-sst-files-size")?;
+            let num_keys = db.get_property(cf_name, "rocksdb.estimate-num-keys")?;
+            total_size += size;
+            println!(
+                "    {:<40} size: {:>16} bytes, estimated keys: {:>14}",
+                cf_name, size, num_keys
+            );
+        }
+        println!("    {:<40} size: {:>16} bytes", "(total)", total_size);
+        Ok(())
+    }
+
+    fn print_key_range<S: Schema>(name: &str, db: &DB) -> Result<()> {
+        let mut iter = db.iter::<S>(ReadOptions::default())?;
+        iter.seek_to_first();
+        let first = iter.next().transpose()?.map(|kv| kv.0);
+        let mut iter = db.iter::<S>(ReadOptions::default())?;
+        iter.seek_to_last();
+        let last = iter.next().transpose()?.map(|kv| kv.0);
+        match (first, last) {
+            (Some(first), Some(last)) => println!("    {}: [{:?}, {:?}]", name, first, last),
+            _ => println!("    {}: empty", name),
+        }
+        Ok(())
+    }
+}
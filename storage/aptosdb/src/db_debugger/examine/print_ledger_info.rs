@@ -0,0 +1,51 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{schema::ledger_info::LedgerInfoSchema, AptosDB};
+use anyhow::Result;
+use aptos_config::config::RocksdbConfigs;
+use aptos_schemadb::ReadOptions;
+use clap::Parser;
+use std::path::PathBuf;
+
+#[derive(Parser)]
+#[clap(about = "Print the latest LedgerInfo of an epoch, or the latest one overall.")]
+pub struct Cmd {
+    #[clap(long, value_parser)]
+    db_dir: PathBuf,
+
+    #[clap(long)]
+    split_ledger_db: bool,
+
+    #[clap(long)]
+    epoch: Option<u64>,
+}
+
+impl Cmd {
+    pub fn run(self) -> Result<()> {
+        let rocksdb_config = RocksdbConfigs {
+            split_ledger_db: self.split_ledger_db,
+            ..Default::default()
+        };
+        let (ledger_db, _state_merkle_db, _state_kv_db) = AptosDB::open_dbs(
+            &self.db_dir,
+            rocksdb_config,
+            /*readonly=*/ true,
+            /*max_num_nodes_per_lru_cache_shard=*/ 0,
+        )?;
+
+        let ledger_info = match self.epoch {
+            Some(epoch) => ledger_db.metadata_db().get::<LedgerInfoSchema>(&epoch)?,
+            None => {
+                let mut iter = ledger_db
+                    .metadata_db()
+                    .iter::<LedgerInfoSchema>(ReadOptions::default())?;
+                iter.seek_to_last();
+                iter.next().transpose()?.map(|kv| kv.1)
+            },
+        };
+        println!("LedgerInfo: {:?}", ledger_info);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,67 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{schema::state_value::StateValueSchema, AptosDB};
+use anyhow::Result;
+use aptos_config::config::RocksdbConfigs;
+use aptos_schemadb::ReadOptions;
+use aptos_types::{state_store::state_key::StateKey, transaction::Version};
+use clap::Parser;
+use std::path::PathBuf;
+
+#[derive(Parser)]
+#[clap(about = "Print the value of a state key as of a version.")]
+pub struct Cmd {
+    #[clap(long, value_parser)]
+    db_dir: PathBuf,
+
+    #[clap(long)]
+    split_ledger_db: bool,
+
+    /// Hex encoded state key, in the same encoding used as the key in the DB.
+    #[clap(long, value_parser = parse_state_key)]
+    state_key: StateKey,
+
+    #[clap(long)]
+    version: Version,
+}
+
+impl Cmd {
+    pub fn run(self) -> Result<()> {
+        let rocksdb_config = RocksdbConfigs {
+            split_ledger_db: self.split_ledger_db,
+            ..Default::default()
+        };
+        let (_ledger_db, _state_merkle_db, state_kv_db) = AptosDB::open_dbs(
+            &self.db_dir,
+            rocksdb_config,
+            /*readonly=*/ true,
+            /*max_num_nodes_per_lru_cache_shard=*/ 0,
+        )?;
+
+        println!("State key: {:?}", self.state_key);
+
+        let mut read_opts = ReadOptions::default();
+        // We want `None` if the state_key changes in iteration.
+        read_opts.set_prefix_same_as_start(true);
+        let mut iter = state_kv_db
+            .db_shard(self.state_key.get_shard_id())
+            .iter::<StateValueSchema>(read_opts)?;
+        iter.seek(&(self.state_key.clone(), self.version))?;
+        match iter.next().transpose()? {
+            Some(((_, version), Some(value))) => {
+                println!("Value (written at version {}): {:?}", version, value)
+            },
+            Some(((_, version), None)) => println!("Deleted at version {}.", version),
+            None => println!("Not found."),
+        }
+
+        Ok(())
+    }
+}
+
+fn parse_state_key(src: &str) -> Result<StateKey> {
+    Ok(StateKey::decode(&hex::decode(
+        src.trim_start_matches("0x"),
+    )?)?)
+}
@@ -0,0 +1,78 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    schema::{
+        event::EventSchema, transaction::TransactionSchema,
+        transaction_info::TransactionInfoSchema, write_set::WriteSetSchema,
+    },
+    AptosDB,
+};
+use anyhow::Result;
+use aptos_config::config::RocksdbConfigs;
+use aptos_schemadb::ReadOptions;
+use aptos_types::transaction::Version;
+use clap::Parser;
+use std::path::PathBuf;
+
+#[derive(Parser)]
+#[clap(about = "Print the transaction, its info, write set and events at a version.")]
+pub struct Cmd {
+    #[clap(long, value_parser)]
+    db_dir: PathBuf,
+
+    #[clap(long)]
+    split_ledger_db: bool,
+
+    #[clap(long)]
+    version: Version,
+}
+
+impl Cmd {
+    pub fn run(self) -> Result<()> {
+        let rocksdb_config = RocksdbConfigs {
+            split_ledger_db: self.split_ledger_db,
+            ..Default::default()
+        };
+        let (ledger_db, _state_merkle_db, _state_kv_db) = AptosDB::open_dbs(
+            &self.db_dir,
+            rocksdb_config,
+            /*readonly=*/ true,
+            /*max_num_nodes_per_lru_cache_shard=*/ 0,
+        )?;
+
+        println!(
+            "Transaction: {:?}",
+            ledger_db
+                .transaction_db()
+                .get::<TransactionSchema>(&self.version)?
+        );
+        println!(
+            "TransactionInfo: {:?}",
+            ledger_db
+                .transaction_info_db()
+                .get::<TransactionInfoSchema>(&self.version)?
+        );
+        println!(
+            "WriteSet: {:?}",
+            ledger_db
+                .write_set_db()
+                .get::<WriteSetSchema>(&self.version)?
+        );
+
+        println!("Events:");
+        let mut iter = ledger_db
+            .event_db()
+            .iter::<EventSchema>(ReadOptions::default())?;
+        iter.seek(&self.version)?;
+        for res in iter {
+            let ((version, index), event) = res?;
+            if version != self.version {
+                break;
+            }
+            println!("    [{}] {:?}", index, event);
+        }
+
+        Ok(())
+    }
+}
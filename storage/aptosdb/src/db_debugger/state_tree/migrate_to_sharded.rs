@@ -0,0 +1,231 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    db_debugger::common::DbDir,
+    schema::{
+        db_metadata::{DbMetadataKey, DbMetadataSchema},
+        jellyfish_merkle_node::JellyfishMerkleNodeSchema,
+        stale_node_index::StaleNodeIndexSchema,
+        stale_node_index_cross_epoch::StaleNodeIndexCrossEpochSchema,
+    },
+    state_merkle_db::StateMerkleDb,
+    NUM_STATE_SHARDS,
+};
+use anyhow::{ensure, format_err, Result};
+use aptos_config::config::RocksdbConfigs;
+use aptos_jellyfish_merkle::node_type::NodeKey;
+use aptos_schemadb::{schema::Schema, ReadOptions, SchemaBatch, DB};
+use aptos_types::transaction::Version;
+use clap::Parser;
+use std::path::PathBuf;
+
+#[derive(Parser)]
+#[clap(
+    about = "Copy a non-sharded state merkle db into the sharded layout, with the nodes spread \
+             across the shards by the first nibble of their paths. The sharded db is written to \
+             `<output_dir>/state_merkle_db`, which can then replace the `state_merkle_db` dir of \
+             the source db, with `use_sharded_state_merkle_db` turned on."
+)]
+pub struct Cmd {
+    #[clap(flatten)]
+    db_dir: DbDir,
+
+    #[clap(long, value_parser)]
+    output_dir: PathBuf,
+
+    #[clap(long, default_value_t = 10000)]
+    batch_size: usize,
+}
+
+impl Cmd {
+    pub fn run(self) -> Result<()> {
+        ensure!(!self.output_dir.exists(), "Output dir already exists.");
+        ensure!(self.batch_size > 0, "Batch size must be positive.");
+
+        let source_db = self.db_dir.open_state_merkle_db()?;
+        let target_db = StateMerkleDb::new(
+            &self.output_dir,
+            RocksdbConfigs {
+                use_sharded_state_merkle_db: true,
+                ..Default::default()
+            },
+            /*readonly=*/ false,
+            /*max_nodes_per_lru_cache_shard=*/ 0,
+        )?;
+
+        let version = Self::get_commit_progress(&source_db)?;
+        println!("State merkle commit progress: {}", version);
+
+        self.migrate_schema::<JellyfishMerkleNodeSchema>(&source_db, &target_db, |node_key| {
+            node_key.get_shard_id()
+        })?;
+        self.migrate_schema::<StaleNodeIndexSchema>(&source_db, &target_db, |index| {
+            index.node_key.get_shard_id()
+        })?;
+        self.migrate_schema::<StaleNodeIndexCrossEpochSchema>(&source_db, &target_db, |index| {
+            index.node_key.get_shard_id()
+        })?;
+        // The progress of the pruners and the commit progress all live in the metadata db.
+        self.migrate_schema::<DbMetadataSchema>(&source_db, &target_db, |_| None)?;
+
+        for shard_id in 0..NUM_STATE_SHARDS {
+            target_db.commit_single_shard(version, shard_id as u8, SchemaBatch::new())?;
+        }
+        target_db.commit_top_levels(version, SchemaBatch::new())?;
+
+        let expected_root_hash = source_db
+            .get::<JellyfishMerkleNodeSchema>(&NodeKey::new_empty_path(version))?
+            .ok_or_else(|| format_err!("Root node at version {} not found.", version))?
+            .hash();
+        let root_hash = target_db.get_root_hash(version)?;
+        ensure!(
+            root_hash == expected_root_hash,
+            "Root hash mismatch after migration, expected: {}, got: {}.",
+            expected_root_hash,
+            root_hash,
+        );
+        println!("Done. Root hash at version {}: {}", version, root_hash);
+
+        Ok(())
+    }
+
+    fn get_commit_progress(source_db: &DB) -> Result<Version> {
+        if let Some(progress) =
+            source_db.get::<DbMetadataSchema>(&DbMetadataKey::StateMerkleCommitProgress)?
+        {
+            return Ok(progress.expect_version());
+        }
+        // Older DBs don't have the commit progress, fall back to the latest node version.
+        let mut iter = source_db.iter::<JellyfishMerkleNodeSchema>(ReadOptions::default())?;
+        iter.seek_to_last();
+        iter.next()
+            .transpose()?
+            .map(|(node_key, _node)| node_key.version())
+            .ok_or_else(|| format_err!("Empty state merkle db."))
+    }
+
+    fn migrate_schema<S: Schema>(
+        &self,
+        source_db: &DB,
+        target_db: &StateMerkleDb,
+        shard_id_fn: impl Fn(&S::Key) -> Option<u8>,
+    ) -> Result<()> {
+        println!("Migrating {}...", S::COLUMN_FAMILY_NAME);
+
+        let mut metadata_batch = SchemaBatch::new();
+        let mut shard_batches: Vec<_> = (0..NUM_STATE_SHARDS).map(|_| SchemaBatch::new()).collect();
+        let mut num_pending = 0;
+        let mut num_migrated = 0;
+
+        let mut iter = source_db.iter::<S>(ReadOptions::default())?;
+        iter.seek_to_first();
+        for res in iter {
+            let (key, value) = res?;
+            match shard_id_fn(&key) {
+                Some(shard_id) => shard_batches[shard_id as usize].put::<S>(&key, &value)?,
+                None => metadata_batch.put::<S>(&key, &value)?,
+            }
+            num_pending += 1;
+            num_migrated += 1;
+
+            if num_pending == self.batch_size {
+                Self::write_batches(target_db, &mut metadata_batch, &mut shard_batches)?;
+                num_pending = 0;
+            }
+        }
+        Self::write_batches(target_db, &mut metadata_batch, &mut shard_batches)?;
+
+        println!("    {} entries migrated.", num_migrated);
+        Ok(())
+    }
+
+    fn write_batches(
+        target_db: &StateMerkleDb,
+        metadata_batch: &mut SchemaBatch,
+        shard_batches: &mut [SchemaBatch],
+    ) -> Result<()> {
+        target_db
+            .metadata_db()
+            .write_schemas(std::mem::take(metadata_batch))?;
+        for (shard_id, batch) in shard_batches.iter_mut().enumerate() {
+            target_db
+                .db_shard(shard_id as u8)
+                .write_schemas(std::mem::take(batch))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        test_helper::{arb_blocks_to_commit_with_block_nums, update_in_memory_state},
+        AptosDB, STATE_MERKLE_DB_NAME,
+    };
+    use aptos_config::config::{
+        BUFFERED_STATE_TARGET_ITEMS, DEFAULT_MAX_NUM_NODES_PER_LRU_CACHE_SHARD,
+        NO_OP_STORAGE_PRUNER_CONFIG,
+    };
+    use aptos_storage_interface::{DbReader, DbWriter};
+    use aptos_temppath::TempPath;
+    use proptest::prelude::*;
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(5))]
+
+        #[test]
+        fn test_migrate_to_sharded(input in arb_blocks_to_commit_with_block_nums(5, 10)) {
+            let tmp_dir = TempPath::new();
+            let db = AptosDB::new_for_test(&tmp_dir);
+            let mut in_memory_state = db.state_store.buffered_state().lock().current_state().clone();
+            let mut version = 0;
+            for (txns_to_commit, ledger_info_with_sigs) in input.iter() {
+                update_in_memory_state(&mut in_memory_state, txns_to_commit.as_slice());
+                db.save_transactions(txns_to_commit, version, version.checked_sub(1), Some(ledger_info_with_sigs), true, in_memory_state.clone())
+                    .unwrap();
+                version += txns_to_commit.len() as u64;
+            }
+
+            let state_checkpoint_version = db.get_latest_state_checkpoint_version().unwrap().unwrap();
+            let state_leaf_count = db.get_state_leaf_count(state_checkpoint_version).unwrap();
+            let expected_chunk = db.get_state_value_chunk_with_proof(state_checkpoint_version, 0, state_leaf_count).unwrap();
+            drop(db);
+
+            let output_dir = TempPath::new();
+            let cmd = Cmd::try_parse_from([
+                "migrate-to-sharded",
+                "--db-dir",
+                tmp_dir.path().to_str().unwrap(),
+                "--output-dir",
+                output_dir.path().to_str().unwrap(),
+                "--batch-size",
+                "7",
+            ]).unwrap();
+            cmd.run().unwrap();
+
+            // Replace the state merkle db as instructed
+            let state_merkle_db_path = tmp_dir.path().join(STATE_MERKLE_DB_NAME);
+            std::fs::remove_dir_all(&state_merkle_db_path).unwrap();
+            std::fs::rename(output_dir.path().join(STATE_MERKLE_DB_NAME), &state_merkle_db_path).unwrap();
+
+            let db = AptosDB::open(
+                &tmp_dir,
+                /*readonly=*/ false,
+                NO_OP_STORAGE_PRUNER_CONFIG,
+                RocksdbConfigs {
+                    use_sharded_state_merkle_db: true,
+                    ..Default::default()
+                },
+                /*enable_indexer=*/ false,
+                BUFFERED_STATE_TARGET_ITEMS,
+                DEFAULT_MAX_NUM_NODES_PER_LRU_CACHE_SHARD,
+            ).unwrap();
+            prop_assert_eq!(db.get_latest_version().unwrap(), version - 1);
+            prop_assert_eq!(db.get_latest_state_checkpoint_version().unwrap(), Some(state_checkpoint_version));
+            let chunk = db.get_state_value_chunk_with_proof(state_checkpoint_version, 0, state_leaf_count).unwrap();
+            prop_assert_eq!(chunk, expected_chunk);
+        }
+    }
+}
@@ -3,14 +3,17 @@
 
 mod get_path;
 mod get_snapshots;
+mod migrate_to_sharded;
 
 use anyhow::Result;
 
-/// Tool supports listing snapshots before version and printing node in merkel tree with version and nibble path
+/// Tool supports listing snapshots before version, printing node in merkel tree with version and nibble path,
+/// and migrating the state merkle db into the sharded layout
 #[derive(clap::Subcommand)]
 pub enum Cmd {
     GetSnapshots(get_snapshots::Cmd),
     GetPath(get_path::Cmd),
+    MigrateToSharded(migrate_to_sharded::Cmd),
 }
 
 impl Cmd {
@@ -18,6 +21,7 @@ impl Cmd {
         match self {
             Self::GetSnapshots(cmd) => cmd.run(),
             Self::GetPath(cmd) => cmd.run(),
+            Self::MigrateToSharded(cmd) => cmd.run(),
         }
     }
 }
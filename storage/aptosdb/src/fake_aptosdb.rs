@@ -689,6 +689,16 @@ impl DbReader for FakeAptosDB {
             .get_account_transactions(address, seq_num, limit, include_events, ledger_version)
     }
 
+    fn get_account_transaction_version_iterator(
+        &self,
+        address: aptos_types::PeerId,
+        seq_num: u64,
+        ledger_version: Version,
+    ) -> Result<Box<dyn Iterator<Item = Result<(u64, Version)>> + '_>> {
+        self.inner
+            .get_account_transaction_version_iterator(address, seq_num, ledger_version)
+    }
+
     fn get_state_proof_with_ledger_info(
         &self,
         known_version: u64,
@@ -306,8 +306,11 @@ impl AptosDB {
             Arc::clone(&state_merkle_db),
             pruner_config.epoch_snapshot_pruner_config.into(),
         );
-        let state_kv_pruner =
-            StateKvPrunerManager::new(Arc::clone(&state_kv_db), pruner_config.ledger_pruner_config);
+        let state_kv_pruner = StateKvPrunerManager::new(
+            Arc::clone(&state_kv_db),
+            pruner_config.state_kv_pruner_config,
+            pruner_config.ledger_pruner_config.prune_window,
+        );
         let state_store = Arc::new(StateStore::new(
             Arc::clone(&ledger_db),
             Arc::clone(&state_merkle_db),
@@ -1394,6 +1397,25 @@ impl DbReader for AptosDB {
         })
     }
 
+    fn get_account_transaction_version_iterator(
+        &self,
+        address: AccountAddress,
+        seq_num: u64,
+        ledger_version: Version,
+    ) -> Result<Box<dyn Iterator<Item = Result<(u64, Version)>> + '_>> {
+        gauged_api("get_account_transaction_version_iterator", || {
+            let iter = self
+                .transaction_store
+                .get_account_transaction_version_iter(
+                    address,
+                    seq_num,
+                    u64::MAX - seq_num,
+                    ledger_version,
+                )?;
+            Ok(Box::new(iter) as Box<dyn Iterator<Item = Result<(u64, Version)>> + '_>)
+        })
+    }
+
     /// This API is best-effort in that it CANNOT provide absence proof.
     fn get_transaction_by_hash(
         &self,
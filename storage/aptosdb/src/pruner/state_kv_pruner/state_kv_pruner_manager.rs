@@ -10,7 +10,7 @@ use crate::{
     state_kv_db::StateKvDb,
 };
 use anyhow::Result;
-use aptos_config::config::LedgerPrunerConfig;
+use aptos_config::config::StateKvPrunerConfig;
 use aptos_types::transaction::{AtomicVersion, Version};
 use std::sync::{atomic::Ordering, Arc};
 
@@ -45,7 +45,7 @@ impl PrunerManager for StateKvPrunerManager {
     /// Sets pruner target version when necessary.
     fn maybe_set_pruner_target_db_version(&self, latest_version: Version) {
         let min_readable_version = self.get_min_readable_version();
-        // Only wake up the state kv pruner if there are `ledger_pruner_pruning_batch_size` pending
+        // Only wake up the state kv pruner if there are `state_kv_pruner_pruning_batch_size` pending
         if self.is_pruner_enabled()
             && latest_version
                 >= min_readable_version + self.pruning_batch_size as u64 + self.prune_window
@@ -81,11 +81,18 @@ impl PrunerManager for StateKvPrunerManager {
 }
 
 impl StateKvPrunerManager {
-    pub fn new(state_kv_db: Arc<StateKvDb>, state_kv_pruner_config: LedgerPrunerConfig) -> Self {
+    /// The prune window falls back to `ledger_prune_window` if the config doesn't set one.
+    pub fn new(
+        state_kv_db: Arc<StateKvDb>,
+        state_kv_pruner_config: StateKvPrunerConfig,
+        ledger_prune_window: u64,
+    ) -> Self {
+        let prune_window = state_kv_pruner_config.effective_prune_window(ledger_prune_window);
         let pruner_worker = if state_kv_pruner_config.enable {
             Some(Self::init_pruner(
                 Arc::clone(&state_kv_db),
-                state_kv_pruner_config,
+                prune_window,
+                state_kv_pruner_config.batch_size,
             ))
         } else {
             None
@@ -100,7 +107,7 @@ impl StateKvPrunerManager {
 
         Self {
             state_kv_db,
-            prune_window: state_kv_pruner_config.prune_window,
+            prune_window,
             pruner_worker,
             pruning_batch_size: state_kv_pruner_config.batch_size,
             min_readable_version: AtomicVersion::new(min_readable_version),
@@ -109,20 +116,21 @@ impl StateKvPrunerManager {
 
     fn init_pruner(
         state_kv_db: Arc<StateKvDb>,
-        state_kv_pruner_config: LedgerPrunerConfig,
+        prune_window: u64,
+        batch_size: usize,
     ) -> PrunerWorker {
         let pruner =
             Arc::new(StateKvPruner::new(state_kv_db).expect("Failed to create state kv pruner."));
 
         PRUNER_WINDOW
             .with_label_values(&["state_kv_pruner"])
-            .set(state_kv_pruner_config.prune_window as i64);
+            .set(prune_window as i64);
 
         PRUNER_BATCH_SIZE
             .with_label_values(&["state_kv_pruner"])
-            .set(state_kv_pruner_config.batch_size as i64);
+            .set(batch_size as i64);
 
-        PrunerWorker::new(pruner, state_kv_pruner_config.batch_size, "state_kv")
+        PrunerWorker::new(pruner, batch_size, "state_kv")
     }
 
     fn set_pruner_target_db_version(&self, latest_version: Version) {
@@ -10,7 +10,7 @@ use crate::{
     test_helper::{arb_state_kv_sets, update_store},
     AptosDB, PrunerManager, StateKvPrunerManager, StateMerklePrunerManager,
 };
-use aptos_config::config::{LedgerPrunerConfig, StateMerklePrunerConfig};
+use aptos_config::config::{StateKvPrunerConfig, StateMerklePrunerConfig};
 use aptos_crypto::HashValue;
 use aptos_schemadb::{ReadOptions, SchemaBatch};
 use aptos_storage_interface::{jmt_update_refs, jmt_updates, DbReader};
@@ -347,12 +347,15 @@ fn verify_state_value_pruner(inputs: Vec<Vec<(StateKey, Option<StateValue>)>>) {
 
     let mut version = 0;
     let mut current_state_values = HashMap::new();
-    let pruner = StateKvPrunerManager::new(Arc::clone(&db.state_kv_db), LedgerPrunerConfig {
-        enable: true,
-        prune_window: 0,
-        batch_size: 1,
-        user_pruning_window_offset: 0,
-    });
+    let pruner = StateKvPrunerManager::new(
+        Arc::clone(&db.state_kv_db),
+        StateKvPrunerConfig {
+            enable: true,
+            prune_window: Some(0),
+            batch_size: 1,
+        },
+        0,
+    );
     for batch in inputs {
         update_store(store, batch.clone().into_iter(), version);
         for (k, v) in batch.iter() {
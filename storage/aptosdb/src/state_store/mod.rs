@@ -445,7 +445,10 @@ impl StateStore {
         );
         let state_kv_pruner = StateKvPrunerManager::new(
             Arc::clone(&state_kv_db),
-            NO_OP_STORAGE_PRUNER_CONFIG.ledger_pruner_config,
+            NO_OP_STORAGE_PRUNER_CONFIG.state_kv_pruner_config,
+            NO_OP_STORAGE_PRUNER_CONFIG
+                .ledger_pruner_config
+                .prune_window,
         );
         let state_db = Arc::new(StateDb {
             ledger_db,
@@ -837,6 +837,18 @@ pub fn verify_committed_transactions(
                 .unwrap();
             assert_eq!(acct_txns_with_proof.len(), 1);
 
+            let acct_txn_version = db
+                .get_account_transaction_version_iterator(
+                    txn.sender(),
+                    txn.sequence_number(),
+                    ledger_version,
+                )
+                .unwrap()
+                .next()
+                .expect("Should exist.")
+                .unwrap();
+            assert_eq!(acct_txn_version, (txn.sequence_number(), cur_ver));
+
             let txn_list_with_proof = db
                 .get_transactions(cur_ver, 1, ledger_version, true /* fetch_events */)
                 .unwrap();
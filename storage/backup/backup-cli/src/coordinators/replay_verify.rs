@@ -134,6 +134,7 @@ impl ReplayVerifyCoordinator {
             run_mode,
             concurrent_downloads: self.concurrent_downloads,
             replay_concurrency_level: 0, // won't replay, doesn't matter
+            db_dir: None,
         };
 
         if let Some(backup) = state_snapshot {
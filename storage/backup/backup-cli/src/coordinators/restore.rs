@@ -8,6 +8,7 @@ use crate::{
         state_snapshot::restore::{StateSnapshotRestoreController, StateSnapshotRestoreOpt},
         transaction::restore::TransactionRestoreBatchController,
     },
+    coordinators::restore_progress::RestoreProgress,
     metadata,
     metadata::{cache::MetadataCacheOpt, TransactionBackupMeta},
     metrics::restore::{
@@ -22,7 +23,7 @@ use aptos_executor_types::VerifyExecutionMode;
 use aptos_logger::prelude::*;
 use aptos_types::transaction::Version;
 use clap::Parser;
-use std::sync::Arc;
+use std::{path::PathBuf, sync::Arc};
 
 #[derive(Parser)]
 pub struct RestoreCoordinatorOpt {
@@ -104,7 +105,7 @@ impl RestoreCoordinator {
     /// b. replay the txn till the target version
     ///
     /// we are support the resume from any point when the restore is interrupted.
-    async fn run_impl(self) -> Result<()> {
+    async fn run_impl(mut self) -> Result<()> {
         if self.replay_all {
             bail!("--replay--all not supported in this version.");
         }
@@ -112,6 +113,20 @@ impl RestoreCoordinator {
         info!("This tool only guarantees resume from previous in-progress restore. \
         If you want to restore a new DB, please either specify a new target db dir or delete previous in-progress DB in the target db dir.");
 
+        // An interrupted restore is resumed with the plan it recorded, unless that conflicts with
+        // the arguments given now.
+        let progress_path = self.global_opt.db_dir.as_deref().map(RestoreProgress::path);
+        let recorded_progress = match &progress_path {
+            Some(path) => RestoreProgress::load(path).await?,
+            None => None,
+        };
+        if let Some(progress) = &recorded_progress {
+            info!(
+                "Found the progress of an interrupted restore at {:?}, resuming it: {:?}",
+                progress_path, progress
+            );
+        }
+
         let metadata_view = metadata::cache::sync_and_load(
             &self.metadata_cache_opt,
             Arc::clone(&self.storage),
@@ -123,11 +138,31 @@ impl RestoreCoordinator {
         let max_txn_ver = metadata_view
             .max_transaction_version()?
             .ok_or_else(|| anyhow!("No transaction backup found."))?;
-        let target_version = std::cmp::min(self.global_opt.target_version, max_txn_ver);
-        info!(
-            "User specified target version: {}, max transaction version: {}, Target version is set to {}",
-            self.global_opt.target_version, max_txn_ver, target_version
-        );
+        let target_version = if let Some(progress) = &recorded_progress {
+            ensure!(
+                progress.target_version
+                    <= std::cmp::min(self.global_opt.target_version, max_txn_ver),
+                "Recorded target version {} of the interrupted restore is beyond the user specified \
+                target version {} or the max transaction version {}, restore into a new target db \
+                dir instead.",
+                progress.target_version,
+                self.global_opt.target_version,
+                max_txn_ver,
+            );
+            info!(
+                "Resuming restore to the recorded target version {}",
+                progress.target_version
+            );
+            self.global_opt.target_version = progress.target_version;
+            progress.target_version
+        } else {
+            let target_version = std::cmp::min(self.global_opt.target_version, max_txn_ver);
+            info!(
+                "User specified target version: {}, max transaction version: {}, Target version is set to {}",
+                self.global_opt.target_version, max_txn_ver, target_version
+            );
+            target_version
+        };
 
         COORDINATOR_TARGET_VERSION.set(target_version as i64);
         let lhs = self.ledger_history_start_version();
@@ -152,7 +187,15 @@ impl RestoreCoordinator {
             .run_mode
             .get_next_expected_transaction_version()?;
 
-        let kv_snapshot = match self.global_opt.run_mode.get_in_progress_state_kv_snapshot() {
+        let in_progress_kv_snapshot = self.global_opt.run_mode.get_in_progress_state_kv_snapshot();
+        if let Some(progress) = &recorded_progress {
+            progress.verify(
+                db_next_version,
+                in_progress_kv_snapshot.as_ref().ok().copied().flatten(),
+            )?;
+        }
+
+        let kv_snapshot = match in_progress_kv_snapshot {
             Ok(Some(ver)) => {
                 if db_next_version >= ver {
                     // already restored the kv snapshot, no need to restore again
@@ -172,13 +215,21 @@ impl RestoreCoordinator {
                     db_next_version, 0,
                     "DB should be empty if no in-progress state snapshot found"
                 );
-                metadata_view
-                    .select_state_snapshot(std::cmp::min(lhs, max_txn_ver))
-                    .expect("Cannot find any snapshot before ledger history start version")
+                match recorded_progress
+                    .as_ref()
+                    .and_then(|progress| progress.kv_snapshot_version)
+                {
+                    Some(ver) => Some(metadata_view.expect_state_snapshot(ver)?),
+                    None => metadata_view
+                        .select_state_snapshot(std::cmp::min(lhs, max_txn_ver))
+                        .expect("Cannot find any snapshot before ledger history start version"),
+                }
             },
         };
 
-        let tree_snapshot = if let Some((latest_tree_version, _)) = latest_tree_version {
+        let tree_snapshot = if let Some(progress) = &recorded_progress {
+            metadata_view.expect_state_snapshot(progress.tree_snapshot_version)?
+        } else if let Some((latest_tree_version, _)) = latest_tree_version {
             let snapshot = metadata_view.select_state_snapshot(latest_tree_version)?;
 
             ensure!(
@@ -206,6 +257,16 @@ impl RestoreCoordinator {
         } else {
             db_next_version
         };
+        let mut progress = recorded_progress.unwrap_or(RestoreProgress {
+            target_version,
+            kv_snapshot_version: kv_snapshot.as_ref().map(|s| s.version),
+            tree_snapshot_version: tree_snapshot.version,
+            next_transaction_version: db_next_version,
+            tree_snapshot_restored: false,
+        });
+        progress.next_transaction_version = db_next_version;
+        Self::save_progress(&progress_path, &progress).await?;
+
         let transaction_backups =
             metadata_view.select_transaction_backups(txn_start_version, target_version)?;
         let epoch_ending_backups = metadata_view.select_epoch_ending_backups(target_version)?;
@@ -296,6 +357,8 @@ impl RestoreCoordinator {
             .await?;
             // update the expected version for the first phase restore
             db_next_version = tree_snapshot.version;
+            progress.next_transaction_version = db_next_version;
+            Self::save_progress(&progress_path, &progress).await?;
         }
 
         // Phase 2: restore the full tree snapshot and replay till the target version
@@ -310,30 +373,47 @@ impl RestoreCoordinator {
             );
             // phase 2.a: if the tree is not completed, we directly restore from the latest snapshot before target
             if !tree_completed {
-                // For boostrap DB to latest version, we want to use default mode
-                let restore_mode = if db_next_version > 0 {
-                    StateSnapshotRestoreMode::TreeOnly
+                // The root node is written last, so a tree found at the snapshot version is complete.
+                let tree_restored = progress.tree_snapshot_restored
+                    && self
+                        .global_opt
+                        .run_mode
+                        .get_state_snapshot_before(tree_snapshot.version + 1)
+                        .map(|(ver, _)| ver)
+                        == Some(tree_snapshot.version);
+                if tree_restored {
+                    info!(
+                        "Tree snapshot at {} already restored, skipping.",
+                        tree_snapshot.version
+                    );
                 } else {
-                    StateSnapshotRestoreMode::Default
-                };
-                info!(
-                    "Start restoring tree snapshot at {} with db_next_version {}",
-                    tree_snapshot.version, db_next_version
-                );
+                    // For boostrap DB to latest version, we want to use default mode
+                    let restore_mode = if db_next_version > 0 {
+                        StateSnapshotRestoreMode::TreeOnly
+                    } else {
+                        StateSnapshotRestoreMode::Default
+                    };
+                    info!(
+                        "Start restoring tree snapshot at {} with db_next_version {}",
+                        tree_snapshot.version, db_next_version
+                    );
 
-                StateSnapshotRestoreController::new(
-                    StateSnapshotRestoreOpt {
-                        manifest_handle: tree_snapshot.manifest.clone(),
-                        version: tree_snapshot.version,
-                        validate_modules: false,
-                        restore_mode,
-                    },
-                    self.global_opt.clone(),
-                    Arc::clone(&self.storage),
-                    epoch_history.clone(),
-                )
-                .run()
-                .await?;
+                    StateSnapshotRestoreController::new(
+                        StateSnapshotRestoreOpt {
+                            manifest_handle: tree_snapshot.manifest.clone(),
+                            version: tree_snapshot.version,
+                            validate_modules: false,
+                            restore_mode,
+                        },
+                        self.global_opt.clone(),
+                        Arc::clone(&self.storage),
+                        epoch_history.clone(),
+                    )
+                    .run()
+                    .await?;
+                    progress.tree_snapshot_restored = true;
+                    Self::save_progress(&progress_path, &progress).await?;
+                }
                 replay_version = Some((
                     tree_snapshot.version + 1,
                     false, /*replay entire txn including update tree and KV*/
@@ -360,6 +440,11 @@ impl RestoreCoordinator {
             .await?;
         }
 
+        // Nothing to resume once finished.
+        if let Some(path) = &progress_path {
+            RestoreProgress::remove(path).await?;
+        }
+
         Ok(())
     }
 }
@@ -369,6 +454,13 @@ impl RestoreCoordinator {
         self.global_opt.target_version
     }
 
+    async fn save_progress(path: &Option<PathBuf>, progress: &RestoreProgress) -> Result<()> {
+        if let Some(path) = path {
+            progress.save(path).await?;
+        }
+        Ok(())
+    }
+
     fn ledger_history_start_version(&self) -> Version {
         self.ledger_history_start_version
             .unwrap_or_else(|| self.target_version())
@@ -0,0 +1,137 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::{ensure, Context, Result};
+use aptos_types::transaction::Version;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Progress of a `RestoreCoordinator` run, persisted in the target DB dir so that an interrupted
+/// restore can be resumed with the same plan instead of starting over.
+///
+/// The DB itself stays the source of truth for what's been written, this only pins the decisions
+/// made by the first run (the target version and the snapshots selected) and records the phases
+/// known to be finished, which are cross checked against the DB on resume.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct RestoreProgress {
+    pub target_version: Version,
+    pub kv_snapshot_version: Option<Version>,
+    pub tree_snapshot_version: Version,
+    /// All transactions before this version are known to be in the DB.
+    pub next_transaction_version: Version,
+    pub tree_snapshot_restored: bool,
+}
+
+impl RestoreProgress {
+    const FILE_NAME: &'static str = "restore_progress.json";
+
+    pub fn path(db_dir: &Path) -> PathBuf {
+        db_dir.join(Self::FILE_NAME)
+    }
+
+    pub async fn load(path: &Path) -> Result<Option<Self>> {
+        if !tokio::fs::try_exists(path).await? {
+            return Ok(None);
+        }
+        let bytes = tokio::fs::read(path).await?;
+        let progress = serde_json::from_slice(&bytes)
+            .with_context(|| format!("Failed to parse restore progress {:?}.", path))?;
+        Ok(Some(progress))
+    }
+
+    pub async fn save(&self, path: &Path) -> Result<()> {
+        // Write to a temp file and rename, so that a crash never leaves a partial file behind.
+        let tmp_path = path.with_extension("json.tmp");
+        tokio::fs::write(&tmp_path, serde_json::to_vec_pretty(self)?).await?;
+        tokio::fs::rename(&tmp_path, path).await?;
+        Ok(())
+    }
+
+    pub async fn remove(path: &Path) -> Result<()> {
+        if tokio::fs::try_exists(path).await? {
+            tokio::fs::remove_file(path).await?;
+        }
+        Ok(())
+    }
+
+    /// Checks the recorded progress against what's found in the DB.
+    pub fn verify(
+        &self,
+        db_next_version: Version,
+        in_progress_kv_snapshot_version: Option<Version>,
+    ) -> Result<()> {
+        ensure!(
+            db_next_version >= self.next_transaction_version,
+            "DB is behind the recorded restore progress, it's been modified since. \
+            next version in DB: {}, recorded: {}",
+            db_next_version,
+            self.next_transaction_version,
+        );
+        if let (Some(recorded), Some(in_db)) =
+            (self.kv_snapshot_version, in_progress_kv_snapshot_version)
+        {
+            ensure!(
+                recorded == in_db,
+                "In progress state snapshot in DB doesn't match the recorded restore progress. \
+                in DB: {}, recorded: {}",
+                in_db,
+                recorded,
+            );
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::coordinators::restore_progress::RestoreProgress;
+    use aptos_temppath::TempPath;
+    use tokio::runtime::Runtime;
+
+    fn progress() -> RestoreProgress {
+        RestoreProgress {
+            target_version: 1000,
+            kv_snapshot_version: Some(100),
+            tree_snapshot_version: 900,
+            next_transaction_version: 100,
+            tree_snapshot_restored: false,
+        }
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        Runtime::new().unwrap().block_on(async {
+            let dir = TempPath::new();
+            dir.create_as_dir().unwrap();
+            let path = RestoreProgress::path(dir.path());
+
+            assert_eq!(RestoreProgress::load(&path).await.unwrap(), None);
+
+            let mut progress = progress();
+            progress.save(&path).await.unwrap();
+            assert_eq!(
+                RestoreProgress::load(&path).await.unwrap(),
+                Some(progress.clone())
+            );
+
+            progress.next_transaction_version = 900;
+            progress.tree_snapshot_restored = true;
+            progress.save(&path).await.unwrap();
+            assert_eq!(RestoreProgress::load(&path).await.unwrap(), Some(progress));
+
+            RestoreProgress::remove(&path).await.unwrap();
+            assert_eq!(RestoreProgress::load(&path).await.unwrap(), None);
+        });
+    }
+
+    #[test]
+    fn test_verify() {
+        let progress = progress();
+        progress.verify(100, Some(100)).unwrap();
+        progress.verify(500, None).unwrap();
+        // DB rolled back.
+        assert!(progress.verify(99, Some(100)).is_err());
+        // Restoring a different snapshot.
+        assert!(progress.verify(100, Some(200)).is_err());
+    }
+}
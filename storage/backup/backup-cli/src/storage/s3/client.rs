@@ -0,0 +1,468 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::storage::s3::config::{Credentials, S3Config};
+use anyhow::{bail, ensure, format_err, Result};
+use aptos_logger::prelude::*;
+use bytes::Bytes;
+use chrono::{DateTime, Utc};
+use futures::TryStreamExt;
+use percent_encoding::{utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+use regex::Regex;
+use reqwest::{Method, Response, StatusCode, Url};
+use ring::{digest, hmac};
+use std::time::Duration;
+use tokio::io::AsyncRead;
+use tokio_util::compat::FuturesAsyncReadCompatExt;
+
+/// Characters other than the "unreserved" ones are percent encoded, per the SigV4 spec.
+const URI_ENCODE_SET: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'.')
+    .remove(b'_')
+    .remove(b'~');
+
+const SIGNING_ALGORITHM: &str = "AWS4-HMAC-SHA256";
+const AMZ_DATE_FORMAT: &str = "%Y%m%dT%H%M%SZ";
+
+/// A minimal S3 client, signing requests with AWS Signature Version 4.
+pub(super) struct S3Client {
+    config: S3Config,
+    credentials: Credentials,
+    endpoint: Url,
+    client: reqwest::Client,
+}
+
+impl S3Client {
+    const BASE_BACKOFF_MS: u64 = 200;
+    const MAX_BACKOFF_MS: u64 = 10_000;
+
+    pub fn new(config: S3Config) -> Result<Self> {
+        let credentials = config.credentials()?;
+        let endpoint = Url::parse(&config.endpoint)?;
+        ensure!(
+            endpoint.host_str().is_some(),
+            "Invalid endpoint: {}",
+            config.endpoint
+        );
+        Ok(Self {
+            config,
+            credentials,
+            endpoint,
+            client: reqwest::Client::builder()
+                .build()
+                .expect("Http client should build."),
+        })
+    }
+
+    pub fn multipart_part_size(&self) -> usize {
+        self.config.multipart_part_size
+    }
+
+    pub async fn put_object(&self, key: &str, body: Bytes) -> Result<()> {
+        self.send(Method::PUT, Some(key), vec![], vec![], body)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn get_object(&self, key: &str) -> Result<Box<dyn AsyncRead + Send + Unpin>> {
+        let reader = self
+            .send(Method::GET, Some(key), vec![], vec![], Bytes::new())
+            .await?
+            .bytes_stream()
+            .map_err(|e| futures::io::Error::new(futures::io::ErrorKind::Other, e))
+            .into_async_read()
+            .compat();
+        Ok(Box::new(reader))
+    }
+
+    pub async fn delete_object(&self, key: &str) -> Result<()> {
+        self.send(Method::DELETE, Some(key), vec![], vec![], Bytes::new())
+            .await?;
+        Ok(())
+    }
+
+    pub async fn copy_object(&self, src_key: &str, dst_key: &str) -> Result<()> {
+        let copy_source = format!("/{}/{}", self.config.bucket, uri_encode(src_key, false));
+        let body = self
+            .send(
+                Method::PUT,
+                Some(dst_key),
+                vec![],
+                vec![("x-amz-copy-source", copy_source)],
+                Bytes::new(),
+            )
+            .await?
+            .text()
+            .await?;
+        ensure_no_error_in_body(&body)
+    }
+
+    /// Lists keys of all objects with the given prefix.
+    pub async fn list_objects(&self, prefix: &str) -> Result<Vec<String>> {
+        let mut keys = Vec::new();
+        let mut continuation_token = None;
+        loop {
+            let mut query = vec![
+                ("list-type".to_string(), "2".to_string()),
+                ("prefix".to_string(), prefix.to_string()),
+            ];
+            if let Some(token) = continuation_token.take() {
+                query.push(("continuation-token".to_string(), token));
+            }
+            let body = self
+                .send(Method::GET, None, query, vec![], Bytes::new())
+                .await?
+                .text()
+                .await?;
+            keys.extend(xml_values(&body, "Key"));
+            if xml_values(&body, "IsTruncated").first().map(String::as_str) != Some("true") {
+                break;
+            }
+            continuation_token = Some(
+                xml_values(&body, "NextContinuationToken")
+                    .pop()
+                    .ok_or_else(|| format_err!("Truncated listing without continuation token."))?,
+            );
+        }
+        Ok(keys)
+    }
+
+    pub async fn create_multipart_upload(&self, key: &str) -> Result<String> {
+        let body = self
+            .send(
+                Method::POST,
+                Some(key),
+                vec![("uploads".to_string(), String::new())],
+                vec![],
+                Bytes::new(),
+            )
+            .await?
+            .text()
+            .await?;
+        xml_values(&body, "UploadId")
+            .pop()
+            .ok_or_else(|| format_err!("No UploadId in response: {}", body))
+    }
+
+    /// Returns the ETag of the uploaded part.
+    pub async fn upload_part(
+        &self,
+        key: &str,
+        upload_id: &str,
+        part_number: usize,
+        body: Bytes,
+    ) -> Result<String> {
+        let response = self
+            .send(
+                Method::PUT,
+                Some(key),
+                vec![
+                    ("partNumber".to_string(), part_number.to_string()),
+                    ("uploadId".to_string(), upload_id.to_string()),
+                ],
+                vec![],
+                body,
+            )
+            .await?;
+        Ok(response
+            .headers()
+            .get("etag")
+            .ok_or_else(|| format_err!("No ETag for uploaded part {}.", part_number))?
+            .to_str()?
+            .to_string())
+    }
+
+    pub async fn complete_multipart_upload(
+        &self,
+        key: &str,
+        upload_id: &str,
+        etags: &[String],
+    ) -> Result<()> {
+        let parts: String = etags
+            .iter()
+            .enumerate()
+            .map(|(idx, etag)| {
+                format!(
+                    "<Part><PartNumber>{}</PartNumber><ETag>{}</ETag></Part>",
+                    idx + 1,
+                    etag
+                )
+            })
+            .collect();
+        let body = self
+            .send(
+                Method::POST,
+                Some(key),
+                vec![("uploadId".to_string(), upload_id.to_string())],
+                vec![],
+                Bytes::from(format!(
+                    "<CompleteMultipartUpload>{}</CompleteMultipartUpload>",
+                    parts
+                )),
+            )
+            .await?
+            .text()
+            .await?;
+        // The service can respond with 200 and report the error in the body.
+        ensure_no_error_in_body(&body)
+    }
+
+    pub async fn abort_multipart_upload(&self, key: &str, upload_id: &str) -> Result<()> {
+        self.send(
+            Method::DELETE,
+            Some(key),
+            vec![("uploadId".to_string(), upload_id.to_string())],
+            vec![],
+            Bytes::new(),
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Path style request path, under any path of the endpoint (e.g. behind a reverse proxy).
+    pub(super) fn request_path(&self, key: Option<&str>) -> String {
+        let endpoint_path = self.endpoint.path().trim_end_matches('/');
+        match key {
+            Some(key) => format!(
+                "{}/{}/{}",
+                endpoint_path,
+                self.config.bucket,
+                uri_encode(key, false)
+            ),
+            None => format!("{}/{}", endpoint_path, self.config.bucket),
+        }
+    }
+
+    async fn send(
+        &self,
+        method: Method,
+        key: Option<&str>,
+        query: Vec<(String, String)>,
+        headers: Vec<(&'static str, String)>,
+        body: Bytes,
+    ) -> Result<Response> {
+        let path = self.request_path(key);
+        let query_string = canonical_query_string(&query);
+        let mut url = self.endpoint.clone();
+        url.set_path(&path);
+        url.set_query(
+            if query_string.is_empty() {
+                None
+            } else {
+                Some(&query_string)
+            },
+        );
+
+        let mut attempt = 0;
+        loop {
+            let err = match self
+                .try_send(method.clone(), &url, &path, &query, &headers, body.clone())
+                .await
+            {
+                Ok(response) if response.status().is_success() => return Ok(response),
+                Ok(response) => {
+                    let status = response.status();
+                    let text = response.text().await.unwrap_or_default();
+                    let err = format_err!("{} {} failed: {} {}", method, path, status, text);
+                    if !is_retryable(status) {
+                        return Err(err);
+                    }
+                    err
+                },
+                Err(err) => err,
+            };
+
+            if attempt >= self.config.max_retries {
+                bail!("Giving up after {} retries: {}", attempt, err);
+            }
+            let backoff_ms = (Self::BASE_BACKOFF_MS << attempt).min(Self::MAX_BACKOFF_MS);
+            warn!(
+                error = %err,
+                attempt = attempt,
+                backoff_ms = backoff_ms,
+                "S3 request failed, retrying."
+            );
+            tokio::time::sleep(Duration::from_millis(backoff_ms)).await;
+            attempt += 1;
+        }
+    }
+
+    async fn try_send(
+        &self,
+        method: Method,
+        url: &Url,
+        path: &str,
+        query: &[(String, String)],
+        headers: &[(&'static str, String)],
+        body: Bytes,
+    ) -> Result<Response> {
+        let now = Utc::now();
+        let payload_hash = sha256_hex(&body);
+        let mut headers = headers.to_vec();
+        headers.push(("x-amz-content-sha256", payload_hash.clone()));
+        headers.push(("x-amz-date", now.format(AMZ_DATE_FORMAT).to_string()));
+        if let Some(token) = &self.credentials.session_token {
+            headers.push(("x-amz-security-token", token.clone()));
+        }
+
+        let authorization = authorization_header(
+            &self.credentials,
+            &self.config.region,
+            method.as_str(),
+            path,
+            query,
+            &host_header(url),
+            &headers,
+            &payload_hash,
+            now,
+        );
+
+        let mut request = self.client.request(method, url.clone()).body(body);
+        for (name, value) in headers {
+            request = request.header(name, value);
+        }
+        Ok(request
+            .header("authorization", authorization)
+            .send()
+            .await?)
+    }
+}
+
+fn is_retryable(status: StatusCode) -> bool {
+    status.is_server_error()
+        || status == StatusCode::TOO_MANY_REQUESTS
+        || status == StatusCode::REQUEST_TIMEOUT
+}
+
+fn ensure_no_error_in_body(body: &str) -> Result<()> {
+    ensure!(!body.contains("<Error>"), "Request failed: {}", body);
+    Ok(())
+}
+
+/// Extracts the text of all elements with the given tag, good enough for the flat responses of
+/// the S3 APIs used here.
+pub(super) fn xml_values(xml: &str, tag: &str) -> Vec<String> {
+    const UNESCAPES: [(&str, &str); 5] = [
+        ("&lt;", "<"),
+        ("&gt;", ">"),
+        ("&quot;", "\""),
+        ("&apos;", "'"),
+        ("&amp;", "&"),
+    ];
+
+    let re = Regex::new(&format!("<{tag}>(.*?)</{tag}>")).expect("Tag should be a valid regex.");
+    re.captures_iter(xml)
+        .map(|cap| {
+            UNESCAPES
+                .iter()
+                .fold(cap[1].to_string(), |s, (from, to)| s.replace(from, to))
+        })
+        .collect()
+}
+
+pub(super) fn uri_encode(s: &str, encode_slash: bool) -> String {
+    if encode_slash {
+        utf8_percent_encode(s, URI_ENCODE_SET).to_string()
+    } else {
+        s.split('/')
+            .map(|segment| uri_encode(segment, true))
+            .collect::<Vec<_>>()
+            .join("/")
+    }
+}
+
+fn canonical_query_string(query: &[(String, String)]) -> String {
+    let mut encoded: Vec<_> = query
+        .iter()
+        .map(|(k, v)| (uri_encode(k, true), uri_encode(v, true)))
+        .collect();
+    encoded.sort();
+    encoded
+        .into_iter()
+        .map(|(k, v)| format!("{}={}", k, v))
+        .collect::<Vec<_>>()
+        .join("&")
+}
+
+fn host_header(url: &Url) -> String {
+    let host = url.host_str().expect("Endpoint checked to have a host.");
+    match url.port() {
+        Some(port) => format!("{}:{}", host, port),
+        None => host.to_string(),
+    }
+}
+
+fn sha256_hex(data: &[u8]) -> String {
+    hex::encode(digest::digest(&digest::SHA256, data))
+}
+
+fn hmac_sha256(key: &[u8], data: &str) -> Vec<u8> {
+    hmac::sign(&hmac::Key::new(hmac::HMAC_SHA256, key), data.as_bytes())
+        .as_ref()
+        .to_vec()
+}
+
+/// Calculates the value of the "Authorization" header, per AWS Signature Version 4. `headers`
+/// must include all "x-amz-*" headers to be sent, including "x-amz-date" which must match `now`.
+/// They are all signed, together with "host".
+#[allow(clippy::too_many_arguments)]
+pub(super) fn authorization_header(
+    credentials: &Credentials,
+    region: &str,
+    method: &str,
+    canonical_uri: &str,
+    query: &[(String, String)],
+    host: &str,
+    headers: &[(&str, String)],
+    payload_hash: &str,
+    now: DateTime<Utc>,
+) -> String {
+    let amz_date = now.format(AMZ_DATE_FORMAT).to_string();
+    let date = now.format("%Y%m%d").to_string();
+    let scope = format!("{}/{}/s3/aws4_request", date, region);
+
+    let mut canonical_headers: Vec<_> = headers
+        .iter()
+        .map(|(name, value)| (name.to_lowercase(), value.trim().to_string()))
+        .chain(std::iter::once(("host".to_string(), host.to_string())))
+        .collect();
+    canonical_headers.sort();
+    let signed_headers = canonical_headers
+        .iter()
+        .map(|(name, _)| name.as_str())
+        .collect::<Vec<_>>()
+        .join(";");
+
+    let canonical_request = [
+        method.to_string(),
+        canonical_uri.to_string(),
+        canonical_query_string(query),
+        canonical_headers
+            .iter()
+            .map(|(name, value)| format!("{}:{}\n", name, value))
+            .collect(),
+        signed_headers.clone(),
+        payload_hash.to_string(),
+    ]
+    .join("\n");
+
+    let string_to_sign = [
+        SIGNING_ALGORITHM.to_string(),
+        amz_date,
+        scope.clone(),
+        sha256_hex(canonical_request.as_bytes()),
+    ]
+    .join("\n");
+
+    let signing_key = [date.as_str(), region, "s3", "aws4_request"].iter().fold(
+        format!("AWS4{}", credentials.secret_access_key).into_bytes(),
+        |key, data| hmac_sha256(&key, data),
+    );
+    let signature = hex::encode(hmac_sha256(&signing_key, &string_to_sign));
+
+    format!(
+        "{} Credential={}/{}, SignedHeaders={}, Signature={}",
+        SIGNING_ALGORITHM, credentials.access_key_id, scope, signed_headers, signature
+    )
+}
@@ -0,0 +1,108 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::utils::error_notes::ErrorNotes;
+use anyhow::{ensure, format_err, Result};
+use serde::Deserialize;
+use std::path::Path;
+use tokio::io::AsyncReadExt;
+
+/// S3 requires all parts of a multipart upload except for the last one to be at least 5MiB.
+const MIN_MULTIPART_PART_SIZE: usize = 5 * 1024 * 1024;
+
+#[derive(Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct S3Config {
+    /// Endpoint of the service, e.g. "https://s3.us-west-2.amazonaws.com", or the endpoint of any
+    /// S3 compatible storage. Objects are addressed in the path style, i.e.
+    /// "<endpoint>/<bucket>/<key>".
+    pub endpoint: String,
+    /// Region used for signing requests.
+    #[serde(default = "default_region")]
+    pub region: String,
+    pub bucket: String,
+    /// Everything is stored under this prefix in the bucket.
+    #[serde(default)]
+    pub prefix: String,
+    /// Falls back to the AWS_ACCESS_KEY_ID env var if not set.
+    #[serde(default)]
+    pub access_key_id: Option<String>,
+    /// Falls back to the AWS_SECRET_ACCESS_KEY env var if not set.
+    #[serde(default)]
+    pub secret_access_key: Option<String>,
+    /// Falls back to the AWS_SESSION_TOKEN env var if not set, optional.
+    #[serde(default)]
+    pub session_token: Option<String>,
+    /// Files larger than this are uploaded in multiple parts of this size. Each part in flight is
+    /// held in memory.
+    #[serde(default = "default_multipart_part_size")]
+    pub multipart_part_size: usize,
+    /// Number of times a failed request is retried, with exponential backoff.
+    #[serde(default = "default_max_retries")]
+    pub max_retries: usize,
+}
+
+fn default_region() -> String {
+    "us-east-1".to_string()
+}
+
+fn default_multipart_part_size() -> usize {
+    16 * 1024 * 1024
+}
+
+fn default_max_retries() -> usize {
+    5
+}
+
+#[derive(Clone)]
+pub struct Credentials {
+    pub access_key_id: String,
+    pub secret_access_key: String,
+    pub session_token: Option<String>,
+}
+
+impl S3Config {
+    pub async fn load_from_file(path: &Path) -> Result<Self> {
+        let path_str = path.to_str().unwrap_or_default();
+        let mut file = tokio::fs::File::open(path).await.err_notes(path_str)?;
+        let mut content = Vec::new();
+        file.read_to_end(&mut content).await.err_notes(path_str)?;
+
+        Self::validate(serde_yaml::from_slice(&content)?)
+    }
+
+    pub fn load_from_str(content: &str) -> Result<Self> {
+        Self::validate(serde_yaml::from_str(content)?)
+    }
+
+    fn validate(config: Self) -> Result<Self> {
+        ensure!(
+            config.multipart_part_size >= MIN_MULTIPART_PART_SIZE,
+            "multipart_part_size must be at least {} bytes.",
+            MIN_MULTIPART_PART_SIZE,
+        );
+        ensure!(!config.bucket.is_empty(), "bucket must be set.");
+        Ok(config)
+    }
+
+    pub fn credentials(&self) -> Result<Credentials> {
+        Ok(Credentials {
+            access_key_id: Self::config_or_env(&self.access_key_id, "AWS_ACCESS_KEY_ID")?,
+            secret_access_key: Self::config_or_env(
+                &self.secret_access_key,
+                "AWS_SECRET_ACCESS_KEY",
+            )?,
+            session_token: self
+                .session_token
+                .clone()
+                .or_else(|| std::env::var("AWS_SESSION_TOKEN").ok()),
+        })
+    }
+
+    fn config_or_env(value: &Option<String>, env_var: &str) -> Result<String> {
+        value
+            .clone()
+            .or_else(|| std::env::var(env_var).ok())
+            .ok_or_else(|| format_err!("Not set in config, nor by the {} env var.", env_var))
+    }
+}
@@ -0,0 +1,299 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+mod client;
+pub mod config;
+
+#[cfg(test)]
+mod tests;
+
+use crate::storage::{
+    s3::{client::S3Client, config::S3Config},
+    BackupHandle, BackupHandleRef, BackupStorage, FileHandle, FileHandleRef, ShellSafeName,
+    TextLine,
+};
+use anyhow::{format_err, Result};
+use aptos_logger::prelude::*;
+use async_trait::async_trait;
+use clap::Parser;
+use futures::{ready, Future};
+use serde::{Deserialize, Serialize};
+use std::{
+    ffi::OsStr,
+    path::{Path, PathBuf},
+    pin::Pin,
+    str::FromStr,
+    sync::Arc,
+    task::{Context, Poll},
+};
+use tokio::{
+    io::{AsyncRead, AsyncReadExt, AsyncWrite, DuplexStream},
+    sync::oneshot,
+    task::JoinHandle,
+};
+
+#[derive(Parser, Clone, Debug, Serialize, Deserialize)]
+pub struct S3Opt {
+    #[clap(long = "config", help = "Config file for the S3 backup store.")]
+    config: PathBuf,
+}
+
+impl FromStr for S3Opt {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(S3Opt {
+            config: PathBuf::from(s),
+        })
+    }
+}
+
+/// A storage backend talking to S3 or any S3 compatible object storage directly, see `S3Config`.
+/// Files are laid out the same way as in `LocalFs`, under the configured prefix in the bucket.
+pub struct S3 {
+    client: Arc<S3Client>,
+    prefix: String,
+}
+
+impl S3 {
+    const METADATA_BACKUP_DIR: &'static str = "metadata_backup";
+    const METADATA_DIR: &'static str = "metadata";
+    /// Size of the in memory pipe between the writer and the uploading task.
+    const PIPE_SIZE: usize = 1024 * 1024;
+
+    pub fn new(config: S3Config) -> Result<Self> {
+        let prefix = config.prefix.trim_matches('/').to_string();
+        Ok(Self {
+            client: Arc::new(S3Client::new(config)?),
+            prefix,
+        })
+    }
+
+    pub async fn new_with_opt(opt: S3Opt) -> Result<Self> {
+        Self::new(S3Config::load_from_file(&opt.config).await?)
+    }
+
+    fn object_key(&self, file_handle: &FileHandleRef) -> String {
+        if self.prefix.is_empty() {
+            file_handle.to_string()
+        } else {
+            format!("{}/{}", self.prefix, file_handle)
+        }
+    }
+
+    fn file_handle(&self, object_key: &str) -> Result<FileHandle> {
+        if self.prefix.is_empty() {
+            Ok(object_key.to_string())
+        } else {
+            object_key
+                .strip_prefix(&self.prefix)
+                .and_then(|s| s.strip_prefix('/'))
+                .map(str::to_string)
+                .ok_or_else(|| format_err!("Unexpected object key: {}", object_key))
+        }
+    }
+
+    /// Reads from the pipe and uploads in a single request if the content fits in one part,
+    /// otherwise in a multipart upload. The upload is only completed if `finished` is signaled,
+    /// i.e. the writer was shut down rather than dropped half way.
+    async fn upload(
+        client: Arc<S3Client>,
+        key: String,
+        mut reader: DuplexStream,
+        mut finished: oneshot::Receiver<()>,
+    ) -> Result<()> {
+        let part_size = client.multipart_part_size();
+        let mut part = Self::read_part(&mut reader, part_size).await?;
+        if part.len() < part_size {
+            Self::ensure_finished(&mut finished, &key)?;
+            return client.put_object(&key, part.into()).await;
+        }
+
+        let upload_id = client.create_multipart_upload(&key).await?;
+        let res = async {
+            let mut etags = Vec::new();
+            while !part.is_empty() {
+                etags.push(
+                    client
+                        .upload_part(&key, &upload_id, etags.len() + 1, part.into())
+                        .await?,
+                );
+                part = Self::read_part(&mut reader, part_size).await?;
+            }
+            Self::ensure_finished(&mut finished, &key)?;
+            client
+                .complete_multipart_upload(&key, &upload_id, &etags)
+                .await
+        }
+        .await;
+
+        if res.is_err() {
+            if let Err(e) = client.abort_multipart_upload(&key, &upload_id).await {
+                warn!(
+                    key = key,
+                    upload_id = upload_id,
+                    error = %e,
+                    "Failed to abort multipart upload."
+                );
+            }
+        }
+        res
+    }
+
+    /// The pipe also reaches its end when the writer is dropped, e.g. on an error mid-backup, in
+    /// which case the content is truncated.
+    fn ensure_finished(finished: &mut oneshot::Receiver<()>, key: &str) -> Result<()> {
+        finished.try_recv().map_err(|_| {
+            format_err!(
+                "Writer of {} dropped without shutting down, not completing the upload.",
+                key
+            )
+        })
+    }
+
+    async fn read_part(reader: &mut DuplexStream, part_size: usize) -> Result<Vec<u8>> {
+        let mut buf = Vec::with_capacity(part_size);
+        reader.take(part_size as u64).read_to_end(&mut buf).await?;
+        Ok(buf)
+    }
+}
+
+#[async_trait]
+impl BackupStorage for S3 {
+    async fn create_backup(&self, name: &ShellSafeName) -> Result<BackupHandle> {
+        // There's no need to create "folders" in an object store.
+        Ok(name.to_string())
+    }
+
+    async fn create_for_write(
+        &self,
+        backup_handle: &BackupHandleRef,
+        name: &ShellSafeName,
+    ) -> Result<(FileHandle, Box<dyn AsyncWrite + Send + Unpin>)> {
+        let file_handle = format!("{}/{}", backup_handle, name.as_ref());
+        let (writer, reader) = tokio::io::duplex(Self::PIPE_SIZE);
+        let (finished_tx, finished_rx) = oneshot::channel();
+        let upload = tokio::spawn(Self::upload(
+            Arc::clone(&self.client),
+            self.object_key(&file_handle),
+            reader,
+            finished_rx,
+        ));
+        Ok((
+            file_handle,
+            Box::new(S3DataSink::new(writer, finished_tx, upload)),
+        ))
+    }
+
+    async fn open_for_read(
+        &self,
+        file_handle: &FileHandleRef,
+    ) -> Result<Box<dyn AsyncRead + Send + Unpin>> {
+        self.client.get_object(&self.object_key(file_handle)).await
+    }
+
+    async fn list_metadata_files(&self) -> Result<Vec<FileHandle>> {
+        let prefix = self.object_key(&format!("{}/", Self::METADATA_DIR));
+        self.client
+            .list_objects(&prefix)
+            .await?
+            .iter()
+            .map(|key| self.file_handle(key))
+            .collect()
+    }
+
+    /// file_handle are expected to be the return results from list_metadata_files
+    /// file_handle is a path with `metadata` in the path, Ex: metadata/epoch_ending_1.meta
+    async fn backup_metadata_file(&self, file_handle: &FileHandleRef) -> Result<()> {
+        let name = Path::new(file_handle)
+            .file_name()
+            .and_then(OsStr::to_str)
+            .ok_or_else(|| format_err!("cannot extract filename from {}", file_handle))?;
+        let src_key = self.object_key(file_handle);
+        let dst_key = self.object_key(&format!("{}/{}", Self::METADATA_BACKUP_DIR, name));
+
+        // There's no "move" in S3.
+        self.client.copy_object(&src_key, &dst_key).await?;
+        self.client.delete_object(&src_key).await
+    }
+
+    async fn save_metadata_lines(
+        &self,
+        name: &ShellSafeName,
+        lines: &[TextLine],
+    ) -> Result<FileHandle> {
+        let file_handle = format!("{}/{}", Self::METADATA_DIR, name.as_ref());
+        let content = lines
+            .iter()
+            .map(|e| e.as_ref())
+            .collect::<Vec<&str>>()
+            .join("");
+        self.client
+            .put_object(&self.object_key(&file_handle), content.into())
+            .await?;
+        Ok(file_handle)
+    }
+}
+
+/// Feeds the uploading task through an in memory pipe, `poll_shutdown()` resolves once the upload
+/// finishes, with its result. If the sink is dropped without being shut down, the upload is
+/// aborted instead.
+struct S3DataSink {
+    writer: DuplexStream,
+    finished: Option<oneshot::Sender<()>>,
+    upload: Option<JoinHandle<Result<()>>>,
+}
+
+impl S3DataSink {
+    fn new(
+        writer: DuplexStream,
+        finished: oneshot::Sender<()>,
+        upload: JoinHandle<Result<()>>,
+    ) -> Self {
+        Self {
+            writer,
+            finished: Some(finished),
+            upload: Some(upload),
+        }
+    }
+}
+
+impl AsyncWrite for S3DataSink {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<Result<usize, tokio::io::Error>> {
+        Pin::new(&mut self.writer).poll_write(cx, buf)
+    }
+
+    fn poll_flush(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), tokio::io::Error>> {
+        Pin::new(&mut self.writer).poll_flush(cx)
+    }
+
+    fn poll_shutdown(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), tokio::io::Error>> {
+        // Signaled before the end of the pipe, so that the uploading task sees it at the end.
+        if let Some(finished) = self.finished.take() {
+            // The uploading task might have failed already, its result is returned below.
+            let _ = finished.send(());
+        }
+        ready!(Pin::new(&mut self.writer).poll_shutdown(cx))?;
+
+        let res = match self.upload.as_mut() {
+            Some(upload) => ready!(Pin::new(upload).poll(cx)),
+            None => return Poll::Ready(Ok(())),
+        };
+        self.upload = None;
+        Poll::Ready(match res {
+            Ok(Ok(())) => Ok(()),
+            Ok(Err(e)) => Err(tokio::io::Error::new(tokio::io::ErrorKind::Other, e)),
+            Err(e) => Err(tokio::io::Error::new(tokio::io::ErrorKind::Other, e)),
+        })
+    }
+}
@@ -274,6 +274,8 @@ pub struct GlobalRestoreOptions {
     pub run_mode: Arc<RestoreRunMode>,
     pub concurrent_downloads: usize,
     pub replay_concurrency_level: usize,
+    /// Where the target DB lives, if known. Restore progress is tracked in it.
+    pub db_dir: Option<PathBuf>,
 }
 
 impl TryFrom<GlobalRestoreOpt> for GlobalRestoreOptions {
@@ -306,6 +308,7 @@ impl TryFrom<GlobalRestoreOpt> for GlobalRestoreOptions {
             run_mode: Arc::new(run_mode),
             concurrent_downloads,
             replay_concurrency_level,
+            db_dir: opt.db_dir,
         })
     }
 }
@@ -357,6 +357,18 @@ pub trait DbReader: Send + Sync {
         unimplemented!()
     }
 
+    /// Returns an iterator over the sequence numbers and versions of the transactions sent by
+    /// an account with `address`, starting at sequence number `seq_num`. Stops at the first
+    /// transaction with `txn.version > ledger_version`.
+    fn get_account_transaction_version_iterator(
+        &self,
+        address: AccountAddress,
+        seq_num: u64,
+        ledger_version: Version,
+    ) -> Result<Box<dyn Iterator<Item = Result<(u64, Version)>> + '_>> {
+        unimplemented!()
+    }
+
     /// Returns proof of new state for a given ledger info with signatures relative to version known
     /// to client
     fn get_state_proof_with_ledger_info(
@@ -44,7 +44,7 @@ use aptos_testcases::{
     validator_reboot_stress_test::ValidatorRebootStressTest,
     CompositeNetworkTest,
 };
-use clap::{Parser, Subcommand, __derive_refs::once_cell::sync::Lazy};
+use clap::{__derive_refs::once_cell::sync::Lazy, Parser, Subcommand};
 use futures::stream::{FuturesUnordered, StreamExt};
 use rand::{rngs::ThreadRng, seq::SliceRandom, Rng};
 use std::{
@@ -1141,9 +1141,9 @@ fn individual_workload_tests(test_name: String) -> ForgeConfig {
                 };
                 job.transaction_mix_per_phase(vec![
                     // warmup
+                    vec![(account_creation_type.clone(), 1)],
                     vec![(account_creation_type, 1)],
-                    vec![(account_creation_type, 1)],
-                    vec![(write_type, 1)],
+                    vec![(write_type.clone(), 1)],
                     // cooldown
                     vec![(write_type, 1)],
                 ])
@@ -0,0 +1,30 @@
+module 0xABCD::dependency_base {
+    use std::signer;
+
+    //
+    //  Library called from modules of other packages (see `dependency_user`), which are
+    //  published under other accounts, so calls load modules across publishers.
+    //
+
+    /// Number of calls made by the holder, through any of the users of this module
+    struct Counter has key {
+        value: u64,
+    }
+
+    public fun increment(account: &signer) acquires Counter {
+        let account_address = signer::address_of(account);
+        if (!exists<Counter>(account_address)) {
+            move_to(account, Counter { value: 0 });
+        };
+        let counter = borrow_global_mut<Counter>(account_address);
+        counter.value = counter.value + 1;
+    }
+
+    public fun value(account_address: address): u64 acquires Counter {
+        if (exists<Counter>(account_address)) {
+            borrow_global<Counter>(account_address).value
+        } else {
+            0
+        }
+    }
+}
@@ -0,0 +1,25 @@
+module 0xABCD::dependency_user {
+    use std::signer;
+    use 0xABCD::dependency_base;
+
+    //
+    //  Calls into `dependency_base`, which is published under another account
+    //  than this package (the address is rewritten when publishing).
+    //
+
+    /// Last counter value read back from the dependency
+    struct LastValue has key {
+        value: u64,
+    }
+
+    public entry fun call_dependency(user: &signer) acquires LastValue {
+        let user_address = signer::address_of(user);
+        dependency_base::increment(user);
+        let value = dependency_base::value(user_address);
+        if (exists<LastValue>(user_address)) {
+            borrow_global_mut<LastValue>(user_address).value = value;
+        } else {
+            move_to(user, LastValue { value });
+        }
+    }
+}
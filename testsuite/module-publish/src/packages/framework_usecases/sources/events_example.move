@@ -0,0 +1,46 @@
+module 0xABCD::events_example {
+    use std::signer;
+    use std::vector;
+    use aptos_framework::account;
+    use aptos_framework::event::{Self, EventHandle};
+
+    //
+    //  Emits a configurable number of events, of a configurable size, per transaction,
+    //  so event storage, the indexer stream and event queries can be measured under
+    //  event dominated traffic. Each user emits on a handle of its own.
+    //
+
+    struct SizedEvent has drop, store {
+        index: u64,
+        data: vector<u8>,
+    }
+
+    struct EventStore has key {
+        events: EventHandle<SizedEvent>,
+    }
+
+    /// Emit `count` events, each holding `size` bytes of data
+    public entry fun emit_events(user: &signer, count: u64, size: u64) acquires EventStore {
+        let user_address = signer::address_of(user);
+        if (!exists<EventStore>(user_address)) {
+            move_to(user, EventStore { events: account::new_event_handle<SizedEvent>(user) });
+        };
+        let events = &mut borrow_global_mut<EventStore>(user_address).events;
+        let data = data(size);
+        let i = 0;
+        while (i < count) {
+            event::emit_event(events, SizedEvent { index: i, data });
+            i = i + 1;
+        };
+    }
+
+    fun data(size: u64): vector<u8> {
+        let data = vector::empty();
+        let i = 0;
+        while (i < size) {
+            vector::push_back(&mut data, ((i % 256) as u8));
+            i = i + 1;
+        };
+        data
+    }
+}
@@ -0,0 +1,52 @@
+module 0xABCD::fungible_asset_example {
+    use std::option;
+    use std::signer;
+    use std::string;
+    use aptos_framework::fungible_asset::{Self, Metadata, MintRef};
+    use aptos_framework::object::{Self, Object};
+    use aptos_framework::primary_fungible_store;
+
+    //
+    //  Code for a fungible asset (FA standard) managed by the publisher
+    //
+
+    const ASSET_NAME: vector<u8> = b"An FA Name";
+    const ASSET_SYMBOL: vector<u8> = b"FA";
+    const ASSET_DECIMALS: u8 = 8;
+
+    /// Stored in the metadata object
+    struct ManagedFungibleAsset has key {
+        mint_ref: MintRef,
+    }
+
+    public entry fun initialize(publisher: &signer) {
+        let constructor_ref = &object::create_named_object(publisher, ASSET_SYMBOL);
+        primary_fungible_store::create_primary_store_enabled_fungible_asset(
+            constructor_ref,
+            option::none(),
+            string::utf8(ASSET_NAME),
+            string::utf8(ASSET_SYMBOL),
+            ASSET_DECIMALS,
+            string::utf8(b""),
+            string::utf8(b""),
+        );
+        move_to(&object::generate_signer(constructor_ref), ManagedFungibleAsset {
+            mint_ref: fungible_asset::generate_mint_ref(constructor_ref),
+        });
+    }
+
+    fun get_metadata(publisher_address: address): Object<Metadata> {
+        object::address_to_object<Metadata>(object::create_object_address(&publisher_address, ASSET_SYMBOL))
+    }
+
+    /// Fund the primary store of the user, the publisher co-signs as the minter
+    public entry fun mint(user: &signer, publisher: &signer, amount: u64) acquires ManagedFungibleAsset {
+        let metadata = get_metadata(signer::address_of(publisher));
+        let managed = borrow_global<ManagedFungibleAsset>(object::object_address(&metadata));
+        primary_fungible_store::mint(&managed.mint_ref, signer::address_of(user), amount);
+    }
+
+    public entry fun transfer(sender: &signer, publisher_address: address, to: address, amount: u64) {
+        primary_fungible_store::transfer(sender, get_metadata(publisher_address), to, amount);
+    }
+}
@@ -0,0 +1,63 @@
+module 0xABCD::governance_example {
+    use std::signer;
+    use aptos_framework::aptos_governance;
+    use aptos_framework::governance_proposal::GovernanceProposal;
+    use aptos_framework::stake;
+    use aptos_framework::timestamp;
+    use aptos_framework::voting;
+
+    //
+    //  Users of a publisher all vote on the same governance proposal, one octa at a time,
+    //  from stake pools of their own. Needs voting power of stake pools outside of the
+    //  validator set to be counted (i.e. validator set changes to be allowed), and a
+    //  required proposer stake the users can afford.
+    //
+
+    const EXECUTION_HASH: vector<u8> = b"governance_example";
+
+    /// Proposal the users of the publisher vote on
+    struct CurrentProposal has key {
+        id: u64,
+        created: bool,
+    }
+
+    public entry fun initialize(publisher: &signer) {
+        move_to(publisher, CurrentProposal { id: 0, created: false });
+    }
+
+    /// Stake one more octa and vote with it on the current proposal, creating the stake pool
+    /// of the user on its first vote. When there is no proposal open to the user's vote (none
+    /// created yet, voting closed, or already voted without partial governance voting),
+    /// the user creates the next one instead.
+    public entry fun vote(user: &signer, publisher: address, should_pass: bool) acquires CurrentProposal {
+        let user_address = signer::address_of(user);
+        if (!stake::stake_pool_exists(user_address)) {
+            stake::initialize_stake_owner(user, 0, user_address, user_address);
+        };
+        let required_stake = aptos_governance::get_required_proposer_stake();
+        let voting_power = aptos_governance::get_voting_power(user_address);
+        let stake_amount = if (voting_power < required_stake) { required_stake - voting_power } else { 1 };
+        stake::add_stake(user, stake_amount);
+        // stake needs to stay locked up until the proposal expires
+        if (stake::get_lockup_secs(user_address) < timestamp::now_seconds() + aptos_governance::get_voting_duration_secs()) {
+            stake::increase_lockup(user);
+        };
+
+        let proposal = borrow_global_mut<CurrentProposal>(publisher);
+        if (proposal.created
+            && !voting::is_voting_closed<GovernanceProposal>(@aptos_framework, proposal.id)
+            && aptos_governance::get_remaining_voting_power(user_address, proposal.id) > 0) {
+            aptos_governance::partial_vote(user, user_address, proposal.id, 1, should_pass);
+        } else {
+            proposal.id = aptos_governance::create_proposal_v2_impl(
+                user,
+                user_address,
+                EXECUTION_HASH,
+                b"",
+                b"",
+                false,
+            );
+            proposal.created = true;
+        }
+    }
+}
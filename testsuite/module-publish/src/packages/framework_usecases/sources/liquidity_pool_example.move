@@ -0,0 +1,78 @@
+module 0xABCD::liquidity_pool_example {
+    use std::signer;
+
+    //
+    //  A constant-product (x * y = k) pool of two virtual tokens per publisher, which every
+    //  user swaps against. Each swap reads and writes the reserves of the pool, so all swaps
+    //  on the same pool conflict with each other, as they do on a popular DEX pair.
+    //
+
+    /// Reserves the pool is created with
+    const INITIAL_RESERVE: u64 = 1000000000;
+    /// Balance of each token a user starts with, on its first swap
+    const INITIAL_BALANCE: u64 = 1000000;
+    /// Fee kept in the pool, in basis points
+    const FEE_BPS: u128 = 30;
+
+    struct Pool has key {
+        reserve_x: u64,
+        reserve_y: u64,
+        num_swaps: u64,
+    }
+
+    struct Balances has key {
+        x: u64,
+        y: u64,
+    }
+
+    public entry fun initialize(publisher: &signer) {
+        move_to(publisher, Pool {
+            reserve_x: INITIAL_RESERVE,
+            reserve_y: INITIAL_RESERVE,
+            num_swaps: 0,
+        });
+    }
+
+    /// Swap up to `amount_in` of X for Y (or of Y for X) in the pool of the publisher,
+    /// capped by the balance of the user
+    public entry fun swap(
+        user: &signer,
+        publisher: address,
+        amount_in: u64,
+        x_to_y: bool,
+    ) acquires Pool, Balances {
+        let user_address = signer::address_of(user);
+        if (!exists<Balances>(user_address)) {
+            move_to(user, Balances { x: INITIAL_BALANCE, y: INITIAL_BALANCE });
+        };
+        let balances = borrow_global_mut<Balances>(user_address);
+        let pool = borrow_global_mut<Pool>(publisher);
+        if (x_to_y) {
+            let amount_in = min(amount_in, balances.x);
+            let amount_out = amount_out(amount_in, pool.reserve_x, pool.reserve_y);
+            pool.reserve_x = pool.reserve_x + amount_in;
+            pool.reserve_y = pool.reserve_y - amount_out;
+            balances.x = balances.x - amount_in;
+            balances.y = balances.y + amount_out;
+        } else {
+            let amount_in = min(amount_in, balances.y);
+            let amount_out = amount_out(amount_in, pool.reserve_y, pool.reserve_x);
+            pool.reserve_y = pool.reserve_y + amount_in;
+            pool.reserve_x = pool.reserve_x - amount_out;
+            balances.y = balances.y - amount_in;
+            balances.x = balances.x + amount_out;
+        };
+        pool.num_swaps = pool.num_swaps + 1;
+    }
+
+    fun amount_out(amount_in: u64, reserve_in: u64, reserve_out: u64): u64 {
+        let amount_in_with_fee = (amount_in as u128) * (10000 - FEE_BPS);
+        let numerator = amount_in_with_fee * (reserve_out as u128);
+        let denominator = (reserve_in as u128) * 10000 + amount_in_with_fee;
+        ((numerator / denominator) as u64)
+    }
+
+    fun min(a: u64, b: u64): u64 {
+        if (a < b) { a } else { b }
+    }
+}
@@ -0,0 +1,85 @@
+module 0xABCD::marketplace {
+    use std::error;
+    use std::option;
+    use std::signer;
+    use std::string;
+    use aptos_std::string_utils::{to_string};
+    use aptos_framework::aptos_coin::AptosCoin;
+    use aptos_framework::coin;
+    use aptos_framework::object::{Self, TransferRef};
+    use aptos_token_objects::collection;
+    use aptos_token_objects::token;
+
+    //
+    //  A minimal marketplace, every listing is a token preminted by the publisher.
+    //  A sold token stays listed on behalf of its buyer, so buyers keep competing
+    //  for the same listings.
+    //
+
+    const COLLECTION_NAME: vector<u8> = b"Marketplace Collection";
+    const COLLECTION_DESCRIPTION: vector<u8> = b"";
+    const COLLECTION_URL: vector<u8> = b"";
+    const TOKEN_DESCRIPTION: vector<u8> = b"";
+    const TOKEN_URI: vector<u8> = b"https://aptos.dev";
+
+    const PRICE: u64 = 1;
+
+    /// The listing doesn't exist
+    const ELISTING_NOT_FOUND: u64 = 1;
+
+    /// Stored in the token object, the token can only change hands through the marketplace
+    struct Listing has key {
+        seller: address,
+        price: u64,
+        transfer_ref: TransferRef,
+    }
+
+    /// Premint `num_listings` tokens and list all of them
+    public entry fun initialize(publisher: &signer, num_listings: u64) {
+        let collection_name = string::utf8(COLLECTION_NAME);
+        collection::create_unlimited_collection(
+            publisher,
+            string::utf8(COLLECTION_DESCRIPTION),
+            collection_name,
+            option::none(),
+            string::utf8(COLLECTION_URL),
+        );
+
+        let seller = signer::address_of(publisher);
+        let i = 0;
+        while (i < num_listings) {
+            let constructor_ref = token::create_named_token(
+                publisher,
+                collection_name,
+                string::utf8(TOKEN_DESCRIPTION),
+                to_string<u64>(&i),
+                option::none(),
+                string::utf8(TOKEN_URI),
+            );
+            let transfer_ref = object::generate_transfer_ref(&constructor_ref);
+            object::disable_ungated_transfer(&transfer_ref);
+            move_to(&object::generate_signer(&constructor_ref), Listing {
+                seller,
+                price: PRICE,
+                transfer_ref,
+            });
+            i = i + 1;
+        };
+    }
+
+    /// Buy the token of the listing, it's relisted at the same price with the buyer as seller
+    public entry fun buy(buyer: &signer, marketplace: address, listing_id: u64) acquires Listing {
+        let token_address = token::create_token_address(
+            &marketplace,
+            &string::utf8(COLLECTION_NAME),
+            &to_string<u64>(&listing_id),
+        );
+        assert!(exists<Listing>(token_address), error::not_found(ELISTING_NOT_FOUND));
+        let listing = borrow_global_mut<Listing>(token_address);
+
+        coin::transfer<AptosCoin>(buyer, listing.seller, listing.price);
+        let buyer_address = signer::address_of(buyer);
+        object::transfer_with_ref(object::generate_linear_transfer_ref(&listing.transfer_ref), buyer_address);
+        listing.seller = buyer_address;
+    }
+}
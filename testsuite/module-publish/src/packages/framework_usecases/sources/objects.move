@@ -0,0 +1,72 @@
+module 0xABCD::objects {
+    use std::signer;
+    use std::vector;
+    use aptos_framework::object::{Self, ExtendRef, Object};
+
+    //
+    //  Objects created by accounts and passed around between them, carrying resources
+    //  of the object group next to their ObjectCore.
+    //
+
+    /// Objects owned by an account, last received one at the end
+    struct Holdings has key {
+        objects: vector<Object<Counter>>,
+    }
+
+    #[resource_group_member(group = aptos_framework::object::ObjectGroup)]
+    /// Stored at the object address, counts how many times the object changed owner
+    struct Counter has key {
+        transfers: u64,
+        extend_ref: ExtendRef,
+    }
+
+    #[resource_group_member(group = aptos_framework::object::ObjectGroup)]
+    /// Added to an object the first time it changes owner
+    struct Payload has key {
+        data: vector<u8>,
+    }
+
+    /// Create an object owned by the user
+    public entry fun create(user: &signer) acquires Holdings {
+        let user_address = signer::address_of(user);
+        if (!exists<Holdings>(user_address)) {
+            move_to(user, Holdings { objects: vector::empty() });
+        };
+        let constructor_ref = object::create_object(user_address);
+        move_to(&object::generate_signer(&constructor_ref), Counter {
+            transfers: 0,
+            extend_ref: object::generate_extend_ref(&constructor_ref),
+        });
+        let holdings = borrow_global_mut<Holdings>(user_address);
+        vector::push_back(&mut holdings.objects, object::object_from_constructor_ref<Counter>(&constructor_ref));
+    }
+
+    /// Transfer the last received object of the user to the recipient, adding a payload of
+    /// `payload_size` bytes to it on its first transfer.
+    /// Creates an object instead if the user has none left, or the recipient can't hold one.
+    public entry fun transfer(user: &signer, recipient: address, payload_size: u64) acquires Counter, Holdings {
+        let user_address = signer::address_of(user);
+        if (!exists<Holdings>(user_address)
+            || vector::is_empty(&borrow_global<Holdings>(user_address).objects)
+            || !exists<Holdings>(recipient)) {
+            create(user);
+            return
+        };
+
+        let obj = vector::pop_back(&mut borrow_global_mut<Holdings>(user_address).objects);
+        let counter = borrow_global_mut<Counter>(object::object_address(&obj));
+        if (counter.transfers == 0) {
+            let data = vector::empty();
+            let i = 0;
+            while (i < payload_size) {
+                vector::push_back(&mut data, ((i % 256) as u8));
+                i = i + 1;
+            };
+            move_to(&object::generate_signer_for_extending(&counter.extend_ref), Payload { data });
+        };
+        counter.transfers = counter.transfers + 1;
+
+        object::transfer(user, obj, recipient);
+        vector::push_back(&mut borrow_global_mut<Holdings>(recipient).objects, obj);
+    }
+}
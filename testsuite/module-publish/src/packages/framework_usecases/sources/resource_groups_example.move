@@ -0,0 +1,85 @@
+module 0xABCD::resource_groups_example {
+    use std::vector;
+    use aptos_framework::object;
+
+    //
+    //  Members of a single resource group, stored together in a named object of the
+    //  publisher and mutated independently by any user. Every write of a member reads
+    //  and writes the whole group.
+    //
+    //  Members are in the object group, as a group declared here would be referred to
+    //  as 0xABCD in the metadata, which isn't rewritten to the publisher address.
+    //
+
+    const SEED: vector<u8> = b"resource_groups_example";
+
+    /// The member doesn't exist
+    const EMEMBER_NOT_FOUND: u64 = 1;
+
+    #[resource_group_member(group = aptos_framework::object::ObjectGroup)]
+    struct Member0 has key {
+        value: u64,
+        data: vector<u8>,
+    }
+
+    #[resource_group_member(group = aptos_framework::object::ObjectGroup)]
+    struct Member1 has key {
+        value: u64,
+        data: vector<u8>,
+    }
+
+    #[resource_group_member(group = aptos_framework::object::ObjectGroup)]
+    struct Member2 has key {
+        value: u64,
+        data: vector<u8>,
+    }
+
+    #[resource_group_member(group = aptos_framework::object::ObjectGroup)]
+    struct Member3 has key {
+        value: u64,
+        data: vector<u8>,
+    }
+
+    /// Create the group, each member holding `member_size` bytes of data
+    public entry fun initialize(publisher: &signer, member_size: u64) {
+        let constructor_ref = object::create_named_object(publisher, SEED);
+        let group_signer = object::generate_signer(&constructor_ref);
+        move_to(&group_signer, Member0 { value: 0, data: data(member_size) });
+        move_to(&group_signer, Member1 { value: 0, data: data(member_size) });
+        move_to(&group_signer, Member2 { value: 0, data: data(member_size) });
+        move_to(&group_signer, Member3 { value: 0, data: data(member_size) });
+    }
+
+    /// Increment the value of one member (0 to 3) of the group of the publisher
+    public entry fun mutate(
+        _user: &signer,
+        publisher: address,
+        member: u64,
+    ) acquires Member0, Member1, Member2, Member3 {
+        let group_address = object::create_object_address(&publisher, SEED);
+        if (member == 0) {
+            let member = borrow_global_mut<Member0>(group_address);
+            member.value = member.value + 1;
+        } else if (member == 1) {
+            let member = borrow_global_mut<Member1>(group_address);
+            member.value = member.value + 1;
+        } else if (member == 2) {
+            let member = borrow_global_mut<Member2>(group_address);
+            member.value = member.value + 1;
+        } else {
+            assert!(member == 3, EMEMBER_NOT_FOUND);
+            let member = borrow_global_mut<Member3>(group_address);
+            member.value = member.value + 1;
+        }
+    }
+
+    fun data(size: u64): vector<u8> {
+        let data = vector::empty();
+        let i = 0;
+        while (i < size) {
+            vector::push_back(&mut data, ((i % 256) as u8));
+            i = i + 1;
+        };
+        data
+    }
+}
@@ -0,0 +1,42 @@
+module 0xABCD::smart_table_example {
+    use aptos_std::smart_table::{Self, SmartTable};
+
+    //
+    //  A single SmartTable per publisher, shared by all users, growing and shrinking
+    //  as entries are inserted and removed, splitting its buckets along the way.
+    //
+
+    /// Value of each key is the number of times it was written
+    struct Store has key {
+        table: SmartTable<u64, u64>,
+    }
+
+    public entry fun initialize(publisher: &signer) {
+        move_to(publisher, Store { table: smart_table::new() });
+    }
+
+    /// Insert the key, or increment its value if already present
+    public entry fun upsert(_user: &signer, publisher: address, key: u64) acquires Store {
+        let table = &mut borrow_global_mut<Store>(publisher).table;
+        if (smart_table::contains(table, key)) {
+            let value = smart_table::borrow_mut(table, key);
+            *value = *value + 1;
+        } else {
+            smart_table::add(table, key, 1);
+        }
+    }
+
+    public entry fun read(_user: &signer, publisher: address, key: u64) acquires Store {
+        let table = &borrow_global<Store>(publisher).table;
+        if (smart_table::contains(table, key)) {
+            smart_table::borrow(table, key);
+        }
+    }
+
+    public entry fun remove(_user: &signer, publisher: address, key: u64) acquires Store {
+        let table = &mut borrow_global_mut<Store>(publisher).table;
+        if (smart_table::contains(table, key)) {
+            smart_table::remove(table, key);
+        }
+    }
+}
@@ -0,0 +1,47 @@
+module 0xABCD::write_set_example {
+    use std::signer;
+    use std::vector;
+    use aptos_std::table::{Self, Table};
+
+    //
+    //  Writes a configurable number of distinct storage slots, of a configurable size, per
+    //  transaction, so commit and state sync throughput can be measured against the size of
+    //  the write set. Slots are table items of the user, at a random offset, so a write
+    //  either allocates a new slot or overwrites an existing one.
+    //
+
+    struct Slots has key {
+        slots: Table<u64, vector<u8>>,
+    }
+
+    /// Write the `num_slots` slots of the user from `offset`, each holding `slot_size` bytes
+    public entry fun write_slots(
+        user: &signer,
+        offset: u64,
+        num_slots: u64,
+        slot_size: u64,
+    ) acquires Slots {
+        let user_address = signer::address_of(user);
+        if (!exists<Slots>(user_address)) {
+            move_to(user, Slots { slots: table::new() });
+        };
+        let slots = &mut borrow_global_mut<Slots>(user_address).slots;
+        // differs across offsets, so overwrites change the value
+        let value = data(slot_size, offset);
+        let i = 0;
+        while (i < num_slots) {
+            table::upsert(slots, offset + i, value);
+            i = i + 1;
+        };
+    }
+
+    fun data(size: u64, seed: u64): vector<u8> {
+        let data = vector::empty();
+        let i = 0;
+        while (i < size) {
+            vector::push_back(&mut data, (((seed + i) % 256) as u8));
+            i = i + 1;
+        };
+        data
+    }
+}
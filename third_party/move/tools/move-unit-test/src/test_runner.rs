@@ -17,20 +17,29 @@ use move_core_types::{
     account_address::AccountAddress,
     effects::{ChangeSet, Op},
     identifier::IdentStr,
+    language_storage::ModuleId,
     value::serialize_values,
     vm_status::StatusCode,
 };
 use move_resource_viewer::MoveValueAnnotator;
 use move_vm_runtime::{
-    move_vm::MoveVM, native_extensions::NativeContextExtensions,
+    move_vm::MoveVM,
+    native_extensions::NativeContextExtensions,
     native_functions::NativeFunctionTable,
+    session::{SerializedReturnValues, Session},
 };
 use move_vm_test_utils::{
     gas_schedule::{zero_cost_schedule, CostTable, Gas, GasCost, GasStatus},
     InMemoryStorage,
 };
+use once_cell::sync::Lazy;
 use rayon::prelude::*;
-use std::{io::Write, marker::Send, sync::Mutex, time::Instant};
+use std::{
+    io::Write,
+    marker::Send,
+    sync::{Mutex, RwLock},
+    time::Instant,
+};
 #[cfg(feature = "evm-backend")]
 use {
     evm::{backend::MemoryVicinity, ExitReason},
@@ -41,6 +50,28 @@ use {
     std::time::Duration,
 };
 
+/// Executes a test function in the given session with a gas meter of its own, returning the
+/// result along with the gas used.
+pub type ExecuteTestHook = dyn for<'r, 'l> Fn(
+        &mut Session<'r, 'l>,
+        &ModuleId,
+        &IdentStr,
+        Vec<Vec<u8>>,
+    ) -> (VMResult<SerializedReturnValues>, u64)
+    + Send
+    + Sync;
+
+static EXECUTE_TEST_HOOK: Lazy<RwLock<Option<Box<ExecuteTestHook>>>> =
+    Lazy::new(|| RwLock::new(None));
+
+/// Sets a hook which is called to execute every test function instead of running it with the
+/// unit cost gas meter bounded by the instruction limit. This can be used to run the tests with
+/// the gas meter of a custom Move environment, e.g. to profile the gas they use. Tests then are
+/// only bounded by the gas meter the hook uses.
+pub fn set_execute_test_hook(hook: Box<ExecuteTestHook>) {
+    *EXECUTE_TEST_HOOK.write().unwrap() = Some(hook)
+}
+
 /// Test state common to all tests
 pub struct SharedTestingConfig {
     save_storage_state_on_failure: bool,
@@ -263,17 +294,34 @@ impl SharedTestingConfig {
         let extensions = extensions::new_extensions();
         let mut session =
             move_vm.new_session_with_extensions(&self.starting_storage_state, extensions);
-        let mut gas_meter = GasStatus::new(&self.cost_table, Gas::new(self.execution_bound));
         // TODO: collect VM logs if the verbose flag (i.e, `self.verbose`) is set
 
         let now = Instant::now();
-        let serialized_return_values_result = session.execute_function_bypass_visibility(
-            &test_plan.module_id,
-            IdentStr::new(function_name).unwrap(),
-            vec![], // no ty args, at least for now
-            serialize_values(test_info.arguments.iter()),
-            &mut gas_meter,
-        );
+        let function_ident = IdentStr::new(function_name).unwrap();
+        let args = serialize_values(test_info.arguments.iter());
+        let hook = EXECUTE_TEST_HOOK.read().unwrap();
+        let (serialized_return_values_result, gas_used) = match &*hook {
+            Some(hook) => hook(&mut session, &test_plan.module_id, function_ident, args),
+            None => {
+                let mut gas_meter =
+                    GasStatus::new(&self.cost_table, Gas::new(self.execution_bound));
+                let result = session.execute_function_bypass_visibility(
+                    &test_plan.module_id,
+                    function_ident,
+                    vec![], // no ty args, at least for now
+                    args,
+                    &mut gas_meter,
+                );
+                // TODO(Gas): This doesn't look quite right...
+                //            We're not computing the number of instructions executed even with a unit gas schedule.
+                let gas_used = Gas::new(self.execution_bound)
+                    .checked_sub(gas_meter.remaining_gas())
+                    .unwrap()
+                    .into();
+                (result, gas_used)
+            },
+        };
+        drop(hook);
         let mut return_result = serialized_return_values_result.map(|res| {
             res.return_values
                 .into_iter()
@@ -285,16 +333,7 @@ impl SharedTestingConfig {
                 err.remove_exec_state();
             }
         }
-        let test_run_info = TestRunInfo::new(
-            function_name.to_string(),
-            now.elapsed(),
-            // TODO(Gas): This doesn't look quite right...
-            //            We're not computing the number of instructions executed even with a unit gas schedule.
-            Gas::new(self.execution_bound)
-                .checked_sub(gas_meter.remaining_gas())
-                .unwrap()
-                .into(),
-        );
+        let test_run_info = TestRunInfo::new(function_name.to_string(), now.elapsed(), gas_used);
         match session.finish_with_extensions() {
             Ok((cs, _, extensions)) => (Ok(cs), Ok(extensions), return_result, test_run_info),
             Err(err) => (Err(err.clone()), Err(err), return_result, test_run_info),
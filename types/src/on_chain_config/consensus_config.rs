@@ -13,6 +13,8 @@ use std::collections::HashMap;
 pub enum OnChainConsensusConfig {
     V1(ConsensusConfigV1),
     V2(ConsensusConfigV1),
+    /// Orders blocks with the DAG instead of Jolteon, on top of the quorum store.
+    DAG(ConsensusConfigV1),
 }
 
 /// The public interface that exposes all values with safe fallback.
@@ -20,9 +22,9 @@ impl OnChainConsensusConfig {
     /// The number of recent rounds that don't count into reputations.
     pub fn leader_reputation_exclude_round(&self) -> u64 {
         match &self {
-            OnChainConsensusConfig::V1(config) | OnChainConsensusConfig::V2(config) => {
-                config.exclude_round
-            },
+            OnChainConsensusConfig::V1(config)
+            | OnChainConsensusConfig::V2(config)
+            | OnChainConsensusConfig::DAG(config) => config.exclude_round,
         }
     }
 
@@ -35,27 +37,32 @@ impl OnChainConsensusConfig {
     // to this max size.
     pub fn max_failed_authors_to_store(&self) -> usize {
         match &self {
-            OnChainConsensusConfig::V1(config) | OnChainConsensusConfig::V2(config) => {
-                config.max_failed_authors_to_store
-            },
+            OnChainConsensusConfig::V1(config)
+            | OnChainConsensusConfig::V2(config)
+            | OnChainConsensusConfig::DAG(config) => config.max_failed_authors_to_store,
         }
     }
 
     // Type and configuration used for proposer election.
     pub fn proposer_election_type(&self) -> &ProposerElectionType {
         match &self {
-            OnChainConsensusConfig::V1(config) | OnChainConsensusConfig::V2(config) => {
-                &config.proposer_election_type
-            },
+            OnChainConsensusConfig::V1(config)
+            | OnChainConsensusConfig::V2(config)
+            | OnChainConsensusConfig::DAG(config) => &config.proposer_election_type,
         }
     }
 
     pub fn quorum_store_enabled(&self) -> bool {
         match &self {
             OnChainConsensusConfig::V1(_config) => false,
-            OnChainConsensusConfig::V2(_config) => true,
+            OnChainConsensusConfig::V2(_config) | OnChainConsensusConfig::DAG(_config) => true,
         }
     }
+
+    /// Order blocks with the DAG, all validators switch at the same epoch boundary.
+    pub fn is_dag_enabled(&self) -> bool {
+        matches!(self, OnChainConsensusConfig::DAG(_))
+    }
 }
 
 /// This is used when on-chain config is not initialized.
@@ -253,4 +260,22 @@ mod test {
             ProposerElectionType::RoundProposer(_value)
         ));
     }
+
+    #[test]
+    fn test_dag_config_onchain_payload() {
+        let consensus_config = OnChainConsensusConfig::DAG(ConsensusConfigV1::default());
+
+        let mut configs = HashMap::new();
+        configs.insert(
+            OnChainConsensusConfig::CONFIG_ID,
+            bcs::to_bytes(&bcs::to_bytes(&consensus_config).unwrap()).unwrap(),
+        );
+
+        let payload = OnChainConfigPayload::new(1, InMemoryOnChainConfig::new(configs));
+
+        let result: OnChainConsensusConfig = payload.get().unwrap();
+        assert!(result.is_dag_enabled());
+        assert!(result.quorum_store_enabled());
+        assert!(!OnChainConsensusConfig::default().is_dag_enabled());
+    }
 }